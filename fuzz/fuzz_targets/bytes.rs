@@ -0,0 +1,19 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use serde_hashkey::{Key, OrderedFloatPolicy};
+use serde_hashkey_fuzz::check_key;
+
+fuzz_target!(|data: &[u8]| {
+    // Decoding arbitrary bytes may fail, but must not panic.
+    let key = match Key::<OrderedFloatPolicy>::from_bytes(data) {
+        Ok(key) => key,
+        Err(..) => return,
+    };
+
+    check_key(&key);
+
+    let normalized = key.clone().normalize();
+    assert_eq!(Key::from_bytes(&key.to_bytes()), Ok(normalized.clone()));
+    assert_eq!(normalized.to_bytes(), key.to_bytes());
+});
@@ -0,0 +1,37 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use serde_hashkey::{Key, OrderedFloatPolicy};
+use serde_hashkey_fuzz::check_key;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(key) = serde_json::from_slice::<Key<OrderedFloatPolicy>>(data) {
+        check_key(&key);
+
+        // Keys parsed from JSON only have string map keys, so they can always
+        // be written back out and must parse into the same key.
+        let json = serde_json::to_vec(&key).expect("key from json should serialize to json");
+        let again = serde_json::from_slice::<Key<OrderedFloatPolicy>>(&json)
+            .expect("serialized json should parse");
+        assert_eq!(key, again);
+
+        let value = serde_json::from_slice::<serde_json::Value>(data).expect("valid json");
+        let from_key = serde_hashkey::from_key::<serde_json::Value, _>(&key)
+            .expect("key from json should deserialize into a json value");
+        assert_eq!(value, from_key);
+    }
+
+    if let Ok(key) = ciborium::de::from_reader::<Key<OrderedFloatPolicy>, _>(data) {
+        check_key(&key);
+
+        // CBOR is free to pick the integer width on decoding (e.g. small
+        // bignums), so compare the re-encoded documents instead of the keys.
+        let mut cbor = Vec::new();
+        ciborium::ser::into_writer(&key, &mut cbor).expect("key from cbor should serialize");
+        let again = ciborium::de::from_reader::<Key<OrderedFloatPolicy>, _>(&cbor[..])
+            .expect("serialized cbor should parse");
+        let mut again_cbor = Vec::new();
+        ciborium::ser::into_writer(&again, &mut again_cbor).expect("key should serialize");
+        assert_eq!(cbor, again_cbor);
+    }
+});
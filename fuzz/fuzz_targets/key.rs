@@ -0,0 +1,15 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use serde_hashkey::{Key, OrderedFloatPolicy};
+use serde_hashkey_fuzz::{check_key, check_pair, ArbitraryKey};
+
+fuzz_target!(|input: (ArbitraryKey, ArbitraryKey)| {
+    let a = Key::<OrderedFloatPolicy>::from(input.0);
+    let b = Key::<OrderedFloatPolicy>::from(input.1);
+
+    check_key(&a);
+    check_key(&b);
+    check_pair(&a, &b);
+    check_pair(&a.clone().normalize(), &b.clone().normalize());
+});
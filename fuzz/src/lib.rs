@@ -0,0 +1,132 @@
+//! Shared helpers for the serde-hashkey fuzz targets.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use arbitrary::Arbitrary;
+use serde_hashkey::{
+    hash_value_with_ordered_float, to_key_with_ordered_float, Error, Float, Key, OrderedFloat,
+    OrderedFloatPolicy,
+};
+
+/// Mirror of [Key] which can be generated by `arbitrary`.
+#[derive(Debug, Arbitrary)]
+pub enum ArbitraryKey {
+    Null,
+    Unit,
+    Bool(bool),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    I128(i128),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    F32(f32),
+    F64(f64),
+    Bytes(Vec<u8>),
+    Char(char),
+    String(String),
+    Seq(Vec<ArbitraryKey>),
+    Map(Vec<(ArbitraryKey, ArbitraryKey)>),
+}
+
+impl From<ArbitraryKey> for Key<OrderedFloatPolicy> {
+    fn from(value: ArbitraryKey) -> Self {
+        match value {
+            ArbitraryKey::Null => Key::Null,
+            ArbitraryKey::Unit => Key::Unit,
+            ArbitraryKey::Bool(v) => Key::Bool(v),
+            ArbitraryKey::I8(v) => Key::from(v),
+            ArbitraryKey::I16(v) => Key::from(v),
+            ArbitraryKey::I32(v) => Key::from(v),
+            ArbitraryKey::I64(v) => Key::from(v),
+            ArbitraryKey::I128(v) => Key::from(v),
+            ArbitraryKey::U8(v) => Key::from(v),
+            ArbitraryKey::U16(v) => Key::from(v),
+            ArbitraryKey::U32(v) => Key::from(v),
+            ArbitraryKey::U64(v) => Key::from(v),
+            ArbitraryKey::U128(v) => Key::from(v),
+            ArbitraryKey::F32(v) => Key::Float(Float::F32(OrderedFloat(v))),
+            ArbitraryKey::F64(v) => Key::Float(Float::F64(OrderedFloat(v))),
+            ArbitraryKey::Bytes(v) => Key::from(v),
+            ArbitraryKey::Char(v) => Key::from(v),
+            ArbitraryKey::String(v) => Key::from(v),
+            ArbitraryKey::Seq(v) => Key::Seq(v.into_iter().map(Key::from).collect()),
+            ArbitraryKey::Map(v) => Key::Map(
+                v.into_iter()
+                    .map(|(k, v)| (Key::from(k), Key::from(v)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// Hash a value with a deterministic hasher.
+pub fn hash<T>(value: &T) -> u64
+where
+    T: Hash,
+{
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Check the invariants which should hold for any key.
+pub fn check_key(key: &Key<OrderedFloatPolicy>) {
+    // Hash, Eq and Ord must agree with themselves.
+    let copy = key.clone();
+    assert_eq!(*key, copy);
+    assert_eq!(key.cmp(&copy), std::cmp::Ordering::Equal);
+    assert_eq!(hash(key), hash(&copy));
+
+    // Normalization must be idempotent.
+    let normalized = copy.normalize();
+    assert_eq!(normalized.clone().normalize(), normalized);
+
+    // Feeding a key through the serializer and deserializer must be lossless,
+    // apart from homogeneous sequences being made compact.
+    let encoded = to_key_with_ordered_float(key).expect("key should serialize into a key");
+    assert_eq!(encoded.clone().normalize(), normalized);
+    let decoded: Key<OrderedFloatPolicy> =
+        serde_hashkey::from_key(key).expect("key should deserialize into a key");
+    assert_eq!(decoded.normalize(), normalized);
+
+    // Hashing a key while serializing it must agree with hashing the key it
+    // serializes into.
+    let mut hasher = DefaultHasher::new();
+    hash_value_with_ordered_float(key, &mut hasher).expect("key should hash");
+    assert_eq!(hasher.finish(), hash(&encoded));
+
+    // Comparing a key while serializing it must agree with comparing the key
+    // it serializes into.
+    assert!(serde_hashkey::value_eq_key(key, &encoded).expect("key should compare"));
+    assert_eq!(
+        serde_hashkey::value_eq_key(key, &normalized).expect("key should compare"),
+        encoded == normalized
+    );
+
+    // The binary encoding must round trip into the normalized key, unless the
+    // key is nested too deeply to be decoded.
+    match Key::<OrderedFloatPolicy>::from_bytes(&key.to_bytes()) {
+        Ok(decoded) => assert_eq!(decoded, normalized),
+        Err(Error::InvalidBytes("nesting too deep")) => {}
+        Err(error) => panic!("key should decode: {}", error),
+    }
+
+    // Deserializing into a foreign data model may fail, but must not panic.
+    let _ = serde_hashkey::from_key::<serde_json::Value, _>(key);
+}
+
+/// Check the invariants between two keys.
+pub fn check_pair(a: &Key<OrderedFloatPolicy>, b: &Key<OrderedFloatPolicy>) {
+    assert_eq!(a == b, a.cmp(b) == std::cmp::Ordering::Equal);
+    assert_eq!(a.cmp(b), b.cmp(a).reverse());
+
+    if a == b {
+        assert_eq!(hash(a), hash(b));
+    }
+}
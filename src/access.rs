@@ -0,0 +1,261 @@
+//! Access to the entries of map and sequence keys.
+
+use std::mem;
+
+use serde::de::DeserializeOwned;
+use serde::ser::Serialize;
+
+use crate::de::from_key;
+use crate::error::Error;
+use crate::float::FloatPolicy;
+use crate::key::Key;
+use crate::ser::to_key_with_policy;
+
+impl<F> Key<F>
+where
+    F: FloatPolicy + PartialEq,
+{
+    /// Get the value of the map entry with the given key.
+    ///
+    /// This scans the entries of the map in order. Returns `None` if there is
+    /// no such entry, or if this key is not a map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_derive::Serialize;
+    /// use serde_hashkey::{to_key, Key};
+    ///
+    /// #[derive(Serialize)]
+    /// struct Author {
+    ///     name: &'static str,
+    ///     age: u32,
+    /// }
+    ///
+    /// let key = to_key(&Author { name: "Noah", age: 42 })?;
+    ///
+    /// assert_eq!(key.get(&to_key("age")?), Some(&to_key(&42u32)?));
+    /// assert_eq!(key.get(&to_key("missing")?), None);
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn get(&self, key: &Key<F>) -> Option<&Key<F>> {
+        match self {
+            Key::Map(map) => map.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Get a mutable reference to the value of the map entry with the given
+    /// key.
+    ///
+    /// See [Key::get] for details. Modifying the value of an entry doesn't
+    /// change the order of the map, but the key might have to be
+    /// [normalized][Key::normalize] again afterwards if the value contains
+    /// maps or sequences.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_derive::Serialize;
+    /// use serde_hashkey::{to_key, Key};
+    ///
+    /// #[derive(Serialize)]
+    /// struct Author {
+    ///     name: &'static str,
+    ///     age: u32,
+    /// }
+    ///
+    /// let mut key = to_key(&Author { name: "Noah", age: 42 })?;
+    ///
+    /// if let Some(age) = key.get_mut(&to_key("age")?) {
+    ///     *age = to_key(&43u32)?;
+    /// }
+    ///
+    /// assert_eq!(key, to_key(&Author { name: "Noah", age: 43 })?);
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn get_mut(&mut self, key: &Key<F>) -> Option<&mut Key<F>> {
+        match self {
+            Key::Map(map) => map.iter_mut().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+}
+
+impl<F> Key<F>
+where
+    F: FloatPolicy,
+{
+    /// Get the element at the given position of a sequence, or the value of
+    /// the entry at the given position of a map.
+    ///
+    /// Returns `None` if the position is out of bounds, or if this key is
+    /// neither a [Key::Seq] nor a [Key::Map]. Note that this includes the
+    /// compact [Key::IntegerSeq] and [Key::FloatSeq], whose elements aren't
+    /// stored as keys. Use [IntegerSeq::get] or [FloatSeq::get] to access
+    /// their elements.
+    ///
+    /// [IntegerSeq::get]: crate::IntegerSeq::get
+    /// [FloatSeq::get]: crate::FloatSeq::get
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::{to_key, Key};
+    ///
+    /// let key = to_key(&("a", 1u32))?;
+    /// assert_eq!(key.get_index(0), Some(&Key::String("a".into())));
+    /// assert_eq!(key.get_index(2), None);
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn get_index(&self, index: usize) -> Option<&Key<F>> {
+        match self {
+            Key::Seq(seq) => seq.get(index),
+            Key::Map(map) => map.get(index).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Get a mutable reference to the element at the given position of a
+    /// sequence, or to the value of the entry at the given position of a map.
+    ///
+    /// See [Key::get_index] for details. The key might have to be
+    /// [normalized][Key::normalize] again after it has been modified, since
+    /// replacing an element can change whether a sequence has a compact
+    /// representation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::{to_key, Key};
+    ///
+    /// let mut key = to_key(&("a", 1u32))?;
+    ///
+    /// if let Some(element) = key.get_index_mut(0) {
+    ///     *element = Key::String("b".into());
+    /// }
+    ///
+    /// assert_eq!(key, to_key(&("b", 1u32))?);
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn get_index_mut(&mut self, index: usize) -> Option<&mut Key<F>> {
+        match self {
+            Key::Seq(seq) => seq.get_mut(index),
+            Key::Map(map) => map.get_mut(index).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+}
+
+impl<F> Key<F>
+where
+    F: FloatPolicy + Ord,
+{
+    /// Get the value of the map entry with the given key, deserialized as
+    /// `T`.
+    ///
+    /// The key is serialized the same way as with [to_key] before it is
+    /// looked up. Returns `Ok(None)` if there is no such entry, and errors if
+    /// this key is not a map or if the value can't be deserialized as `T`.
+    ///
+    /// [to_key]: crate::to_key
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_derive::Serialize;
+    /// use serde_hashkey::to_key;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Author {
+    ///     name: &'static str,
+    ///     age: Option<u32>,
+    /// }
+    ///
+    /// let key = to_key(&Author { name: "Noah", age: Some(42) })?;
+    ///
+    /// assert_eq!(key.get_as::<String, _>("name")?.as_deref(), Some("Noah"));
+    /// assert_eq!(key.get_as::<Option<u32>, _>("age")?, Some(Some(42)));
+    /// assert_eq!(key.get_as::<u32, _>("missing")?, None);
+    /// assert!(key.get_as::<u32, _>("name").is_err());
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn get_as<T, K>(&self, key: K) -> Result<Option<T>, Error>
+    where
+        T: DeserializeOwned,
+        K: Serialize,
+    {
+        let map = match self {
+            Key::Map(map) => map,
+            _ => return Err(Error::Unexpected("map")),
+        };
+
+        let key = to_key_with_policy::<_, F>(&key)?;
+
+        match map.iter().find(|(k, _)| *k == key) {
+            Some((_, value)) => from_key(value).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Insert an entry with the given key and value into this map, returning
+    /// the previous value of the entry if there was one.
+    ///
+    /// Both the key and the value are serialized the same way as with
+    /// [to_key], and this key is left unchanged if either fails. If the map
+    /// is sorted, like the ones in a [normalized][Key::normalize] key, new
+    /// entries are inserted so that it stays sorted. Otherwise they are added
+    /// to the end. Errors if this key is not a map.
+    ///
+    /// [to_key]: crate::to_key
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_derive::Serialize;
+    /// use serde_hashkey::to_key;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Author {
+    ///     name: &'static str,
+    ///     age: u32,
+    /// }
+    ///
+    /// let mut key = to_key(&Author { name: "Noah", age: 42 })?;
+    ///
+    /// let previous = key.insert_value("age", &43u32)?;
+    /// assert_eq!(previous, Some(to_key(&42u32)?));
+    /// assert_eq!(key, to_key(&Author { name: "Noah", age: 43 })?);
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn insert_value<K, V>(&mut self, key: K, value: &V) -> Result<Option<Key<F>>, Error>
+    where
+        K: Serialize,
+        V: ?Sized + Serialize,
+    {
+        let map = match self {
+            Key::Map(map) => map,
+            _ => return Err(Error::Unexpected("map")),
+        };
+
+        let key = to_key_with_policy::<_, F>(&key)?;
+        let value = to_key_with_policy::<_, F>(&value)?;
+
+        if let Some((_, existing)) = map.iter_mut().find(|(k, _)| *k == key) {
+            return Ok(Some(mem::replace(existing, value)));
+        }
+
+        let sorted = map.windows(2).all(|w| w[0].0 <= w[1].0);
+        let mut entries = mem::take(map).into_vec();
+
+        let index = if sorted {
+            entries.partition_point(|(k, _)| *k < key)
+        } else {
+            entries.len()
+        };
+
+        entries.insert(index, (key, value));
+        *map = entries.into();
+        Ok(None)
+    }
+}
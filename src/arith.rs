@@ -0,0 +1,146 @@
+//! Checked arithmetic on integers of any width.
+
+use crate::key::{narrowest_signed, narrowest_unsigned, Integer};
+
+impl Integer {
+    /// The integer zero, in its [compact representation][Integer::compact].
+    pub const ZERO: Integer = Integer::U8(0);
+
+    /// The integer one, in its [compact representation][Integer::compact].
+    pub const ONE: Integer = Integer::U8(1);
+
+    /// Add two integers of any width, or `None` if the result doesn't fit in
+    /// an `i128` or an `u128`.
+    ///
+    /// The result is in its [compact representation][Integer::compact], so it
+    /// is stored in the narrowest variant which can hold it regardless of the
+    /// widths of the operands.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::Integer;
+    ///
+    /// assert_eq!(Integer::U64(41).checked_add(&Integer::ONE), Some(Integer::U8(42)));
+    /// assert_eq!(Integer::I8(-1).checked_add(&Integer::U8(255)), Some(Integer::U8(254)));
+    /// assert_eq!(Integer::U128(u128::MAX).checked_add(&Integer::ONE), None);
+    /// ```
+    pub fn checked_add(&self, rhs: &Integer) -> Option<Integer> {
+        self.magnitude().add(rhs.magnitude())?.into_integer()
+    }
+
+    /// Subtract an integer of any width from this one, or `None` if the result
+    /// doesn't fit in an `i128` or an `u128`.
+    ///
+    /// The result is in its [compact representation][Integer::compact], so it
+    /// is stored in the narrowest variant which can hold it regardless of the
+    /// widths of the operands.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::Integer;
+    ///
+    /// assert_eq!(Integer::U8(1).checked_sub(&Integer::U8(2)), Some(Integer::I8(-1)));
+    /// assert_eq!(Integer::I64(-1).checked_sub(&Integer::I64(-1)), Some(Integer::ZERO));
+    /// assert_eq!(Integer::I128(i128::MIN).checked_sub(&Integer::ONE), None);
+    /// ```
+    pub fn checked_sub(&self, rhs: &Integer) -> Option<Integer> {
+        self.magnitude().add(rhs.magnitude().neg())?.into_integer()
+    }
+
+    /// Multiply two integers of any width, or `None` if the result doesn't
+    /// fit in an `i128` or an `u128`.
+    ///
+    /// The result is in its [compact representation][Integer::compact], so it
+    /// is stored in the narrowest variant which can hold it regardless of the
+    /// widths of the operands.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::Integer;
+    ///
+    /// assert_eq!(Integer::U8(16).checked_mul(&Integer::U8(16)), Some(Integer::U16(256)));
+    /// assert_eq!(Integer::I8(-2).checked_mul(&Integer::U64(3)), Some(Integer::I8(-6)));
+    /// assert_eq!(Integer::U128(u128::MAX).checked_mul(&Integer::I8(-1)), None);
+    /// ```
+    pub fn checked_mul(&self, rhs: &Integer) -> Option<Integer> {
+        self.magnitude().mul(rhs.magnitude())?.into_integer()
+    }
+
+    fn magnitude(&self) -> Magnitude {
+        match self.to_u128() {
+            Some(value) => Magnitude {
+                negative: false,
+                value,
+            },
+            None => Magnitude {
+                negative: true,
+                // Only negative values don't fit in an u128.
+                value: self
+                    .to_i128()
+                    .expect("negative integers fit in an i128")
+                    .unsigned_abs(),
+            },
+        }
+    }
+}
+
+/// The sign and magnitude of an integer, which can represent the values of
+/// every integer variant.
+#[derive(Clone, Copy)]
+struct Magnitude {
+    negative: bool,
+    value: u128,
+}
+
+impl Magnitude {
+    fn neg(self) -> Magnitude {
+        Magnitude {
+            negative: !self.negative && self.value != 0,
+            value: self.value,
+        }
+    }
+
+    fn add(self, rhs: Magnitude) -> Option<Magnitude> {
+        if self.negative == rhs.negative {
+            return Some(Magnitude {
+                negative: self.negative,
+                value: self.value.checked_add(rhs.value)?,
+            });
+        }
+
+        // The signs differ, so the result has the sign of the operand with
+        // the largest magnitude.
+        let (large, small) = if self.value >= rhs.value {
+            (self, rhs)
+        } else {
+            (rhs, self)
+        };
+
+        let value = large.value - small.value;
+
+        Some(Magnitude {
+            negative: large.negative && value != 0,
+            value,
+        })
+    }
+
+    fn mul(self, rhs: Magnitude) -> Option<Magnitude> {
+        let value = self.value.checked_mul(rhs.value)?;
+
+        Some(Magnitude {
+            negative: self.negative != rhs.negative && value != 0,
+            value,
+        })
+    }
+
+    fn into_integer(self) -> Option<Integer> {
+        if !self.negative {
+            return Some(narrowest_unsigned(self.value));
+        }
+
+        Some(narrowest_signed(0i128.checked_sub_unsigned(self.value)?))
+    }
+}
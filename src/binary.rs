@@ -0,0 +1,404 @@
+//! A stable, canonical binary encoding of keys.
+
+use std::borrow::Cow;
+use std::convert::TryFrom;
+
+use crate::error::Error;
+use crate::float::{self, FloatPolicy, FloatRepr};
+use crate::key::{Float, Integer, Key};
+
+const UNIT: u8 = 0x00;
+const BOOL: u8 = 0x01;
+const I8: u8 = 0x02;
+const I16: u8 = 0x03;
+const I32: u8 = 0x04;
+const I64: u8 = 0x05;
+const I128: u8 = 0x06;
+const U8: u8 = 0x07;
+const U16: u8 = 0x08;
+const U32: u8 = 0x09;
+const U64: u8 = 0x0a;
+const U128: u8 = 0x0b;
+const F32: u8 = 0x0c;
+const F64: u8 = 0x0d;
+const BYTES: u8 = 0x0e;
+const STRING: u8 = 0x0f;
+const SEQ: u8 = 0x10;
+const MAP: u8 = 0x11;
+const NULL: u8 = 0x12;
+const CHAR: u8 = 0x13;
+const F16: u8 = 0x14;
+
+/// The deepest nesting of sequences and maps which will be decoded.
+const MAX_DEPTH: usize = 128;
+
+impl<F> Key<F>
+where
+    F: FloatPolicy + Ord + Clone,
+{
+    /// Encode this key using a stable binary encoding.
+    ///
+    /// Every key is encoded as a single tag byte followed by its payload:
+    ///
+    /// | Tag    | Variant              | Payload                                   |
+    /// |--------|----------------------|-------------------------------------------|
+    /// | `0x00` | [Key::Unit]          | nothing                                   |
+    /// | `0x01` | [Key::Bool]          | `0x00` or `0x01`                          |
+    /// | `0x02` | [Integer::I8]        | 1 byte                                    |
+    /// | `0x03` | [Integer::I16]       | 2 bytes, big-endian                       |
+    /// | `0x04` | [Integer::I32]       | 4 bytes, big-endian                       |
+    /// | `0x05` | [Integer::I64]       | 8 bytes, big-endian                       |
+    /// | `0x06` | [Integer::I128]      | 16 bytes, big-endian                      |
+    /// | `0x07` | [Integer::U8]        | 1 byte                                    |
+    /// | `0x08` | [Integer::U16]       | 2 bytes, big-endian                       |
+    /// | `0x09` | [Integer::U32]       | 4 bytes, big-endian                       |
+    /// | `0x0a` | [Integer::U64]       | 8 bytes, big-endian                       |
+    /// | `0x0b` | [Integer::U128]      | 16 bytes, big-endian                      |
+    /// | `0x0c` | [Float::F32]         | IEEE 754 bits, 4 bytes, big-endian        |
+    /// | `0x0d` | [Float::F64]         | IEEE 754 bits, 8 bytes, big-endian        |
+    /// | `0x0e` | [Key::Bytes]         | length, followed by the bytes             |
+    /// | `0x0f` | [Key::String]        | length, followed by UTF-8 bytes           |
+    /// | `0x10` | [Key::Seq]           | length, followed by each element          |
+    /// | `0x11` | [Key::Map]           | length, followed by each key and value    |
+    /// | `0x12` | [Key::Null]          | nothing                                   |
+    /// | `0x13` | [Key::Char]          | Unicode scalar value, 4 bytes, big-endian |
+    /// | `0x14` | [Float::F16]         | `f32` IEEE 754 bits, 4 bytes, big-endian  |
+    ///
+    /// Lengths are encoded as unsigned [LEB128] using as few bytes as
+    /// possible. Map entries are encoded sorted by their normalized keys the
+    /// same way as [Key::normalize] sorts them, and [Key::IntegerSeq] and
+    /// [Key::FloatSeq] are encoded like the [Key::Seq] they represent, so two
+    /// keys which are equal once normalized have identical encodings.
+    /// Decoding always produces a normalized key.
+    ///
+    /// Float representations which can't be visited as a floating point
+    /// number, which none of the float policies provided by this crate have,
+    /// are encoded as a NaN.
+    ///
+    /// [LEB128]: https://en.wikipedia.org/wiki/LEB128
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::{to_key, Key};
+    ///
+    /// let key = to_key(&("hello", 42u8))?;
+    /// assert_eq!(key.to_bytes(), b"\x10\x02\x0f\x05hello\x07\x2a");
+    /// assert_eq!(Key::from_bytes(&key.to_bytes())?, key);
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.write_bytes(&mut out);
+        out
+    }
+
+    /// Encode this key using the stable binary encoding, appending it to
+    /// `out`.
+    ///
+    /// See [to_bytes] for details.
+    ///
+    /// [to_bytes]: Key::to_bytes
+    pub fn write_bytes(&self, out: &mut Vec<u8>) {
+        match self {
+            Key::Null => out.push(NULL),
+            Key::Unit => out.push(UNIT),
+            Key::Bool(b) => out.extend_from_slice(&[BOOL, u8::from(*b)]),
+            Key::Integer(integer) => match *integer {
+                Integer::I8(v) => encode_int(out, I8, &v.to_be_bytes()),
+                Integer::I16(v) => encode_int(out, I16, &v.to_be_bytes()),
+                Integer::I32(v) => encode_int(out, I32, &v.to_be_bytes()),
+                Integer::I64(v) => encode_int(out, I64, &v.to_be_bytes()),
+                Integer::I128(v) => encode_int(out, I128, &v.to_be_bytes()),
+                Integer::U8(v) => encode_int(out, U8, &v.to_be_bytes()),
+                Integer::U16(v) => encode_int(out, U16, &v.to_be_bytes()),
+                Integer::U32(v) => encode_int(out, U32, &v.to_be_bytes()),
+                Integer::U64(v) => encode_int(out, U64, &v.to_be_bytes()),
+                Integer::U128(v) => encode_int(out, U128, &v.to_be_bytes()),
+            },
+            Key::Float(f) => {
+                let v = float::to_f64(f).unwrap_or(f64::NAN);
+
+                match f {
+                    // NB: widening an f32 is lossless, so this round trips.
+                    Float::F32(..) => encode_int(out, F32, &(v as f32).to_bits().to_be_bytes()),
+                    Float::F64(..) => encode_int(out, F64, &v.to_bits().to_be_bytes()),
+                    Float::F16(..) => encode_int(out, F16, &(v as f32).to_bits().to_be_bytes()),
+                }
+            }
+            Key::Char(c) => encode_int(out, CHAR, &u32::from(*c).to_be_bytes()),
+            Key::Bytes(bytes) => {
+                out.push(BYTES);
+                encode_len(out, bytes.len());
+                out.extend_from_slice(bytes);
+            }
+            Key::String(string) => {
+                out.push(STRING);
+                encode_len(out, string.len());
+                out.extend_from_slice(string.as_bytes());
+            }
+            Key::Seq(seq) => {
+                out.push(SEQ);
+                encode_len(out, seq.len());
+
+                for value in seq.iter() {
+                    value.write_bytes(out);
+                }
+            }
+            Key::IntegerSeq(seq) => {
+                out.push(SEQ);
+                encode_len(out, seq.len());
+
+                for value in seq.iter() {
+                    Key::<F>::Integer(value).write_bytes(out);
+                }
+            }
+            Key::FloatSeq(seq) => {
+                out.push(SEQ);
+                encode_len(out, seq.len());
+
+                for value in seq.iter() {
+                    Key::Float(value).write_bytes(out);
+                }
+            }
+            Key::Map(map) => {
+                out.push(MAP);
+                encode_len(out, map.len());
+
+                let mut entries = map
+                    .iter()
+                    .map(|(key, value)| (normalized(key), value))
+                    .collect::<Vec<_>>();
+
+                entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+                for (key, value) in entries {
+                    key.write_bytes(out);
+                    value.write_bytes(out);
+                }
+            }
+        }
+    }
+
+    /// Decode a key from the stable binary encoding produced by [to_bytes].
+    ///
+    /// [to_bytes]: Key::to_bytes
+    ///
+    /// # Errors
+    ///
+    /// Errors with [Error::InvalidBytes] if `bytes` is not a valid encoding,
+    /// is truncated, has trailing bytes or nests sequences and maps more than
+    /// 128 levels deep. Encodings which [to_bytes] would never produce are
+    /// also rejected, such as lengths using more bytes than necessary and map
+    /// entries which are not sorted by key. Maps may contain duplicate keys,
+    /// since [Key::normalize] keeps them. Floats are also subject to the float
+    /// policy, so decoding a float with [RejectFloatPolicy] fails.
+    ///
+    /// [RejectFloatPolicy]: crate::RejectFloatPolicy
+    pub fn from_bytes(bytes: &[u8]) -> Result<Key<F>, Error> {
+        let mut reader = Reader { bytes, depth: 0 };
+        let key = reader.key()?;
+
+        if !reader.bytes.is_empty() {
+            return Err(Error::InvalidBytes("trailing bytes"));
+        }
+
+        Ok(key)
+    }
+}
+
+/// Normalize the key of a map entry so that entries can be sorted the same way
+/// as [Key::normalize] sorts them. Only containers can change when normalized,
+/// so other keys are borrowed as-is.
+fn normalized<F>(key: &Key<F>) -> Cow<'_, Key<F>>
+where
+    F: FloatPolicy + Ord + Clone,
+{
+    match key {
+        Key::Seq(..) | Key::Map(..) => Cow::Owned(key.clone().normalize()),
+        _ => Cow::Borrowed(key),
+    }
+}
+
+fn encode_int(out: &mut Vec<u8>, tag: u8, bytes: &[u8]) {
+    out.push(tag);
+    out.extend_from_slice(bytes);
+}
+
+fn encode_len(out: &mut Vec<u8>, len: usize) {
+    let mut len = len as u64;
+
+    loop {
+        let b = (len & 0x7f) as u8;
+        len >>= 7;
+
+        if len == 0 {
+            out.push(b);
+            break;
+        }
+
+        out.push(b | 0x80);
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    depth: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], Error> {
+        if self.bytes.len() < n {
+            return Err(Error::InvalidBytes("unexpected end of input"));
+        }
+
+        let (head, tail) = self.bytes.split_at(n);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+
+    fn byte(&mut self) -> Result<u8, Error> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn len(&mut self) -> Result<usize, Error> {
+        let mut len = 0u64;
+        let mut shift = 0;
+
+        loop {
+            let b = self.byte()?;
+
+            if (shift == 63 && b > 1) || shift > 63 {
+                return Err(Error::InvalidBytes("length overflow"));
+            }
+
+            // NB: a trailing zero byte could always be left out.
+            if shift > 0 && b == 0 {
+                return Err(Error::InvalidBytes("non-canonical length"));
+            }
+
+            len |= u64::from(b & 0x7f) << shift;
+
+            if b & 0x80 == 0 {
+                break;
+            }
+
+            shift += 7;
+        }
+
+        let len = usize::try_from(len).map_err(|_| Error::InvalidBytes("length overflow"))?;
+
+        // Every element takes at least one byte, so any length longer than the
+        // remaining input is invalid. This also guards preallocation below.
+        if len > self.bytes.len() {
+            return Err(Error::InvalidBytes("unexpected end of input"));
+        }
+
+        Ok(len)
+    }
+
+    /// Decode the elements of a sequence or map one level deeper.
+    fn nested<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T, Error>) -> Result<T, Error> {
+        if self.depth == MAX_DEPTH {
+            return Err(Error::InvalidBytes("nesting too deep"));
+        }
+
+        self.depth += 1;
+        let output = f(self)?;
+        self.depth -= 1;
+        Ok(output)
+    }
+
+    fn key<F>(&mut self) -> Result<Key<F>, Error>
+    where
+        F: FloatPolicy + Ord,
+    {
+        let key = match self.byte()? {
+            NULL => Key::Null,
+            UNIT => Key::Unit,
+            BOOL => match self.byte()? {
+                0 => Key::Bool(false),
+                1 => Key::Bool(true),
+                _ => return Err(Error::InvalidBytes("invalid boolean")),
+            },
+            I8 => Key::Integer(Integer::I8(i8::from_be_bytes(self.array()?))),
+            I16 => Key::Integer(Integer::I16(i16::from_be_bytes(self.array()?))),
+            I32 => Key::Integer(Integer::I32(i32::from_be_bytes(self.array()?))),
+            I64 => Key::Integer(Integer::I64(i64::from_be_bytes(self.array()?))),
+            I128 => Key::Integer(Integer::I128(i128::from_be_bytes(self.array()?))),
+            U8 => Key::Integer(Integer::U8(u8::from_be_bytes(self.array()?))),
+            U16 => Key::Integer(Integer::U16(u16::from_be_bytes(self.array()?))),
+            U32 => Key::Integer(Integer::U32(u32::from_be_bytes(self.array()?))),
+            U64 => Key::Integer(Integer::U64(u64::from_be_bytes(self.array()?))),
+            U128 => Key::Integer(Integer::U128(u128::from_be_bytes(self.array()?))),
+            F32 => {
+                let v = f32::from_bits(u32::from_be_bytes(self.array()?));
+                Key::Float(F::serialize_f32(v)?)
+            }
+            F64 => {
+                let v = f64::from_bits(u64::from_be_bytes(self.array()?));
+                Key::Float(Float::F64(<F::F64 as FloatRepr<f64>>::serialize(v)?))
+            }
+            F16 => {
+                let v = f32::from_bits(u32::from_be_bytes(self.array()?));
+                Key::Float(F::serialize_f16(v)?)
+            }
+            CHAR => match char::from_u32(u32::from_be_bytes(self.array()?)) {
+                Some(c) => Key::Char(c),
+                None => return Err(Error::InvalidBytes("invalid char")),
+            },
+            BYTES => {
+                let len = self.len()?;
+                Key::Bytes(self.take(len)?.into())
+            }
+            STRING => {
+                let len = self.len()?;
+                let string = std::str::from_utf8(self.take(len)?)
+                    .map_err(|_| Error::InvalidBytes("invalid UTF-8"))?;
+                Key::String(string.into())
+            }
+            SEQ => {
+                let len = self.len()?;
+
+                self.nested(|this| {
+                    let mut seq = Vec::with_capacity(len);
+
+                    for _ in 0..len {
+                        seq.push(this.key()?);
+                    }
+
+                    Ok(Key::seq(seq))
+                })?
+            }
+            MAP => {
+                let len = self.len()?;
+
+                self.nested(|this| {
+                    let mut map = Vec::<(Key<F>, Key<F>)>::with_capacity(len);
+
+                    for _ in 0..len {
+                        let key = this.key()?;
+
+                        if matches!(map.last(), Some((last, _)) if *last > key) {
+                            return Err(Error::InvalidBytes("unsorted map entries"));
+                        }
+
+                        let value = this.key()?;
+                        map.push((key, value));
+                    }
+
+                    Ok(Key::Map(map.into()))
+                })?
+            }
+            _ => return Err(Error::InvalidBytes("invalid tag")),
+        };
+
+        Ok(key)
+    }
+}
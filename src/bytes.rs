@@ -0,0 +1,229 @@
+//! The byte array stored in [Key::Bytes](crate::Key::Bytes).
+
+use std::borrow::Borrow;
+use std::cmp;
+use std::fmt;
+use std::hash;
+use std::ops;
+
+cfg_bytes! {
+    use crate::float::FloatPolicy;
+    use crate::key::Key;
+}
+
+/// A byte array, as stored in [Key::Bytes].
+///
+/// This dereferences to `[u8]`, and comparisons and hashing only consider its
+/// content. With the `bytes` feature enabled it can also be backed by a shared
+/// [`bytes::Bytes`] buffer, which is how keys can hold byte payloads without
+/// copying them.
+///
+/// [Key::Bytes]: crate::Key::Bytes
+/// [`bytes::Bytes`]: https://docs.rs/bytes/1/bytes/struct.Bytes.html
+///
+/// # Examples
+///
+/// ```
+/// use serde_hashkey::{Bytes, Key};
+///
+/// let key: Key = Key::Bytes(Bytes::from(&b"hello"[..]));
+/// assert_eq!(key, Key::from(b"hello".to_vec()));
+///
+/// if let Key::Bytes(bytes) = &key {
+///     assert_eq!(&bytes[..], b"hello");
+/// }
+/// ```
+#[derive(Clone)]
+pub struct Bytes {
+    repr: Repr,
+}
+
+#[derive(Clone)]
+enum Repr {
+    Boxed(Box<[u8]>),
+    // NB: boxed so that enabling the `bytes` feature doesn't grow every key.
+    #[cfg(feature = "bytes")]
+    Shared(Box<bytes1::Bytes>),
+}
+
+impl ops::Deref for Bytes {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        match &self.repr {
+            Repr::Boxed(bytes) => bytes,
+            #[cfg(feature = "bytes")]
+            Repr::Shared(bytes) => bytes,
+        }
+    }
+}
+
+impl AsRef<[u8]> for Bytes {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl Borrow<[u8]> for Bytes {
+    #[inline]
+    fn borrow(&self) -> &[u8] {
+        self
+    }
+}
+
+impl fmt::Debug for Bytes {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        <[u8] as fmt::Debug>::fmt(self, fmt)
+    }
+}
+
+impl PartialEq for Bytes {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl Eq for Bytes {}
+
+impl PartialOrd for Bytes {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Bytes {
+    #[inline]
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        (**self).cmp(&**other)
+    }
+}
+
+impl hash::Hash for Bytes {
+    #[inline]
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: hash::Hasher,
+    {
+        (**self).hash(state)
+    }
+}
+
+impl Bytes {
+    /// Convert into a vector, which doesn't copy unless the bytes are shared.
+    pub(crate) fn into_vec(self) -> Vec<u8> {
+        match self.repr {
+            Repr::Boxed(bytes) => bytes.into_vec(),
+            #[cfg(feature = "bytes")]
+            Repr::Shared(bytes) => bytes.to_vec(),
+        }
+    }
+}
+
+impl From<Box<[u8]>> for Bytes {
+    #[inline]
+    fn from(bytes: Box<[u8]>) -> Self {
+        Self {
+            repr: Repr::Boxed(bytes),
+        }
+    }
+}
+
+impl From<Vec<u8>> for Bytes {
+    #[inline]
+    fn from(bytes: Vec<u8>) -> Self {
+        Self::from(bytes.into_boxed_slice())
+    }
+}
+
+impl From<&[u8]> for Bytes {
+    #[inline]
+    fn from(bytes: &[u8]) -> Self {
+        Self::from(Box::<[u8]>::from(bytes))
+    }
+}
+
+cfg_bytes! {
+    /// Wrap a shared buffer without copying it.
+    impl From<bytes1::Bytes> for Bytes {
+        #[inline]
+        fn from(bytes: bytes1::Bytes) -> Self {
+            Self {
+                repr: Repr::Shared(Box::new(bytes)),
+            }
+        }
+    }
+
+    /// A shared buffer is stored in a [Key::Bytes] without copying it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use bytes1 as bytes;
+    /// use serde_hashkey::Key;
+    ///
+    /// let payload = bytes::Bytes::from_static(b"payload");
+    /// let key: Key = Key::from(payload.clone());
+    ///
+    /// let shared = key.as_shared_bytes().unwrap();
+    /// assert_eq!(shared.as_ptr(), payload.as_ptr());
+    /// ```
+    impl<F> From<bytes1::Bytes> for Key<F>
+    where
+        F: FloatPolicy,
+    {
+        #[inline]
+        fn from(bytes: bytes1::Bytes) -> Self {
+            Key::Bytes(Bytes::from(bytes))
+        }
+    }
+
+    impl Bytes {
+        /// Get the shared buffer backing these bytes, if any.
+        ///
+        /// This is a cheap reference counted clone, and is `None` if the bytes
+        /// aren't backed by a shared buffer.
+        #[inline]
+        pub fn as_shared(&self) -> Option<bytes1::Bytes> {
+            match &self.repr {
+                Repr::Shared(bytes) => Some(bytes1::Bytes::clone(bytes)),
+                Repr::Boxed(..) => None,
+            }
+        }
+    }
+
+    impl<F> Key<F>
+    where
+        F: FloatPolicy,
+    {
+        /// Get the shared buffer backing a [Key::Bytes], if any.
+        ///
+        /// This is a cheap reference counted clone. It is `None` for any other
+        /// kind of key, and for bytes which aren't backed by a shared buffer,
+        /// like those produced through serialization.
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// # use bytes1 as bytes;
+        /// use serde_hashkey::Key;
+        ///
+        /// let key: Key = Key::from(bytes::Bytes::from_static(b"payload"));
+        /// assert_eq!(key.as_shared_bytes().as_deref(), Some(&b"payload"[..]));
+        ///
+        /// let key: Key = Key::from(b"payload".to_vec());
+        /// assert_eq!(key.as_shared_bytes(), None);
+        /// ```
+        #[inline]
+        pub fn as_shared_bytes(&self) -> Option<bytes1::Bytes> {
+            match self {
+                Key::Bytes(bytes) => bytes.as_shared(),
+                _ => None,
+            }
+        }
+    }
+}
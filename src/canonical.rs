@@ -0,0 +1,323 @@
+//! Keys where integers are compared by value regardless of their width.
+
+use std::convert::TryFrom;
+
+use serde::ser;
+
+use crate::error::Error;
+use crate::float::{FloatPolicy, RejectFloatPolicy};
+use crate::key::{narrowest_signed, narrowest_unsigned, Integer, IntegerSeq, Key};
+use crate::ser::to_key_with_policy;
+use crate::walk::Walk;
+
+impl Integer {
+    /// Convert the integer into its canonical representation, which is the
+    /// same for every integer with the same value regardless of its width.
+    ///
+    /// * Non-negative values are stored in the smallest unsigned variant which
+    ///   can hold them, [Integer::U8] through [Integer::U128].
+    /// * Negative values are always stored as an [Integer::I128].
+    ///
+    /// Integers are always ordered by value, but since every value has exactly
+    /// one canonical representation, canonical integers are also equal and
+    /// hash the same when their values are.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::Integer;
+    ///
+    /// assert_eq!(Integer::U64(1).canonical(), Integer::U8(1));
+    /// assert_eq!(Integer::I32(1).canonical(), Integer::U8(1));
+    /// assert_eq!(Integer::I16(300).canonical(), Integer::U16(300));
+    /// assert_eq!(Integer::I8(-1).canonical(), Integer::I128(-1));
+    /// assert!(Integer::I8(-1).canonical() < Integer::U8(0).canonical());
+    /// assert!(Integer::U16(255).canonical() < Integer::U64(256).canonical());
+    /// ```
+    pub fn canonical(&self) -> Integer {
+        match self.to_u128() {
+            Some(v) => narrowest_unsigned(v),
+            // Only negative values don't fit in an u128.
+            None => Integer::I128(self.to_i128().expect("negative integers fit in an i128")),
+        }
+    }
+
+    /// Convert the integer into its compact representation, which is the
+    /// narrowest variant which can hold its value.
+    ///
+    /// * Non-negative values are stored in the smallest unsigned variant which
+    ///   can hold them, [Integer::U8] through [Integer::U128].
+    /// * Negative values are stored in the smallest signed variant which can
+    ///   hold them, [Integer::I8] through [Integer::I128].
+    ///
+    /// Like the [canonical representation][Integer::canonical], every value
+    /// has exactly one compact representation. This is also the
+    /// representation produced when [parsing][std::str::FromStr] an integer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::Integer;
+    ///
+    /// assert_eq!(Integer::U64(7).compact(), Integer::U8(7));
+    /// assert_eq!(Integer::I64(7).compact(), Integer::U8(7));
+    /// assert_eq!(Integer::I64(-3).compact(), Integer::I8(-3));
+    /// assert_eq!(Integer::I128(-129).compact(), Integer::I16(-129));
+    /// assert_eq!(Integer::U128(u128::MAX).compact(), Integer::U128(u128::MAX));
+    /// ```
+    pub fn compact(&self) -> Integer {
+        match self.to_u128() {
+            Some(v) => narrowest_unsigned(v),
+            None => narrowest_signed(self.to_i128().expect("negative integers fit in an i128")),
+        }
+    }
+}
+
+impl<F> Key<F>
+where
+    F: FloatPolicy,
+{
+    /// Convert every integer in this key into its
+    /// [canonical representation][Integer::canonical], so that integers with
+    /// the same value are equal regardless of their width.
+    ///
+    /// Sequences of integers are converted as a whole into a
+    /// [Key::IntegerSeq], using the narrowest width which holds every
+    /// canonical element. Sequences holding negative values use `i128`, unless
+    /// some element doesn't fit in one. Maps are not sorted again, so a key
+    /// with integer map keys might have to be [normalized][Key::normalize]
+    /// afterwards.
+    ///
+    /// Traversal does not use recursion, so it is safe to use on deeply nested
+    /// keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::to_key;
+    ///
+    /// let mut a = to_key(&(1u8, vec![2u16, 300]))?;
+    /// let mut b = to_key(&(1u64, vec![2i32, 300]))?;
+    /// assert_ne!(a, b);
+    ///
+    /// a.canonicalize_integers();
+    /// b.canonicalize_integers();
+    /// assert_eq!(a, b);
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn canonicalize_integers(&mut self) {
+        self.map_integers(Integer::canonical);
+    }
+
+    /// Convert every integer in this key into its
+    /// [compact representation][Integer::compact], so that integers with the
+    /// same value are equal regardless of their width.
+    ///
+    /// Sequences of integers are converted as a whole into a
+    /// [Key::IntegerSeq], using the narrowest width which holds every element.
+    /// That is signed if any element is negative, and unsigned otherwise. Maps
+    /// are not sorted again, so a key with integer map keys might have to be
+    /// [normalized][Key::normalize] afterwards.
+    ///
+    /// Traversal does not use recursion, so it is safe to use on deeply nested
+    /// keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::{key, to_key, Integer, Key};
+    ///
+    /// let mut key = to_key(&(7u64, -3i64, vec![1u64, 300]))?;
+    /// key.compact_integers();
+    /// assert_eq!(key[0], Key::Integer(Integer::U8(7)));
+    /// assert_eq!(key[1], Key::Integer(Integer::I8(-3)));
+    /// assert_eq!(key[2], key!([1u16, 300u16]));
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn compact_integers(&mut self) {
+        self.map_integers(Integer::compact);
+    }
+
+    /// Replace every integer in this key.
+    ///
+    /// Sequences of integers are replaced as a whole, and stored as a
+    /// [Key::IntegerSeq] using the narrowest width which holds every replaced
+    /// element. So the same values produce the same key, regardless of whether
+    /// they were serialized from a tuple or a sequence of any width.
+    fn map_integers(&mut self, f: fn(&Integer) -> Integer) {
+        self.walk_mut(|key| {
+            match key {
+                Key::Integer(integer) => *integer = f(integer),
+                Key::IntegerSeq(seq) => {
+                    let integers = seq.iter().map(|integer| f(&integer)).collect::<Vec<_>>();
+
+                    if let Some(seq) = unify(&integers) {
+                        *key = Key::IntegerSeq(seq);
+                    } else {
+                        *key = Key::Seq(integers.into_iter().map(Key::Integer).collect());
+                    }
+                }
+                Key::Seq(seq) => {
+                    let integers = seq
+                        .iter()
+                        .map(|element| match element {
+                            Key::Integer(integer) => Some(f(integer)),
+                            _ => None,
+                        })
+                        .collect::<Option<Vec<_>>>();
+
+                    if let Some(seq) = integers.as_deref().and_then(unify) {
+                        *key = Key::IntegerSeq(seq);
+                    }
+                }
+                _ => {}
+            }
+
+            Walk::Continue
+        });
+    }
+}
+
+/// The number of bits used by the variant of the given integer.
+fn bits(integer: &Integer) -> u32 {
+    match integer {
+        Integer::I8(..) | Integer::U8(..) => 8,
+        Integer::I16(..) | Integer::U16(..) => 16,
+        Integer::I32(..) | Integer::U32(..) => 32,
+        Integer::I64(..) | Integer::U64(..) => 64,
+        Integer::I128(..) | Integer::U128(..) => 128,
+    }
+}
+
+/// Store the given integers in a compact sequence using the narrowest width
+/// which can hold every one of them without narrowing any.
+///
+/// The sequence is signed if any of the integers use a signed variant, and
+/// unsigned otherwise. This returns `None` if the integers are empty, or if
+/// they need a signed width but hold an unsigned value which doesn't fit in
+/// an `i128`.
+fn unify(integers: &[Integer]) -> Option<IntegerSeq> {
+    if integers.is_empty() {
+        return None;
+    }
+
+    let signed = integers.iter().any(Integer::is_signed_variant);
+    let mut width = 8;
+
+    for integer in integers {
+        let needed = match integer.to_u128() {
+            Some(value) if signed && !integer.is_signed_variant() => {
+                bits(&narrowest_signed(i128::try_from(value).ok()?))
+            }
+            _ => bits(integer),
+        };
+
+        width = width.max(needed);
+    }
+
+    macro_rules! collect {
+        ($variant:ident, $ty:ty) => {
+            IntegerSeq::$variant(
+                integers
+                    .iter()
+                    .map(|integer| <$ty>::try_from(integer).ok())
+                    .collect::<Option<_>>()?,
+            )
+        };
+    }
+
+    Some(match (signed, width) {
+        (true, 8) => collect!(I8, i8),
+        (true, 16) => collect!(I16, i16),
+        (true, 32) => collect!(I32, i32),
+        (true, 64) => collect!(I64, i64),
+        (true, _) => collect!(I128, i128),
+        (false, 8) => collect!(U8, u8),
+        (false, 16) => collect!(U16, u16),
+        (false, 32) => collect!(U32, u32),
+        (false, 64) => collect!(U64, u64),
+        (false, _) => collect!(U128, u128),
+    })
+}
+
+/// Serialize the given value to a [Key] where every integer is stored in its
+/// [canonical representation][Integer::canonical].
+///
+/// This is the same as calling [Key::canonicalize_integers] on the key
+/// produced by [to_key]. Since integers of different widths with the same
+/// value produce the same key, equality and hashing of integers become
+/// value-based. The key can still be deserialized into the original
+/// types with [from_key], as long as their values fit.
+///
+/// [to_key]: crate::to_key
+/// [from_key]: crate::from_key
+///
+/// # Examples
+///
+/// ```
+/// use serde_derive::{Deserialize, Serialize};
+/// use serde_hashkey::{from_key, to_key_with_canonical_integers};
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Narrow {
+///     id: u8,
+/// }
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Wide {
+///     id: i64,
+/// }
+///
+/// let key = to_key_with_canonical_integers(&Narrow { id: 42 })?;
+/// assert_eq!(key, to_key_with_canonical_integers(&Wide { id: 42 })?);
+/// assert_eq!(from_key::<Wide, _>(&key)?, Wide { id: 42 });
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+pub fn to_key_with_canonical_integers<T>(value: &T) -> Result<Key<RejectFloatPolicy>, Error>
+where
+    T: ?Sized + ser::Serialize,
+{
+    let mut key = to_key_with_policy::<T, RejectFloatPolicy>(value)?;
+    key.canonicalize_integers();
+    Ok(key)
+}
+
+/// Serialize the given value to a [Key] where every integer is stored in its
+/// [compact representation][Integer::compact], which is the narrowest variant
+/// which can hold its value.
+///
+/// This is the same as calling [Key::compact_integers] on the key produced by
+/// [to_key]. Keys serialized from types which use wide integers are cheaper to
+/// compare and hash, and are equal to keys serialized from types with narrower
+/// integers holding the same values. The key can still be deserialized into
+/// the original types with [from_key], as long as their values fit.
+///
+/// [to_key]: crate::to_key
+/// [from_key]: crate::from_key
+///
+/// # Examples
+///
+/// ```
+/// use serde_derive::{Deserialize, Serialize};
+/// use serde_hashkey::{from_key, to_key_with_compact_integers, Integer, Key};
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Wide {
+///     id: u64,
+///     offset: i64,
+/// }
+///
+/// let key = to_key_with_compact_integers(&Wide { id: 7, offset: -3 })?;
+/// assert_eq!(key["id"], Key::Integer(Integer::U8(7)));
+/// assert_eq!(key["offset"], Key::Integer(Integer::I8(-3)));
+/// assert_eq!(from_key::<Wide, _>(&key)?, Wide { id: 7, offset: -3 });
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+pub fn to_key_with_compact_integers<T>(value: &T) -> Result<Key<RejectFloatPolicy>, Error>
+where
+    T: ?Sized + ser::Serialize,
+{
+    let mut key = to_key_with_policy::<T, RejectFloatPolicy>(value)?;
+    key.compact_integers();
+    Ok(key)
+}
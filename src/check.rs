@@ -0,0 +1,574 @@
+//! Validation of values without stopping at the first error.
+
+use std::marker::PhantomData;
+
+use serde::ser;
+
+use crate::error::Error;
+use crate::float::{self, FloatPolicy, FloatRepr, RejectFloatPolicy};
+use crate::key::Key;
+use crate::path::{KeyPath, PathSegment};
+use crate::ser::to_key_with_policy;
+
+/// Check that the given value can be serialized with [to_key], reporting
+/// every problem along with the path to where it occurred instead of stopping
+/// at the first one.
+///
+/// Paths are the same as the ones which refer to the corresponding values in
+/// the key that would have been built, see [KeyPath]. Apart from map keys, no
+/// part of the key is built while checking.
+///
+/// [to_key]: crate::to_key
+///
+/// # Examples
+///
+/// ```
+/// use serde_derive::Serialize;
+/// use serde_hashkey::{check_key, Error, FloatWidth};
+///
+/// #[derive(Serialize)]
+/// struct Point {
+///     x: f32,
+///     y: f64,
+///     label: &'static str,
+/// }
+///
+/// let errors = check_key(&Point { x: 1.0, y: 2.0, label: "origin" }).unwrap_err();
+///
+/// let errors = errors
+///     .into_iter()
+///     .map(|(path, error)| (path.to_string(), error))
+///     .collect::<Vec<_>>();
+///
+/// assert_eq!(errors, vec![
+///     (String::from("x"), Error::UnsupportedFloat { width: FloatWidth::F32, value: 1.0 }),
+///     (String::from("y"), Error::UnsupportedFloat { width: FloatWidth::F64, value: 2.0 }),
+/// ]);
+/// ```
+pub fn check_key<T>(value: &T) -> Result<(), Vec<(KeyPath, Error)>>
+where
+    T: ?Sized + ser::Serialize,
+{
+    check_key_with_policy::<T, RejectFloatPolicy>(value)
+}
+
+/// Internal helper to check a value with the given policy.
+pub(crate) fn check_key_with_policy<T, F>(value: &T) -> Result<(), Vec<(KeyPath, Error)>>
+where
+    T: ?Sized + ser::Serialize,
+    F: FloatPolicy,
+{
+    let mut state = State {
+        path: KeyPath::new(),
+        errors: Vec::new(),
+    };
+
+    state.check::<T, F>(value);
+
+    if state.errors.is_empty() {
+        Ok(())
+    } else {
+        Err(state.errors)
+    }
+}
+
+struct State {
+    path: KeyPath,
+    errors: Vec<(KeyPath, Error)>,
+}
+
+impl State {
+    /// Check a value at the current path, recording the error it was aborted
+    /// with if any.
+    fn check<T, F>(&mut self, value: &T)
+    where
+        T: ?Sized + ser::Serialize,
+        F: FloatPolicy,
+    {
+        let len = self.path.len();
+
+        if let Err(error) = value.serialize(Checker::<F> {
+            state: self,
+            _marker: PhantomData,
+        }) {
+            // NB: an aborted variant might have left its segment behind.
+            while self.path.len() > len {
+                self.path.pop();
+            }
+
+            self.report(error);
+        }
+    }
+
+    /// Check a value at the given segment relative to the current path.
+    fn check_at<T, F>(&mut self, segment: PathSegment, value: &T)
+    where
+        T: ?Sized + ser::Serialize,
+        F: FloatPolicy,
+    {
+        self.path.push(segment);
+        self.check::<T, F>(value);
+        self.path.pop();
+    }
+
+    fn report(&mut self, error: Error) {
+        self.errors.push((self.path.clone(), error));
+    }
+}
+
+/// A serializer which records errors instead of building a key.
+///
+/// Errors are only returned if a [ser::Serialize] implementation fails on its
+/// own, in which case they are recorded by the closest enclosing value.
+struct Checker<'a, F> {
+    state: &'a mut State,
+    _marker: PhantomData<F>,
+}
+
+impl<'a, F> ser::Serializer for Checker<'a, F>
+where
+    F: FloatPolicy,
+{
+    type Ok = ();
+    type Error = Error;
+
+    type SerializeSeq = CheckSeq<'a, F>;
+    type SerializeTuple = CheckSeq<'a, F>;
+    type SerializeTupleStruct = CheckSeq<'a, F>;
+    type SerializeTupleVariant = CheckSeq<'a, F>;
+    type SerializeMap = CheckMap<'a, F>;
+    type SerializeStruct = CheckMap<'a, F>;
+    type SerializeStructVariant = CheckMap<'a, F>;
+
+    fn serialize_bool(self, _: bool) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_i8(self, _: i8) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_i16(self, _: i16) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_i32(self, _: i32) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_i64(self, _: i64) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_i128(self, _: i128) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_u8(self, _: u8) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_u16(self, _: u16) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_u32(self, _: u32) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_u64(self, _: u64) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_u128(self, _: u128) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_f32(self, value: f32) -> Result<(), Error> {
+        if let Err(error) = F::serialize_f32(value) {
+            self.state.report(error);
+        }
+
+        Ok(())
+    }
+
+    fn serialize_f64(self, value: f64) -> Result<(), Error> {
+        if let Err(error) = <F::F64 as FloatRepr<f64>>::serialize(value) {
+            self.state.report(error);
+        }
+
+        Ok(())
+    }
+
+    fn serialize_char(self, _: char) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_str(self, _: &str) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_bytes(self, _: &[u8]) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_unit(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        if float::is_f16(name) {
+            let key = to_key_with_policy::<T, F>(value)?;
+
+            if let Err(error) = float::from_newtype_struct(name, key) {
+                self.state.report(error);
+            }
+
+            return Ok(());
+        }
+
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        self.state
+            .check_at::<T, F>(PathSegment::Field(variant.into()), value);
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        Ok(CheckSeq::new(self.state, false))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        self.state.path.push(PathSegment::Field(variant.into()));
+        Ok(CheckSeq::new(self.state, true))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Ok(CheckMap::new(self.state, false))
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        self.state.path.push(PathSegment::Field(variant.into()));
+        Ok(CheckMap::new(self.state, true))
+    }
+
+    #[inline]
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+/// Checks the elements of sequences, tuples and tuple variants.
+struct CheckSeq<'a, F> {
+    state: &'a mut State,
+    index: usize,
+    /// If the path has a variant segment which needs to be popped at the end.
+    variant: bool,
+    _marker: PhantomData<F>,
+}
+
+impl<'a, F> CheckSeq<'a, F>
+where
+    F: FloatPolicy,
+{
+    fn new(state: &'a mut State, variant: bool) -> Self {
+        Self {
+            state,
+            index: 0,
+            variant,
+            _marker: PhantomData,
+        }
+    }
+
+    fn element<T>(&mut self, value: &T)
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        self.state
+            .check_at::<T, F>(PathSegment::Index(self.index), value);
+        self.index += 1;
+    }
+
+    fn end(self) {
+        if self.variant {
+            self.state.path.pop();
+        }
+    }
+}
+
+impl<F> ser::SerializeSeq for CheckSeq<'_, F>
+where
+    F: FloatPolicy,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        self.element(value);
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), Error> {
+        CheckSeq::end(self);
+        Ok(())
+    }
+}
+
+impl<F> ser::SerializeTuple for CheckSeq<'_, F>
+where
+    F: FloatPolicy,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        self.element(value);
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), Error> {
+        CheckSeq::end(self);
+        Ok(())
+    }
+}
+
+impl<F> ser::SerializeTupleStruct for CheckSeq<'_, F>
+where
+    F: FloatPolicy,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        self.element(value);
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), Error> {
+        CheckSeq::end(self);
+        Ok(())
+    }
+}
+
+impl<F> ser::SerializeTupleVariant for CheckSeq<'_, F>
+where
+    F: FloatPolicy,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        self.element(value);
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), Error> {
+        CheckSeq::end(self);
+        Ok(())
+    }
+}
+
+/// Checks the entries of maps, structs and struct variants.
+struct CheckMap<'a, F> {
+    state: &'a mut State,
+    index: usize,
+    /// The segment of the value whose key was most recently checked.
+    next_segment: Option<PathSegment>,
+    /// If the path has a variant segment which needs to be popped at the end.
+    variant: bool,
+    _marker: PhantomData<F>,
+}
+
+impl<'a, F> CheckMap<'a, F>
+where
+    F: FloatPolicy,
+{
+    fn new(state: &'a mut State, variant: bool) -> Self {
+        Self {
+            state,
+            index: 0,
+            next_segment: None,
+            variant,
+            _marker: PhantomData,
+        }
+    }
+
+    fn end(self) {
+        if self.variant {
+            self.state.path.pop();
+        }
+    }
+}
+
+impl<F> ser::SerializeMap for CheckMap<'_, F>
+where
+    F: FloatPolicy,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        let index = self.index;
+        self.index += 1;
+
+        // NB: map keys are built since their value determines the path.
+        let segment = match to_key_with_policy::<_, F>(&key) {
+            Ok(Key::String(field)) => PathSegment::Field((*field).into()),
+            Ok(..) => PathSegment::Entry(index),
+            Err(..) => {
+                self.state.check_at::<T, F>(PathSegment::Entry(index), key);
+                PathSegment::Entry(index)
+            }
+        };
+
+        self.next_segment = Some(segment);
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        let segment = match self.next_segment.take() {
+            Some(segment) => segment,
+            None => return Err(Error::MissingValue),
+        };
+
+        self.state.check_at::<T, F>(segment, value);
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), Error> {
+        CheckMap::end(self);
+        Ok(())
+    }
+}
+
+impl<F> ser::SerializeStruct for CheckMap<'_, F>
+where
+    F: FloatPolicy,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        self.state
+            .check_at::<T, F>(PathSegment::Field(key.into()), value);
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), Error> {
+        CheckMap::end(self);
+        Ok(())
+    }
+}
+
+impl<F> ser::SerializeStructVariant for CheckMap<'_, F>
+where
+    F: FloatPolicy,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        self.state
+            .check_at::<T, F>(PathSegment::Field(key.into()), value);
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), Error> {
+        CheckMap::end(self);
+        Ok(())
+    }
+}
@@ -0,0 +1,138 @@
+//! Cloning of keys without recursion.
+
+use std::slice;
+
+use crate::float::FloatPolicy;
+use crate::key::Key;
+
+/// Keys are cloned without recursion, so that cloning a deeply nested key
+/// can't overflow the stack.
+impl<F> Clone for Key<F>
+where
+    F: FloatPolicy + Clone,
+{
+    fn clone(&self) -> Self {
+        let mut stack = Vec::<Frame<'_, F>>::new();
+        let mut next = self;
+
+        loop {
+            let mut key = match next {
+                Key::Seq(seq) if !seq.is_empty() => {
+                    stack.push(Frame::Seq(seq.iter(), Vec::with_capacity(seq.len())));
+                    None
+                }
+                Key::Map(map) if !map.is_empty() => {
+                    stack.push(Frame::Map(
+                        map.iter(),
+                        Vec::with_capacity(map.len()),
+                        None,
+                        None,
+                    ));
+                    None
+                }
+                key => Some(key.clone_shallow()),
+            };
+
+            next = loop {
+                let frame = match stack.last_mut() {
+                    Some(frame) => frame,
+                    None => return key.unwrap_or(Key::Unit),
+                };
+
+                if let Some(key) = key.take() {
+                    frame.accept(key);
+                }
+
+                match frame.next() {
+                    Some(next) => break next,
+                    // Every element of the innermost container has been cloned.
+                    None => key = stack.pop().map(Frame::finish),
+                }
+            };
+        }
+    }
+}
+
+impl<F> Key<F>
+where
+    F: FloatPolicy + Clone,
+{
+    /// Clone a key which doesn't contain any other keys.
+    fn clone_shallow(&self) -> Self {
+        match self {
+            Key::Null => Key::Null,
+            Key::Unit => Key::Unit,
+            Key::Bool(value) => Key::Bool(*value),
+            Key::Integer(value) => Key::Integer(value.clone()),
+            Key::Float(value) => Key::Float(value.clone()),
+            Key::Bytes(value) => Key::Bytes(value.clone()),
+            Key::Char(value) => Key::Char(*value),
+            Key::String(value) => Key::String(value.clone()),
+            Key::Seq(..) => Key::Seq(Box::default()),
+            Key::Map(..) => Key::Map(Box::default()),
+            Key::IntegerSeq(value) => Key::IntegerSeq(value.clone()),
+            Key::FloatSeq(value) => Key::FloatSeq(value.clone()),
+        }
+    }
+}
+
+/// A container whose elements are currently being cloned.
+enum Frame<'a, F>
+where
+    F: FloatPolicy,
+{
+    Seq(slice::Iter<'a, Key<F>>, Vec<Key<F>>),
+    /// The entries of a map, followed by the clone of the key of the current
+    /// entry once it has been cloned and the value of the current entry while
+    /// its key is being cloned.
+    Map(
+        slice::Iter<'a, (Key<F>, Key<F>)>,
+        Vec<(Key<F>, Key<F>)>,
+        Option<Key<F>>,
+        Option<&'a Key<F>>,
+    ),
+}
+
+impl<'a, F> Frame<'a, F>
+where
+    F: FloatPolicy,
+{
+    /// The next element to clone.
+    fn next(&mut self) -> Option<&'a Key<F>> {
+        match self {
+            Frame::Seq(iter, _) => iter.next(),
+            Frame::Map(iter, _, _, value) => {
+                if let Some(value) = value.take() {
+                    return Some(value);
+                }
+
+                let (key, next) = iter.next()?;
+                *value = Some(next);
+                Some(key)
+            }
+        }
+    }
+
+    /// Accept the clone of the element which was last returned by
+    /// [Frame::next].
+    fn accept(&mut self, element: Key<F>) {
+        match self {
+            Frame::Seq(_, done) => done.push(element),
+            Frame::Map(_, done, key, value) => {
+                if value.is_some() {
+                    *key = Some(element);
+                } else if let Some(key) = key.take() {
+                    done.push((key, element));
+                }
+            }
+        }
+    }
+
+    /// Construct the cloned container.
+    fn finish(self) -> Key<F> {
+        match self {
+            Frame::Seq(_, done) => Key::Seq(done.into()),
+            Frame::Map(_, done, ..) => Key::Map(done.into()),
+        }
+    }
+}
@@ -0,0 +1,45 @@
+//! Comparisons between keys and primitive values.
+//!
+//! A key is only equal to a primitive value if it's the corresponding kind of
+//! key, so `Key::String("true".into())` is not equal to `true`. Integers are
+//! compared by value, so a [Key::Integer] of any width is equal to an `i64`
+//! or `u64` with the same value.
+
+use crate::float::FloatPolicy;
+use crate::key::Key;
+
+macro_rules! impl_partial_eq {
+    ($($ty:ty, $key:ident, $other:ident => $eq:expr;)*) => {
+        $(
+            impl<F> PartialEq<$ty> for Key<F>
+            where
+                F: FloatPolicy,
+            {
+                #[inline]
+                fn eq(&self, other: &$ty) -> bool {
+                    let ($key, $other) = (self, other);
+                    $eq
+                }
+            }
+
+            impl<F> PartialEq<Key<F>> for $ty
+            where
+                F: FloatPolicy,
+            {
+                #[inline]
+                fn eq(&self, other: &Key<F>) -> bool {
+                    other == self
+                }
+            }
+        )*
+    };
+}
+
+impl_partial_eq! {
+    str, key, other => key.as_str() == Some(other);
+    &str, key, other => key.as_str() == Some(*other);
+    bool, key, other => key.as_bool() == Some(*other);
+    i64, key, other => key.as_i64() == Some(*other);
+    u64, key, other => key.as_u64() == Some(*other);
+    [u8], key, other => key.as_bytes() == Some(other);
+}
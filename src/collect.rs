@@ -0,0 +1,153 @@
+//! Building sequence and map keys from iterators.
+
+use std::iter::FromIterator;
+use std::mem;
+
+use crate::float::FloatPolicy;
+use crate::key::Key;
+
+/// Collect keys into a sequence.
+///
+/// This produces the same key as serializing a sequence of the same elements,
+/// which means that integers or floats of the same width are stored as a
+/// [Key::IntegerSeq] or [Key::FloatSeq].
+///
+/// # Examples
+///
+/// ```
+/// use serde_hashkey::{to_key, Key};
+///
+/// let key: Key = (1u32..4).map(Key::from).collect();
+/// assert_eq!(key, to_key(&vec![1u32, 2, 3])?);
+///
+/// let key: Key = Vec::<Key>::new().into_iter().collect();
+/// assert_eq!(key, to_key(&Vec::<u32>::new())?);
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+impl<F> FromIterator<Key<F>> for Key<F>
+where
+    F: FloatPolicy,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = Key<F>>,
+    {
+        Key::seq(iter.into_iter().collect())
+    }
+}
+
+/// Collect key-value pairs into a map, with entries kept in the order they
+/// are produced.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::BTreeMap;
+///
+/// use serde_hashkey::{to_key, Error, Key};
+///
+/// let entries = vec![("a", 1u32), ("b", 2u32)];
+///
+/// let key: Key = entries
+///     .iter()
+///     .map(|(k, v)| Ok((to_key(k)?, to_key(v)?)))
+///     .collect::<Result<_, Error>>()?;
+///
+/// assert_eq!(key, to_key(&entries.into_iter().collect::<BTreeMap<_, _>>())?);
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+impl<F> FromIterator<(Key<F>, Key<F>)> for Key<F>
+where
+    F: FloatPolicy,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (Key<F>, Key<F>)>,
+    {
+        Key::Map(iter.into_iter().collect())
+    }
+}
+
+/// Append elements to the end of a sequence.
+///
+/// A [Key::Unit] is treated as an empty sequence and is turned into one. See
+/// [Key::push] for details.
+///
+/// # Panics
+///
+/// Panics if this key is any other kind of key than a sequence.
+///
+/// # Examples
+///
+/// ```
+/// use serde_hashkey::{to_key, Key};
+///
+/// let mut key = Key::Unit;
+/// key.extend(vec![Key::from(1u32), Key::from(2u32)]);
+/// key.extend(vec![Key::from(3u32)]);
+/// assert_eq!(key, to_key(&vec![1u32, 2, 3])?);
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+impl<F> Extend<Key<F>> for Key<F>
+where
+    F: FloatPolicy,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = Key<F>>,
+    {
+        let mut elements = match self.take_elements() {
+            Ok(elements) => elements,
+            Err(..) => panic!("can only extend a sequence with elements"),
+        };
+
+        elements.extend(iter);
+        *self = Key::seq(elements);
+    }
+}
+
+/// Append entries to the end of a map.
+///
+/// A [Key::Unit] is treated as an empty map and is turned into one. Entries
+/// are added without checking if the map already has an entry with the same
+/// key, and the map is not sorted again.
+///
+/// # Panics
+///
+/// Panics if this key is any other kind of key than a map.
+///
+/// # Examples
+///
+/// ```
+/// use serde_derive::Serialize;
+/// use serde_hashkey::{to_key, Key};
+///
+/// #[derive(Serialize)]
+/// struct Author {
+///     name: &'static str,
+///     age: u32,
+/// }
+///
+/// let mut key = to_key(&Author { name: "Noah", age: 42 })?;
+/// key.extend(vec![(Key::String("pages".into()), Key::from(320u32))]);
+/// assert_eq!(key.get(&to_key("pages")?), Some(&Key::from(320u32)));
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+impl<F> Extend<(Key<F>, Key<F>)> for Key<F>
+where
+    F: FloatPolicy,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (Key<F>, Key<F>)>,
+    {
+        let mut entries = match self {
+            Key::Unit => Vec::new(),
+            Key::Map(map) => mem::take(map).into_vec(),
+            _ => panic!("can only extend a map with entries"),
+        };
+
+        entries.extend(iter);
+        *self = Key::Map(entries.into());
+    }
+}
@@ -0,0 +1,320 @@
+//! Configuration of how values are represented in a [Key].
+
+use crate::float::FloatPolicy;
+use crate::key::Key;
+use crate::path::PathSegment;
+use std::sync::Arc;
+
+/// How enum variants are represented in a [Key].
+///
+/// # Examples
+///
+/// ```
+/// use serde_derive::Serialize;
+/// use serde_hashkey::{to_key, to_key_with_config, EnumRepr, Key, KeyConfig, RejectFloatPolicy};
+///
+/// #[derive(Serialize)]
+/// enum Operation {
+///     Move(u32, u32),
+/// }
+///
+/// let config = KeyConfig::new().enum_repr(EnumRepr::adjacent());
+/// let key = to_key_with_config::<RejectFloatPolicy, _>(&Operation::Move(1, 2), &config)?;
+///
+/// let expected: Key = Key::Map(vec![
+///     (Key::String("t".into()), Key::String("Move".into())),
+///     (Key::String("c".into()), to_key(&(1u32, 2u32))?),
+/// ].into());
+///
+/// assert_eq!(key, expected);
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum EnumRepr {
+    /// Variants are represented as a map with a single entry from the name of
+    /// the variant to its content, like `{"Move": [1, 2]}`. Unit variants are
+    /// represented as the name of the variant.
+    ///
+    /// This is the default, and the same as serde's externally tagged
+    /// representation.
+    #[default]
+    External,
+    /// Variants are represented as a map with an entry for the name of the
+    /// variant and one for its content, like `{"t": "Move", "c": [1, 2]}`.
+    /// Unit variants only have the entry for their name.
+    ///
+    /// This is the same as serde's adjacently tagged representation, and keeps
+    /// enums distinct from maps with a single entry.
+    Adjacent {
+        /// The key of the entry holding the name of the variant.
+        tag: &'static str,
+        /// The key of the entry holding the content of the variant.
+        content: &'static str,
+    },
+}
+
+impl EnumRepr {
+    /// The adjacently tagged representation using `t` as the key of the tag
+    /// and `c` as the key of the content.
+    pub const fn adjacent() -> Self {
+        EnumRepr::Adjacent {
+            tag: "t",
+            content: "c",
+        }
+    }
+}
+
+/// Configuration of how values are represented in a [Key], used by
+/// [to_key_with_config] and [from_key_with_config].
+///
+/// Keys built with different configurations are not comparable with each
+/// other, and have to be deserialized using the configuration they were built
+/// with.
+///
+/// [to_key_with_config]: crate::to_key_with_config
+/// [from_key_with_config]: crate::from_key_with_config
+///
+/// # Examples
+///
+/// ```
+/// use serde_derive::{Deserialize, Serialize};
+/// use serde_hashkey::{from_key_with_config, to_key_with_config, EnumRepr, KeyConfig, RejectFloatPolicy};
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// enum Operation {
+///     Move { x: u32, y: u32 },
+///     Stop,
+/// }
+///
+/// let config = KeyConfig::new().enum_repr(EnumRepr::adjacent());
+///
+/// let key = to_key_with_config::<RejectFloatPolicy, _>(&Operation::Move { x: 1, y: 2 }, &config)?;
+/// let operation: Operation = from_key_with_config(&key, &config)?;
+/// assert_eq!(operation, Operation::Move { x: 1, y: 2 });
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyConfig {
+    enum_repr: EnumRepr,
+    transparent_newtype_variants: bool,
+    type_names: bool,
+    reject_duplicate_keys: bool,
+}
+
+impl KeyConfig {
+    /// Construct the default configuration, which is what [to_key] and
+    /// [from_key] use.
+    ///
+    /// [to_key]: crate::to_key
+    /// [from_key]: crate::from_key
+    pub const fn new() -> Self {
+        Self {
+            enum_repr: EnumRepr::External,
+            transparent_newtype_variants: false,
+            type_names: false,
+            reject_duplicate_keys: false,
+        }
+    }
+
+    /// Configure how enum variants are represented.
+    pub const fn enum_repr(self, enum_repr: EnumRepr) -> Self {
+        Self { enum_repr, ..self }
+    }
+
+    /// Configure newtype variants to be represented by the key of their
+    /// content alone, without anything identifying the variant.
+    ///
+    /// This makes `Id::Uuid(s)` produce the same key as `s`, so keys built
+    /// from an enum match keys built from the values it wraps. It's one-way,
+    /// since the variant can't be recovered from the key. Deserializing any
+    /// enum with this option enabled fails with
+    /// [Error::TransparentEnum][crate::Error::TransparentEnum]; deserialize the
+    /// type of the content instead.
+    ///
+    /// See [to_key_transparent][crate::to_key_transparent].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_derive::{Deserialize, Serialize};
+    /// use serde_hashkey::{from_key_with_config, to_key, to_key_with_config, Error, KeyConfig, RejectFloatPolicy};
+    ///
+    /// #[derive(Debug, Serialize, Deserialize)]
+    /// enum Id {
+    ///     Uuid(String),
+    ///     Serial(u64),
+    /// }
+    ///
+    /// let config = KeyConfig::new().transparent_newtype_variants(true);
+    ///
+    /// let key = to_key_with_config::<RejectFloatPolicy, _>(&Id::Serial(42), &config)?;
+    /// assert_eq!(key, to_key(&42u64)?);
+    ///
+    /// let error = from_key_with_config::<Id, _>(&key, &config).unwrap_err();
+    /// assert_eq!(error, Error::TransparentEnum("Id"));
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub const fn transparent_newtype_variants(self, transparent_newtype_variants: bool) -> Self {
+        Self {
+            transparent_newtype_variants,
+            ..self
+        }
+    }
+
+    /// Configure structs and enums to be tagged with the name of their type.
+    ///
+    /// The names of types are otherwise discarded, so `A { x: 1 }` and
+    /// `B { x: 1 }` produce the same key. With this option enabled, the key of
+    /// a value of a named type is wrapped in a sequence of two elements, where
+    /// the first is the name of the type and the second is the key it would
+    /// otherwise have, like `["A", {"x": 1}]`. This applies to unit, newtype,
+    /// tuple and regular structs as well as to enums, which are tagged with
+    /// the name of the enum around the representation of the variant.
+    ///
+    /// The name is the one passed to serde, so it's affected by
+    /// `#[serde(rename)]` but not by the module a type is declared in.
+    /// Deserializing with the same configuration checks and skips the tags.
+    /// serde's internally tagged and untagged enums and flattened fields
+    /// deserialize by buffering values and can't skip tags inside of them.
+    ///
+    /// See [to_key_tagged][crate::to_key_tagged] and
+    /// [from_key_tagged][crate::from_key_tagged].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_derive::{Deserialize, Serialize};
+    /// use serde_hashkey::{from_key_with_config, to_key, to_key_with_config, KeyConfig, RejectFloatPolicy};
+    ///
+    /// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    /// struct Meters(u32);
+    ///
+    /// let config = KeyConfig::new().type_names(true);
+    ///
+    /// let key = to_key_with_config::<RejectFloatPolicy, _>(&Meters(42), &config)?;
+    /// assert_eq!(key, to_key(&("Meters", 42u32))?);
+    ///
+    /// let meters: Meters = from_key_with_config(&key, &config)?;
+    /// assert_eq!(meters, Meters(42));
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub const fn type_names(self, type_names: bool) -> Self {
+        Self { type_names, ..self }
+    }
+
+    /// Configure maps which serialize the same key more than once to be
+    /// rejected with [Error::DuplicateKey][crate::Error::DuplicateKey].
+    ///
+    /// Maps are otherwise stored with their entries as they are serialized,
+    /// so a broken `Serialize` implementation, or a `HashMap` whose keys have
+    /// a broken `Eq` implementation, silently produces a [Key::Map] with
+    /// duplicate keys which isn't equal to the map without them. This also
+    /// applies to structs, which are stored as maps.
+    ///
+    /// The check is done once all entries of a map have been serialized, by
+    /// sorting its keys.
+    ///
+    /// [Key::Map]: crate::Key::Map
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::{to_key_with_config, Error, Key, KeyConfig, RejectFloatPolicy};
+    ///
+    /// // A map which holds the same key twice.
+    /// let map: Key = Key::Map(vec![
+    ///     (Key::from("a"), Key::from(1u32)),
+    ///     (Key::from("a"), Key::from(2u32)),
+    /// ].into());
+    ///
+    /// let config = KeyConfig::new().reject_duplicate_keys(true);
+    ///
+    /// let error = to_key_with_config::<RejectFloatPolicy, _>(&map, &config).unwrap_err();
+    /// assert_eq!(error, Error::DuplicateKey(String::from("a")));
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub const fn reject_duplicate_keys(self, reject_duplicate_keys: bool) -> Self {
+        Self {
+            reject_duplicate_keys,
+            ..self
+        }
+    }
+
+    /// How enum variants are represented.
+    pub(crate) fn get_enum_repr(&self) -> EnumRepr {
+        self.enum_repr
+    }
+
+    /// Whether newtype variants are represented by their content alone.
+    pub(crate) fn get_transparent_newtype_variants(&self) -> bool {
+        self.transparent_newtype_variants
+    }
+
+    /// Whether structs and enums are tagged with the names of their types.
+    pub(crate) fn get_type_names(&self) -> bool {
+        self.type_names
+    }
+
+    /// Whether maps which serialize the same key more than once are rejected.
+    pub(crate) fn get_reject_duplicate_keys(&self) -> bool {
+        self.reject_duplicate_keys
+    }
+
+    /// Build the key of a variant with the given name and content.
+    pub(crate) fn variant<F>(&self, name: Arc<str>, content: Option<Key<F>>) -> Key<F>
+    where
+        F: FloatPolicy,
+    {
+        match (self.enum_repr, content) {
+            (EnumRepr::External, None) => Key::String(name),
+            (EnumRepr::External, Some(content)) => Key::Map([(Key::String(name), content)].into()),
+            (EnumRepr::Adjacent { tag, .. }, None) => {
+                Key::Map([(Key::String(tag.into()), Key::String(name))].into())
+            }
+            (EnumRepr::Adjacent { tag, content: key }, Some(content)) => Key::Map(
+                [
+                    (Key::String(tag.into()), Key::String(name)),
+                    (Key::String(key.into()), content),
+                ]
+                .into(),
+            ),
+        }
+    }
+
+    /// Build the key of a variant with the given name whose content is left
+    /// out, either because it's excluded or because it failed to serialize.
+    ///
+    /// Unlike the key of a unit variant this is always a map, holding the
+    /// entries of the variant which are present.
+    pub(crate) fn incomplete_variant<F>(&self, name: Arc<str>, content: Option<Key<F>>) -> Key<F>
+    where
+        F: FloatPolicy,
+    {
+        match (self.enum_repr, content) {
+            (_, Some(content)) => self.variant(name, Some(content)),
+            (EnumRepr::External, None) => Key::Map(Box::default()),
+            (EnumRepr::Adjacent { .. }, None) => self.variant(name, None),
+        }
+    }
+
+    /// The name of the field holding the content of the variant with the given
+    /// name.
+    pub(crate) fn variant_field<'a>(&self, name: &'a str) -> &'a str {
+        match self.enum_repr {
+            EnumRepr::External => name,
+            EnumRepr::Adjacent { content, .. } => content,
+        }
+    }
+
+    /// The path segment of the content of the variant with the given name.
+    pub(crate) fn variant_segment(&self, name: &str) -> PathSegment {
+        PathSegment::Field(self.variant_field(name).into())
+    }
+}
+
+impl Default for KeyConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,213 @@
+//! Conversions from keys into the values they contain.
+//!
+//! Each conversion only succeeds for the corresponding kind of key, and errors
+//! with [Error::Unexpected] naming the expected kind otherwise. Integers of
+//! any width, either as keys or as an [Integer], can be converted into any
+//! integer type which can hold their value, and error with
+//! [Error::IntegerOverflow] if it can't.
+
+use std::convert::TryFrom;
+
+use crate::error::Error;
+use crate::float::FloatPolicy;
+use crate::key::{Integer, Key};
+
+impl<F> TryFrom<Key<F>> for String
+where
+    F: FloatPolicy,
+{
+    type Error = Error;
+
+    fn try_from(key: Key<F>) -> Result<Self, Error> {
+        match key {
+            Key::String(string) => Ok(String::from(&*string)),
+            _ => Err(Error::Unexpected("string")),
+        }
+    }
+}
+
+impl<F> TryFrom<&Key<F>> for String
+where
+    F: FloatPolicy,
+{
+    type Error = Error;
+
+    fn try_from(key: &Key<F>) -> Result<Self, Error> {
+        match key.as_str() {
+            Some(string) => Ok(string.to_owned()),
+            None => Err(Error::Unexpected("string")),
+        }
+    }
+}
+
+impl<F> TryFrom<Key<F>> for Vec<u8>
+where
+    F: FloatPolicy,
+{
+    type Error = Error;
+
+    fn try_from(key: Key<F>) -> Result<Self, Error> {
+        match key {
+            Key::Bytes(bytes) => Ok(bytes.into_vec()),
+            _ => Err(Error::Unexpected("bytes")),
+        }
+    }
+}
+
+impl<F> TryFrom<&Key<F>> for Vec<u8>
+where
+    F: FloatPolicy,
+{
+    type Error = Error;
+
+    fn try_from(key: &Key<F>) -> Result<Self, Error> {
+        match key.as_bytes() {
+            Some(bytes) => Ok(bytes.to_vec()),
+            None => Err(Error::Unexpected("bytes")),
+        }
+    }
+}
+
+impl<F> TryFrom<Key<F>> for bool
+where
+    F: FloatPolicy,
+{
+    type Error = Error;
+
+    fn try_from(key: Key<F>) -> Result<Self, Error> {
+        bool::try_from(&key)
+    }
+}
+
+impl<F> TryFrom<&Key<F>> for bool
+where
+    F: FloatPolicy,
+{
+    type Error = Error;
+
+    fn try_from(key: &Key<F>) -> Result<Self, Error> {
+        key.as_bool().ok_or(Error::Unexpected("bool"))
+    }
+}
+
+/// Sequences are converted into their elements, and the compact
+/// [Key::IntegerSeq] and [Key::FloatSeq] are expanded into individual keys.
+impl<F> TryFrom<Key<F>> for Vec<Key<F>>
+where
+    F: FloatPolicy,
+{
+    type Error = Error;
+
+    fn try_from(key: Key<F>) -> Result<Self, Error> {
+        match key.into_seq_iter() {
+            Some(elements) => Ok(elements.collect()),
+            None => Err(Error::Unexpected("sequence")),
+        }
+    }
+}
+
+/// Sequences are converted into their elements, and the compact
+/// [Key::IntegerSeq] and [Key::FloatSeq] are expanded into individual keys.
+impl<F> TryFrom<&Key<F>> for Vec<Key<F>>
+where
+    F: FloatPolicy + Clone,
+{
+    type Error = Error;
+
+    fn try_from(key: &Key<F>) -> Result<Self, Error> {
+        match key.seq_elements() {
+            Some(elements) => Ok(elements.to_vec()),
+            None => Err(Error::Unexpected("sequence")),
+        }
+    }
+}
+
+impl<F> TryFrom<Key<F>> for Vec<(Key<F>, Key<F>)>
+where
+    F: FloatPolicy,
+{
+    type Error = Error;
+
+    fn try_from(key: Key<F>) -> Result<Self, Error> {
+        match key {
+            Key::Map(map) => Ok(map.into_vec()),
+            _ => Err(Error::Unexpected("map")),
+        }
+    }
+}
+
+impl<F> TryFrom<&Key<F>> for Vec<(Key<F>, Key<F>)>
+where
+    F: FloatPolicy + Clone,
+{
+    type Error = Error;
+
+    fn try_from(key: &Key<F>) -> Result<Self, Error> {
+        match key {
+            Key::Map(map) => Ok(map.to_vec()),
+            _ => Err(Error::Unexpected("map")),
+        }
+    }
+}
+
+macro_rules! impl_try_from_integer {
+    ($($ty:ty, $wide:ident;)*) => {
+        $(
+            impl<F> TryFrom<Key<F>> for $ty
+            where
+                F: FloatPolicy,
+            {
+                type Error = Error;
+
+                fn try_from(key: Key<F>) -> Result<Self, Error> {
+                    <$ty>::try_from(&key)
+                }
+            }
+
+            impl<F> TryFrom<&Key<F>> for $ty
+            where
+                F: FloatPolicy,
+            {
+                type Error = Error;
+
+                fn try_from(key: &Key<F>) -> Result<Self, Error> {
+                    <$ty>::try_from(key.as_integer().ok_or(Error::Unexpected("integer"))?)
+                }
+            }
+
+            impl TryFrom<Integer> for $ty {
+                type Error = Error;
+
+                fn try_from(integer: Integer) -> Result<Self, Error> {
+                    <$ty>::try_from(&integer)
+                }
+            }
+
+            impl TryFrom<&Integer> for $ty {
+                type Error = Error;
+
+                fn try_from(integer: &Integer) -> Result<Self, Error> {
+                    integer
+                        .$wide()
+                        .and_then(|value| <$ty>::try_from(value).ok())
+                        .ok_or(Error::IntegerOverflow(stringify!($ty)))
+                }
+            }
+        )*
+    };
+}
+
+impl_try_from_integer! {
+    i8, to_i128;
+    i16, to_i128;
+    i32, to_i128;
+    i64, to_i128;
+    i128, to_i128;
+    isize, to_i128;
+    u8, to_u128;
+    u16, to_u128;
+    u32, to_u128;
+    u64, to_u128;
+    u128, to_u128;
+    usize, to_u128;
+}
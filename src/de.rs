@@ -1,13 +1,60 @@
 //! Deserialization for serde-hashkey.
 
+use serde::de::value::BorrowedStrDeserializer;
 use serde::de::{self, IntoDeserializer};
+use std::convert::TryFrom;
+use std::fmt;
+use std::slice;
 
+use crate::config::{EnumRepr, KeyConfig};
 use crate::error::Error;
-use crate::float::{FloatPolicy, FloatRepr};
-use crate::key::{Float, Integer, Key};
+use crate::float::{self, FloatPolicy, FloatRepr};
+use crate::key::{Float, FloatSeq, Integer, IntegerSeq, Key};
+
+/// Implement the methods which deserialize integers of a specific type, which
+/// convert from whichever integer type is stored and visit exactly the type
+/// which is asked for.
+///
+/// Values which aren't integers are deserialized through `deserialize_any`.
+macro_rules! deserialize_integers {
+    ($($method:ident => $visit:ident($ty:ty)),* $(,)?) => {
+        $(
+            #[inline]
+            fn $method<V>(self, visitor: V) -> Result<V::Value, Error>
+            where
+                V: de::Visitor<'de>,
+            {
+                let value = match self.integer() {
+                    Some(integer) => <$ty as TryFrom<&crate::key::Integer>>::try_from(integer)?,
+                    None => return self.deserialize_any(visitor),
+                };
+
+                visitor.$visit(value)
+            }
+        )*
+    };
+}
 
 /// Deserialize the given type from a [Key].
 ///
+/// Strings and bytes are borrowed from the key, so the deserialized value can
+/// hold onto them through `&'de str`, `&'de [u8]` or `Cow<'de, str>` for as
+/// long as the key lives.
+///
+/// The fields of structs are looked up by name, so they are served in the
+/// order they are declared even if the map holding them is stored in a
+/// different order, like after [Key::normalize].
+///
+/// All of serde's enum representations are supported, which includes
+/// externally, internally and adjacently tagged as well as untagged enums.
+///
+/// Untagged and internally tagged enums and flattened fields buffer their
+/// content in serde's private `Content` type, which doesn't support 128-bit
+/// integers. 128-bit integers are therefore delivered as 64-bit integers if
+/// they fit, and values which don't fit fail to deserialize when buffered.
+/// Fields declared as `u128` or `i128` can't be deserialized from buffered
+/// content at all.
+///
 /// # Examples
 ///
 /// ```
@@ -44,9 +91,29 @@ use crate::key::{Float, Integer, Key};
 /// # }
 /// ```
 ///
+/// Borrowing from the key:
+///
+/// ```
+/// use serde_derive::{Deserialize, Serialize};
+/// use serde_hashkey::{from_key, to_key};
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Author<'a> {
+///     name: &'a str,
+///     age: u32,
+/// }
+///
+/// let key = to_key(&Author { name: "Noah", age: 42 })?;
+/// let author: Author<'_> = from_key(&key)?;
+///
+/// assert_eq!(author, Author { name: "Noah", age: 42 });
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+///
 /// Using a non-standard float policy:
 ///
 /// ```
+/// # #[cfg(feature = "ordered-float")] {
 /// use serde_derive::{Deserialize, Serialize};
 /// use serde_hashkey::{from_key, to_key_with_ordered_float, OrderedFloat, Key};
 /// use std::collections::HashMap;
@@ -63,7 +130,6 @@ use crate::key::{Float, Integer, Key};
 ///     author: Author,
 /// }
 ///
-/// # fn main() -> serde_hashkey::Result<()> {
 /// let book = Book {
 ///     title: String::from("Birds of a feather"),
 ///     author: Author {
@@ -76,321 +142,472 @@ use crate::key::{Float, Integer, Key};
 /// let book2 = from_key(&key)?;
 ///
 /// assert_eq!(book, book2);
-/// # Ok(())
 /// # }
+/// # Ok::<_, serde_hashkey::Error>(())
 /// ```
-pub fn from_key<T, F>(value: &Key<F>) -> Result<T, crate::error::Error>
+pub fn from_key<'de, T, F>(value: &'de Key<F>) -> Result<T, crate::error::Error>
 where
-    T: de::DeserializeOwned,
+    T: de::Deserialize<'de>,
     F: FloatPolicy,
 {
-    T::deserialize(Deserializer::new(value))
+    T::deserialize(KeyDeserializer::new(value))
 }
 
-impl<'de, F> IntoDeserializer<'de, Error> for &'de Key<F>
+/// Deserialize the given type from a [Key] which was built using the given
+/// [KeyConfig].
+///
+/// # Examples
+///
+/// ```
+/// use serde_derive::{Deserialize, Serialize};
+/// use serde_hashkey::{from_key, from_key_with_config, to_key_with_config, EnumRepr, KeyConfig, RejectFloatPolicy};
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// enum Shape {
+///     Circle(u32),
+///     Empty,
+/// }
+///
+/// let config = KeyConfig::new().enum_repr(EnumRepr::adjacent());
+///
+/// let key = to_key_with_config::<RejectFloatPolicy, _>(&Shape::Circle(7), &config)?;
+/// assert_eq!(from_key_with_config::<Shape, _>(&key, &config)?, Shape::Circle(7));
+/// assert!(from_key::<Shape, _>(&key).is_err());
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+pub fn from_key_with_config<'de, T, F>(value: &'de Key<F>, config: &KeyConfig) -> Result<T, Error>
 where
+    T: de::Deserialize<'de>,
     F: FloatPolicy,
 {
-    type Deserializer = Deserializer<'de, F>;
-
-    fn into_deserializer(self) -> Self::Deserializer {
-        Deserializer::new(self)
-    }
+    T::deserialize(KeyDeserializer::with_config(value, *config))
 }
 
-pub struct Deserializer<'de, F>
+/// Deserialize the given type from a [Key] which was built using
+/// [to_key_tagged], checking and skipping the names of types.
+///
+/// This is a shorthand for [from_key_with_config] using
+/// [KeyConfig::type_names].
+///
+/// [to_key_tagged]: crate::to_key_tagged
+///
+/// # Examples
+///
+/// ```
+/// use serde_derive::{Deserialize, Serialize};
+/// use serde_hashkey::{from_key_tagged, to_key_tagged};
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct A {
+///     x: u32,
+/// }
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct B {
+///     x: u32,
+/// }
+///
+/// let key = to_key_tagged(&A { x: 1 })?;
+/// assert_eq!(from_key_tagged::<A, _>(&key)?, A { x: 1 });
+/// assert!(from_key_tagged::<B, _>(&key).is_err());
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+pub fn from_key_tagged<'de, T, F>(value: &'de Key<F>) -> Result<T, Error>
 where
+    T: de::Deserialize<'de>,
     F: FloatPolicy,
 {
-    value: &'de Key<F>,
+    let config = KeyConfig::new().type_names(true);
+    from_key_with_config(value, &config)
 }
 
-impl<'de, F> Deserializer<'de, F>
+impl<'de, F> IntoDeserializer<'de, Error> for &'de Key<F>
 where
     F: FloatPolicy,
 {
-    pub fn new(value: &'de Key<F>) -> Self {
-        Self { value }
+    type Deserializer = KeyDeserializer<'de, F>;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        KeyDeserializer::new(self)
     }
 }
 
-impl<'de, F> de::Deserializer<'de> for Deserializer<'de, F>
+/// A [Deserializer] which reads from a borrowed [Key].
+///
+/// This is what [from_key] uses to deserialize values, and can be used
+/// directly where a [Deserializer] is expected. Like with adapters such as
+/// [`serde_path_to_error`], or to drive a [DeserializeSeed].
+///
+/// The lifetime `'de` is the lifetime of the key being deserialized from.
+/// Strings and bytes are borrowed from it, so the deserialized value can hold
+/// onto them, like through `&'de str`.
+///
+/// Sequences and maps are handed to visitors through access types which are
+/// internal to this crate, so they can only be deserialized through this type.
+///
+/// Integers stored as 128-bit integers are visited as 64-bit integers if they
+/// fit, unless a [Key] is being deserialized, in which case their width is
+/// preserved. This is because serde buffers the content of untagged and
+/// internally tagged enums and of flattened fields in a type which doesn't
+/// support 128-bit integers. Values which don't fit are visited as 128-bit
+/// integers, so buffering them fails with an error like ``invalid type:
+/// integer `340282366920938463463374607431768211455` as u128, expected any
+/// value``. Note that serde can't deserialize fields declared as `u128` or
+/// `i128` out of buffered content at all, no matter their value.
+///
+/// [Deserializer]: serde::Deserializer
+/// [DeserializeSeed]: serde::de::DeserializeSeed
+/// [`serde_path_to_error`]: https://docs.rs/serde_path_to_error
+///
+/// # Examples
+///
+/// ```
+/// use serde_derive::{Deserialize, Serialize};
+/// use serde_hashkey::{to_key, KeyDeserializer};
+///
+/// #[derive(Serialize)]
+/// struct Input {
+///     name: &'static str,
+///     age: &'static str,
+/// }
+///
+/// #[derive(Debug, Deserialize)]
+/// struct Author<'a> {
+///     name: &'a str,
+///     age: u32,
+/// }
+///
+/// let key = to_key(&Input { name: "Noah", age: "forty-two" })?;
+///
+/// let result: Result<Author<'_>, _> = serde_path_to_error::deserialize(KeyDeserializer::new(&key));
+/// let error = result.unwrap_err();
+/// assert_eq!(error.path().to_string(), "age");
+///
+/// let key = to_key(&("Noah", 42u32))?;
+/// let (name, age): (&str, u32) = serde::Deserialize::deserialize(key.deserializer())?;
+/// assert_eq!(name, "Noah");
+/// assert_eq!(age, 42);
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+pub struct KeyDeserializer<'de, F>
 where
     F: FloatPolicy,
 {
-    type Error = Error;
+    value: &'de Key<F>,
+    config: KeyConfig,
+}
 
-    #[inline]
-    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Error>
-    where
-        V: de::Visitor<'de>,
-    {
-        match self.value {
-            Key::Unit => visitor.visit_unit(),
-            Key::Bool(b) => visitor.visit_bool(*b),
-            Key::Integer(Integer::U8(v)) => visitor.visit_u8(*v),
-            Key::Integer(Integer::U16(v)) => visitor.visit_u16(*v),
-            Key::Integer(Integer::U32(v)) => visitor.visit_u32(*v),
-            Key::Integer(Integer::U64(v)) => visitor.visit_u64(*v),
-            Key::Integer(Integer::U128(v)) => visitor.visit_u128(*v),
-            Key::Integer(Integer::I8(v)) => visitor.visit_i8(*v),
-            Key::Integer(Integer::I16(v)) => visitor.visit_i16(*v),
-            Key::Integer(Integer::I32(v)) => visitor.visit_i32(*v),
-            Key::Integer(Integer::I64(v)) => visitor.visit_i64(*v),
-            Key::Integer(Integer::I128(v)) => visitor.visit_i128(*v),
-            Key::Float(Float::F32(float)) => <F::F32 as FloatRepr<f32>>::visit(float, visitor),
-            Key::Float(Float::F64(float)) => <F::F64 as FloatRepr<f64>>::visit(float, visitor),
-            Key::String(s) => visitor.visit_str(s),
-            Key::Seq(array) => visitor.visit_seq(SeqDeserializer::new(array)),
-            Key::Map(m) => visitor.visit_map(MapDeserializer::new(m)),
-            Key::Bytes(bytes) => visitor.visit_borrowed_bytes(bytes),
-        }
+impl<'de, F> KeyDeserializer<'de, F>
+where
+    F: FloatPolicy,
+{
+    /// Construct a new deserializer reading from the given key, which was
+    /// built using the default [KeyConfig].
+    pub const fn new(value: &'de Key<F>) -> Self {
+        Self::with_config(value, KeyConfig::new())
     }
 
-    #[inline]
-    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Error>
-    where
-        V: de::Visitor<'de>,
-    {
-        match self.value {
-            Key::Unit => visitor.visit_none(),
-            _ => visitor.visit_some(self),
-        }
+    /// Construct a new deserializer reading from the given key, which was
+    /// built using the given [KeyConfig].
+    pub const fn with_config(value: &'de Key<F>, config: KeyConfig) -> Self {
+        Self { value, config }
     }
 
-    #[inline]
-    fn deserialize_newtype_struct<V>(self, _name: &str, visitor: V) -> Result<V::Value, Error>
-    where
-        V: de::Visitor<'de>,
-    {
-        visitor.visit_newtype_struct(self)
+    /// The deserializer which does the work.
+    fn source(self) -> SourceDeserializer<&'de Key<F>> {
+        SourceDeserializer::new(self.value, self.config)
     }
+}
 
-    #[inline]
-    fn deserialize_enum<V>(
-        self,
-        _name: &str,
-        _variants: &'static [&'static str],
-        visitor: V,
-    ) -> Result<V::Value, Error>
-    where
-        V: de::Visitor<'de>,
-    {
-        let (variant, value) = match self.value {
-            Key::Map(value) => {
-                let mut iter = value.iter();
+impl<'de, F> Clone for KeyDeserializer<'de, F>
+where
+    F: FloatPolicy,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
 
-                let (variant, value) = match iter.next() {
-                    Some(v) => v,
-                    None => {
-                        return Err(Error::Unexpected("map with a single key"));
-                    }
-                };
+impl<'de, F> Copy for KeyDeserializer<'de, F> where F: FloatPolicy {}
 
-                // enums are encoded in json as maps with a single key:value pair
-                if iter.next().is_some() {
-                    return Err(Error::Unexpected("map with a single key"));
-                }
+impl<F> Key<F>
+where
+    F: FloatPolicy,
+{
+    /// Construct a [KeyDeserializer] reading from this key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde::Deserialize;
+    /// use serde_hashkey::to_key;
+    ///
+    /// let key = to_key(&vec!["a", "b"])?;
+    /// let values = Vec::<&str>::deserialize(key.deserializer())?;
+    /// assert_eq!(values, ["a", "b"]);
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn deserializer(&self) -> KeyDeserializer<'_, F> {
+        KeyDeserializer::new(self)
+    }
+}
 
-                (variant, Some(value))
-            }
-            Key::String(_) => (self.value, None),
-            _ => {
-                return Err(Error::Unexpected("string or map"));
+/// Implement the methods of a deserializer by forwarding them to the
+/// [SourceDeserializer] returned by `self.source()`.
+macro_rules! forward_to_source {
+    ($($method:ident($($arg:ident: $ty:ty),*)),* $(,)?) => {
+        $(
+            #[inline]
+            fn $method<V>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, Error>
+            where
+                V: de::Visitor<'de>,
+            {
+                de::Deserializer::$method(self.source(), $($arg,)* visitor)
             }
-        };
+        )*
+    };
+}
 
-        visitor.visit_enum(EnumDeserializer { variant, value })
-    }
+impl<'de, F> de::Deserializer<'de> for KeyDeserializer<'de, F>
+where
+    F: FloatPolicy,
+{
+    type Error = Error;
 
     #[inline]
     fn is_human_readable(&self) -> bool {
         false
     }
 
-    serde::forward_to_deserialize_any! {
-        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string unit
-        unit_struct seq tuple tuple_struct map struct identifier ignored_any
-        bytes byte_buf
+    forward_to_source! {
+        deserialize_any(),
+        deserialize_bool(),
+        deserialize_i8(),
+        deserialize_i16(),
+        deserialize_i32(),
+        deserialize_i64(),
+        deserialize_i128(),
+        deserialize_u8(),
+        deserialize_u16(),
+        deserialize_u32(),
+        deserialize_u64(),
+        deserialize_u128(),
+        deserialize_f32(),
+        deserialize_f64(),
+        deserialize_char(),
+        deserialize_str(),
+        deserialize_string(),
+        deserialize_bytes(),
+        deserialize_byte_buf(),
+        deserialize_option(),
+        deserialize_unit(),
+        deserialize_unit_struct(name: &'static str),
+        deserialize_newtype_struct(name: &'static str),
+        deserialize_seq(),
+        deserialize_tuple(len: usize),
+        deserialize_tuple_struct(name: &'static str, len: usize),
+        deserialize_map(),
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]),
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]),
+        deserialize_identifier(),
+        deserialize_ignored_any(),
     }
 }
 
-struct EnumDeserializer<'de, F>
-where
-    F: FloatPolicy,
-{
-    variant: &'de Key<F>,
-    value: Option<&'de Key<F>>,
-}
+/// A key which is deserialized either by reference through [from_key], or by
+/// value through [from_key_owned].
+///
+/// Everything which depends on how the key is held goes through this trait,
+/// so that both are deserialized by the same [SourceDeserializer].
+///
+/// [from_key_owned]: crate::from_key_owned
+pub(crate) trait Source<'de>: Sized {
+    /// The float policy of the key.
+    type Policy: FloatPolicy;
+    /// The elements of a sequence.
+    type Elements: ExactSizeIterator<Item = Self>;
+    /// The entries of a map.
+    type Entries: Iterator<Item = (Self, Self)>;
 
-impl<'de, F> de::EnumAccess<'de> for EnumDeserializer<'de, F>
-where
-    F: FloatPolicy,
-{
-    type Error = Error;
-    type Variant = VariantDeserializer<'de, F>;
+    /// Access the key being deserialized.
+    fn key(&self) -> &Key<Self::Policy>;
 
-    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Error>
+    /// Take the elements of a [Key::Seq]. Any other key has no elements.
+    fn into_seq(self) -> Self::Elements;
+
+    /// Take the entries of a [Key::Map]. Any other key has no entries.
+    fn into_map(self) -> Self::Entries;
+
+    /// Take the key of the value wrapped by a `Some`, see [Key::unwrap_some].
+    fn into_some(self) -> Self;
+
+    /// Visit a [Key::String], [Key::Bytes] or a compact sequence of bytes.
+    fn visit_data<V>(self, visitor: V) -> Result<V::Value, Error>
     where
-        V: de::DeserializeSeed<'de>,
-    {
-        let variant = self.variant.into_deserializer();
-        let visitor = VariantDeserializer { value: self.value };
-        seed.deserialize(variant).map(|v| (v, visitor))
-    }
+        V: de::Visitor<'de>;
 }
 
-struct VariantDeserializer<'de, F>
+/// Strings and bytes are borrowed from the key.
+impl<'de, F> Source<'de> for &'de Key<F>
 where
     F: FloatPolicy,
 {
-    value: Option<&'de Key<F>>,
-}
+    type Policy = F;
+    type Elements = slice::Iter<'de, Key<F>>;
+    type Entries = BorrowedEntries<'de, F>;
 
-impl<'de, F> de::VariantAccess<'de> for VariantDeserializer<'de, F>
-where
-    F: FloatPolicy,
-{
-    type Error = Error;
+    #[inline]
+    fn key(&self) -> &Key<F> {
+        self
+    }
 
-    fn unit_variant(self) -> Result<(), Error> {
-        match self.value {
-            Some(value) => de::Deserialize::deserialize(Deserializer::new(value)),
-            None => Ok(()),
+    #[inline]
+    fn into_seq(self) -> Self::Elements {
+        match self {
+            Key::Seq(values) => values.iter(),
+            _ => [].iter(),
         }
     }
 
-    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Error>
-    where
-        T: de::DeserializeSeed<'de>,
-    {
-        match self.value {
-            Some(value) => seed.deserialize(Deserializer::new(value)),
-            None => Err(Error::UnexpectedVariant("newtype variant")),
+    #[inline]
+    fn into_map(self) -> Self::Entries {
+        match self {
+            Key::Map(entries) => BorrowedEntries(entries.iter()),
+            _ => BorrowedEntries([].iter()),
         }
     }
 
-    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value, Error>
-    where
-        V: de::Visitor<'de>,
-    {
-        match self.value {
-            Some(Key::Seq(values)) => {
-                de::Deserializer::deserialize_any(SeqDeserializer::new(values), visitor)
-            }
-            Some(_) => Err(Error::UnexpectedVariant("tuple variant")),
-            None => Err(Error::UnexpectedVariant("tuple variant")),
-        }
+    #[inline]
+    fn into_some(self) -> Self {
+        self.unwrap_some().unwrap_or(self)
     }
 
-    fn struct_variant<V>(
-        self,
-        _fields: &'static [&'static str],
-        visitor: V,
-    ) -> Result<V::Value, Error>
+    #[inline]
+    fn visit_data<V>(self, visitor: V) -> Result<V::Value, Error>
     where
         V: de::Visitor<'de>,
     {
-        match self.value {
-            Some(Key::Map(v)) => {
-                de::Deserializer::deserialize_any(MapDeserializer::new(v), visitor)
-            }
-            Some(_) => Err(Error::UnexpectedVariant("struct variant")),
-            _ => Err(Error::UnexpectedVariant("struct variant")),
+        match self {
+            Key::String(s) => visitor.visit_borrowed_str(s),
+            Key::Bytes(bytes) => visitor.visit_borrowed_bytes(bytes),
+            Key::IntegerSeq(IntegerSeq::U8(bytes)) => visitor.visit_borrowed_bytes(bytes),
+            _ => Err(Error::Unexpected("string or bytes")),
         }
     }
 }
 
-struct SeqDeserializer<'de, F>
+/// The entries of a borrowed map.
+pub(crate) struct BorrowedEntries<'de, F>(slice::Iter<'de, (Key<F>, Key<F>)>)
 where
-    F: FloatPolicy,
-{
-    values: &'de [Key<F>],
-}
+    F: FloatPolicy;
 
-impl<'de, F> SeqDeserializer<'de, F>
+impl<'de, F> Iterator for BorrowedEntries<'de, F>
 where
     F: FloatPolicy,
 {
-    pub fn new(values: &'de [Key<F>]) -> Self {
-        Self { values }
+    type Item = (&'de Key<F>, &'de Key<F>);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, value) = self.0.next()?;
+        Some((key, value))
     }
 }
 
-impl<'de, F> serde::Deserializer<'de> for SeqDeserializer<'de, F>
+/// A deserializer reading from a [Source].
+pub(crate) struct SourceDeserializer<S> {
+    value: S,
+    config: KeyConfig,
+}
+
+impl<'de, S> SourceDeserializer<S>
 where
-    F: FloatPolicy,
+    S: Source<'de>,
 {
-    type Error = Error;
+    pub(crate) fn new(value: S, config: KeyConfig) -> Self {
+        Self { value, config }
+    }
 
-    #[inline]
-    fn deserialize_any<V>(mut self, visitor: V) -> Result<V::Value, Error>
+    /// Get the integer being deserialized, if it is one.
+    fn integer<'a>(&'a self) -> Option<&'a Integer>
     where
-        V: de::Visitor<'de>,
+        'de: 'a,
     {
-        let len = self.values.len();
-
-        if len == 0 {
-            return visitor.visit_unit();
+        match self.value.key() {
+            Key::Integer(integer) => Some(integer),
+            _ => None,
         }
+    }
 
-        let ret = visitor.visit_seq(&mut self)?;
+    /// Skip the tag of a value of the type with the given name, if type names
+    /// are enabled.
+    ///
+    /// See [KeyConfig::type_names].
+    fn untagged(self, name: &str) -> Result<Self, Error> {
+        const EXPECTED: &str = "sequence tagged with the name of the type";
 
-        if self.values.is_empty() {
-            return Ok(ret);
+        if !self.config.get_type_names() {
+            return Ok(self);
         }
 
-        Err(Error::InvalidLength)
-    }
+        match self.value.key() {
+            Key::Seq(values) if matches!(&values[..], [Key::String(tag), _] if **tag == *name) => {}
+            _ => return Err(Error::Unexpected(EXPECTED)),
+        }
 
-    serde::forward_to_deserialize_any! {
-        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
-        bytes byte_buf option unit unit_struct newtype_struct seq tuple
-        tuple_struct map struct enum identifier ignored_any
+        match self.value.into_seq().last() {
+            Some(value) => Ok(Self::new(value, self.config)),
+            None => Err(Error::Unexpected(EXPECTED)),
+        }
     }
-}
-
-impl<'de, F> de::SeqAccess<'de> for SeqDeserializer<'de, F>
-where
-    F: FloatPolicy,
-{
-    type Error = Error;
 
-    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    /// Deserialize an enum stored using [EnumRepr::Adjacent], which is a map
+    /// with an entry for the variant and an optional entry for its content.
+    fn deserialize_adjacent_enum<V>(
+        self,
+        tag: &str,
+        content: &str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
     where
-        T: de::DeserializeSeed<'de>,
+        V: de::Visitor<'de>,
     {
-        let (first, rest) = match self.values.split_first() {
-            Some((first, rest)) => (first, rest),
-            None => return Ok(None),
-        };
+        const EXPECTED: &str = "map with a tag and optional content";
 
-        self.values = rest;
-        let value = seed.deserialize(Deserializer::new(first))?;
-        Ok(Some(value))
-    }
-}
+        if !matches!(self.value.key(), Key::Map(..)) {
+            return Err(Error::Unexpected(EXPECTED));
+        }
 
-struct MapDeserializer<'de, F>
-where
-    F: FloatPolicy,
-{
-    map: &'de [(Key<F>, Key<F>)],
-    value: Option<&'de Key<F>>,
-}
+        let mut variant = None;
+        let mut value = None;
 
-impl<'de, F> MapDeserializer<'de, F>
-where
-    F: FloatPolicy,
-{
-    pub fn new(map: &'de [(Key<F>, Key<F>)]) -> Self {
-        Self { map, value: None }
+        for (key, entry) in self.value.into_map() {
+            match key.key() {
+                Key::String(key) if **key == *tag && variant.is_none() => {
+                    variant = Some(entry);
+                }
+                Key::String(key) if **key == *content && value.is_none() => {
+                    value = Some(entry);
+                }
+                _ => return Err(Error::Unexpected(EXPECTED)),
+            }
+        }
+
+        let variant = variant.ok_or(Error::Unexpected(EXPECTED))?;
+
+        visitor.visit_enum(EnumDeserializer {
+            variant,
+            variants,
+            value,
+            config: self.config,
+        })
     }
 }
 
-impl<'de, F> serde::Deserializer<'de> for MapDeserializer<'de, F>
+impl<'de, S> de::Deserializer<'de> for SourceDeserializer<S>
 where
-    F: FloatPolicy,
+    S: Source<'de>,
 {
     type Error = Error;
 
@@ -399,19 +616,667 @@ where
     where
         V: de::Visitor<'de>,
     {
-        visitor.visit_map(self)
-    }
-
-    serde::forward_to_deserialize_any! {
-        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
-        bytes byte_buf option unit unit_struct newtype_struct seq tuple
-        tuple_struct map struct enum identifier ignored_any
+        match self.value.key() {
+            Key::Null => visitor.visit_none(),
+            Key::Unit => visitor.visit_unit(),
+            Key::Bool(b) => visitor.visit_bool(*b),
+            Key::Integer(Integer::U8(v)) => visitor.visit_u8(*v),
+            Key::Integer(Integer::U16(v)) => visitor.visit_u16(*v),
+            Key::Integer(Integer::U32(v)) => visitor.visit_u32(*v),
+            Key::Integer(Integer::U64(v)) => visitor.visit_u64(*v),
+            Key::Integer(Integer::U128(v)) => visit_u128(*v, visitor),
+            Key::Integer(Integer::I8(v)) => visitor.visit_i8(*v),
+            Key::Integer(Integer::I16(v)) => visitor.visit_i16(*v),
+            Key::Integer(Integer::I32(v)) => visitor.visit_i32(*v),
+            Key::Integer(Integer::I64(v)) => visitor.visit_i64(*v),
+            Key::Integer(Integer::I128(v)) => visit_i128(*v, visitor),
+            Key::Float(float) => visit_float(float, visitor),
+            Key::Char(c) => visitor.visit_char(*c),
+            Key::String(..) | Key::Bytes(..) => self.value.visit_data(visitor),
+            Key::Seq(..) => {
+                visitor.visit_seq(SeqDeserializer::new(self.value.into_seq(), self.config))
+            }
+            Key::IntegerSeq(seq) => {
+                CompactSeqDeserializer::<S::Policy>::new(CompactSeq::Integer(seq)).visit(visitor)
+            }
+            Key::FloatSeq(seq) => {
+                CompactSeqDeserializer::new(CompactSeq::Float(seq)).visit(visitor)
+            }
+            Key::Map(..) => {
+                visitor.visit_map(MapDeserializer::new(self.value.into_map(), self.config))
+            }
+        }
+    }
+
+    #[inline]
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        // NB: A nested `None` is wrapped in a single-element sequence, see
+        // `Key::some`.
+        match self.value.key() {
+            Key::Null => visitor.visit_none(),
+            _ => visitor.visit_some(Self::new(self.value.into_some(), self.config)),
+        }
+    }
+
+    #[inline]
+    fn deserialize_newtype_struct<V>(self, name: &str, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        // NB: `half::f16` can be deserialized directly from a float.
+        if float::is_f16(name) && matches!(self.value.key(), Key::Float(..)) {
+            return self.deserialize_any(visitor);
+        }
+
+        let this = if float::is_f16(name) {
+            self
+        } else {
+            self.untagged(name)?
+        };
+
+        visitor.visit_newtype_struct(this)
+    }
+
+    #[inline]
+    fn deserialize_unit_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.untagged(name)?.deserialize_any(visitor)
+    }
+
+    #[inline]
+    fn deserialize_tuple_struct<V>(
+        self,
+        name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.untagged(name)?.deserialize_any(visitor)
+    }
+
+    #[inline]
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        let this = self.untagged(name)?;
+
+        // NB: Fields are looked up by name, since the entries of the map might
+        // not be stored in the order in which they are declared.
+        match this.value.key() {
+            Key::Map(..) => visit_struct(this.value, fields, this.config, visitor),
+            _ => this.deserialize_any(visitor),
+        }
+    }
+
+    #[inline]
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        if self.config.get_transparent_newtype_variants() {
+            return Err(Error::TransparentEnum(name));
+        }
+
+        let this = self.untagged(name)?;
+
+        if let EnumRepr::Adjacent { tag, content } = this.config.get_enum_repr() {
+            return this.deserialize_adjacent_enum(tag, content, variants, visitor);
+        }
+
+        let (variant, value) = match this.value.key() {
+            Key::Map(..) => {
+                let mut entries = this.value.into_map();
+
+                // enums are encoded in json as maps with a single key:value pair
+                match (entries.next(), entries.next()) {
+                    (Some((variant, value)), None) => (variant, Some(value)),
+                    _ => return Err(Error::Unexpected("map with a single key")),
+                }
+            }
+            // NB: integers are accepted as unit variants identified by their
+            // index.
+            Key::String(_) | Key::Integer(_) => (this.value, None),
+            _ => {
+                return Err(Error::Unexpected("string, integer or map"));
+            }
+        };
+
+        visitor.visit_enum(EnumDeserializer {
+            variant,
+            variants,
+            value,
+            config: this.config,
+        })
+    }
+
+    #[inline]
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        // NB: A `&[u8]` is serialized as a sequence of bytes, which is stored
+        // compactly unless it's empty.
+        match self.value.key() {
+            Key::IntegerSeq(IntegerSeq::U8(..)) => self.value.visit_data(visitor),
+            Key::Seq(seq) if seq.is_empty() => visitor.visit_borrowed_bytes(&[]),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    #[inline]
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_bytes(visitor)
+    }
+
+    #[inline]
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        // NB: There's nothing to consume since the key is already in memory,
+        // so ignored values such as unknown struct fields are skipped without
+        // visiting their contents.
+        visitor.visit_unit()
+    }
+
+    #[inline]
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    deserialize_integers! {
+        deserialize_i8 => visit_i8(i8),
+        deserialize_i16 => visit_i16(i16),
+        deserialize_i32 => visit_i32(i32),
+        deserialize_i64 => visit_i64(i64),
+        deserialize_i128 => visit_i128(i128),
+        deserialize_u8 => visit_u8(u8),
+        deserialize_u16 => visit_u16(u16),
+        deserialize_u32 => visit_u32(u32),
+        deserialize_u64 => visit_u64(u64),
+        deserialize_u128 => visit_u128(u128),
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool f32 f64 char str string unit seq tuple map identifier
+    }
+}
+
+struct EnumDeserializer<S> {
+    variant: S,
+    variants: &'static [&'static str],
+    value: Option<S>,
+    config: KeyConfig,
+}
+
+impl<'de, S> de::EnumAccess<'de> for EnumDeserializer<S>
+where
+    S: Source<'de>,
+{
+    type Error = Error;
+    type Variant = VariantDeserializer<S>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Error>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let visitor = VariantDeserializer {
+            value: self.value,
+            config: self.config,
+        };
+
+        // NB: Integer tags are resolved to the name of the variant at that
+        // index, so that seeds which only accept names can identify it.
+        let name = match self.variant.key() {
+            Key::Integer(index) => variant_name(index, self.variants),
+            _ => None,
+        };
+
+        let value = match name {
+            Some(name) => seed.deserialize(BorrowedStrDeserializer::<Error>::new(name))?,
+            None => seed.deserialize(SourceDeserializer::new(self.variant, self.config))?,
+        };
+
+        Ok((value, visitor))
     }
 }
 
-impl<'de, F> de::MapAccess<'de> for MapDeserializer<'de, F>
+struct VariantDeserializer<S> {
+    value: Option<S>,
+    config: KeyConfig,
+}
+
+impl<'de, S> de::VariantAccess<'de> for VariantDeserializer<S>
+where
+    S: Source<'de>,
+{
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        // NB: Any value is ignored, since this is also what a `#[serde(other)]`
+        // variant receives when the stored variant is not recognized.
+        if let Some(value) = self.value {
+            <de::IgnoredAny as de::Deserialize>::deserialize(SourceDeserializer::new(
+                value,
+                self.config,
+            ))?;
+        }
+
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Error>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        match self.value {
+            Some(value) => seed.deserialize(SourceDeserializer::new(value, self.config)),
+            None => Err(Error::UnexpectedVariant("newtype variant")),
+        }
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        let value = match self.value {
+            Some(value) => value,
+            None => return Err(Error::UnexpectedVariant("tuple variant")),
+        };
+
+        match value.key() {
+            Key::Seq(..) => de::Deserializer::deserialize_any(
+                SeqDeserializer::new(value.into_seq(), self.config),
+                visitor,
+            ),
+            Key::IntegerSeq(..) | Key::FloatSeq(..) => de::Deserializer::deserialize_any(
+                SourceDeserializer::new(value, self.config),
+                visitor,
+            ),
+            _ => Err(Error::UnexpectedVariant("tuple variant")),
+        }
+    }
+
+    fn struct_variant<V>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.value {
+            Some(value) if matches!(value.key(), Key::Map(..)) => {
+                visit_struct(value, fields, self.config, visitor)
+            }
+            _ => Err(Error::UnexpectedVariant("struct variant")),
+        }
+    }
+}
+
+struct SeqDeserializer<I> {
+    values: I,
+    config: KeyConfig,
+}
+
+impl<I> SeqDeserializer<I> {
+    fn new(values: I, config: KeyConfig) -> Self {
+        Self { values, config }
+    }
+}
+
+impl<'de, I> serde::Deserializer<'de> for SeqDeserializer<I>
+where
+    I: ExactSizeIterator,
+    I::Item: Source<'de>,
+{
+    type Error = Error;
+
+    #[inline]
+    fn deserialize_any<V>(mut self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        let ret = visitor.visit_seq(&mut self)?;
+
+        if self.values.len() == 0 {
+            return Ok(ret);
+        }
+
+        Err(Error::InvalidLength)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+impl<'de, I> de::SeqAccess<'de> for SeqDeserializer<I>
+where
+    I: ExactSizeIterator,
+    I::Item: Source<'de>,
+{
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        match self.values.next() {
+            Some(value) => {
+                let value = seed.deserialize(SourceDeserializer::new(value, self.config))?;
+                Ok(Some(value))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.values.len())
+    }
+}
+
+enum CompactSeq<'a, F>
+where
+    F: FloatPolicy,
+{
+    Integer(&'a IntegerSeq),
+    Float(&'a FloatSeq<F>),
+}
+
+/// Deserializer for the elements of a [Key::IntegerSeq] or [Key::FloatSeq].
+///
+/// Elements are copied out of the sequence, so they don't borrow from it.
+struct CompactSeqDeserializer<'a, F>
 where
     F: FloatPolicy,
+{
+    seq: CompactSeq<'a, F>,
+    index: usize,
+}
+
+impl<'a, F> CompactSeqDeserializer<'a, F>
+where
+    F: FloatPolicy,
+{
+    fn new(seq: CompactSeq<'a, F>) -> Self {
+        Self { seq, index: 0 }
+    }
+
+    fn len(&self) -> usize {
+        match self.seq {
+            CompactSeq::Integer(seq) => seq.len(),
+            CompactSeq::Float(seq) => seq.len(),
+        }
+    }
+
+    fn visit<'de, V>(mut self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        let ret = visitor.visit_seq(&mut self)?;
+
+        if self.index == self.len() {
+            return Ok(ret);
+        }
+
+        Err(Error::InvalidLength)
+    }
+}
+
+impl<'a, 'de, F> de::SeqAccess<'de> for CompactSeqDeserializer<'a, F>
+where
+    F: FloatPolicy,
+{
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        let element = match self.seq {
+            CompactSeq::Integer(seq) => seq.get(self.index).map(Element::Integer),
+            CompactSeq::Float(seq) => seq.get(self.index).map(Element::Float),
+        };
+
+        let element = match element {
+            Some(element) => element,
+            None => return Ok(None),
+        };
+
+        self.index += 1;
+        let value = seed.deserialize(element)?;
+        Ok(Some(value))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.len() - self.index)
+    }
+}
+
+/// A single element of a compact sequence.
+enum Element<F>
+where
+    F: FloatPolicy,
+{
+    Integer(Integer),
+    Float(Float<F>),
+}
+
+impl<'de, F> de::Deserializer<'de> for Element<F>
+where
+    F: FloatPolicy,
+{
+    type Error = Error;
+
+    #[inline]
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self {
+            Element::Integer(Integer::U8(v)) => visitor.visit_u8(v),
+            Element::Integer(Integer::U16(v)) => visitor.visit_u16(v),
+            Element::Integer(Integer::U32(v)) => visitor.visit_u32(v),
+            Element::Integer(Integer::U64(v)) => visitor.visit_u64(v),
+            Element::Integer(Integer::U128(v)) => visit_u128(v, visitor),
+            Element::Integer(Integer::I8(v)) => visitor.visit_i8(v),
+            Element::Integer(Integer::I16(v)) => visitor.visit_i16(v),
+            Element::Integer(Integer::I32(v)) => visitor.visit_i32(v),
+            Element::Integer(Integer::I64(v)) => visitor.visit_i64(v),
+            Element::Integer(Integer::I128(v)) => visit_i128(v, visitor),
+            Element::Float(float) => visit_float(&float, visitor),
+        }
+    }
+
+    #[inline]
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_some(self)
+    }
+
+    #[inline]
+    fn deserialize_newtype_struct<V>(self, name: &str, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        // NB: `half::f16` can be deserialized directly from a float.
+        if float::is_f16(name) && matches!(self, Element::Float(..)) {
+            return self.deserialize_any(visitor);
+        }
+
+        visitor.visit_newtype_struct(self)
+    }
+
+    #[inline]
+    fn deserialize_enum<V>(
+        self,
+        _name: &str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        match &self {
+            Element::Integer(index) => match variant_name(index, variants) {
+                Some(name) => visitor.visit_enum(NamedVariant(name)),
+                None => visitor.visit_enum(self),
+            },
+            Element::Float(..) => Err(Error::Unexpected("string, integer or map")),
+        }
+    }
+
+    #[inline]
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    deserialize_integers! {
+        deserialize_i8 => visit_i8(i8),
+        deserialize_i16 => visit_i16(i16),
+        deserialize_i32 => visit_i32(i32),
+        deserialize_i64 => visit_i64(i64),
+        deserialize_i128 => visit_i128(i128),
+        deserialize_u8 => visit_u8(u8),
+        deserialize_u16 => visit_u16(u16),
+        deserialize_u32 => visit_u32(u32),
+        deserialize_u64 => visit_u64(u64),
+        deserialize_u128 => visit_u128(u128),
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool f32 f64 char str string unit unit_struct seq tuple tuple_struct
+        map struct identifier ignored_any bytes byte_buf
+    }
+}
+
+impl<F> Element<F>
+where
+    F: FloatPolicy,
+{
+    /// Get the integer being deserialized, if it is one.
+    fn integer(&self) -> Option<&Integer> {
+        match self {
+            Element::Integer(integer) => Some(integer),
+            Element::Float(..) => None,
+        }
+    }
+}
+
+/// An integer element is a unit variant identified by its index.
+impl<'de, F> de::EnumAccess<'de> for Element<F>
+where
+    F: FloatPolicy,
+{
+    type Error = Error;
+    type Variant = UnitVariant;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Error>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        seed.deserialize(self).map(|v| (v, UnitVariant))
+    }
+}
+
+/// A unit variant identified by its name.
+struct NamedVariant(&'static str);
+
+impl<'de> de::EnumAccess<'de> for NamedVariant {
+    type Error = Error;
+    type Variant = UnitVariant;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Error>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        seed.deserialize(BorrowedStrDeserializer::new(self.0))
+            .map(|v| (v, UnitVariant))
+    }
+}
+
+/// Variant access for variants which are stored without a value.
+struct UnitVariant;
+
+impl<'de> de::VariantAccess<'de> for UnitVariant {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T>(self, _seed: T) -> Result<T::Value, Error>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        Err(Error::UnexpectedVariant("newtype variant"))
+    }
+
+    fn tuple_variant<V>(self, _len: usize, _visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        Err(Error::UnexpectedVariant("tuple variant"))
+    }
+
+    fn struct_variant<V>(
+        self,
+        _fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        Err(Error::UnexpectedVariant("struct variant"))
+    }
+}
+
+struct MapDeserializer<S, I> {
+    entries: I,
+    value: Option<S>,
+    config: KeyConfig,
+}
+
+impl<S, I> MapDeserializer<S, I> {
+    fn new(entries: I, config: KeyConfig) -> Self {
+        Self {
+            entries,
+            value: None,
+            config,
+        }
+    }
+}
+
+impl<'de, S, I> de::MapAccess<'de> for MapDeserializer<S, I>
+where
+    S: Source<'de>,
+    I: Iterator<Item = (S, S)>,
 {
     type Error = Error;
 
@@ -419,13 +1284,10 @@ where
     where
         K: de::DeserializeSeed<'de>,
     {
-        let next = self.map.split_first();
-
-        match next {
-            Some(((key, value), map)) => {
+        match self.entries.next() {
+            Some((key, value)) => {
                 self.value = Some(value);
-                self.map = map;
-                let value = seed.deserialize(key.into_deserializer())?;
+                let value = seed.deserialize(SourceDeserializer::new(key, self.config))?;
                 Ok(Some(value))
             }
             None => Ok(None),
@@ -441,6 +1303,192 @@ where
             None => return Err(Error::MissingValue),
         };
 
-        seed.deserialize(Deserializer::new(value))
+        seed.deserialize(SourceDeserializer::new(value, self.config))
+    }
+}
+
+/// Visit the entries of a map as the fields of a struct.
+///
+/// Fields are served in the order in which they are declared, followed by any
+/// entries which aren't fields in the order they are stored. Missing fields
+/// are left for the visitor to report, and extra entries for it to ignore or
+/// reject.
+fn visit_struct<'de, S, V>(
+    map: S,
+    fields: &'static [&'static str],
+    config: KeyConfig,
+    visitor: V,
+) -> Result<V::Value, Error>
+where
+    S: Source<'de>,
+    V: de::Visitor<'de>,
+{
+    let in_order = match map.key() {
+        Key::Map(entries) => is_field_order(entries, fields),
+        _ => true,
+    };
+
+    if in_order {
+        return visitor.visit_map(MapDeserializer::new(map.into_map(), config));
+    }
+
+    let index = FieldIndex::new(fields);
+    let mut sorted = map.into_map().collect::<Vec<_>>();
+    sorted.sort_by_cached_key(|(key, _)| index.rank(key.key()));
+    visitor.visit_map(MapDeserializer::new(sorted.into_iter(), config))
+}
+
+/// Test if the entries of a map are already in the order in which the given
+/// fields are declared, with any entries which aren't fields last.
+///
+/// This walks the entries and the fields side by side, so it doesn't need a
+/// [FieldIndex].
+fn is_field_order<F>(entries: &[(Key<F>, Key<F>)], fields: &[&str]) -> bool
+where
+    F: FloatPolicy,
+{
+    let mut rest = fields;
+
+    for (key, _) in entries {
+        let position = match key {
+            Key::String(name) => rest.iter().position(|field| *field == &**name),
+            _ => None,
+        };
+
+        match position {
+            // NB: a duplicate field stays at the same position.
+            Some(n) => rest = &rest[n..],
+            None if matches!(key, Key::String(name) if fields.contains(&&**name)) => return false,
+            None => rest = &[],
+        }
+    }
+
+    true
+}
+
+/// The positions of the fields of a struct sorted by name, which is built once
+/// per struct to find the field a map entry holds through a binary search.
+struct FieldIndex {
+    fields: Vec<(&'static str, usize)>,
+}
+
+impl FieldIndex {
+    fn new(fields: &'static [&'static str]) -> Self {
+        let mut fields = fields
+            .iter()
+            .enumerate()
+            .map(|(n, field)| (*field, n))
+            .collect::<Vec<_>>();
+
+        fields.sort_unstable();
+        Self { fields }
+    }
+
+    /// Get the position of the field which a map entry with the given key
+    /// holds, where entries which aren't fields are sorted last.
+    fn rank<F>(&self, key: &Key<F>) -> usize
+    where
+        F: FloatPolicy,
+    {
+        let name = match key {
+            Key::String(name) => &**name,
+            _ => return self.fields.len(),
+        };
+
+        match self
+            .fields
+            .binary_search_by(|(field, _)| (*field).cmp(name))
+        {
+            Ok(n) => self.fields[n].1,
+            Err(..) => self.fields.len(),
+        }
+    }
+}
+
+/// Resolve an integer variant tag to the name of the variant at that index.
+///
+/// Indexes which are out of range are left for the visitor to handle, so that
+/// they can be caught by a `#[serde(other)]` variant or be reported along with
+/// the number of variants the enum has.
+fn variant_name(index: &Integer, variants: &'static [&'static str]) -> Option<&'static str> {
+    let index = usize::try_from(index.to_u64()?).ok()?;
+    variants.get(index).copied()
+}
+
+/// Test if the visitor is the one used to deserialize a [Key], which preserves
+/// the width of integers.
+///
+/// That visitor is defined by this crate, so what it describes itself as
+/// expecting is known. It's compared without allocating.
+fn is_key_visitor<'de, V>(visitor: &V) -> bool
+where
+    V: de::Visitor<'de>,
+{
+    struct Expecting<'a, V>(&'a V);
+
+    impl<'de, V> fmt::Display for Expecting<'_, V>
+    where
+        V: de::Visitor<'de>,
+    {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            self.0.expecting(f)
+        }
+    }
+
+    /// Consumes the expected string, failing on the first difference.
+    struct Matches<'a>(&'a str);
+
+    impl fmt::Write for Matches<'_> {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            self.0 = self.0.strip_prefix(s).ok_or(fmt::Error)?;
+            Ok(())
+        }
+    }
+
+    let mut matches = Matches("any valid key");
+    fmt::write(&mut matches, format_args!("{}", Expecting(visitor))).is_ok() && matches.0.is_empty()
+}
+
+/// Visit a float with the representation of its float policy.
+fn visit_float<'de, F, V>(float: &Float<F>, visitor: V) -> Result<V::Value, Error>
+where
+    F: FloatPolicy,
+    V: de::Visitor<'de>,
+{
+    match float {
+        Float::F32(float) => <F::F32 as FloatRepr<f32>>::visit(float, visitor),
+        Float::F64(float) => <F::F64 as FloatRepr<f64>>::visit(float, visitor),
+        Float::F16(float) => <F::F32 as FloatRepr<f32>>::visit(float, visitor),
+    }
+}
+
+/// Visit a `u128`, narrowing it to a `u64` if it fits unless the visitor is
+/// deserializing a [Key].
+///
+/// serde buffers untagged enums and flattened fields in a way which doesn't
+/// support 128-bit integers. Values which don't fit are visited as-is.
+fn visit_u128<'de, V>(v: u128, visitor: V) -> Result<V::Value, Error>
+where
+    V: de::Visitor<'de>,
+{
+    match u64::try_from(v) {
+        Ok(v) if !is_key_visitor(&visitor) => visitor.visit_u64(v),
+        _ => visitor.visit_u128(v),
+    }
+}
+
+/// Visit an `i128`, narrowing it to an `i64` if it fits unless the visitor is
+/// deserializing a [Key].
+///
+/// Visitors for narrower signed integers don't accept 128-bit integers, and
+/// canonical keys store every negative integer as an `i128`. Values which
+/// don't fit are visited as-is.
+fn visit_i128<'de, V>(v: i128, visitor: V) -> Result<V::Value, Error>
+where
+    V: de::Visitor<'de>,
+{
+    match i64::try_from(v) {
+        Ok(v) if !is_key_visitor(&visitor) => visitor.visit_i64(v),
+        _ => visitor.visit_i128(v),
     }
 }
@@ -0,0 +1,722 @@
+//! Comparison of serializable values against keys, without building a key
+//! out of the value.
+
+use std::mem;
+
+use serde::ser;
+
+use crate::error::Error;
+use crate::float::{self, FloatPolicy, FloatRepr};
+use crate::key::{Float, FloatSeq, IntegerSeq, Key};
+use crate::option;
+use crate::path::PathSegment;
+use crate::ser::KeySerializer;
+
+/// Test if the given value serializes into the given [Key] using the float
+/// policy of the key, without building a key out of the value.
+///
+/// This is equivalent to `to_key_with::<F, _>(value)? == *key`, so the entries
+/// of maps are compared in order. But the value is compared against the key
+/// while it's being serialized, and as soon as a difference is found the
+/// remaining parts of the value are skipped. This makes it cheap to probe a
+/// collection of candidate keys with a single value.
+///
+/// # Errors
+///
+/// This fails in the same way as [to_key_with] does, like if a float is
+/// rejected by the policy. Since parts of the value which follow a difference
+/// are not serialized, errors in them are not reported and `false` is returned
+/// instead.
+///
+/// [to_key_with]: crate::to_key_with
+///
+/// # Examples
+///
+/// ```
+/// use serde_derive::Serialize;
+/// use serde_hashkey::{to_key, value_eq_key};
+///
+/// #[derive(Serialize)]
+/// struct Author {
+///     name: &'static str,
+///     books: Vec<u32>,
+/// }
+///
+/// let key = to_key(&Author { name: "Noah", books: vec![1, 2, 3] })?;
+///
+/// assert!(value_eq_key(&Author { name: "Noah", books: vec![1, 2, 3] }, &key)?);
+/// assert!(!value_eq_key(&Author { name: "Noah", books: vec![1, 2] }, &key)?);
+/// assert!(!value_eq_key(&Author { name: "Jane", books: vec![1, 2, 3] }, &key)?);
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+pub fn value_eq_key<T, F>(value: &T, key: &Key<F>) -> Result<bool, Error>
+where
+    T: ?Sized + ser::Serialize,
+    F: FloatPolicy,
+{
+    value.serialize(EqSerializer::new(key))
+}
+
+/// Serializer which tests if a value serializes into the key it holds.
+struct EqSerializer<'a, F>
+where
+    F: FloatPolicy,
+{
+    key: &'a Key<F>,
+}
+
+impl<'a, F> EqSerializer<'a, F>
+where
+    F: FloatPolicy,
+{
+    #[inline]
+    fn new(key: &'a Key<F>) -> Self {
+        Self { key }
+    }
+
+    #[inline]
+    fn scalar(self, key: Key<F>) -> Result<bool, Error> {
+        Ok(scalar_eq(self.key, &key))
+    }
+
+    /// Get the content of the variant with the given name, if the key is a
+    /// map with a single entry holding it.
+    fn variant(&self, variant: &str) -> Option<&'a Key<F>> {
+        match self.key {
+            Key::Map(entries) => match &entries[..] {
+                [(Key::String(name), content)] if **name == *variant => Some(content),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+impl<'a, F> ser::Serializer for EqSerializer<'a, F>
+where
+    F: FloatPolicy,
+{
+    type Ok = bool;
+    type Error = Error;
+
+    type SerializeSeq = SeqEq<'a, F>;
+    type SerializeTuple = SeqEq<'a, F>;
+    type SerializeTupleStruct = SeqEq<'a, F>;
+    type SerializeTupleVariant = TupleVariantEq<'a, F>;
+    type SerializeMap = MapEq<'a, F>;
+    type SerializeStruct = MapEq<'a, F>;
+    type SerializeStructVariant = StructVariantEq<'a, F>;
+
+    #[inline]
+    fn serialize_bool(self, value: bool) -> Result<bool, Error> {
+        self.scalar(Key::Bool(value))
+    }
+
+    #[inline]
+    fn serialize_i8(self, value: i8) -> Result<bool, Error> {
+        self.scalar(value.into())
+    }
+
+    #[inline]
+    fn serialize_i16(self, value: i16) -> Result<bool, Error> {
+        self.scalar(value.into())
+    }
+
+    #[inline]
+    fn serialize_i32(self, value: i32) -> Result<bool, Error> {
+        self.scalar(value.into())
+    }
+
+    #[inline]
+    fn serialize_i64(self, value: i64) -> Result<bool, Error> {
+        self.scalar(value.into())
+    }
+
+    #[inline]
+    fn serialize_i128(self, value: i128) -> Result<bool, Error> {
+        self.scalar(value.into())
+    }
+
+    #[inline]
+    fn serialize_u8(self, value: u8) -> Result<bool, Error> {
+        self.scalar(value.into())
+    }
+
+    #[inline]
+    fn serialize_u16(self, value: u16) -> Result<bool, Error> {
+        self.scalar(value.into())
+    }
+
+    #[inline]
+    fn serialize_u32(self, value: u32) -> Result<bool, Error> {
+        self.scalar(value.into())
+    }
+
+    #[inline]
+    fn serialize_u64(self, value: u64) -> Result<bool, Error> {
+        self.scalar(value.into())
+    }
+
+    #[inline]
+    fn serialize_u128(self, value: u128) -> Result<bool, Error> {
+        self.scalar(value.into())
+    }
+
+    #[inline]
+    fn serialize_f32(self, value: f32) -> Result<bool, Error> {
+        self.scalar(Key::Float(F::serialize_f32(value)?))
+    }
+
+    #[inline]
+    fn serialize_f64(self, value: f64) -> Result<bool, Error> {
+        let value = <F::F64 as FloatRepr<f64>>::serialize(value)?;
+        self.scalar(Key::Float(Float::F64(value)))
+    }
+
+    #[inline]
+    fn serialize_char(self, value: char) -> Result<bool, Error> {
+        self.scalar(Key::Char(value))
+    }
+
+    #[inline]
+    fn serialize_str(self, value: &str) -> Result<bool, Error> {
+        Ok(matches!(self.key, Key::String(string) if **string == *value))
+    }
+
+    #[inline]
+    fn serialize_bytes(self, value: &[u8]) -> Result<bool, Error> {
+        Ok(matches!(self.key, Key::Bytes(bytes) if **bytes == *value))
+    }
+
+    #[inline]
+    fn serialize_unit(self) -> Result<bool, Error> {
+        self.scalar(Key::Unit)
+    }
+
+    #[inline]
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<bool, Error> {
+        self.serialize_unit()
+    }
+
+    #[inline]
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<bool, Error> {
+        self.serialize_str(variant)
+    }
+
+    #[inline]
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<bool, Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        // NB: `half::f16` is serialized as a newtype struct, but is stored as
+        // a float.
+        if float::is_f16(name) {
+            let key = value.serialize(KeySerializer::<F>::new())?;
+            return self.scalar(float::from_newtype_struct(name, key)?);
+        }
+
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<bool, Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        let content = match self.variant(variant) {
+            Some(content) => content,
+            None => return Ok(false),
+        };
+
+        value
+            .serialize(EqSerializer::new(content))
+            .map_err(|error| error.at(PathSegment::Field(variant.into())))
+    }
+
+    #[inline]
+    fn serialize_none(self) -> Result<bool, Error> {
+        self.scalar(Key::Null)
+    }
+
+    #[inline]
+    fn serialize_some<T>(self, value: &T) -> Result<bool, Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        // NB: A nested `None` is wrapped in a single-element sequence.
+        if option::is_nested_none(value) {
+            return match self.key {
+                Key::Seq(seq) if seq.len() == 1 => value.serialize(EqSerializer::new(&seq[0])),
+                _ => Ok(false),
+            };
+        }
+
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        Ok(SeqEq::new(self.key))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        let seq = match self.variant(variant) {
+            Some(content) => SeqEq::new(content),
+            None => SeqEq::different(),
+        };
+
+        Ok(TupleVariantEq { variant, seq })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Ok(MapEq::new(self.key))
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        let map = match self.variant(variant) {
+            Some(content) => MapEq::new(content),
+            None => MapEq::different(),
+        };
+
+        Ok(StructVariantEq { variant, map })
+    }
+
+    #[inline]
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+/// Compare two keys which are not collections.
+fn scalar_eq<F>(a: &Key<F>, b: &Key<F>) -> bool
+where
+    F: FloatPolicy,
+{
+    match (a, b) {
+        (Key::Null, Key::Null) => true,
+        (Key::Unit, Key::Unit) => true,
+        (Key::Bool(a), Key::Bool(b)) => a == b,
+        (Key::Integer(a), Key::Integer(b)) => a == b,
+        (Key::Float(Float::F32(a)), Key::Float(Float::F32(b))) => a == b,
+        (Key::Float(Float::F64(a)), Key::Float(Float::F64(b))) => a == b,
+        (Key::Float(Float::F16(a)), Key::Float(Float::F16(b))) => a == b,
+        (Key::Char(a), Key::Char(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// Test if the given keys are stored as a compact sequence when serialized,
+/// see [Key::seq].
+fn is_compact<F>(keys: &[Key<F>]) -> bool
+where
+    F: FloatPolicy,
+{
+    let (first, rest) = match keys.split_first() {
+        Some(split) => split,
+        None => return false,
+    };
+
+    match first {
+        Key::Integer(a) => rest.iter().all(
+            |key| matches!(key, Key::Integer(b) if mem::discriminant(a) == mem::discriminant(b)),
+        ),
+        Key::Float(Float::F32(..)) => rest
+            .iter()
+            .all(|key| matches!(key, Key::Float(Float::F32(..)))),
+        Key::Float(Float::F64(..)) => rest
+            .iter()
+            .all(|key| matches!(key, Key::Float(Float::F64(..)))),
+        _ => false,
+    }
+}
+
+/// The elements of a sequence key being compared against.
+enum Elements<'a, F>
+where
+    F: FloatPolicy,
+{
+    Seq(&'a [Key<F>]),
+    Integers(&'a IntegerSeq),
+    Floats(&'a FloatSeq<F>),
+}
+
+/// Compares the elements of a sequence against a key.
+///
+/// Once an element differs, `elements` is cleared and the remaining elements
+/// are skipped.
+struct SeqEq<'a, F>
+where
+    F: FloatPolicy,
+{
+    elements: Option<Elements<'a, F>>,
+    index: usize,
+}
+
+impl<'a, F> SeqEq<'a, F>
+where
+    F: FloatPolicy,
+{
+    fn new(key: &'a Key<F>) -> Self {
+        let elements = match key {
+            Key::Seq(seq) => Some(Elements::Seq(seq)),
+            Key::IntegerSeq(seq) => Some(Elements::Integers(seq)),
+            Key::FloatSeq(seq) => Some(Elements::Floats(seq)),
+            _ => None,
+        };
+
+        Self { elements, index: 0 }
+    }
+
+    fn different() -> Self {
+        Self {
+            elements: None,
+            index: 0,
+        }
+    }
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        let elements = match &self.elements {
+            Some(elements) => elements,
+            None => return Ok(()),
+        };
+
+        let index = self.index;
+        self.index += 1;
+
+        let result = match elements {
+            Elements::Seq(seq) => match seq.get(index) {
+                Some(key) => value.serialize(EqSerializer::new(key)),
+                None => Ok(false),
+            },
+            Elements::Integers(seq) => match seq.get(index) {
+                Some(integer) => value.serialize(EqSerializer::new(&Key::<F>::Integer(integer))),
+                None => Ok(false),
+            },
+            Elements::Floats(seq) => match seq.get(index) {
+                Some(float) => value.serialize(EqSerializer::new(&Key::Float(float))),
+                None => Ok(false),
+            },
+        };
+
+        if !result.map_err(|error| error.at(PathSegment::Index(index)))? {
+            self.elements = None;
+        }
+
+        Ok(())
+    }
+
+    fn end(self) -> Result<bool, Error> {
+        Ok(match self.elements {
+            // NB: A sequence which would be stored compactly never equals a
+            // sequence key which isn't.
+            Some(Elements::Seq(seq)) => self.index == seq.len() && !is_compact(seq),
+            Some(Elements::Integers(seq)) => self.index == seq.len(),
+            Some(Elements::Floats(seq)) => self.index == seq.len(),
+            None => false,
+        })
+    }
+}
+
+impl<F> ser::SerializeSeq for SeqEq<'_, F>
+where
+    F: FloatPolicy,
+{
+    type Ok = bool;
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        SeqEq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<bool, Error> {
+        SeqEq::end(self)
+    }
+}
+
+impl<F> ser::SerializeTuple for SeqEq<'_, F>
+where
+    F: FloatPolicy,
+{
+    type Ok = bool;
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        SeqEq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<bool, Error> {
+        SeqEq::end(self)
+    }
+}
+
+impl<F> ser::SerializeTupleStruct for SeqEq<'_, F>
+where
+    F: FloatPolicy,
+{
+    type Ok = bool;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        SeqEq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<bool, Error> {
+        SeqEq::end(self)
+    }
+}
+
+/// Compares the fields of a tuple variant against the content of a variant
+/// key.
+struct TupleVariantEq<'a, F>
+where
+    F: FloatPolicy,
+{
+    variant: &'static str,
+    seq: SeqEq<'a, F>,
+}
+
+impl<F> ser::SerializeTupleVariant for TupleVariantEq<'_, F>
+where
+    F: FloatPolicy,
+{
+    type Ok = bool;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        let variant = self.variant;
+
+        self.seq
+            .serialize_element(value)
+            .map_err(|error| error.at(PathSegment::Field(variant.into())))
+    }
+
+    fn end(self) -> Result<bool, Error> {
+        self.seq.end()
+    }
+}
+
+/// Compares the entries of a map or the fields of a struct against a key.
+///
+/// Once an entry differs, `entries` is cleared and the remaining entries are
+/// skipped.
+struct MapEq<'a, F>
+where
+    F: FloatPolicy,
+{
+    entries: Option<&'a [(Key<F>, Key<F>)]>,
+    index: usize,
+    has_key: bool,
+}
+
+impl<'a, F> MapEq<'a, F>
+where
+    F: FloatPolicy,
+{
+    fn new(key: &'a Key<F>) -> Self {
+        let entries = match key {
+            Key::Map(entries) => Some(&entries[..]),
+            _ => None,
+        };
+
+        Self {
+            entries,
+            index: 0,
+            has_key: false,
+        }
+    }
+
+    fn different() -> Self {
+        Self {
+            entries: None,
+            index: 0,
+            has_key: false,
+        }
+    }
+}
+
+impl<F> ser::SerializeMap for MapEq<'_, F>
+where
+    F: FloatPolicy,
+{
+    type Ok = bool;
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        self.has_key = true;
+
+        let entries = match self.entries {
+            Some(entries) => entries,
+            None => return Ok(()),
+        };
+
+        let index = self.index;
+
+        let equal = match entries.get(index) {
+            Some((expected, _)) => key
+                .serialize(EqSerializer::new(expected))
+                .map_err(|error| error.at(PathSegment::Entry(index)))?,
+            None => false,
+        };
+
+        if !equal {
+            self.entries = None;
+        }
+
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        if !mem::take(&mut self.has_key) {
+            return Err(Error::MissingValue);
+        }
+
+        let entries = match self.entries {
+            Some(entries) => entries,
+            None => return Ok(()),
+        };
+
+        let index = self.index;
+        self.index += 1;
+
+        // NB: The key of the entry has already been found to be equal.
+        let (key, expected) = &entries[index];
+
+        let equal = value
+            .serialize(EqSerializer::new(expected))
+            .map_err(|error| {
+                let segment = match key {
+                    Key::String(field) => PathSegment::Field((**field).into()),
+                    _ => PathSegment::Entry(index),
+                };
+
+                error.at(segment)
+            })?;
+
+        if !equal {
+            self.entries = None;
+        }
+
+        Ok(())
+    }
+
+    fn end(self) -> Result<bool, Error> {
+        Ok(match self.entries {
+            Some(entries) => self.index == entries.len(),
+            None => false,
+        })
+    }
+}
+
+impl<F> ser::SerializeStruct for MapEq<'_, F>
+where
+    F: FloatPolicy,
+{
+    type Ok = bool;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        ser::SerializeMap::serialize_key(self, key)?;
+        ser::SerializeMap::serialize_value(self, value)
+    }
+
+    fn end(self) -> Result<bool, Error> {
+        ser::SerializeMap::end(self)
+    }
+}
+
+/// Compares the fields of a struct variant against the content of a variant
+/// key.
+struct StructVariantEq<'a, F>
+where
+    F: FloatPolicy,
+{
+    variant: &'static str,
+    map: MapEq<'a, F>,
+}
+
+impl<F> ser::SerializeStructVariant for StructVariantEq<'_, F>
+where
+    F: FloatPolicy,
+{
+    type Ok = bool;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        let variant = self.variant;
+
+        ser::SerializeStruct::serialize_field(&mut self.map, key, value)
+            .map_err(|error| error.at(PathSegment::Field(variant.into())))
+    }
+
+    fn end(self) -> Result<bool, Error> {
+        ser::SerializeMap::end(self.map)
+    }
+}
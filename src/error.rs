@@ -1,4 +1,5 @@
 //! Errors raised during serialization/deserialization.
+use crate::path::{KeyPath, PathSegment};
 use serde::{de, ser};
 use std::{error, fmt, result};
 
@@ -10,6 +11,59 @@ pub enum Error {
     Unexpected(&'static str),
     /// Type is not supported for serialization.
     UnsupportedType(&'static str),
+    /// A floating point value was rejected by the float policy in use.
+    UnsupportedFloat {
+        /// The width of the rejected float.
+        width: FloatWidth,
+        /// The rejected value, widened to `f64`.
+        value: f64,
+    },
+    /// A floating point value was rejected because it's NaN or infinite, by a
+    /// float policy which otherwise accepts floats.
+    NonFiniteFloat {
+        /// The width of the rejected float.
+        width: FloatWidth,
+        /// The rejected value, widened to `f64`.
+        value: f64,
+    },
+    /// A floating point value nested inside of the value being serialized was
+    /// rejected by the float policy in use.
+    ///
+    /// Floats which are rejected at the root of the value are reported as
+    /// [Error::UnsupportedFloat] or [Error::NonFiniteFloat] directly.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_derive::Serialize;
+    /// use serde_hashkey::{to_key, Error, FloatWidth};
+    ///
+    /// #[derive(Serialize)]
+    /// struct Author {
+    ///     scores: Vec<f32>,
+    /// }
+    ///
+    /// #[derive(Serialize)]
+    /// struct Book {
+    ///     author: Author,
+    /// }
+    ///
+    /// let book = Book { author: Author { scores: vec![1.5] } };
+    ///
+    /// match to_key(&book) {
+    ///     Err(Error::FloatRejected { path, error }) => {
+    ///         assert_eq!(path.to_string(), "author.scores[0]");
+    ///         assert_eq!(*error, Error::UnsupportedFloat { width: FloatWidth::F32, value: 1.5 });
+    ///     }
+    ///     _ => panic!("expected a rejected float"),
+    /// }
+    /// ```
+    FloatRejected {
+        /// The path to the rejected float, see [KeyPath].
+        path: KeyPath,
+        /// The error the float was rejected with.
+        error: Box<Error>,
+    },
     /// Unsupported deserialization variant.
     UnexpectedVariant(&'static str),
     /// A custom error.
@@ -18,6 +72,56 @@ pub enum Error {
     MissingValue,
     /// Array has invalid length.
     InvalidLength,
+    /// An empty separator was used to flatten or unflatten a key.
+    EmptySeparator,
+    /// A map key contains the separator used to flatten a key.
+    SeparatorInKey(String),
+    /// Two entries in a flattened key conflict with each other when
+    /// unflattened.
+    ConflictingPath(String),
+    /// A path which was expected to exist in a key is missing.
+    MissingPath(String),
+    /// A query string could not be decoded.
+    InvalidQuery(String),
+    /// Bytes could not be decoded as a key.
+    InvalidBytes(&'static str),
+    /// An integer is out of range for the type it's being converted or
+    /// deserialized into, which holds the name of the type like `u8`.
+    IntegerOverflow(&'static str),
+    /// A string could not be parsed as an integer.
+    InvalidInteger(String),
+    /// An enum with the given name was deserialized from a key built with
+    /// [transparent newtype variants], which can't identify the variant.
+    ///
+    /// [transparent newtype variants]: crate::KeyConfig::transparent_newtype_variants
+    TransparentEnum(&'static str),
+    /// A map serialized the same key more than once, which is rejected when
+    /// [duplicate keys are rejected]. This holds a rendering of the key.
+    ///
+    /// [duplicate keys are rejected]: crate::KeyConfig::reject_duplicate_keys
+    DuplicateKey(String),
+}
+
+/// The width of a floating point value, as reported by
+/// [Error::UnsupportedFloat].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FloatWidth {
+    /// A 32-bit float.
+    F32,
+    /// A 64-bit float.
+    F64,
+    /// A 16-bit float.
+    F16,
+}
+
+impl fmt::Display for FloatWidth {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FloatWidth::F32 => fmt.write_str("f32"),
+            FloatWidth::F64 => fmt.write_str("f64"),
+            FloatWidth::F16 => fmt.write_str("f16"),
+        }
+    }
 }
 
 /// Helper alias for a Result which already represents our local [Error] type.
@@ -30,15 +134,79 @@ impl fmt::Display for Error {
         match self {
             Unexpected(expected) => write!(fmt, "unexpected type, expected: {}", expected),
             UnsupportedType(ty) => write!(fmt, "unsupported type: {}", ty),
+            UnsupportedFloat {
+                width: FloatWidth::F32,
+                value,
+            } => write!(fmt, "unsupported float f32 = {}", *value as f32),
+            UnsupportedFloat {
+                width: FloatWidth::F64,
+                value,
+            } => write!(fmt, "unsupported float f64 = {}", value),
+            UnsupportedFloat {
+                width: FloatWidth::F16,
+                value,
+            } => write!(fmt, "unsupported float f16 = {}", *value as f32),
+            NonFiniteFloat {
+                width: FloatWidth::F32,
+                value,
+            } => write!(fmt, "non-finite float f32 = {}", *value as f32),
+            NonFiniteFloat {
+                width: FloatWidth::F64,
+                value,
+            } => write!(fmt, "non-finite float f64 = {}", value),
+            NonFiniteFloat {
+                width: FloatWidth::F16,
+                value,
+            } => write!(fmt, "non-finite float f16 = {}", *value as f32),
+            FloatRejected { path, error } => write!(fmt, "{} at {}", error, path),
             UnexpectedVariant(variant) => write!(fmt, "unexpectec variant: {}", variant),
             Custom(e) => write!(fmt, "{}", e),
             MissingValue => write!(fmt, "missing value duration deserialization"),
             InvalidLength => write!(fmt, "array with invalid length"),
+            EmptySeparator => write!(fmt, "separator must not be empty"),
+            SeparatorInKey(key) => write!(fmt, "map key `{}` contains the separator", key),
+            ConflictingPath(path) => write!(fmt, "conflicting entries for path `{}`", path),
+            MissingPath(path) => write!(fmt, "missing path `{}`", path),
+            InvalidQuery(query) => write!(fmt, "invalid query string `{}`", query),
+            InvalidBytes(reason) => write!(fmt, "invalid binary encoding: {}", reason),
+            IntegerOverflow(ty) => write!(fmt, "integer out of range for {}", ty),
+            InvalidInteger(string) => write!(fmt, "invalid integer `{}`", string),
+            TransparentEnum(name) => write!(
+                fmt,
+                "enum `{}` can't be deserialized from a key with transparent newtype variants",
+                name
+            ),
+            DuplicateKey(key) => write!(fmt, "duplicate map key `{}`", key),
         }
     }
 }
 
-impl error::Error for Error {}
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::FloatRejected { error, .. } => Some(&**error),
+            _ => None,
+        }
+    }
+}
+
+impl Error {
+    /// Attach a segment to the front of the path of a rejected float, which
+    /// is used as errors propagate out of the value they were raised in.
+    pub(crate) fn at(self, segment: PathSegment) -> Self {
+        match self {
+            Error::UnsupportedFloat { .. } | Error::NonFiniteFloat { .. } => Error::FloatRejected {
+                path: KeyPath::from(vec![segment]),
+                error: Box::new(self),
+            },
+            Error::FloatRejected { mut path, error } => {
+                path.push_front(segment);
+                Error::FloatRejected { path, error }
+            }
+            error => error,
+        }
+    }
+}
 
 impl ser::Error for Error {
     fn custom<T>(msg: T) -> Self
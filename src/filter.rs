@@ -0,0 +1,338 @@
+//! Exclusion of fields from keys, either while serializing or afterwards.
+
+use std::mem;
+
+use serde::ser;
+
+use crate::config::KeyConfig;
+use crate::error::Error;
+use crate::float::FloatPolicy;
+use crate::hooks::{Hooks, Segment};
+use crate::key::Key;
+use crate::ser::KeySerializer;
+
+/// A set of patterns matching fields to exclude from a key, used by
+/// [to_key_filtered] and [Key::strip].
+///
+/// Fields are the fields of structs and struct variants, the entries of maps
+/// whose keys are strings, and the names of newtype, tuple and struct
+/// variants, which are all represented as string map keys in a [Key].
+///
+/// A pattern without a `.` matches fields with that name at any depth. A
+/// pattern which is a `.` separated list of fields, like `author.name`, only
+/// matches the field at that path from the root. Sequences and maps without
+/// string keys are transparent to paths, so `books.title` matches the `title`
+/// field of every element in `books`.
+///
+/// Each part of a pattern supports globs, where `*` matches any number of
+/// characters and `?` matches a single character. A part which is exactly
+/// `**` matches any number of fields, so `**.id` is the same as `id`.
+///
+/// # Examples
+///
+/// ```
+/// use serde_hashkey::FieldFilter;
+///
+/// let filter = FieldFilter::new()
+///     .exclude("trace_id")
+///     .exclude("request.*_at");
+///
+/// assert!(filter.is_excluded(&["trace_id"]));
+/// assert!(filter.is_excluded(&["request", "headers", "trace_id"]));
+/// assert!(filter.is_excluded(&["request", "created_at"]));
+/// assert!(!filter.is_excluded(&["response", "created_at"]));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FieldFilter {
+    patterns: Vec<Vec<Part>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    /// Matches any number of fields.
+    Any,
+    /// Matches a single field through a glob.
+    Glob(Box<str>),
+}
+
+impl FieldFilter {
+    /// Construct a new filter which doesn't exclude any fields.
+    pub const fn new() -> Self {
+        Self {
+            patterns: Vec::new(),
+        }
+    }
+
+    /// Exclude fields matching the given pattern.
+    ///
+    /// See [FieldFilter] for the syntax of patterns. An empty pattern matches
+    /// nothing.
+    pub fn exclude(mut self, pattern: &str) -> Self {
+        if pattern.is_empty() {
+            return self;
+        }
+
+        let mut parts = Vec::new();
+
+        if !pattern.contains('.') {
+            parts.push(Part::Any);
+        }
+
+        for part in pattern.split('.') {
+            parts.push(match part {
+                "**" => Part::Any,
+                glob => Part::Glob(glob.into()),
+            });
+        }
+
+        self.patterns.push(parts);
+        self
+    }
+
+    /// Test if the field at the given path of field names is excluded.
+    pub fn is_excluded<S>(&self, path: &[S]) -> bool
+    where
+        S: AsRef<str>,
+    {
+        if path.is_empty() {
+            return false;
+        }
+
+        self.patterns.iter().any(|parts| matches_path(parts, path))
+    }
+}
+
+/// Test if the given pattern matches the whole path.
+fn matches_path<S>(parts: &[Part], path: &[S]) -> bool
+where
+    S: AsRef<str>,
+{
+    match parts.split_first() {
+        None => path.is_empty(),
+        Some((Part::Any, rest)) => (0..=path.len()).any(|n| matches_path(rest, &path[n..])),
+        Some((Part::Glob(glob), rest)) => match path.split_first() {
+            Some((field, path)) => {
+                matches_glob(glob.as_bytes(), field.as_ref().as_bytes()) && matches_path(rest, path)
+            }
+            None => false,
+        },
+    }
+}
+
+/// Test if the given glob matches the whole text.
+fn matches_glob(glob: &[u8], text: &[u8]) -> bool {
+    let (mut g, mut t) = (0, 0);
+    // The position of the last `*` in the glob and the position in the text
+    // it was tried at, to backtrack to on a mismatch.
+    let mut backtrack = None;
+
+    while t < text.len() {
+        match glob.get(g) {
+            Some(b'*') => {
+                backtrack = Some((g, t));
+                g += 1;
+                continue;
+            }
+            Some(b'?') => {
+                g += 1;
+                t += 1;
+                continue;
+            }
+            Some(c) if *c == text[t] => {
+                g += 1;
+                t += 1;
+                continue;
+            }
+            _ => {}
+        }
+
+        match backtrack {
+            Some((star, at)) => {
+                g = star + 1;
+                t = at + 1;
+                backtrack = Some((star, at + 1));
+            }
+            None => return false,
+        }
+    }
+
+    glob[g..].iter().all(|c| *c == b'*')
+}
+
+/// Serialize the given value into a [Key], leaving out any fields excluded by
+/// `exclude`.
+///
+/// Excluded values are never serialized, so they don't have to be
+/// representable as keys. Apart from this, the result is the same as
+/// serializing the value with [to_key] and then calling [Key::strip] with the
+/// same filter.
+///
+/// [to_key]: crate::to_key
+///
+/// # Examples
+///
+/// ```
+/// use serde_derive::Serialize;
+/// use serde_hashkey::{to_key_filtered, FieldFilter, Key};
+///
+/// #[derive(Serialize)]
+/// struct Request {
+///     path: &'static str,
+///     trace_id: &'static str,
+///     timestamp: f64,
+/// }
+///
+/// let filter = FieldFilter::new().exclude("trace_id").exclude("timestamp");
+///
+/// let a: Key = to_key_filtered(&Request { path: "/", trace_id: "a", timestamp: 1.0 }, &filter)?;
+/// let b: Key = to_key_filtered(&Request { path: "/", trace_id: "b", timestamp: 2.0 }, &filter)?;
+/// assert_eq!(a, b);
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+pub fn to_key_filtered<T, F>(value: &T, exclude: &FieldFilter) -> Result<Key<F>, Error>
+where
+    T: ?Sized + ser::Serialize,
+    F: FloatPolicy,
+{
+    let mut state = State {
+        filter: exclude,
+        path: Vec::new(),
+        keys: 0,
+    };
+
+    value.serialize(KeySerializer::with_hooks(KeyConfig::new(), &mut state))
+}
+
+impl<F> Key<F>
+where
+    F: FloatPolicy,
+{
+    /// Remove every field excluded by the given filter from this key.
+    ///
+    /// See [FieldFilter] for which fields can be matched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_derive::Serialize;
+    /// use serde_hashkey::{to_key, FieldFilter};
+    ///
+    /// #[derive(Serialize)]
+    /// struct Author {
+    ///     name: &'static str,
+    ///     updated_at: u64,
+    /// }
+    ///
+    /// let filter = FieldFilter::new().exclude("*_at");
+    ///
+    /// let mut a = to_key(&Author { name: "Noah", updated_at: 1 })?;
+    /// let mut b = to_key(&Author { name: "Noah", updated_at: 2 })?;
+    /// assert_ne!(a, b);
+    ///
+    /// a.strip(&filter);
+    /// b.strip(&filter);
+    /// assert_eq!(a, b);
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn strip(&mut self, filter: &FieldFilter) {
+        strip(self, filter, &mut Vec::new());
+    }
+}
+
+fn strip<F>(key: &mut Key<F>, filter: &FieldFilter, path: &mut Vec<Box<str>>)
+where
+    F: FloatPolicy,
+{
+    match key {
+        Key::Seq(seq) => {
+            for value in seq.iter_mut() {
+                strip(value, filter, path);
+            }
+        }
+        Key::Map(map) => {
+            let entries = mem::take(map)
+                .into_vec()
+                .into_iter()
+                .filter_map(|(key, mut value)| {
+                    if let Key::String(name) = &key {
+                        path.push((**name).into());
+                        let excluded = filter.is_excluded(path);
+
+                        if !excluded {
+                            strip(&mut value, filter, path);
+                        }
+
+                        path.pop();
+
+                        if excluded {
+                            return None;
+                        }
+                    } else {
+                        strip(&mut value, filter, path);
+                    }
+
+                    Some((key, value))
+                })
+                .collect::<Vec<_>>();
+
+            *map = entries.into();
+        }
+        _ => {}
+    }
+}
+
+/// Hooks which leave out the fields excluded by a filter.
+struct State<'f> {
+    filter: &'f FieldFilter,
+    /// The field names leading to the value being serialized.
+    path: Vec<Box<str>>,
+    /// The number of map keys being serialized, inside of which nothing is
+    /// excluded.
+    keys: usize,
+}
+
+impl<'f, F> Hooks<F> for &mut State<'f>
+where
+    F: FloatPolicy,
+{
+    type Reborrow<'b>
+        = &'b mut State<'f>
+    where
+        Self: 'b;
+
+    #[inline]
+    fn reborrow(&mut self) -> Self::Reborrow<'_> {
+        self
+    }
+
+    fn enter(&mut self, segment: Segment<'_>) -> bool {
+        match segment {
+            Segment::Field(name) => {
+                self.path.push(name.into());
+
+                if self.keys == 0 && self.filter.is_excluded(&self.path) {
+                    self.path.pop();
+                    return false;
+                }
+            }
+            Segment::Key(..) => {
+                self.keys += 1;
+            }
+            Segment::Index(..) | Segment::Entry(..) => {}
+        }
+
+        true
+    }
+
+    fn leave(&mut self, segment: Segment<'_>) {
+        match segment {
+            Segment::Field(..) => {
+                self.path.pop();
+            }
+            Segment::Key(..) => {
+                self.keys -= 1;
+            }
+            Segment::Index(..) | Segment::Entry(..) => {}
+        }
+    }
+}
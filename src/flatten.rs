@@ -0,0 +1,219 @@
+//! Flattening of nested maps into single-level maps with joined paths.
+
+use crate::error::Error;
+use crate::float::FloatPolicy;
+use crate::key::Key;
+
+impl<F> Key<F>
+where
+    F: FloatPolicy,
+{
+    /// Flatten nested string-keyed maps into a single-level map, where each key
+    /// is the path to the value joined by `separator`.
+    ///
+    /// Only maps are flattened. Sequences, scalars and empty maps are kept as
+    /// values in the resulting map. Keys which are not maps are returned as
+    /// they are.
+    ///
+    /// # Errors
+    ///
+    /// Errors if `separator` is empty, if any map along the way has a key which
+    /// is not a string, or if any key contains the separator since that would
+    /// make it impossible to [unflatten] the result.
+    ///
+    /// [unflatten]: Key::unflatten
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_derive::Serialize;
+    /// use serde_hashkey::{to_key, Key};
+    ///
+    /// #[derive(Serialize)]
+    /// struct Author {
+    ///     name: &'static str,
+    ///     age: u32,
+    /// }
+    ///
+    /// #[derive(Serialize)]
+    /// struct Book {
+    ///     title: &'static str,
+    ///     author: Author,
+    /// }
+    ///
+    /// let key = to_key(&Book { title: "Birds of a feather", author: Author { name: "Noah", age: 42 } })?;
+    /// let flat = key.clone().flatten(".")?;
+    ///
+    /// let expected: Key = Key::Map(vec![
+    ///     (Key::from("title".to_owned()), Key::from("Birds of a feather".to_owned())),
+    ///     (Key::from("author.name".to_owned()), Key::from("Noah".to_owned())),
+    ///     (Key::from("author.age".to_owned()), Key::from(42u32)),
+    /// ].into());
+    ///
+    /// assert_eq!(flat, expected);
+    /// assert_eq!(flat.unflatten(".")?, key);
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn flatten(self, separator: &str) -> Result<Key<F>, Error> {
+        if separator.is_empty() {
+            return Err(Error::EmptySeparator);
+        }
+
+        let map = match self {
+            Key::Map(map) => map,
+            other => return Ok(other),
+        };
+
+        let mut out = Vec::new();
+        flatten_into(&mut String::new(), true, map, separator, &mut out)?;
+        Ok(Key::Map(out.into()))
+    }
+
+    /// Reverse the effect of [flatten], splitting every key in a single-level
+    /// map by `separator` and turning it into nested maps.
+    ///
+    /// Keys which are not maps are returned as they are.
+    ///
+    /// # Errors
+    ///
+    /// Errors if `separator` is empty, if any key in the map is not a string, or
+    /// if two entries conflict, like `a` and `a.b` where `a` would have to be
+    /// both a value and a map.
+    ///
+    /// [flatten]: Key::flatten
+    pub fn unflatten(self, separator: &str) -> Result<Key<F>, Error> {
+        if separator.is_empty() {
+            return Err(Error::EmptySeparator);
+        }
+
+        let map = match self {
+            Key::Map(map) => map,
+            other => return Ok(other),
+        };
+
+        let mut root = Vec::new();
+
+        for (key, value) in Vec::from(map) {
+            let key = match key {
+                Key::String(key) => key,
+                _ => return Err(Error::Unexpected("string map key")),
+            };
+
+            insert(&mut root, &key, separator, value)?;
+        }
+
+        Ok(into_key(root))
+    }
+}
+
+fn flatten_into<F>(
+    prefix: &mut String,
+    root: bool,
+    map: Box<[(Key<F>, Key<F>)]>,
+    separator: &str,
+    out: &mut Vec<(Key<F>, Key<F>)>,
+) -> Result<(), Error>
+where
+    F: FloatPolicy,
+{
+    for (key, value) in Vec::from(map) {
+        let key = match key {
+            Key::String(key) => key,
+            _ => return Err(Error::Unexpected("string map key")),
+        };
+
+        if key.contains(separator) {
+            return Err(Error::SeparatorInKey(String::from(&*key)));
+        }
+
+        let len = prefix.len();
+
+        if !root {
+            prefix.push_str(separator);
+        }
+
+        prefix.push_str(&key);
+
+        match value {
+            Key::Map(map) if !map.is_empty() => {
+                flatten_into(prefix, false, map, separator, out)?;
+            }
+            value => {
+                out.push((Key::String(prefix.as_str().into()), value));
+            }
+        }
+
+        prefix.truncate(len);
+    }
+
+    Ok(())
+}
+
+/// Intermediate tree used while unflattening.
+enum Node<F>
+where
+    F: FloatPolicy,
+{
+    Leaf(Key<F>),
+    Branch(Vec<(Box<str>, Node<F>)>),
+}
+
+fn insert<F>(
+    mut branch: &mut Vec<(Box<str>, Node<F>)>,
+    path: &str,
+    separator: &str,
+    value: Key<F>,
+) -> Result<(), Error>
+where
+    F: FloatPolicy,
+{
+    let mut segments = path.split(separator).peekable();
+
+    while let Some(segment) = segments.next() {
+        let index = match branch.iter().position(|(name, _)| &**name == segment) {
+            Some(index) => index,
+            None => {
+                branch.push((segment.into(), Node::Branch(Vec::new())));
+                branch.len() - 1
+            }
+        };
+
+        let node = &mut branch[index].1;
+
+        if segments.peek().is_none() {
+            match node {
+                Node::Branch(children) if children.is_empty() => {
+                    *node = Node::Leaf(value);
+                    return Ok(());
+                }
+                _ => return Err(Error::ConflictingPath(path.into())),
+            }
+        }
+
+        branch = match node {
+            Node::Branch(children) => children,
+            Node::Leaf(..) => return Err(Error::ConflictingPath(path.into())),
+        };
+    }
+
+    Ok(())
+}
+
+fn into_key<F>(branch: Vec<(Box<str>, Node<F>)>) -> Key<F>
+where
+    F: FloatPolicy,
+{
+    let map = branch
+        .into_iter()
+        .map(|(name, node)| {
+            let value = match node {
+                Node::Leaf(value) => value,
+                Node::Branch(children) => into_key(children),
+            };
+
+            (Key::String(name.into()), value)
+        })
+        .collect::<Vec<_>>();
+
+    Key::Map(map.into())
+}
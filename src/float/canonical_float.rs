@@ -0,0 +1,176 @@
+use crate::error::Error;
+use crate::float::{FloatPolicy, FloatRepr};
+use crate::key::{Float, Key};
+use serde::{de, ser};
+use std::cmp;
+use std::fmt;
+use std::hash;
+
+/// An opaque floating-point representation which is canonicalized when it's
+/// constructed. This is used by [CanonicalFloatPolicy].
+///
+/// Every NaN is stored as the same quiet NaN, and `-0.0` is stored as `0.0`.
+/// Equality and hashing operate on the bits of the canonical value, and
+/// ordering follows the total order of IEEE 754, so NaN sorts after positive
+/// infinity.
+///
+/// # Examples
+///
+/// ```
+/// use serde_hashkey::CanonicalFloat;
+///
+/// assert_eq!(CanonicalFloat::new(-0.0f64), CanonicalFloat::new(0.0f64));
+/// assert_eq!(CanonicalFloat::new(f32::NAN), CanonicalFloat::new(-f32::NAN));
+/// assert!(CanonicalFloat::new(f64::INFINITY) < CanonicalFloat::new(f64::NAN));
+/// assert_eq!(CanonicalFloat::new(-0.0f64).get().to_bits(), 0);
+/// ```
+#[derive(Clone, Copy)]
+pub struct CanonicalFloat<T>(T);
+
+impl<T> CanonicalFloat<T>
+where
+    T: Copy,
+{
+    /// Construct a canonical float, folding every NaN into a single quiet NaN
+    /// and `-0.0` into `0.0`.
+    pub fn new(value: T) -> Self
+    where
+        Self: FloatRepr<T>,
+    {
+        match <Self as FloatRepr<T>>::serialize(value) {
+            Ok(float) => float,
+            Err(..) => unreachable!("canonical floats accept every value"),
+        }
+    }
+
+    /// Get the canonical value.
+    pub fn get(self) -> T {
+        self.0
+    }
+}
+
+impl<T> fmt::Debug for CanonicalFloat<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, fmt)
+    }
+}
+
+macro_rules! impl_canonical_float {
+    ($ty:ty, $nan:expr, $visit:ident, $serialize:ident) => {
+        impl FloatRepr<$ty> for CanonicalFloat<$ty> {
+            fn serialize(value: $ty) -> Result<Self, Error> {
+                if value.is_nan() {
+                    Ok(CanonicalFloat(<$ty>::from_bits($nan)))
+                } else if value == 0.0 {
+                    Ok(CanonicalFloat(0.0))
+                } else {
+                    Ok(CanonicalFloat(value))
+                }
+            }
+
+            fn visit<'de, V>(&self, visitor: V) -> Result<V::Value, Error>
+            where
+                V: de::Visitor<'de>,
+            {
+                visitor.$visit(self.0)
+            }
+        }
+
+        impl ser::Serialize for CanonicalFloat<$ty> {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: ser::Serializer,
+            {
+                serializer.$serialize(self.0)
+            }
+        }
+
+        impl PartialEq for CanonicalFloat<$ty> {
+            fn eq(&self, other: &Self) -> bool {
+                self.0.to_bits() == other.0.to_bits()
+            }
+        }
+
+        impl Eq for CanonicalFloat<$ty> {}
+
+        impl PartialOrd for CanonicalFloat<$ty> {
+            fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl Ord for CanonicalFloat<$ty> {
+            fn cmp(&self, other: &Self) -> cmp::Ordering {
+                self.0.total_cmp(&other.0)
+            }
+        }
+
+        impl hash::Hash for CanonicalFloat<$ty> {
+            fn hash<H: hash::Hasher>(&self, state: &mut H) {
+                self.0.to_bits().hash(state)
+            }
+        }
+    };
+}
+
+impl_canonical_float!(f32, 0x7fc0_0000, visit_f32, serialize_f32);
+impl_canonical_float!(f64, 0x7ff8_0000_0000_0000, visit_f64, serialize_f64);
+
+/// A float serialization policy with strictly canonical semantics, which is
+/// suitable for keys which are persisted. This policy is used by the
+/// [to_key_with_canonical_float] function.
+///
+/// Floats are stored as a [CanonicalFloat], so every NaN produces the same key
+/// and `-0.0` produces the same key as `0.0`. Their [binary encoding] is
+/// therefore also canonical.
+///
+/// [binary encoding]: crate::Key::to_bytes
+#[derive(Debug, Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct CanonicalFloatPolicy(());
+
+impl FloatPolicy for CanonicalFloatPolicy {
+    type F32 = CanonicalFloat<f32>;
+    type F64 = CanonicalFloat<f64>;
+}
+
+impl From<f32> for Key<CanonicalFloatPolicy> {
+    fn from(v: f32) -> Self {
+        Key::Float(Float::F32(CanonicalFloat::new(v)))
+    }
+}
+
+impl From<f64> for Key<CanonicalFloatPolicy> {
+    fn from(v: f64) -> Self {
+        Key::Float(Float::F64(CanonicalFloat::new(v)))
+    }
+}
+
+/// Serialize the given value to a [Key] using [CanonicalFloatPolicy].
+///
+/// # Examples
+///
+/// ```
+/// use serde_hashkey::{from_key, to_key_with_canonical_float};
+///
+/// assert_eq!(
+///     to_key_with_canonical_float(&f64::NAN)?,
+///     to_key_with_canonical_float(&-f64::NAN)?
+/// );
+/// assert_eq!(
+///     to_key_with_canonical_float(&-0.0f32)?,
+///     to_key_with_canonical_float(&0.0f32)?
+/// );
+///
+/// let key = to_key_with_canonical_float(&(1.5f64, -0.0f64))?;
+/// assert_eq!(from_key::<(f64, f64), _>(&key)?, (1.5, 0.0));
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+pub fn to_key_with_canonical_float<T>(value: &T) -> Result<Key<CanonicalFloatPolicy>, Error>
+where
+    T: ?Sized + ser::Serialize,
+{
+    crate::ser::to_key_with_policy::<T, CanonicalFloatPolicy>(value)
+}
@@ -0,0 +1,171 @@
+use crate::error::{Error, FloatWidth};
+use crate::float::{FloatPolicy, FloatRepr, NeverFloat};
+use crate::key::{Float, Key};
+use rust_decimal1::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal1::Decimal;
+use serde::{de, ser};
+use std::fmt;
+
+/// A floating-point representation which stores the float as a decimal. This
+/// is used by [DecimalFloatPolicy].
+///
+/// Equality, ordering and hashing are delegated to [Decimal], so decimals
+/// which only differ in their scale like `0.1` and `0.10` are equal.
+///
+/// Deserializing and [encoding][Key::to_bytes] a decimal converts it into the
+/// nearest `f64`, so decimals constructed directly which have more
+/// significant digits than an `f64` can hold lose precision.
+///
+/// [Decimal]: rust_decimal1::Decimal
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DecimalFloat(pub Decimal);
+
+impl fmt::Debug for DecimalFloat {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, fmt)
+    }
+}
+
+impl DecimalFloat {
+    fn to_f64(self) -> f64 {
+        // NB: every decimal is within the range of an `f64`.
+        self.0.to_f64().unwrap_or_default()
+    }
+}
+
+impl FloatRepr<f64> for DecimalFloat {
+    fn serialize(value: f64) -> Result<Self, Error> {
+        if !value.is_finite() {
+            return Err(Error::NonFiniteFloat {
+                width: FloatWidth::F64,
+                value,
+            });
+        }
+
+        match Decimal::from_f64(value) {
+            Some(decimal) => Ok(DecimalFloat(decimal)),
+            None => Err(Error::UnsupportedFloat {
+                width: FloatWidth::F64,
+                value,
+            }),
+        }
+    }
+
+    fn visit<'de, V>(&self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_f64(self.to_f64())
+    }
+}
+
+impl ser::Serialize for DecimalFloat {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        serializer.serialize_f64(self.to_f64())
+    }
+}
+
+/// A float serialization policy which stores every float as a [Decimal]. This
+/// policy is used by the [to_key_with_decimal_float] function.
+///
+/// Since decimals don't have a width, floats of every width are stored as a
+/// [Float::F64], and half-precision floats are converted like an `f32`.
+/// Deserializing a decimal converts it back into the nearest float.
+///
+/// # Conversion
+///
+/// Floats are converted in the same way as [Decimal::from_f32] and
+/// [Decimal::from_f64], which produce the shortest decimal that the float is
+/// printed as. So `0.1f64` becomes the decimal `0.1` and not the binary
+/// approximation `0.1000000000000000055511151231`, and `0.1f32` becomes the
+/// same decimal. This is exact for floats with few significant digits, like
+/// monetary amounts, but note that:
+///
+/// * NaN and infinity are rejected with [Error::NonFiniteFloat].
+/// * Floats whose magnitude is larger than [Decimal::MAX] are rejected with
+///   [Error::UnsupportedFloat].
+/// * Decimals have at most 28 digits after the decimal point, so floats with
+///   a smaller magnitude than that are rounded, down to zero if they're
+///   small enough. Both `0.0` and `-0.0` produce the same key.
+///
+/// [Decimal]: rust_decimal1::Decimal
+/// [Decimal::from_f32]: rust_decimal1::prelude::FromPrimitive::from_f32
+/// [Decimal::from_f64]: rust_decimal1::prelude::FromPrimitive::from_f64
+/// [Decimal::MAX]: rust_decimal1::Decimal::MAX
+#[derive(Debug, Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct DecimalFloatPolicy(());
+
+impl FloatPolicy for DecimalFloatPolicy {
+    // NB: floats are never stored as an `F32`, see `serialize_f32`.
+    type F32 = NeverFloat;
+    type F64 = DecimalFloat;
+
+    fn serialize_f32(value: f32) -> Result<Float<Self>, Error> {
+        serialize_f32(value, FloatWidth::F32)
+    }
+
+    fn serialize_f16(value: f32) -> Result<Float<Self>, Error> {
+        serialize_f32(value, FloatWidth::F16)
+    }
+}
+
+fn serialize_f32(value: f32, width: FloatWidth) -> Result<Float<DecimalFloatPolicy>, Error> {
+    if !value.is_finite() {
+        return Err(Error::NonFiniteFloat {
+            width,
+            value: f64::from(value),
+        });
+    }
+
+    match Decimal::from_f32(value) {
+        Some(decimal) => Ok(Float::F64(DecimalFloat(decimal))),
+        None => Err(Error::UnsupportedFloat {
+            width,
+            value: f64::from(value),
+        }),
+    }
+}
+
+impl From<Decimal> for Key<DecimalFloatPolicy> {
+    fn from(v: Decimal) -> Self {
+        Key::Float(Float::F64(DecimalFloat(v)))
+    }
+}
+
+/// Serialize the given value to a [Key] using [DecimalFloatPolicy].
+///
+/// # Examples
+///
+/// ```
+/// use rust_decimal1::Decimal;
+/// use serde_derive::{Deserialize, Serialize};
+/// use serde_hashkey::{from_key, to_key_with_decimal_float, DecimalFloat, Float, Key};
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Payment {
+///     account: String,
+///     amount: f64,
+/// }
+///
+/// let payment = Payment {
+///     account: String::from("savings"),
+///     amount: 0.1,
+/// };
+///
+/// let key = to_key_with_decimal_float(&payment)?;
+/// let amount = Key::Float(Float::F64(DecimalFloat(Decimal::new(1, 1))));
+/// assert_eq!(key["amount"], amount);
+/// assert_eq!(from_key::<Payment, _>(&key)?, payment);
+///
+/// assert!(to_key_with_decimal_float(&f64::NAN).is_err());
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+pub fn to_key_with_decimal_float<T>(value: &T) -> Result<Key<DecimalFloatPolicy>, Error>
+where
+    T: ?Sized + ser::Serialize,
+{
+    crate::ser::to_key_with_policy::<T, DecimalFloatPolicy>(value)
+}
@@ -0,0 +1,161 @@
+use crate::error::{Error, FloatWidth};
+use crate::float::{FloatPolicy, FloatRepr};
+use crate::key::Key;
+use serde::{de, ser};
+use std::cmp;
+use std::fmt;
+use std::hash;
+
+/// An opaque floating-point representation which can only hold finite values.
+/// This is used by [RejectNonFinitePolicy].
+///
+/// Since NaN can't be stored, floats are totally ordered by their value. Like
+/// with [OrderedFloat], `-0.0` is equal to `0.0`, but the sign is preserved.
+///
+/// [OrderedFloat]: crate::OrderedFloat
+///
+/// # Examples
+///
+/// ```
+/// use serde_hashkey::{Error, FiniteFloat, FloatWidth};
+///
+/// assert_eq!(FiniteFloat::new(1.5f64)?.get(), 1.5);
+/// assert_eq!(FiniteFloat::new(-0.0f32)?, FiniteFloat::new(0.0f32)?);
+/// assert!(FiniteFloat::new(f64::NAN).is_err());
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+#[derive(Clone, Copy)]
+pub struct FiniteFloat<T>(T);
+
+impl<T> FiniteFloat<T>
+where
+    T: Copy,
+{
+    /// Construct a finite float, or error with [Error::NonFiniteFloat] if the
+    /// value is NaN or infinite.
+    pub fn new(value: T) -> Result<Self, Error>
+    where
+        Self: FloatRepr<T>,
+    {
+        <Self as FloatRepr<T>>::serialize(value)
+    }
+
+    /// Get the value.
+    pub fn get(self) -> T {
+        self.0
+    }
+}
+
+impl<T> fmt::Debug for FiniteFloat<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, fmt)
+    }
+}
+
+macro_rules! impl_finite_float {
+    ($ty:ty, $width:ident, $visit:ident, $serialize:ident) => {
+        impl FloatRepr<$ty> for FiniteFloat<$ty> {
+            fn serialize(value: $ty) -> Result<Self, Error> {
+                if !value.is_finite() {
+                    return Err(Error::NonFiniteFloat {
+                        width: FloatWidth::$width,
+                        value: f64::from(value),
+                    });
+                }
+
+                Ok(FiniteFloat(value))
+            }
+
+            fn visit<'de, V>(&self, visitor: V) -> Result<V::Value, Error>
+            where
+                V: de::Visitor<'de>,
+            {
+                visitor.$visit(self.0)
+            }
+        }
+
+        impl ser::Serialize for FiniteFloat<$ty> {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: ser::Serializer,
+            {
+                serializer.$serialize(self.0)
+            }
+        }
+
+        impl PartialEq for FiniteFloat<$ty> {
+            fn eq(&self, other: &Self) -> bool {
+                self.0 == other.0
+            }
+        }
+
+        impl Eq for FiniteFloat<$ty> {}
+
+        impl PartialOrd for FiniteFloat<$ty> {
+            fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl Ord for FiniteFloat<$ty> {
+            fn cmp(&self, other: &Self) -> cmp::Ordering {
+                // NB: finite floats are totally ordered.
+                self.0.partial_cmp(&other.0).unwrap_or(cmp::Ordering::Equal)
+            }
+        }
+
+        impl hash::Hash for FiniteFloat<$ty> {
+            fn hash<H: hash::Hasher>(&self, state: &mut H) {
+                // NB: adding zero folds `-0.0` into `0.0`, since they're equal.
+                (self.0 + 0.0).to_bits().hash(state)
+            }
+        }
+    };
+}
+
+impl_finite_float!(f32, F32, visit_f32, serialize_f32);
+impl_finite_float!(f64, F64, visit_f64, serialize_f64);
+
+/// A float serialization policy which accepts finite floats, but rejects NaN
+/// and infinity with [Error::NonFiniteFloat]. This policy is used by the
+/// [to_key_rejecting_non_finite] function.
+///
+/// Floats are stored as a [FiniteFloat].
+#[derive(Debug, Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct RejectNonFinitePolicy(());
+
+impl FloatPolicy for RejectNonFinitePolicy {
+    type F32 = FiniteFloat<f32>;
+    type F64 = FiniteFloat<f64>;
+}
+
+/// Serialize the given value to a [Key] using [RejectNonFinitePolicy].
+///
+/// # Errors
+///
+/// Errors with [Error::NonFiniteFloat] if the value contains a float which is
+/// NaN or infinite.
+///
+/// # Examples
+///
+/// ```
+/// use serde_hashkey::{from_key, to_key_rejecting_non_finite, Error, FloatWidth};
+///
+/// let key = to_key_rejecting_non_finite(&(1.5f64, -2.25f32))?;
+/// assert_eq!(from_key::<(f64, f32), _>(&key)?, (1.5, -2.25));
+///
+/// assert!(matches!(
+///     to_key_rejecting_non_finite(&f64::NAN),
+///     Err(Error::NonFiniteFloat { width: FloatWidth::F64, .. })
+/// ));
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+pub fn to_key_rejecting_non_finite<T>(value: &T) -> Result<Key<RejectNonFinitePolicy>, Error>
+where
+    T: ?Sized + ser::Serialize,
+{
+    crate::ser::to_key_with_policy::<T, RejectNonFinitePolicy>(value)
+}
@@ -1,41 +1,162 @@
+use crate::error::{Error, FloatWidth};
 use crate::float::FloatRepr;
+use crate::key::Float;
 
 /// A policy for handling floating point types in a [Key].
 ///
-/// Currently there are two important `FloatPolicy` types: [RejectFloatPolicy]
-/// and [OrderedFloat]. The former will emit errors instead of allowing floats
-/// to be serialized and the latter while serialize them and provide a total
-/// order which does not adhere to the IEEE standard.
+/// This crate provides the following policies:
+///
+/// * [RejectFloatPolicy], which emits errors instead of allowing floats to be
+///   serialized.
+/// * [OrderedFloatPolicy], which serializes them and provides a total order
+///   which does not adhere to the IEEE standard.
+/// * [CanonicalFloatPolicy], which additionally treats every NaN as the same
+///   value and `-0.0` as `0.0`.
+/// * [RejectNonFinitePolicy], which serializes finite floats but emits errors
+///   for NaN and infinity.
+/// * [TotalOrderFloatPolicy], which serializes them and orders them using the
+///   `totalOrder` predicate of IEEE 754.
+/// * [WidenF32Policy], which serializes every float as an `f64`.
+/// * [QuantizedFloatPolicy], which rounds every float to a fixed number of
+///   decimal places.
+/// * [StringFloatPolicy], which compares floats using the string they're
+///   formatted as.
+/// * [DecimalFloatPolicy], which serializes every float as a decimal.
+///
+/// Custom policies can be implemented by providing a [FloatRepr] for each
+/// float width, see its documentation for the invariants they must uphold.
+/// The policy itself is only used as a type parameter of [Key], but since
+/// [Key] derives its traits it should implement `Debug`, `Clone`, `Copy`,
+/// `PartialEq`, `Eq`, `PartialOrd`, `Ord` and `Hash` for keys using it to
+/// implement them too.
 ///
 /// [Key]: crate::Key
 /// [RejectFloatPolicy]: crate::RejectFloatPolicy
-/// [OrderedFloat]: crate::OrderedFloat
+/// [OrderedFloatPolicy]: crate::OrderedFloatPolicy
+/// [CanonicalFloatPolicy]: crate::CanonicalFloatPolicy
+/// [RejectNonFinitePolicy]: crate::RejectNonFinitePolicy
+/// [TotalOrderFloatPolicy]: crate::TotalOrderFloatPolicy
+/// [WidenF32Policy]: crate::WidenF32Policy
+/// [QuantizedFloatPolicy]: crate::QuantizedFloatPolicy
+/// [StringFloatPolicy]: crate::StringFloatPolicy
+/// [DecimalFloatPolicy]: crate::DecimalFloatPolicy
 ///
 /// # Examples
 ///
 /// Example using a non-default float policy:
 ///
 /// ```
+/// # #[cfg(feature = "ordered-float")] {
 /// use serde_hashkey::{Key, Float, to_key_with_ordered_float, OrderedFloat, OrderedFloatPolicy};
 ///
-/// # fn main() -> Result<(), serde_hashkey::Error> {
 /// let a: Key<OrderedFloatPolicy> = to_key_with_ordered_float(&42.42f32)?;
 /// assert!(matches!(a, Key::Float(Float::F32(OrderedFloat(..)))));
 ///
 /// let b: Key<OrderedFloatPolicy> = to_key_with_ordered_float(&42.42f64)?;
 /// assert!(matches!(b, Key::Float(Float::F64(OrderedFloat(..)))));
-/// # Ok(()) }
+/// # }
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+///
+/// Example implementing a custom policy which rounds floats to three decimal
+/// places, so that floats which only differ beyond that produce equal keys:
+///
 /// ```
-pub trait FloatPolicy: self::private::Sealed {
+/// use serde::{de, ser};
+/// use serde_hashkey::{to_key_with, Error, FloatPolicy, FloatRepr, FloatWidth, Key};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// struct Rounded(i64);
+///
+/// impl FloatRepr<f64> for Rounded {
+///     fn serialize(value: f64) -> Result<Self, Error> {
+///         let rounded = (value * 1000.0).round();
+///
+///         // Only accept values which are exactly representable.
+///         if !rounded.is_finite() || rounded.abs() >= (1u64 << 53) as f64 {
+///             return Err(Error::UnsupportedFloat { width: FloatWidth::F64, value });
+///         }
+///
+///         Ok(Rounded(rounded as i64))
+///     }
+///
+///     fn visit<'de, V>(&self, visitor: V) -> Result<V::Value, Error>
+///     where
+///         V: de::Visitor<'de>,
+///     {
+///         visitor.visit_f64(self.0 as f64 / 1000.0)
+///     }
+/// }
+///
+/// impl ser::Serialize for Rounded {
+///     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+///     where
+///         S: ser::Serializer,
+///     {
+///         serializer.serialize_f64(self.0 as f64 / 1000.0)
+///     }
+/// }
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// struct RoundedPolicy;
+///
+/// impl FloatPolicy for RoundedPolicy {
+///     type F32 = serde_hashkey::NeverFloat;
+///     type F64 = Rounded;
+/// }
+///
+/// let a: Key<RoundedPolicy> = to_key_with(&[1.0001f64, 2.5])?;
+/// let b: Key<RoundedPolicy> = to_key_with(&[1.0004f64, 2.5])?;
+/// let c: Key<RoundedPolicy> = to_key_with(&[1.0006f64, 2.5])?;
+/// assert_eq!(a, b);
+/// assert_ne!(b, c);
+/// assert!(a < c);
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+pub trait FloatPolicy {
     /// The type encapsulating a 32-bit float, or `f32`.
     type F32: FloatRepr<f32>;
 
     /// The type encapsulating a 64-bit float, or `f64`.
     type F64: FloatRepr<f64>;
-}
 
-// NB: we completely seal the FloatPolicy to prevent external implementations.
-mod private {
-    pub trait Sealed {}
-    impl<T> Sealed for T where T: super::FloatPolicy {}
+    /// Convert an `f32` into the float stored in a [Key].
+    ///
+    /// By default this stores it as a [Float::F32] using [FloatPolicy::F32],
+    /// but a policy can override this to store it differently, like
+    /// [WidenF32Policy] which stores it as a [Float::F64].
+    ///
+    /// [Key]: crate::Key
+    /// [WidenF32Policy]: crate::WidenF32Policy
+    fn serialize_f32(value: f32) -> Result<Float<Self>, Error>
+    where
+        Self: Sized,
+    {
+        Ok(Float::F32(<Self::F32 as FloatRepr<f32>>::serialize(value)?))
+    }
+
+    /// Convert a half-precision float, widened into an `f32`, into the float
+    /// stored in a [Key].
+    ///
+    /// By default this stores it as a [Float::F16] using [FloatPolicy::F32],
+    /// and errors raised by it report the width as [FloatWidth::F16].
+    ///
+    /// [Key]: crate::Key
+    fn serialize_f16(value: f32) -> Result<Float<Self>, Error>
+    where
+        Self: Sized,
+    {
+        match <Self::F32 as FloatRepr<f32>>::serialize(value) {
+            Ok(float) => Ok(Float::F16(float)),
+            Err(Error::UnsupportedFloat { value, .. }) => Err(Error::UnsupportedFloat {
+                width: FloatWidth::F16,
+                value,
+            }),
+            Err(Error::NonFiniteFloat { value, .. }) => Err(Error::NonFiniteFloat {
+                width: FloatWidth::F16,
+                value,
+            }),
+            Err(error) => Err(error),
+        }
+    }
 }
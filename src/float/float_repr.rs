@@ -9,19 +9,32 @@ use std::hash;
 /// typically a wrapper, and defines the protocol necessary to incorporate the
 /// floating point type `T` into the [Key] protocol.
 ///
+/// # Invariants
+///
+/// Implementations must uphold the following for keys using them to behave
+/// correctly. Violating them doesn't cause undefined behavior, but keys might
+/// end up in the wrong place in collections and conversions might not
+/// round-trip.
+///
+/// * `Eq`, `Ord` and `Hash` must agree with each other, so `a == b` if and only
+///   if `a.cmp(&b)` is `Ordering::Equal`, in which case `a` and `b` must also
+///   hash the same. The order must be total, so it can't be derived from the
+///   IEEE 754 comparison of the floats.
+/// * [FloatRepr::visit] must visit the float using `visit_f32` or `visit_f64`,
+///   with a value which [FloatRepr::serialize] turns back into an equal
+///   representation. Values which are visited in any other way are encoded as
+///   a NaN by [Key::to_bytes].
+/// * The [ser::Serialize] implementation must serialize the same value as the
+///   one visited by [FloatRepr::visit].
+///
 /// [Key]: crate::Key
+/// [Key::to_bytes]: crate::Key::to_bytes
 /// [FloatPolicy]: crate::FloatPolicy
 pub trait FloatRepr<T>:
-    self::private::Sealed<T>
-    + Copy
-    + Sized
-    + fmt::Debug
-    + ser::Serialize
-    + cmp::Eq
-    + cmp::Ord
-    + hash::Hash
+    Copy + Sized + fmt::Debug + ser::Serialize + cmp::Eq + cmp::Ord + hash::Hash
 {
-    /// Serialize impl for a floating point value.
+    /// Convert a floating point value into its representation, or error if
+    /// the policy doesn't accept it.
     fn serialize(value: T) -> Result<Self, Error>;
 
     /// Visit the current value.
@@ -29,9 +42,3 @@ pub trait FloatRepr<T>:
     where
         V: de::Visitor<'de>;
 }
-
-// NB: we completely seal the FloatPolicy to prevent external implementations.
-mod private {
-    pub trait Sealed<A> {}
-    impl<T, A> Sealed<A> for T where T: super::FloatRepr<A> {}
-}
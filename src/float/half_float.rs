@@ -0,0 +1,49 @@
+use serde::ser::{self, Serialize};
+
+use crate::error::Error;
+use crate::float::FloatPolicy;
+use crate::key::{Float, Integer, Key};
+use half2 as h2;
+
+/// The name `half::f16` is serialized with, as a newtype struct wrapping its
+/// bits as an `u16`.
+const NAME: &str = "f16";
+
+/// Test if a newtype struct with the given name is a serialized `half::f16`.
+#[inline]
+pub(crate) fn is_f16(name: &str) -> bool {
+    name == NAME
+}
+
+/// Convert a key serialized from a newtype struct into a [Float::F16] if it's
+/// a serialized `half::f16`.
+///
+/// [Float::F16]: crate::Float::F16
+pub(crate) fn from_newtype_struct<F>(name: &str, key: Key<F>) -> Result<Key<F>, Error>
+where
+    F: FloatPolicy,
+{
+    match key {
+        Key::Integer(Integer::U16(bits)) if is_f16(name) => {
+            let value = h2::f16::from_bits(bits).to_f32();
+            Ok(Key::Float(F::serialize_f16(value)?))
+        }
+        key => Ok(key),
+    }
+}
+
+/// Serialize the value of a [Float::F16] the same way `half::f16` is
+/// serialized, so that serializing it into a key produces the same key.
+///
+/// Representations which can't be visited as a floating point number are
+/// serialized as they are.
+pub(crate) fn serialize_f16<F, S>(value: &F::F32, serializer: S) -> Result<S::Ok, S::Error>
+where
+    F: FloatPolicy,
+    S: ser::Serializer,
+{
+    match super::to_f64(&Float::<F>::F16(*value)) {
+        Some(v) => serializer.serialize_newtype_struct(NAME, &h2::f16::from_f64(v).to_bits()),
+        None => value.serialize(serializer),
+    }
+}
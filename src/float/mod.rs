@@ -1,19 +1,52 @@
 cfg_ordered_float! {
-    pub use self::ordered_float::{to_key_with_ordered_float, OrderedFloat, OrderedFloatPolicy};
+    pub use self::ordered_float::{hash_value_with_ordered_float, to_key_with_ordered_float, OrderedFloat, OrderedFloatPolicy};
 }
 
-use crate::error::Error;
+cfg_decimal! {
+    pub use self::decimal_float::{to_key_with_decimal_float, DecimalFloat, DecimalFloatPolicy};
+}
+
+use crate::error::{Error, FloatWidth};
+use crate::key::Float;
 use serde::de;
+use std::fmt;
 
+mod canonical_float;
+mod finite_float;
 mod float_policy;
 mod float_repr;
+mod quantized_float;
+mod string_float;
+mod total_order_float;
+mod widen_float;
 
 cfg_ordered_float! {
     mod ordered_float;
 }
 
+cfg_decimal! {
+    mod decimal_float;
+}
+
+cfg_half! {
+    mod half_float;
+    pub(crate) use self::half_float::{from_newtype_struct, is_f16, serialize_f16};
+}
+
+pub use self::canonical_float::{
+    to_key_with_canonical_float, CanonicalFloat, CanonicalFloatPolicy,
+};
+pub use self::finite_float::{to_key_rejecting_non_finite, FiniteFloat, RejectNonFinitePolicy};
 pub use self::float_policy::FloatPolicy;
 pub use self::float_repr::FloatRepr;
+pub use self::quantized_float::{
+    to_key_with_quantized_float, QuantizedFloat, QuantizedFloatPolicy,
+};
+pub use self::string_float::{to_key_with_string_float, StringFloat, StringFloatPolicy};
+pub use self::total_order_float::{
+    to_key_with_total_order_float, TotalOrderFloat, TotalOrderFloatPolicy,
+};
+pub use self::widen_float::{to_key_with_widened_float, WidenF32Policy};
 
 /// An uninhabitable type for float policies that cannot produce a value of the
 /// corresponding type. This is used by [RejectFloatPolicy].
@@ -21,28 +54,36 @@ pub use self::float_repr::FloatRepr;
 pub enum NeverFloat {}
 
 impl FloatRepr<f32> for NeverFloat {
-    fn serialize(_: f32) -> Result<Self, Error> {
-        Err(Error::UnsupportedType("f32"))
+    fn serialize(value: f32) -> Result<Self, Error> {
+        Err(Error::UnsupportedFloat {
+            width: FloatWidth::F32,
+            value: f64::from(value),
+        })
     }
 
     fn visit<'de, V>(&self, _: V) -> Result<V::Value, Error>
     where
         V: de::Visitor<'de>,
     {
-        Err(Error::UnsupportedType("f32"))
+        // Note: type is uninhabitable, so this can never be reached.
+        match *self {}
     }
 }
 
 impl FloatRepr<f64> for NeverFloat {
-    fn serialize(_: f64) -> Result<Self, Error> {
-        Err(Error::UnsupportedType("f64"))
+    fn serialize(value: f64) -> Result<Self, Error> {
+        Err(Error::UnsupportedFloat {
+            width: FloatWidth::F64,
+            value,
+        })
     }
 
     fn visit<'de, V>(&self, _: V) -> Result<V::Value, Error>
     where
         V: de::Visitor<'de>,
     {
-        Err(Error::UnsupportedType("f64"))
+        // Note: type is uninhabitable, so this can never be reached.
+        match *self {}
     }
 }
 
@@ -78,3 +119,75 @@ impl FloatPolicy for RejectFloatPolicy {
     type F32 = NeverFloat;
     type F64 = NeverFloat;
 }
+
+/// Test if a newtype struct with the given name is a serialized `half::f16`,
+/// which is never the case without the `half` feature.
+#[cfg(not(feature = "half"))]
+#[inline]
+pub(crate) fn is_f16(_: &str) -> bool {
+    false
+}
+
+/// Convert a key serialized from a newtype struct into a float, which is never
+/// done without the `half` feature.
+#[cfg(not(feature = "half"))]
+#[inline]
+pub(crate) fn from_newtype_struct<F>(_: &str, key: crate::Key<F>) -> Result<crate::Key<F>, Error>
+where
+    F: FloatPolicy,
+{
+    Ok(key)
+}
+
+/// Serialize the value of a [Float::F16], which is serialized like an `f32`
+/// without the `half` feature.
+#[cfg(not(feature = "half"))]
+#[inline]
+pub(crate) fn serialize_f16<F, S>(value: &F::F32, serializer: S) -> Result<S::Ok, S::Error>
+where
+    F: FloatPolicy,
+    S: serde::Serializer,
+{
+    serde::Serialize::serialize(value, serializer)
+}
+
+/// Extract the value of a float stored under any policy by visiting it.
+///
+/// Returns `None` if the representation visits with something other than a
+/// floating point value.
+pub(crate) fn to_f64<F>(float: &Float<F>) -> Option<f64>
+where
+    F: FloatPolicy,
+{
+    struct FloatVisitor;
+
+    impl<'de> de::Visitor<'de> for FloatVisitor {
+        type Value = f64;
+
+        fn expecting(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+            fmt.write_str("a float")
+        }
+
+        fn visit_f32<E>(self, v: f32) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            Ok(f64::from(v))
+        }
+
+        fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            Ok(v)
+        }
+    }
+
+    let value = match float {
+        Float::F32(float) => <F::F32 as FloatRepr<f32>>::visit(float, FloatVisitor),
+        Float::F64(float) => <F::F64 as FloatRepr<f64>>::visit(float, FloatVisitor),
+        Float::F16(float) => <F::F32 as FloatRepr<f32>>::visit(float, FloatVisitor),
+    };
+
+    value.ok()
+}
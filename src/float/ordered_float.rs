@@ -1,16 +1,31 @@
 use crate::error::Error;
 use crate::float::{FloatPolicy, FloatRepr};
-use crate::key::Key;
+use crate::key::{Float, Key};
 use num_traits02 as nt02;
 use ordered_float3 as of3;
 use serde::{de, ser};
 use std::cmp;
 use std::fmt;
 use std::hash;
+use std::ops;
 
 /// An opaque floating-point representation which has a total ordering. This is
 /// used by [OrderedFloatPolicy].
-#[derive(Clone, Copy)]
+///
+/// It's serialized and deserialized transparently as the float it wraps, and
+/// dereferences to it.
+///
+/// # Examples
+///
+/// ```
+/// use serde_hashkey::OrderedFloat;
+///
+/// let float: OrderedFloat<f64> = serde_json::from_str("1.5")?;
+/// assert_eq!(float, OrderedFloat(1.5));
+/// assert_eq!(serde_json::to_string(&float)?, "1.5");
+/// # Ok::<_, serde_json::Error>(())
+/// ```
+#[derive(Clone, Copy, Default)]
 pub struct OrderedFloat<T>(pub T);
 
 impl<T> fmt::Debug for OrderedFloat<T>
@@ -22,6 +37,63 @@ where
     }
 }
 
+impl<T> fmt::Display for OrderedFloat<T>
+where
+    T: fmt::Display,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, fmt)
+    }
+}
+
+impl<T> ops::Deref for OrderedFloat<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> ops::DerefMut for OrderedFloat<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T> From<T> for OrderedFloat<T> {
+    fn from(value: T) -> Self {
+        OrderedFloat(value)
+    }
+}
+
+impl From<OrderedFloat<f32>> for f32 {
+    fn from(value: OrderedFloat<f32>) -> Self {
+        value.0
+    }
+}
+
+impl From<OrderedFloat<f64>> for f64 {
+    fn from(value: OrderedFloat<f64>) -> Self {
+        value.0
+    }
+}
+
+/// Comparison against a primitive uses the same semantics as comparing two
+/// ordered floats, so NaN is equal to itself.
+impl PartialEq<f32> for OrderedFloat<f32> {
+    fn eq(&self, other: &f32) -> bool {
+        *self == OrderedFloat(*other)
+    }
+}
+
+/// Comparison against a primitive uses the same semantics as comparing two
+/// ordered floats, so NaN is equal to itself.
+impl PartialEq<f64> for OrderedFloat<f64> {
+    fn eq(&self, other: &f64) -> bool {
+        *self == OrderedFloat(*other)
+    }
+}
+
 impl FloatRepr<f32> for OrderedFloat<f32> {
     fn serialize(float: f32) -> Result<Self, Error> {
         Ok(OrderedFloat(float))
@@ -60,6 +132,18 @@ where
     }
 }
 
+impl<'de, T> serde::Deserialize<'de> for OrderedFloat<T>
+where
+    T: serde::Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        T::deserialize(deserializer).map(OrderedFloat)
+    }
+}
+
 impl<T> PartialEq for OrderedFloat<T>
 where
     T: nt02::Float,
@@ -76,7 +160,7 @@ where
     T: nt02::Float,
 {
     fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
-        Some(of3::OrderedFloat(self.0).cmp(&of3::OrderedFloat(other.0)))
+        Some(self.cmp(other))
     }
 }
 
@@ -111,14 +195,30 @@ impl FloatPolicy for OrderedFloatPolicy {
     type F64 = OrderedFloat<f64>;
 }
 
+impl From<f32> for Key<OrderedFloatPolicy> {
+    fn from(v: f32) -> Self {
+        Key::Float(Float::F32(OrderedFloat(v)))
+    }
+}
+
+impl From<f64> for Key<OrderedFloatPolicy> {
+    fn from(v: f64) -> Self {
+        Key::Float(Float::F64(OrderedFloat(v)))
+    }
+}
+
 /// Serialize the given value to a [Key] using [OrderedFloatPolicy].
 ///
+/// This is a thin wrapper around [to_key_with], which can be used to be
+/// generic over the float policy.
+///
 /// This policy is derived from the [`OrderedFloat` type] in the
 /// [`ordered-float` crate].
 ///
 /// [`OrderedFloat` type]:
 ///     https://docs.rs/ordered-float/2/ordered_float/struct.OrderedFloat.html
 /// [`ordered-float` crate]: https://docs.rs/ordered-float/2/ordered_float/
+/// [to_key_with]: crate::to_key_with
 ///
 /// # Examples
 ///
@@ -157,7 +257,40 @@ impl FloatPolicy for OrderedFloatPolicy {
 /// ```
 pub fn to_key_with_ordered_float<T>(value: &T) -> Result<Key<OrderedFloatPolicy>, Error>
 where
-    T: ser::Serialize,
+    T: ?Sized + ser::Serialize,
 {
     crate::ser::to_key_with_policy::<T, OrderedFloatPolicy>(value)
 }
+
+/// Feed the hash of the [Key] the given value serializes into using
+/// [OrderedFloatPolicy] to the given hasher, without building the key.
+///
+/// This is a thin wrapper around [hash_value_with], and feeds the hasher with
+/// the same data as `to_key_with_ordered_float(value)?.hash(hasher)`.
+///
+/// [hash_value_with]: crate::hash_value_with
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::hash_map::DefaultHasher;
+/// use std::hash::{Hash, Hasher};
+///
+/// use serde_hashkey::{hash_value_with_ordered_float, to_key_with_ordered_float};
+///
+/// let mut a = DefaultHasher::new();
+/// hash_value_with_ordered_float(&(1.5f32, "pi", 3.14f64), &mut a)?;
+///
+/// let mut b = DefaultHasher::new();
+/// to_key_with_ordered_float(&(1.5f32, "pi", 3.14f64))?.hash(&mut b);
+///
+/// assert_eq!(a.finish(), b.finish());
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+pub fn hash_value_with_ordered_float<T, H>(value: &T, hasher: &mut H) -> Result<(), Error>
+where
+    T: ?Sized + ser::Serialize,
+    H: hash::Hasher,
+{
+    crate::hash::hash_value_with::<OrderedFloatPolicy, T, H>(value, hasher)
+}
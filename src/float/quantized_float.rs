@@ -0,0 +1,203 @@
+use crate::error::{Error, FloatWidth};
+use crate::float::{FloatPolicy, FloatRepr, NeverFloat};
+use crate::key::{Float, Key};
+use serde::{de, ser};
+use std::cmp;
+use std::convert::TryFrom;
+use std::fmt;
+use std::hash;
+
+/// A floating-point representation which is rounded to `DECIMALS` decimal
+/// places. This is used by [QuantizedFloatPolicy].
+///
+/// The value is stored as the number of steps of size `10^-DECIMALS` on the
+/// grid it's rounded to, so equality, ordering and hashing operate on the
+/// quantized value.
+#[derive(Clone, Copy)]
+pub struct QuantizedFloat<const DECIMALS: u32> {
+    /// The value multiplied by `10^DECIMALS` and rounded, which is always an
+    /// integer and never negative zero.
+    steps: f64,
+}
+
+impl<const DECIMALS: u32> QuantizedFloat<DECIMALS> {
+    /// Quantize the given value.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the value is NaN or infinite, or if it's too large to be
+    /// quantized.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::QuantizedFloat;
+    ///
+    /// let a = QuantizedFloat::<2>::new(1.004)?;
+    /// let b = QuantizedFloat::<2>::new(0.996)?;
+    /// assert_eq!(a, b);
+    /// assert_eq!(a.get(), 1.0);
+    /// assert!(QuantizedFloat::<2>::new(f64::NAN).is_err());
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn new(value: f64) -> Result<Self, Error> {
+        Self::quantize(value, FloatWidth::F64)
+    }
+
+    /// Get the quantized value.
+    pub fn get(self) -> f64 {
+        self.steps / scale(DECIMALS)
+    }
+
+    fn quantize(value: f64, width: FloatWidth) -> Result<Self, Error> {
+        if !value.is_finite() {
+            return Err(Error::NonFiniteFloat { width, value });
+        }
+
+        let steps = (value * scale(DECIMALS)).round();
+
+        if !steps.is_finite() {
+            return Err(Error::UnsupportedFloat { width, value });
+        }
+
+        // NB: adding zero turns negative zero into positive zero.
+        Ok(QuantizedFloat { steps: steps + 0.0 })
+    }
+}
+
+/// The size of the grid floats are rounded to.
+fn scale(decimals: u32) -> f64 {
+    10f64.powi(i32::try_from(decimals).unwrap_or(i32::MAX))
+}
+
+impl<const DECIMALS: u32> fmt::Debug for QuantizedFloat<DECIMALS> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.get(), fmt)
+    }
+}
+
+impl<const DECIMALS: u32> FloatRepr<f64> for QuantizedFloat<DECIMALS> {
+    fn serialize(value: f64) -> Result<Self, Error> {
+        Self::new(value)
+    }
+
+    fn visit<'de, V>(&self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_f64(self.get())
+    }
+}
+
+impl<const DECIMALS: u32> ser::Serialize for QuantizedFloat<DECIMALS> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        serializer.serialize_f64(self.get())
+    }
+}
+
+impl<const DECIMALS: u32> PartialEq for QuantizedFloat<DECIMALS> {
+    fn eq(&self, other: &Self) -> bool {
+        self.steps == other.steps
+    }
+}
+
+impl<const DECIMALS: u32> Eq for QuantizedFloat<DECIMALS> {}
+
+impl<const DECIMALS: u32> PartialOrd for QuantizedFloat<DECIMALS> {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const DECIMALS: u32> Ord for QuantizedFloat<DECIMALS> {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.steps.total_cmp(&other.steps)
+    }
+}
+
+impl<const DECIMALS: u32> hash::Hash for QuantizedFloat<DECIMALS> {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.steps.to_bits().hash(state)
+    }
+}
+
+/// A float serialization policy which rounds every float to `DECIMALS`
+/// decimal places, so that floats which are close to each other produce equal
+/// keys. This policy is used by the [to_key_with_quantized_float] function.
+///
+/// Floats are rounded to the nearest multiple of `10^-DECIMALS`, and values
+/// which are exactly halfway between two multiples are rounded away from
+/// zero. Note that many decimal values like `0.05` can't be represented
+/// exactly, so whether a float which looks like it's halfway is rounded up or
+/// down depends on which side of it the float actually is. Deserializing a key
+/// produces the rounded value.
+///
+/// Since the width of a float doesn't matter once it's rounded, floats of
+/// every width are stored as a [Float::F64]. NaN and infinity are rejected
+/// with [Error::NonFiniteFloat], and floats which are too large to be rounded
+/// with [Error::UnsupportedFloat].
+///
+/// # Examples
+///
+/// ```
+/// use serde_hashkey::{to_key_with_quantized_float, QuantizedFloatPolicy, Key};
+///
+/// let a = to_key_with_quantized_float::<1, _>(&21.4999999f64)?;
+/// let b = to_key_with_quantized_float::<1, _>(&21.5f32)?;
+/// assert_eq!(a, b);
+///
+/// let key: Key<QuantizedFloatPolicy<1>> = Key::from_f64(21.54)?;
+/// assert_eq!(a, key);
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct QuantizedFloatPolicy<const DECIMALS: u32>(());
+
+impl<const DECIMALS: u32> FloatPolicy for QuantizedFloatPolicy<DECIMALS> {
+    // NB: floats are never stored as an `F32`, see `serialize_f32`.
+    type F32 = NeverFloat;
+    type F64 = QuantizedFloat<DECIMALS>;
+
+    fn serialize_f32(value: f32) -> Result<Float<Self>, Error> {
+        let float = QuantizedFloat::quantize(f64::from(value), FloatWidth::F32)?;
+        Ok(Float::F64(float))
+    }
+
+    fn serialize_f16(value: f32) -> Result<Float<Self>, Error> {
+        let float = QuantizedFloat::quantize(f64::from(value), FloatWidth::F16)?;
+        Ok(Float::F64(float))
+    }
+}
+
+/// Serialize the given value to a [Key] using [QuantizedFloatPolicy], rounding
+/// every float to `DECIMALS` decimal places.
+///
+/// # Examples
+///
+/// ```
+/// use serde_derive::{Deserialize, Serialize};
+/// use serde_hashkey::{from_key, to_key_with_quantized_float};
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Reading {
+///     sensor: u32,
+///     celsius: f64,
+/// }
+///
+/// let a = to_key_with_quantized_float::<2, _>(&Reading { sensor: 1, celsius: 21.4999 })?;
+/// let b = to_key_with_quantized_float::<2, _>(&Reading { sensor: 1, celsius: 21.5001 })?;
+/// assert_eq!(a, b);
+/// assert_eq!(from_key::<Reading, _>(&a)?, Reading { sensor: 1, celsius: 21.5 });
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+pub fn to_key_with_quantized_float<const DECIMALS: u32, T>(
+    value: &T,
+) -> Result<Key<QuantizedFloatPolicy<DECIMALS>>, Error>
+where
+    T: ?Sized + ser::Serialize,
+{
+    crate::ser::to_key_with_policy::<T, QuantizedFloatPolicy<DECIMALS>>(value)
+}
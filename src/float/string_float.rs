@@ -0,0 +1,220 @@
+use crate::error::Error;
+use crate::float::{FloatPolicy, FloatRepr};
+use crate::key::{Float, Key};
+use serde::{de, ser};
+use std::cmp;
+use std::convert::TryFrom;
+use std::fmt;
+use std::hash;
+use std::str;
+
+/// The longest string a float is formatted as, which is an `f64` like
+/// `-2.2250738585072014e-308`.
+const CAPACITY: usize = 24;
+
+/// A floating-point representation which is compared using the shortest
+/// string which parses back into the same float. This is used by
+/// [StringFloatPolicy].
+///
+/// Floats are formatted the same way as their `Debug` implementation, like
+/// `1.0`, `0.30000000000000004` or `1e300`. Equality, ordering and hashing
+/// operate on the string, so keys can be compared in the same way as the
+/// textual form they're exported as. This means that every NaN is equal,
+/// `-0.0` and `0.0` are not, and floats are ordered lexicographically by
+/// their strings. The float itself is kept so that deserializing and
+/// serializing a key produces the original float.
+///
+/// # Examples
+///
+/// ```
+/// use serde_hashkey::{FloatRepr, StringFloat};
+///
+/// let float = <StringFloat<f64> as FloatRepr<f64>>::serialize(1e300)?;
+/// assert_eq!(float.as_str(), "1e300");
+/// assert_eq!(float.get(), 1e300);
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+#[derive(Clone, Copy)]
+pub struct StringFloat<T> {
+    value: T,
+    len: u8,
+    text: [u8; CAPACITY],
+}
+
+impl<T> StringFloat<T>
+where
+    T: Copy + fmt::Debug,
+{
+    fn new(value: T) -> Self {
+        let mut float = StringFloat {
+            value,
+            len: 0,
+            text: [0; CAPACITY],
+        };
+
+        let mut writer = Writer(&mut float);
+
+        if fmt::Write::write_fmt(&mut writer, format_args!("{:?}", value)).is_err() {
+            unreachable!("float formatted as more than {} bytes", CAPACITY);
+        }
+
+        float
+    }
+
+    /// Get the float.
+    pub fn get(self) -> T {
+        self.value
+    }
+
+    /// Get the string the float is formatted as.
+    pub fn as_str(&self) -> &str {
+        match str::from_utf8(&self.text[..usize::from(self.len)]) {
+            Ok(text) => text,
+            Err(..) => unreachable!("float formatted as invalid UTF-8"),
+        }
+    }
+}
+
+/// Appends formatted text to a [StringFloat].
+struct Writer<'a, T>(&'a mut StringFloat<T>);
+
+impl<T> fmt::Write for Writer<'_, T> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let start = usize::from(self.0.len);
+        let end = start + s.len();
+
+        let out = self.0.text.get_mut(start..end).ok_or(fmt::Error)?;
+        out.copy_from_slice(s.as_bytes());
+        self.0.len = u8::try_from(end).map_err(|_| fmt::Error)?;
+        Ok(())
+    }
+}
+
+impl<T> fmt::Debug for StringFloat<T>
+where
+    T: Copy + fmt::Debug,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str(self.as_str())
+    }
+}
+
+macro_rules! impl_string_float {
+    ($ty:ty, $visit:ident, $serialize:ident) => {
+        impl FloatRepr<$ty> for StringFloat<$ty> {
+            fn serialize(value: $ty) -> Result<Self, Error> {
+                Ok(StringFloat::new(value))
+            }
+
+            fn visit<'de, V>(&self, visitor: V) -> Result<V::Value, Error>
+            where
+                V: de::Visitor<'de>,
+            {
+                visitor.$visit(self.value)
+            }
+        }
+
+        /// Floats are serialized as numbers, and not as their strings.
+        impl ser::Serialize for StringFloat<$ty> {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: ser::Serializer,
+            {
+                serializer.$serialize(self.value)
+            }
+        }
+    };
+}
+
+impl_string_float!(f32, visit_f32, serialize_f32);
+impl_string_float!(f64, visit_f64, serialize_f64);
+
+impl<T> PartialEq for StringFloat<T>
+where
+    T: Copy + fmt::Debug,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl<T> Eq for StringFloat<T> where T: Copy + fmt::Debug {}
+
+impl<T> PartialOrd for StringFloat<T>
+where
+    T: Copy + fmt::Debug,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for StringFloat<T>
+where
+    T: Copy + fmt::Debug,
+{
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl<T> hash::Hash for StringFloat<T>
+where
+    T: Copy + fmt::Debug,
+{
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+/// A float serialization policy which compares floats using the shortest
+/// string which parses back into the same float. This policy is used by the
+/// [to_key_with_string_float] function.
+///
+/// This is useful for keys which are exported as text and imported again,
+/// since floats which are formatted the same produce equal keys. See
+/// [StringFloat] for details.
+#[derive(Debug, Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct StringFloatPolicy(());
+
+impl FloatPolicy for StringFloatPolicy {
+    type F32 = StringFloat<f32>;
+    type F64 = StringFloat<f64>;
+}
+
+impl From<f32> for Key<StringFloatPolicy> {
+    fn from(v: f32) -> Self {
+        Key::Float(Float::F32(StringFloat::new(v)))
+    }
+}
+
+impl From<f64> for Key<StringFloatPolicy> {
+    fn from(v: f64) -> Self {
+        Key::Float(Float::F64(StringFloat::new(v)))
+    }
+}
+
+/// Serialize the given value to a [Key] using [StringFloatPolicy].
+///
+/// # Examples
+///
+/// ```
+/// use serde_derive::{Deserialize, Serialize};
+/// use serde_hashkey::{from_key, to_key_with_string_float};
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Sample {
+///     mass: f64,
+/// }
+///
+/// let key = to_key_with_string_float(&Sample { mass: 1.5e-12 })?;
+/// assert_eq!(serde_json::to_string(&key)?, r#"{"mass":1.5e-12}"#);
+/// assert_eq!(from_key::<Sample, _>(&key)?, Sample { mass: 1.5e-12 });
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub fn to_key_with_string_float<T>(value: &T) -> Result<Key<StringFloatPolicy>, Error>
+where
+    T: ?Sized + ser::Serialize,
+{
+    crate::ser::to_key_with_policy::<T, StringFloatPolicy>(value)
+}
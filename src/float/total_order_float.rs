@@ -0,0 +1,146 @@
+use crate::error::Error;
+use crate::float::{FloatPolicy, FloatRepr};
+use crate::key::{Float, Key};
+use serde::{de, ser};
+use std::cmp;
+use std::fmt;
+use std::hash;
+
+/// An opaque floating-point representation which is ordered using the
+/// `totalOrder` predicate of IEEE 754. This is used by
+/// [TotalOrderFloatPolicy].
+///
+/// Floats are ordered as `-NaN < -inf < ... < -0.0 < 0.0 < ... < inf < NaN`,
+/// where NaNs are further ordered by their payload. Equality and hashing
+/// operate on the bits of the float, so `-0.0` is not equal to `0.0`, and NaNs
+/// are only equal to NaNs with the same sign and payload.
+///
+/// # Examples
+///
+/// ```
+/// use serde_hashkey::TotalOrderFloat;
+///
+/// assert!(TotalOrderFloat(-0.0f64) < TotalOrderFloat(0.0f64));
+/// assert!(TotalOrderFloat(-f64::NAN) < TotalOrderFloat(f64::NEG_INFINITY));
+/// assert!(TotalOrderFloat(f64::INFINITY) < TotalOrderFloat(f64::NAN));
+/// assert_eq!(TotalOrderFloat(f32::NAN), TotalOrderFloat(f32::NAN));
+/// ```
+#[derive(Clone, Copy)]
+pub struct TotalOrderFloat<T>(pub T);
+
+impl<T> fmt::Debug for TotalOrderFloat<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, fmt)
+    }
+}
+
+macro_rules! impl_total_order_float {
+    ($ty:ty, $visit:ident, $serialize:ident) => {
+        impl FloatRepr<$ty> for TotalOrderFloat<$ty> {
+            fn serialize(value: $ty) -> Result<Self, Error> {
+                Ok(TotalOrderFloat(value))
+            }
+
+            fn visit<'de, V>(&self, visitor: V) -> Result<V::Value, Error>
+            where
+                V: de::Visitor<'de>,
+            {
+                visitor.$visit(self.0)
+            }
+        }
+
+        impl ser::Serialize for TotalOrderFloat<$ty> {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: ser::Serializer,
+            {
+                serializer.$serialize(self.0)
+            }
+        }
+
+        impl PartialEq for TotalOrderFloat<$ty> {
+            fn eq(&self, other: &Self) -> bool {
+                self.0.to_bits() == other.0.to_bits()
+            }
+        }
+
+        impl Eq for TotalOrderFloat<$ty> {}
+
+        impl PartialOrd for TotalOrderFloat<$ty> {
+            fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl Ord for TotalOrderFloat<$ty> {
+            fn cmp(&self, other: &Self) -> cmp::Ordering {
+                self.0.total_cmp(&other.0)
+            }
+        }
+
+        impl hash::Hash for TotalOrderFloat<$ty> {
+            fn hash<H: hash::Hasher>(&self, state: &mut H) {
+                self.0.to_bits().hash(state)
+            }
+        }
+    };
+}
+
+impl_total_order_float!(f32, visit_f32, serialize_f32);
+impl_total_order_float!(f64, visit_f64, serialize_f64);
+
+/// A float serialization policy which orders floats using the `totalOrder`
+/// predicate of IEEE 754, and compares and hashes them by their bits. This
+/// policy is used by the [to_key_with_total_order_float] function.
+///
+/// Floats are stored as a [TotalOrderFloat]. Unlike [OrderedFloatPolicy] this
+/// distinguishes between `-0.0` and `0.0`, and between NaNs with different
+/// signs or payloads, so every float is preserved exactly.
+///
+/// [OrderedFloatPolicy]: crate::OrderedFloatPolicy
+#[derive(Debug, Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct TotalOrderFloatPolicy(());
+
+impl FloatPolicy for TotalOrderFloatPolicy {
+    type F32 = TotalOrderFloat<f32>;
+    type F64 = TotalOrderFloat<f64>;
+}
+
+impl From<f32> for Key<TotalOrderFloatPolicy> {
+    fn from(v: f32) -> Self {
+        Key::Float(Float::F32(TotalOrderFloat(v)))
+    }
+}
+
+impl From<f64> for Key<TotalOrderFloatPolicy> {
+    fn from(v: f64) -> Self {
+        Key::Float(Float::F64(TotalOrderFloat(v)))
+    }
+}
+
+/// Serialize the given value to a [Key] using [TotalOrderFloatPolicy].
+///
+/// # Examples
+///
+/// ```
+/// use serde_hashkey::{from_key, to_key_with_total_order_float};
+///
+/// let zero = to_key_with_total_order_float(&0.0f64)?;
+/// let negative = to_key_with_total_order_float(&-0.0f64)?;
+/// assert!(negative < zero);
+///
+/// let key = to_key_with_total_order_float(&(f32::NAN, -0.0f64))?;
+/// let (nan, zero) = from_key::<(f32, f64), _>(&key)?;
+/// assert_eq!(nan.to_bits(), f32::NAN.to_bits());
+/// assert!(zero.is_sign_negative());
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+pub fn to_key_with_total_order_float<T>(value: &T) -> Result<Key<TotalOrderFloatPolicy>, Error>
+where
+    T: ?Sized + ser::Serialize,
+{
+    crate::ser::to_key_with_policy::<T, TotalOrderFloatPolicy>(value)
+}
@@ -0,0 +1,96 @@
+use crate::error::Error;
+use crate::float::{CanonicalFloat, FloatPolicy, NeverFloat};
+use crate::key::{Float, Key};
+use serde::ser;
+
+/// A float serialization policy which stores every float as a 64-bit float, so
+/// that `f32` and `f64` values which are equal produce equal keys. This
+/// includes half-precision floats. This policy
+/// is used by the [to_key_with_widened_float] function.
+///
+/// Floats are stored as a [CanonicalFloat], so like with
+/// [CanonicalFloatPolicy] every NaN is treated as the same value and `-0.0` as
+/// `0.0`.
+///
+/// # Precision
+///
+/// Every `f32` can be widened into an `f64` exactly, and deserializing it into
+/// an `f32` again produces the same value. But the reverse is not true, so an
+/// `f32` and an `f64` only produce the same key if the `f64` holds exactly the
+/// same value. For example `0.1f32` is not equal to `0.1f64`, since neither
+/// can represent `0.1` exactly and the `f32` is rounded more coarsely.
+/// Deserializing a key produced from an `f64` into an `f32` rounds it to the
+/// nearest `f32`.
+///
+/// [CanonicalFloatPolicy]: crate::CanonicalFloatPolicy
+///
+/// # Examples
+///
+/// ```
+/// use serde_hashkey::{to_key_with_widened_float, Float, Key};
+///
+/// let key = to_key_with_widened_float(&1.5f32)?;
+/// assert_eq!(key, to_key_with_widened_float(&1.5f64)?);
+/// assert!(matches!(key, Key::Float(Float::F64(..))));
+///
+/// assert_ne!(to_key_with_widened_float(&0.1f32)?, to_key_with_widened_float(&0.1f64)?);
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct WidenF32Policy(());
+
+impl FloatPolicy for WidenF32Policy {
+    // NB: floats are never stored as an `F32`, see `serialize_f32`.
+    type F32 = NeverFloat;
+    type F64 = CanonicalFloat<f64>;
+
+    fn serialize_f32(value: f32) -> Result<Float<Self>, Error> {
+        Ok(Float::F64(CanonicalFloat::new(f64::from(value))))
+    }
+
+    fn serialize_f16(value: f32) -> Result<Float<Self>, Error> {
+        Self::serialize_f32(value)
+    }
+}
+
+impl From<f32> for Key<WidenF32Policy> {
+    fn from(v: f32) -> Self {
+        Key::Float(Float::F64(CanonicalFloat::new(f64::from(v))))
+    }
+}
+
+impl From<f64> for Key<WidenF32Policy> {
+    fn from(v: f64) -> Self {
+        Key::Float(Float::F64(CanonicalFloat::new(v)))
+    }
+}
+
+/// Serialize the given value to a [Key] using [WidenF32Policy].
+///
+/// # Examples
+///
+/// ```
+/// use serde_derive::{Deserialize, Serialize};
+/// use serde_hashkey::{from_key, to_key_with_widened_float};
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Narrow {
+///     celsius: f32,
+/// }
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Wide {
+///     celsius: f64,
+/// }
+///
+/// let key = to_key_with_widened_float(&Narrow { celsius: 21.5 })?;
+/// assert_eq!(key, to_key_with_widened_float(&Wide { celsius: 21.5 })?);
+/// assert_eq!(from_key::<Narrow, _>(&key)?, Narrow { celsius: 21.5 });
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+pub fn to_key_with_widened_float<T>(value: &T) -> Result<Key<WidenF32Policy>, Error>
+where
+    T: ?Sized + ser::Serialize,
+{
+    crate::ser::to_key_with_policy::<T, WidenF32Policy>(value)
+}
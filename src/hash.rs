@@ -0,0 +1,1097 @@
+//! Hashing of keys, and of serializable values without building a [Key].
+//!
+//! The [Hash] implementation of [Key] and the serializer used by [hash_value]
+//! share the helpers in this module, so that they feed a hasher with exactly
+//! the same data.
+
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::mem;
+
+use serde::ser;
+
+use crate::error::Error;
+use crate::float::{self, FloatPolicy, FloatRepr, RejectFloatPolicy};
+use crate::key::{Float, Key};
+use crate::option;
+use crate::path::PathSegment;
+use crate::ser::{to_key_with_policy, KeySerializer, SerializeMap, SerializeVec};
+
+const NULL: u8 = 0;
+const UNIT: u8 = 1;
+const BOOL: u8 = 2;
+const INTEGER: u8 = 3;
+const FLOAT: u8 = 4;
+const BYTES: u8 = 5;
+const CHAR: u8 = 6;
+const STRING: u8 = 7;
+const SEQ: u8 = 8;
+const MAP: u8 = 9;
+const INTEGER_SEQ: u8 = 10;
+const FLOAT_SEQ: u8 = 11;
+
+impl<F> Hash for Key<F>
+where
+    F: FloatPolicy + Hash,
+{
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        match self {
+            Key::Null => state.write_u8(NULL),
+            Key::Unit => state.write_u8(UNIT),
+            Key::Bool(v) => {
+                state.write_u8(BOOL);
+                v.hash(state);
+            }
+            Key::Integer(v) => {
+                state.write_u8(INTEGER);
+                v.hash(state);
+            }
+            Key::Float(v) => {
+                state.write_u8(FLOAT);
+                v.hash(state);
+            }
+            Key::Bytes(v) => hash_bytes(v, state),
+            Key::Char(v) => {
+                state.write_u8(CHAR);
+                v.hash(state);
+            }
+            Key::String(v) => hash_str(v, state),
+            Key::Seq(seq) => {
+                hash_len(SEQ, seq.len(), state);
+
+                for value in seq.iter() {
+                    value.hash(state);
+                }
+            }
+            Key::Map(map) => {
+                hash_len(MAP, map.len(), state);
+
+                for (key, value) in map.iter() {
+                    key.hash(state);
+                    value.hash(state);
+                }
+            }
+            Key::IntegerSeq(v) => {
+                state.write_u8(INTEGER_SEQ);
+                v.hash(state);
+            }
+            Key::FloatSeq(v) => {
+                state.write_u8(FLOAT_SEQ);
+                v.hash(state);
+            }
+        }
+    }
+}
+
+fn hash_bytes<H>(value: &[u8], state: &mut H)
+where
+    H: Hasher,
+{
+    state.write_u8(BYTES);
+    value.hash(state);
+}
+
+fn hash_str<H>(value: &str, state: &mut H)
+where
+    H: Hasher,
+{
+    state.write_u8(STRING);
+    value.hash(state);
+}
+
+/// Hash the tag and the length of a sequence or a map, which is followed by
+/// its elements or entries.
+fn hash_len<H>(tag: u8, len: usize, state: &mut H)
+where
+    H: Hasher,
+{
+    state.write_u8(tag);
+    state.write_usize(len);
+}
+
+/// Feed the hash of the [Key] the given value serializes into to the given
+/// hasher, without building the key.
+///
+/// This is guaranteed to feed the hasher with the same data as
+/// `to_key(value)?.hash(hasher)`, so the two can be used interchangeably.
+/// Like when looking up keys in a map, where only the hash of the key which
+/// is being looked up is needed.
+///
+/// Floats are rejected, since this uses [RejectFloatPolicy]. See
+/// [hash_value_with] to use another policy.
+///
+/// # Errors
+///
+/// This fails in the same way as [to_key] does. Since the length of a sequence
+/// or a map is hashed before its elements, this also fails with
+/// [Error::InvalidLength] if a [Serialize] implementation reports a length
+/// which doesn't match the number of elements it serializes.
+///
+/// Since keys are not kept around, the path of an error in a map value which
+/// is serialized separately from its key refers to the entry by index, even
+/// if the key is a string. Maps serialized through `serialize_entry`, which
+/// is what serde does for the standard maps, report the same paths as
+/// [to_key].
+///
+/// [to_key]: crate::to_key
+/// [Serialize]: serde::Serialize
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::hash_map::DefaultHasher;
+/// use std::hash::{Hash, Hasher};
+///
+/// use serde_derive::Serialize;
+/// use serde_hashkey::{hash_value, to_key};
+///
+/// #[derive(Serialize)]
+/// struct Author {
+///     name: &'static str,
+///     books: Vec<u32>,
+/// }
+///
+/// let author = Author { name: "Noah", books: vec![1, 2, 3] };
+///
+/// let mut a = DefaultHasher::new();
+/// hash_value(&author, &mut a)?;
+///
+/// let mut b = DefaultHasher::new();
+/// to_key(&author)?.hash(&mut b);
+///
+/// assert_eq!(a.finish(), b.finish());
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+pub fn hash_value<T, H>(value: &T, hasher: &mut H) -> Result<(), Error>
+where
+    T: ?Sized + ser::Serialize,
+    H: Hasher,
+{
+    hash_value_with::<RejectFloatPolicy, T, H>(value, hasher)
+}
+
+/// Feed the hash of the [Key] the given value serializes into to the given
+/// hasher using the given [FloatPolicy], without building the key.
+///
+/// This is guaranteed to feed the hasher with the same data as
+/// `to_key_with::<F, _>(value)?.hash(hasher)`. See [hash_value].
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::hash_map::DefaultHasher;
+/// use std::hash::{Hash, Hasher};
+///
+/// use serde_hashkey::{hash_value_with, to_key_with, TotalOrderFloatPolicy};
+///
+/// let mut a = DefaultHasher::new();
+/// hash_value_with::<TotalOrderFloatPolicy, _, _>(&[1.5f64, 2.5], &mut a)?;
+///
+/// let mut b = DefaultHasher::new();
+/// to_key_with::<TotalOrderFloatPolicy, _>(&[1.5f64, 2.5])?.hash(&mut b);
+///
+/// assert_eq!(a.finish(), b.finish());
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+pub fn hash_value_with<F, T, H>(value: &T, hasher: &mut H) -> Result<(), Error>
+where
+    F: FloatPolicy + Hash,
+    T: ?Sized + ser::Serialize,
+    H: Hasher,
+{
+    value.serialize(HashSerializer::<F, H>::new(hasher))
+}
+
+/// Serializer which feeds the hash of the key a value serializes into to a
+/// hasher.
+struct HashSerializer<'a, F, H> {
+    state: &'a mut H,
+    _marker: PhantomData<F>,
+}
+
+impl<'a, F, H> HashSerializer<'a, F, H>
+where
+    F: FloatPolicy + Hash,
+    H: Hasher,
+{
+    fn new(state: &'a mut H) -> Self {
+        Self {
+            state,
+            _marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn key(self, key: Key<F>) -> Result<(), Error> {
+        key.hash(self.state);
+        Ok(())
+    }
+
+    /// Hash the header of the map with a single entry which holds a variant,
+    /// followed by the name of the variant.
+    fn variant(&mut self, variant: &str) {
+        hash_len(MAP, 1, self.state);
+        hash_str(variant, self.state);
+    }
+}
+
+impl<'a, F, H> ser::Serializer for HashSerializer<'a, F, H>
+where
+    F: FloatPolicy + Hash,
+    H: Hasher,
+{
+    type Ok = ();
+    type Error = Error;
+
+    type SerializeSeq = HashSeq<'a, F, H>;
+    type SerializeTuple = HashSeq<'a, F, H>;
+    type SerializeTupleStruct = HashSeq<'a, F, H>;
+    type SerializeTupleVariant = HashTupleVariant<'a, F, H>;
+    type SerializeMap = HashMap<'a, F, H>;
+    type SerializeStruct = HashMap<'a, F, H>;
+    type SerializeStructVariant = HashStructVariant<'a, F, H>;
+
+    #[inline]
+    fn serialize_bool(self, value: bool) -> Result<(), Error> {
+        self.key(Key::Bool(value))
+    }
+
+    #[inline]
+    fn serialize_i8(self, value: i8) -> Result<(), Error> {
+        self.key(value.into())
+    }
+
+    #[inline]
+    fn serialize_i16(self, value: i16) -> Result<(), Error> {
+        self.key(value.into())
+    }
+
+    #[inline]
+    fn serialize_i32(self, value: i32) -> Result<(), Error> {
+        self.key(value.into())
+    }
+
+    #[inline]
+    fn serialize_i64(self, value: i64) -> Result<(), Error> {
+        self.key(value.into())
+    }
+
+    #[inline]
+    fn serialize_i128(self, value: i128) -> Result<(), Error> {
+        self.key(value.into())
+    }
+
+    #[inline]
+    fn serialize_u8(self, value: u8) -> Result<(), Error> {
+        self.key(value.into())
+    }
+
+    #[inline]
+    fn serialize_u16(self, value: u16) -> Result<(), Error> {
+        self.key(value.into())
+    }
+
+    #[inline]
+    fn serialize_u32(self, value: u32) -> Result<(), Error> {
+        self.key(value.into())
+    }
+
+    #[inline]
+    fn serialize_u64(self, value: u64) -> Result<(), Error> {
+        self.key(value.into())
+    }
+
+    #[inline]
+    fn serialize_u128(self, value: u128) -> Result<(), Error> {
+        self.key(value.into())
+    }
+
+    #[inline]
+    fn serialize_f32(self, value: f32) -> Result<(), Error> {
+        self.key(Key::Float(F::serialize_f32(value)?))
+    }
+
+    #[inline]
+    fn serialize_f64(self, value: f64) -> Result<(), Error> {
+        let value = <F::F64 as FloatRepr<f64>>::serialize(value)?;
+        self.key(Key::Float(Float::F64(value)))
+    }
+
+    #[inline]
+    fn serialize_char(self, value: char) -> Result<(), Error> {
+        self.key(Key::Char(value))
+    }
+
+    #[inline]
+    fn serialize_str(self, value: &str) -> Result<(), Error> {
+        hash_str(value, self.state);
+        Ok(())
+    }
+
+    #[inline]
+    fn serialize_bytes(self, value: &[u8]) -> Result<(), Error> {
+        hash_bytes(value, self.state);
+        Ok(())
+    }
+
+    #[inline]
+    fn serialize_unit(self) -> Result<(), Error> {
+        self.key(Key::Unit)
+    }
+
+    #[inline]
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), Error> {
+        self.serialize_unit()
+    }
+
+    #[inline]
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<(), Error> {
+        self.serialize_str(variant)
+    }
+
+    #[inline]
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        // NB: `half::f16` is serialized as a newtype struct, but is stored as
+        // a float.
+        if float::is_f16(name) {
+            let key = value.serialize(KeySerializer::<F>::new())?;
+            return self.key(float::from_newtype_struct(name, key)?);
+        }
+
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        mut self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        self.variant(variant);
+
+        value
+            .serialize(self)
+            .map_err(|error| error.at(PathSegment::Field(variant.into())))
+    }
+
+    #[inline]
+    fn serialize_none(self) -> Result<(), Error> {
+        self.key(Key::Null)
+    }
+
+    #[inline]
+    fn serialize_some<T>(self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        // NB: A nested `None` is wrapped in a single-element sequence.
+        if option::is_nested_none(value) {
+            hash_len(SEQ, 1, self.state);
+        }
+
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        Ok(HashSeq::new(self.state, len))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        mut self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        self.variant(variant);
+
+        Ok(HashTupleVariant {
+            variant,
+            seq: HashSeq::new(self.state, Some(len)),
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Ok(HashMap::new(self.state, len))
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        mut self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        self.variant(variant);
+
+        Ok(HashStructVariant {
+            variant,
+            map: HashMap::new(self.state, Some(len)),
+        })
+    }
+
+    #[inline]
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+/// Hashes a sequence.
+///
+/// Sequences of integers or floats of a single width are stored in their
+/// compact form, which is hashed differently. So elements are first probed
+/// with [ScalarSerializer] and buffered for as long as they could be part of
+/// a compact sequence. Once an element which can't is encountered, the
+/// buffered elements are hashed and the remaining elements are hashed
+/// directly.
+///
+/// If the length of the sequence is unknown, it has to be hashed after all of
+/// its elements have been seen, so the sequence is built as a [Key] instead.
+enum HashSeq<'a, F, H>
+where
+    F: FloatPolicy,
+{
+    Streaming {
+        state: &'a mut H,
+        len: usize,
+        index: usize,
+        compact: Option<Vec<Key<F>>>,
+    },
+    Buffered {
+        state: &'a mut H,
+        seq: SerializeVec<F>,
+    },
+}
+
+impl<'a, F, H> HashSeq<'a, F, H>
+where
+    F: FloatPolicy + Hash,
+    H: Hasher,
+{
+    fn new(state: &'a mut H, len: Option<usize>) -> Self {
+        match len {
+            Some(len) => HashSeq::Streaming {
+                state,
+                len,
+                index: 0,
+                compact: Some(Vec::new()),
+            },
+            None => HashSeq::Buffered {
+                state,
+                seq: SerializeVec::new(),
+            },
+        }
+    }
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        let (state, len, index, compact) = match self {
+            HashSeq::Streaming {
+                state,
+                len,
+                index,
+                compact,
+            } => (state, *len, index, compact),
+            HashSeq::Buffered { seq, .. } => {
+                return ser::SerializeSeq::serialize_element(seq, value);
+            }
+        };
+
+        let current = *index;
+        *index += 1;
+
+        if let Some(elements) = compact {
+            // NB: Errors are raised by hashing the element below, so that
+            // they get the same path.
+            if let Ok(Some(key)) = value.serialize(ScalarSerializer::<F>::new()) {
+                if is_compact(elements.first(), &key) {
+                    elements.push(key);
+                    return Ok(());
+                }
+            }
+
+            hash_len(SEQ, len, *state);
+
+            for key in elements.drain(..) {
+                key.hash(*state);
+            }
+
+            *compact = None;
+        }
+
+        value
+            .serialize(HashSerializer::<F, H>::new(&mut **state))
+            .map_err(|error| error.at(PathSegment::Index(current)))
+    }
+
+    fn end(self) -> Result<(), Error> {
+        match self {
+            HashSeq::Streaming {
+                state,
+                compact: Some(elements),
+                ..
+            } => {
+                Key::seq(elements).hash(state);
+            }
+            HashSeq::Streaming { len, index, .. } => {
+                if len != index {
+                    return Err(Error::InvalidLength);
+                }
+            }
+            HashSeq::Buffered { state, seq } => {
+                ser::SerializeSeq::end(seq)?.hash(state);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Test if the given key can be stored in the same compact sequence as the
+/// first element, see [Key::seq].
+fn is_compact<F>(first: Option<&Key<F>>, key: &Key<F>) -> bool
+where
+    F: FloatPolicy,
+{
+    match (first, key) {
+        (None, Key::Integer(..) | Key::Float(Float::F32(..) | Float::F64(..))) => true,
+        (Some(Key::Integer(a)), Key::Integer(b)) => mem::discriminant(a) == mem::discriminant(b),
+        (Some(Key::Float(Float::F32(..))), Key::Float(Float::F32(..))) => true,
+        (Some(Key::Float(Float::F64(..))), Key::Float(Float::F64(..))) => true,
+        _ => false,
+    }
+}
+
+impl<F, H> ser::SerializeSeq for HashSeq<'_, F, H>
+where
+    F: FloatPolicy + Hash,
+    H: Hasher,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        HashSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        HashSeq::end(self)
+    }
+}
+
+impl<F, H> ser::SerializeTuple for HashSeq<'_, F, H>
+where
+    F: FloatPolicy + Hash,
+    H: Hasher,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        HashSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        HashSeq::end(self)
+    }
+}
+
+impl<F, H> ser::SerializeTupleStruct for HashSeq<'_, F, H>
+where
+    F: FloatPolicy + Hash,
+    H: Hasher,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        HashSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        HashSeq::end(self)
+    }
+}
+
+/// Hashes the fields of a tuple variant, after the variant itself has been
+/// hashed.
+struct HashTupleVariant<'a, F, H>
+where
+    F: FloatPolicy,
+{
+    variant: &'static str,
+    seq: HashSeq<'a, F, H>,
+}
+
+impl<F, H> ser::SerializeTupleVariant for HashTupleVariant<'_, F, H>
+where
+    F: FloatPolicy + Hash,
+    H: Hasher,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        let variant = self.variant;
+
+        self.seq
+            .serialize_element(value)
+            .map_err(|error| error.at(PathSegment::Field(variant.into())))
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.seq.end()
+    }
+}
+
+/// Hashes a map or a struct.
+///
+/// If the length of the map is unknown, it has to be hashed after all of its
+/// entries have been seen, so the map is built as a [Key] instead.
+enum HashMap<'a, F, H>
+where
+    F: FloatPolicy,
+{
+    Streaming {
+        state: &'a mut H,
+        len: usize,
+        index: usize,
+        has_key: bool,
+    },
+    Buffered {
+        state: &'a mut H,
+        map: SerializeMap<F>,
+    },
+}
+
+impl<'a, F, H> HashMap<'a, F, H>
+where
+    F: FloatPolicy + Hash,
+    H: Hasher,
+{
+    fn new(state: &'a mut H, len: Option<usize>) -> Self {
+        match len {
+            Some(len) => {
+                hash_len(MAP, len, state);
+
+                HashMap::Streaming {
+                    state,
+                    len,
+                    index: 0,
+                    has_key: false,
+                }
+            }
+            None => HashMap::Buffered {
+                state,
+                map: SerializeMap::new(),
+            },
+        }
+    }
+
+    fn serialize_value_at<T>(
+        &mut self,
+        value: &T,
+        segment: impl FnOnce(usize) -> PathSegment,
+    ) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        match self {
+            HashMap::Streaming {
+                state,
+                index,
+                has_key,
+                ..
+            } => {
+                if !mem::take(has_key) {
+                    return Err(Error::MissingValue);
+                }
+
+                let current = *index;
+                *index += 1;
+
+                value
+                    .serialize(HashSerializer::<F, H>::new(&mut **state))
+                    .map_err(|error| error.at(segment(current)))
+            }
+            HashMap::Buffered { map, .. } => ser::SerializeMap::serialize_value(map, value),
+        }
+    }
+
+    fn end(self) -> Result<(), Error> {
+        match self {
+            HashMap::Streaming { len, index, .. } => {
+                if len != index {
+                    return Err(Error::InvalidLength);
+                }
+            }
+            HashMap::Buffered { state, map } => {
+                ser::SerializeMap::end(map)?.hash(state);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<F, H> ser::SerializeMap for HashMap<'_, F, H>
+where
+    F: FloatPolicy + Hash,
+    H: Hasher,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        match self {
+            HashMap::Streaming {
+                state,
+                index,
+                has_key,
+                ..
+            } => {
+                let current = *index;
+
+                key.serialize(HashSerializer::<F, H>::new(&mut **state))
+                    .map_err(|error| error.at(PathSegment::Entry(current)))?;
+
+                *has_key = true;
+                Ok(())
+            }
+            HashMap::Buffered { map, .. } => ser::SerializeMap::serialize_key(map, key),
+        }
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        self.serialize_value_at(value, PathSegment::Entry)
+    }
+
+    fn serialize_entry<K, V>(&mut self, key: &K, value: &V) -> Result<(), Error>
+    where
+        K: ?Sized + ser::Serialize,
+        V: ?Sized + ser::Serialize,
+    {
+        self.serialize_key(key)?;
+
+        // NB: The key is only built if it's needed for the path of an error.
+        self.serialize_value_at(value, |index| match to_key_with_policy::<_, F>(key) {
+            Ok(Key::String(field)) => PathSegment::Field((*field).into()),
+            _ => PathSegment::Entry(index),
+        })
+    }
+
+    fn end(self) -> Result<(), Error> {
+        HashMap::end(self)
+    }
+}
+
+impl<F, H> ser::SerializeStruct for HashMap<'_, F, H>
+where
+    F: FloatPolicy + Hash,
+    H: Hasher,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        ser::SerializeMap::serialize_key(self, key)?;
+        self.serialize_value_at(value, |_| PathSegment::Field(key.into()))
+    }
+
+    fn end(self) -> Result<(), Error> {
+        HashMap::end(self)
+    }
+}
+
+/// Hashes the fields of a struct variant, after the variant itself has been
+/// hashed.
+struct HashStructVariant<'a, F, H>
+where
+    F: FloatPolicy,
+{
+    variant: &'static str,
+    map: HashMap<'a, F, H>,
+}
+
+impl<F, H> ser::SerializeStructVariant for HashStructVariant<'_, F, H>
+where
+    F: FloatPolicy + Hash,
+    H: Hasher,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        let variant = self.variant;
+
+        ser::SerializeStruct::serialize_field(&mut self.map, key, value)
+            .map_err(|error| error.at(PathSegment::Field(variant.into())))
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.map.end()
+    }
+}
+
+/// Serializer which produces the key of integers and floats, which are the
+/// values which can be stored in compact sequences, and `None` for anything
+/// else.
+///
+/// Values which are not scalars are rejected with an error as soon as they
+/// start serializing, so probing them is cheap.
+struct ScalarSerializer<F> {
+    _marker: PhantomData<F>,
+}
+
+impl<F> ScalarSerializer<F> {
+    fn new() -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F> ser::Serializer for ScalarSerializer<F>
+where
+    F: FloatPolicy,
+{
+    type Ok = Option<Key<F>>;
+    type Error = Error;
+
+    type SerializeSeq = ser::Impossible<Option<Key<F>>, Error>;
+    type SerializeTuple = ser::Impossible<Option<Key<F>>, Error>;
+    type SerializeTupleStruct = ser::Impossible<Option<Key<F>>, Error>;
+    type SerializeTupleVariant = ser::Impossible<Option<Key<F>>, Error>;
+    type SerializeMap = ser::Impossible<Option<Key<F>>, Error>;
+    type SerializeStruct = ser::Impossible<Option<Key<F>>, Error>;
+    type SerializeStructVariant = ser::Impossible<Option<Key<F>>, Error>;
+
+    fn serialize_bool(self, _: bool) -> Result<Self::Ok, Error> {
+        Ok(None)
+    }
+
+    fn serialize_i8(self, value: i8) -> Result<Self::Ok, Error> {
+        Ok(Some(value.into()))
+    }
+
+    fn serialize_i16(self, value: i16) -> Result<Self::Ok, Error> {
+        Ok(Some(value.into()))
+    }
+
+    fn serialize_i32(self, value: i32) -> Result<Self::Ok, Error> {
+        Ok(Some(value.into()))
+    }
+
+    fn serialize_i64(self, value: i64) -> Result<Self::Ok, Error> {
+        Ok(Some(value.into()))
+    }
+
+    fn serialize_i128(self, value: i128) -> Result<Self::Ok, Error> {
+        Ok(Some(value.into()))
+    }
+
+    fn serialize_u8(self, value: u8) -> Result<Self::Ok, Error> {
+        Ok(Some(value.into()))
+    }
+
+    fn serialize_u16(self, value: u16) -> Result<Self::Ok, Error> {
+        Ok(Some(value.into()))
+    }
+
+    fn serialize_u32(self, value: u32) -> Result<Self::Ok, Error> {
+        Ok(Some(value.into()))
+    }
+
+    fn serialize_u64(self, value: u64) -> Result<Self::Ok, Error> {
+        Ok(Some(value.into()))
+    }
+
+    fn serialize_u128(self, value: u128) -> Result<Self::Ok, Error> {
+        Ok(Some(value.into()))
+    }
+
+    fn serialize_f32(self, value: f32) -> Result<Self::Ok, Error> {
+        Ok(Some(Key::Float(F::serialize_f32(value)?)))
+    }
+
+    fn serialize_f64(self, value: f64) -> Result<Self::Ok, Error> {
+        let value = <F::F64 as FloatRepr<f64>>::serialize(value)?;
+        Ok(Some(Key::Float(Float::F64(value))))
+    }
+
+    fn serialize_char(self, _: char) -> Result<Self::Ok, Error> {
+        Ok(None)
+    }
+
+    fn serialize_str(self, _: &str) -> Result<Self::Ok, Error> {
+        Ok(None)
+    }
+
+    fn serialize_bytes(self, _: &[u8]) -> Result<Self::Ok, Error> {
+        Ok(None)
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Error> {
+        Ok(None)
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Error> {
+        Ok(None)
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<Self::Ok, Error> {
+        Ok(None)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+    ) -> Result<Self::Ok, Error> {
+        Ok(None)
+    }
+
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<Self::Ok, Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        // NB: `half::f16` is stored as a float which can't be part of a
+        // compact sequence.
+        if float::is_f16(name) {
+            return Ok(None);
+        }
+
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> Result<Self::Ok, Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        Ok(None)
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        Err(Error::Unexpected("scalar"))
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, Error> {
+        Err(Error::Unexpected("scalar"))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        Err(Error::Unexpected("scalar"))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Err(Error::Unexpected("scalar"))
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Err(Error::Unexpected("scalar"))
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<Self::SerializeStruct, Error> {
+        Err(Error::Unexpected("scalar"))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Err(Error::Unexpected("scalar"))
+    }
+
+    #[inline]
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
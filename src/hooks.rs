@@ -0,0 +1,138 @@
+//! Hooks into how [KeySerializer] builds keys, which are used to filter fields
+//! and to capture partial keys without duplicating the serializer.
+//!
+//! See [Hooks] for how to use them.
+//!
+//! [KeySerializer]: crate::KeySerializer
+
+use crate::float::FloatPolicy;
+use crate::key::Key;
+use crate::path::PathSegment;
+
+/// A segment of the path to the value being serialized.
+#[derive(Debug, Clone, Copy)]
+pub enum Segment<'a> {
+    /// A field of a struct, the content of a variant, or the value of a map
+    /// entry with a string key.
+    Field(&'a str),
+    /// An element of a sequence or a tuple.
+    Index(usize),
+    /// The key of the map entry at the given index.
+    Key(usize),
+    /// The value of the map entry at the given index, if its key is not a
+    /// string.
+    Entry(usize),
+}
+
+impl From<Segment<'_>> for PathSegment {
+    fn from(segment: Segment<'_>) -> Self {
+        match segment {
+            Segment::Field(name) => PathSegment::Field(name.into()),
+            Segment::Index(index) => PathSegment::Index(index),
+            Segment::Key(index) | Segment::Entry(index) => PathSegment::Entry(index),
+        }
+    }
+}
+
+/// Hooks called by [KeySerializer] as it enters and leaves values.
+///
+/// Hooks are used with [KeySerializer::with_hooks]. This is how
+/// [to_key_filtered] leaves out fields without serializing them.
+///
+/// [KeySerializer]: crate::KeySerializer
+/// [KeySerializer::with_hooks]: crate::KeySerializer::with_hooks
+/// [to_key_filtered]: crate::to_key_filtered
+///
+/// # Examples
+///
+/// ```
+/// use serde_derive::Serialize;
+/// use serde_hashkey::{key, Hooks, Key, KeyConfig, KeySerializer, RejectFloatPolicy, Segment};
+///
+/// /// Leave out fields which start with an underscore.
+/// #[derive(Clone, Copy)]
+/// struct SkipPrivate;
+///
+/// impl Hooks<RejectFloatPolicy> for SkipPrivate {
+///     type Reborrow<'a> = SkipPrivate;
+///
+///     fn reborrow(&mut self) -> SkipPrivate {
+///         *self
+///     }
+///
+///     fn enter(&mut self, segment: Segment<'_>) -> bool {
+///         !matches!(segment, Segment::Field(name) if name.starts_with('_'))
+///     }
+///
+///     fn leave(&mut self, _: Segment<'_>) {}
+/// }
+///
+/// #[derive(Serialize)]
+/// struct Request {
+///     path: &'static str,
+///     _trace: u32,
+/// }
+///
+/// let serializer = KeySerializer::with_hooks(KeyConfig::new(), SkipPrivate);
+/// let request = Request { path: "/", _trace: 42 };
+/// let key: Key = serde::Serialize::serialize(&request, serializer)?;
+/// assert_eq!(key, key!({ "path": "/" }));
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+pub trait Hooks<F>
+where
+    F: FloatPolicy,
+{
+    /// The hooks passed on to the serializers of nested values.
+    type Reborrow<'a>: Hooks<F>
+    where
+        Self: 'a;
+
+    /// Borrow the hooks for the serializer of a nested value.
+    fn reborrow(&mut self) -> Self::Reborrow<'_>;
+
+    /// Enter the value at the given segment, returning `false` if it should be
+    /// left out of the key. A value which is left out is not entered.
+    fn enter(&mut self, segment: Segment<'_>) -> bool;
+
+    /// Leave a value once it has been built. Values which fail to serialize are
+    /// never left.
+    fn leave(&mut self, segment: Segment<'_>);
+
+    /// Record the partial key of a value which failed to serialize, given the
+    /// partial key of the nested value which failed, if any.
+    #[inline]
+    fn capture<B>(&mut self, build: B)
+    where
+        B: FnOnce(Option<Key<F>>) -> Option<Key<F>>,
+    {
+        let _ = build;
+    }
+}
+
+/// The hooks used by [KeySerializer] unless otherwise specified, which don't
+/// do anything.
+///
+/// [KeySerializer]: crate::KeySerializer
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoHooks;
+
+impl<F> Hooks<F> for NoHooks
+where
+    F: FloatPolicy,
+{
+    type Reborrow<'a> = NoHooks;
+
+    #[inline]
+    fn reborrow(&mut self) -> Self::Reborrow<'_> {
+        NoHooks
+    }
+
+    #[inline]
+    fn enter(&mut self, _: Segment<'_>) -> bool {
+        true
+    }
+
+    #[inline]
+    fn leave(&mut self, _: Segment<'_>) {}
+}
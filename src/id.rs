@@ -0,0 +1,333 @@
+//! Filesystem-safe identifiers derived from keys.
+
+use crate::float::FloatPolicy;
+use crate::key::{Integer, Key};
+
+/// The maximum length of a prefix derived from a field.
+const MAX_PREFIX: usize = 32;
+
+/// The alphabet used to encode the digest of an identifier.
+///
+/// Every alphabet only uses characters which are safe to use both in URLs
+/// and in file names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum IdAlphabet {
+    /// Lowercase base32 as defined in [RFC 4648] without padding, which is
+    /// safe to use on case-insensitive filesystems. Encodes 5 bits per
+    /// character.
+    ///
+    /// [RFC 4648]: https://datatracker.ietf.org/doc/html/rfc4648#section-6
+    Base32,
+    /// URL-safe base64 as defined in [RFC 4648] without padding. Encodes 6
+    /// bits per character.
+    ///
+    /// [RFC 4648]: https://datatracker.ietf.org/doc/html/rfc4648#section-5
+    Base64Url,
+    /// Lowercase hexadecimal. Encodes 4 bits per character.
+    Hex,
+}
+
+impl IdAlphabet {
+    fn bits(self) -> usize {
+        match self {
+            IdAlphabet::Base32 => 5,
+            IdAlphabet::Base64Url => 6,
+            IdAlphabet::Hex => 4,
+        }
+    }
+
+    fn chars(self) -> &'static [u8] {
+        match self {
+            IdAlphabet::Base32 => b"abcdefghijklmnopqrstuvwxyz234567",
+            IdAlphabet::Base64Url => {
+                b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_"
+            }
+            IdAlphabet::Hex => b"0123456789abcdef",
+        }
+    }
+
+    /// The number of characters it takes to encode a complete digest.
+    fn max_len(self) -> usize {
+        (256 + self.bits() - 1) / self.bits()
+    }
+}
+
+/// Configuration used by [Key::to_id_with].
+///
+/// # Examples
+///
+/// ```
+/// use serde_derive::Serialize;
+/// use serde_hashkey::{to_key, IdAlphabet, IdConfig};
+///
+/// #[derive(Serialize)]
+/// struct Artifact {
+///     crate_name: &'static str,
+///     version: &'static str,
+/// }
+///
+/// let config = IdConfig::new()
+///     .alphabet(IdAlphabet::Hex)
+///     .len(16)
+///     .prefix_field("crate_name");
+///
+/// let key = to_key(&Artifact { crate_name: "serde", version: "1.0.0" })?;
+/// let id = key.to_id_with(&config);
+/// assert!(id.starts_with("serde-"));
+/// assert_eq!(id.len(), "serde-".len() + 16);
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IdConfig<'a> {
+    alphabet: IdAlphabet,
+    len: usize,
+    prefix_field: Option<&'a str>,
+}
+
+impl<'a> IdConfig<'a> {
+    /// Construct the default configuration, which produces 26 characters of
+    /// [IdAlphabet::Base32] without a prefix. This is the same as what
+    /// [Key::to_id] produces.
+    pub const fn new() -> Self {
+        Self {
+            alphabet: IdAlphabet::Base32,
+            len: 26,
+            prefix_field: None,
+        }
+    }
+
+    /// Configure the alphabet used to encode the digest.
+    pub const fn alphabet(self, alphabet: IdAlphabet) -> Self {
+        Self { alphabet, ..self }
+    }
+
+    /// Configure the number of characters of the digest to include.
+    ///
+    /// This is capped to the number of characters it takes to encode the
+    /// full 256-bit digest in the configured alphabet, and is at least 1.
+    pub const fn len(self, len: usize) -> Self {
+        Self { len, ..self }
+    }
+
+    /// Configure a top-level map field whose value is used as a
+    /// human-readable prefix, separated from the digest with a `-`.
+    ///
+    /// The prefix is only included if the key is a map containing the field
+    /// and its value is a string, boolean or integer. Any character other
+    /// than an ASCII letter, digit, `-` or `_` is replaced with `_`, ASCII
+    /// letters are lowercased, and the prefix is truncated to at most 32
+    /// characters.
+    pub const fn prefix_field(self, field: &'a str) -> Self {
+        Self {
+            prefix_field: Some(field),
+            ..self
+        }
+    }
+}
+
+impl Default for IdConfig<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F> Key<F>
+where
+    F: FloatPolicy + Ord + Clone,
+{
+    /// Produce a short identifier for this key which is safe to use in URLs
+    /// and as a file name, using the default [IdConfig].
+    ///
+    /// See [to_id_with] for details.
+    ///
+    /// [to_id_with]: Key::to_id_with
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::to_key;
+    ///
+    /// let key = to_key(&("hello", 42u8))?;
+    /// assert_eq!(key.to_id(), "fkvjprcmd2brrfhzjezymourxs");
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn to_id(&self) -> String {
+        self.to_id_with(&IdConfig::new())
+    }
+
+    /// Produce a short identifier for this key which is safe to use in URLs
+    /// and as a file name.
+    ///
+    /// The identifier is the SHA-256 digest of the stable binary encoding
+    /// produced by [to_bytes], encoded using the configured [IdAlphabet] and
+    /// truncated to the configured length. Keys which are equal once
+    /// [normalized] have identical encodings, so they have the same
+    /// identifier without having to be normalized first.
+    ///
+    /// Identifiers are guaranteed to be stable across releases.
+    ///
+    /// [to_bytes]: Key::to_bytes
+    /// [normalized]: Key::normalize
+    pub fn to_id_with(&self, config: &IdConfig<'_>) -> String {
+        let digest = sha256(&self.to_bytes());
+
+        let mut out = String::new();
+
+        if let Some(prefix) = config.prefix_field.and_then(|field| self.prefix(field)) {
+            out.push_str(&prefix);
+            out.push('-');
+        }
+
+        let alphabet = config.alphabet;
+        let chars = alphabet.chars();
+        let bits = alphabet.bits();
+        let len = config.len.max(1).min(alphabet.max_len());
+
+        for n in 0..len {
+            let mut index = 0;
+
+            for bit in n * bits..(n + 1) * bits {
+                // NB: bits past the end of the digest are zero, like padding.
+                let set = bit < 256 && digest[bit / 8] & (0x80 >> (bit % 8)) != 0;
+                index = index << 1 | usize::from(set);
+            }
+
+            out.push(chars[index] as char);
+        }
+
+        out
+    }
+
+    /// Derive a sanitized prefix from the given top-level field.
+    fn prefix(&self, field: &str) -> Option<String> {
+        let map = match self {
+            Key::Map(map) => map,
+            _ => return None,
+        };
+
+        let value = map.iter().find_map(|(key, value)| match key {
+            Key::String(name) if **name == *field => Some(value),
+            _ => None,
+        })?;
+
+        let string = match value {
+            Key::String(string) => string.to_string(),
+            Key::Bool(b) => b.to_string(),
+            Key::Integer(integer) => match *integer {
+                Integer::I8(v) => v.to_string(),
+                Integer::I16(v) => v.to_string(),
+                Integer::I32(v) => v.to_string(),
+                Integer::I64(v) => v.to_string(),
+                Integer::I128(v) => v.to_string(),
+                Integer::U8(v) => v.to_string(),
+                Integer::U16(v) => v.to_string(),
+                Integer::U32(v) => v.to_string(),
+                Integer::U64(v) => v.to_string(),
+                Integer::U128(v) => v.to_string(),
+            },
+            _ => return None,
+        };
+
+        let prefix = string
+            .chars()
+            .take(MAX_PREFIX)
+            .map(|c| match c {
+                'a'..='z' | '0'..='9' | '-' | '_' => c,
+                'A'..='Z' => c.to_ascii_lowercase(),
+                _ => '_',
+            })
+            .collect::<String>();
+
+        if prefix.is_empty() {
+            return None;
+        }
+
+        Some(prefix)
+    }
+}
+
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// Compute the SHA-256 digest of the given bytes.
+///
+/// This is implemented here to avoid taking on a dependency for the small
+/// amount of data we need to hash.
+fn sha256(bytes: &[u8]) -> [u8; 32] {
+    let mut state: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+        0x5be0cd19,
+    ];
+
+    let mut message = bytes.to_vec();
+    let bit_len = (bytes.len() as u64).wrapping_mul(8);
+    message.push(0x80);
+
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+
+    message.extend_from_slice(&bit_len.to_be_bytes());
+
+    for block in message.chunks_exact(64) {
+        let mut w = [0u32; 64];
+
+        for (w, word) in w.iter_mut().zip(block.chunks_exact(4)) {
+            *w = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = h
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(K[i])
+                .wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+
+        for (s, v) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *s = s.wrapping_add(v);
+        }
+    }
+
+    let mut out = [0; 32];
+
+    for (out, s) in out.chunks_exact_mut(4).zip(state.iter()) {
+        out.copy_from_slice(&s.to_be_bytes());
+    }
+
+    out
+}
@@ -0,0 +1,98 @@
+//! Indexing into keys, like `key["field"][0]`.
+
+use std::marker::PhantomData;
+use std::ops;
+
+use crate::float::FloatPolicy;
+use crate::key::Key;
+
+/// Holds the static [Key::Unit] which is returned when indexing misses.
+struct Missing<F>(PhantomData<F>);
+
+impl<F> Missing<F>
+where
+    F: 'static + FloatPolicy,
+{
+    const UNIT: &'static Key<F> = &Key::Unit;
+}
+
+/// Index into a map by the string key of an entry.
+///
+/// Returns [Key::Unit] if this key is not a map, or if it has no entry whose
+/// key is a [Key::String] equal to `index`. Use [Key::get] to distinguish a
+/// missing entry from one whose value is [Key::Unit].
+///
+/// # Examples
+///
+/// ```
+/// use serde_derive::Serialize;
+/// use serde_hashkey::{to_key, Key};
+///
+/// #[derive(Serialize)]
+/// struct Author {
+///     name: &'static str,
+///     tags: Vec<&'static str>,
+/// }
+///
+/// let key = to_key(&Author { name: "Noah", tags: vec!["birds"] })?;
+///
+/// assert_eq!(key["name"], Key::String("Noah".into()));
+/// assert_eq!(key["tags"][0], Key::String("birds".into()));
+/// assert_eq!(key["missing"]["field"][1], Key::Unit);
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+impl<F> ops::Index<&str> for Key<F>
+where
+    F: 'static + FloatPolicy,
+{
+    type Output = Key<F>;
+
+    fn index(&self, index: &str) -> &Self::Output {
+        let map = match self {
+            Key::Map(map) => map,
+            _ => return Missing::<F>::UNIT,
+        };
+
+        for (key, value) in map.iter() {
+            if let Key::String(key) = key {
+                if **key == *index {
+                    return value;
+                }
+            }
+        }
+
+        Missing::<F>::UNIT
+    }
+}
+
+/// Index into a sequence by the position of an element.
+///
+/// Returns [Key::Unit] if this key is not a [Key::Seq], or if `index` is out
+/// of bounds. The compact [Key::IntegerSeq] and [Key::FloatSeq] don't store
+/// their elements as keys, so they can't be indexed into and also return
+/// [Key::Unit].
+///
+/// # Examples
+///
+/// ```
+/// use serde_hashkey::{to_key, Key};
+///
+/// let key = to_key(&vec!["a", "b"])?;
+///
+/// assert_eq!(key[1], Key::String("b".into()));
+/// assert_eq!(key[2], Key::Unit);
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+impl<F> ops::Index<usize> for Key<F>
+where
+    F: 'static + FloatPolicy,
+{
+    type Output = Key<F>;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        match self {
+            Key::Seq(seq) => seq.get(index).unwrap_or(Missing::<F>::UNIT),
+            _ => Missing::<F>::UNIT,
+        }
+    }
+}
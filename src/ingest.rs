@@ -0,0 +1,447 @@
+//! Building keys directly out of a [Deserializer].
+//!
+//! [Deserializer]: serde::Deserializer
+
+use std::cmp;
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem;
+
+use serde::de;
+
+use crate::error::Error;
+use crate::float::{FloatPolicy, FloatRepr, RejectFloatPolicy};
+use crate::key::{Float, Key};
+use crate::path::{KeyPath, PathSegment};
+
+/// Build a [Key] out of the document read by the given [Deserializer].
+///
+/// Floats are rejected, since this uses [RejectFloatPolicy]. This is a thin
+/// wrapper around [from_deserializer_with], which can be used with other
+/// policies.
+///
+/// [Deserializer]: serde::Deserializer
+///
+/// # Errors
+///
+/// Floats rejected by the float policy are reported through
+/// [de::Error::custom], with a message which includes the rejected value and
+/// the path to it, like `unsupported float f64 = 4.5 at books[0].rating`.
+///
+/// # Examples
+///
+/// ```
+/// use serde_derive::Serialize;
+/// use serde_hashkey::{from_deserializer, to_key};
+///
+/// #[derive(Serialize)]
+/// struct Book {
+///     title: &'static str,
+///     pages: u64,
+/// }
+///
+/// let mut json = serde_json::Deserializer::from_str(r#"{"title": "Birds of a feather", "pages": 42}"#);
+/// let key = from_deserializer(&mut json)?;
+/// assert_eq!(key, to_key(&Book { title: "Birds of a feather", pages: 42 })?);
+///
+/// let mut json = serde_json::Deserializer::from_str(r#"{"books": [{"rating": 4.5}]}"#);
+/// let error = from_deserializer(&mut json).unwrap_err();
+/// assert_eq!(
+///     error.to_string(),
+///     "unsupported float f64 = 4.5 at books[0].rating at line 1 column 25"
+/// );
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub fn from_deserializer<'de, D>(deserializer: D) -> Result<Key<RejectFloatPolicy>, D::Error>
+where
+    D: de::Deserializer<'de>,
+{
+    from_deserializer_with::<RejectFloatPolicy, D>(deserializer)
+}
+
+/// Build a [Key] out of the document read by the given [Deserializer] using
+/// the given [FloatPolicy].
+///
+/// Since the structure of the document is not known up front, this reads it
+/// using [`deserialize_any`], so the format has to be self-describing. This is
+/// also what the [Deserialize] implementation of [Key] uses.
+///
+/// [Deserializer]: serde::Deserializer
+/// [Deserialize]: serde::Deserialize
+/// [`deserialize_any`]: serde::Deserializer::deserialize_any
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "ordered-float")] {
+/// use serde_hashkey::{from_deserializer_with, Key, OrderedFloatPolicy};
+///
+/// let mut json = serde_json::Deserializer::from_str("[1.5, 2]");
+/// let key = from_deserializer_with::<OrderedFloatPolicy, _>(&mut json)?;
+/// assert_eq!(key, Key::Seq(vec![Key::from_f64(1.5)?, Key::from(2u64)].into()));
+/// # }
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub fn from_deserializer_with<'de, F, D>(deserializer: D) -> Result<Key<F>, D::Error>
+where
+    F: FloatPolicy,
+    D: de::Deserializer<'de>,
+{
+    de::DeserializeSeed::deserialize(KeySeed::<F>::new(None), deserializer)
+}
+
+/// Size hints are provided by the data being deserialized and can't be
+/// trusted, so cap how much we preallocate based on them in the same way serde
+/// does for its own collections.
+fn cautious<T>(hint: Option<usize>) -> usize {
+    const MAX_PREALLOC_BYTES: usize = 1024 * 1024;
+
+    match mem::size_of::<T>() {
+        0 => 0,
+        size => cmp::min(hint.unwrap_or(0), MAX_PREALLOC_BYTES / size),
+    }
+}
+
+/// The position of the value being deserialized, relative to the value it's
+/// contained in.
+///
+/// These are chained through the stack, so that the path to a rejected float
+/// is only built if there is one.
+struct Parent<'a, F>
+where
+    F: FloatPolicy,
+{
+    parent: Option<&'a Parent<'a, F>>,
+    segment: Segment<'a, F>,
+}
+
+enum Segment<'a, F>
+where
+    F: FloatPolicy,
+{
+    /// The element at the given index in a sequence.
+    Index(usize),
+    /// The key of the map entry at the given position.
+    Key(usize),
+    /// The value of the map entry at the given position.
+    Value(usize, &'a Key<F>),
+}
+
+impl<F> Parent<'_, F>
+where
+    F: FloatPolicy,
+{
+    fn path(&self) -> KeyPath {
+        let mut segments = Vec::new();
+        let mut current = Some(self);
+
+        while let Some(parent) = current {
+            segments.push(match parent.segment {
+                Segment::Index(index) => PathSegment::Index(index),
+                Segment::Key(index) => PathSegment::Entry(index),
+                Segment::Value(_, Key::String(field)) => PathSegment::Field((**field).into()),
+                Segment::Value(index, _) => PathSegment::Entry(index),
+            });
+
+            current = parent.parent;
+        }
+
+        segments.reverse();
+        KeyPath::from(segments)
+    }
+}
+
+/// Deserializes a single value of a document into a key.
+struct KeySeed<'a, F>
+where
+    F: FloatPolicy,
+{
+    parent: Option<&'a Parent<'a, F>>,
+    _marker: PhantomData<F>,
+}
+
+impl<'a, F> KeySeed<'a, F>
+where
+    F: FloatPolicy,
+{
+    fn new(parent: Option<&'a Parent<'a, F>>) -> Self {
+        Self {
+            parent,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Convert an error raised by the float policy.
+    fn float_error<E>(&self, error: Error) -> E
+    where
+        E: de::Error,
+    {
+        match self.parent {
+            Some(parent) => E::custom(Error::FloatRejected {
+                path: parent.path(),
+                error: Box::new(error),
+            }),
+            None => E::custom(error),
+        }
+    }
+}
+
+impl<'de, F> de::DeserializeSeed<'de> for KeySeed<'_, F>
+where
+    F: FloatPolicy,
+{
+    type Value = Key<F>;
+
+    #[inline]
+    fn deserialize<D>(self, deserializer: D) -> Result<Key<F>, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de, F> de::Visitor<'de> for KeySeed<'_, F>
+where
+    F: FloatPolicy,
+{
+    type Value = Key<F>;
+
+    fn expecting(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str("any valid key")
+    }
+
+    #[inline]
+    fn visit_char<E>(self, c: char) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Key::Char(c))
+    }
+
+    #[inline]
+    fn visit_str<E>(self, s: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Key::String(s.into()))
+    }
+
+    #[inline]
+    fn visit_string<E>(self, s: String) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Key::String(s.into()))
+    }
+
+    #[inline]
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Key::Bytes(v.into()))
+    }
+
+    #[inline]
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Key::Bytes(v.into()))
+    }
+
+    #[inline]
+    fn visit_i8<E>(self, v: i8) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(v.into())
+    }
+
+    #[inline]
+    fn visit_i16<E>(self, v: i16) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(v.into())
+    }
+
+    #[inline]
+    fn visit_i32<E>(self, v: i32) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(v.into())
+    }
+
+    #[inline]
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(v.into())
+    }
+
+    #[inline]
+    fn visit_i128<E>(self, v: i128) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(v.into())
+    }
+
+    #[inline]
+    fn visit_u8<E>(self, v: u8) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(v.into())
+    }
+
+    #[inline]
+    fn visit_u16<E>(self, v: u16) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(v.into())
+    }
+
+    #[inline]
+    fn visit_u32<E>(self, v: u32) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(v.into())
+    }
+
+    #[inline]
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(v.into())
+    }
+
+    #[inline]
+    fn visit_u128<E>(self, v: u128) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(v.into())
+    }
+
+    #[inline]
+    fn visit_f32<E>(self, v: f32) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        match F::serialize_f32(v) {
+            Ok(float) => Ok(Key::Float(float)),
+            Err(error) => Err(self.float_error(error)),
+        }
+    }
+
+    #[inline]
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        match <F::F64 as FloatRepr<f64>>::serialize(v) {
+            Ok(float) => Ok(Key::Float(Float::F64(float))),
+            Err(error) => Err(self.float_error(error)),
+        }
+    }
+
+    #[inline]
+    fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Key::Bool(v))
+    }
+
+    #[inline]
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Key::Null)
+    }
+
+    #[inline]
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        de::DeserializeSeed::deserialize(self, deserializer).map(Key::some)
+    }
+
+    #[inline]
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        de::DeserializeSeed::deserialize(self, deserializer)
+    }
+
+    #[inline]
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Key::Unit)
+    }
+
+    fn visit_seq<V>(self, mut visitor: V) -> Result<Self::Value, V::Error>
+    where
+        V: de::SeqAccess<'de>,
+    {
+        let mut vec = Vec::with_capacity(cautious::<Key<F>>(visitor.size_hint()));
+
+        loop {
+            let parent = Parent {
+                parent: self.parent,
+                segment: Segment::Index(vec.len()),
+            };
+
+            match visitor.next_element_seed(KeySeed::new(Some(&parent)))? {
+                Some(element) => vec.push(element),
+                None => break,
+            }
+        }
+
+        Ok(Key::seq(vec))
+    }
+
+    fn visit_map<V>(self, mut visitor: V) -> Result<Self::Value, V::Error>
+    where
+        V: de::MapAccess<'de>,
+    {
+        let mut map = Vec::with_capacity(cautious::<(Key<F>, Key<F>)>(visitor.size_hint()));
+
+        loop {
+            let index = map.len();
+
+            let parent = Parent {
+                parent: self.parent,
+                segment: Segment::Key(index),
+            };
+
+            let key = match visitor.next_key_seed(KeySeed::new(Some(&parent)))? {
+                Some(key) => key,
+                None => break,
+            };
+
+            let parent = Parent {
+                parent: self.parent,
+                segment: Segment::Value(index, &key),
+            };
+
+            let value = visitor.next_value_seed(KeySeed::new(Some(&parent)))?;
+            map.push((key, value));
+        }
+
+        Ok(Key::Map(map.into()))
+    }
+}
@@ -0,0 +1,29 @@
+//! Interning of the field and variant names used when serializing.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+thread_local! {
+    /// Names which have been interned on the current thread.
+    ///
+    /// Names are provided by serde as `&'static str`, so this is bounded by
+    /// the number of distinct names of the types being serialized.
+    static NAMES: RefCell<HashMap<&'static str, Arc<str>>> = RefCell::new(HashMap::new());
+}
+
+/// Get a shared string for the given name, which is only allocated the first
+/// time the name is seen on the current thread.
+pub(crate) fn intern(name: &'static str) -> Arc<str> {
+    // NB: The cache is not available while the thread is being torn down, in
+    // which case we fall back to allocating.
+    NAMES
+        .try_with(|names| {
+            names
+                .borrow_mut()
+                .entry(name)
+                .or_insert_with(|| Arc::from(name))
+                .clone()
+        })
+        .unwrap_or_else(|_| Arc::from(name))
+}
@@ -0,0 +1,271 @@
+//! Iteration over the values in a key.
+
+use std::iter::FusedIterator;
+use std::slice;
+use std::vec;
+
+use crate::float::FloatPolicy;
+use crate::key::Key;
+
+impl<F> Key<F>
+where
+    F: FloatPolicy,
+{
+    /// Iterate over every value in this key in depth-first order.
+    ///
+    /// This key is yielded first, followed by the elements of a [Key::Seq]
+    /// and the key and value of each entry in a [Key::Map], each of which is
+    /// followed by everything it contains in turn. Compact sequences,
+    /// [Key::IntegerSeq] and [Key::FloatSeq], don't store their elements as
+    /// keys and are yielded as a single value.
+    ///
+    /// Traversal does not use recursion, so it is safe to use on deeply nested
+    /// keys. It only allocates to keep track of the containers it is
+    /// currently inside of.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::{to_key, Key};
+    ///
+    /// let key = to_key(&("a", vec![("b", true)]))?;
+    ///
+    /// let values = key.iter().collect::<Vec<_>>();
+    ///
+    /// assert_eq!(values, vec![
+    ///     &key,
+    ///     &Key::String("a".into()),
+    ///     &to_key(&vec![("b", true)])?,
+    ///     &to_key(&("b", true))?,
+    ///     &Key::String("b".into()),
+    ///     &Key::Bool(true),
+    /// ]);
+    ///
+    /// let key: Key = Key::Seq(vec![Key::String("blob".into()), Key::from(vec![0u8; 2048])].into());
+    ///
+    /// assert!(key.iter().any(|value| match value {
+    ///     Key::Bytes(bytes) => bytes.len() > 1024,
+    ///     _ => false,
+    /// }));
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn iter(&self) -> Iter<'_, F> {
+        Iter {
+            next: Some(self),
+            stack: Vec::new(),
+        }
+    }
+
+    /// Iterate over the elements of a [Key::Seq], or `None` if this is any
+    /// other kind of key.
+    ///
+    /// The compact [Key::IntegerSeq] and [Key::FloatSeq] don't store their
+    /// elements as keys, so this is `None` for them as well. Their elements
+    /// can be accessed through [IntegerSeq::iter] and [FloatSeq::iter], or
+    /// they can be consumed with [Key::into_seq_iter].
+    ///
+    /// [IntegerSeq::iter]: crate::IntegerSeq::iter
+    /// [FloatSeq::iter]: crate::FloatSeq::iter
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::{to_key, Key};
+    ///
+    /// let key = to_key(&vec!["a", "b"])?;
+    /// let mut iter = key.iter_seq().unwrap();
+    /// assert_eq!(iter.next_back(), Some(&Key::String("b".into())));
+    /// assert_eq!(iter.len(), 1);
+    ///
+    /// assert!(to_key(&Vec::<String>::new())?.iter_seq().is_some());
+    /// assert!(to_key("a")?.iter_seq().is_none());
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn iter_seq(&self) -> Option<slice::Iter<'_, Key<F>>> {
+        match self {
+            Key::Seq(seq) => Some(seq.iter()),
+            _ => None,
+        }
+    }
+
+    /// Iterate over the entries of a [Key::Map], or `None` if this is any
+    /// other kind of key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_derive::Serialize;
+    /// use serde_hashkey::{to_key, Key};
+    ///
+    /// #[derive(Serialize)]
+    /// struct Author {
+    ///     name: &'static str,
+    /// }
+    ///
+    /// let key = to_key(&Author { name: "Noah" })?;
+    /// let entries = key.iter_map().unwrap().collect::<Vec<_>>();
+    /// assert_eq!(entries, vec![&(Key::String("name".into()), Key::String("Noah".into()))]);
+    ///
+    /// assert!(to_key(&vec!["name"])?.iter_map().is_none());
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn iter_map(&self) -> Option<slice::Iter<'_, (Key<F>, Key<F>)>> {
+        match self {
+            Key::Map(map) => Some(map.iter()),
+            _ => None,
+        }
+    }
+
+    /// Consume a sequence and iterate over its elements, or `None` if this is
+    /// any other kind of key.
+    ///
+    /// The compact [Key::IntegerSeq] and [Key::FloatSeq] are expanded, so
+    /// that each element is yielded as a [Key::Integer] or [Key::Float].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::{to_key, Key};
+    ///
+    /// let key = to_key(&vec![1u32, 2])?;
+    /// let elements = key.into_seq_iter().unwrap().rev().collect::<Vec<_>>();
+    /// assert_eq!(elements, vec![Key::from(2u32), Key::from(1u32)]);
+    ///
+    /// assert!(to_key("a")?.into_seq_iter().is_none());
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn into_seq_iter(self) -> Option<vec::IntoIter<Key<F>>> {
+        let seq = match self {
+            Key::Seq(seq) => seq.into_vec(),
+            Key::IntegerSeq(seq) => seq.iter().map(Key::Integer).collect(),
+            Key::FloatSeq(seq) => seq.iter().map(Key::Float).collect(),
+            _ => return None,
+        };
+
+        Some(seq.into_iter())
+    }
+
+    /// Consume a map and iterate over its entries, or `None` if this is any
+    /// other kind of key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    ///
+    /// use serde_hashkey::{to_key, Key};
+    ///
+    /// let mut map = BTreeMap::new();
+    /// map.insert(1u32, "one");
+    /// map.insert(2u32, "two");
+    ///
+    /// let key = to_key(&map)?;
+    /// let keys = key.into_map_iter().unwrap().map(|(key, _)| key).collect::<Vec<_>>();
+    /// assert_eq!(keys, vec![Key::from(1u32), Key::from(2u32)]);
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn into_map_iter(self) -> Option<vec::IntoIter<(Key<F>, Key<F>)>> {
+        match self {
+            Key::Map(map) => Some(map.into_vec().into_iter()),
+            _ => None,
+        }
+    }
+}
+
+/// Depth-first iterator over every value in a [Key].
+///
+/// See [Key::iter].
+pub struct Iter<'a, F>
+where
+    F: FloatPolicy,
+{
+    next: Option<&'a Key<F>>,
+    stack: Vec<Frame<'a, F>>,
+}
+
+impl<'a, F> Iterator for Iter<'a, F>
+where
+    F: FloatPolicy,
+{
+    type Item = &'a Key<F>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let key = match self.next.take() {
+            Some(key) => key,
+            None => loop {
+                let frame = self.stack.last_mut()?;
+
+                match frame.next() {
+                    Some(key) => break key,
+                    None => {
+                        self.stack.pop();
+                    }
+                }
+            },
+        };
+
+        match key {
+            Key::Seq(seq) if !seq.is_empty() => self.stack.push(Frame::Seq(seq.iter())),
+            Key::Map(map) if !map.is_empty() => self.stack.push(Frame::Map(map.iter(), None)),
+            _ => {}
+        }
+
+        Some(key)
+    }
+}
+
+impl<F> FusedIterator for Iter<'_, F> where F: FloatPolicy {}
+
+impl<F> Clone for Iter<'_, F>
+where
+    F: FloatPolicy,
+{
+    fn clone(&self) -> Self {
+        Self {
+            next: self.next,
+            stack: self.stack.clone(),
+        }
+    }
+}
+
+enum Frame<'a, F>
+where
+    F: FloatPolicy,
+{
+    Seq(slice::Iter<'a, Key<F>>),
+    /// The entries of a map, and the value of the entry whose key was yielded
+    /// last.
+    Map(slice::Iter<'a, (Key<F>, Key<F>)>, Option<&'a Key<F>>),
+}
+
+impl<'a, F> Frame<'a, F>
+where
+    F: FloatPolicy,
+{
+    fn next(&mut self) -> Option<&'a Key<F>> {
+        match self {
+            Frame::Seq(iter) => iter.next(),
+            Frame::Map(iter, value) => {
+                if let Some(value) = value.take() {
+                    return Some(value);
+                }
+
+                let (key, next) = iter.next()?;
+                *value = Some(next);
+                Some(key)
+            }
+        }
+    }
+}
+
+impl<F> Clone for Frame<'_, F>
+where
+    F: FloatPolicy,
+{
+    fn clone(&self) -> Self {
+        match self {
+            Frame::Seq(iter) => Frame::Seq(iter.clone()),
+            Frame::Map(iter, value) => Frame::Map(iter.clone(), *value),
+        }
+    }
+}
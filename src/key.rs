@@ -1,13 +1,32 @@
 //! In-memory value representation for values.
-use crate::float::{FloatPolicy, FloatRepr, RejectFloatPolicy};
+use crate::bytes::Bytes;
+use crate::error::Error;
+use crate::float::{self, FloatPolicy, FloatRepr, RejectFloatPolicy};
 use serde::{de, ser};
+use std::borrow::Cow;
+use std::cmp;
+use std::convert::TryFrom;
 use std::fmt;
 use std::hash::Hash;
-use std::marker;
 use std::mem;
+use std::num;
+use std::ops;
+use std::str;
+use std::sync::Arc;
 
 /// An opaque integer.
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+///
+/// Integers are ordered by value regardless of their width, so
+/// `Integer::I64(-1) < Integer::U8(0) < Integer::U128(u128::MAX)`. Equality and
+/// hashing are width-sensitive though, so `Integer::U8(1)` is not equal to
+/// `Integer::U64(1)`. To stay consistent with equality, integers with the same
+/// value are ordered by their width, with the signed variants from [Integer::I8]
+/// to [Integer::I128] first and the unsigned variants from [Integer::U8] to
+/// [Integer::U128] after them.
+///
+/// See [Integer::canonical] for a representation where integers with the same
+/// value are also equal.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Integer {
     /// Variant representing a `i8` integer.
     I8(i8),
@@ -31,6 +50,295 @@ pub enum Integer {
     U128(u128),
 }
 
+impl Integer {
+    /// Test if the integer is stored in one of the signed variants,
+    /// [Integer::I8] through [Integer::I128], regardless of its value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::Integer;
+    ///
+    /// assert!(Integer::I32(42).is_signed_variant());
+    /// assert!(!Integer::U32(42).is_signed_variant());
+    /// ```
+    pub const fn is_signed_variant(&self) -> bool {
+        matches!(
+            self,
+            Integer::I8(..)
+                | Integer::I16(..)
+                | Integer::I32(..)
+                | Integer::I64(..)
+                | Integer::I128(..)
+        )
+    }
+
+    /// Test if the value of the integer is negative.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::Integer;
+    ///
+    /// assert!(Integer::I8(-1).is_negative());
+    /// assert!(!Integer::I8(0).is_negative());
+    /// assert!(!Integer::U128(u128::MAX).is_negative());
+    /// ```
+    pub const fn is_negative(&self) -> bool {
+        match *self {
+            Integer::I8(v) => v < 0,
+            Integer::I16(v) => v < 0,
+            Integer::I32(v) => v < 0,
+            Integer::I64(v) => v < 0,
+            Integer::I128(v) => v < 0,
+            _ => false,
+        }
+    }
+
+    /// Widen the integer into an `i128`, or `None` if it's an `u128` which is
+    /// too large to fit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::Integer;
+    ///
+    /// assert_eq!(Integer::I8(-1).to_i128(), Some(-1));
+    /// assert_eq!(Integer::U64(u64::MAX).to_i128(), Some(i128::from(u64::MAX)));
+    /// assert_eq!(Integer::U128(u128::MAX).to_i128(), None);
+    /// ```
+    pub const fn to_i128(&self) -> Option<i128> {
+        // Every cast is lossless, since it's either widening or checked.
+        match *self {
+            Integer::I8(v) => Some(v as i128),
+            Integer::I16(v) => Some(v as i128),
+            Integer::I32(v) => Some(v as i128),
+            Integer::I64(v) => Some(v as i128),
+            Integer::I128(v) => Some(v),
+            Integer::U8(v) => Some(v as i128),
+            Integer::U16(v) => Some(v as i128),
+            Integer::U32(v) => Some(v as i128),
+            Integer::U64(v) => Some(v as i128),
+            Integer::U128(v) if v <= i128::MAX as u128 => Some(v as i128),
+            Integer::U128(..) => None,
+        }
+    }
+
+    /// Widen the integer into an `u128`, or `None` if it's negative.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::Integer;
+    ///
+    /// assert_eq!(Integer::U32(42).to_u128(), Some(42));
+    /// assert_eq!(Integer::I64(42).to_u128(), Some(42));
+    /// assert_eq!(Integer::I64(-42).to_u128(), None);
+    /// ```
+    pub const fn to_u128(&self) -> Option<u128> {
+        if self.is_negative() {
+            return None;
+        }
+
+        // Every cast is lossless, since the value is known to be non-negative.
+        match *self {
+            Integer::I8(v) => Some(v as u128),
+            Integer::I16(v) => Some(v as u128),
+            Integer::I32(v) => Some(v as u128),
+            Integer::I64(v) => Some(v as u128),
+            Integer::I128(v) => Some(v as u128),
+            Integer::U8(v) => Some(v as u128),
+            Integer::U16(v) => Some(v as u128),
+            Integer::U32(v) => Some(v as u128),
+            Integer::U64(v) => Some(v as u128),
+            Integer::U128(v) => Some(v),
+        }
+    }
+
+    /// Convert the integer into an `i64`, or `None` if its value doesn't fit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::Integer;
+    ///
+    /// assert_eq!(Integer::I128(i128::from(i64::MIN)).to_i64(), Some(i64::MIN));
+    /// assert_eq!(Integer::U64(u64::MAX).to_i64(), None);
+    /// assert_eq!(Integer::U8(42).to_i64(), Some(42));
+    /// ```
+    pub const fn to_i64(&self) -> Option<i64> {
+        match self.to_i128() {
+            Some(v) if v >= i64::MIN as i128 && v <= i64::MAX as i128 => Some(v as i64),
+            _ => None,
+        }
+    }
+
+    /// Convert the integer into an `u64`, or `None` if its value doesn't fit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::Integer;
+    ///
+    /// assert_eq!(Integer::U128(u128::from(u64::MAX)).to_u64(), Some(u64::MAX));
+    /// assert_eq!(Integer::U128(u128::MAX).to_u64(), None);
+    /// assert_eq!(Integer::I8(-1).to_u64(), None);
+    /// ```
+    pub const fn to_u64(&self) -> Option<u64> {
+        match self.to_u128() {
+            Some(v) if v <= u64::MAX as u128 => Some(v as u64),
+            _ => None,
+        }
+    }
+}
+
+impl Integer {
+    /// The position of the variant in declaration order, which is used to
+    /// order integers with the same value.
+    const fn variant_index(&self) -> u8 {
+        match self {
+            Integer::I8(..) => 0,
+            Integer::I16(..) => 1,
+            Integer::I32(..) => 2,
+            Integer::I64(..) => 3,
+            Integer::I128(..) => 4,
+            Integer::U8(..) => 5,
+            Integer::U16(..) => 6,
+            Integer::U32(..) => 7,
+            Integer::U64(..) => 8,
+            Integer::U128(..) => 9,
+        }
+    }
+
+    /// Compare the values of two integers, ignoring their widths.
+    fn cmp_value(&self, other: &Integer) -> cmp::Ordering {
+        match (self.to_i128(), other.to_i128()) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            // Only an u128 larger than any i128 doesn't fit.
+            (None, Some(..)) => cmp::Ordering::Greater,
+            (Some(..), None) => cmp::Ordering::Less,
+            (None, None) => self.to_u128().cmp(&other.to_u128()),
+        }
+    }
+}
+
+impl PartialOrd for Integer {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Integer {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.cmp_value(other)
+            .then_with(|| self.variant_index().cmp(&other.variant_index()))
+    }
+}
+
+/// Displays the decimal value of the integer, regardless of its width.
+///
+/// # Examples
+///
+/// ```
+/// use serde_hashkey::Integer;
+///
+/// assert_eq!(Integer::I8(-42).to_string(), "-42");
+/// assert_eq!(Integer::U128(u128::MAX).to_string(), u128::MAX.to_string());
+/// ```
+impl fmt::Display for Integer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Integer::I8(v) => v.fmt(f),
+            Integer::I16(v) => v.fmt(f),
+            Integer::I32(v) => v.fmt(f),
+            Integer::I64(v) => v.fmt(f),
+            Integer::I128(v) => v.fmt(f),
+            Integer::U8(v) => v.fmt(f),
+            Integer::U16(v) => v.fmt(f),
+            Integer::U32(v) => v.fmt(f),
+            Integer::U64(v) => v.fmt(f),
+            Integer::U128(v) => v.fmt(f),
+        }
+    }
+}
+
+/// Parses a decimal integer into the narrowest variant which can hold it.
+/// Negative values are stored in the signed variants, and everything else in
+/// the unsigned variants.
+///
+/// Values which don't fit in an `i128` or an `u128` error with
+/// [Error::IntegerOverflow], and anything which isn't an integer errors with
+/// [Error::InvalidInteger].
+///
+/// # Examples
+///
+/// ```
+/// use serde_hashkey::{Error, Integer};
+///
+/// assert_eq!("42".parse(), Ok(Integer::U8(42)));
+/// assert_eq!("-129".parse(), Ok(Integer::I16(-129)));
+/// assert_eq!("1.5".parse::<Integer>(), Err(Error::InvalidInteger("1.5".into())));
+/// ```
+impl str::FromStr for Integer {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        if s.starts_with('-') {
+            let v = s.parse::<i128>().map_err(|e| parse_error(e, "i128", s))?;
+
+            if v < 0 {
+                return Ok(narrowest_signed(v));
+            }
+
+            // Negative zero.
+            return Ok(Integer::U8(0));
+        }
+
+        let v = s.parse::<u128>().map_err(|e| parse_error(e, "u128", s))?;
+        Ok(narrowest_unsigned(v))
+    }
+}
+
+fn parse_error(error: num::ParseIntError, ty: &'static str, s: &str) -> Error {
+    match error.kind() {
+        num::IntErrorKind::PosOverflow | num::IntErrorKind::NegOverflow => {
+            Error::IntegerOverflow(ty)
+        }
+        _ => Error::InvalidInteger(s.to_owned()),
+    }
+}
+
+/// Store the value in the narrowest signed variant which can hold it.
+pub(crate) fn narrowest_signed(v: i128) -> Integer {
+    if let Ok(v) = i8::try_from(v) {
+        Integer::I8(v)
+    } else if let Ok(v) = i16::try_from(v) {
+        Integer::I16(v)
+    } else if let Ok(v) = i32::try_from(v) {
+        Integer::I32(v)
+    } else if let Ok(v) = i64::try_from(v) {
+        Integer::I64(v)
+    } else {
+        Integer::I128(v)
+    }
+}
+
+/// Store the value in the narrowest unsigned variant which can hold it.
+pub(crate) fn narrowest_unsigned(v: u128) -> Integer {
+    if let Ok(v) = u8::try_from(v) {
+        Integer::U8(v)
+    } else if let Ok(v) = u16::try_from(v) {
+        Integer::U16(v)
+    } else if let Ok(v) = u32::try_from(v) {
+        Integer::U32(v)
+    } else if let Ok(v) = u64::try_from(v) {
+        Integer::U64(v)
+    } else {
+        Integer::U128(v)
+    }
+}
+
 /// An opaque float derived from a given policy.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Float<F>
@@ -41,6 +349,334 @@ where
     F32(F::F32),
     /// Variant representing a `f64` float.
     F64(F::F64),
+    /// Variant representing a half-precision `f16` float.
+    ///
+    /// Every `f16` can be represented exactly as an `f32`, so it's stored using
+    /// the `f32` representation of the policy. It's kept separate from
+    /// [Float::F32] so that keys of `f16` values are distinct from keys of the
+    /// same `f32` values.
+    ///
+    /// This is produced when serializing a `half::f16` with the `half` feature
+    /// enabled, and is deserialized as an `f32`. It's serialized the same way
+    /// as `half::f16` is with the `half` feature enabled, and as an `f32`
+    /// otherwise.
+    ///
+    /// The variant is present whether the `half` feature is enabled or not, so
+    /// that enabling the feature doesn't change the shape of this enum.
+    F16(F::F32),
+}
+
+impl<F> Float<F>
+where
+    F: FloatPolicy,
+{
+    /// Construct a float from an `f32` using the float policy, the same way
+    /// as serializing it would.
+    ///
+    /// This is usually a [Float::F32], but policies can store it differently
+    /// such as [WidenF32Policy] which stores it as a [Float::F64].
+    ///
+    /// [WidenF32Policy]: crate::WidenF32Policy
+    ///
+    /// # Errors
+    ///
+    /// Errors if the float policy rejects the value, like
+    /// [RejectFloatPolicy] which rejects every float.
+    ///
+    /// [RejectFloatPolicy]: crate::RejectFloatPolicy
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::{CanonicalFloatPolicy, Error, Float, FloatWidth, RejectFloatPolicy};
+    ///
+    /// let float = Float::<CanonicalFloatPolicy>::f32(1.5)?;
+    /// assert!(matches!(float, Float::F32(..)));
+    ///
+    /// let error = Error::UnsupportedFloat { width: FloatWidth::F32, value: 1.5 };
+    /// assert_eq!(Float::<RejectFloatPolicy>::f32(1.5), Err(error));
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn f32(value: f32) -> Result<Self, Error> {
+        F::serialize_f32(value)
+    }
+
+    /// Construct a [Float::F64] from an `f64` using the float policy, the same
+    /// way as serializing it would.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the float policy rejects the value, like
+    /// [RejectFloatPolicy] which rejects every float.
+    ///
+    /// [RejectFloatPolicy]: crate::RejectFloatPolicy
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::{CanonicalFloatPolicy, Error, Float, FloatWidth, RejectFloatPolicy};
+    ///
+    /// let float = Float::<CanonicalFloatPolicy>::f64(1.5)?;
+    /// assert!(matches!(float, Float::F64(..)));
+    ///
+    /// let error = Error::UnsupportedFloat { width: FloatWidth::F64, value: 1.5 };
+    /// assert_eq!(Float::<RejectFloatPolicy>::f64(1.5), Err(error));
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn f64(value: f64) -> Result<Self, Error> {
+        Ok(Float::F64(<F::F64 as FloatRepr<f64>>::serialize(value)?))
+    }
+}
+
+/// A compact sequence of integers which all have the same width.
+///
+/// This is used by [Key::IntegerSeq] to store homogeneous sequences of
+/// integers without the overhead of storing each element as a [Key].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum IntegerSeq {
+    /// A sequence of `i8` integers.
+    I8(Box<[i8]>),
+    /// A sequence of `i16` integers.
+    I16(Box<[i16]>),
+    /// A sequence of `i32` integers.
+    I32(Box<[i32]>),
+    /// A sequence of `i64` integers.
+    I64(Box<[i64]>),
+    /// A sequence of `i128` integers.
+    I128(Box<[i128]>),
+    /// A sequence of `u8` integers.
+    U8(Box<[u8]>),
+    /// A sequence of `u16` integers.
+    U16(Box<[u16]>),
+    /// A sequence of `u32` integers.
+    U32(Box<[u32]>),
+    /// A sequence of `u64` integers.
+    U64(Box<[u64]>),
+    /// A sequence of `u128` integers.
+    U128(Box<[u128]>),
+}
+
+macro_rules! integer_seq {
+    ($value:expr, $seq:ident => $expr:expr) => {
+        match $value {
+            IntegerSeq::I8($seq) => $expr,
+            IntegerSeq::I16($seq) => $expr,
+            IntegerSeq::I32($seq) => $expr,
+            IntegerSeq::I64($seq) => $expr,
+            IntegerSeq::I128($seq) => $expr,
+            IntegerSeq::U8($seq) => $expr,
+            IntegerSeq::U16($seq) => $expr,
+            IntegerSeq::U32($seq) => $expr,
+            IntegerSeq::U64($seq) => $expr,
+            IntegerSeq::U128($seq) => $expr,
+        }
+    };
+}
+
+impl IntegerSeq {
+    /// The number of integers in the sequence.
+    pub fn len(&self) -> usize {
+        integer_seq!(self, seq => seq.len())
+    }
+
+    /// Test if the sequence is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the integer at the given index.
+    pub fn get(&self, index: usize) -> Option<Integer> {
+        Some(match self {
+            IntegerSeq::I8(seq) => Integer::I8(*seq.get(index)?),
+            IntegerSeq::I16(seq) => Integer::I16(*seq.get(index)?),
+            IntegerSeq::I32(seq) => Integer::I32(*seq.get(index)?),
+            IntegerSeq::I64(seq) => Integer::I64(*seq.get(index)?),
+            IntegerSeq::I128(seq) => Integer::I128(*seq.get(index)?),
+            IntegerSeq::U8(seq) => Integer::U8(*seq.get(index)?),
+            IntegerSeq::U16(seq) => Integer::U16(*seq.get(index)?),
+            IntegerSeq::U32(seq) => Integer::U32(*seq.get(index)?),
+            IntegerSeq::U64(seq) => Integer::U64(*seq.get(index)?),
+            IntegerSeq::U128(seq) => Integer::U128(*seq.get(index)?),
+        })
+    }
+
+    /// Iterate over the integers in the sequence.
+    pub fn iter(&self) -> IntegerSeqIter<'_> {
+        IntegerSeqIter {
+            seq: self,
+            index: 0,
+        }
+    }
+
+    /// Try to construct a compact sequence from the given keys, which
+    /// succeeds if they are all integers of the same width.
+    fn from_keys<F>(keys: &[Key<F>]) -> Option<Self>
+    where
+        F: FloatPolicy,
+    {
+        macro_rules! collect {
+            ($variant:ident) => {{
+                let mut out = Vec::with_capacity(keys.len());
+
+                for key in keys {
+                    match key {
+                        Key::Integer(Integer::$variant(v)) => out.push(*v),
+                        _ => return None,
+                    }
+                }
+
+                IntegerSeq::$variant(out.into())
+            }};
+        }
+
+        let seq = match keys.first()? {
+            Key::Integer(Integer::I8(..)) => collect!(I8),
+            Key::Integer(Integer::I16(..)) => collect!(I16),
+            Key::Integer(Integer::I32(..)) => collect!(I32),
+            Key::Integer(Integer::I64(..)) => collect!(I64),
+            Key::Integer(Integer::I128(..)) => collect!(I128),
+            Key::Integer(Integer::U8(..)) => collect!(U8),
+            Key::Integer(Integer::U16(..)) => collect!(U16),
+            Key::Integer(Integer::U32(..)) => collect!(U32),
+            Key::Integer(Integer::U64(..)) => collect!(U64),
+            Key::Integer(Integer::U128(..)) => collect!(U128),
+            _ => return None,
+        };
+
+        Some(seq)
+    }
+}
+
+/// Iterator over the integers in an [IntegerSeq].
+///
+/// See [IntegerSeq::iter].
+pub struct IntegerSeqIter<'a> {
+    seq: &'a IntegerSeq,
+    index: usize,
+}
+
+impl Iterator for IntegerSeqIter<'_> {
+    type Item = Integer;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = self.seq.get(self.index)?;
+        self.index += 1;
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.seq.len().saturating_sub(self.index);
+        (len, Some(len))
+    }
+}
+
+/// A compact sequence of floats which all have the same width.
+///
+/// This is used by [Key::FloatSeq] to store homogeneous sequences of floats
+/// without the overhead of storing each element as a [Key].
+///
+/// There is no compact sequence of [Float::F16], since half-precision floats
+/// are stored using the same representation as an `f32` and only exist to
+/// keep their width distinct. Sequences of them are stored as a [Key::Seq].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FloatSeq<F>
+where
+    F: FloatPolicy,
+{
+    /// A sequence of `f32` floats.
+    F32(Box<[F::F32]>),
+    /// A sequence of `f64` floats.
+    F64(Box<[F::F64]>),
+}
+
+impl<F> FloatSeq<F>
+where
+    F: FloatPolicy,
+{
+    /// The number of floats in the sequence.
+    pub fn len(&self) -> usize {
+        match self {
+            FloatSeq::F32(seq) => seq.len(),
+            FloatSeq::F64(seq) => seq.len(),
+        }
+    }
+
+    /// Test if the sequence is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the float at the given index.
+    pub fn get(&self, index: usize) -> Option<Float<F>> {
+        Some(match self {
+            FloatSeq::F32(seq) => Float::F32(*seq.get(index)?),
+            FloatSeq::F64(seq) => Float::F64(*seq.get(index)?),
+        })
+    }
+
+    /// Iterate over the floats in the sequence.
+    pub fn iter(&self) -> FloatSeqIter<'_, F> {
+        FloatSeqIter {
+            seq: self,
+            index: 0,
+        }
+    }
+
+    /// Try to construct a compact sequence from the given keys, which
+    /// succeeds if they are all floats of the same width.
+    fn from_keys(keys: &[Key<F>]) -> Option<Self> {
+        macro_rules! collect {
+            ($variant:ident) => {{
+                let mut out = Vec::with_capacity(keys.len());
+
+                for key in keys {
+                    match key {
+                        Key::Float(Float::$variant(v)) => out.push(*v),
+                        _ => return None,
+                    }
+                }
+
+                FloatSeq::$variant(out.into())
+            }};
+        }
+
+        let seq = match keys.first()? {
+            Key::Float(Float::F32(..)) => collect!(F32),
+            Key::Float(Float::F64(..)) => collect!(F64),
+            _ => return None,
+        };
+
+        Some(seq)
+    }
+}
+
+/// Iterator over the floats in a [FloatSeq].
+///
+/// See [FloatSeq::iter].
+pub struct FloatSeqIter<'a, F>
+where
+    F: FloatPolicy,
+{
+    seq: &'a FloatSeq<F>,
+    index: usize,
+}
+
+impl<F> Iterator for FloatSeqIter<'_, F>
+where
+    F: FloatPolicy,
+{
+    type Item = Float<F>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = self.seq.get(self.index)?;
+        self.index += 1;
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.seq.len().saturating_sub(self.index);
+        (len, Some(len))
+    }
 }
 
 /// The central key type, which is an in-memory representation of all supported
@@ -60,7 +696,7 @@ where
 ///
 /// ```
 /// use serde_derive::{Deserialize, Serialize};
-/// use serde_hashkey::{to_key, to_key_with_ordered_float};
+/// use serde_hashkey::to_key;
 ///
 /// #[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
 /// struct Author {
@@ -77,14 +713,24 @@ where
 /// // Note: serializing floats will fail under the default policy, but succeed
 /// // under one supporting floats.
 /// assert!(to_key(&42.0f32).is_err());
-/// assert!(to_key_with_ordered_float(&42.0f32).is_ok());
+/// # #[cfg(feature = "ordered-float")]
+/// assert!(serde_hashkey::to_key_with_ordered_float(&42.0f32).is_ok());
 /// # Ok(()) }
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Key<F = RejectFloatPolicy>
 where
     F: FloatPolicy,
 {
+    /// An absent optional value, which is what `None` serializes as.
+    ///
+    /// This is distinct from [Key::Unit] so that `Some(())` and `None` produce
+    /// different keys. It is the first variant, so it sorts before every other
+    /// key.
+    ///
+    /// A `Some` which wraps a `None` is stored as a [Key::Seq] holding it, see
+    /// [optional values](crate#optional-values).
+    Null,
     /// A unit value.
     Unit,
     /// A boolean value.
@@ -94,15 +740,42 @@ where
     /// A 32-bit floating-point number.
     Float(Float<F>),
     /// A byte array.
-    Bytes(Box<[u8]>),
+    Bytes(Bytes),
+    /// A character.
+    ///
+    /// This is distinct from a [Key::String] containing a single character.
+    /// It sorts after every [Key::Bytes] and before every [Key::String].
+    Char(char),
     /// A string.
-    String(Box<str>),
+    ///
+    /// Strings are reference counted, so the names of fields and variants can
+    /// be shared by every key they appear in instead of being allocated for
+    /// each of them.
+    String(Arc<str>),
     /// A vector.
     Seq(Box<[Key<F>]>),
     /// A map.
     Map(Box<[(Key<F>, Key<F>)]>),
+    /// A non-empty sequence of integers which all have the same width.
+    ///
+    /// This is a compact representation of a [Key::Seq] whose elements are
+    /// all [Key::Integer] of the same width. It's the canonical form for such
+    /// sequences, which is what serialization and [Key::normalize] produce.
+    /// It is not considered equal to the corresponding [Key::Seq], so keys
+    /// which are constructed by hand should be [normalized][Key::normalize]
+    /// before being compared.
+    IntegerSeq(IntegerSeq),
+    /// A non-empty sequence of floats which all have the same width.
+    ///
+    /// This is a compact representation of a [Key::Seq] whose elements are
+    /// all [Key::Float] of the same width, see [Key::IntegerSeq]. Sequences
+    /// of [Float::F16] are not compacted and stay a [Key::Seq], see
+    /// [FloatSeq].
+    FloatSeq(FloatSeq<F>),
 }
 
+// Deriving this would require the float policy to implement `Default`.
+#[allow(clippy::derivable_impls)]
 impl Default for Key {
     fn default() -> Self {
         Self::Unit
@@ -111,28 +784,535 @@ impl Default for Key {
 
 impl<F> Key<F>
 where
-    F: FloatPolicy + Ord,
+    F: FloatPolicy,
 {
-    /// Normalize the key, making sure that all contained maps are sorted.
-    pub fn normalize(self) -> Self {
+    /// Construct a [Key::Float] from an `f32` using the float policy, which
+    /// produces the same key as serializing it would.
+    ///
+    /// This can be used to construct keys for policies where the float
+    /// representation is opaque. See [Float::f32].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::{to_key_with_canonical_float, Key, RejectFloatPolicy};
+    ///
+    /// let key = Key::from_f32(0.5)?;
+    /// assert_eq!(key, to_key_with_canonical_float(&0.5f32)?);
+    /// assert!(Key::<RejectFloatPolicy>::from_f32(0.5).is_err());
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn from_f32(value: f32) -> Result<Self, Error> {
+        Ok(Key::Float(Float::f32(value)?))
+    }
+
+    /// Construct a [Key::Float] from an `f64` using the float policy, which
+    /// produces the same key as serializing it would.
+    ///
+    /// This can be used to construct keys for policies where the float
+    /// representation is opaque. See [Float::f64].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::{to_key_with_canonical_float, Key, RejectFloatPolicy};
+    ///
+    /// let key = Key::from_f64(0.5)?;
+    /// assert_eq!(key, to_key_with_canonical_float(&0.5f64)?);
+    /// assert!(Key::<RejectFloatPolicy>::from_f64(0.5).is_err());
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn from_f64(value: f64) -> Result<Self, Error> {
+        Ok(Key::Float(Float::f64(value)?))
+    }
+
+    /// Test if this is a [Key::Null].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::to_key;
+    ///
+    /// assert!(to_key(&None::<()>)?.is_null());
+    /// assert!(!to_key(&Some(()))?.is_null());
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn is_null(&self) -> bool {
+        matches!(self, Key::Null)
+    }
+
+    /// Test if this is a [Key::Unit].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::{to_key, Key};
+    ///
+    /// assert!(to_key(&())?.is_unit());
+    /// assert!(!to_key(&Some(()))?.is_string());
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn is_unit(&self) -> bool {
+        matches!(self, Key::Unit)
+    }
+
+    /// Test if this is a [Key::Bool].
+    pub fn is_bool(&self) -> bool {
+        matches!(self, Key::Bool(..))
+    }
+
+    /// Test if this is a [Key::String].
+    pub fn is_string(&self) -> bool {
+        matches!(self, Key::String(..))
+    }
+
+    /// Test if this is a [Key::Bytes].
+    pub fn is_bytes(&self) -> bool {
+        matches!(self, Key::Bytes(..))
+    }
+
+    /// Get the integer of a [Key::Integer], or `None` for any other kind of
+    /// key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::{to_key, Integer};
+    ///
+    /// assert_eq!(to_key(&42u32)?.as_integer(), Some(&Integer::U32(42)));
+    /// assert_eq!(to_key("42")?.as_integer(), None);
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn as_integer(&self) -> Option<&Integer> {
         match self {
-            Key::<F>::Seq(mut vec) => {
-                for value in vec.iter_mut() {
-                    *value = mem::replace(value, Key::<F>::Unit).normalize();
+            Key::Integer(integer) => Some(integer),
+            _ => None,
+        }
+    }
+
+    /// Get the value of a [Key::Integer] of any width as an `i64`, or `None`
+    /// if it doesn't fit or if this is any other kind of key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::to_key;
+    ///
+    /// assert_eq!(to_key(&42u8)?.as_i64(), Some(42));
+    /// assert_eq!(to_key(&-42i128)?.as_i64(), Some(-42));
+    /// assert_eq!(to_key(&u64::MAX)?.as_i64(), None);
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn as_i64(&self) -> Option<i64> {
+        i64::try_from(self.as_integer()?.to_i128()?).ok()
+    }
+
+    /// Get the value of a [Key::Integer] of any width as an `u64`, or `None`
+    /// if it doesn't fit or if this is any other kind of key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::to_key;
+    ///
+    /// assert_eq!(to_key(&42i32)?.as_u64(), Some(42));
+    /// assert_eq!(to_key(&-1i32)?.as_u64(), None);
+    /// assert_eq!(to_key(&u128::MAX)?.as_u64(), None);
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn as_u64(&self) -> Option<u64> {
+        u64::try_from(self.as_integer()?.to_u128()?).ok()
+    }
+
+    /// Get the value of a [Key::Bool], or `None` for any other kind of key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::{to_key, Key};
+    ///
+    /// assert_eq!(to_key(&true)?.as_bool(), Some(true));
+    /// assert_eq!(to_key(&1u32)?.as_bool(), None);
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn as_bool(&self) -> Option<bool> {
+        match *self {
+            Key::Bool(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Get the string of a [Key::String], or `None` for any other kind of key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::{to_key, Key};
+    ///
+    /// assert_eq!(to_key("hello")?.as_str(), Some("hello"));
+    /// assert_eq!(to_key(&b"hello".to_vec())?.as_str(), None);
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Key::String(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    /// Get the bytes of a [Key::Bytes], or `None` for any other kind of key.
+    ///
+    /// Note that sequences of `u8`, like a `Vec<u8>` which isn't serialized
+    /// as bytes, are stored as a [Key::IntegerSeq] and are not considered
+    /// bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::Key;
+    ///
+    /// let key: Key = Key::from(b"hello".to_vec());
+    /// assert_eq!(key.as_bytes(), Some(&b"hello"[..]));
+    ///
+    /// let key: Key = Key::Unit;
+    /// assert_eq!(key.as_bytes(), None);
+    /// ```
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Key::Bytes(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    /// Estimate the number of bytes of memory used by this key, including
+    /// everything it contains.
+    ///
+    /// This is the size of this [Key] itself, plus the size of the heap
+    /// allocation of every container and scalar it contains:
+    ///
+    /// * [Key::String] and [Key::Bytes] count the length of their content.
+    /// * [Key::Seq] counts the size of a [Key] for each element.
+    /// * [Key::Map] counts the size of two [Key]s for each entry, one for the
+    ///   key and one for the value.
+    /// * [Key::IntegerSeq] and [Key::FloatSeq] count the size of each element
+    ///   in its compact representation.
+    ///
+    /// Any other key is stored inline and uses no additional memory. The
+    /// estimate doesn't include any overhead or padding added by the
+    /// allocator, and bytes which are shared with other keys are counted in
+    /// full for each key. Traversal does not use recursion, so it is safe to
+    /// use on deeply nested keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::mem;
+    ///
+    /// use serde_hashkey::{to_key, Key};
+    ///
+    /// let key = to_key("hello")?;
+    /// assert_eq!(key.memory_usage(), mem::size_of::<Key>() + 5);
+    ///
+    /// let key = to_key(&vec!["hello"])?;
+    /// assert_eq!(key.memory_usage(), 2 * mem::size_of::<Key>() + 5);
+    ///
+    /// let key = to_key(&vec![1u32, 2, 3])?;
+    /// assert_eq!(key.memory_usage(), mem::size_of::<Key>() + 3 * 4);
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn memory_usage(&self) -> usize {
+        let mut total = mem::size_of::<Key<F>>();
+
+        for key in self.iter() {
+            total += match key {
+                Key::String(string) => string.len(),
+                Key::Bytes(bytes) => bytes.len(),
+                Key::Seq(seq) => mem::size_of_val(&**seq),
+                Key::Map(map) => mem::size_of_val(&**map),
+                Key::IntegerSeq(seq) => integer_seq!(seq, seq => mem::size_of_val(&**seq)),
+                Key::FloatSeq(FloatSeq::F32(seq)) => mem::size_of_val(&**seq),
+                Key::FloatSeq(FloatSeq::F64(seq)) => mem::size_of_val(&**seq),
+                Key::Null
+                | Key::Unit
+                | Key::Bool(..)
+                | Key::Char(..)
+                | Key::Integer(..)
+                | Key::Float(..) => 0,
+            };
+        }
+
+        total
+    }
+}
+
+impl<F> Key<F>
+where
+    F: FloatPolicy + Ord,
+{
+    /// Normalize the key, making sure that all contained maps are sorted and
+    /// that homogeneous sequences of integers or floats use their compact
+    /// [Key::IntegerSeq] or [Key::FloatSeq] representation.
+    ///
+    /// This is available for every float policy which provides a total order,
+    /// such as [OrderedFloatPolicy] where NaN sorts after every other float.
+    ///
+    /// [OrderedFloatPolicy]: crate::OrderedFloatPolicy
+    pub fn normalize(mut self) -> Self {
+        self.normalize_in_place();
+        self
+    }
+
+    /// Normalize the key in place.
+    ///
+    /// This is the same as [Key::normalize], but doesn't require ownership of
+    /// the key. Containers are normalized without moving their elements
+    /// around, except for the entries of maps which have to be sorted.
+    ///
+    /// Traversal does not use recursion, so it is safe to use on deeply nested
+    /// keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::Key;
+    ///
+    /// let mut key: Key = Key::Seq(vec![
+    ///     Key::Map(vec![(Key::from(2u32), Key::Unit), (Key::from(1u32), Key::Unit)].into()),
+    /// ].into());
+    ///
+    /// let expected = key.clone().normalize();
+    /// key.normalize_in_place();
+    /// assert_eq!(key, expected);
+    /// ```
+    pub fn normalize_in_place(&mut self) {
+        let mut stack = match Normalize::take(self) {
+            Some(frame) => vec![frame],
+            None => return,
+        };
+
+        while let Some(frame) = stack.last_mut() {
+            if let Some(child) = frame.next() {
+                if let Some(frame) = Normalize::take(child) {
+                    stack.push(frame);
                 }
 
-                Key::<F>::Seq(vec)
+                continue;
+            }
+
+            // Every element of the innermost container has been normalized.
+            if let Some(key) = stack.pop().map(Normalize::finish) {
+                match stack.last_mut() {
+                    Some(parent) => *parent.current() = key,
+                    None => *self = key,
+                }
+            }
+        }
+    }
+}
+
+/// A container which is being normalized by [Key::normalize_in_place].
+///
+/// The elements of the container are taken out of the key while it's being
+/// normalized, and put back once all of them have been normalized.
+enum Normalize<F>
+where
+    F: FloatPolicy,
+{
+    Seq(Box<[Key<F>]>, usize),
+    /// The entries of a map, and the position of the next key or value to
+    /// normalize counted over both.
+    Map(Box<[(Key<F>, Key<F>)]>, usize),
+}
+
+impl<F> Normalize<F>
+where
+    F: FloatPolicy + Ord,
+{
+    /// Take the elements out of a container which needs normalizing.
+    fn take(key: &mut Key<F>) -> Option<Self> {
+        match key {
+            Key::Seq(seq) => Some(Normalize::Seq(mem::take(seq), 0)),
+            Key::Map(map) => Some(Normalize::Map(mem::take(map), 0)),
+            _ => None,
+        }
+    }
+
+    /// Advance to the next element to normalize.
+    fn next(&mut self) -> Option<&mut Key<F>> {
+        match self {
+            Normalize::Seq(seq, next) => {
+                let key = seq.get_mut(*next)?;
+                *next += 1;
+                Some(key)
             }
-            Key::<F>::Map(mut map) => {
-                for (key, value) in map.iter_mut() {
-                    *key = mem::replace(key, Key::<F>::Unit).normalize();
-                    *value = mem::replace(value, Key::<F>::Unit).normalize();
+            Normalize::Map(map, next) => {
+                let (key, value) = map.get_mut(*next / 2)?;
+                let key = if *next % 2 == 0 { key } else { value };
+                *next += 1;
+                Some(key)
+            }
+        }
+    }
+
+    /// The element which was last advanced to.
+    fn current(&mut self) -> &mut Key<F> {
+        match self {
+            Normalize::Seq(seq, next) => &mut seq[*next - 1],
+            Normalize::Map(map, next) => {
+                let index = *next - 1;
+                let (key, value) = &mut map[index / 2];
+
+                if index % 2 == 0 {
+                    key
+                } else {
+                    value
                 }
+            }
+        }
+    }
 
+    /// Construct the normalized container.
+    fn finish(self) -> Key<F> {
+        match self {
+            Normalize::Seq(seq, _) => Key::seq(seq.into_vec()),
+            Normalize::Map(mut map, _) => {
                 map.sort_by(|a, b| a.0.cmp(&b.0));
-                Key::<F>::Map(map)
+                Key::Map(map)
             }
-            other => other,
+        }
+    }
+}
+
+impl<F> Key<F>
+where
+    F: FloatPolicy,
+{
+    /// Construct a sequence, using a compact representation if all elements
+    /// are integers or floats of the same width.
+    ///
+    /// Sequences of [Float::F16] are never compacted, since [FloatSeq] has no
+    /// variant for them.
+    pub(crate) fn seq(vec: Vec<Key<F>>) -> Self {
+        if let Some(seq) = IntegerSeq::from_keys(&vec) {
+            return Key::IntegerSeq(seq);
+        }
+
+        if let Some(seq) = FloatSeq::from_keys(&vec) {
+            return Key::FloatSeq(seq);
+        }
+
+        Key::Seq(vec.into())
+    }
+
+    /// Access the elements of any kind of sequence, expanding compact
+    /// sequences into individual keys.
+    pub(crate) fn seq_elements(&self) -> Option<SeqElements<'_, F>> {
+        match self {
+            Key::Seq(seq) => Some(SeqElements::Borrowed(seq)),
+            Key::IntegerSeq(seq) => {
+                Some(SeqElements::Owned(seq.iter().map(Key::Integer).collect()))
+            }
+            Key::FloatSeq(seq) => Some(SeqElements::Owned(seq.iter().map(Key::Float).collect())),
+            _ => None,
+        }
+    }
+
+    /// Compare two keys the same way as [Ord] does, which is available for
+    /// every float policy since the floats it stores are always ordered.
+    pub(crate) fn total_cmp(&self, other: &Self) -> cmp::Ordering {
+        match (self, other) {
+            (Key::Bool(a), Key::Bool(b)) => a.cmp(b),
+            (Key::Integer(a), Key::Integer(b)) => a.cmp(b),
+            (Key::Float(a), Key::Float(b)) => match (a, b) {
+                (Float::F32(a), Float::F32(b)) => a.cmp(b),
+                (Float::F64(a), Float::F64(b)) => a.cmp(b),
+                (Float::F16(a), Float::F16(b)) => a.cmp(b),
+                (a, b) => float_variant_index(a).cmp(&float_variant_index(b)),
+            },
+            (Key::Bytes(a), Key::Bytes(b)) => a.cmp(b),
+            (Key::Char(a), Key::Char(b)) => a.cmp(b),
+            (Key::String(a), Key::String(b)) => a.cmp(b),
+            (Key::Seq(a), Key::Seq(b)) => {
+                for (a, b) in a.iter().zip(b.iter()) {
+                    match a.total_cmp(b) {
+                        cmp::Ordering::Equal => {}
+                        ordering => return ordering,
+                    }
+                }
+
+                a.len().cmp(&b.len())
+            }
+            (Key::Map(a), Key::Map(b)) => {
+                for ((a, x), (b, y)) in a.iter().zip(b.iter()) {
+                    match a.total_cmp(b).then_with(|| x.total_cmp(y)) {
+                        cmp::Ordering::Equal => {}
+                        ordering => return ordering,
+                    }
+                }
+
+                a.len().cmp(&b.len())
+            }
+            (Key::IntegerSeq(a), Key::IntegerSeq(b)) => a.cmp(b),
+            (Key::FloatSeq(a), Key::FloatSeq(b)) => match (a, b) {
+                (FloatSeq::F32(a), FloatSeq::F32(b)) => a.cmp(b),
+                (FloatSeq::F64(a), FloatSeq::F64(b)) => a.cmp(b),
+                (FloatSeq::F32(..), FloatSeq::F64(..)) => cmp::Ordering::Less,
+                (FloatSeq::F64(..), FloatSeq::F32(..)) => cmp::Ordering::Greater,
+            },
+            (a, b) => a.variant_index().cmp(&b.variant_index()),
+        }
+    }
+
+    /// The position of the variant in declaration order.
+    const fn variant_index(&self) -> u8 {
+        match self {
+            Key::Null => 0,
+            Key::Unit => 1,
+            Key::Bool(..) => 2,
+            Key::Integer(..) => 3,
+            Key::Float(..) => 4,
+            Key::Bytes(..) => 5,
+            Key::Char(..) => 6,
+            Key::String(..) => 7,
+            Key::Seq(..) => 8,
+            Key::Map(..) => 9,
+            Key::IntegerSeq(..) => 10,
+            Key::FloatSeq(..) => 11,
+        }
+    }
+}
+
+/// The position of the variant of a float in declaration order.
+const fn float_variant_index<F>(float: &Float<F>) -> u8
+where
+    F: FloatPolicy,
+{
+    match float {
+        Float::F32(..) => 0,
+        Float::F64(..) => 1,
+        Float::F16(..) => 2,
+    }
+}
+
+/// The elements of a sequence, as returned by [Key::seq_elements].
+pub(crate) enum SeqElements<'a, F>
+where
+    F: FloatPolicy,
+{
+    Borrowed(&'a [Key<F>]),
+    Owned(Vec<Key<F>>),
+}
+
+impl<F> ops::Deref for SeqElements<'_, F>
+where
+    F: FloatPolicy,
+{
+    type Target = [Key<F>];
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            SeqElements::Borrowed(seq) => seq,
+            SeqElements::Owned(seq) => seq,
         }
     }
 }
@@ -174,12 +1354,213 @@ impl_integer_from!(U32, u32);
 impl_integer_from!(U64, u64);
 impl_integer_from!(U128, u128);
 
+macro_rules! impl_non_zero_from {
+    ($variant:ident, $for_type:ty) => {
+        impl<F> From<$for_type> for Key<F>
+        where
+            F: FloatPolicy,
+        {
+            fn from(v: $for_type) -> Key<F> {
+                Key::Integer(Integer::$variant(v.get()))
+            }
+        }
+    };
+}
+
+impl_non_zero_from!(I8, num::NonZeroI8);
+impl_non_zero_from!(I16, num::NonZeroI16);
+impl_non_zero_from!(I32, num::NonZeroI32);
+impl_non_zero_from!(I64, num::NonZeroI64);
+impl_non_zero_from!(I128, num::NonZeroI128);
+impl_non_zero_from!(U8, num::NonZeroU8);
+impl_non_zero_from!(U16, num::NonZeroU16);
+impl_non_zero_from!(U32, num::NonZeroU32);
+impl_non_zero_from!(U64, num::NonZeroU64);
+impl_non_zero_from!(U128, num::NonZeroU128);
+
+/// A `usize` is always stored as [Integer::U64], regardless of the width of
+/// `usize` on the current platform.
+///
+/// This matches how `usize` is serialized, so keys built by hand and keys built
+/// through [to_key] are the same.
+///
+/// [to_key]: crate::to_key
+///
+/// # Examples
+///
+/// ```
+/// use serde_hashkey::{to_key, Integer, Key};
+///
+/// let key: Key = Key::from(42usize);
+/// assert_eq!(key, Key::Integer(Integer::U64(42)));
+/// assert_eq!(to_key(&42usize)?, key);
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+impl<F> From<usize> for Key<F>
+where
+    F: FloatPolicy,
+{
+    fn from(v: usize) -> Key<F> {
+        // NB: no supported platform has a usize wider than 64 bits.
+        Key::Integer(Integer::U64(v as u64))
+    }
+}
+
+/// An `isize` is always stored as [Integer::I64], regardless of the width of
+/// `isize` on the current platform.
+///
+/// This matches how `isize` is serialized, so keys built by hand and keys built
+/// through [to_key] are the same.
+///
+/// [to_key]: crate::to_key
+impl<F> From<isize> for Key<F>
+where
+    F: FloatPolicy,
+{
+    fn from(v: isize) -> Key<F> {
+        // NB: no supported platform has an isize wider than 64 bits.
+        Key::Integer(Integer::I64(v as i64))
+    }
+}
+
+/// A [NonZeroUsize][num::NonZeroUsize] is stored as [Integer::U64], like
+/// `usize`.
+impl<F> From<num::NonZeroUsize> for Key<F>
+where
+    F: FloatPolicy,
+{
+    fn from(v: num::NonZeroUsize) -> Key<F> {
+        Key::from(v.get())
+    }
+}
+
+/// A [NonZeroIsize][num::NonZeroIsize] is stored as [Integer::I64], like
+/// `isize`.
+impl<F> From<num::NonZeroIsize> for Key<F>
+where
+    F: FloatPolicy,
+{
+    fn from(v: num::NonZeroIsize) -> Key<F> {
+        Key::from(v.get())
+    }
+}
+
+/// A [Wrapping][num::Wrapping] value is stored as the value it wraps.
+///
+/// # Examples
+///
+/// ```
+/// use std::num::Wrapping;
+/// use serde_hashkey::{to_key, Key};
+///
+/// let key: Key = Key::from(Wrapping(42u32));
+/// assert_eq!(key, Key::from(42u32));
+/// assert_eq!(to_key(&Wrapping(42u32))?, key);
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+impl<F, T> From<num::Wrapping<T>> for Key<F>
+where
+    F: FloatPolicy,
+    Key<F>: From<T>,
+{
+    fn from(v: num::Wrapping<T>) -> Key<F> {
+        Key::from(v.0)
+    }
+}
+
 impl_from!(Key::Bool, bool);
 impl_from!(Key::Bytes, Vec<u8>);
 impl_from!(Key::String, String);
-impl_from!(Key::Seq, Vec<Key<F>>);
 impl_from!(Key::Map, Vec<(Key<F>, Key<F>)>);
 
+impl<F> From<Vec<Key<F>>> for Key<F>
+where
+    F: FloatPolicy,
+{
+    fn from(v: Vec<Key<F>>) -> Key<F> {
+        // NB: go through the same compaction as serialized sequences, so that
+        // equal sequences produce equal keys.
+        Key::seq(v)
+    }
+}
+
+impl<F> From<char> for Key<F>
+where
+    F: FloatPolicy,
+{
+    fn from(v: char) -> Key<F> {
+        Key::Char(v)
+    }
+}
+
+impl<F> From<&str> for Key<F>
+where
+    F: FloatPolicy,
+{
+    fn from(v: &str) -> Key<F> {
+        Key::String(v.into())
+    }
+}
+
+impl<F> From<Cow<'_, str>> for Key<F>
+where
+    F: FloatPolicy,
+{
+    fn from(v: Cow<'_, str>) -> Key<F> {
+        match v {
+            Cow::Borrowed(v) => Key::from(v),
+            Cow::Owned(v) => Key::from(v),
+        }
+    }
+}
+
+impl<F> From<&[u8]> for Key<F>
+where
+    F: FloatPolicy,
+{
+    fn from(v: &[u8]) -> Key<F> {
+        Key::Bytes(v.into())
+    }
+}
+
+/// A byte array is stored as [Key::Bytes], which allows byte string literals
+/// like `b"hello"` to be converted directly.
+impl<F, const N: usize> From<&[u8; N]> for Key<F>
+where
+    F: FloatPolicy,
+{
+    fn from(v: &[u8; N]) -> Key<F> {
+        Key::Bytes(v[..].into())
+    }
+}
+
+/// A byte array is stored as [Key::Bytes], see the implementation for
+/// `&[u8; N]`.
+impl<F, const N: usize> From<[u8; N]> for Key<F>
+where
+    F: FloatPolicy,
+{
+    fn from(v: [u8; N]) -> Key<F> {
+        Key::Bytes(v[..].into())
+    }
+}
+
+/// An optional value is converted the same way it's serialized, so `None` is
+/// a [Key::Null] and `Some` is the key of the contained value, unless it's a
+/// nested `None` in which case it's wrapped in a single-element sequence.
+impl<F, T> From<Option<T>> for Key<F>
+where
+    F: FloatPolicy,
+    T: Into<Key<F>>,
+{
+    fn from(v: Option<T>) -> Key<F> {
+        match v {
+            Some(v) => Key::some(v.into()),
+            None => Key::Null,
+        }
+    }
+}
+
 /// Serialize implementation for a [Key].
 ///
 /// This allows keys to be serialized immediately.
@@ -187,6 +1568,7 @@ impl_from!(Key::Map, Vec<(Key<F>, Key<F>)>);
 /// # Examples
 ///
 /// ```
+/// # #[cfg(feature = "ordered-float")] {
 /// use serde_derive::Serialize;
 /// use serde_hashkey::{Key, OrderedFloatPolicy, OrderedFloat, Float};
 ///
@@ -195,12 +1577,11 @@ impl_from!(Key::Map, Vec<(Key<F>, Key<F>)>);
 ///     key: Key<OrderedFloatPolicy>,
 /// }
 ///
-/// # fn main() -> Result<(), serde_json::Error> {
 /// let foo: String = serde_json::to_string(&Foo { key: Key::Float(Float::F64(OrderedFloat(42.42f64))) })?;
 ///
 /// assert_eq!(foo, "{\"key\":42.42}");
-/// Ok(())
 /// # }
+/// # Ok::<_, serde_json::Error>(())
 /// ```
 impl<F> ser::Serialize for Key<F>
 where
@@ -212,6 +1593,7 @@ where
         S: ser::Serializer,
     {
         match self {
+            Key::Null => serializer.serialize_none(),
             Key::Unit => serializer.serialize_unit(),
             Key::Integer(Integer::U8(v)) => serializer.serialize_u8(*v),
             Key::Integer(Integer::U16(v)) => serializer.serialize_u16(*v),
@@ -225,9 +1607,14 @@ where
             Key::Integer(Integer::I128(v)) => serializer.serialize_i128(*v),
             Key::Float(Float::F32(float)) => float.serialize(serializer),
             Key::Float(Float::F64(float)) => float.serialize(serializer),
+            Key::Float(Float::F16(v)) => float::serialize_f16::<F, _>(v, serializer),
             Key::Bytes(v) => serializer.serialize_bytes(v),
+            Key::Char(v) => serializer.serialize_char(*v),
             Key::String(v) => serializer.serialize_str(v),
             Key::Seq(v) => v.serialize(serializer),
+            Key::IntegerSeq(seq) => integer_seq!(seq, v => v.serialize(serializer)),
+            Key::FloatSeq(FloatSeq::F32(v)) => v.serialize(serializer),
+            Key::FloatSeq(FloatSeq::F64(v)) => v.serialize(serializer),
             Key::Map(m) => {
                 use self::ser::SerializeMap as _;
 
@@ -247,11 +1634,16 @@ where
 
 /// Deserialize implementation for a [Key].
 ///
-/// This allows keys to be serialized immediately.
+/// This allows keys to be serialized immediately. Keys are built using
+/// [from_deserializer_with], so floats rejected by the float policy are
+/// reported along with the path to them.
+///
+/// [from_deserializer_with]: crate::from_deserializer_with
 ///
 /// # Examples
 ///
 /// ```
+/// # #[cfg(feature = "ordered-float")] {
 /// use serde_derive::Deserialize;
 /// use serde_hashkey::{Key, OrderedFloatPolicy};
 ///
@@ -260,12 +1652,11 @@ where
 ///     key: Key<OrderedFloatPolicy>,
 /// }
 ///
-/// # fn main() -> Result<(), serde_json::Error> {
 /// let foo: Foo = serde_json::from_str("{\"key\": 42.42}")?;
 ///
 /// assert!(matches!(foo.key, Key::Float(..)));
-/// Ok(())
 /// # }
+/// # Ok::<_, serde_json::Error>(())
 /// ```
 impl<'de, F> de::Deserialize<'de> for Key<F>
 where
@@ -276,212 +1667,7 @@ where
     where
         D: de::Deserializer<'de>,
     {
-        struct ValueVisitor<F>(marker::PhantomData<F>)
-        where
-            F: FloatPolicy;
-
-        impl<'de, F> de::Visitor<'de> for ValueVisitor<F>
-        where
-            F: FloatPolicy,
-        {
-            type Value = Key<F>;
-
-            fn expecting(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
-                fmt.write_str("any valid key")
-            }
-
-            #[inline]
-            fn visit_str<E>(self, s: &str) -> Result<Self::Value, E>
-            where
-                E: de::Error,
-            {
-                Ok(Key::String(s.into()))
-            }
-
-            #[inline]
-            fn visit_string<E>(self, s: String) -> Result<Self::Value, E>
-            where
-                E: de::Error,
-            {
-                Ok(Key::String(s.into()))
-            }
-
-            #[inline]
-            fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
-            where
-                E: de::Error,
-            {
-                self.visit_byte_buf(v.to_owned())
-            }
-
-            #[inline]
-            fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
-            where
-                E: de::Error,
-            {
-                Ok(Key::Bytes(v.into()))
-            }
-
-            #[inline]
-            fn visit_i8<E>(self, v: i8) -> Result<Self::Value, E>
-            where
-                E: de::Error,
-            {
-                Ok(v.into())
-            }
-
-            #[inline]
-            fn visit_i16<E>(self, v: i16) -> Result<Self::Value, E>
-            where
-                E: de::Error,
-            {
-                Ok(v.into())
-            }
-
-            #[inline]
-            fn visit_i32<E>(self, v: i32) -> Result<Self::Value, E>
-            where
-                E: de::Error,
-            {
-                Ok(v.into())
-            }
-
-            #[inline]
-            fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
-            where
-                E: de::Error,
-            {
-                Ok(v.into())
-            }
-
-            #[inline]
-            fn visit_i128<E>(self, v: i128) -> Result<Self::Value, E>
-            where
-                E: de::Error,
-            {
-                Ok(v.into())
-            }
-
-            #[inline]
-            fn visit_u8<E>(self, v: u8) -> Result<Self::Value, E>
-            where
-                E: de::Error,
-            {
-                Ok(v.into())
-            }
-
-            #[inline]
-            fn visit_u16<E>(self, v: u16) -> Result<Self::Value, E>
-            where
-                E: de::Error,
-            {
-                Ok(v.into())
-            }
-
-            #[inline]
-            fn visit_u32<E>(self, v: u32) -> Result<Self::Value, E>
-            where
-                E: de::Error,
-            {
-                Ok(v.into())
-            }
-
-            #[inline]
-            fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
-            where
-                E: de::Error,
-            {
-                Ok(v.into())
-            }
-
-            #[inline]
-            fn visit_u128<E>(self, v: u128) -> Result<Self::Value, E>
-            where
-                E: de::Error,
-            {
-                Ok(v.into())
-            }
-
-            #[inline]
-            fn visit_f32<E>(self, v: f32) -> Result<Self::Value, E>
-            where
-                E: de::Error,
-            {
-                Ok(Key::Float(Float::F32(
-                    <F::F32 as FloatRepr<f32>>::serialize(v).map_err(E::custom)?,
-                )))
-            }
-
-            #[inline]
-            fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
-            where
-                E: de::Error,
-            {
-                Ok(Key::Float(Float::F64(
-                    <F::F64 as FloatRepr<f64>>::serialize(v).map_err(E::custom)?,
-                )))
-            }
-
-            #[inline]
-            fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E>
-            where
-                E: de::Error,
-            {
-                Ok(Key::Bool(v))
-            }
-
-            #[inline]
-            fn visit_none<E>(self) -> Result<Self::Value, E>
-            where
-                E: de::Error,
-            {
-                self.visit_unit()
-            }
-
-            #[inline]
-            fn visit_unit<E>(self) -> Result<Self::Value, E>
-            where
-                E: de::Error,
-            {
-                Ok(Key::Unit)
-            }
-
-            #[inline]
-            fn visit_seq<V>(self, mut visitor: V) -> Result<Self::Value, V::Error>
-            where
-                V: de::SeqAccess<'de>,
-            {
-                let mut vec = visitor
-                    .size_hint()
-                    .map(Vec::with_capacity)
-                    .unwrap_or_default();
-
-                while let Some(elem) = visitor.next_element()? {
-                    vec.push(elem);
-                }
-
-                Ok(Key::Seq(vec.into()))
-            }
-
-            #[inline]
-            fn visit_map<V>(self, mut visitor: V) -> Result<Self::Value, V::Error>
-            where
-                V: de::MapAccess<'de>,
-            {
-                let mut map = visitor
-                    .size_hint()
-                    .map(Vec::with_capacity)
-                    .unwrap_or_default();
-
-                while let Some((key, value)) = visitor.next_entry()? {
-                    map.push((key, value));
-                }
-
-                Ok(Key::Map(map.into()))
-            }
-        }
-
-        deserializer.deserialize_any(ValueVisitor::<F>(marker::PhantomData))
+        crate::ingest::from_deserializer_with(deserializer)
     }
 }
 
@@ -502,6 +1688,25 @@ mod tests {
         assert_hash::<Key<RejectFloatPolicy>>();
         assert_ord::<Key<RejectFloatPolicy>>();
 
+        assert_eq::<Key<crate::CanonicalFloatPolicy>>();
+        assert_hash::<Key<crate::CanonicalFloatPolicy>>();
+        assert_ord::<Key<crate::CanonicalFloatPolicy>>();
+        assert_eq::<Key<crate::RejectNonFinitePolicy>>();
+        assert_hash::<Key<crate::RejectNonFinitePolicy>>();
+        assert_ord::<Key<crate::RejectNonFinitePolicy>>();
+        assert_eq::<Key<crate::TotalOrderFloatPolicy>>();
+        assert_hash::<Key<crate::TotalOrderFloatPolicy>>();
+        assert_ord::<Key<crate::TotalOrderFloatPolicy>>();
+        assert_eq::<Key<crate::WidenF32Policy>>();
+        assert_hash::<Key<crate::WidenF32Policy>>();
+        assert_ord::<Key<crate::WidenF32Policy>>();
+        assert_eq::<Key<crate::QuantizedFloatPolicy<3>>>();
+        assert_hash::<Key<crate::QuantizedFloatPolicy<3>>>();
+        assert_ord::<Key<crate::QuantizedFloatPolicy<3>>>();
+        assert_eq::<Key<crate::StringFloatPolicy>>();
+        assert_hash::<Key<crate::StringFloatPolicy>>();
+        assert_ord::<Key<crate::StringFloatPolicy>>();
+
         #[cfg(feature = "ordered-float")]
         {
             use crate::OrderedFloatPolicy;
@@ -511,6 +1716,15 @@ mod tests {
             assert_ord::<Key<OrderedFloatPolicy>>();
         }
 
+        #[cfg(feature = "decimal")]
+        {
+            use crate::DecimalFloatPolicy;
+
+            assert_eq::<Key<DecimalFloatPolicy>>();
+            assert_hash::<Key<DecimalFloatPolicy>>();
+            assert_ord::<Key<DecimalFloatPolicy>>();
+        }
+
         fn assert_eq<T: std::cmp::Eq>() {}
         fn assert_hash<T: std::hash::Hash>() {}
         fn assert_ord<T: std::cmp::Ord>() {}
@@ -0,0 +1,106 @@
+//! Keys which are computed once and then reused.
+
+use std::fmt;
+use std::sync::OnceLock;
+
+use crate::error::Error;
+use crate::float::{FloatPolicy, RejectFloatPolicy};
+use crate::key::Key;
+
+/// A key which is computed the first time it's accessed, and then reused for
+/// every access after that.
+///
+/// This is intended to be stored in a `static` for keys which never change,
+/// so that they don't have to be serialized again every time they are used.
+/// If computing the key fails, the error is stored and returned on every
+/// access instead. See [static_key!](crate::static_key!) for a more convenient
+/// way to declare one.
+///
+/// # Examples
+///
+/// ```
+/// use serde_hashkey::{to_key, LazyKey};
+///
+/// static SCHEMA_VERSION: LazyKey = LazyKey::new(|| to_key(&("schema_version", 2u32)));
+///
+/// let key = SCHEMA_VERSION.get().expect("key is valid");
+/// assert_eq!(*key, to_key(&("schema_version", 2u32))?);
+/// assert!(std::ptr::eq(key, SCHEMA_VERSION.get().unwrap()));
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+pub struct LazyKey<F = RejectFloatPolicy>
+where
+    F: FloatPolicy,
+{
+    init: fn() -> Result<Key<F>, Error>,
+    cell: OnceLock<Result<Key<F>, Error>>,
+}
+
+impl<F> LazyKey<F>
+where
+    F: FloatPolicy,
+{
+    /// Construct a lazy key which is computed by calling `init` on first
+    /// access.
+    pub const fn new(init: fn() -> Result<Key<F>, Error>) -> Self {
+        Self {
+            init,
+            cell: OnceLock::new(),
+        }
+    }
+
+    /// Get the key, computing it if this is the first access.
+    ///
+    /// If several threads access the key at the same time before it has been
+    /// computed, only one of them computes it and the rest wait for it to
+    /// finish. Fails with the error raised while computing the key, which is
+    /// returned on every access.
+    pub fn get(&self) -> Result<&Key<F>, &Error> {
+        self.cell.get_or_init(self.init).as_ref()
+    }
+}
+
+impl<F> fmt::Debug for LazyKey<F>
+where
+    F: FloatPolicy + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LazyKey")
+            .field("key", &self.cell.get())
+            .finish()
+    }
+}
+
+/// Declare a key which is serialized with [to_key] once, and return a
+/// `&'static` reference to it.
+///
+/// This expands to a `static` [LazyKey], so the expression is evaluated the
+/// first time the macro is reached and can't refer to any local variables. The
+/// result is a `Result<&'static Key, &'static Error>`, and if serialization
+/// fails the same error is returned every time.
+///
+/// [to_key]: crate::to_key
+///
+/// # Examples
+///
+/// ```
+/// use serde_hashkey::{static_key, to_key, Key};
+///
+/// fn schema_version() -> &'static Key {
+///     static_key!(("schema_version", 2u32)).expect("key is valid")
+/// }
+///
+/// assert_eq!(*schema_version(), to_key(&("schema_version", 2u32))?);
+/// assert!(std::ptr::eq(schema_version(), schema_version()));
+///
+/// let error = static_key!(1.5f32).unwrap_err();
+/// assert_eq!(error.to_string(), "unsupported float f32 = 1.5");
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+#[macro_export]
+macro_rules! static_key {
+    ($value:expr) => {{
+        static KEY: $crate::LazyKey = $crate::LazyKey::new(|| $crate::to_key(&$value));
+        KEY.get()
+    }};
+}
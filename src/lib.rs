@@ -20,7 +20,7 @@
 //!
 //! ```toml
 //! [dependencies]
-//! serde-hashkey = "0.4.6"
+//! serde-hashkey = "0.5.0"
 //! ```
 //!
 //! <br>
@@ -38,6 +38,88 @@
 //! * [OrderedFloat] - the behavior when using [to_key_with_ordered_float]. The
 //!   `ordered-float` feature must be enabled to use this. The behavior is
 //!   derived from the [`ordered-float` crate].
+//! * [CanonicalFloatPolicy] - the behavior when using
+//!   [to_key_with_canonical_float]. Every NaN is treated as the same value and
+//!   `-0.0` as `0.0`, which is suitable for keys which are persisted.
+//! * [RejectNonFinitePolicy] - the behavior when using
+//!   [to_key_rejecting_non_finite]. Finite floats are accepted, but NaN and
+//!   infinity are rejected with an error.
+//! * [TotalOrderFloatPolicy] - the behavior when using
+//!   [to_key_with_total_order_float]. Floats are ordered using the
+//!   `totalOrder` predicate of IEEE 754, and every float is preserved exactly.
+//! * [WidenF32Policy] - the behavior when using [to_key_with_widened_float].
+//!   Every float is stored as an `f64`, so `f32` and `f64` values which are
+//!   equal produce equal keys.
+//! * [QuantizedFloatPolicy] - the behavior when using
+//!   [to_key_with_quantized_float]. Floats are rounded to a fixed number of
+//!   decimal places, so floats which are close to each other produce equal keys.
+//! * [StringFloatPolicy] - the behavior when using [to_key_with_string_float].
+//!   Floats are compared using the shortest string which parses back into the
+//!   same float, so keys agree with the textual form they're exported as.
+//! * [DecimalFloatPolicy] - the behavior when using [to_key_with_decimal_float].
+//!   The `decimal` feature must be enabled to use this. Floats are stored as
+//!   decimals from the [`rust_decimal` crate], so `0.1` is stored exactly.
+//!
+//! <br>
+//!
+//! ## Platform independence
+//!
+//! Keys don't depend on the platform they're built on. In particular `usize`
+//! and `isize` are always stored as an [Integer::U64] and an [Integer::I64]
+//! respectively, regardless of their width on the current platform. So keys
+//! built on 32-bit targets, such as `wasm32`, are equal to, hash the same as,
+//! and have the same [binary encoding] and [identifier] as keys built on
+//! 64-bit targets.
+//!
+//! ```
+//! use serde_derive::Serialize;
+//! use serde_hashkey::to_key;
+//!
+//! #[derive(Serialize)]
+//! struct Page {
+//!     index: usize,
+//!     offset: isize,
+//! }
+//!
+//! let key = to_key(&Page { index: 3, offset: -1 })?;
+//!
+//! assert_eq!(
+//!     key.to_bytes(),
+//!     b"\x11\x02\x0f\x05index\x0a\0\0\0\0\0\0\0\x03\x0f\x06offset\x05\xff\xff\xff\xff\xff\xff\xff\xff"
+//! );
+//! assert_eq!(key.to_id(), "l3xsrk3cl2wuqirxm754a5igil");
+//! # Ok::<_, serde_hashkey::Error>(())
+//! ```
+//!
+//! <br>
+//!
+//! ## Optional values
+//!
+//! `Some(value)` is stored like `value` is, and `None` is stored as
+//! [Key::Null]. Since that would make `Some(None)` indistinguishable from
+//! `None`, a `Some` which wraps a `None` is stored as a single-element sequence
+//! holding it. So nested options such as `Option<Option<T>>` round-trip.
+//!
+//! ```
+//! use serde_hashkey::{from_key, key, to_key};
+//!
+//! assert_eq!(to_key(&None::<Option<u32>>)?, key!(null));
+//! assert_eq!(to_key(&Some(None::<u32>))?, key!([null]));
+//! assert_eq!(to_key(&Some(Some(42u32)))?, key!(42u32));
+//!
+//! let value: Option<Option<u32>> = from_key(&key!([null]))?;
+//! assert_eq!(value, Some(None));
+//! # Ok::<_, serde_hashkey::Error>(())
+//! ```
+//!
+//! Compatibility note: earlier versions stored `Some(None)` as `null`, so keys
+//! holding a `Some` which wraps a `None` are different from the ones built by
+//! those versions. Such keys still deserialize, but into the outer `None`. All
+//! other keys are unaffected.
+//!
+//! Types which buffer their content before deserializing it, such as
+//! internally tagged and untagged enums, only see the sequence. So a `Some`
+//! which wraps a `None` doesn't round-trip when it's a field of one of them.
 //!
 //! <br>
 //!
@@ -45,6 +127,20 @@
 //!
 //! * `ordered-float` - Enables serializing floating point numbers through
 //!   behavior derived from the [`ordered-float` crate]
+//! * `decimal` - Enables serializing floating point numbers as decimals
+//!   through the [`rust_decimal` crate].
+//! * `half` - Serializes `f16` from the [`half` crate] as a half-precision
+//!   float, which produces keys that are distinct from the same `f32` values.
+//! * `miette` - Implements [`miette::Diagnostic`] for [Error], with a stable
+//!   error code for each variant and help text where applicable.
+//! * `redis` - Allows a [Key] to be used directly as a Redis key through its
+//!   stable binary encoding, see [`Key::to_bytes`].
+//! * `bytes` - Allows a [Key] to hold a shared [`bytes::Bytes`] buffer without
+//!   copying it, see [`Key::as_shared_bytes`].
+//! * `testing` - Enables the [`testing`] module, with helpers to test how
+//!   types are represented as keys.
+//! * `valuable` - Implements [`valuable::Valuable`] for [Key], so that keys can
+//!   be recorded as structured values, such as fields in [`tracing`].
 //!
 //! <br>
 //!
@@ -104,7 +200,34 @@
 //! [RejectFloatPolicy]: https://docs.rs/serde-hashkey/latest/serde_hashkey/struct.RejectFloatPolicy.html
 //! [OrderedFloat]: https://docs.rs/serde-hashkey/latest/serde_hashkey/struct.OrderedFloat.html
 //! [to_key_with_ordered_float]: https://docs.rs/serde-hashkey/latest/serde_hashkey/fn.to_key_with_ordered_float.html
+//! [CanonicalFloatPolicy]: https://docs.rs/serde-hashkey/latest/serde_hashkey/struct.CanonicalFloatPolicy.html
+//! [to_key_with_canonical_float]: https://docs.rs/serde-hashkey/latest/serde_hashkey/fn.to_key_with_canonical_float.html
+//! [RejectNonFinitePolicy]: https://docs.rs/serde-hashkey/latest/serde_hashkey/struct.RejectNonFinitePolicy.html
+//! [to_key_rejecting_non_finite]: https://docs.rs/serde-hashkey/latest/serde_hashkey/fn.to_key_rejecting_non_finite.html
+//! [TotalOrderFloatPolicy]: https://docs.rs/serde-hashkey/latest/serde_hashkey/struct.TotalOrderFloatPolicy.html
+//! [to_key_with_total_order_float]: https://docs.rs/serde-hashkey/latest/serde_hashkey/fn.to_key_with_total_order_float.html
+//! [WidenF32Policy]: https://docs.rs/serde-hashkey/latest/serde_hashkey/struct.WidenF32Policy.html
+//! [to_key_with_widened_float]: https://docs.rs/serde-hashkey/latest/serde_hashkey/fn.to_key_with_widened_float.html
+//! [QuantizedFloatPolicy]: https://docs.rs/serde-hashkey/latest/serde_hashkey/struct.QuantizedFloatPolicy.html
+//! [to_key_with_quantized_float]: https://docs.rs/serde-hashkey/latest/serde_hashkey/fn.to_key_with_quantized_float.html
+//! [StringFloatPolicy]: https://docs.rs/serde-hashkey/latest/serde_hashkey/struct.StringFloatPolicy.html
+//! [to_key_with_string_float]: https://docs.rs/serde-hashkey/latest/serde_hashkey/fn.to_key_with_string_float.html
+//! [DecimalFloatPolicy]: https://docs.rs/serde-hashkey/latest/serde_hashkey/struct.DecimalFloatPolicy.html
+//! [to_key_with_decimal_float]: https://docs.rs/serde-hashkey/latest/serde_hashkey/fn.to_key_with_decimal_float.html
 //! [`ordered-float` crate]: https://docs.rs/ordered-float/2/ordered_float/
+//! [`rust_decimal` crate]: https://docs.rs/rust_decimal/1/rust_decimal/
+//! [`half` crate]: https://docs.rs/half/2/half/
+//! [`valuable::Valuable`]: https://docs.rs/valuable/0.1/valuable/trait.Valuable.html
+//! [`tracing`]: https://docs.rs/tracing
+//! [`miette::Diagnostic`]: https://docs.rs/miette/7/miette/trait.Diagnostic.html
+//! [`Key::to_bytes`]: https://docs.rs/serde-hashkey/latest/serde_hashkey/enum.Key.html#method.to_bytes
+//! [`testing`]: https://docs.rs/serde-hashkey/latest/serde_hashkey/testing/index.html
+//! [`bytes::Bytes`]: https://docs.rs/bytes/1/bytes/struct.Bytes.html
+//! [`Key::as_shared_bytes`]: https://docs.rs/serde-hashkey/latest/serde_hashkey/enum.Key.html#method.as_shared_bytes
+//! [Integer::U64]: https://docs.rs/serde-hashkey/latest/serde_hashkey/enum.Integer.html#variant.U64
+//! [Integer::I64]: https://docs.rs/serde-hashkey/latest/serde_hashkey/enum.Integer.html#variant.I64
+//! [binary encoding]: https://docs.rs/serde-hashkey/latest/serde_hashkey/enum.Key.html#method.to_bytes
+//! [identifier]: https://docs.rs/serde-hashkey/latest/serde_hashkey/enum.Key.html#method.to_id
 
 #![deny(missing_docs)]
 #![cfg_attr(docsrs, feature(doc_cfg))]
@@ -119,24 +242,205 @@ macro_rules! cfg_ordered_float {
     }
 }
 
+macro_rules! cfg_decimal {
+    ($($item:item)*) => {
+        $(
+            #[cfg(feature = "decimal")]
+            #[cfg_attr(docsrs, doc(cfg(feature = "decimal")))]
+            $item
+        )*
+    }
+}
+
+macro_rules! cfg_half {
+    ($($item:item)*) => {
+        $(
+            #[cfg(feature = "half")]
+            #[cfg_attr(docsrs, doc(cfg(feature = "half")))]
+            $item
+        )*
+    }
+}
+
+macro_rules! cfg_valuable {
+    ($($item:item)*) => {
+        $(
+            #[cfg(feature = "valuable")]
+            #[cfg_attr(docsrs, doc(cfg(feature = "valuable")))]
+            $item
+        )*
+    }
+}
+
+macro_rules! cfg_redis {
+    ($($item:item)*) => {
+        $(
+            #[cfg(feature = "redis")]
+            #[cfg_attr(docsrs, doc(cfg(feature = "redis")))]
+            $item
+        )*
+    }
+}
+
+macro_rules! cfg_bytes {
+    ($($item:item)*) => {
+        $(
+            #[cfg(feature = "bytes")]
+            #[cfg_attr(docsrs, doc(cfg(feature = "bytes")))]
+            $item
+        )*
+    }
+}
+
+macro_rules! cfg_testing {
+    ($($item:item)*) => {
+        $(
+            #[cfg(feature = "testing")]
+            #[cfg_attr(docsrs, doc(cfg(feature = "testing")))]
+            $item
+        )*
+    }
+}
+
+macro_rules! cfg_miette {
+    ($($item:item)*) => {
+        $(
+            #[cfg(feature = "miette")]
+            #[cfg_attr(docsrs, doc(cfg(feature = "miette")))]
+            $item
+        )*
+    }
+}
+
+mod access;
+mod arith;
+mod binary;
+mod bytes;
+mod canonical;
+mod check;
+mod clone;
+mod cmp;
+mod collect;
+mod config;
+mod convert;
 mod de;
+mod eq;
 mod error;
+mod filter;
+mod flatten;
 mod float;
+mod hash;
+mod hooks;
+mod id;
+mod index;
+mod ingest;
+mod intern;
+mod iter;
 mod key;
+mod lazy;
+mod macros;
+mod normalized;
+mod numeric;
+mod option;
+mod owned;
+mod partial;
+mod path;
+mod policy;
+pub mod portable_paths;
+mod query;
+mod rename;
+mod retain;
+mod schema;
+mod select;
+mod seq;
 mod ser;
+mod set;
+mod transform;
+mod unordered;
+mod walk;
+cfg_miette! {
+    mod miette;
+}
+cfg_redis! {
+    mod redis;
+}
+cfg_testing! {
+    pub mod testing;
+}
+cfg_valuable! {
+    mod valuable;
+}
 
 #[doc(inline)]
-pub use crate::de::from_key;
+pub use crate::bytes::Bytes;
+#[doc(inline)]
+pub use crate::canonical::{to_key_with_canonical_integers, to_key_with_compact_integers};
+#[doc(inline)]
+pub use crate::check::check_key;
+#[doc(inline)]
+pub use crate::config::{EnumRepr, KeyConfig};
+#[doc(inline)]
+pub use crate::de::{from_key, from_key_tagged, from_key_with_config, KeyDeserializer};
+#[doc(inline)]
+pub use crate::eq::value_eq_key;
 #[doc(inline)]
-pub use crate::error::{Error, Result};
+pub use crate::error::{Error, FloatWidth, Result};
+#[doc(inline)]
+pub use crate::filter::{to_key_filtered, FieldFilter};
 
 cfg_ordered_float! {
-    pub use crate::float::{to_key_with_ordered_float, OrderedFloat, OrderedFloatPolicy};
+    pub use crate::float::{
+        hash_value_with_ordered_float, to_key_with_ordered_float, OrderedFloat, OrderedFloatPolicy,
+    };
+}
+
+cfg_decimal! {
+    pub use crate::float::{to_key_with_decimal_float, DecimalFloat, DecimalFloatPolicy};
 }
 
-pub use crate::float::{FloatPolicy, FloatRepr, NeverFloat, RejectFloatPolicy};
+pub use crate::float::{
+    to_key_rejecting_non_finite, to_key_with_canonical_float, to_key_with_quantized_float,
+    to_key_with_string_float, to_key_with_total_order_float, to_key_with_widened_float,
+    CanonicalFloat, CanonicalFloatPolicy, FiniteFloat, FloatPolicy, FloatRepr, NeverFloat,
+    QuantizedFloat, QuantizedFloatPolicy, RejectFloatPolicy, RejectNonFinitePolicy, StringFloat,
+    StringFloatPolicy, TotalOrderFloat, TotalOrderFloatPolicy, WidenF32Policy,
+};
 
 #[doc(inline)]
-pub use crate::key::{Float, Integer, Key};
+pub use crate::hash::{hash_value, hash_value_with};
+#[doc(inline)]
+pub use crate::hooks::{Hooks, NoHooks, Segment};
+#[doc(inline)]
+pub use crate::id::{IdAlphabet, IdConfig};
+#[doc(inline)]
+pub use crate::ingest::{from_deserializer, from_deserializer_with};
+#[doc(inline)]
+pub use crate::iter::Iter;
+#[doc(inline)]
+pub use crate::key::{Float, FloatSeq, FloatSeqIter, Integer, IntegerSeq, IntegerSeqIter, Key};
+#[doc(inline)]
+pub use crate::lazy::LazyKey;
+#[doc(inline)]
+pub use crate::normalized::{to_key_normalized, NormalizedKey};
+#[doc(inline)]
+pub use crate::numeric::NumericKey;
+#[doc(inline)]
+pub use crate::owned::from_key_owned;
+#[doc(inline)]
+pub use crate::partial::{to_key_with_partial, PartialError};
+#[doc(inline)]
+pub use crate::path::{KeyPath, Leaves, PathSegment};
+#[doc(inline)]
+pub use crate::query::{QueryConfig, QueryNesting};
+#[doc(inline)]
+pub use crate::schema::{FieldSchema, KeySchema, SchemaViolation};
+#[doc(inline)]
+pub use crate::ser::{
+    to_key, to_key_from_iter, to_key_from_pairs, to_key_tagged, to_key_transparent, to_key_with,
+    to_key_with_config, KeySerializer, SerializeMap, SerializeStructVariant, SerializeTupleVariant,
+    SerializeVec,
+};
+#[doc(inline)]
+pub use crate::unordered::UnorderedKey;
 #[doc(inline)]
-pub use crate::ser::to_key;
+pub use crate::walk::Walk;
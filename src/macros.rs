@@ -0,0 +1,161 @@
+//! Macros for constructing keys.
+
+/// Construct a [Key] from a JSON-like literal.
+///
+/// * `null` is a [Key::Null], which is what `None` serializes as.
+/// * `()` is a [Key::Unit].
+/// * `[...]` is a sequence, with elements separated by commas.
+/// * `{...}` is a map, with entries written as `key: value` and separated by
+///   commas. Keys which are more than a single token, like a negative number
+///   or an arbitrary expression, have to be wrapped in parenthesis.
+/// * Anything else is an expression which is converted into a key through
+///   [From], such as string literals, byte string literals, characters,
+///   integers, booleans, or other keys.
+///
+/// Trailing commas are allowed. The key produced is the same as the one
+/// produced by serializing the equivalent value, so sequences of integers of
+/// the same width are stored as a [Key::IntegerSeq]. Since `From` is
+/// implemented for fixed-width integers, integer literals without a suffix
+/// are `i32`.
+///
+/// This produces a key which uses [RejectFloatPolicy], so it can't contain
+/// floats. See [key_float!] for a version which supports them.
+///
+/// [Key]: crate::Key
+/// [Key::Null]: crate::Key::Null
+/// [Key::Unit]: crate::Key::Unit
+/// [Key::IntegerSeq]: crate::Key::IntegerSeq
+/// [RejectFloatPolicy]: crate::RejectFloatPolicy
+/// [key_float!]: crate::key_float!
+///
+/// # Examples
+///
+/// ```
+/// use serde_derive::Serialize;
+/// use serde_hashkey::{key, to_key};
+///
+/// #[derive(Serialize)]
+/// struct Author {
+///     id: u32,
+///     name: &'static str,
+///     tags: Vec<&'static str>,
+///     avatar: Option<()>,
+/// }
+///
+/// let id = 42u32;
+///
+/// let key = key!({
+///     "id": id,
+///     "name": "Noah",
+///     "tags": ["birds", "flocking",],
+///     "avatar": null,
+/// });
+///
+/// assert_eq!(key, to_key(&Author {
+///     id,
+///     name: "Noah",
+///     tags: vec!["birds", "flocking"],
+///     avatar: None,
+/// })?);
+///
+/// assert_eq!(key!([1u8, 2u8, 3u8]), to_key(&vec![1u8, 2, 3])?);
+/// assert_eq!(key!({ (id + 1): b"bytes" })["missing"], key!(()));
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+#[macro_export]
+macro_rules! key {
+    ($($tt:tt)+) => {{
+        let key: $crate::Key = $crate::__key!($($tt)+);
+        key
+    }};
+}
+
+cfg_ordered_float! {
+    /// Construct a [Key] which can contain floats from a JSON-like literal.
+    ///
+    /// This is the same as [key!], except that it produces a key which uses
+    /// [OrderedFloatPolicy] so float literals can be used.
+    ///
+    /// [Key]: crate::Key
+    /// [key!]: crate::key!
+    /// [OrderedFloatPolicy]: crate::OrderedFloatPolicy
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::{key_float, to_key_with_ordered_float};
+    ///
+    /// let key = key_float!({ "rating": 4.5, "votes": [1.0f32, 2.5f32] });
+    ///
+    /// let expected = to_key_with_ordered_float(&(
+    ///     ("rating", 4.5f64),
+    ///     ("votes", vec![1.0f32, 2.5]),
+    /// ))?;
+    ///
+    /// assert_eq!(key["rating"], expected[0][1]);
+    /// assert_eq!(key["votes"], to_key_with_ordered_float(&vec![1.0f32, 2.5])?);
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    #[macro_export]
+    macro_rules! key_float {
+        ($($tt:tt)+) => {{
+            let key: $crate::Key<$crate::OrderedFloatPolicy> = $crate::__key!($($tt)+);
+            key
+        }};
+    }
+}
+
+/// Implementation detail of [key!] and [key_float!], which produces a key with
+/// an inferred float policy.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __key {
+    // Munch the elements of a sequence, one token at a time.
+    (@seq [$($done:expr,)*] ($($element:tt)+) , $($rest:tt)*) => {
+        $crate::__key!(@seq [$($done,)* $crate::__key!($($element)+),] () $($rest)*)
+    };
+    (@seq [$($done:expr,)*] ($($element:tt)*) $next:tt $($rest:tt)*) => {
+        $crate::__key!(@seq [$($done,)*] ($($element)* $next) $($rest)*)
+    };
+    (@seq [$($done:expr,)*] ($($element:tt)+)) => {
+        $crate::__key!(@seq [$($done,)* $crate::__key!($($element)+),] ())
+    };
+    (@seq [$($done:expr,)*] ()) => {
+        <$crate::Key<_> as ::std::iter::FromIterator<$crate::Key<_>>>::from_iter(
+            ::std::vec![$($done,)*]
+        )
+    };
+
+    // Munch the entries of a map, one token at a time.
+    (@map [$($done:expr,)*] $key:tt : $($rest:tt)+) => {
+        $crate::__key!(@value [$($done,)*] $key () $($rest)+)
+    };
+    (@map [$($done:expr,)*]) => {
+        $crate::Key::Map(::std::vec![$($done,)*].into())
+    };
+    (@value [$($done:expr,)*] $key:tt ($($value:tt)+) , $($rest:tt)*) => {
+        $crate::__key!(@map [$($done,)* ($crate::__key!($key), $crate::__key!($($value)+)),] $($rest)*)
+    };
+    (@value [$($done:expr,)*] $key:tt ($($value:tt)*) $next:tt $($rest:tt)*) => {
+        $crate::__key!(@value [$($done,)*] $key ($($value)* $next) $($rest)*)
+    };
+    (@value [$($done:expr,)*] $key:tt ($($value:tt)+)) => {
+        $crate::__key!(@map [$($done,)* ($crate::__key!($key), $crate::__key!($($value)+)),])
+    };
+
+    (null) => {
+        $crate::Key::Null
+    };
+    (()) => {
+        $crate::Key::Unit
+    };
+    ([$($tt:tt)*]) => {
+        $crate::__key!(@seq [] () $($tt)*)
+    };
+    ({$($tt:tt)*}) => {
+        $crate::__key!(@map [] $($tt)*)
+    };
+    ($other:expr) => {
+        $crate::Key::from($other)
+    };
+}
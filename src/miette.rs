@@ -0,0 +1,82 @@
+//! Integration with the [`miette`] crate.
+//!
+//! [`miette`]: https://docs.rs/miette
+
+use std::fmt;
+
+use miette7::Diagnostic;
+
+use crate::error::Error;
+
+/// An [Error] is a diagnostic with a stable error code per variant, like
+/// `serde_hashkey::unsupported_type`, and help text where there is something
+/// useful to suggest.
+///
+/// # Examples
+///
+/// ```
+/// use miette7::Diagnostic;
+/// use serde_hashkey::to_key;
+///
+/// let error = to_key(&1.5f64).unwrap_err();
+/// assert_eq!(error.code().unwrap().to_string(), "serde_hashkey::unsupported_float");
+/// assert!(error.help().is_some());
+/// ```
+impl Diagnostic for Error {
+    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        let code = match self {
+            Error::Unexpected(..) => "serde_hashkey::unexpected",
+            Error::UnsupportedType(..) => "serde_hashkey::unsupported_type",
+            Error::UnsupportedFloat { .. } => "serde_hashkey::unsupported_float",
+            Error::NonFiniteFloat { .. } => "serde_hashkey::non_finite_float",
+            Error::FloatRejected { .. } => "serde_hashkey::float_rejected",
+            Error::UnexpectedVariant(..) => "serde_hashkey::unexpected_variant",
+            Error::Custom(..) => "serde_hashkey::custom",
+            Error::MissingValue => "serde_hashkey::missing_value",
+            Error::InvalidLength => "serde_hashkey::invalid_length",
+            Error::EmptySeparator => "serde_hashkey::empty_separator",
+            Error::SeparatorInKey(..) => "serde_hashkey::separator_in_key",
+            Error::ConflictingPath(..) => "serde_hashkey::conflicting_path",
+            Error::MissingPath(..) => "serde_hashkey::missing_path",
+            Error::InvalidQuery(..) => "serde_hashkey::invalid_query",
+            Error::InvalidBytes(..) => "serde_hashkey::invalid_bytes",
+            Error::IntegerOverflow(..) => "serde_hashkey::integer_overflow",
+            Error::InvalidInteger(..) => "serde_hashkey::invalid_integer",
+            Error::TransparentEnum(..) => "serde_hashkey::transparent_enum",
+            Error::DuplicateKey(..) => "serde_hashkey::duplicate_key",
+        };
+
+        Some(Box::new(code))
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        let help = match self {
+            Error::FloatRejected { error, .. } => return error.help(),
+            Error::UnsupportedFloat { .. } => {
+                "floats are rejected by the default float policy, use `to_key_with_ordered_float` (requires the `ordered-float` feature) to include them in keys"
+            }
+            Error::NonFiniteFloat { .. } => {
+                "NaN and infinity are rejected by the float policy, check where the value was produced"
+            }
+            Error::EmptySeparator => "use a non-empty separator, like `.`",
+            Error::SeparatorInKey(..) => {
+                "use a separator which does not appear in any map key"
+            }
+            Error::ConflictingPath(..) => {
+                "a path can't both hold a value and contain nested entries"
+            }
+            Error::InvalidBytes(..) => {
+                "only bytes produced by `Key::to_bytes` can be decoded with `Key::from_bytes`"
+            }
+            Error::TransparentEnum(..) => {
+                "transparent newtype variants are one-way, deserialize the content of the variant instead"
+            }
+            Error::DuplicateKey(..) => {
+                "check the `Serialize` implementation of the map, and the `Eq` and `Hash` implementations of its keys"
+            }
+            _ => return None,
+        };
+
+        Some(Box::new(help))
+    }
+}
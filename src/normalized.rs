@@ -0,0 +1,159 @@
+//! Keys which are known to be normalized.
+
+use std::ops;
+
+use serde::ser;
+
+use crate::error::Error;
+use crate::float::{FloatPolicy, RejectFloatPolicy};
+use crate::key::Key;
+use crate::ser::to_key_with_policy;
+
+/// A [Key] which is known to be [normalized][Key::normalize].
+///
+/// Every map contained in a normalized key is sorted, so two keys which only
+/// differ in the order of their map entries are equal and hash the same once
+/// they are normalized. Using this type instead of [Key] in a collection
+/// makes sure that keys which haven't been normalized can't be mixed in by
+/// accident.
+///
+/// A normalized key can only be constructed by normalizing a key, either
+/// through [Key::into_normalized], [NormalizedKey::new], its [From]
+/// implementation, or [to_key_normalized]. It dereferences to the [Key] it
+/// wraps, and can be converted back into one for free.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+///
+/// use serde_hashkey::{Key, NormalizedKey};
+///
+/// let a: Key = Key::Map(vec![(Key::from(1u32), Key::from(true)), (Key::from(2u32), Key::from(false))].into());
+/// let b: Key = Key::Map(vec![(Key::from(2u32), Key::from(false)), (Key::from(1u32), Key::from(true))].into());
+/// assert_ne!(a, b);
+///
+/// let mut cache = HashMap::new();
+/// cache.insert(a.into_normalized(), 42);
+/// assert_eq!(cache.get(&NormalizedKey::new(b)), Some(&42));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NormalizedKey<F = RejectFloatPolicy>(Key<F>)
+where
+    F: FloatPolicy;
+
+impl<F> NormalizedKey<F>
+where
+    F: FloatPolicy + Ord,
+{
+    /// Normalize the given key.
+    pub fn new(key: Key<F>) -> Self {
+        NormalizedKey(key.normalize())
+    }
+}
+
+impl<F> NormalizedKey<F>
+where
+    F: FloatPolicy,
+{
+    /// Access the wrapped key.
+    pub fn into_inner(self) -> Key<F> {
+        self.0
+    }
+}
+
+impl<F> Key<F>
+where
+    F: FloatPolicy + Ord,
+{
+    /// Normalize this key, wrapping it in a [NormalizedKey] to indicate that
+    /// it has been normalized.
+    ///
+    /// See [Key::normalize] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::Key;
+    ///
+    /// let key: Key = Key::Map(vec![(Key::from(2u32), Key::Unit), (Key::from(1u32), Key::Unit)].into());
+    /// let normalized = key.clone().into_normalized();
+    ///
+    /// assert_eq!(*normalized, key.normalize());
+    /// ```
+    pub fn into_normalized(self) -> NormalizedKey<F> {
+        NormalizedKey::new(self)
+    }
+}
+
+impl<F> ops::Deref for NormalizedKey<F>
+where
+    F: FloatPolicy,
+{
+    type Target = Key<F>;
+
+    #[inline]
+    fn deref(&self) -> &Key<F> {
+        &self.0
+    }
+}
+
+impl<F> AsRef<Key<F>> for NormalizedKey<F>
+where
+    F: FloatPolicy,
+{
+    #[inline]
+    fn as_ref(&self) -> &Key<F> {
+        &self.0
+    }
+}
+
+/// A key is normalized when it is converted.
+impl<F> From<Key<F>> for NormalizedKey<F>
+where
+    F: FloatPolicy + Ord,
+{
+    #[inline]
+    fn from(key: Key<F>) -> Self {
+        NormalizedKey::new(key)
+    }
+}
+
+impl<F> From<NormalizedKey<F>> for Key<F>
+where
+    F: FloatPolicy,
+{
+    #[inline]
+    fn from(key: NormalizedKey<F>) -> Self {
+        key.0
+    }
+}
+
+/// Serialize the given value to a [NormalizedKey].
+///
+/// This is the same as normalizing the key produced by [to_key].
+///
+/// [to_key]: crate::to_key
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::{BTreeMap, HashMap};
+///
+/// use serde_hashkey::to_key_normalized;
+///
+/// let mut a = HashMap::new();
+/// a.insert("b", 2u32);
+/// a.insert("a", 1u32);
+///
+/// let b = a.iter().map(|(k, v)| (*k, *v)).collect::<BTreeMap<_, _>>();
+///
+/// assert_eq!(to_key_normalized(&a)?, to_key_normalized(&b)?);
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+pub fn to_key_normalized<T>(value: &T) -> Result<NormalizedKey, Error>
+where
+    T: ?Sized + ser::Serialize,
+{
+    Ok(NormalizedKey::new(to_key_with_policy(value)?))
+}
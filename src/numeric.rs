@@ -0,0 +1,261 @@
+//! Comparison and hashing of keys by numeric value.
+
+use std::hash::{Hash, Hasher};
+
+use crate::float::{self, FloatPolicy, RejectFloatPolicy};
+use crate::key::{Float, Integer, Key};
+
+/// A wrapper around a [Key] which compares and hashes numbers by their
+/// mathematical value.
+///
+/// Under this wrapper integers of different widths and floats which hold
+/// exactly the same value are considered equal and hash identically. So `1u8`,
+/// `1u64`, `1.0f32` and `1.0f64` are all the same number, while `0.5` or a float
+/// which is merely close to an integer is not equal to any integer.
+///
+/// Some further details:
+/// * A float is only equal to an integer if it is integral and converts to it
+///   exactly. For example `2^53 + 1` is not equal to any `f64`, since it is
+///   not exactly representable as one.
+/// * Negative zero is equal to zero.
+/// * All NaNs are equal to each other, but not to anything else.
+///
+/// Only numbers are affected, all other parts of the key compare as they
+/// normally would. In particular map entries still compare in order.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "ordered-float")] {
+/// use serde_hashkey::{to_key_with_ordered_float, NumericKey};
+///
+/// let a = to_key_with_ordered_float(&1u32)?;
+/// let b = to_key_with_ordered_float(&1.0f64)?;
+///
+/// assert_ne!(a, b);
+/// assert_eq!(NumericKey(a), NumericKey(b));
+/// # }
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct NumericKey<F = RejectFloatPolicy>(pub Key<F>)
+where
+    F: FloatPolicy;
+
+impl<F> NumericKey<F>
+where
+    F: FloatPolicy,
+{
+    /// Access the wrapped key.
+    pub fn into_inner(self) -> Key<F> {
+        self.0
+    }
+}
+
+impl<F> From<Key<F>> for NumericKey<F>
+where
+    F: FloatPolicy,
+{
+    fn from(key: Key<F>) -> Self {
+        NumericKey(key)
+    }
+}
+
+impl<F> PartialEq for NumericKey<F>
+where
+    F: FloatPolicy,
+{
+    fn eq(&self, other: &Self) -> bool {
+        eq(&self.0, &other.0)
+    }
+}
+
+impl<F> Eq for NumericKey<F> where F: FloatPolicy {}
+
+impl<F> Hash for NumericKey<F>
+where
+    F: FloatPolicy,
+{
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        hash(&self.0, state);
+    }
+}
+
+/// The canonical form of a number, used for both equality and hashing.
+#[derive(Debug, PartialEq, Eq, Hash)]
+enum Number {
+    /// An integral value. Zero is never negative.
+    Integer { negative: bool, magnitude: u128 },
+    /// Any other float, including infinities.
+    Float(u64),
+    /// Not a number.
+    NaN,
+}
+
+impl Number {
+    fn from_integer(integer: &Integer) -> Self {
+        let (negative, magnitude) = match *integer {
+            Integer::I8(v) => (v < 0, u128::from(v.unsigned_abs())),
+            Integer::I16(v) => (v < 0, u128::from(v.unsigned_abs())),
+            Integer::I32(v) => (v < 0, u128::from(v.unsigned_abs())),
+            Integer::I64(v) => (v < 0, u128::from(v.unsigned_abs())),
+            Integer::I128(v) => (v < 0, v.unsigned_abs()),
+            Integer::U8(v) => (false, u128::from(v)),
+            Integer::U16(v) => (false, u128::from(v)),
+            Integer::U32(v) => (false, u128::from(v)),
+            Integer::U64(v) => (false, u128::from(v)),
+            Integer::U128(v) => (false, v),
+        };
+
+        Number::Integer {
+            negative,
+            magnitude,
+        }
+    }
+
+    fn from_float(value: f64) -> Self {
+        // 2^128, the first magnitude which doesn't fit in an u128.
+        const LIMIT: f64 = 340_282_366_920_938_463_463_374_607_431_768_211_456.0;
+
+        if value.is_nan() {
+            return Number::NaN;
+        }
+
+        let magnitude = value.abs();
+
+        if magnitude.fract() == 0.0 && magnitude < LIMIT {
+            // NB: the conversion is exact since the value is integral and in
+            // range.
+            let magnitude = magnitude as u128;
+
+            return Number::Integer {
+                negative: value < 0.0 && magnitude != 0,
+                magnitude,
+            };
+        }
+
+        Number::Float(value.to_bits())
+    }
+}
+
+/// Get the canonical number of an integer or float key.
+///
+/// Returns `None` for floats which can't be read out of their representation,
+/// those are compared by representation instead.
+fn number<F>(key: &Key<F>) -> Option<Number>
+where
+    F: FloatPolicy,
+{
+    match key {
+        Key::Integer(integer) => Some(Number::from_integer(integer)),
+        Key::Float(value) => float::to_f64(value).map(Number::from_float),
+        _ => None,
+    }
+}
+
+fn eq<F>(a: &Key<F>, b: &Key<F>) -> bool
+where
+    F: FloatPolicy,
+{
+    match (a, b) {
+        (Key::Integer(..) | Key::Float(..), Key::Integer(..) | Key::Float(..)) => {
+            match (number(a), number(b), a, b) {
+                (Some(a), Some(b), _, _) => a == b,
+                (None, None, Key::Float(Float::F32(a)), Key::Float(Float::F32(b))) => a == b,
+                (None, None, Key::Float(Float::F64(a)), Key::Float(Float::F64(b))) => a == b,
+                (None, None, Key::Float(Float::F16(a)), Key::Float(Float::F16(b))) => a == b,
+                _ => false,
+            }
+        }
+        (Key::Null, Key::Null) | (Key::Unit, Key::Unit) => true,
+        (Key::Bool(a), Key::Bool(b)) => a == b,
+        (Key::Bytes(a), Key::Bytes(b)) => a == b,
+        (Key::Char(a), Key::Char(b)) => a == b,
+        (Key::String(a), Key::String(b)) => a == b,
+        (
+            Key::Seq(..) | Key::IntegerSeq(..) | Key::FloatSeq(..),
+            Key::Seq(..) | Key::IntegerSeq(..) | Key::FloatSeq(..),
+        ) => match (a.seq_elements(), b.seq_elements()) {
+            (Some(a), Some(b)) => {
+                a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| eq(a, b))
+            }
+            _ => false,
+        },
+        (Key::Map(a), Key::Map(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .zip(b.iter())
+                    .all(|((ak, av), (bk, bv))| eq(ak, bk) && eq(av, bv))
+        }
+        _ => false,
+    }
+}
+
+fn hash<F, H>(key: &Key<F>, state: &mut H)
+where
+    F: FloatPolicy,
+    H: Hasher,
+{
+    match key {
+        Key::Null => state.write_u8(8),
+        Key::Unit => state.write_u8(0),
+        Key::Bool(v) => {
+            state.write_u8(1);
+            v.hash(state);
+        }
+        Key::Integer(integer) => {
+            state.write_u8(2);
+            Number::from_integer(integer).hash(state);
+        }
+        Key::Float(value) => match float::to_f64(value) {
+            Some(value) => {
+                state.write_u8(2);
+                Number::from_float(value).hash(state);
+            }
+            None => {
+                state.write_u8(7);
+
+                match value {
+                    Float::F32(v) => v.hash(state),
+                    Float::F64(v) => v.hash(state),
+                    Float::F16(v) => v.hash(state),
+                }
+            }
+        },
+        Key::Bytes(v) => {
+            state.write_u8(3);
+            v.hash(state);
+        }
+        Key::Char(v) => {
+            state.write_u8(9);
+            v.hash(state);
+        }
+        Key::String(v) => {
+            state.write_u8(4);
+            v.hash(state);
+        }
+        Key::Seq(..) | Key::IntegerSeq(..) | Key::FloatSeq(..) => {
+            state.write_u8(5);
+
+            if let Some(v) = key.seq_elements() {
+                state.write_usize(v.len());
+
+                for v in v.iter() {
+                    hash(v, state);
+                }
+            }
+        }
+        Key::Map(v) => {
+            state.write_u8(6);
+            state.write_usize(v.len());
+
+            for (k, v) in v.iter() {
+                hash(k, state);
+                hash(v, state);
+            }
+        }
+    }
+}
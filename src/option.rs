@@ -0,0 +1,343 @@
+//! The representation of optional values.
+//!
+//! `Some` is transparent, so `Some(value)` is stored like `value` is. The
+//! exception is when the value is `None` or a `Some` wrapping one, in which
+//! case it's wrapped in a single-element sequence so that `Some(None)` can be
+//! told apart from `None`:
+//!
+//! * `None` is stored as `null`.
+//! * `Some(None)` is stored as `[null]`.
+//! * `Some(Some(None))` is stored as `[[null]]`.
+//! * `Some(Some(Some(42)))` is stored as `42`.
+
+use std::fmt;
+
+use serde::ser::{self, Serialize};
+
+use crate::float::FloatPolicy;
+use crate::key::Key;
+
+impl<F> Key<F>
+where
+    F: FloatPolicy,
+{
+    /// Construct the key of `Some(value)` out of the key of the value.
+    pub(crate) fn some(value: Self) -> Self {
+        if value.is_nested_none() {
+            Key::Seq(Box::new([value]))
+        } else {
+            value
+        }
+    }
+
+    /// Test if the key is `null`, or the key of a `Some` which wraps one.
+    pub(crate) fn is_nested_none(&self) -> bool {
+        let mut key = self;
+
+        loop {
+            match key {
+                Key::Null => return true,
+                Key::Seq(seq) => match &seq[..] {
+                    [value] => key = value,
+                    _ => return false,
+                },
+                _ => return false,
+            }
+        }
+    }
+
+    /// If the key is the key of a `Some` which wraps a nested `None`, get the
+    /// key of the wrapped value.
+    pub(crate) fn unwrap_some(&self) -> Option<&Self> {
+        match self {
+            Key::Seq(seq) if self.is_nested_none() => seq.first(),
+            _ => None,
+        }
+    }
+
+    /// Convert the key of a `Some` into the key of the value it wraps, see
+    /// [Key::unwrap_some].
+    pub(crate) fn into_some_value(self) -> Self {
+        match self {
+            Key::Seq(seq) if seq.len() == 1 && seq[0].is_nested_none() => {
+                seq.into_vec().swap_remove(0)
+            }
+            key => key,
+        }
+    }
+}
+
+/// Test if the given value serializes into `null` or the key of a `Some`
+/// which wraps one, using the default configuration.
+///
+/// Only the parts of the value which are needed to tell are serialized, and
+/// values which fail to serialize are reported as not being `None`.
+pub(crate) fn is_nested_none<T>(value: &T) -> bool
+where
+    T: ?Sized + Serialize,
+{
+    value.serialize(Probe).unwrap_or(false)
+}
+
+/// Serializer which tests if a value serializes into a nested `None`.
+struct Probe;
+
+/// Error raised by [Probe], which is never inspected.
+#[derive(Debug)]
+struct ProbeError;
+
+impl fmt::Display for ProbeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("probe error")
+    }
+}
+
+impl std::error::Error for ProbeError {}
+
+impl ser::Error for ProbeError {
+    fn custom<T>(_: T) -> Self
+    where
+        T: fmt::Display,
+    {
+        ProbeError
+    }
+}
+
+impl ser::Serializer for Probe {
+    type Ok = bool;
+    type Error = ProbeError;
+
+    type SerializeSeq = ProbeSeq;
+    type SerializeTuple = ProbeSeq;
+    type SerializeTupleStruct = ProbeSeq;
+    type SerializeTupleVariant = ser::Impossible<bool, ProbeError>;
+    type SerializeMap = ser::Impossible<bool, ProbeError>;
+    type SerializeStruct = ser::Impossible<bool, ProbeError>;
+    type SerializeStructVariant = ser::Impossible<bool, ProbeError>;
+
+    fn serialize_bool(self, _: bool) -> Result<bool, ProbeError> {
+        Ok(false)
+    }
+
+    fn serialize_i8(self, _: i8) -> Result<bool, ProbeError> {
+        Ok(false)
+    }
+
+    fn serialize_i16(self, _: i16) -> Result<bool, ProbeError> {
+        Ok(false)
+    }
+
+    fn serialize_i32(self, _: i32) -> Result<bool, ProbeError> {
+        Ok(false)
+    }
+
+    fn serialize_i64(self, _: i64) -> Result<bool, ProbeError> {
+        Ok(false)
+    }
+
+    fn serialize_i128(self, _: i128) -> Result<bool, ProbeError> {
+        Ok(false)
+    }
+
+    fn serialize_u8(self, _: u8) -> Result<bool, ProbeError> {
+        Ok(false)
+    }
+
+    fn serialize_u16(self, _: u16) -> Result<bool, ProbeError> {
+        Ok(false)
+    }
+
+    fn serialize_u32(self, _: u32) -> Result<bool, ProbeError> {
+        Ok(false)
+    }
+
+    fn serialize_u64(self, _: u64) -> Result<bool, ProbeError> {
+        Ok(false)
+    }
+
+    fn serialize_u128(self, _: u128) -> Result<bool, ProbeError> {
+        Ok(false)
+    }
+
+    fn serialize_f32(self, _: f32) -> Result<bool, ProbeError> {
+        Ok(false)
+    }
+
+    fn serialize_f64(self, _: f64) -> Result<bool, ProbeError> {
+        Ok(false)
+    }
+
+    fn serialize_char(self, _: char) -> Result<bool, ProbeError> {
+        Ok(false)
+    }
+
+    fn serialize_str(self, _: &str) -> Result<bool, ProbeError> {
+        Ok(false)
+    }
+
+    fn serialize_bytes(self, _: &[u8]) -> Result<bool, ProbeError> {
+        Ok(false)
+    }
+
+    fn serialize_none(self) -> Result<bool, ProbeError> {
+        Ok(true)
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<bool, ProbeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<bool, ProbeError> {
+        Ok(false)
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<bool, ProbeError> {
+        Ok(false)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+    ) -> Result<bool, ProbeError> {
+        Ok(false)
+    }
+
+    fn serialize_newtype_struct<T>(self, _: &'static str, value: &T) -> Result<bool, ProbeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        // NB: `half::f16` is the only newtype struct which isn't transparent,
+        // and it's stored as a float.
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> Result<bool, ProbeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        Ok(false)
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, ProbeError> {
+        Ok(ProbeSeq {
+            len: 0,
+            first: false,
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, ProbeError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, ProbeError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleVariant, ProbeError> {
+        Err(ProbeError)
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, ProbeError> {
+        Err(ProbeError)
+    }
+
+    fn serialize_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStruct, ProbeError> {
+        Err(ProbeError)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStructVariant, ProbeError> {
+        Err(ProbeError)
+    }
+}
+
+/// Probes a sequence, which is a nested `None` if its only element is.
+///
+/// Only the first element is probed, the rest are counted.
+struct ProbeSeq {
+    len: usize,
+    first: bool,
+}
+
+impl ser::SerializeSeq for ProbeSeq {
+    type Ok = bool;
+    type Error = ProbeError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), ProbeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        if self.len == 0 {
+            self.first = is_nested_none(value);
+        }
+
+        self.len += 1;
+        Ok(())
+    }
+
+    fn end(self) -> Result<bool, ProbeError> {
+        Ok(self.len == 1 && self.first)
+    }
+}
+
+impl ser::SerializeTuple for ProbeSeq {
+    type Ok = bool;
+    type Error = ProbeError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), ProbeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<bool, ProbeError> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for ProbeSeq {
+    type Ok = bool;
+    type Error = ProbeError;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), ProbeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<bool, ProbeError> {
+        ser::SerializeSeq::end(self)
+    }
+}
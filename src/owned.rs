@@ -0,0 +1,103 @@
+//! Deserialization which consumes a [Key].
+
+use serde::de;
+use std::vec;
+
+use crate::config::KeyConfig;
+use crate::de::{Source, SourceDeserializer};
+use crate::error::Error;
+use crate::float::FloatPolicy;
+use crate::key::{IntegerSeq, Key};
+
+/// Deserialize the given type by consuming a [Key].
+///
+/// This behaves like [from_key], except that the storage of the key is moved
+/// into the deserialized value where possible instead of being copied. Bytes
+/// are handed over through [`visit_byte_buf`], and the elements of sequences
+/// and maps are moved out of the key as they are deserialized.
+///
+/// Strings are shared by keys, so they are handed over through [`visit_str`]
+/// and are copied at most once by the deserialized value.
+///
+/// [from_key]: crate::from_key
+/// [`visit_byte_buf`]: serde::de::Visitor::visit_byte_buf
+/// [`visit_str`]: serde::de::Visitor::visit_str
+///
+/// # Examples
+///
+/// ```
+/// use serde_derive::{Deserialize, Serialize};
+/// use serde_hashkey::{from_key_owned, to_key};
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Author {
+///     name: String,
+///     books: Vec<String>,
+/// }
+///
+/// let author = Author {
+///     name: String::from("Noah"),
+///     books: vec![String::from("Birds of a feather")],
+/// };
+///
+/// let key = to_key(&author)?;
+/// let author2: Author = from_key_owned(key)?;
+/// assert_eq!(author, author2);
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+pub fn from_key_owned<T, F>(value: Key<F>) -> Result<T, Error>
+where
+    T: de::DeserializeOwned,
+    F: FloatPolicy,
+{
+    T::deserialize(SourceDeserializer::new(value, KeyConfig::new()))
+}
+
+/// Bytes and the contents of sequences and maps are moved out of the key.
+impl<'de, F> Source<'de> for Key<F>
+where
+    F: FloatPolicy,
+{
+    type Policy = F;
+    type Elements = vec::IntoIter<Key<F>>;
+    type Entries = vec::IntoIter<(Key<F>, Key<F>)>;
+
+    #[inline]
+    fn key(&self) -> &Key<F> {
+        self
+    }
+
+    #[inline]
+    fn into_seq(self) -> Self::Elements {
+        match self {
+            Key::Seq(values) => values.into_vec().into_iter(),
+            _ => Vec::new().into_iter(),
+        }
+    }
+
+    #[inline]
+    fn into_map(self) -> Self::Entries {
+        match self {
+            Key::Map(entries) => entries.into_vec().into_iter(),
+            _ => Vec::new().into_iter(),
+        }
+    }
+
+    #[inline]
+    fn into_some(self) -> Self {
+        self.into_some_value()
+    }
+
+    #[inline]
+    fn visit_data<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self {
+            Key::String(s) => visitor.visit_str(&s),
+            Key::Bytes(bytes) => visitor.visit_byte_buf(bytes.into_vec()),
+            Key::IntegerSeq(IntegerSeq::U8(bytes)) => visitor.visit_byte_buf(bytes.into_vec()),
+            _ => Err(Error::Unexpected("string or bytes")),
+        }
+    }
+}
@@ -0,0 +1,222 @@
+//! Serialization which keeps what was built of a key when it fails.
+
+use std::error;
+use std::fmt;
+
+use serde::ser;
+
+use crate::config::KeyConfig;
+use crate::error::Error;
+use crate::float::{FloatPolicy, RejectFloatPolicy};
+use crate::hooks::{Hooks, Segment};
+use crate::key::Key;
+use crate::path::KeyPath;
+use crate::ser::KeySerializer;
+
+/// An error raised by [to_key_with_partial], which carries the part of the
+/// key which was built before serialization failed.
+///
+/// When displayed, the path at which serialization stopped is included, like
+/// `unsupported float f32 = 42.5 at books[3].rating`.
+#[derive(Debug, PartialEq)]
+pub struct PartialError<F = RejectFloatPolicy>
+where
+    F: FloatPolicy,
+{
+    error: Error,
+    path: KeyPath,
+    partial: Option<Key<F>>,
+}
+
+impl<F> PartialError<F>
+where
+    F: FloatPolicy,
+{
+    /// The error serialization failed with.
+    pub fn error(&self) -> &Error {
+        &self.error
+    }
+
+    /// Convert into the error serialization failed with.
+    pub fn into_error(self) -> Error {
+        self.error
+    }
+
+    /// The path at which serialization stopped.
+    ///
+    /// For errors raised by the value at a path this is the path of that
+    /// value, otherwise it is the path of the closest enclosing value.
+    pub fn path(&self) -> &KeyPath {
+        &self.path
+    }
+
+    /// The largest complete prefix of the key which was built before
+    /// serialization failed.
+    ///
+    /// This contains every sequence, map and variant which was entered, with
+    /// the elements and entries which were completed before the failure. The
+    /// value which failed is left out, so the partial key of a sequence
+    /// failing at its first element is an empty sequence.
+    ///
+    /// This is `None` if nothing was built, like when a scalar at the root
+    /// fails to serialize.
+    pub fn partial(&self) -> Option<&Key<F>> {
+        self.partial.as_ref()
+    }
+}
+
+impl<F> fmt::Display for PartialError<F>
+where
+    F: FloatPolicy,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            self.error.fmt(fmt)
+        } else {
+            write!(fmt, "{} at {}", self.error, self.path)
+        }
+    }
+}
+
+impl<F> error::Error for PartialError<F>
+where
+    F: FloatPolicy + fmt::Debug,
+{
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl<F> From<PartialError<F>> for Error
+where
+    F: FloatPolicy,
+{
+    fn from(error: PartialError<F>) -> Self {
+        error.error
+    }
+}
+
+/// Serialize the given value into a [Key], keeping the part of the key which
+/// was built if serialization fails.
+///
+/// This produces the same key as [to_key] does with the same float policy, but
+/// retains intermediate state to be able to report it through
+/// [PartialError::partial], which makes it slower.
+///
+/// [to_key]: crate::to_key
+///
+/// # Examples
+///
+/// ```
+/// use serde_derive::Serialize;
+/// use serde_hashkey::{to_key, to_key_with_partial, PartialError};
+///
+/// #[derive(Serialize)]
+/// struct Book {
+///     title: &'static str,
+///     rating: f32,
+/// }
+///
+/// #[derive(Serialize)]
+/// struct Library {
+///     books: Vec<Book>,
+/// }
+///
+/// let library = Library {
+///     books: vec![
+///         Book { title: "Birds of a feather", rating: 4.5 },
+///     ],
+/// };
+///
+/// let error: PartialError = to_key_with_partial(&library).unwrap_err();
+/// assert_eq!(error.to_string(), "unsupported float f32 = 4.5 at books[0].rating");
+///
+/// #[derive(Serialize)]
+/// struct PartialBook {
+///     title: &'static str,
+/// }
+///
+/// #[derive(Serialize)]
+/// struct PartialLibrary {
+///     books: Vec<PartialBook>,
+/// }
+///
+/// let partial = PartialLibrary {
+///     books: vec![PartialBook { title: "Birds of a feather" }],
+/// };
+///
+/// assert_eq!(error.partial(), Some(&to_key(&partial)?));
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+pub fn to_key_with_partial<T, F>(value: &T) -> Result<Key<F>, PartialError<F>>
+where
+    T: ?Sized + ser::Serialize,
+    F: FloatPolicy,
+{
+    let mut state = State {
+        path: KeyPath::new(),
+        partial: None,
+    };
+
+    match value.serialize(KeySerializer::with_hooks(KeyConfig::new(), &mut state)) {
+        Ok(key) => Ok(key),
+        Err(error) => {
+            // NB: the path is reported separately, so rejected floats are
+            // unwrapped.
+            let error = match error {
+                Error::FloatRejected { error, .. } => *error,
+                error => error,
+            };
+
+            Err(PartialError {
+                error,
+                path: state.path,
+                partial: state.partial,
+            })
+        }
+    }
+}
+
+/// Hooks which track the path to the value being serialized, and record the
+/// partial key of any value which fails.
+struct State<F>
+where
+    F: FloatPolicy,
+{
+    /// The path to the value being serialized. Segments are only popped once a
+    /// value has been built, so after a failure this is where it stopped.
+    path: KeyPath,
+    /// The partial key of the value which most recently failed.
+    partial: Option<Key<F>>,
+}
+
+impl<F> Hooks<F> for &mut State<F>
+where
+    F: FloatPolicy,
+{
+    type Reborrow<'b>
+        = &'b mut State<F>
+    where
+        Self: 'b;
+
+    #[inline]
+    fn reborrow(&mut self) -> Self::Reborrow<'_> {
+        self
+    }
+
+    fn enter(&mut self, segment: Segment<'_>) -> bool {
+        self.path.push(segment.into());
+        true
+    }
+
+    fn leave(&mut self, _: Segment<'_>) {
+        self.path.pop();
+    }
+
+    fn capture<B>(&mut self, build: B)
+    where
+        B: FnOnce(Option<Key<F>>) -> Option<Key<F>>,
+    {
+        self.partial = build(self.partial.take());
+    }
+}
@@ -0,0 +1,313 @@
+//! Paths to values inside of a [Key].
+
+use std::fmt;
+use std::iter::FromIterator;
+use std::slice;
+
+use crate::float::FloatPolicy;
+use crate::key::Key;
+
+/// A single step in a [KeyPath].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PathSegment {
+    /// The value of the map entry with the given string key.
+    ///
+    /// If a map contains multiple entries with the same key this refers to the
+    /// first one.
+    Field(Box<str>),
+    /// The element at the given index in a sequence.
+    Index(usize),
+    /// The value of the map entry at the given position. This is used for map
+    /// entries whose keys are not strings.
+    Entry(usize),
+}
+
+/// A path to a value inside of a [Key].
+///
+/// When displayed, fields are separated by `.`, sequence indexes are written
+/// as `[index]` and map entries without a string key as `{index}`, like
+/// `books[3].rating`. The empty path refers to the key itself and is displayed
+/// as `.`.
+///
+/// # Examples
+///
+/// ```
+/// use serde_hashkey::{KeyPath, PathSegment};
+///
+/// let path = vec![
+///     PathSegment::Field("books".into()),
+///     PathSegment::Index(3),
+///     PathSegment::Field("rating".into()),
+/// ].into_iter().collect::<KeyPath>();
+///
+/// assert_eq!(path.to_string(), "books[3].rating");
+/// assert_eq!(KeyPath::new().to_string(), ".");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct KeyPath {
+    segments: Vec<PathSegment>,
+}
+
+impl KeyPath {
+    /// Construct a new empty path, which refers to the root of a key.
+    pub const fn new() -> Self {
+        Self {
+            segments: Vec::new(),
+        }
+    }
+
+    /// Add a segment to the end of the path.
+    pub fn push(&mut self, segment: PathSegment) {
+        self.segments.push(segment);
+    }
+
+    /// Add a segment to the start of the path.
+    pub(crate) fn push_front(&mut self, segment: PathSegment) {
+        self.segments.insert(0, segment);
+    }
+
+    /// Remove the last segment of the path.
+    pub fn pop(&mut self) -> Option<PathSegment> {
+        self.segments.pop()
+    }
+
+    /// The segments making up the path.
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    /// Test if the path is empty, which means that it refers to the root of a
+    /// key.
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// The number of segments in the path.
+    pub fn len(&self) -> usize {
+        self.segments.len()
+    }
+}
+
+impl FromIterator<PathSegment> for KeyPath {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = PathSegment>,
+    {
+        Self {
+            segments: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl From<Vec<PathSegment>> for KeyPath {
+    fn from(segments: Vec<PathSegment>) -> Self {
+        Self { segments }
+    }
+}
+
+impl fmt::Display for KeyPath {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.segments.is_empty() {
+            return fmt.write_str(".");
+        }
+
+        for (n, segment) in self.segments.iter().enumerate() {
+            match segment {
+                PathSegment::Field(field) if n == 0 => write!(fmt, "{}", field)?,
+                PathSegment::Field(field) => write!(fmt, ".{}", field)?,
+                PathSegment::Index(index) => write!(fmt, "[{}]", index)?,
+                PathSegment::Entry(index) => write!(fmt, "{{{}}}", index)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<F> Key<F>
+where
+    F: FloatPolicy,
+{
+    /// Look up the value at the given path.
+    ///
+    /// Returns `None` if the path doesn't exist in this key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_derive::Serialize;
+    /// use serde_hashkey::{to_key, Key, KeyPath, PathSegment};
+    ///
+    /// #[derive(Serialize)]
+    /// struct Book {
+    ///     tags: Vec<&'static str>,
+    /// }
+    ///
+    /// let key = to_key(&Book { tags: vec!["fiction", "new"] })?;
+    ///
+    /// let path = vec![PathSegment::Field("tags".into()), PathSegment::Index(1)].into_iter().collect::<KeyPath>();
+    /// assert_eq!(key.get_path(&path), Some(&Key::String("new".into())));
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn get_path(&self, path: &KeyPath) -> Option<&Key<F>> {
+        let mut current = self;
+
+        for segment in path.segments() {
+            current = match (segment, current) {
+                (PathSegment::Field(field), Key::Map(map)) => {
+                    map.iter().find_map(|(k, v)| match k {
+                        Key::String(k) if **k == **field => Some(v),
+                        _ => None,
+                    })?
+                }
+                (PathSegment::Index(index), Key::Seq(seq)) => seq.get(*index)?,
+                (PathSegment::Entry(index), Key::Map(map)) => &map.get(*index)?.1,
+                _ => return None,
+            };
+        }
+
+        Some(current)
+    }
+
+    /// Iterate over every scalar value in this key along with its path.
+    ///
+    /// Scalars are all values which are not sequences or maps. Map entries are
+    /// addressed with [PathSegment::Field] if their key is a string and
+    /// [PathSegment::Entry] otherwise, and only their values are visited.
+    /// Empty sequences and maps contain no leaves and are not yielded.
+    /// Compact sequences, [Key::IntegerSeq] and [Key::FloatSeq], are not
+    /// expanded and are yielded as a single leaf.
+    ///
+    /// Traversal is depth-first and does not use recursion, so it is safe to
+    /// use on deeply nested keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_derive::Serialize;
+    /// use serde_hashkey::{to_key, Key};
+    ///
+    /// #[derive(Serialize)]
+    /// struct Author {
+    ///     name: &'static str,
+    ///     age: u32,
+    /// }
+    ///
+    /// #[derive(Serialize)]
+    /// struct Book {
+    ///     title: &'static str,
+    ///     author: Author,
+    /// }
+    ///
+    /// let key = to_key(&Book { title: "Birds of a feather", author: Author { name: "Noah", age: 42 } })?;
+    ///
+    /// let leaves = key.leaves().map(|(path, value)| (path.to_string(), value.clone())).collect::<Vec<_>>();
+    ///
+    /// assert_eq!(leaves, vec![
+    ///     (String::from("title"), Key::String("Birds of a feather".into())),
+    ///     (String::from("author.name"), Key::String("Noah".into())),
+    ///     (String::from("author.age"), Key::from(42u32)),
+    /// ]);
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn leaves(&self) -> Leaves<'_, F> {
+        Leaves {
+            root: Some(self),
+            path: KeyPath::new(),
+            stack: Vec::new(),
+        }
+    }
+}
+
+/// Iterator over the scalar values in a [Key] and their paths.
+///
+/// See [Key::leaves].
+pub struct Leaves<'a, F>
+where
+    F: FloatPolicy,
+{
+    root: Option<&'a Key<F>>,
+    path: KeyPath,
+    stack: Vec<Frame<'a, F>>,
+}
+
+enum Frame<'a, F>
+where
+    F: FloatPolicy,
+{
+    Seq(std::iter::Enumerate<slice::Iter<'a, Key<F>>>),
+    Map(std::iter::Enumerate<slice::Iter<'a, (Key<F>, Key<F>)>>),
+}
+
+impl<'a, F> Frame<'a, F>
+where
+    F: FloatPolicy,
+{
+    /// Construct a frame for the given key, or `None` if the key is a scalar.
+    fn new(key: &'a Key<F>) -> Option<Self> {
+        match key {
+            Key::Seq(seq) => Some(Frame::Seq(seq.iter().enumerate())),
+            Key::Map(map) => Some(Frame::Map(map.iter().enumerate())),
+            _ => None,
+        }
+    }
+
+    fn next(&mut self) -> Option<(PathSegment, &'a Key<F>)> {
+        match self {
+            Frame::Seq(iter) => {
+                let (index, value) = iter.next()?;
+                Some((PathSegment::Index(index), value))
+            }
+            Frame::Map(iter) => {
+                let (index, (key, value)) = iter.next()?;
+
+                let segment = match key {
+                    Key::String(key) => PathSegment::Field((**key).into()),
+                    _ => PathSegment::Entry(index),
+                };
+
+                Some((segment, value))
+            }
+        }
+    }
+}
+
+impl<'a, F> Iterator for Leaves<'a, F>
+where
+    F: FloatPolicy,
+{
+    type Item = (KeyPath, &'a Key<F>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(root) = self.root.take() {
+            match Frame::new(root) {
+                Some(frame) => self.stack.push(frame),
+                None => return Some((KeyPath::new(), root)),
+            }
+        }
+
+        loop {
+            let frame = self.stack.last_mut()?;
+
+            let (segment, value) = match frame.next() {
+                Some(next) => next,
+                None => {
+                    self.stack.pop();
+                    self.path.pop();
+                    continue;
+                }
+            };
+
+            self.path.push(segment);
+
+            match Frame::new(value) {
+                Some(frame) => self.stack.push(frame),
+                None => {
+                    let path = self.path.clone();
+                    self.path.pop();
+                    return Some((path, value));
+                }
+            }
+        }
+    }
+}
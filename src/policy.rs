@@ -0,0 +1,211 @@
+//! Conversion of keys between float policies without recursion.
+
+use std::vec;
+
+use crate::error::Error;
+use crate::float::{self, FloatPolicy, FloatRepr};
+use crate::key::{Float, Key};
+
+impl<F> Key<F>
+where
+    F: FloatPolicy,
+{
+    /// Convert this key into a key using another float policy.
+    ///
+    /// Every float is converted by serializing its value again using the
+    /// float policy `G`, which produces the same key as serializing the
+    /// original value with `G` would have. Every other kind of key is moved
+    /// into the new key without copying any of its data.
+    ///
+    /// Traversal does not use recursion, so it is safe to use on deeply nested
+    /// keys.
+    ///
+    /// # Errors
+    ///
+    /// Errors with the first float which is rejected by `G`. So converting
+    /// into [RejectFloatPolicy] only succeeds if the key doesn't contain any
+    /// floats, while converting from it always succeeds.
+    ///
+    /// [RejectFloatPolicy]: crate::RejectFloatPolicy
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::{
+    ///     to_key, to_key_with_canonical_float, CanonicalFloatPolicy, Error, FloatWidth, Key,
+    ///     RejectFloatPolicy,
+    /// };
+    ///
+    /// let key = to_key_with_canonical_float(&("id", 42u32))?;
+    /// let key: Key<RejectFloatPolicy> = key.try_into_policy()?;
+    /// assert_eq!(key, to_key(&("id", 42u32))?);
+    ///
+    /// let key: Key<CanonicalFloatPolicy> = key.try_into_policy()?;
+    /// assert_eq!(key, to_key_with_canonical_float(&("id", 42u32))?);
+    ///
+    /// let key = to_key_with_canonical_float(&("id", 1.5f32))?;
+    /// let error = Error::UnsupportedFloat { width: FloatWidth::F32, value: 1.5 };
+    /// assert_eq!(key.try_into_policy::<RejectFloatPolicy>(), Err(error));
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn try_into_policy<G>(self) -> Result<Key<G>, Error>
+    where
+        G: FloatPolicy,
+    {
+        let mut stack = Vec::<Frame<F, G>>::new();
+        let mut next = self;
+
+        loop {
+            let mut key = match next {
+                Key::Seq(seq) if !seq.is_empty() => {
+                    let seq = Vec::from(seq);
+                    let len = seq.len();
+                    stack.push(Frame::Seq(seq.into_iter(), Vec::with_capacity(len)));
+                    None
+                }
+                Key::Map(map) if !map.is_empty() => {
+                    let map = Vec::from(map);
+                    let len = map.len();
+                    stack.push(Frame::Map(
+                        map.into_iter(),
+                        Vec::with_capacity(len),
+                        None,
+                        None,
+                    ));
+                    None
+                }
+                key => Some(key.try_into_policy_shallow()?),
+            };
+
+            next = loop {
+                let frame = match stack.last_mut() {
+                    Some(frame) => frame,
+                    None => return Ok(key.unwrap_or(Key::Unit)),
+                };
+
+                if let Some(key) = key.take() {
+                    frame.accept(key);
+                }
+
+                match frame.next() {
+                    Some(next) => break next,
+                    // Every element of the innermost container has been
+                    // converted.
+                    None => key = stack.pop().map(Frame::finish),
+                }
+            };
+        }
+    }
+
+    /// Convert a key which doesn't contain any other keys.
+    fn try_into_policy_shallow<G>(self) -> Result<Key<G>, Error>
+    where
+        G: FloatPolicy,
+    {
+        Ok(match self {
+            Key::Null => Key::Null,
+            Key::Unit => Key::Unit,
+            Key::Bool(value) => Key::Bool(value),
+            Key::Integer(value) => Key::Integer(value),
+            Key::Float(value) => Key::Float(convert_float(&value)?),
+            Key::Bytes(value) => Key::Bytes(value),
+            Key::Char(value) => Key::Char(value),
+            Key::String(value) => Key::String(value),
+            Key::Seq(..) => Key::Seq(Box::default()),
+            Key::Map(..) => Key::Map(Box::default()),
+            Key::IntegerSeq(value) => Key::IntegerSeq(value),
+            Key::FloatSeq(seq) => {
+                let mut out = Vec::with_capacity(seq.len());
+
+                for float in seq.iter() {
+                    out.push(Key::Float(convert_float(&float)?));
+                }
+
+                // NB: the converted floats might not have the same width.
+                Key::seq(out)
+            }
+        })
+    }
+}
+
+/// Convert a float by serializing its value using the policy `G`.
+fn convert_float<F, G>(value: &Float<F>) -> Result<Float<G>, Error>
+where
+    F: FloatPolicy,
+    G: FloatPolicy,
+{
+    let v = match float::to_f64(value) {
+        Some(v) => v,
+        None => return Err(Error::UnsupportedType("float")),
+    };
+
+    // NB: narrowing is lossless, since the value was widened from an f32.
+    match value {
+        Float::F32(..) => G::serialize_f32(v as f32),
+        Float::F64(..) => Ok(Float::F64(<G::F64 as FloatRepr<f64>>::serialize(v)?)),
+        Float::F16(..) => G::serialize_f16(v as f32),
+    }
+}
+
+/// A container whose elements are currently being converted.
+enum Frame<F, G>
+where
+    F: FloatPolicy,
+    G: FloatPolicy,
+{
+    Seq(vec::IntoIter<Key<F>>, Vec<Key<G>>),
+    /// The entries of a map, followed by the converted key of the current
+    /// entry once it has been converted and the value of the current entry
+    /// while its key is being converted.
+    Map(
+        vec::IntoIter<(Key<F>, Key<F>)>,
+        Vec<(Key<G>, Key<G>)>,
+        Option<Key<G>>,
+        Option<Key<F>>,
+    ),
+}
+
+impl<F, G> Frame<F, G>
+where
+    F: FloatPolicy,
+    G: FloatPolicy,
+{
+    /// The next element to convert.
+    fn next(&mut self) -> Option<Key<F>> {
+        match self {
+            Frame::Seq(iter, _) => iter.next(),
+            Frame::Map(iter, _, _, value) => {
+                if let Some(value) = value.take() {
+                    return Some(value);
+                }
+
+                let (key, next) = iter.next()?;
+                *value = Some(next);
+                Some(key)
+            }
+        }
+    }
+
+    /// Accept the conversion of the element which was last returned by
+    /// [Frame::next].
+    fn accept(&mut self, element: Key<G>) {
+        match self {
+            Frame::Seq(_, done) => done.push(element),
+            Frame::Map(_, done, key, value) => {
+                if value.is_some() {
+                    *key = Some(element);
+                } else if let Some(key) = key.take() {
+                    done.push((key, element));
+                }
+            }
+        }
+    }
+
+    /// Construct the converted container.
+    fn finish(self) -> Key<G> {
+        match self {
+            Frame::Seq(_, done) => Key::seq(done),
+            Frame::Map(_, done, ..) => Key::Map(done.into()),
+        }
+    }
+}
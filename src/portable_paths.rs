@@ -0,0 +1,186 @@
+//! Portable serialization of paths, for use with `#[serde(with = "...")]`.
+//!
+//! The [Serialize] implementation of [Path] only succeeds for paths which are
+//! valid UTF-8, and on Windows the same logical path is written with `\`
+//! separators. This means the same path can produce different keys across
+//! platforms, or fail to serialize outright.
+//!
+//! Paths serialized through this module are written in a canonical form, which
+//! is a UTF-8 string where every `\` is replaced with `/`. Since `\` is
+//! replaced on all platforms, this should not be used for Unix paths whose
+//! file names contain a `\`.
+//!
+//! Paths which aren't valid UTF-8 fail to serialize. The [lossy] module can be
+//! used instead to replace invalid sequences with `U+FFFD REPLACEMENT
+//! CHARACTER`.
+//!
+//! [Serialize]: serde::Serialize
+//!
+//! # Examples
+//!
+//! ```
+//! use std::path::PathBuf;
+//!
+//! use serde_derive::{Deserialize, Serialize};
+//! use serde_hashkey::{from_key, to_key, Key};
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Asset {
+//!     #[serde(with = "serde_hashkey::portable_paths")]
+//!     path: PathBuf,
+//! }
+//!
+//! let unix = to_key(&Asset { path: PathBuf::from("images/logo.png") })?;
+//! let windows = to_key(&Asset { path: PathBuf::from(r"images\logo.png") })?;
+//! assert_eq!(unix, windows);
+//!
+//! let asset: Asset = from_key(&windows)?;
+//! assert_eq!(asset.path, PathBuf::from("images/logo.png"));
+//! # Ok::<_, serde_hashkey::Error>(())
+//! ```
+
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::path::{Path, PathBuf};
+
+use serde::{de, ser};
+
+use crate::error::Error;
+use crate::float::FloatPolicy;
+use crate::key::Key;
+
+/// Serialize a path in its canonical form, failing if it isn't valid UTF-8.
+pub fn serialize<P, S>(path: &P, serializer: S) -> Result<S::Ok, S::Error>
+where
+    P: ?Sized + AsRef<Path>,
+    S: ser::Serializer,
+{
+    let path = path.as_ref();
+
+    match path.to_str() {
+        Some(path) => serializer.serialize_str(&separators(Cow::Borrowed(path))),
+        None => Err(ser::Error::custom(format_args!(
+            "path `{}` is not valid UTF-8",
+            path.display()
+        ))),
+    }
+}
+
+/// Deserialize a path which was serialized in its canonical form.
+///
+/// This can deserialize anything which can be converted from a [PathBuf],
+/// such as an [OsString](std::ffi::OsString).
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: From<PathBuf>,
+    D: de::Deserializer<'de>,
+{
+    let path = <String as de::Deserialize>::deserialize(deserializer)?;
+    Ok(T::from(PathBuf::from(path)))
+}
+
+/// Portable serialization of paths, which replaces sequences that aren't
+/// valid UTF-8 instead of failing.
+///
+/// The replaced sequences can't be recovered, so distinct paths which only
+/// differ in invalid sequences produce the same key.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::PathBuf;
+///
+/// use serde_derive::Serialize;
+/// use serde_hashkey::{to_key, Key};
+///
+/// #[derive(Serialize)]
+/// struct Asset {
+///     #[serde(with = "serde_hashkey::portable_paths::lossy")]
+///     path: PathBuf,
+/// }
+///
+/// let key = to_key(&Asset { path: PathBuf::from(r"images\logo.png") })?;
+/// let path = Key::String("images/logo.png".into());
+/// assert_eq!(key, Key::Map(vec![(Key::String("path".into()), path)].into()));
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+pub mod lossy {
+    use std::path::{Path, PathBuf};
+
+    use serde::{de, ser};
+
+    /// Serialize a path in its canonical form, replacing any sequences which
+    /// aren't valid UTF-8.
+    pub fn serialize<P, S>(path: &P, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        P: ?Sized + AsRef<Path>,
+        S: ser::Serializer,
+    {
+        serializer.serialize_str(&super::canonical_lossy(path.as_ref()))
+    }
+
+    /// Deserialize a path which was serialized in its canonical form.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: From<PathBuf>,
+        D: de::Deserializer<'de>,
+    {
+        super::deserialize(deserializer)
+    }
+}
+
+/// Convert a path into its canonical form, replacing any sequences which
+/// aren't valid UTF-8.
+fn canonical_lossy(path: &Path) -> Cow<'_, str> {
+    separators(path.to_string_lossy())
+}
+
+/// Replace all `\` separators with `/`.
+fn separators(path: Cow<'_, str>) -> Cow<'_, str> {
+    if path.contains('\\') {
+        Cow::Owned(path.replace('\\', "/"))
+    } else {
+        path
+    }
+}
+
+/// A path is converted into a [Key::String] holding its canonical form, as
+/// produced by [portable_paths::lossy](crate::portable_paths::lossy).
+///
+/// # Examples
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use std::path::{Path, PathBuf};
+///
+/// use serde_hashkey::Key;
+///
+/// let key: Key = Key::from(Path::new(r"images\logo.png"));
+/// assert_eq!(key, Key::String("images/logo.png".into()));
+/// assert_eq!(PathBuf::try_from(&key)?, PathBuf::from("images/logo.png"));
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+impl<F> From<&Path> for Key<F>
+where
+    F: FloatPolicy,
+{
+    fn from(path: &Path) -> Self {
+        Key::String(canonical_lossy(path).into())
+    }
+}
+
+/// A [Key::String] can be converted back into a path. Any other key fails with
+/// [Error::Unexpected].
+impl<F> TryFrom<&Key<F>> for PathBuf
+where
+    F: FloatPolicy,
+{
+    type Error = Error;
+
+    fn try_from(key: &Key<F>) -> Result<Self, Error> {
+        match key {
+            Key::String(path) => Ok(PathBuf::from(&**path)),
+            _ => Err(Error::Unexpected("string")),
+        }
+    }
+}
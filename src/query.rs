@@ -0,0 +1,512 @@
+//! Encoding of flat keys as URL query strings.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::error::Error;
+use crate::float::{self, FloatPolicy, FloatRepr};
+use crate::key::{Float, Integer, Key};
+
+/// How nested maps are handled when encoding or decoding a query string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum QueryNesting {
+    /// Nested maps are not supported, and will cause encoding to error.
+    Reject,
+    /// Nested maps are flattened using dots, like `author.name=Noah`.
+    Dot,
+    /// Nested maps are flattened using brackets, like `author[name]=Noah`.
+    Bracket,
+}
+
+/// Configuration used by [Key::to_query_string_with] and
+/// [Key::from_query_string_with].
+///
+/// # Examples
+///
+/// ```
+/// use serde_hashkey::{Key, QueryConfig, QueryNesting};
+///
+/// let config = QueryConfig::new()
+///     .nesting(QueryNesting::Bracket)
+///     .parse_numbers(true);
+///
+/// let key: Key = Key::from_query_string_with("author[name]=Noah&author[age]=42", &config)?;
+/// assert_eq!(key.to_query_string_with(&config)?, "author[age]=42&author[name]=Noah");
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueryConfig {
+    nesting: QueryNesting,
+    parse_numbers: bool,
+}
+
+impl QueryConfig {
+    /// Construct the default configuration, which rejects nested maps and
+    /// decodes every value as a string.
+    pub const fn new() -> Self {
+        Self {
+            nesting: QueryNesting::Reject,
+            parse_numbers: false,
+        }
+    }
+
+    /// Configure how nested maps are handled.
+    pub const fn nesting(self, nesting: QueryNesting) -> Self {
+        Self { nesting, ..self }
+    }
+
+    /// Configure whether values which look like numbers are decoded as
+    /// numbers rather than strings.
+    ///
+    /// Non-negative integers are decoded as [Integer::U64], negative integers
+    /// as [Integer::I64] and anything else which parses as a floating point
+    /// number as [Float::F64], as long as the float policy accepts it.
+    pub const fn parse_numbers(self, parse_numbers: bool) -> Self {
+        Self {
+            parse_numbers,
+            ..self
+        }
+    }
+}
+
+impl Default for QueryConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F> Key<F>
+where
+    F: FloatPolicy,
+{
+    /// Encode a string-keyed map of scalars as a URL query string using the
+    /// default [QueryConfig].
+    ///
+    /// See [to_query_string_with] for details.
+    ///
+    /// [to_query_string_with]: Key::to_query_string_with
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_derive::Serialize;
+    /// use serde_hashkey::to_key;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Purge {
+    ///     path: &'static str,
+    ///     tags: Vec<&'static str>,
+    ///     recursive: bool,
+    /// }
+    ///
+    /// let key = to_key(&Purge { path: "/a b", tags: vec!["x", "y"], recursive: true })?;
+    /// assert_eq!(key.to_query_string()?, "path=%2Fa%20b&recursive=true&tags=x&tags=y");
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn to_query_string(&self) -> Result<String, Error> {
+        self.to_query_string_with(&QueryConfig::new())
+    }
+
+    /// Encode a string-keyed map of scalars as a URL query string.
+    ///
+    /// Parameters are sorted by name, and names and values are
+    /// percent-encoded. Values are encoded like this:
+    ///
+    /// * [Key::Null] and [Key::Unit] are encoded as a name without a value,
+    ///   like `name`.
+    /// * Booleans, integers and floats are encoded using their textual
+    ///   representation.
+    /// * Sequences are encoded as one repeated parameter per element, in
+    ///   order.
+    /// * Nested maps are handled according to [QueryConfig::nesting].
+    ///
+    /// Empty sequences and nested maps would produce no parameters, which
+    /// can't be told apart from the entry not being there, so they are
+    /// rejected.
+    ///
+    /// # Errors
+    ///
+    /// Errors if this key is not a map, if any map key is not a string, if
+    /// any value is bytes, if a sequence contains anything other than scalars,
+    /// if a sequence or nested map is empty, or if a nested map is encountered
+    /// and nesting is rejected. When
+    /// nesting is enabled, map keys containing the nesting syntax are also
+    /// rejected since they can't be decoded again.
+    pub fn to_query_string_with(&self, config: &QueryConfig) -> Result<String, Error> {
+        let map = match self {
+            Key::Map(map) => map,
+            _ => return Err(Error::Unexpected("map")),
+        };
+
+        let mut params = Vec::new();
+        collect(&mut String::new(), map, config.nesting, &mut params)?;
+
+        // NB: stable sort so that repeated parameters keep their order.
+        params.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut out = String::new();
+
+        for (name, value) in params {
+            if !out.is_empty() {
+                out.push('&');
+            }
+
+            encode(&mut out, &name);
+
+            if let Some(value) = value {
+                out.push('=');
+                encode(&mut out, &value);
+            }
+        }
+
+        Ok(out)
+    }
+
+    /// Decode a URL query string into a map using the default
+    /// [QueryConfig].
+    ///
+    /// See [from_query_string_with] for details.
+    ///
+    /// [from_query_string_with]: Key::from_query_string_with
+    pub fn from_query_string(query: &str) -> Result<Key<F>, Error> {
+        Self::from_query_string_with(query, &QueryConfig::new())
+    }
+
+    /// Decode a URL query string into a map.
+    ///
+    /// This is the reverse of [to_query_string_with]. Repeated parameters are
+    /// decoded as a sequence and parameters without a value as [Key::Unit].
+    /// All other values are decoded as strings, unless
+    /// [QueryConfig::parse_numbers] is enabled.
+    ///
+    /// Since type information is not part of a query string, decoding an
+    /// encoded key only produces the same key if it consists of strings,
+    /// units and sequences with more than one element.
+    ///
+    /// [to_query_string_with]: Key::to_query_string_with
+    ///
+    /// # Errors
+    ///
+    /// Errors if `query` contains invalid percent-encoding or bracket syntax,
+    /// or if two parameters conflict, like `a=1` and `a.b=2`.
+    pub fn from_query_string_with(query: &str, config: &QueryConfig) -> Result<Key<F>, Error> {
+        let mut root = BTreeMap::new();
+
+        for param in query.split('&').filter(|param| !param.is_empty()) {
+            let (name, value) = match param.find('=') {
+                Some(index) => (&param[..index], Some(&param[index + 1..])),
+                None => (param, None),
+            };
+
+            let name = decode(name)?;
+
+            let value = match value {
+                Some(value) => decode_value(decode(value)?, config.parse_numbers),
+                None => Key::Unit,
+            };
+
+            let segments = match config.nesting {
+                QueryNesting::Reject => vec![name.as_str()],
+                QueryNesting::Dot => name.split('.').collect(),
+                QueryNesting::Bracket => split_brackets(&name)?,
+            };
+
+            insert(&mut root, &segments, value, &name)?;
+        }
+
+        Ok(Node::Map(root).into_key())
+    }
+}
+
+/// Collect parameters from the given map, where values are `None` for units.
+fn collect<F>(
+    prefix: &mut String,
+    map: &[(Key<F>, Key<F>)],
+    nesting: QueryNesting,
+    out: &mut Vec<(String, Option<String>)>,
+) -> Result<(), Error>
+where
+    F: FloatPolicy,
+{
+    for (key, value) in map {
+        let name = match key {
+            Key::String(name) => name,
+            _ => return Err(Error::Unexpected("string map key")),
+        };
+
+        let reserved: &[char] = match nesting {
+            QueryNesting::Reject => &[],
+            QueryNesting::Dot => &['.'],
+            QueryNesting::Bracket => &['[', ']'],
+        };
+
+        if name.contains(reserved) {
+            return Err(Error::SeparatorInKey(name.to_string()));
+        }
+
+        let len = prefix.len();
+
+        match nesting {
+            QueryNesting::Bracket if len > 0 => {
+                prefix.push('[');
+                prefix.push_str(name);
+                prefix.push(']');
+            }
+            QueryNesting::Dot if len > 0 => {
+                prefix.push('.');
+                prefix.push_str(name);
+            }
+            _ => {
+                prefix.push_str(name);
+            }
+        }
+
+        match value {
+            Key::Map(..) if nesting == QueryNesting::Reject => {
+                return Err(Error::UnsupportedType("nested map"));
+            }
+            // NB: an empty map or sequence produces no parameters, which
+            // can't be told apart from the entry not being there at all.
+            Key::Map(map) if map.is_empty() => {
+                return Err(Error::UnsupportedType("empty map"));
+            }
+            Key::Map(map) => {
+                collect(prefix, map, nesting, out)?;
+            }
+            Key::Seq(..) | Key::IntegerSeq(..) | Key::FloatSeq(..) => {
+                let values = match value.seq_elements() {
+                    Some(values) if !values.is_empty() => values,
+                    _ => return Err(Error::UnsupportedType("empty sequence")),
+                };
+
+                for value in values.iter() {
+                    out.push((prefix.clone(), scalar(value)?));
+                }
+            }
+            value => {
+                out.push((prefix.clone(), scalar(value)?));
+            }
+        }
+
+        prefix.truncate(len);
+    }
+
+    Ok(())
+}
+
+/// Convert a scalar into its textual representation.
+fn scalar<F>(value: &Key<F>) -> Result<Option<String>, Error>
+where
+    F: FloatPolicy,
+{
+    let string = match value {
+        Key::Null | Key::Unit => return Ok(None),
+        Key::Bool(b) => b.to_string(),
+        Key::Char(c) => c.to_string(),
+        Key::Integer(integer) => match *integer {
+            Integer::I8(v) => v.to_string(),
+            Integer::I16(v) => v.to_string(),
+            Integer::I32(v) => v.to_string(),
+            Integer::I64(v) => v.to_string(),
+            Integer::I128(v) => v.to_string(),
+            Integer::U8(v) => v.to_string(),
+            Integer::U16(v) => v.to_string(),
+            Integer::U32(v) => v.to_string(),
+            Integer::U64(v) => v.to_string(),
+            Integer::U128(v) => v.to_string(),
+        },
+        Key::Float(f) => match (f, float::to_f64(f)) {
+            // NB: f32 is formatted as an f32 to get its shortest representation.
+            (Float::F32(..) | Float::F16(..), Some(v)) => (v as f32).to_string(),
+            (Float::F64(..), Some(v)) => v.to_string(),
+            (_, None) => return Err(Error::UnsupportedType("float")),
+        },
+        Key::String(string) => string.to_string(),
+        Key::Bytes(..) => return Err(Error::UnsupportedType("bytes")),
+        Key::Seq(..) | Key::IntegerSeq(..) | Key::FloatSeq(..) => {
+            return Err(Error::UnsupportedType("nested sequence"))
+        }
+        Key::Map(..) => return Err(Error::UnsupportedType("map in sequence")),
+    };
+
+    Ok(Some(string))
+}
+
+fn encode(out: &mut String, string: &str) {
+    for b in string.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                out.push(b as char);
+            }
+            // Brackets are kept as-is so that nested names stay readable.
+            b'[' | b']' => {
+                out.push(b as char);
+            }
+            b => {
+                let _ = write!(out, "%{:02X}", b);
+            }
+        }
+    }
+}
+
+fn decode(string: &str) -> Result<String, Error> {
+    let mut out = Vec::with_capacity(string.len());
+    let mut bytes = string.bytes();
+
+    while let Some(b) = bytes.next() {
+        match b {
+            b'+' => out.push(b' '),
+            b'%' => {
+                let hi = bytes.next().and_then(hex);
+                let lo = bytes.next().and_then(hex);
+
+                match (hi, lo) {
+                    (Some(hi), Some(lo)) => out.push(hi << 4 | lo),
+                    _ => return Err(Error::InvalidQuery(string.to_owned())),
+                }
+            }
+            b => out.push(b),
+        }
+    }
+
+    String::from_utf8(out).map_err(|_| Error::InvalidQuery(string.to_owned()))
+}
+
+fn hex(b: u8) -> Option<u8> {
+    match b {
+        b'0'..=b'9' => Some(b - b'0'),
+        b'a'..=b'f' => Some(b - b'a' + 10),
+        b'A'..=b'F' => Some(b - b'A' + 10),
+        _ => None,
+    }
+}
+
+fn decode_value<F>(value: String, parse_numbers: bool) -> Key<F>
+where
+    F: FloatPolicy,
+{
+    if parse_numbers && is_number(&value) {
+        if let Ok(v) = value.parse::<u64>() {
+            return Key::Integer(Integer::U64(v));
+        }
+
+        if let Ok(v) = value.parse::<i64>() {
+            return Key::Integer(Integer::I64(v));
+        }
+
+        if let Ok(v) = value.parse::<f64>() {
+            if let Ok(v) = <F::F64 as FloatRepr<f64>>::serialize(v) {
+                return Key::Float(Float::F64(v));
+            }
+        }
+    }
+
+    Key::String(value.into())
+}
+
+/// Test if the given string looks like a number, which excludes things like
+/// `inf` and `NaN` which are otherwise accepted when parsing floats.
+fn is_number(string: &str) -> bool {
+    let digits = string.strip_prefix('-').unwrap_or(string);
+
+    digits.starts_with(|c: char| c.is_ascii_digit())
+        && digits
+            .chars()
+            .all(|c| matches!(c, '0'..='9' | '.' | 'e' | 'E' | '-' | '+'))
+}
+
+/// Split a name like `a[b][c]` into its segments.
+fn split_brackets(name: &str) -> Result<Vec<&str>, Error> {
+    let (first, mut rest) = match name.find('[') {
+        Some(index) => (&name[..index], &name[index..]),
+        None => return Ok(vec![name]),
+    };
+
+    let mut segments = vec![first];
+
+    while !rest.is_empty() {
+        let end = match (rest.strip_prefix('['), rest.find(']')) {
+            (Some(..), Some(end)) => end,
+            _ => return Err(Error::InvalidQuery(name.to_owned())),
+        };
+
+        let segment = &rest[1..end];
+
+        if segment.contains('[') {
+            return Err(Error::InvalidQuery(name.to_owned()));
+        }
+
+        segments.push(segment);
+        rest = &rest[end + 1..];
+    }
+
+    Ok(segments)
+}
+
+enum Node<F>
+where
+    F: FloatPolicy,
+{
+    Values(Vec<Key<F>>),
+    Map(BTreeMap<String, Node<F>>),
+}
+
+impl<F> Node<F>
+where
+    F: FloatPolicy,
+{
+    fn into_key(self) -> Key<F> {
+        match self {
+            Node::Values(mut values) if values.len() == 1 => values.remove(0),
+            Node::Values(values) => Key::seq(values),
+            // NB: BTreeMap is sorted by string, which matches the order of a
+            // normalized key.
+            Node::Map(map) => Key::Map(
+                map.into_iter()
+                    .map(|(key, node)| (Key::String(key.into()), node.into_key()))
+                    .collect::<Vec<_>>()
+                    .into(),
+            ),
+        }
+    }
+}
+
+fn insert<F>(
+    map: &mut BTreeMap<String, Node<F>>,
+    segments: &[&str],
+    value: Key<F>,
+    name: &str,
+) -> Result<(), Error>
+where
+    F: FloatPolicy,
+{
+    let (last, parents) = match segments.split_last() {
+        Some(split) => split,
+        None => return Err(Error::InvalidQuery(name.to_owned())),
+    };
+
+    let mut map = map;
+
+    for segment in parents {
+        let node = map
+            .entry((*segment).to_owned())
+            .or_insert_with(|| Node::Map(BTreeMap::new()));
+
+        map = match node {
+            Node::Map(map) => map,
+            Node::Values(..) => return Err(Error::ConflictingPath(name.to_owned())),
+        };
+    }
+
+    let node = map
+        .entry((*last).to_owned())
+        .or_insert_with(|| Node::Values(Vec::new()));
+
+    match node {
+        Node::Values(values) => values.push(value),
+        Node::Map(..) => return Err(Error::ConflictingPath(name.to_owned())),
+    }
+
+    Ok(())
+}
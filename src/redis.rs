@@ -0,0 +1,57 @@
+//! Integration with the [`redis`] crate.
+//!
+//! [`redis`]: https://docs.rs/redis
+
+use redis1::{FromRedisValue, ParsingError, RedisWrite, ToRedisArgs, ToSingleRedisArg, Value};
+
+use crate::float::FloatPolicy;
+use crate::key::Key;
+
+/// A [Key] is written as a single Redis argument using its stable binary
+/// encoding, as produced by [Key::to_bytes].
+///
+/// Since the encoding is canonical, equal keys always produce the same Redis
+/// key regardless of the order of entries in their maps.
+///
+/// # Examples
+///
+/// ```
+/// use redis1::ToRedisArgs;
+/// use serde_hashkey::to_key;
+///
+/// let key = to_key(&("user", 42u32))?;
+/// assert_eq!(key.to_redis_args(), vec![key.to_bytes()]);
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+impl<F> ToRedisArgs for Key<F>
+where
+    F: FloatPolicy + Ord + Clone,
+{
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        out.write_arg(&self.to_bytes());
+    }
+}
+
+impl<F> ToSingleRedisArg for Key<F> where F: FloatPolicy + Ord + Clone {}
+
+/// A [Key] can be read back from a bulk string holding its stable binary
+/// encoding, as produced by [Key::to_bytes]. This is useful when reading keys
+/// back from commands like `SCAN`.
+impl<F> FromRedisValue for Key<F>
+where
+    F: FloatPolicy + Ord + Clone,
+{
+    fn from_redis_value(value: Value) -> Result<Self, ParsingError> {
+        match value {
+            Value::BulkString(bytes) => {
+                Key::from_bytes(&bytes).map_err(|error| ParsingError::from(error.to_string()))
+            }
+            _ => Err(ParsingError::from(String::from(
+                "expected bulk string for key",
+            ))),
+        }
+    }
+}
@@ -0,0 +1,121 @@
+//! Renaming of fields in map-shaped keys.
+
+use crate::float::FloatPolicy;
+use crate::key::Key;
+
+impl<F> Key<F>
+where
+    F: FloatPolicy + Ord,
+{
+    /// Rename string map keys in place according to `mapping`, which is a list
+    /// of `(from, to)` pairs.
+    ///
+    /// A `from` without a `.` renames every matching field, no matter how
+    /// deeply nested it is. A `from` which is a `.` separated list of fields,
+    /// like `author.name`, only renames the field at that path. Sequences are
+    /// transparent to paths, so `books.title` renames the `title` field of
+    /// every element in `books`. Paths always refer to the fields as they were
+    /// named before renaming, and if several pairs match a field the first
+    /// one is used.
+    ///
+    /// Maps which were sorted, like the ones in a [normalized][Key::normalize]
+    /// key, are sorted again after any of their fields are renamed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_derive::Serialize;
+    /// use serde_hashkey::to_key;
+    ///
+    /// #[derive(Serialize)]
+    /// struct OldBook {
+    ///     name: &'static str,
+    ///     author: OldAuthor,
+    /// }
+    ///
+    /// #[derive(Serialize)]
+    /// struct OldAuthor {
+    ///     name: &'static str,
+    /// }
+    ///
+    /// #[derive(Serialize)]
+    /// struct Book {
+    ///     name: &'static str,
+    ///     author: Author,
+    /// }
+    ///
+    /// #[derive(Serialize)]
+    /// struct Author {
+    ///     full_name: &'static str,
+    /// }
+    ///
+    /// let mut key = to_key(&OldBook { name: "Birds of a feather", author: OldAuthor { name: "Noah" } })?;
+    /// key.rename_fields(&[("author.name", "full_name")]);
+    ///
+    /// assert_eq!(key, to_key(&Book { name: "Birds of a feather", author: Author { full_name: "Noah" } })?);
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn rename_fields(&mut self, mapping: &[(&str, &str)]) {
+        rename_fields(self, mapping, &mut String::new());
+    }
+}
+
+/// Rename fields in the given key, where `prefix` is the path to it followed
+/// by a `.`, or empty at the root.
+fn rename_fields<F>(key: &mut Key<F>, mapping: &[(&str, &str)], prefix: &mut String)
+where
+    F: FloatPolicy + Ord,
+{
+    match key {
+        Key::Seq(seq) => {
+            for value in seq.iter_mut() {
+                rename_fields(value, mapping, prefix);
+            }
+        }
+        Key::Map(map) => {
+            let sorted = map.windows(2).all(|w| w[0].0 <= w[1].0);
+            let mut renamed = false;
+
+            for (key, value) in map.iter_mut() {
+                let name = match key {
+                    Key::String(name) => name,
+                    _ => {
+                        rename_fields(value, mapping, prefix);
+                        continue;
+                    }
+                };
+
+                let len = prefix.len();
+                prefix.push_str(name);
+
+                let to = mapping.iter().find_map(|(from, to)| {
+                    let matches = if from.contains('.') {
+                        *from == prefix.as_str()
+                    } else {
+                        **from == **name
+                    };
+
+                    if matches {
+                        Some(*to)
+                    } else {
+                        None
+                    }
+                });
+
+                prefix.push('.');
+                rename_fields(value, mapping, prefix);
+                prefix.truncate(len);
+
+                if let Some(to) = to {
+                    *name = to.into();
+                    renamed = true;
+                }
+            }
+
+            if sorted && renamed {
+                map.sort_by(|a, b| a.0.cmp(&b.0));
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,125 @@
+//! Filtering the entries of map and sequence keys.
+
+use std::mem;
+
+use crate::float::FloatPolicy;
+use crate::key::Key;
+use crate::walk::Walk;
+
+impl<F> Key<F>
+where
+    F: FloatPolicy,
+{
+    /// Retain only the entries of a map or the elements of a sequence for
+    /// which `retain` returns `true`.
+    ///
+    /// For map entries the closure is called with the key of the entry and
+    /// `Some(value)`, and for sequence elements it is called with the element
+    /// and `None`. Only the entries of this key are visited, see
+    /// [Key::retain_recursive] for a version which visits every map and
+    /// sequence this key contains. Any other kind of key is left unchanged.
+    ///
+    /// Like with [Key::remove], compact sequences are expanded before their
+    /// elements are visited and compacted again afterwards.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_derive::Serialize;
+    /// use serde_hashkey::{to_key, Key};
+    ///
+    /// #[derive(Serialize)]
+    /// struct Request {
+    ///     request_id: u64,
+    ///     path: &'static str,
+    /// }
+    ///
+    /// #[derive(Serialize)]
+    /// struct Stable {
+    ///     path: &'static str,
+    /// }
+    ///
+    /// let mut key = to_key(&Request { request_id: 42, path: "/books" })?;
+    /// key.retain(|key, _| key != "request_id");
+    /// assert_eq!(key, to_key(&Stable { path: "/books" })?);
+    ///
+    /// let mut key = to_key(&vec![1u32, 2, 3, 4])?;
+    /// key.retain(|element, _| element.as_u64().map_or(false, |n| n % 2 == 0));
+    /// assert_eq!(key, to_key(&vec![2u32, 4])?);
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn retain<R>(&mut self, mut retain: R)
+    where
+        R: FnMut(&Key<F>, Option<&Key<F>>) -> bool,
+    {
+        match self {
+            Key::Map(map) => {
+                let mut entries = mem::take(map).into_vec();
+                entries.retain(|(key, value)| retain(key, Some(value)));
+                *map = entries.into();
+            }
+            Key::Seq(..) | Key::IntegerSeq(..) | Key::FloatSeq(..) => {
+                if let Ok(mut elements) = self.take_elements() {
+                    elements.retain(|element| retain(element, None));
+                    *self = Key::seq(elements);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Retain only the map entries and sequence elements for which `retain`
+    /// returns `true`, in this key and every map and sequence it contains.
+    ///
+    /// This calls [Key::retain] on every value visited by [Key::walk_mut], so
+    /// the contents of removed entries are never visited. Since map keys are
+    /// values as well, maps and sequences used as map keys are filtered too.
+    ///
+    /// Traversal does not use recursion, so it is safe to use on deeply nested
+    /// keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_derive::Serialize;
+    /// use serde_hashkey::{to_key, Key};
+    ///
+    /// #[derive(Serialize)]
+    /// struct Event {
+    ///     name: &'static str,
+    ///     timestamp: u64,
+    ///     children: Vec<Event>,
+    /// }
+    ///
+    /// #[derive(Serialize)]
+    /// struct Stable {
+    ///     name: &'static str,
+    ///     children: Vec<Stable>,
+    /// }
+    ///
+    /// let mut key = to_key(&Event {
+    ///     name: "parent",
+    ///     timestamp: 1700000000,
+    ///     children: vec![Event { name: "child", timestamp: 1700000001, children: vec![] }],
+    /// })?;
+    ///
+    /// key.retain_recursive(|key, value| value.is_none() || key != "timestamp");
+    ///
+    /// let expected = to_key(&Stable {
+    ///     name: "parent",
+    ///     children: vec![Stable { name: "child", children: vec![] }],
+    /// })?;
+    ///
+    /// assert_eq!(key, expected);
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn retain_recursive<R>(&mut self, mut retain: R)
+    where
+        R: FnMut(&Key<F>, Option<&Key<F>>) -> bool,
+    {
+        self.walk_mut(|key| {
+            key.retain(&mut retain);
+            Walk::Continue
+        });
+    }
+}
@@ -0,0 +1,567 @@
+//! Structural schemas inferred from keys.
+
+use std::fmt;
+
+use serde::de::{self, Deserialize, Deserializer, IgnoredAny};
+use serde::ser::{Serialize, SerializeMap, SerializeTuple, Serializer};
+
+use crate::float::FloatPolicy;
+use crate::key::Key;
+use crate::path::{KeyPath, PathSegment};
+
+/// The names of scalar schemas when serialized.
+const SCALARS: &[&str] = &[
+    "any", "unit", "bool", "integer", "float", "bytes", "char", "string",
+];
+
+/// The names of compound schemas when serialized.
+const COMPOUNDS: &[&str] = &["optional", "seq", "map", "entries"];
+
+/// A description of the structure of a [Key].
+///
+/// Schemas are inferred from example keys with [KeySchema::infer], widened
+/// across several examples with [KeySchema::merge], and used to check that
+/// other keys have the same structure with [KeySchema::validate].
+///
+/// Schemas can be serialized and deserialized so that they can be stored
+/// alongside the keys they describe.
+///
+/// # Examples
+///
+/// ```
+/// use serde_derive::Serialize;
+/// use serde_hashkey::{to_key, KeySchema, SchemaViolation};
+///
+/// #[derive(Serialize)]
+/// struct Author {
+///     name: &'static str,
+///     age: Option<u32>,
+/// }
+///
+/// let schema = KeySchema::infer(&to_key(&Author { name: "Noah", age: Some(42) })?)
+///     .merge(KeySchema::infer(&to_key(&Author { name: "Jane", age: None })?));
+///
+/// assert!(schema.validate(&to_key(&Author { name: "Ola", age: None })?).is_ok());
+///
+/// let errors = schema.validate(&to_key(&("Noah", 42u32))?).unwrap_err();
+/// assert_eq!(errors.len(), 1);
+/// assert_eq!(errors[0].0.to_string(), ".");
+/// assert_eq!(errors[0].1, SchemaViolation::UnexpectedKind { expected: "map", found: "sequence" });
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum KeySchema {
+    /// Any key is accepted.
+    ///
+    /// This is inferred for the elements of empty sequences, and is what
+    /// schemas of incompatible kinds are widened to when merged.
+    Any,
+    /// A unit value, see [Key::Unit].
+    Unit,
+    /// A boolean, see [Key::Bool].
+    Bool,
+    /// An integer of any width, see [Key::Integer].
+    Integer,
+    /// A float of any width, see [Key::Float].
+    Float,
+    /// A byte array, see [Key::Bytes].
+    Bytes,
+    /// A character, see [Key::Char].
+    Char,
+    /// A string, see [Key::String].
+    String,
+    /// Either a null value or a key matching the given schema, which is what
+    /// optional values serialize as, see [Key::Null].
+    ///
+    /// This is inferred with an inner schema of [KeySchema::Any] for
+    /// [Key::Null], which is narrowed once it's merged with a schema inferred
+    /// from a value which is present.
+    Optional(Box<KeySchema>),
+    /// A sequence whose elements all match the given schema.
+    Seq(Box<KeySchema>),
+    /// A map with string keys, like the ones structs serialize as.
+    Map(Vec<FieldSchema>),
+    /// A map whose keys and values all match the given schemas.
+    ///
+    /// This is inferred for maps which have keys other than strings or
+    /// duplicate keys.
+    Entries(Box<KeySchema>, Box<KeySchema>),
+}
+
+/// A field in a [KeySchema::Map].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FieldSchema {
+    /// The name of the field.
+    pub name: Box<str>,
+    /// The schema of the value of the field.
+    pub schema: KeySchema,
+    /// If the field must be present.
+    pub required: bool,
+}
+
+/// A way in which a [Key] doesn't conform to a [KeySchema], as reported by
+/// [KeySchema::validate].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaViolation {
+    /// The key is of a different kind than the one expected.
+    UnexpectedKind {
+        /// The kind of key expected by the schema.
+        expected: &'static str,
+        /// The kind of key that was found.
+        found: &'static str,
+    },
+    /// A required field is missing.
+    MissingField,
+    /// A field or map entry is not part of the schema.
+    UnknownField,
+}
+
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaViolation::UnexpectedKind { expected, found } => {
+                write!(fmt, "expected {}, found {}", expected, found)
+            }
+            SchemaViolation::MissingField => fmt.write_str("missing required field"),
+            SchemaViolation::UnknownField => fmt.write_str("unknown field"),
+        }
+    }
+}
+
+impl KeySchema {
+    /// Infer the schema of the given key.
+    ///
+    /// Every field of a map is inferred to be required, and the elements of a
+    /// sequence are merged into a single schema with [KeySchema::merge].
+    pub fn infer<F>(key: &Key<F>) -> Self
+    where
+        F: FloatPolicy,
+    {
+        match key {
+            Key::Null => KeySchema::Optional(Box::new(KeySchema::Any)),
+            Key::Unit => KeySchema::Unit,
+            Key::Bool(..) => KeySchema::Bool,
+            Key::Integer(..) => KeySchema::Integer,
+            Key::Float(..) => KeySchema::Float,
+            Key::Bytes(..) => KeySchema::Bytes,
+            Key::Char(..) => KeySchema::Char,
+            Key::String(..) => KeySchema::String,
+            Key::Seq(seq) => KeySchema::Seq(Box::new(
+                seq.iter()
+                    .map(KeySchema::infer)
+                    .fold(KeySchema::Any, KeySchema::merge),
+            )),
+            Key::IntegerSeq(..) => KeySchema::Seq(Box::new(KeySchema::Integer)),
+            Key::FloatSeq(..) => KeySchema::Seq(Box::new(KeySchema::Float)),
+            Key::Map(map) => {
+                let mut fields = Vec::<FieldSchema>::with_capacity(map.len());
+
+                for (key, value) in map.iter() {
+                    let name = match key {
+                        Key::String(name) if !fields.iter().any(|f| *f.name == **name) => name,
+                        _ => return KeySchema::infer_entries(map),
+                    };
+
+                    fields.push(FieldSchema {
+                        name: (**name).into(),
+                        schema: KeySchema::infer(value),
+                        required: true,
+                    });
+                }
+
+                KeySchema::Map(fields)
+            }
+        }
+    }
+
+    fn infer_entries<F>(map: &[(Key<F>, Key<F>)]) -> Self
+    where
+        F: FloatPolicy,
+    {
+        let mut keys = KeySchema::Any;
+        let mut values = KeySchema::Any;
+
+        for (key, value) in map {
+            keys = keys.merge(KeySchema::infer(key));
+            values = values.merge(KeySchema::infer(value));
+        }
+
+        KeySchema::Entries(Box::new(keys), Box::new(values))
+    }
+
+    /// Merge this schema with another, producing a schema which accepts keys
+    /// matching either of them.
+    ///
+    /// * Merging with a [KeySchema::Optional] makes a schema
+    ///   [KeySchema::Optional].
+    /// * Fields which are only present in one [KeySchema::Map] are made
+    ///   optional.
+    /// * A [KeySchema::Map] merged with [KeySchema::Entries] becomes
+    ///   [KeySchema::Entries] with string keys.
+    /// * Schemas of incompatible kinds are widened to [KeySchema::Any].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::{to_key, KeySchema};
+    ///
+    /// let a = KeySchema::infer(&to_key(&vec![1u32, 2])?);
+    /// let b = KeySchema::infer(&to_key(&vec![None, Some(3u32)])?);
+    ///
+    /// assert_eq!(
+    ///     a.merge(b),
+    ///     KeySchema::Seq(Box::new(KeySchema::Optional(Box::new(KeySchema::Integer))))
+    /// );
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn merge(self, other: Self) -> Self {
+        match (self, other) {
+            (KeySchema::Any, other) | (other, KeySchema::Any) => other,
+            (a, b) if a == b => a,
+            (KeySchema::Optional(a), KeySchema::Optional(b)) => {
+                KeySchema::Optional(Box::new(a.merge(*b)))
+            }
+            (KeySchema::Optional(a), b) | (b, KeySchema::Optional(a)) => {
+                KeySchema::Optional(Box::new(a.merge(b)))
+            }
+            (KeySchema::Seq(a), KeySchema::Seq(b)) => KeySchema::Seq(Box::new(a.merge(*b))),
+            (KeySchema::Map(a), KeySchema::Map(b)) => KeySchema::Map(merge_fields(a, b)),
+            (KeySchema::Entries(ak, av), KeySchema::Entries(bk, bv)) => {
+                KeySchema::Entries(Box::new(ak.merge(*bk)), Box::new(av.merge(*bv)))
+            }
+            (KeySchema::Map(fields), KeySchema::Entries(keys, values))
+            | (KeySchema::Entries(keys, values), KeySchema::Map(fields)) => {
+                let mut keys = *keys;
+                let mut values = *values;
+
+                for field in fields {
+                    keys = keys.merge(KeySchema::String);
+                    values = values.merge(field.schema);
+                }
+
+                KeySchema::Entries(Box::new(keys), Box::new(values))
+            }
+            _ => KeySchema::Any,
+        }
+    }
+
+    /// Validate that the given key conforms to this schema, reporting every
+    /// violation along with the path to where it occurred.
+    ///
+    /// Map entries are addressed the same way as in [Key::leaves].
+    pub fn validate<F>(&self, key: &Key<F>) -> Result<(), Vec<(KeyPath, SchemaViolation)>>
+    where
+        F: FloatPolicy,
+    {
+        let mut state = State {
+            path: KeyPath::new(),
+            violations: Vec::new(),
+        };
+
+        state.validate(self, key);
+
+        if state.violations.is_empty() {
+            Ok(())
+        } else {
+            Err(state.violations)
+        }
+    }
+
+    /// A description of the kind of key this schema expects.
+    fn expected(&self) -> &'static str {
+        match self {
+            KeySchema::Any => "any",
+            KeySchema::Unit => "unit",
+            KeySchema::Bool => "bool",
+            KeySchema::Integer => "integer",
+            KeySchema::Float => "float",
+            KeySchema::Bytes => "bytes",
+            KeySchema::Char => "char",
+            KeySchema::String => "string",
+            KeySchema::Optional(inner) => inner.expected(),
+            KeySchema::Seq(..) => "sequence",
+            KeySchema::Map(..) | KeySchema::Entries(..) => "map",
+        }
+    }
+}
+
+/// Merge the fields of two map schemas, making fields which are only present
+/// in one of them optional.
+fn merge_fields(a: Vec<FieldSchema>, mut b: Vec<FieldSchema>) -> Vec<FieldSchema> {
+    let mut fields = Vec::with_capacity(a.len().max(b.len()));
+
+    for mut field in a {
+        match b.iter().position(|f| f.name == field.name) {
+            Some(index) => {
+                let other = b.remove(index);
+                field.schema = field.schema.merge(other.schema);
+                field.required &= other.required;
+            }
+            None => {
+                field.required = false;
+            }
+        }
+
+        fields.push(field);
+    }
+
+    fields.extend(b.into_iter().map(|field| FieldSchema {
+        required: false,
+        ..field
+    }));
+
+    fields
+}
+
+/// A description of the kind of the given key.
+fn found<F>(key: &Key<F>) -> &'static str
+where
+    F: FloatPolicy,
+{
+    match key {
+        Key::Null => "null",
+        Key::Unit => "unit",
+        Key::Bool(..) => "bool",
+        Key::Integer(..) => "integer",
+        Key::Float(..) => "float",
+        Key::Bytes(..) => "bytes",
+        Key::Char(..) => "char",
+        Key::String(..) => "string",
+        Key::Seq(..) | Key::IntegerSeq(..) | Key::FloatSeq(..) => "sequence",
+        Key::Map(..) => "map",
+    }
+}
+
+struct State {
+    path: KeyPath,
+    violations: Vec<(KeyPath, SchemaViolation)>,
+}
+
+impl State {
+    fn violation(&mut self, violation: SchemaViolation) {
+        self.violations.push((self.path.clone(), violation));
+    }
+
+    fn validate_at<F>(&mut self, segment: PathSegment, schema: &KeySchema, key: &Key<F>)
+    where
+        F: FloatPolicy,
+    {
+        self.path.push(segment);
+        self.validate(schema, key);
+        self.path.pop();
+    }
+
+    fn validate<F>(&mut self, schema: &KeySchema, key: &Key<F>)
+    where
+        F: FloatPolicy,
+    {
+        match (schema, key) {
+            (KeySchema::Any, _)
+            | (KeySchema::Unit, Key::Unit)
+            | (KeySchema::Bool, Key::Bool(..))
+            | (KeySchema::Integer, Key::Integer(..))
+            | (KeySchema::Float, Key::Float(..))
+            | (KeySchema::Bytes, Key::Bytes(..))
+            | (KeySchema::Char, Key::Char(..))
+            | (KeySchema::String, Key::String(..))
+            | (KeySchema::Optional(..), Key::Null) => {}
+            (KeySchema::Optional(inner), key) => {
+                self.validate(inner, key);
+            }
+            (KeySchema::Seq(inner), Key::IntegerSeq(..))
+                if matches!(**inner, KeySchema::Any | KeySchema::Integer) => {}
+            (KeySchema::Seq(inner), Key::FloatSeq(..))
+                if matches!(**inner, KeySchema::Any | KeySchema::Float) => {}
+            (
+                KeySchema::Seq(inner),
+                key @ (Key::Seq(..) | Key::IntegerSeq(..) | Key::FloatSeq(..)),
+            ) => {
+                if let Some(elements) = key.seq_elements() {
+                    for (index, element) in elements.iter().enumerate() {
+                        self.validate_at(PathSegment::Index(index), inner, element);
+                    }
+                }
+            }
+            (KeySchema::Map(fields), Key::Map(map)) => {
+                for field in fields.iter().filter(|field| field.required) {
+                    let present = map
+                        .iter()
+                        .any(|(key, _)| matches!(key, Key::String(name) if **name == *field.name));
+
+                    if !present {
+                        self.path.push(PathSegment::Field(field.name.clone()));
+                        self.violation(SchemaViolation::MissingField);
+                        self.path.pop();
+                    }
+                }
+
+                for (index, (key, value)) in map.iter().enumerate() {
+                    let (segment, field) = match key {
+                        Key::String(name) => (
+                            PathSegment::Field((**name).into()),
+                            fields.iter().find(|field| *field.name == **name),
+                        ),
+                        _ => (PathSegment::Entry(index), None),
+                    };
+
+                    match field {
+                        Some(field) => self.validate_at(segment, &field.schema, value),
+                        None => {
+                            self.path.push(segment);
+                            self.violation(SchemaViolation::UnknownField);
+                            self.path.pop();
+                        }
+                    }
+                }
+            }
+            (KeySchema::Entries(keys, values), Key::Map(map)) => {
+                for (index, (key, value)) in map.iter().enumerate() {
+                    let segment = match key {
+                        Key::String(name) => PathSegment::Field((**name).into()),
+                        _ => PathSegment::Entry(index),
+                    };
+
+                    self.path.push(segment);
+                    self.validate(keys, key);
+                    self.validate(values, value);
+                    self.path.pop();
+                }
+            }
+            (schema, key) => {
+                self.violation(SchemaViolation::UnexpectedKind {
+                    expected: schema.expected(),
+                    found: found(key),
+                });
+            }
+        }
+    }
+}
+
+impl Serialize for KeySchema {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // NB: scalars are serialized as their name, and compound schemas as a
+        // map with a single entry from their name to their contents.
+        let name = match self {
+            KeySchema::Any => "any",
+            KeySchema::Unit => "unit",
+            KeySchema::Bool => "bool",
+            KeySchema::Integer => "integer",
+            KeySchema::Float => "float",
+            KeySchema::Bytes => "bytes",
+            KeySchema::Char => "char",
+            KeySchema::String => "string",
+            KeySchema::Optional(inner) => return serialize_entry(serializer, "optional", inner),
+            KeySchema::Seq(inner) => return serialize_entry(serializer, "seq", inner),
+            KeySchema::Map(fields) => return serialize_entry(serializer, "map", fields),
+            KeySchema::Entries(keys, values) => {
+                return serialize_entry(serializer, "entries", &(keys, values))
+            }
+        };
+
+        serializer.serialize_str(name)
+    }
+}
+
+fn serialize_entry<S, T>(serializer: S, name: &str, value: &T) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: ?Sized + Serialize,
+{
+    let mut map = serializer.serialize_map(Some(1))?;
+    map.serialize_entry(name, value)?;
+    map.end()
+}
+
+impl<'de> Deserialize<'de> for KeySchema {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(KeySchemaVisitor)
+    }
+}
+
+struct KeySchemaVisitor;
+
+impl<'de> de::Visitor<'de> for KeySchemaVisitor {
+    type Value = KeySchema;
+
+    fn expecting(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str("a key schema")
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<KeySchema, E>
+    where
+        E: de::Error,
+    {
+        Ok(match value {
+            "any" => KeySchema::Any,
+            "unit" => KeySchema::Unit,
+            "bool" => KeySchema::Bool,
+            "integer" => KeySchema::Integer,
+            "float" => KeySchema::Float,
+            "bytes" => KeySchema::Bytes,
+            "char" => KeySchema::Char,
+            "string" => KeySchema::String,
+            other => return Err(E::unknown_variant(other, SCALARS)),
+        })
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<KeySchema, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        let name = match map.next_key::<Box<str>>()? {
+            Some(name) => name,
+            None => return Err(de::Error::invalid_length(0, &self)),
+        };
+
+        let schema = match &*name {
+            "optional" => KeySchema::Optional(map.next_value()?),
+            "seq" => KeySchema::Seq(map.next_value()?),
+            "map" => KeySchema::Map(map.next_value()?),
+            "entries" => {
+                let (keys, values) = map.next_value()?;
+                KeySchema::Entries(keys, values)
+            }
+            other => return Err(de::Error::unknown_variant(other, COMPOUNDS)),
+        };
+
+        if map.next_key::<IgnoredAny>()?.is_some() {
+            return Err(de::Error::invalid_length(2, &self));
+        }
+
+        Ok(schema)
+    }
+}
+
+impl Serialize for FieldSchema {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut tuple = serializer.serialize_tuple(3)?;
+        tuple.serialize_element(&self.name)?;
+        tuple.serialize_element(&self.schema)?;
+        tuple.serialize_element(&self.required)?;
+        tuple.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for FieldSchema {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let (name, schema, required) = Deserialize::deserialize(deserializer)?;
+
+        Ok(FieldSchema {
+            name,
+            schema,
+            required,
+        })
+    }
+}
@@ -0,0 +1,156 @@
+//! Projection of map-shaped keys down to a subset of their fields.
+
+use crate::error::Error;
+use crate::float::FloatPolicy;
+use crate::key::Key;
+
+impl<F> Key<F>
+where
+    F: FloatPolicy + Clone + Ord,
+{
+    /// Project this key down to only the fields selected by `paths`.
+    ///
+    /// Each path is a `.` separated list of string map keys, like
+    /// `author.name`, and the selected values are kept at the same position in
+    /// the resulting map. Paths which do not exist in this key are skipped, use
+    /// [try_select] to treat them as errors instead.
+    ///
+    /// The result is [normalized][Key::normalize].
+    ///
+    /// [try_select]: Key::try_select
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_derive::Serialize;
+    /// use serde_hashkey::to_key;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Request {
+    ///     user: u32,
+    ///     query: Query,
+    ///     trace_id: &'static str,
+    /// }
+    ///
+    /// #[derive(Serialize)]
+    /// struct Query {
+    ///     text: &'static str,
+    ///     page: u32,
+    /// }
+    ///
+    /// let a = to_key(&Request { user: 1, query: Query { text: "birds", page: 1 }, trace_id: "a" })?;
+    /// let b = to_key(&Request { user: 1, query: Query { text: "birds", page: 2 }, trace_id: "b" })?;
+    ///
+    /// assert_ne!(a, b);
+    /// assert_eq!(a.select(&["user", "query.text"]), b.select(&["user", "query.text"]));
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn select(&self, paths: &[&str]) -> Key<F> {
+        let mut root = Selection::Partial(Vec::new());
+
+        for path in paths {
+            select_path(self, &mut root, path);
+        }
+
+        root.into_key()
+    }
+
+    /// Project this key down to only the fields selected by `paths`, erroring
+    /// if any of them do not exist.
+    ///
+    /// See [select] for details.
+    ///
+    /// [select]: Key::select
+    pub fn try_select(&self, paths: &[&str]) -> Result<Key<F>, Error> {
+        let mut root = Selection::Partial(Vec::new());
+
+        for path in paths {
+            if !select_path(self, &mut root, path) {
+                return Err(Error::MissingPath((*path).to_owned()));
+            }
+        }
+
+        Ok(root.into_key())
+    }
+}
+
+enum Selection<'a, F>
+where
+    F: FloatPolicy,
+{
+    /// The whole value is selected.
+    Whole(&'a Key<F>),
+    /// Some of the entries in a map are selected.
+    Partial(Vec<(&'a Key<F>, Selection<'a, F>)>),
+}
+
+impl<F> Selection<'_, F>
+where
+    F: FloatPolicy + Clone + Ord,
+{
+    fn into_key(self) -> Key<F> {
+        match self {
+            Selection::Whole(value) => value.clone().normalize(),
+            Selection::Partial(entries) => {
+                let map = entries
+                    .into_iter()
+                    .map(|(key, selection)| (key.clone(), selection.into_key()))
+                    .collect::<Vec<_>>();
+
+                Key::Map(map.into()).normalize()
+            }
+        }
+    }
+}
+
+/// Select a single path, returning `false` if it doesn't exist.
+fn select_path<'a, F>(source: &'a Key<F>, root: &mut Selection<'a, F>, path: &str) -> bool
+where
+    F: FloatPolicy,
+{
+    let mut source = source;
+    let mut entries = Vec::new();
+
+    // Resolve the full path first so that missing paths leave no trace in the
+    // selection.
+    for segment in path.split('.') {
+        let map = match source {
+            Key::Map(map) => map,
+            _ => return false,
+        };
+
+        let (key, value) = match map
+            .iter()
+            .find(|(key, _)| matches!(key, Key::String(key) if &**key == segment))
+        {
+            Some(entry) => entry,
+            None => return false,
+        };
+
+        entries.push(key);
+        source = value;
+    }
+
+    let mut selection = root;
+
+    for key in entries {
+        let children = match selection {
+            // Everything below this point is already selected.
+            Selection::Whole(..) => return true,
+            Selection::Partial(children) => children,
+        };
+
+        let index = match children.iter().position(|(k, _)| std::ptr::eq(*k, key)) {
+            Some(index) => index,
+            None => {
+                children.push((key, Selection::Partial(Vec::new())));
+                children.len() - 1
+            }
+        };
+
+        selection = &mut children[index].1;
+    }
+
+    *selection = Selection::Whole(source);
+    true
+}
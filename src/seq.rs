@@ -0,0 +1,193 @@
+//! Mutation of sequence-shaped keys.
+//!
+//! Sequences are stored in boxed slices, so every mutation moves the elements
+//! into a [Vec] and back again. Compact sequences are expanded into individual
+//! keys first, and the result is compacted again if possible so that it stays
+//! equal to the key of the equivalent serialized sequence. Each mutation is
+//! therefore linear in the length of the sequence, and building a large
+//! sequence one element at a time is quadratic. Prefer collecting the elements
+//! into a [Vec] and serializing it when that is an option.
+
+use std::mem;
+
+use crate::error::Error;
+use crate::float::FloatPolicy;
+use crate::key::Key;
+
+impl<F> Key<F>
+where
+    F: FloatPolicy,
+{
+    /// Append an element to the end of this sequence.
+    ///
+    /// A [Key::Unit] is treated as an empty sequence and is turned into one.
+    /// Errors if this key is any other kind of key than a sequence.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::{to_key, Key};
+    ///
+    /// let mut key = Key::Unit;
+    /// key.push(Key::from(1u32))?;
+    /// key.push(Key::from(2u32))?;
+    /// assert_eq!(key, to_key(&vec![1u32, 2])?);
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn push(&mut self, element: Key<F>) -> Result<(), Error> {
+        let mut elements = self.take_elements()?;
+        elements.push(element);
+        *self = Key::seq(elements);
+        Ok(())
+    }
+
+    /// Remove the last element of this sequence and return it, or `None` if
+    /// it is empty.
+    ///
+    /// A [Key::Unit] is treated as an empty sequence and is left unchanged.
+    /// Errors if this key is any other kind of key than a sequence.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::{to_key, Key};
+    ///
+    /// let mut key = to_key(&vec![1u32, 2])?;
+    /// assert_eq!(key.pop()?, Some(Key::from(2u32)));
+    /// assert_eq!(key, to_key(&vec![1u32])?);
+    ///
+    /// let mut key: Key = Key::Unit;
+    /// assert_eq!(key.pop()?, None);
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn pop(&mut self) -> Result<Option<Key<F>>, Error> {
+        if let Key::Unit = self {
+            return Ok(None);
+        }
+
+        let mut elements = self.take_elements()?;
+        let element = elements.pop();
+        *self = Key::seq(elements);
+        Ok(element)
+    }
+
+    /// Insert an element at position `index` of this sequence, shifting all
+    /// elements after it to the right.
+    ///
+    /// A [Key::Unit] is treated as an empty sequence and is turned into one.
+    /// Errors if this key is any other kind of key than a sequence.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the length of the sequence.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::{to_key, Key};
+    ///
+    /// let mut key = to_key(&vec!["a", "c"])?;
+    /// key.insert(1, Key::String("b".into()))?;
+    /// assert_eq!(key, to_key(&vec!["a", "b", "c"])?);
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn insert(&mut self, index: usize, element: Key<F>) -> Result<(), Error> {
+        let mut elements = self.take_elements()?;
+        let len = elements.len();
+
+        if index > len {
+            *self = Key::seq(elements);
+            panic!(
+                "insertion index (is {}) should be <= len (is {})",
+                index, len
+            );
+        }
+
+        elements.insert(index, element);
+        *self = Key::seq(elements);
+        Ok(())
+    }
+
+    /// Remove and return the element at position `index` of this sequence,
+    /// shifting all elements after it to the left.
+    ///
+    /// A [Key::Unit] is treated as an empty sequence and is left unchanged.
+    /// Errors if this key is any other kind of key than a sequence.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::{to_key, Key};
+    ///
+    /// let mut key = to_key(&vec![1u32, 2, 3])?;
+    /// assert_eq!(key.remove(0)?, Key::from(1u32));
+    /// assert_eq!(key, to_key(&vec![2u32, 3])?);
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn remove(&mut self, index: usize) -> Result<Key<F>, Error> {
+        if let Key::Unit = self {
+            panic!("removal index (is {}) should be < len (is 0)", index);
+        }
+
+        let mut elements = self.take_elements()?;
+        let len = elements.len();
+
+        if index >= len {
+            *self = Key::seq(elements);
+            panic!("removal index (is {}) should be < len (is {})", index, len);
+        }
+
+        let element = elements.remove(index);
+        *self = Key::seq(elements);
+        Ok(element)
+    }
+
+    /// Shorten this sequence, keeping the first `len` elements and dropping
+    /// the rest.
+    ///
+    /// This does nothing if the sequence is already no longer than `len`. A
+    /// [Key::Unit] is treated as an empty sequence and is left unchanged.
+    /// Errors if this key is any other kind of key than a sequence.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::{to_key, Key};
+    ///
+    /// let mut key = to_key(&vec![1u32, 2, 3])?;
+    /// key.truncate(1)?;
+    /// assert_eq!(key, to_key(&vec![1u32])?);
+    ///
+    /// key.truncate(8)?;
+    /// assert_eq!(key, to_key(&vec![1u32])?);
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn truncate(&mut self, len: usize) -> Result<(), Error> {
+        if let Key::Unit = self {
+            return Ok(());
+        }
+
+        let mut elements = self.take_elements()?;
+        elements.truncate(len);
+        *self = Key::seq(elements);
+        Ok(())
+    }
+
+    /// Take the elements of this sequence, expanding compact sequences. The
+    /// caller is expected to put a sequence back with [Key::seq].
+    pub(crate) fn take_elements(&mut self) -> Result<Vec<Key<F>>, Error> {
+        let elements = match self {
+            Key::Unit => Vec::new(),
+            Key::Seq(seq) => mem::take(seq).into_vec(),
+            Key::IntegerSeq(seq) => seq.iter().map(Key::Integer).collect(),
+            Key::FloatSeq(seq) => seq.iter().map(Key::Float).collect(),
+            _ => return Err(Error::Unexpected("sequence")),
+        };
+
+        Ok(elements)
+    }
+}
@@ -1,14 +1,29 @@
 //! Serialization for serde-hashkey.
 
+use crate::config::KeyConfig;
 use crate::error::Error;
 use serde::ser;
+use std::fmt;
 use std::marker::PhantomData;
+use std::mem;
+use std::sync::Arc;
 
-use crate::float::{FloatPolicy, FloatRepr, RejectFloatPolicy};
+use crate::float::{self, FloatPolicy, FloatRepr, RejectFloatPolicy};
+use crate::hooks::{Hooks, NoHooks, Segment};
+use crate::intern::intern;
 use crate::key::{Float, Key};
+use crate::path::PathSegment;
 
 /// Serialize the given value to a [Key].
 ///
+/// Floats are rejected, since this uses [RejectFloatPolicy]. This is a thin
+/// wrapper around [to_key_with], which can be used with other policies.
+///
+/// # Errors
+///
+/// Floats nested inside of the value are reported as [Error::FloatRejected],
+/// with the path to the field or element which contained them.
+///
 /// # Examples
 ///
 /// ```
@@ -46,38 +61,494 @@ use crate::key::{Float, Key};
 /// ```
 pub fn to_key<T>(value: &T) -> Result<Key<RejectFloatPolicy>, Error>
 where
-    T: ser::Serialize,
+    T: ?Sized + ser::Serialize,
 {
     to_key_with_policy::<T, RejectFloatPolicy>(value)
 }
 
+/// Serialize the given value to a [Key] using the given [FloatPolicy].
+///
+/// Functions like [to_key] and [to_key_with_ordered_float] are thin wrappers
+/// around this which use a fixed policy. This can be used to be generic over
+/// the policy, or to use a custom one.
+///
+/// [to_key_with_ordered_float]: crate::to_key_with_ordered_float
+///
+/// # Examples
+///
+/// ```
+/// use serde::Serialize;
+/// use serde_hashkey::{to_key, to_key_with, Error, FloatPolicy, Key, RejectFloatPolicy};
+///
+/// fn cache_key<F, T>(value: &T) -> Result<Key<F>, Error>
+/// where
+///     F: FloatPolicy,
+///     T: ?Sized + Serialize,
+/// {
+///     to_key_with::<F, T>(value)
+/// }
+///
+/// let key = cache_key::<RejectFloatPolicy, _>(&("user", 42u32))?;
+/// assert_eq!(key, to_key(&("user", 42u32))?);
+/// assert!(cache_key::<RejectFloatPolicy, _>(&1.5f64).is_err());
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+pub fn to_key_with<F, T>(value: &T) -> Result<Key<F>, Error>
+where
+    F: FloatPolicy,
+    T: ?Sized + ser::Serialize,
+{
+    to_key_with_policy::<T, F>(value)
+}
+
+/// Serialize the given value to a [Key] using the given [FloatPolicy] and
+/// [KeyConfig].
+///
+/// # Examples
+///
+/// ```
+/// use serde_derive::Serialize;
+/// use serde_hashkey::{to_key, to_key_with_config, EnumRepr, KeyConfig, RejectFloatPolicy};
+///
+/// #[derive(Serialize)]
+/// enum Shape {
+///     Circle(u32),
+/// }
+///
+/// #[derive(Serialize)]
+/// #[serde(tag = "t", content = "c")]
+/// enum Tagged {
+///     Circle(u32),
+/// }
+///
+/// let config = KeyConfig::new().enum_repr(EnumRepr::adjacent());
+/// let key = to_key_with_config::<RejectFloatPolicy, _>(&Shape::Circle(7), &config)?;
+///
+/// assert_ne!(key, to_key(&Shape::Circle(7))?);
+/// assert_eq!(key, to_key(&Tagged::Circle(7))?);
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+pub fn to_key_with_config<F, T>(value: &T, config: &KeyConfig) -> Result<Key<F>, Error>
+where
+    F: FloatPolicy,
+    T: ?Sized + ser::Serialize,
+{
+    value.serialize(KeySerializer::with_config(*config))
+}
+
+/// Serialize the given value to a [Key], tagging structs and enums with the
+/// names of their types.
+///
+/// This is a shorthand for [to_key_with_config] using
+/// [KeyConfig::type_names], which documents how keys are tagged. Keys built
+/// this way can be deserialized using [from_key_tagged].
+///
+/// [from_key_tagged]: crate::from_key_tagged
+///
+/// # Examples
+///
+/// ```
+/// use serde_derive::Serialize;
+/// use serde_hashkey::{to_key, to_key_tagged};
+///
+/// #[derive(Serialize)]
+/// struct A {
+///     x: u32,
+/// }
+///
+/// #[derive(Serialize)]
+/// struct B {
+///     x: u32,
+/// }
+///
+/// assert_eq!(to_key(&A { x: 1 })?, to_key(&B { x: 1 })?);
+/// assert_ne!(to_key_tagged(&A { x: 1 })?, to_key_tagged(&B { x: 1 })?);
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+pub fn to_key_tagged<T>(value: &T) -> Result<Key<RejectFloatPolicy>, Error>
+where
+    T: ?Sized + ser::Serialize,
+{
+    let config = KeyConfig::new().type_names(true);
+    to_key_with_config(value, &config)
+}
+
+/// Serialize the given value to a [Key], representing newtype variants by the
+/// key of their content alone.
+///
+/// This is a shorthand for [to_key_with_config] using
+/// [KeyConfig::transparent_newtype_variants]. Keys built this way can't be
+/// deserialized back into the enums they were built from.
+///
+/// # Examples
+///
+/// ```
+/// use serde_derive::Serialize;
+/// use serde_hashkey::{to_key, to_key_transparent};
+///
+/// #[derive(Serialize)]
+/// enum Id {
+///     Uuid(String),
+/// }
+///
+/// let uuid = String::from("67e55044-10b1-426f-9247-bb680e5fe0c8");
+/// assert_eq!(to_key_transparent(&Id::Uuid(uuid.clone()))?, to_key(&uuid)?);
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+pub fn to_key_transparent<T>(value: &T) -> Result<Key<RejectFloatPolicy>, Error>
+where
+    T: ?Sized + ser::Serialize,
+{
+    let config = KeyConfig::new().transparent_newtype_variants(true);
+    to_key_with_config(value, &config)
+}
+
+/// Serialize each item produced by an iterator, collecting them into a
+/// sequence.
+///
+/// This produces the same key as collecting the items into a `Vec` and
+/// serializing it with [to_key], without having to allocate the intermediate
+/// collection. The lower bound of the iterator's size hint is used to
+/// preallocate the sequence.
+///
+/// Unlike [to_key] this is generic over the [FloatPolicy] to use, which is
+/// inferred from the returned key.
+///
+/// # Errors
+///
+/// Stops at the first item which fails to serialize, returning the error it
+/// raised. Like with [to_key], rejected floats are reported as
+/// [Error::FloatRejected] with a path starting at the index of the item.
+///
+/// # Examples
+///
+/// ```
+/// use serde_hashkey::{to_key, to_key_from_iter, Error, Key};
+///
+/// let key: Key = to_key_from_iter((1u32..4).map(|n| n * 10))?;
+/// assert_eq!(key, to_key(&vec![10u32, 20, 30])?);
+///
+/// let result: Result<Key, _> = to_key_from_iter(vec![1.0f32]);
+/// assert_eq!(result.unwrap_err().to_string(), "unsupported float f32 = 1 at [0]");
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+pub fn to_key_from_iter<I, T, F>(iter: I) -> Result<Key<F>, Error>
+where
+    I: IntoIterator<Item = T>,
+    T: ser::Serialize,
+    F: FloatPolicy,
+{
+    use self::ser::SerializeSeq as _;
+
+    let iter = iter.into_iter();
+    let mut seq = SerializeVec::with_capacity(iter.size_hint().0);
+
+    for item in iter {
+        seq.serialize_element(&item)?;
+    }
+
+    seq.end()
+}
+
+/// Serialize each key-value pair produced by an iterator, collecting them into
+/// a map.
+///
+/// This produces the same key as collecting the pairs into a map and
+/// serializing it with [to_key], with entries kept in the order they are
+/// produced. The lower bound of the iterator's size hint is used to
+/// preallocate the map.
+///
+/// # Errors
+///
+/// Stops at the first pair which fails to serialize, returning the error it
+/// raised. Like with [to_key], rejected floats are reported as
+/// [Error::FloatRejected] with a path starting at the entry of the pair.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::BTreeMap;
+/// use serde_hashkey::{to_key, to_key_from_pairs, Key};
+///
+/// let pairs = vec![("a", 1u32), ("b", 2u32)];
+///
+/// let key: Key = to_key_from_pairs(pairs.iter().copied())?;
+/// assert_eq!(key, to_key(&pairs.into_iter().collect::<BTreeMap<_, _>>())?);
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+pub fn to_key_from_pairs<I, K, V, F>(iter: I) -> Result<Key<F>, Error>
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: ser::Serialize,
+    V: ser::Serialize,
+    F: FloatPolicy,
+{
+    use self::ser::SerializeMap as _;
+
+    let iter = iter.into_iter();
+    let mut map = SerializeMap::with_capacity(iter.size_hint().0);
+
+    for (key, value) in iter {
+        map.serialize_entry(&key, &value)?;
+    }
+
+    map.end()
+}
+
 /// Internal helper to serialize a value with the given policy.
 pub(crate) fn to_key_with_policy<T, F>(value: &T) -> Result<Key<F>, Error>
 where
-    T: ser::Serialize,
+    T: ?Sized + ser::Serialize,
+    F: FloatPolicy,
+{
+    value.serialize(KeySerializer::new())
+}
+
+/// The name to tag a value of the type with the given name with, if type names
+/// are enabled.
+fn type_name(config: KeyConfig, name: &'static str) -> Option<&'static str> {
+    config.get_type_names().then_some(name)
+}
+
+/// Wrap a key in a sequence tagged with the given type name, if any.
+///
+/// See [KeyConfig::type_names].
+fn tagged<F>(type_name: Option<&'static str>, key: Key<F>) -> Key<F>
+where
+    F: FloatPolicy,
+{
+    match type_name {
+        Some(name) => Key::seq(vec![Key::String(intern(name)), key]),
+        None => key,
+    }
+}
+
+/// Adjust the path of an error raised inside of a key wrapped by [tagged].
+fn tagged_error(type_name: Option<&'static str>, error: Error) -> Error {
+    match type_name {
+        Some(..) => error.at(PathSegment::Index(1)),
+        None => error,
+    }
+}
+
+/// Reject duplicate keys among the entries of a map, if the configuration
+/// says so.
+///
+/// See [KeyConfig::reject_duplicate_keys].
+fn check_duplicate_keys<F>(config: KeyConfig, map: &[(Key<F>, Key<F>)]) -> Result<(), Error>
+where
     F: FloatPolicy,
 {
-    value.serialize(Serializer(PhantomData))
+    if !config.get_reject_duplicate_keys() || map.len() < 2 {
+        return Ok(());
+    }
+
+    let mut keys = map.iter().map(|(key, _)| key).collect::<Vec<_>>();
+    keys.sort_by(|a, b| a.total_cmp(b));
+
+    for pair in keys.windows(2) {
+        if pair[0].total_cmp(pair[1]).is_eq() {
+            return Err(Error::DuplicateKey(Render(pair[0]).to_string()));
+        }
+    }
+
+    Ok(())
 }
 
-struct Serializer<F>(PhantomData<F>)
+/// Renders a key for [Error::DuplicateKey].
+struct Render<'a, F>(&'a Key<F>)
 where
     F: FloatPolicy;
 
-impl<F> ser::Serializer for Serializer<F>
+impl<F> fmt::Display for Render<'_, F>
+where
+    F: FloatPolicy,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn float<F>(f: &mut fmt::Formatter<'_>, float: &Float<F>) -> fmt::Result
+        where
+            F: FloatPolicy,
+        {
+            match float::to_f64(float) {
+                Some(value) => write!(f, "{}", value),
+                None => f.write_str("float"),
+            }
+        }
+
+        fn list<I>(f: &mut fmt::Formatter<'_>, iter: I) -> fmt::Result
+        where
+            I: IntoIterator,
+            I::Item: fmt::Display,
+        {
+            f.write_str("[")?;
+
+            for (index, item) in iter.into_iter().enumerate() {
+                if index > 0 {
+                    f.write_str(", ")?;
+                }
+
+                write!(f, "{}", item)?;
+            }
+
+            f.write_str("]")
+        }
+
+        match self.0 {
+            Key::Null => f.write_str("null"),
+            Key::Unit => f.write_str("()"),
+            Key::Bool(value) => write!(f, "{}", value),
+            Key::Integer(value) => write!(f, "{}", value),
+            Key::Float(value) => float(f, value),
+            Key::Bytes(value) => list(f, value.iter()),
+            Key::Char(value) => write!(f, "{}", value),
+            Key::String(value) => f.write_str(value),
+            Key::Seq(seq) => list(f, seq.iter().map(Render)),
+            Key::Map(map) => {
+                f.write_str("{")?;
+
+                for (index, (key, value)) in map.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(", ")?;
+                    }
+
+                    write!(f, "{}: {}", Render(key), Render(value))?;
+                }
+
+                f.write_str("}")
+            }
+            Key::IntegerSeq(seq) => list(f, seq.iter()),
+            Key::FloatSeq(seq) => {
+                f.write_str("[")?;
+
+                for (index, value) in seq.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(", ")?;
+                    }
+
+                    float(f, &value)?;
+                }
+
+                f.write_str("]")
+            }
+        }
+    }
+}
+
+/// A [Serializer] which produces a [Key] using the float policy `F`.
+///
+/// This is what [to_key] and [to_key_with] use to build keys, and can be used
+/// directly where a [Serializer] is expected. Like when transcoding a document
+/// straight into a key with [`serde_transcode`], without deserializing it into
+/// an intermediate type first.
+///
+/// The compound types it produces are [SerializeVec], [SerializeTupleVariant],
+/// [SerializeMap] and [SerializeStructVariant]. How values are represented can
+/// be configured using [KeySerializer::with_config], and values can be left
+/// out as they are serialized using [KeySerializer::with_hooks].
+///
+/// [Serializer]: serde::Serializer
+/// [`serde_transcode`]: https://docs.rs/serde-transcode
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "ordered-float")] {
+/// use serde_derive::Serialize;
+/// use serde_hashkey::{to_key_with_ordered_float, Key, KeySerializer, OrderedFloatPolicy};
+///
+/// #[derive(Serialize)]
+/// struct Point {
+///     x: f64,
+///     y: f64,
+/// }
+///
+/// let mut json = serde_json::Deserializer::from_str(r#"{"x": 1.5, "y": -2.0}"#);
+/// let key: Key<OrderedFloatPolicy> = serde_transcode::transcode(&mut json, KeySerializer::new())?;
+/// assert_eq!(key, to_key_with_ordered_float(&Point { x: 1.5, y: -2.0 })?);
+/// # }
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// ```
+pub struct KeySerializer<F, H = NoHooks>
+where
+    F: FloatPolicy,
+{
+    config: KeyConfig,
+    hooks: H,
+    _marker: PhantomData<F>,
+}
+
+impl<F> KeySerializer<F>
+where
+    F: FloatPolicy,
+{
+    /// Construct a new serializer using the default [KeyConfig].
+    pub const fn new() -> Self {
+        Self::with_config(KeyConfig::new())
+    }
+
+    /// Construct a new serializer using the given [KeyConfig].
+    pub const fn with_config(config: KeyConfig) -> Self {
+        Self::with_hooks(config, NoHooks)
+    }
+}
+
+impl<F, H> KeySerializer<F, H>
+where
+    F: FloatPolicy,
+    H: Hooks<F>,
+{
+    /// Construct a new serializer using the given [KeyConfig], which calls the
+    /// given [Hooks] as it enters and leaves values.
+    pub const fn with_hooks(config: KeyConfig, hooks: H) -> Self {
+        Self {
+            config,
+            hooks,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F> Default for KeySerializer<F>
+where
+    F: FloatPolicy,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F, H> Clone for KeySerializer<F, H>
 where
     F: FloatPolicy,
+    H: Copy,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<F, H> Copy for KeySerializer<F, H>
+where
+    F: FloatPolicy,
+    H: Copy,
+{
+}
+
+impl<F, H> ser::Serializer for KeySerializer<F, H>
+where
+    F: FloatPolicy,
+    H: Hooks<F>,
 {
     type Ok = Key<F>;
     type Error = Error;
 
-    type SerializeSeq = SerializeVec<F>;
-    type SerializeTuple = SerializeVec<F>;
-    type SerializeTupleStruct = SerializeVec<F>;
-    type SerializeTupleVariant = SerializeTupleVariant<F>;
-    type SerializeMap = SerializeMap<F>;
-    type SerializeStruct = SerializeMap<F>;
-    type SerializeStructVariant = SerializeStructVariant<F>;
+    type SerializeSeq = SerializeVec<F, H>;
+    type SerializeTuple = SerializeVec<F, H>;
+    type SerializeTupleStruct = SerializeVec<F, H>;
+    type SerializeTupleVariant = SerializeTupleVariant<F, H>;
+    type SerializeMap = SerializeMap<F, H>;
+    type SerializeStruct = SerializeMap<F, H>;
+    type SerializeStructVariant = SerializeStructVariant<F, H>;
 
     #[inline]
     fn serialize_bool(self, value: bool) -> Result<Key<F>, Error> {
@@ -135,9 +606,7 @@ where
 
     #[inline]
     fn serialize_f32(self, value: f32) -> Result<Key<F>, Error> {
-        Ok(Key::Float(Float::F32(
-            <F::F32 as FloatRepr<f32>>::serialize(value)?,
-        )))
+        Ok(Key::Float(F::serialize_f32(value)?))
     }
 
     #[inline]
@@ -149,9 +618,7 @@ where
 
     #[inline]
     fn serialize_char(self, value: char) -> Result<Key<F>, Error> {
-        let mut s = String::new();
-        s.push(value);
-        self.serialize_str(&s)
+        Ok(Key::Char(value))
     }
 
     #[inline]
@@ -170,31 +637,52 @@ where
     }
 
     #[inline]
-    fn serialize_unit_struct(self, _name: &'static str) -> Result<Key<F>, Error> {
-        self.serialize_unit()
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Key<F>, Error> {
+        Ok(tagged(type_name(self.config, name), Key::Unit))
     }
 
     #[inline]
     fn serialize_unit_variant(
         self,
-        _name: &'static str,
+        name: &'static str,
         _variant_index: u32,
         variant: &'static str,
     ) -> Result<Key<F>, Error> {
-        self.serialize_str(variant)
+        let key = self.config.variant(intern(variant), None);
+        Ok(tagged(type_name(self.config, name), key))
     }
 
     #[inline]
-    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<Key<F>, Error>
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<Key<F>, Error>
     where
         T: ?Sized + ser::Serialize,
     {
-        value.serialize(self)
+        // NB: `half::f16` is serialized as a newtype struct, but is stored as
+        // a float.
+        if float::is_f16(name) {
+            let key = value.serialize(self)?;
+            return float::from_newtype_struct(name, key);
+        }
+
+        let type_name = match type_name(self.config, name) {
+            Some(type_name) => type_name,
+            None => return value.serialize(self),
+        };
+
+        let mut hooks = self.hooks;
+
+        match value.serialize(KeySerializer::with_hooks(self.config, hooks.reborrow())) {
+            Ok(key) => Ok(tagged(Some(type_name), key)),
+            Err(error) => {
+                hooks.capture(|partial| partial.map(|key| tagged(Some(type_name), key)));
+                Err(tagged_error(Some(type_name), error))
+            }
+        }
     }
 
     fn serialize_newtype_variant<T>(
         self,
-        _name: &'static str,
+        name: &'static str,
         _variant_index: u32,
         variant: &'static str,
         value: &T,
@@ -202,13 +690,39 @@ where
     where
         T: ?Sized + ser::Serialize,
     {
-        let value = (Key::from(variant.to_owned()), to_key_with_policy(&value)?);
-        Ok(Key::Map([value].into()))
+        let config = self.config;
+
+        if config.get_transparent_newtype_variants() {
+            return value.serialize(self);
+        }
+
+        let type_name = type_name(config, name);
+        let segment = Segment::Field(config.variant_field(variant));
+        let mut hooks = self.hooks;
+
+        match serialize_at(config, &mut hooks, segment, value) {
+            Ok(Some(value)) => Ok(tagged(
+                type_name,
+                config.variant(intern(variant), Some(value)),
+            )),
+            Ok(None) => Ok(tagged(
+                type_name,
+                config.incomplete_variant(intern(variant), None),
+            )),
+            Err(error) => {
+                hooks.capture(|partial| {
+                    let key = config.incomplete_variant(intern(variant), partial);
+                    Some(tagged(type_name, key))
+                });
+
+                Err(tagged_error(type_name, error.at(segment.into())))
+            }
+        }
     }
 
     #[inline]
     fn serialize_none(self) -> Result<Key<F>, Error> {
-        self.serialize_unit()
+        Ok(Key::Null)
     }
 
     #[inline]
@@ -216,12 +730,15 @@ where
     where
         T: ?Sized + ser::Serialize,
     {
-        value.serialize(self)
+        value.serialize(self).map(Key::some)
     }
 
     fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
         Ok(SerializeVec {
             vec: Vec::with_capacity(len.unwrap_or(0)),
+            type_name: None,
+            config: self.config,
+            hooks: self.hooks,
         })
     }
 
@@ -231,50 +748,80 @@ where
 
     fn serialize_tuple_struct(
         self,
-        _name: &'static str,
+        name: &'static str,
         len: usize,
     ) -> Result<Self::SerializeTupleStruct, Error> {
-        self.serialize_tuple(len)
+        Ok(SerializeVec {
+            vec: Vec::with_capacity(len),
+            type_name: type_name(self.config, name),
+            config: self.config,
+            hooks: self.hooks,
+        })
     }
 
     fn serialize_tuple_variant(
-        self,
-        _name: &'static str,
+        mut self,
+        name: &'static str,
         _variant_index: u32,
         variant: &'static str,
         len: usize,
     ) -> Result<Self::SerializeTupleVariant, Error> {
+        let excluded = !self
+            .hooks
+            .enter(Segment::Field(self.config.variant_field(variant)));
+
         Ok(SerializeTupleVariant {
-            name: String::from(variant),
+            name: intern(variant),
             vec: Vec::with_capacity(len),
+            type_name: type_name(self.config, name),
+            config: self.config,
+            hooks: self.hooks,
+            excluded,
         })
     }
 
-    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Error> {
         Ok(SerializeMap {
-            map: Vec::new(),
+            map: Vec::with_capacity(len.unwrap_or(0)),
             next_key: None,
+            type_name: None,
+            config: self.config,
+            hooks: self.hooks,
         })
     }
 
     fn serialize_struct(
         self,
-        _name: &'static str,
+        name: &'static str,
         len: usize,
     ) -> Result<Self::SerializeStruct, Error> {
-        self.serialize_map(Some(len))
+        Ok(SerializeMap {
+            map: Vec::with_capacity(len),
+            next_key: None,
+            type_name: type_name(self.config, name),
+            config: self.config,
+            hooks: self.hooks,
+        })
     }
 
     fn serialize_struct_variant(
-        self,
-        _name: &'static str,
+        mut self,
+        name: &'static str,
         _variant_index: u32,
         variant: &'static str,
-        _len: usize,
+        len: usize,
     ) -> Result<Self::SerializeStructVariant, Error> {
+        let excluded = !self
+            .hooks
+            .enter(Segment::Field(self.config.variant_field(variant)));
+
         Ok(SerializeStructVariant {
-            name: String::from(variant),
-            map: Vec::new(),
+            name: intern(variant),
+            map: Vec::with_capacity(len),
+            type_name: type_name(self.config, name),
+            config: self.config,
+            hooks: self.hooks,
+            excluded,
         })
     }
 
@@ -284,40 +831,299 @@ where
     }
 }
 
-pub struct SerializeVec<F>
+/// Builder for a sequence [Key], which implements [SerializeSeq],
+/// [SerializeTuple] and [SerializeTupleStruct].
+///
+/// This is what serializing a sequence or a tuple produces, and can also be
+/// used to build a sequence key manually out of serializable elements.
+/// Sequences of integers or floats of a single width are compacted the same
+/// way as through [to_key].
+///
+/// [SerializeSeq]: serde::ser::SerializeSeq
+/// [SerializeTuple]: serde::ser::SerializeTuple
+/// [SerializeTupleStruct]: serde::ser::SerializeTupleStruct
+///
+/// # Examples
+///
+/// ```
+/// use serde::ser::SerializeSeq as _;
+/// use serde_hashkey::{to_key, Key, SerializeVec};
+///
+/// let mut seq = SerializeVec::new();
+/// seq.serialize_element("hello")?;
+/// seq.serialize_element(&42u32)?;
+/// let key: Key = seq.end()?;
+///
+/// assert_eq!(key, to_key(&("hello", 42u32))?);
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+pub struct SerializeVec<F, H = NoHooks>
 where
     F: FloatPolicy,
 {
     vec: Vec<Key<F>>,
+    type_name: Option<&'static str>,
+    config: KeyConfig,
+    hooks: H,
+}
+
+impl<F> SerializeVec<F>
+where
+    F: FloatPolicy,
+{
+    /// Construct a new empty sequence builder.
+    pub const fn new() -> Self {
+        Self {
+            vec: Vec::new(),
+            type_name: None,
+            config: KeyConfig::new(),
+            hooks: NoHooks,
+        }
+    }
+
+    /// Construct a new empty sequence builder with room for `capacity`
+    /// elements.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            vec: Vec::with_capacity(capacity),
+            type_name: None,
+            config: KeyConfig::new(),
+            hooks: NoHooks,
+        }
+    }
 }
 
-pub struct SerializeTupleVariant<F>
+impl<F> Default for SerializeVec<F>
+where
+    F: FloatPolicy,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Builder for the [Key] of a tuple variant, which implements
+/// [SerializeTupleVariant].
+///
+/// The resulting key is a map with a single entry, from the name of the
+/// variant to the sequence of its fields.
+///
+/// [SerializeTupleVariant]: serde::ser::SerializeTupleVariant
+///
+/// # Examples
+///
+/// ```
+/// use serde::ser::SerializeTupleVariant as _;
+/// use serde_derive::Serialize;
+/// use serde_hashkey::{to_key, Key, SerializeTupleVariant};
+///
+/// #[derive(Serialize)]
+/// enum Shape {
+///     Rectangle(u32, u32),
+/// }
+///
+/// let mut variant = SerializeTupleVariant::new("Rectangle");
+/// variant.serialize_field(&3u32)?;
+/// variant.serialize_field(&4u32)?;
+/// let key: Key = variant.end()?;
+///
+/// assert_eq!(key, to_key(&Shape::Rectangle(3, 4))?);
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+pub struct SerializeTupleVariant<F, H = NoHooks>
 where
     F: FloatPolicy,
 {
-    name: String,
+    name: Arc<str>,
     vec: Vec<Key<F>>,
+    type_name: Option<&'static str>,
+    config: KeyConfig,
+    hooks: H,
+    /// Whether the content of the variant is left out by the hooks.
+    excluded: bool,
 }
 
-pub struct SerializeMap<F>
+impl<F> SerializeTupleVariant<F>
+where
+    F: FloatPolicy,
+{
+    /// Construct a new builder for the tuple variant with the given name.
+    pub fn new(variant: &str) -> Self {
+        Self {
+            name: variant.into(),
+            vec: Vec::new(),
+            type_name: None,
+            config: KeyConfig::new(),
+            hooks: NoHooks,
+            excluded: false,
+        }
+    }
+}
+
+/// Builder for a map [Key], which implements [SerializeMap] and
+/// [SerializeStruct].
+///
+/// This is what serializing a map or a struct produces, and can also be
+/// used to build a map key manually out of serializable keys and values.
+/// Entries are kept in the order they are added. The length hint of maps and
+/// the number of fields of structs are used to preallocate the entries.
+///
+/// [SerializeMap]: serde::ser::SerializeMap
+/// [SerializeStruct]: serde::ser::SerializeStruct
+///
+/// # Examples
+///
+/// ```
+/// use serde::ser::SerializeMap as _;
+/// use serde_derive::Serialize;
+/// use serde_hashkey::{to_key, Key, SerializeMap};
+///
+/// #[derive(Serialize)]
+/// struct Author {
+///     name: &'static str,
+///     age: u32,
+/// }
+///
+/// let mut map = SerializeMap::new();
+/// map.serialize_entry("name", "Noah")?;
+/// map.serialize_entry("age", &42u32)?;
+/// let key: Key = map.end()?;
+///
+/// assert_eq!(key, to_key(&Author { name: "Noah", age: 42 })?);
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+pub struct SerializeMap<F, H = NoHooks>
 where
     F: FloatPolicy,
 {
     map: Vec<(Key<F>, Key<F>)>,
     next_key: Option<Key<F>>,
+    type_name: Option<&'static str>,
+    config: KeyConfig,
+    hooks: H,
+}
+
+impl<F> SerializeMap<F>
+where
+    F: FloatPolicy,
+{
+    /// Construct a new empty map builder.
+    pub const fn new() -> Self {
+        Self {
+            map: Vec::new(),
+            next_key: None,
+            type_name: None,
+            config: KeyConfig::new(),
+            hooks: NoHooks,
+        }
+    }
+
+    /// Construct a new empty map builder with room for `capacity` entries.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            map: Vec::with_capacity(capacity),
+            next_key: None,
+            type_name: None,
+            config: KeyConfig::new(),
+            hooks: NoHooks,
+        }
+    }
+}
+
+impl<F> Default for SerializeMap<F>
+where
+    F: FloatPolicy,
+{
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
-pub struct SerializeStructVariant<F>
+/// Builder for the [Key] of a struct variant, which implements
+/// [SerializeStructVariant].
+///
+/// The resulting key is a map with a single entry, from the name of the
+/// variant to a map of its fields.
+///
+/// [SerializeStructVariant]: serde::ser::SerializeStructVariant
+///
+/// # Examples
+///
+/// ```
+/// use serde::ser::SerializeStructVariant as _;
+/// use serde_derive::Serialize;
+/// use serde_hashkey::{to_key, Key, SerializeStructVariant};
+///
+/// #[derive(Serialize)]
+/// enum Shape {
+///     Circle { radius: u32 },
+/// }
+///
+/// let mut variant = SerializeStructVariant::new("Circle");
+/// variant.serialize_field("radius", &7u32)?;
+/// let key: Key = variant.end()?;
+///
+/// assert_eq!(key, to_key(&Shape::Circle { radius: 7 })?);
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+pub struct SerializeStructVariant<F, H = NoHooks>
 where
     F: FloatPolicy,
 {
-    name: String,
+    name: Arc<str>,
     map: Vec<(Key<F>, Key<F>)>,
+    type_name: Option<&'static str>,
+    config: KeyConfig,
+    hooks: H,
+    /// Whether the content of the variant is left out by the hooks.
+    excluded: bool,
+}
+
+impl<F> SerializeStructVariant<F>
+where
+    F: FloatPolicy,
+{
+    /// Construct a new builder for the struct variant with the given name.
+    pub fn new(variant: &str) -> Self {
+        Self {
+            name: variant.into(),
+            map: Vec::new(),
+            type_name: None,
+            config: KeyConfig::new(),
+            hooks: NoHooks,
+            excluded: false,
+        }
+    }
+}
+
+/// Serialize a value at the given segment of the path, with serializers which
+/// call the given hooks.
+///
+/// Returns `None` if the hooks leave the value out.
+fn serialize_at<F, H, T>(
+    config: KeyConfig,
+    hooks: &mut H,
+    segment: Segment<'_>,
+    value: &T,
+) -> Result<Option<Key<F>>, Error>
+where
+    F: FloatPolicy,
+    H: Hooks<F>,
+    T: ?Sized + ser::Serialize,
+{
+    if !hooks.enter(segment) {
+        return Ok(None);
+    }
+
+    let key = value.serialize(KeySerializer::with_hooks(config, hooks.reborrow()))?;
+    hooks.leave(segment);
+    Ok(Some(key))
 }
 
-impl<F> ser::SerializeSeq for SerializeVec<F>
+impl<F, H> ser::SerializeSeq for SerializeVec<F, H>
 where
     F: FloatPolicy,
+    H: Hooks<F>,
 {
     type Ok = Key<F>;
     type Error = Error;
@@ -326,18 +1132,37 @@ where
     where
         T: ?Sized + ser::Serialize,
     {
-        self.vec.push(to_key_with_policy(&value)?);
-        Ok(())
+        let index = self.vec.len();
+        let segment = Segment::Index(index);
+
+        match serialize_at(self.config, &mut self.hooks, segment, value) {
+            Ok(value) => {
+                self.vec.extend(value);
+                Ok(())
+            }
+            Err(error) => {
+                let (vec, type_name) = (&mut self.vec, self.type_name);
+
+                self.hooks.capture(|partial| {
+                    let mut vec = mem::take(vec);
+                    vec.extend(partial);
+                    Some(tagged(type_name, Key::seq(vec)))
+                });
+
+                Err(tagged_error(type_name, error.at(segment.into())))
+            }
+        }
     }
 
     fn end(self) -> Result<Key<F>, Error> {
-        Ok(Key::Seq(self.vec.into()))
+        Ok(tagged(self.type_name, Key::seq(self.vec)))
     }
 }
 
-impl<F> ser::SerializeTuple for SerializeVec<F>
+impl<F, H> ser::SerializeTuple for SerializeVec<F, H>
 where
     F: FloatPolicy,
+    H: Hooks<F>,
 {
     type Ok = Key<F>;
     type Error = Error;
@@ -354,9 +1179,10 @@ where
     }
 }
 
-impl<F> ser::SerializeTupleStruct for SerializeVec<F>
+impl<F, H> ser::SerializeTupleStruct for SerializeVec<F, H>
 where
     F: FloatPolicy,
+    H: Hooks<F>,
 {
     type Ok = Key<F>;
     type Error = Error;
@@ -373,9 +1199,10 @@ where
     }
 }
 
-impl<F> ser::SerializeTupleVariant for SerializeTupleVariant<F>
+impl<F, H> ser::SerializeTupleVariant for SerializeTupleVariant<F, H>
 where
     F: FloatPolicy,
+    H: Hooks<F>,
 {
     type Ok = Key<F>;
     type Error = Error;
@@ -384,19 +1211,56 @@ where
     where
         T: ?Sized + ser::Serialize,
     {
-        self.vec.push(to_key_with_policy(&value)?);
-        Ok(())
+        if self.excluded {
+            return Ok(());
+        }
+
+        let index = self.vec.len();
+        let segment = Segment::Index(index);
+
+        match serialize_at(self.config, &mut self.hooks, segment, value) {
+            Ok(value) => {
+                self.vec.extend(value);
+                Ok(())
+            }
+            Err(error) => {
+                let (vec, name) = (&mut self.vec, &self.name);
+                let (config, type_name) = (self.config, self.type_name);
+
+                self.hooks.capture(|partial| {
+                    let mut vec = mem::take(vec);
+                    vec.extend(partial);
+                    let key = config.variant(name.clone(), Some(Key::seq(vec)));
+                    Some(tagged(type_name, key))
+                });
+
+                let error = error
+                    .at(segment.into())
+                    .at(config.variant_segment(&self.name));
+
+                Err(tagged_error(type_name, error))
+            }
+        }
     }
 
-    fn end(self) -> Result<Key<F>, Error> {
-        let value = (Key::from(self.name), Key::Seq(self.vec.into()));
-        Ok(Key::Map([value].into()))
+    fn end(mut self) -> Result<Key<F>, Error> {
+        if self.excluded {
+            let key = self.config.incomplete_variant(self.name, None);
+            return Ok(tagged(self.type_name, key));
+        }
+
+        self.hooks
+            .leave(Segment::Field(self.config.variant_field(&self.name)));
+
+        let key = self.config.variant(self.name, Some(Key::seq(self.vec)));
+        Ok(tagged(self.type_name, key))
     }
 }
 
-impl<F> ser::SerializeMap for SerializeMap<F>
+impl<F, H> ser::SerializeMap for SerializeMap<F, H>
 where
     F: FloatPolicy,
+    H: Hooks<F>,
 {
     type Ok = Key<F>;
     type Error = Error;
@@ -405,8 +1269,24 @@ where
     where
         T: ?Sized + ser::Serialize,
     {
-        self.next_key = Some(to_key_with_policy(&key)?);
-        Ok(())
+        let segment = Segment::Key(self.map.len());
+
+        match serialize_at(self.config, &mut self.hooks, segment, key) {
+            Ok(key) => {
+                self.next_key = key;
+                Ok(())
+            }
+            Err(error) => {
+                let (map, type_name) = (&mut self.map, self.type_name);
+
+                // NB: the partial key of a failing map key is left out, since
+                // the entry it belongs to is incomplete.
+                self.hooks
+                    .capture(|_| Some(tagged(type_name, Key::Map(mem::take(map).into()))));
+
+                Err(tagged_error(type_name, error.at(segment.into())))
+            }
+        }
     }
 
     fn serialize_value<T>(&mut self, value: &T) -> Result<(), Error>
@@ -418,18 +1298,42 @@ where
             None => return Err(Error::MissingValue),
         };
 
-        self.map.push((key, to_key_with_policy(&value)?));
-        Ok(())
+        let segment = match &key {
+            Key::String(field) => Segment::Field(field),
+            _ => Segment::Entry(self.map.len()),
+        };
+
+        match serialize_at(self.config, &mut self.hooks, segment, value) {
+            Ok(Some(value)) => {
+                self.map.push((key, value));
+                Ok(())
+            }
+            Ok(None) => Ok(()),
+            Err(error) => {
+                let error = tagged_error(self.type_name, error.at(segment.into()));
+                let (map, type_name) = (&mut self.map, self.type_name);
+
+                self.hooks.capture(|partial| {
+                    let mut map = mem::take(map);
+                    map.extend(partial.map(|value| (key, value)));
+                    Some(tagged(type_name, Key::Map(map.into())))
+                });
+
+                Err(error)
+            }
+        }
     }
 
     fn end(self) -> Result<Key<F>, Error> {
-        Ok(Key::Map(self.map.into()))
+        check_duplicate_keys(self.config, &self.map)?;
+        Ok(tagged(self.type_name, Key::Map(self.map.into())))
     }
 }
 
-impl<F> ser::SerializeStruct for SerializeMap<F>
+impl<F, H> ser::SerializeStruct for SerializeMap<F, H>
 where
     F: FloatPolicy,
+    H: Hooks<F>,
 {
     type Ok = Key<F>;
     type Error = Error;
@@ -438,7 +1342,9 @@ where
     where
         T: ?Sized + ser::Serialize,
     {
-        ser::SerializeMap::serialize_key(self, key)?;
+        // NB: Field names are interned, so they are only allocated once no
+        // matter how many values they are serialized for.
+        self.next_key = Some(Key::String(intern(key)));
         ser::SerializeMap::serialize_value(self, value)
     }
 
@@ -447,9 +1353,10 @@ where
     }
 }
 
-impl<F> ser::SerializeStructVariant for SerializeStructVariant<F>
+impl<F, H> ser::SerializeStructVariant for SerializeStructVariant<F, H>
 where
     F: FloatPolicy,
+    H: Hooks<F>,
 {
     type Ok = Key<F>;
     type Error = Error;
@@ -458,13 +1365,52 @@ where
     where
         T: ?Sized + ser::Serialize,
     {
-        self.map
-            .push((Key::from(String::from(key)), to_key_with_policy(&value)?));
-        Ok(())
+        if self.excluded {
+            return Ok(());
+        }
+
+        let segment = Segment::Field(key);
+
+        match serialize_at(self.config, &mut self.hooks, segment, value) {
+            Ok(Some(value)) => {
+                self.map.push((Key::String(intern(key)), value));
+                Ok(())
+            }
+            Ok(None) => Ok(()),
+            Err(error) => {
+                let (map, name) = (&mut self.map, &self.name);
+                let (config, type_name) = (self.config, self.type_name);
+
+                self.hooks.capture(|partial| {
+                    let mut map = mem::take(map);
+                    map.extend(partial.map(|value| (Key::String(intern(key)), value)));
+                    let key = config.variant(name.clone(), Some(Key::Map(map.into())));
+                    Some(tagged(type_name, key))
+                });
+
+                let error = error
+                    .at(segment.into())
+                    .at(config.variant_segment(&self.name));
+
+                Err(tagged_error(type_name, error))
+            }
+        }
     }
 
-    fn end(self) -> Result<Key<F>, Error> {
-        let value = (Key::from(self.name), Key::Map(self.map.into()));
-        Ok(Key::Map([value].into()))
+    fn end(mut self) -> Result<Key<F>, Error> {
+        if self.excluded {
+            let key = self.config.incomplete_variant(self.name, None);
+            return Ok(tagged(self.type_name, key));
+        }
+
+        self.hooks
+            .leave(Segment::Field(self.config.variant_field(&self.name)));
+
+        check_duplicate_keys(self.config, &self.map)?;
+
+        let key = self
+            .config
+            .variant(self.name, Some(Key::Map(self.map.into())));
+        Ok(tagged(self.type_name, key))
     }
 }
@@ -0,0 +1,305 @@
+//! Set operations over map-shaped keys.
+
+use std::collections::BTreeMap;
+
+use crate::float::FloatPolicy;
+use crate::key::Key;
+
+impl<F> Key<F>
+where
+    F: FloatPolicy + Clone + Ord,
+{
+    /// Compute the intersection between two keys.
+    ///
+    /// For maps, this is the entries whose key and value match in both maps.
+    /// If an entry has the same key in both maps and both values are maps,
+    /// the intersection of those maps is computed recursively and the entry is
+    /// kept if it is not empty.
+    ///
+    /// Keys which are not both maps are kept if they are equal, otherwise the
+    /// intersection is an empty map.
+    ///
+    /// The result is [normalized][Key::normalize].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_derive::Serialize;
+    /// use serde_hashkey::to_key;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Author {
+    ///     name: &'static str,
+    ///     age: u32,
+    /// }
+    ///
+    /// #[derive(Serialize)]
+    /// struct Book {
+    ///     title: &'static str,
+    ///     author: Author,
+    /// }
+    ///
+    /// #[derive(Serialize)]
+    /// struct Partial {
+    ///     author: PartialAuthor,
+    /// }
+    ///
+    /// #[derive(Serialize)]
+    /// struct PartialAuthor {
+    ///     name: &'static str,
+    /// }
+    ///
+    /// let a = to_key(&Book { title: "Birds of a feather", author: Author { name: "Noah", age: 42 } })?;
+    /// let b = to_key(&Book { title: "Fishes of a fin", author: Author { name: "Noah", age: 43 } })?;
+    ///
+    /// let expected = to_key(&Partial { author: PartialAuthor { name: "Noah" } })?;
+    /// assert_eq!(a.intersection(&b), expected);
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn intersection(&self, other: &Key<F>) -> Key<F> {
+        match (self, other) {
+            (Key::Map(a), Key::Map(b)) => {
+                let b = entries(b);
+                let mut out = Vec::new();
+
+                for (key, value) in a.iter() {
+                    let other = match b.get(key) {
+                        Some(other) => *other,
+                        None => continue,
+                    };
+
+                    let value = if value == other {
+                        value.clone()
+                    } else if value.is_map() && other.is_map() {
+                        let nested = value.intersection(other);
+
+                        if nested.is_empty_map() {
+                            continue;
+                        }
+
+                        nested
+                    } else {
+                        continue;
+                    };
+
+                    out.push((key.clone(), value));
+                }
+
+                Key::Map(out.into()).normalize()
+            }
+            (a, b) if a == b => a.clone().normalize(),
+            _ => Key::Map(Box::default()),
+        }
+    }
+
+    /// Compute the difference between two keys.
+    ///
+    /// For maps, this is the entries in `self` which are either missing from
+    /// `other` or which have a different value. If an entry has the same key in
+    /// both maps and both values are maps, their difference is computed
+    /// recursively and the entry is kept if it is not empty.
+    ///
+    /// Keys which are not both maps are kept if they are different, otherwise
+    /// the difference is an empty map.
+    ///
+    /// The result is [normalized][Key::normalize].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_derive::Serialize;
+    /// use serde_hashkey::to_key;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Author {
+    ///     name: &'static str,
+    ///     age: u32,
+    /// }
+    ///
+    /// #[derive(Serialize)]
+    /// struct Partial {
+    ///     age: u32,
+    /// }
+    ///
+    /// let a = to_key(&Author { name: "Noah", age: 43 })?;
+    /// let b = to_key(&Author { name: "Noah", age: 42 })?;
+    ///
+    /// assert_eq!(a.difference(&b), to_key(&Partial { age: 43 })?);
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn difference(&self, other: &Key<F>) -> Key<F> {
+        match (self, other) {
+            (Key::Map(a), Key::Map(b)) => {
+                let b = entries(b);
+                let mut out = Vec::new();
+
+                for (key, value) in a.iter() {
+                    let value = match b.get(key) {
+                        None => value.clone(),
+                        Some(other) if value == *other => continue,
+                        Some(other) if value.is_map() && other.is_map() => {
+                            let diff = value.difference(other);
+
+                            if diff.is_empty_map() {
+                                continue;
+                            }
+
+                            diff
+                        }
+                        Some(..) => value.clone(),
+                    };
+
+                    out.push((key.clone(), value));
+                }
+
+                Key::Map(out.into()).normalize()
+            }
+            (a, b) if a == b => Key::Map(Box::default()),
+            (a, _) => a.clone().normalize(),
+        }
+    }
+
+    /// Test if this key is a subset of another key.
+    ///
+    /// This is intended for matching a partial key against a full key, like
+    /// when filtering subscriptions. The rules are:
+    /// * For maps, every entry in `self` must have an entry in `other` with an
+    ///   equal key, and a value which `self`'s value is a subset of.
+    /// * For sequences, `self` must be a prefix of `other` where every element
+    ///   is a subset of the corresponding element in `other`.
+    /// * Any other keys must be equal.
+    ///
+    /// Every key is a subset of itself, and if two keys are subsets of each
+    /// other they are equal up to the order and duplication of map entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_derive::Serialize;
+    /// use serde_hashkey::to_key;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Event {
+    ///     topic: &'static str,
+    ///     user: u32,
+    ///     tags: Vec<&'static str>,
+    /// }
+    ///
+    /// #[derive(Serialize)]
+    /// struct Filter {
+    ///     topic: &'static str,
+    ///     tags: Vec<&'static str>,
+    /// }
+    ///
+    /// let event = to_key(&Event { topic: "books", user: 42, tags: vec!["new", "fiction"] })?;
+    ///
+    /// assert!(to_key(&Filter { topic: "books", tags: vec!["new"] })?.is_subset_of(&event));
+    /// assert!(!to_key(&Filter { topic: "music", tags: vec![] })?.is_subset_of(&event));
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn is_subset_of(&self, other: &Key<F>) -> bool {
+        match (self, other) {
+            (Key::Map(a), Key::Map(b)) => a
+                .iter()
+                .all(|(key, value)| b.iter().any(|(k, v)| k == key && value.is_subset_of(v))),
+            (a, b) => match (a.seq_elements(), b.seq_elements()) {
+                (Some(a), Some(b)) => {
+                    a.len() <= b.len() && a.iter().zip(b.iter()).all(|(a, b)| a.is_subset_of(b))
+                }
+                _ => a == b,
+            },
+        }
+    }
+
+    /// Merge two keys, producing the union of the entries of two maps.
+    ///
+    /// Entries from `other` replace entries in `self` with the same key, even
+    /// if both values are maps, see [Key::merge_deep] for a version which
+    /// merges them. If either key is not a map, `other` replaces `self`
+    /// wholesale.
+    ///
+    /// The result is [normalized][Key::normalize].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::key;
+    ///
+    /// let tenant = key!({ "tenant": "acme", "region": "eu" });
+    /// let query = key!({ "region": "us", "page": 2u32 });
+    ///
+    /// assert_eq!(
+    ///     tenant.merge(query),
+    ///     key!({ "page": 2u32, "region": "us", "tenant": "acme" })
+    /// );
+    ///
+    /// assert_eq!(key!({ "a": 1u32 }).merge(key!("b")), key!("b"));
+    /// ```
+    pub fn merge(self, other: Key<F>) -> Key<F> {
+        self.merge_with(other, false)
+    }
+
+    /// Merge two keys, recursively merging the values of entries which are
+    /// maps in both keys.
+    ///
+    /// This is the same as [Key::merge], except that when both `self` and
+    /// `other` have an entry with the same key whose values are maps, the
+    /// values are merged instead of replaced.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::key;
+    ///
+    /// let defaults = key!({ "page": { "size": 20u32, "number": 1u32 }, "sort": "name" });
+    /// let query = key!({ "page": { "number": 3u32 }, "sort": ["name", "age"] });
+    ///
+    /// assert_eq!(
+    ///     defaults.merge_deep(query),
+    ///     key!({ "page": { "number": 3u32, "size": 20u32 }, "sort": ["name", "age"] })
+    /// );
+    /// ```
+    pub fn merge_deep(self, other: Key<F>) -> Key<F> {
+        self.merge_with(other, true)
+    }
+
+    fn merge_with(self, other: Key<F>, deep: bool) -> Key<F> {
+        match (self, other) {
+            (Key::Map(a), Key::Map(b)) => {
+                let mut out = a.into_vec().into_iter().collect::<BTreeMap<_, _>>();
+
+                for (key, value) in b.into_vec() {
+                    let value = match out.remove(&key) {
+                        Some(existing) if deep && existing.is_map() && value.is_map() => {
+                            existing.merge_with(value, deep)
+                        }
+                        _ => value,
+                    };
+
+                    out.insert(key, value);
+                }
+
+                Key::Map(out.into_iter().collect()).normalize()
+            }
+            (_, other) => other.normalize(),
+        }
+    }
+
+    fn is_map(&self) -> bool {
+        matches!(self, Key::Map(..))
+    }
+
+    fn is_empty_map(&self) -> bool {
+        matches!(self, Key::Map(map) if map.is_empty())
+    }
+}
+
+/// Index the entries of a map by their keys, for lookups while walking the
+/// entries of another map. Later entries replace earlier ones with the same
+/// key, like when merging.
+fn entries<F>(map: &[(Key<F>, Key<F>)]) -> BTreeMap<&Key<F>, &Key<F>>
+where
+    F: FloatPolicy + Ord,
+{
+    map.iter().map(|(key, value)| (key, value)).collect()
+}
@@ -0,0 +1,201 @@
+//! Helpers for testing how types are represented as keys.
+//!
+//! These panic with a description of what went wrong, which includes the
+//! intermediate key and a diff between keys where relevant, so they are
+//! intended to be used in tests.
+//!
+//! # Examples
+//!
+//! ```
+//! use serde_derive::{Deserialize, Serialize};
+//! use serde_hashkey::testing::assert_roundtrip;
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Author {
+//!     name: String,
+//!     age: u32,
+//! }
+//!
+//! assert_roundtrip(&Author { name: String::from("Noah"), age: 42 });
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Write as _};
+
+use serde::de::DeserializeOwned;
+use serde::ser::Serialize;
+
+use crate::de::from_key;
+use crate::float::{FloatPolicy, RejectFloatPolicy};
+use crate::key::Key;
+use crate::path::KeyPath;
+use crate::ser::to_key_with_policy;
+
+cfg_ordered_float! {
+    use crate::float::OrderedFloatPolicy;
+}
+
+/// Assert that a value survives being serialized with [to_key] and
+/// deserialized with [from_key].
+///
+/// [to_key]: crate::to_key
+///
+/// # Panics
+///
+/// Panics if the value can't be serialized or deserialized, or if the
+/// deserialized value isn't equal to the original. The panic message includes
+/// the intermediate key, and if the values differ a diff between the key and
+/// the key of the deserialized value.
+#[track_caller]
+pub fn assert_roundtrip<T>(value: &T)
+where
+    T: fmt::Debug + PartialEq + Serialize + DeserializeOwned,
+{
+    roundtrip::<T, RejectFloatPolicy>(value);
+}
+
+cfg_ordered_float! {
+    /// Assert that a value survives being serialized with
+    /// [to_key_with_ordered_float] and deserialized with [from_key].
+    ///
+    /// See [assert_roundtrip] for details.
+    ///
+    /// [to_key_with_ordered_float]: crate::to_key_with_ordered_float
+    #[track_caller]
+    pub fn assert_roundtrip_ordered_float<T>(value: &T)
+    where
+        T: fmt::Debug + PartialEq + Serialize + DeserializeOwned,
+    {
+        roundtrip::<T, OrderedFloatPolicy>(value);
+    }
+}
+
+/// Assert that a value serializes into the expected key.
+///
+/// This is intended for golden tests, which catch changes to a type that
+/// would change its key and with it invalidate any keys that are already
+/// stored somewhere.
+///
+/// The value is serialized with the float policy of the expected key.
+///
+/// # Panics
+///
+/// Panics if the value can't be serialized, or if its key isn't equal to the
+/// expected key. The panic message includes both keys and a diff between
+/// them.
+///
+/// # Examples
+///
+/// ```
+/// use serde_derive::Serialize;
+/// use serde_hashkey::testing::assert_key_stable;
+/// use serde_hashkey::Key;
+///
+/// #[derive(Debug, Serialize)]
+/// struct Author {
+///     name: &'static str,
+///     age: u32,
+/// }
+///
+/// let expected: Key = Key::Map(vec![
+///     (Key::String("name".into()), Key::String("Noah".into())),
+///     (Key::String("age".into()), Key::from(42u32)),
+/// ].into());
+///
+/// assert_key_stable(&Author { name: "Noah", age: 42 }, &expected);
+/// ```
+#[track_caller]
+pub fn assert_key_stable<T, F>(value: &T, expected: &Key<F>)
+where
+    T: ?Sized + fmt::Debug + Serialize,
+    F: FloatPolicy + fmt::Debug + PartialEq,
+{
+    let key = match to_key_with_policy::<_, F>(value) {
+        Ok(key) => key,
+        Err(error) => panic!("failed to serialize {:#?} into a key: {}", value, error),
+    };
+
+    if key == *expected {
+        return;
+    }
+
+    panic!(
+        "the key of {:#?} has changed\n\nexpected: {:#?}\nactual: {:#?}\n\ndiff (- expected, + actual):\n{}\n\
+         if the change is intentional, update the expected key, but note that keys which are already stored will no longer match",
+        value,
+        expected,
+        key,
+        diff(expected, &key),
+    );
+}
+
+#[track_caller]
+fn roundtrip<T, F>(value: &T)
+where
+    T: fmt::Debug + PartialEq + Serialize + DeserializeOwned,
+    F: FloatPolicy + fmt::Debug + PartialEq,
+{
+    let key = match to_key_with_policy::<T, F>(value) {
+        Ok(key) => key,
+        Err(error) => panic!("failed to serialize {:#?} into a key: {}", value, error),
+    };
+
+    let output = match from_key::<T, F>(&key) {
+        Ok(output) => output,
+        Err(error) => panic!(
+            "failed to deserialize {:#?} from its key: {}\n\nkey: {:#?}",
+            value, error, key
+        ),
+    };
+
+    if output == *value {
+        return;
+    }
+
+    let diff = match to_key_with_policy::<T, F>(&output) {
+        Ok(other) => diff(&key, &other),
+        Err(error) => format!("  failed to serialize the deserialized value: {}\n", error),
+    };
+
+    panic!(
+        "{:#?} changed when round tripped through a key\n\ndeserialized: {:#?}\nkey: {:#?}\n\ndiff (- key, + key of deserialized value):\n{}\n\
+         this usually means that the Serialize and Deserialize implementations of the type disagree, like a field which is skipped when serializing but not defaulted when deserializing",
+        value, output, key, diff,
+    );
+}
+
+/// Produce a line based diff between the leaves of two keys.
+fn diff<F>(a: &Key<F>, b: &Key<F>) -> String
+where
+    F: FloatPolicy + fmt::Debug + PartialEq,
+{
+    let a = a.leaves().collect::<BTreeMap<KeyPath, &Key<F>>>();
+    let b = b.leaves().collect::<BTreeMap<KeyPath, &Key<F>>>();
+
+    let mut out = String::new();
+
+    for (path, a) in &a {
+        match b.get(path) {
+            Some(b) if a == b => {}
+            Some(b) => {
+                let _ = writeln!(out, "  - {}: {:?}", path, a);
+                let _ = writeln!(out, "  + {}: {:?}", path, b);
+            }
+            None => {
+                let _ = writeln!(out, "  - {}: {:?}", path, a);
+            }
+        }
+    }
+
+    for (path, b) in &b {
+        if !a.contains_key(path) {
+            let _ = writeln!(out, "  + {}: {:?}", path, b);
+        }
+    }
+
+    if out.is_empty() {
+        out.push_str("  no scalar values differ, so the keys differ in empty sequences or maps, or in the order of map entries\n");
+    }
+
+    out
+}
@@ -0,0 +1,238 @@
+//! Transformation of the values or map keys in a key.
+
+use std::vec;
+
+use crate::float::FloatPolicy;
+use crate::key::Key;
+
+impl<F> Key<F>
+where
+    F: FloatPolicy + Clone + Ord,
+{
+    /// Apply `f` to every leaf value in this key, and return the result.
+    ///
+    /// Leaf values are every value which isn't a map or a sequence, such as
+    /// the elements of sequences and the values of map entries, or this key
+    /// itself if it is one. The elements of the compact [Key::IntegerSeq] and
+    /// [Key::FloatSeq] are passed to `f` one at a time as a [Key::Integer] or
+    /// [Key::Float]. Map keys are left as they are, see [Key::map_keys] to
+    /// transform them.
+    ///
+    /// Maps and sequences keep their structure, but sequences are stored
+    /// compactly again if possible so that they stay equal to the key of the
+    /// equivalent serialized sequence.
+    ///
+    /// Traversal does not use recursion, so it is safe to use on deeply nested
+    /// keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_hashkey::{key, Key};
+    ///
+    /// let key = key!({ "Accept": "TEXT/HTML", "Sizes": [1u32, 2u32] });
+    ///
+    /// let key = key.map_values(|value| match value {
+    ///     Key::String(string) => Key::String(string.to_lowercase().into()),
+    ///     Key::Integer(..) => Key::from(value.as_u64().unwrap_or_default() * 10),
+    ///     value => value,
+    /// });
+    ///
+    /// assert_eq!(key, key!({ "Accept": "text/html", "Sizes": [10u64, 20u64] }));
+    /// ```
+    pub fn map_values<T>(self, f: T) -> Key<F>
+    where
+        T: FnMut(Key<F>) -> Key<F>,
+    {
+        self.transform(f, Target::Values)
+    }
+
+    /// Apply `f` to the key of every map entry in this key, and return the
+    /// result.
+    ///
+    /// This includes the entries of maps which are nested in sequences or in
+    /// the values of other map entries, but not of maps used as map keys since
+    /// those are passed to `f` as a whole. Maps whose keys were changed by
+    /// `f` are sorted by key again, so a [normalized][Key::normalize] key stays
+    /// normalized.
+    ///
+    /// Traversal does not use recursion, so it is safe to use on deeply nested
+    /// keys.
+    ///
+    /// # Examples
+    ///
+    /// Lowercasing header names:
+    ///
+    /// ```
+    /// use serde_hashkey::{key, Key};
+    ///
+    /// let key = key!({ "Content-Type": "text/html", "Accept": ["text/*"] }).normalize();
+    ///
+    /// let key = key.map_keys(|key| match key {
+    ///     Key::String(string) => Key::String(string.to_lowercase().into()),
+    ///     key => key,
+    /// });
+    ///
+    /// assert_eq!(key, key!({ "accept": ["text/*"], "content-type": "text/html" }));
+    /// ```
+    pub fn map_keys<T>(self, f: T) -> Key<F>
+    where
+        T: FnMut(Key<F>) -> Key<F>,
+    {
+        self.transform(f, Target::Keys)
+    }
+
+    fn transform<T>(self, mut f: T, target: Target) -> Key<F>
+    where
+        T: FnMut(Key<F>) -> Key<F>,
+    {
+        let mut stack = Vec::<Frame<F>>::new();
+        let mut next = self;
+
+        loop {
+            let mut value = match next {
+                Key::Seq(seq) => {
+                    stack.push(Frame::seq(seq.into_vec()));
+                    None
+                }
+                Key::IntegerSeq(seq) if target == Target::Values => {
+                    stack.push(Frame::seq(seq.iter().map(Key::Integer).collect()));
+                    None
+                }
+                Key::FloatSeq(seq) if target == Target::Values => {
+                    stack.push(Frame::seq(seq.iter().map(Key::Float).collect()));
+                    None
+                }
+                Key::Map(map) => {
+                    stack.push(Frame::map(map.into_vec()));
+                    None
+                }
+                leaf if target == Target::Values => Some(f(leaf)),
+                leaf => Some(leaf),
+            };
+
+            next = loop {
+                let frame = match stack.last_mut() {
+                    Some(frame) => frame,
+                    None => return value.unwrap_or(Key::Unit),
+                };
+
+                if let Some(value) = value.take() {
+                    frame.accept(value);
+                }
+
+                match frame.next(&mut f, target) {
+                    Some(next) => break next,
+                    // Every value in the innermost container has been
+                    // transformed.
+                    None => value = stack.pop().map(Frame::finish),
+                }
+            };
+        }
+    }
+}
+
+/// What is being transformed.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Target {
+    Values,
+    Keys,
+}
+
+/// A container whose values are currently being transformed.
+enum Frame<F>
+where
+    F: FloatPolicy,
+{
+    Seq {
+        rest: vec::IntoIter<Key<F>>,
+        done: Vec<Key<F>>,
+    },
+    Map {
+        rest: vec::IntoIter<(Key<F>, Key<F>)>,
+        done: Vec<(Key<F>, Key<F>)>,
+        /// The key of the entry whose value is being transformed.
+        key: Option<Key<F>>,
+        /// If any key in the map was changed.
+        changed: bool,
+    },
+}
+
+impl<F> Frame<F>
+where
+    F: FloatPolicy + Clone + Ord,
+{
+    fn seq(seq: Vec<Key<F>>) -> Self {
+        let done = Vec::with_capacity(seq.len());
+
+        Frame::Seq {
+            rest: seq.into_iter(),
+            done,
+        }
+    }
+
+    fn map(map: Vec<(Key<F>, Key<F>)>) -> Self {
+        let done = Vec::with_capacity(map.len());
+
+        Frame::Map {
+            rest: map.into_iter(),
+            done,
+            key: None,
+            changed: false,
+        }
+    }
+
+    /// Take the next value to transform out of this container, transforming
+    /// the key of map entries if that's the target.
+    fn next<T>(&mut self, f: &mut T, target: Target) -> Option<Key<F>>
+    where
+        T: FnMut(Key<F>) -> Key<F>,
+    {
+        match self {
+            Frame::Seq { rest, .. } => rest.next(),
+            Frame::Map {
+                rest, key, changed, ..
+            } => {
+                let (next_key, value) = rest.next()?;
+
+                *key = Some(if target == Target::Keys {
+                    let mapped = f(next_key.clone());
+                    *changed |= mapped != next_key;
+                    mapped
+                } else {
+                    next_key
+                });
+
+                Some(value)
+            }
+        }
+    }
+
+    /// Put the value which was last taken out of this container back.
+    fn accept(&mut self, value: Key<F>) {
+        match self {
+            Frame::Seq { done, .. } => done.push(value),
+            Frame::Map { done, key, .. } => {
+                if let Some(key) = key.take() {
+                    done.push((key, value));
+                }
+            }
+        }
+    }
+
+    /// Turn this container back into a key.
+    fn finish(self) -> Key<F> {
+        match self {
+            Frame::Seq { done, .. } => Key::seq(done),
+            Frame::Map {
+                mut done, changed, ..
+            } => {
+                if changed {
+                    done.sort_by(|a, b| a.0.cmp(&b.0));
+                }
+
+                Key::Map(done.into())
+            }
+        }
+    }
+}
@@ -0,0 +1,229 @@
+//! Order-insensitive comparison and hashing of keys.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use crate::float::{FloatPolicy, RejectFloatPolicy};
+use crate::key::{Float, Key};
+
+/// A wrapper around a [Key] which compares and hashes maps without regard to
+/// the order of their entries.
+///
+/// This makes it possible to use keys containing maps in hashed collections
+/// without having to [normalize] them first. Maps are compared as multisets of
+/// entries, so duplicate entries still matter. Sequences are still compared in
+/// order.
+///
+/// The hash of a map is computed by combining the hashes of its entries with a
+/// commutative operation. Entry hashes are computed with a fixed-key hasher
+/// regardless of the hasher used by the collection, so the collision
+/// resistance for map contents is weaker than for a normalized [Key]. In
+/// particular it does not protect against adversarially constructed maps in
+/// the way a randomly seeded hasher would.
+///
+/// [normalize]: Key::normalize
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use serde_hashkey::{Key, UnorderedKey};
+///
+/// let a: Key = Key::Map(vec![(Key::from(1u32), Key::from(true)), (Key::from(2u32), Key::from(false))].into());
+/// let b: Key = Key::Map(vec![(Key::from(2u32), Key::from(false)), (Key::from(1u32), Key::from(true))].into());
+///
+/// assert_ne!(a, b);
+///
+/// let mut map = HashMap::new();
+/// map.insert(UnorderedKey(a), 42);
+/// assert_eq!(map.get(&UnorderedKey(b)), Some(&42));
+/// ```
+#[derive(Debug, Clone)]
+pub struct UnorderedKey<F = RejectFloatPolicy>(pub Key<F>)
+where
+    F: FloatPolicy;
+
+impl<F> UnorderedKey<F>
+where
+    F: FloatPolicy,
+{
+    /// Access the wrapped key.
+    pub fn into_inner(self) -> Key<F> {
+        self.0
+    }
+}
+
+impl<F> From<Key<F>> for UnorderedKey<F>
+where
+    F: FloatPolicy,
+{
+    fn from(key: Key<F>) -> Self {
+        UnorderedKey(key)
+    }
+}
+
+impl<F> PartialEq for UnorderedKey<F>
+where
+    F: FloatPolicy,
+{
+    fn eq(&self, other: &Self) -> bool {
+        eq(&self.0, &other.0)
+    }
+}
+
+impl<F> Eq for UnorderedKey<F> where F: FloatPolicy {}
+
+impl<F> Hash for UnorderedKey<F>
+where
+    F: FloatPolicy,
+{
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        hash(&self.0, state);
+    }
+}
+
+fn eq<F>(a: &Key<F>, b: &Key<F>) -> bool
+where
+    F: FloatPolicy,
+{
+    match (a, b) {
+        (
+            Key::Seq(..) | Key::IntegerSeq(..) | Key::FloatSeq(..),
+            Key::Seq(..) | Key::IntegerSeq(..) | Key::FloatSeq(..),
+        ) => match (a.seq_elements(), b.seq_elements()) {
+            (Some(a), Some(b)) => {
+                a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| eq(a, b))
+            }
+            _ => false,
+        },
+        (Key::Map(a), Key::Map(b)) => {
+            if a.len() != b.len() {
+                return false;
+            }
+
+            let mut candidates = b
+                .iter()
+                .enumerate()
+                .map(|(index, (k, v))| (entry_hash(k, v), index))
+                .collect::<Vec<_>>();
+            candidates.sort_unstable();
+
+            let mut used = vec![false; b.len()];
+
+            'outer: for (k, v) in a.iter() {
+                let hash = entry_hash(k, v);
+                let start = candidates.partition_point(|(h, _)| *h < hash);
+
+                for &(h, index) in &candidates[start..] {
+                    if h != hash {
+                        break;
+                    }
+
+                    if used[index] {
+                        continue;
+                    }
+
+                    let (other_k, other_v) = &b[index];
+
+                    if eq(k, other_k) && eq(v, other_v) {
+                        used[index] = true;
+                        continue 'outer;
+                    }
+                }
+
+                return false;
+            }
+
+            true
+        }
+        (Key::Null, Key::Null) | (Key::Unit, Key::Unit) => true,
+        (Key::Bool(a), Key::Bool(b)) => a == b,
+        (Key::Integer(a), Key::Integer(b)) => a == b,
+        (Key::Float(Float::F32(a)), Key::Float(Float::F32(b))) => a == b,
+        (Key::Float(Float::F64(a)), Key::Float(Float::F64(b))) => a == b,
+        (Key::Float(Float::F16(a)), Key::Float(Float::F16(b))) => a == b,
+        (Key::Bytes(a), Key::Bytes(b)) => a == b,
+        (Key::Char(a), Key::Char(b)) => a == b,
+        (Key::String(a), Key::String(b)) => a == b,
+        _ => false,
+    }
+}
+
+fn hash<F, H>(key: &Key<F>, state: &mut H)
+where
+    F: FloatPolicy,
+    H: Hasher,
+{
+    match key {
+        Key::Null => state.write_u8(9),
+        Key::Unit => state.write_u8(0),
+        Key::Bool(v) => {
+            state.write_u8(1);
+            v.hash(state);
+        }
+        Key::Integer(v) => {
+            state.write_u8(2);
+            v.hash(state);
+        }
+        Key::Float(Float::F32(v)) => {
+            state.write_u8(3);
+            v.hash(state);
+        }
+        Key::Float(Float::F64(v)) => {
+            state.write_u8(4);
+            v.hash(state);
+        }
+        Key::Float(Float::F16(v)) => {
+            state.write_u8(11);
+            v.hash(state);
+        }
+        Key::Bytes(v) => {
+            state.write_u8(5);
+            v.hash(state);
+        }
+        Key::Char(v) => {
+            state.write_u8(10);
+            v.hash(state);
+        }
+        Key::String(v) => {
+            state.write_u8(6);
+            v.hash(state);
+        }
+        Key::Seq(..) | Key::IntegerSeq(..) | Key::FloatSeq(..) => {
+            state.write_u8(7);
+
+            if let Some(v) = key.seq_elements() {
+                state.write_usize(v.len());
+
+                for v in v.iter() {
+                    hash(v, state);
+                }
+            }
+        }
+        Key::Map(v) => {
+            state.write_u8(8);
+            state.write_usize(v.len());
+
+            let combined = v
+                .iter()
+                .fold(0u64, |acc, (k, v)| acc.wrapping_add(entry_hash(k, v)));
+
+            state.write_u64(combined);
+        }
+    }
+}
+
+/// Hash a single map entry independently of the outer hasher, so that the
+/// result can be combined commutatively.
+fn entry_hash<F>(k: &Key<F>, v: &Key<F>) -> u64
+where
+    F: FloatPolicy,
+{
+    let mut state = DefaultHasher::new();
+    hash(k, &mut state);
+    hash(v, &mut state);
+    state.finish()
+}
@@ -0,0 +1,136 @@
+//! Integration with the [`valuable`] crate.
+//!
+//! [`valuable`]: https://docs.rs/valuable
+
+use valuable01::{Listable, Mappable, Slice, Valuable, Value, Visit};
+
+use crate::float::{self, FloatPolicy};
+use crate::key::{Float, Integer, Key};
+
+/// A [Key] is valuable by mapping each variant to the closest corresponding
+/// [Value]:
+///
+/// * [Key::Null] and [Key::Unit] are [Value::Unit].
+/// * [Key::Bool], [Key::Integer], [Key::Float], [Key::Char] and
+///   [Key::String] are the corresponding primitive values, with integers and floats keeping their
+///   width.
+/// * [Key::Bytes] is a [Value::Listable] of [Value::U8].
+/// * [Key::Seq], [Key::IntegerSeq] and [Key::FloatSeq] are a
+///   [Value::Listable] of their elements.
+/// * [Key::Map] is a [Value::Mappable] of its entries. Since [Mappable] keys
+///   are themselves values, non-string map keys are visited as their
+///   corresponding value just like any other key.
+///
+/// A float whose representation can't be visited as a floating point value is
+/// visited as [Value::Unit].
+///
+/// # Examples
+///
+/// ```
+/// use serde_hashkey::{to_key, Key};
+/// use valuable01::{Valuable, Value};
+///
+/// let key: Key = to_key(&["hello", "world"])?;
+/// assert!(matches!(key.as_value(), Value::Listable(..)));
+/// # Ok::<_, serde_hashkey::Error>(())
+/// ```
+impl<F> Valuable for Key<F>
+where
+    F: FloatPolicy,
+{
+    fn as_value(&self) -> Value<'_> {
+        match self {
+            Key::Null | Key::Unit => Value::Unit,
+            Key::Bool(b) => Value::Bool(*b),
+            Key::Integer(integer) => integer_value(integer),
+            Key::Float(f) => float_value(f),
+            Key::Bytes(..) => Value::Listable(self),
+            Key::Char(c) => Value::Char(*c),
+            Key::String(string) => Value::String(string),
+            Key::Seq(seq) => Value::Listable(seq),
+            Key::IntegerSeq(..) | Key::FloatSeq(..) => Value::Listable(self),
+            Key::Map(..) => Value::Mappable(self),
+        }
+    }
+
+    fn visit(&self, visit: &mut dyn Visit) {
+        match self {
+            Key::Bytes(bytes) => visit.visit_primitive_slice(Slice::U8(bytes)),
+            Key::Seq(seq) => seq.visit(visit),
+            Key::IntegerSeq(seq) => {
+                for integer in seq.iter() {
+                    visit.visit_value(integer_value(&integer));
+                }
+            }
+            Key::FloatSeq(seq) => {
+                for f in seq.iter() {
+                    visit.visit_value(float_value(&f));
+                }
+            }
+            Key::Map(map) => {
+                for (key, value) in map.iter() {
+                    visit.visit_entry(key.as_value(), value.as_value());
+                }
+            }
+            _ => visit.visit_value(self.as_value()),
+        }
+    }
+}
+
+/// Visiting a [Key] as a [Mappable] is only meaningful for [Key::Map], any
+/// other key reports itself as being empty.
+impl<F> Mappable for Key<F>
+where
+    F: FloatPolicy,
+{
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            Key::Map(map) => (map.len(), Some(map.len())),
+            _ => (0, Some(0)),
+        }
+    }
+}
+
+/// Visiting a [Key] as a [Listable] is only meaningful for sequences and
+/// [Key::Bytes], any other key reports itself as being empty.
+impl<F> Listable for Key<F>
+where
+    F: FloatPolicy,
+{
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            Key::Bytes(bytes) => (bytes.len(), Some(bytes.len())),
+            Key::Seq(seq) => (seq.len(), Some(seq.len())),
+            Key::IntegerSeq(seq) => (seq.len(), Some(seq.len())),
+            Key::FloatSeq(seq) => (seq.len(), Some(seq.len())),
+            _ => (0, Some(0)),
+        }
+    }
+}
+
+fn integer_value(integer: &Integer) -> Value<'static> {
+    match *integer {
+        Integer::I8(v) => Value::I8(v),
+        Integer::I16(v) => Value::I16(v),
+        Integer::I32(v) => Value::I32(v),
+        Integer::I64(v) => Value::I64(v),
+        Integer::I128(v) => Value::I128(v),
+        Integer::U8(v) => Value::U8(v),
+        Integer::U16(v) => Value::U16(v),
+        Integer::U32(v) => Value::U32(v),
+        Integer::U64(v) => Value::U64(v),
+        Integer::U128(v) => Value::U128(v),
+    }
+}
+
+fn float_value<F>(f: &Float<F>) -> Value<'static>
+where
+    F: FloatPolicy,
+{
+    match (f, float::to_f64(f)) {
+        // Widening an f32 to an f64 is lossless, so this round trips exactly.
+        (Float::F32(..) | Float::F16(..), Some(v)) => Value::F32(v as f32),
+        (Float::F64(..), Some(v)) => Value::F64(v),
+        (_, None) => Value::Unit,
+    }
+}
@@ -0,0 +1,259 @@
+//! In-place transformation of every value in a key.
+
+use std::mem;
+use std::vec;
+
+use crate::float::FloatPolicy;
+use crate::key::Key;
+
+/// What [Key::walk_mut] should do after visiting a value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Walk {
+    /// Continue by visiting the values contained in this value, if any.
+    Continue,
+    /// Continue without visiting the values contained in this value.
+    SkipChildren,
+    /// Remove this value from its container.
+    ///
+    /// Removing an element of a sequence drops the element, and removing
+    /// either the key or the value of a map entry drops the whole entry.
+    /// Removing the key being walked replaces it with [Key::Unit].
+    Remove,
+}
+
+impl<F> Key<F>
+where
+    F: FloatPolicy,
+{
+    /// Visit every value in this key in depth-first order, allowing each one
+    /// to be modified in place.
+    ///
+    /// Values are visited in the same order as with [Key::iter], and the
+    /// closure decides what happens next by returning a [Walk]. The values
+    /// contained in a value are visited after the closure has returned, so
+    /// they are the ones it left behind. Compact sequences, [Key::IntegerSeq]
+    /// and [Key::FloatSeq], are visited as a single value.
+    ///
+    /// Sequences whose elements have been modified or removed are stored
+    /// compactly again if possible, so that they stay equal to the key of the
+    /// equivalent serialized sequence. Maps are not sorted again, so a key
+    /// which modifies the keys of a sorted map might have to be
+    /// [normalized][Key::normalize] afterwards.
+    ///
+    /// Traversal does not use recursion, so it is safe to use on deeply nested
+    /// keys.
+    ///
+    /// # Examples
+    ///
+    /// Stripping a field from every map, no matter how deeply nested it is:
+    ///
+    /// ```
+    /// use std::mem;
+    ///
+    /// use serde_derive::Serialize;
+    /// use serde_hashkey::{to_key, Key, Walk};
+    ///
+    /// #[derive(Serialize)]
+    /// struct Event {
+    ///     name: &'static str,
+    ///     timestamp: u64,
+    ///     children: Vec<Event>,
+    /// }
+    ///
+    /// #[derive(Serialize)]
+    /// struct Stripped {
+    ///     name: &'static str,
+    ///     children: Vec<Stripped>,
+    /// }
+    ///
+    /// let mut key = to_key(&Event {
+    ///     name: "parent",
+    ///     timestamp: 1700000000,
+    ///     children: vec![Event { name: "child", timestamp: 1700000001, children: vec![] }],
+    /// })?;
+    ///
+    /// let timestamp = Key::String("timestamp".into());
+    ///
+    /// key.walk_mut(|key| {
+    ///     if let Key::Map(map) = key {
+    ///         let mut entries = mem::take(map).into_vec();
+    ///         entries.retain(|(key, _)| *key != timestamp);
+    ///         *map = entries.into();
+    ///     }
+    ///
+    ///     Walk::Continue
+    /// });
+    ///
+    /// let expected = to_key(&Stripped {
+    ///     name: "parent",
+    ///     children: vec![Stripped { name: "child", children: vec![] }],
+    /// })?;
+    ///
+    /// assert_eq!(key, expected);
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    ///
+    /// Removing values:
+    ///
+    /// ```
+    /// use serde_hashkey::{to_key, Key, Walk};
+    ///
+    /// let mut key = to_key(&vec![Some(1u32), None, Some(2)])?;
+    ///
+    /// key.walk_mut(|key| match key {
+    ///     Key::Null => Walk::Remove,
+    ///     _ => Walk::Continue,
+    /// });
+    ///
+    /// assert_eq!(key, to_key(&vec![1u32, 2])?);
+    /// # Ok::<_, serde_hashkey::Error>(())
+    /// ```
+    pub fn walk_mut<W>(&mut self, mut walk: W)
+    where
+        W: FnMut(&mut Key<F>) -> Walk,
+    {
+        let mut stack = Vec::<Frame<F>>::new();
+        let mut step = Step::Visit(mem::replace(self, Key::Unit));
+
+        loop {
+            step = match step {
+                Step::Visit(mut key) => match walk(&mut key) {
+                    Walk::Remove => Step::Return(None),
+                    Walk::SkipChildren => Step::Return(Some(key)),
+                    Walk::Continue => match key {
+                        Key::Seq(seq) => {
+                            stack.push(Frame::seq(seq));
+                            Step::Next
+                        }
+                        Key::Map(map) => {
+                            stack.push(Frame::map(map));
+                            Step::Next
+                        }
+                        key => Step::Return(Some(key)),
+                    },
+                },
+                Step::Return(key) => match stack.last_mut() {
+                    Some(frame) => {
+                        frame.accept(key);
+                        Step::Next
+                    }
+                    None => {
+                        *self = key.unwrap_or(Key::Unit);
+                        return;
+                    }
+                },
+                Step::Next => match stack.last_mut().and_then(Frame::next) {
+                    Some(key) => Step::Visit(key),
+                    // Every value in the innermost container has been visited.
+                    None => Step::Return(stack.pop().map(Frame::finish)),
+                },
+            };
+        }
+    }
+}
+
+enum Step<F>
+where
+    F: FloatPolicy,
+{
+    /// Visit the given value.
+    Visit(Key<F>),
+    /// Return a visited value to the container it was taken from, or `None`
+    /// if it was removed.
+    Return(Option<Key<F>>),
+    /// Move on to the next value in the innermost container.
+    Next,
+}
+
+/// A container whose values are currently being visited.
+enum Frame<F>
+where
+    F: FloatPolicy,
+{
+    Seq {
+        rest: vec::IntoIter<Key<F>>,
+        done: Vec<Key<F>>,
+    },
+    Map {
+        rest: vec::IntoIter<(Key<F>, Key<F>)>,
+        done: Vec<(Key<F>, Key<F>)>,
+        /// The key of the current entry once it has been visited.
+        key: Option<Key<F>>,
+        /// The value of the current entry while its key is being visited.
+        value: Option<Key<F>>,
+    },
+}
+
+impl<F> Frame<F>
+where
+    F: FloatPolicy,
+{
+    fn seq(seq: Box<[Key<F>]>) -> Self {
+        let done = Vec::with_capacity(seq.len());
+
+        Frame::Seq {
+            rest: seq.into_vec().into_iter(),
+            done,
+        }
+    }
+
+    fn map(map: Box<[(Key<F>, Key<F>)]>) -> Self {
+        let done = Vec::with_capacity(map.len());
+
+        Frame::Map {
+            rest: map.into_vec().into_iter(),
+            done,
+            key: None,
+            value: None,
+        }
+    }
+
+    /// Take the next value to visit out of this container.
+    fn next(&mut self) -> Option<Key<F>> {
+        match self {
+            Frame::Seq { rest, .. } => rest.next(),
+            Frame::Map { rest, value, .. } => {
+                if let Some(value) = value.take() {
+                    return Some(value);
+                }
+
+                let (key, next) = rest.next()?;
+                *value = Some(next);
+                Some(key)
+            }
+        }
+    }
+
+    /// Put the value which was last taken out of this container back.
+    fn accept(&mut self, element: Option<Key<F>>) {
+        match self {
+            Frame::Seq { done, .. } => {
+                if let Some(element) = element {
+                    done.push(element);
+                }
+            }
+            Frame::Map {
+                done, key, value, ..
+            } => {
+                if value.is_some() {
+                    // The key of the entry was visited, and if it was removed
+                    // the whole entry is dropped.
+                    match element {
+                        Some(element) => *key = Some(element),
+                        None => *value = None,
+                    }
+                } else if let (Some(key), Some(element)) = (key.take(), element) {
+                    done.push((key, element));
+                }
+            }
+        }
+    }
+
+    /// Turn this container back into a key.
+    fn finish(self) -> Key<F> {
+        match self {
+            Frame::Seq { done, .. } => Key::seq(done),
+            Frame::Map { done, .. } => Key::Map(done.into()),
+        }
+    }
+}
@@ -0,0 +1,200 @@
+use serde_derive::{Deserialize, Serialize};
+use serde_hashkey::{to_key, Error, Key};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum Format {
+    Paper,
+    Digital { size: u64 },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Author {
+    name: String,
+    age: Option<u32>,
+}
+
+#[derive(Serialize)]
+struct Book {
+    title: &'static str,
+    author: Author,
+    format: Format,
+    subtitle: Option<&'static str>,
+}
+
+fn book() -> Book {
+    Book {
+        title: "Birds of a feather",
+        author: Author {
+            name: String::from("Noah"),
+            age: None,
+        },
+        format: Format::Digital { size: 1024 },
+        subtitle: None,
+    }
+}
+
+#[test]
+fn test_get_as() -> Result<(), Error> {
+    let key = to_key(&book())?;
+
+    assert_eq!(
+        key.get_as::<Author, _>("author")?,
+        Some(Author {
+            name: String::from("Noah"),
+            age: None
+        })
+    );
+    assert_eq!(
+        key.get_as::<Format, _>("format")?,
+        Some(Format::Digital { size: 1024 })
+    );
+    assert_eq!(key.get_as::<Option<String>, _>("subtitle")?, Some(None));
+    assert_eq!(key.get_as::<String, _>("missing")?, None);
+
+    // Non-string keys.
+    let key = to_key(
+        &vec![(1u32, "one"), (2u32, "two")]
+            .into_iter()
+            .collect::<std::collections::BTreeMap<_, _>>(),
+    )?;
+    assert_eq!(key.get_as::<String, _>(2u32)?.as_deref(), Some("two"));
+    assert_eq!(key.get_as::<String, _>(2u64)?, None);
+    Ok(())
+}
+
+#[test]
+fn test_insert_value() -> Result<(), Error> {
+    let mut key = to_key(&book())?;
+
+    let previous = key.insert_value("format", &Format::Paper)?;
+    assert_eq!(previous, Some(to_key(&Format::Digital { size: 1024 })?));
+    assert_eq!(key.get_as::<Format, _>("format")?, Some(Format::Paper));
+
+    assert_eq!(
+        key.insert_value("subtitle", &Some("A novel"))?,
+        Some(Key::Null)
+    );
+    assert_eq!(
+        key.get_as::<Option<String>, _>("subtitle")?,
+        Some(Some(String::from("A novel")))
+    );
+
+    assert_eq!(key.insert_value("pages", &320u32)?, None);
+    assert_eq!(key.get_as::<u32, _>("pages")?, Some(320));
+
+    // Nested values can be updated by reading, modifying and writing them.
+    let mut author = key.get_as::<Author, _>("author")?.unwrap();
+    author.age = Some(42);
+    key.insert_value("author", &author)?;
+    assert_eq!(key.get_as::<Author, _>("author")?, Some(author));
+    Ok(())
+}
+
+#[test]
+fn test_insert_sorted() -> Result<(), Error> {
+    let mut key = to_key(&book())?.normalize();
+    key.insert_value("pages", &320u32)?;
+    assert_eq!(key.clone().normalize(), key);
+
+    // Unsorted maps get new entries at the end.
+    let mut key = to_key(&book())?;
+    key.insert_value("pages", &320u32)?;
+
+    match &key {
+        Key::Map(map) => assert_eq!(map.last().map(|e| &e.0), Some(&to_key(&"pages")?)),
+        _ => panic!("expected map"),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_errors() -> Result<(), Error> {
+    let mut key = to_key(&"not a map")?;
+    assert!(matches!(
+        key.get_as::<u32, _>("a"),
+        Err(Error::Unexpected("map"))
+    ));
+    assert!(matches!(
+        key.insert_value("a", &1u32),
+        Err(Error::Unexpected("map"))
+    ));
+
+    let mut key = to_key(&book())?;
+    let before = key.clone();
+    assert!(key.get_as::<u32, _>("title").is_err());
+    assert!(matches!(
+        key.insert_value("rating", &1.5f32),
+        Err(Error::UnsupportedFloat { value, .. }) if value == 1.5
+    ));
+    assert_eq!(key, before);
+    Ok(())
+}
+
+#[test]
+fn test_get() -> Result<(), Error> {
+    let key = to_key(&book())?;
+
+    assert_eq!(
+        key.get(&to_key("title")?),
+        Some(&to_key("Birds of a feather")?)
+    );
+    assert_eq!(key.get(&to_key("subtitle")?), Some(&Key::Null));
+    assert_eq!(key.get(&to_key("missing")?), None);
+
+    let author = key.get(&to_key("author")?).unwrap();
+    assert_eq!(author.get(&to_key("name")?), Some(&to_key("Noah")?));
+
+    // Lookups in normalized keys behave the same.
+    let normalized = key.clone().normalize();
+    assert_eq!(
+        normalized.get(&to_key("title")?),
+        key.get(&to_key("title")?)
+    );
+
+    // Non-maps.
+    assert_eq!(to_key("title")?.get(&to_key("title")?), None);
+    assert_eq!(to_key(&vec!["title"])?.get(&to_key("title")?), None);
+    assert_eq!(to_key(&())?.get(&Key::Unit), None);
+    Ok(())
+}
+
+#[test]
+fn test_get_index() -> Result<(), Error> {
+    let key = to_key(&book())?;
+    assert_eq!(key.get_index(0), Some(&to_key("Birds of a feather")?));
+    assert_eq!(key.get_index(3), Some(&Key::Null));
+    assert_eq!(key.get_index(4), None);
+
+    let key = to_key(&("a", true))?;
+    assert_eq!(key.get_index(1), Some(&to_key(&true)?));
+    assert_eq!(key.get_index(2), None);
+
+    // Compact sequences and scalars.
+    assert_eq!(to_key(&vec![1u32, 2])?.get_index(0), None);
+    assert_eq!(to_key("a")?.get_index(0), None);
+    Ok(())
+}
+
+#[test]
+fn test_get_mut() -> Result<(), Error> {
+    let mut key = to_key(&book())?;
+
+    *key.get_mut(&to_key("format")?).unwrap() = to_key(&Format::Paper)?;
+    assert_eq!(key.get_as::<Format, _>("format")?, Some(Format::Paper));
+    assert_eq!(key.get_mut(&to_key("missing")?), None);
+
+    *key.get_index_mut(0).unwrap() = to_key("Flocking")?;
+    assert_eq!(
+        key.get_as::<String, _>("title")?.as_deref(),
+        Some("Flocking")
+    );
+    assert_eq!(key.get_index_mut(4), None);
+
+    // Replacing elements can require normalizing the key again.
+    let mut key = to_key(&("a", 1u32))?;
+    *key.get_index_mut(0).unwrap() = to_key(&2u32)?;
+    assert_ne!(key, to_key(&vec![2u32, 1])?);
+    assert_eq!(key.normalize(), to_key(&vec![2u32, 1])?);
+    Ok(())
+}
@@ -0,0 +1,137 @@
+use serde_derive::Serialize;
+use serde_hashkey::{to_key, Error, FloatPolicy, Integer, Key};
+
+#[derive(Serialize)]
+struct Author {
+    name: &'static str,
+}
+
+fn check<F>(
+    key: &Key<F>,
+    unit: bool,
+    boolean: Option<bool>,
+    string: Option<&str>,
+    bytes: Option<&[u8]>,
+) where
+    F: FloatPolicy,
+{
+    assert_eq!(key.is_unit(), unit);
+    assert_eq!(key.as_bool(), boolean);
+    assert_eq!(key.is_bool(), boolean.is_some());
+    assert_eq!(key.as_str(), string);
+    assert_eq!(key.is_string(), string.is_some());
+    assert_eq!(key.as_bytes(), bytes);
+    assert_eq!(key.is_bytes(), bytes.is_some());
+}
+
+#[test]
+fn test_variants() -> Result<(), Error> {
+    check(&to_key(&())?, true, None, None, None);
+    check(&to_key(&true)?, false, Some(true), None, None);
+    check(&to_key(&false)?, false, Some(false), None, None);
+    check(&to_key(&42u32)?, false, None, None, None);
+    check(&to_key("hello")?, false, None, Some("hello"), None);
+
+    let key: Key = Key::from(b"hello".to_vec());
+    check(&key, false, None, None, Some(&b"hello"[..]));
+
+    // A sequence of bytes is not bytes.
+    check(&to_key(&b"hello".to_vec())?, false, None, None, None);
+    Ok(())
+}
+
+#[test]
+fn test_containers() -> Result<(), Error> {
+    check(&to_key(&vec!["a"])?, false, None, None, None);
+    check(&to_key(&vec![true])?, false, None, None, None);
+    check(&to_key(&Vec::<()>::new())?, false, None, None, None);
+    check(&to_key(&Author { name: "Noah" })?, false, None, None, None);
+    Ok(())
+}
+
+#[cfg(feature = "ordered-float")]
+#[test]
+fn test_ordered_float() -> Result<(), Error> {
+    use serde_hashkey::to_key_with_ordered_float;
+
+    check(
+        &to_key_with_ordered_float(&1.5f32)?,
+        false,
+        None,
+        None,
+        None,
+    );
+    check(
+        &to_key_with_ordered_float("a")?,
+        false,
+        None,
+        Some("a"),
+        None,
+    );
+    check(
+        &to_key_with_ordered_float(&true)?,
+        false,
+        Some(true),
+        None,
+        None,
+    );
+    Ok(())
+}
+
+#[test]
+fn test_integers() -> Result<(), Error> {
+    let key = to_key(&42u16)?;
+    assert_eq!(key.as_integer(), Some(&Integer::U16(42)));
+    assert_eq!(key.as_i64(), Some(42));
+    assert_eq!(key.as_u64(), Some(42));
+
+    let key = to_key(&-1i8)?;
+    assert_eq!(key.as_i64(), Some(-1));
+    assert_eq!(key.as_u64(), None);
+
+    assert_eq!(to_key(&i64::MIN)?.as_i64(), Some(i64::MIN));
+    assert_eq!(to_key(&(i128::from(i64::MIN) - 1))?.as_i64(), None);
+    assert_eq!(to_key(&u64::MAX)?.as_u64(), Some(u64::MAX));
+    assert_eq!(to_key(&(u128::from(u64::MAX) + 1))?.as_u64(), None);
+
+    for key in [to_key(&())?, to_key("1")?, to_key(&vec![1u32])?] {
+        assert_eq!(key.as_integer(), None);
+        assert_eq!(key.as_i64(), None);
+        assert_eq!(key.as_u64(), None);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_widening() {
+    let cases = [
+        (Integer::I8(i8::MIN), Some(i128::from(i8::MIN)), None),
+        (Integer::I16(-1), Some(-1), None),
+        (Integer::I32(7), Some(7), Some(7)),
+        (
+            Integer::I64(i64::MAX),
+            Some(i128::from(i64::MAX)),
+            Some(u128::from(i64::MAX as u64)),
+        ),
+        (Integer::I128(i128::MIN), Some(i128::MIN), None),
+        (Integer::U8(u8::MAX), Some(255), Some(255)),
+        (Integer::U16(1), Some(1), Some(1)),
+        (
+            Integer::U32(u32::MAX),
+            Some(i128::from(u32::MAX)),
+            Some(u128::from(u32::MAX)),
+        ),
+        (
+            Integer::U64(u64::MAX),
+            Some(i128::from(u64::MAX)),
+            Some(u128::from(u64::MAX)),
+        ),
+        (Integer::U128(u128::MAX), None, Some(u128::MAX)),
+    ];
+
+    for (integer, signed, unsigned) in cases {
+        assert_eq!(integer.to_i128(), signed, "{:?}", integer);
+        assert_eq!(integer.to_u128(), unsigned, "{:?}", integer);
+    }
+}
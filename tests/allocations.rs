@@ -0,0 +1,183 @@
+//! Guards against extra allocations when serializing maps and enum variants,
+//! and when deserializing from an owned key.
+//!
+//! This uses a counting global allocator, so everything that is measured is
+//! done in a single test to avoid interference from other threads.
+//!
+//! Field and variant names are interned the first time they are serialized on
+//! a thread, so values are serialized once before measuring.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use serde_derive::Serialize;
+use serde_hashkey::{from_key_owned, key, to_key, Key};
+
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+#[derive(Clone, Serialize)]
+enum Shape {
+    Empty,
+    Circle(u32),
+    Rect(u32, u32),
+    Point { x: u32, y: u32 },
+}
+
+/// Count the allocations needed to serialize `n` copies of the given value,
+/// and return the number of allocations per value.
+fn per_value<T>(value: &T) -> usize
+where
+    T: Clone + serde::Serialize,
+{
+    let count = |n: usize| {
+        let values = vec![value.clone(); n];
+        let before = ALLOCATIONS.load(Ordering::SeqCst);
+        let key: Key = to_key(&values).unwrap();
+        let after = ALLOCATIONS.load(Ordering::SeqCst);
+        drop(key);
+        after - before
+    };
+
+    count(1);
+    let small = count(100);
+    let large = count(200);
+    (large - small) / 100
+}
+
+/// Count the allocations needed to serialize the given value.
+fn allocations<T>(value: &T) -> usize
+where
+    T: serde::Serialize,
+{
+    drop(to_key(value).unwrap());
+    let before = ALLOCATIONS.load(Ordering::SeqCst);
+    let key: Key = to_key(value).unwrap();
+    let after = ALLOCATIONS.load(Ordering::SeqCst);
+    drop(key);
+    after - before
+}
+
+#[derive(Clone, Serialize)]
+struct Point {
+    x: u32,
+    y: u32,
+    z: u32,
+}
+
+#[derive(Clone, Serialize)]
+struct Record {
+    a: u32,
+    b: u32,
+    c: u32,
+    d: u32,
+    e: u32,
+    f: u32,
+    g: u32,
+    h: u32,
+    i: u32,
+    j: u32,
+}
+
+#[test]
+fn test_allocations() {
+    // Maps and structs are preallocated using their length, so the only
+    // allocation of a map with integer keys is its entries.
+    let map = (0..1000u32).map(|n| (n, n)).collect::<BTreeMap<_, _>>();
+    assert_eq!(allocations(&map), 1);
+    // Field names are interned, so only the map is allocated.
+    assert_eq!(allocations(&Point { x: 1, y: 2, z: 3 }), 1);
+
+    let record = Record {
+        a: 1,
+        b: 2,
+        c: 3,
+        d: 4,
+        e: 5,
+        f: 6,
+        g: 7,
+        h: 8,
+        i: 9,
+        j: 10,
+    };
+
+    // The sequence, then one map per record no matter how many fields it has.
+    assert_eq!(per_value(&record), 1);
+    assert_eq!(allocations(&vec![record; 10000]), 10001);
+
+    // Variant names are interned as well.
+    assert_eq!(per_value(&Shape::Empty), 0);
+    // The map holding the variant.
+    assert_eq!(per_value(&Shape::Circle(1)), 1);
+    // The fields, their compact form and the map.
+    assert_eq!(per_value(&Shape::Rect(1, 2)), 3);
+    // The fields and the map.
+    assert_eq!(per_value(&Shape::Point { x: 1, y: 2 }), 2);
+
+    let values = [
+        Shape::Empty,
+        Shape::Circle(1),
+        Shape::Rect(1, 2),
+        Shape::Point { x: 1, y: 2 },
+    ];
+
+    let expected = Key::Seq(
+        [
+            key!("Empty"),
+            key!({ "Circle": 1u32 }),
+            key!({ "Rect": [1u32, 2u32] }),
+            key!({ "Point": { "x": 1u32, "y": 2u32 } }),
+        ]
+        .iter()
+        .cycle()
+        .take(4000)
+        .cloned()
+        .collect(),
+    );
+
+    let key: Key = to_key(&values.iter().cycle().take(4000).collect::<Vec<_>>()).unwrap();
+    assert_eq!(key, expected);
+
+    // Deserializing strings from an owned key allocates each string once, on
+    // top of the vector holding them.
+    let strings = (0..100).map(|n| n.to_string()).collect::<Vec<_>>();
+    let key: Key = to_key(&strings).unwrap();
+    let before = ALLOCATIONS.load(Ordering::SeqCst);
+    let output: Vec<String> = from_key_owned(key).unwrap();
+    let after = ALLOCATIONS.load(Ordering::SeqCst);
+    assert_eq!(after - before, 101);
+    assert_eq!(output, strings);
+
+    // Bytes are moved out of the key, so only the vector holding them is
+    // allocated.
+    let buffers = (0..100u8)
+        .map(|n| Key::from(vec![n; 16]))
+        .collect::<Vec<_>>();
+    let key = Key::Seq(buffers.clone().into());
+    let before = ALLOCATIONS.load(Ordering::SeqCst);
+    let output: Vec<Key> = from_key_owned(key).unwrap();
+    let after = ALLOCATIONS.load(Ordering::SeqCst);
+    assert_eq!(after - before, 1);
+    assert_eq!(output, buffers);
+}
@@ -0,0 +1,147 @@
+use serde_hashkey::{from_key, key, Integer, Key};
+
+#[test]
+fn test_constants() {
+    assert_eq!(Integer::ZERO, Integer::U8(0));
+    assert_eq!(Integer::ONE, Integer::U8(1));
+    assert_eq!(Integer::ZERO.checked_add(&Integer::ONE), Some(Integer::ONE));
+    assert_eq!(
+        Integer::ONE.checked_mul(&Integer::ZERO),
+        Some(Integer::ZERO)
+    );
+}
+
+#[test]
+fn test_narrowest_result() {
+    assert_eq!(
+        Integer::U64(254).checked_add(&Integer::U64(1)),
+        Some(Integer::U8(255))
+    );
+    assert_eq!(
+        Integer::U8(255).checked_add(&Integer::U8(1)),
+        Some(Integer::U16(256))
+    );
+    assert_eq!(
+        Integer::I128(-100).checked_mul(&Integer::I8(2)),
+        Some(Integer::I16(-200))
+    );
+    assert_eq!(
+        Integer::U32(u32::MAX).checked_mul(&Integer::U32(u32::MAX)),
+        Some(Integer::U64(u64::from(u32::MAX) * u64::from(u32::MAX)))
+    );
+}
+
+#[test]
+fn test_signed_unsigned_mixing() {
+    assert_eq!(
+        Integer::I8(-5).checked_add(&Integer::U8(3)),
+        Some(Integer::I8(-2))
+    );
+    assert_eq!(
+        Integer::U8(3).checked_add(&Integer::I8(-5)),
+        Some(Integer::I8(-2))
+    );
+    assert_eq!(
+        Integer::U8(5).checked_add(&Integer::I8(-5)),
+        Some(Integer::ZERO)
+    );
+    assert_eq!(
+        Integer::U8(3).checked_sub(&Integer::I8(-5)),
+        Some(Integer::U8(8))
+    );
+    assert_eq!(
+        Integer::I8(-3).checked_sub(&Integer::U8(5)),
+        Some(Integer::I8(-8))
+    );
+    assert_eq!(
+        Integer::I8(-3).checked_mul(&Integer::I8(-5)),
+        Some(Integer::U8(15))
+    );
+    assert_eq!(
+        Integer::I8(-3).checked_mul(&Integer::U8(0)),
+        Some(Integer::ZERO)
+    );
+
+    // The result doesn't depend on the widths of the operands.
+    assert_eq!(
+        Integer::I64(-1).checked_add(&Integer::U128(2)),
+        Integer::I8(-1).checked_add(&Integer::U8(2)),
+    );
+}
+
+#[test]
+fn test_boundaries() {
+    let u128_max = Integer::U128(u128::MAX);
+    let i128_min = Integer::I128(i128::MIN);
+    let i128_max = Integer::I128(i128::MAX);
+
+    assert_eq!(u128_max.checked_add(&Integer::ONE), None);
+    assert_eq!(
+        u128_max.checked_add(&Integer::I8(-1)),
+        Some(Integer::U128(u128::MAX - 1))
+    );
+    assert_eq!(u128_max.checked_sub(&Integer::I8(-1)), None);
+    assert_eq!(u128_max.checked_mul(&Integer::U8(2)), None);
+    assert_eq!(u128_max.checked_mul(&Integer::ONE), Some(u128_max.clone()));
+    assert_eq!(u128_max.checked_mul(&Integer::I8(-1)), None);
+    assert_eq!(
+        u128_max.checked_add(&i128_min),
+        Some(Integer::U128(i128::MAX as u128))
+    );
+
+    assert_eq!(i128_min.checked_sub(&Integer::ONE), None);
+    assert_eq!(i128_min.checked_add(&Integer::I8(-1)), None);
+    assert_eq!(
+        i128_min.checked_mul(&Integer::I8(-1)),
+        Some(Integer::U128(1 << 127))
+    );
+    assert_eq!(i128_min.checked_sub(&i128_min), Some(Integer::ZERO));
+    assert_eq!(
+        i128_min.checked_add(&u128_max),
+        Some(Integer::U128(i128::MAX as u128))
+    );
+
+    // Results which only fit in an u128 are fine.
+    assert_eq!(
+        i128_max.checked_add(&Integer::ONE),
+        Some(Integer::U128(1 << 127))
+    );
+    assert_eq!(
+        i128_max.checked_add(&i128_max),
+        Some(Integer::U128(u128::MAX - 1))
+    );
+
+    // Results which are exactly `i128::MIN` are fine.
+    assert_eq!(
+        Integer::U128(1 << 127).checked_mul(&Integer::I8(-1)),
+        Some(i128_min.clone())
+    );
+    assert_eq!(
+        Integer::ZERO.checked_sub(&Integer::U128(1 << 127)),
+        Some(i128_min.clone())
+    );
+    assert_eq!(
+        Integer::ZERO.checked_sub(&Integer::U128((1 << 127) + 1)),
+        None
+    );
+    assert_eq!(Integer::I8(-1).checked_sub(&Integer::U128(1 << 127)), None);
+}
+
+#[test]
+fn test_counter_in_map() -> Result<(), serde_hashkey::Error> {
+    let mut key = key!({ "hits": 41u64 });
+
+    if let Some(Key::Integer(hits)) = key.get_mut(&key!("hits")) {
+        *hits = hits.checked_add(&Integer::ONE).unwrap();
+    }
+
+    assert_eq!(key["hits"], Key::Integer(Integer::U8(42)));
+
+    #[derive(Debug, PartialEq, serde_derive::Deserialize)]
+    struct Counter {
+        hits: u64,
+    }
+
+    assert_eq!(from_key::<Counter, _>(&key)?, Counter { hits: 42 });
+    Ok(())
+}
@@ -0,0 +1,201 @@
+use serde_derive::Serialize;
+use serde_hashkey::{key, to_key, Error, Integer, Key};
+
+#[derive(Serialize)]
+struct Entry {
+    name: &'static str,
+    tags: Vec<&'static str>,
+    flag: bool,
+    id: u64,
+    unit: (),
+}
+
+#[test]
+fn test_golden() {
+    let key = to_key(&Entry {
+        name: "a",
+        tags: vec!["x"],
+        flag: true,
+        id: 258,
+        unit: (),
+    })
+    .unwrap();
+
+    let expected: &[u8] = &[
+        0x11, 0x05, // map, 5 entries
+        0x0f, 0x04, b'f', b'l', b'a', b'g', 0x01, 0x01, // flag
+        0x0f, 0x02, b'i', b'd', 0x0a, 0, 0, 0, 0, 0, 0, 1, 2, // id
+        0x0f, 0x04, b'n', b'a', b'm', b'e', 0x0f, 0x01, b'a', // name
+        0x0f, 0x04, b't', b'a', b'g', b's', 0x10, 0x01, 0x0f, 0x01, b'x', // tags
+        0x0f, 0x04, b'u', b'n', b'i', b't', 0x00, // unit
+    ];
+
+    assert_eq!(key.to_bytes(), expected);
+    assert_eq!(Key::from_bytes(expected), Ok(key.normalize()));
+}
+
+#[test]
+fn test_integers() {
+    let values: Vec<(Key, &[u8])> = vec![
+        (Key::Integer(Integer::I8(-1)), &[0x02, 0xff]),
+        (Key::Integer(Integer::I16(-2)), &[0x03, 0xff, 0xfe]),
+        (Key::Integer(Integer::I32(1)), &[0x04, 0, 0, 0, 1]),
+        (Key::Integer(Integer::U16(0x1234)), &[0x08, 0x12, 0x34]),
+        (
+            Key::Integer(Integer::U128(1)),
+            &[0x0b, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1],
+        ),
+    ];
+
+    for (key, expected) in values {
+        assert_eq!(key.to_bytes(), expected);
+        assert_eq!(Key::from_bytes(expected), Ok(key));
+    }
+}
+
+#[test]
+fn test_long_length() {
+    let key: Key = Key::Bytes(vec![7; 300].into());
+    let bytes = key.to_bytes();
+    assert_eq!(&bytes[..3], &[0x0e, 0xac, 0x02]);
+    assert_eq!(Key::from_bytes(&bytes), Ok(key));
+}
+
+#[test]
+fn test_order_independent() {
+    let a = key!({ "b": true, "a": () });
+
+    let b = a.clone().normalize();
+    assert_ne!(a, b);
+    assert_eq!(a.to_bytes(), b.to_bytes());
+}
+
+#[test]
+fn test_normalized_order() {
+    // Compact sequences sort after other sequences once normalized, which
+    // reverses the order of these keys.
+    let seqs: Key = Key::Map(
+        vec![
+            (Key::Seq(vec![key!(0u8)].into()), key!(1u32)),
+            (Key::Seq(vec![key!(1u8), key!("x")].into()), key!(2u32)),
+        ]
+        .into(),
+    );
+
+    let maps: Key = key!({
+        "b": { "d": 1u32, "c": [{ "f": (), "e": () }] },
+        "a": { "y": 1u32, "x": 2u32 },
+    });
+
+    let nested: Key = key!({ (maps.clone()): (seqs.clone()), "a": () });
+
+    for key in [seqs, maps, nested] {
+        let normalized = key.clone().normalize();
+        assert_eq!(key.to_bytes(), normalized.to_bytes());
+        assert_eq!(Key::from_bytes(&key.to_bytes()), Ok(normalized));
+    }
+}
+
+#[test]
+fn test_non_canonical() {
+    assert_eq!(
+        Key::<serde_hashkey::RejectFloatPolicy>::from_bytes(&[0x10, 0x80, 0x00]),
+        Err(Error::InvalidBytes("non-canonical length"))
+    );
+    assert_eq!(
+        Key::<serde_hashkey::RejectFloatPolicy>::from_bytes(&[0x0e, 0x81, 0x00, 0x07]),
+        Err(Error::InvalidBytes("non-canonical length"))
+    );
+    assert_eq!(
+        Key::<serde_hashkey::RejectFloatPolicy>::from_bytes(&[
+            0x11, 0x02, 0x07, 0x02, 0x00, 0x07, 0x01, 0x00
+        ]),
+        Err(Error::InvalidBytes("unsorted map entries"))
+    );
+
+    // Duplicate keys are kept by normalization, so they are encoded as-is.
+    let duplicates: Key = key!({ 1u8: (), 1u8: null });
+    let bytes = [0x11, 0x02, 0x07, 0x01, 0x00, 0x07, 0x01, 0x12];
+    assert_eq!(duplicates.to_bytes(), bytes);
+    assert_eq!(Key::from_bytes(&bytes), Ok(duplicates));
+}
+
+#[test]
+fn test_invalid() {
+    assert_eq!(
+        Key::<serde_hashkey::RejectFloatPolicy>::from_bytes(&[]),
+        Err(Error::InvalidBytes("unexpected end of input"))
+    );
+    assert_eq!(
+        Key::<serde_hashkey::RejectFloatPolicy>::from_bytes(&[0x00, 0x00]),
+        Err(Error::InvalidBytes("trailing bytes"))
+    );
+    assert_eq!(
+        Key::<serde_hashkey::RejectFloatPolicy>::from_bytes(&[0xff]),
+        Err(Error::InvalidBytes("invalid tag"))
+    );
+    assert_eq!(
+        Key::<serde_hashkey::RejectFloatPolicy>::from_bytes(&[0x0f, 0x01, 0xff]),
+        Err(Error::InvalidBytes("invalid UTF-8"))
+    );
+    assert_eq!(
+        Key::<serde_hashkey::RejectFloatPolicy>::from_bytes(&[0x10, 0xff, 0xff, 0xff, 0xff, 0x0f]),
+        Err(Error::InvalidBytes("unexpected end of input"))
+    );
+    assert!(
+        Key::<serde_hashkey::RejectFloatPolicy>::from_bytes(&[0x0d, 0, 0, 0, 0, 0, 0, 0, 0])
+            .is_err()
+    );
+}
+
+#[test]
+fn test_nesting() {
+    fn nested(depth: usize) -> Vec<u8> {
+        let mut bytes = [0x10, 0x01].repeat(depth);
+        bytes.push(0x00);
+        bytes
+    }
+
+    let mut key = Key::<serde_hashkey::RejectFloatPolicy>::Unit;
+
+    for _ in 0..128 {
+        key = Key::Seq(vec![key].into());
+    }
+
+    assert_eq!(key.to_bytes(), nested(128));
+    assert_eq!(Key::from_bytes(&nested(128)), Ok(key));
+
+    assert_eq!(
+        Key::<serde_hashkey::RejectFloatPolicy>::from_bytes(&nested(129)),
+        Err(Error::InvalidBytes("nesting too deep"))
+    );
+    assert_eq!(
+        Key::<serde_hashkey::RejectFloatPolicy>::from_bytes(&nested(1_000_000)),
+        Err(Error::InvalidBytes("nesting too deep"))
+    );
+
+    let mut bytes = [0x11, 0x01, 0x00].repeat(129);
+    bytes.push(0x00);
+    assert_eq!(
+        Key::<serde_hashkey::RejectFloatPolicy>::from_bytes(&bytes),
+        Err(Error::InvalidBytes("nesting too deep"))
+    );
+}
+
+#[cfg(feature = "ordered-float")]
+#[test]
+fn test_floats() {
+    use serde_hashkey::{to_key_with_ordered_float, OrderedFloatPolicy};
+
+    let key = to_key_with_ordered_float(&(1.5f32, -0.25f64)).unwrap();
+    let bytes = key.to_bytes();
+
+    assert_eq!(
+        bytes,
+        [
+            0x10, 0x02, 0x0c, 0x3f, 0xc0, 0x00, 0x00, 0x0d, 0xbf, 0xd0, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00,
+        ]
+    );
+    assert_eq!(Key::<OrderedFloatPolicy>::from_bytes(&bytes), Ok(key));
+}
@@ -0,0 +1,149 @@
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+use serde::{Deserializer, Serializer};
+use serde_derive::{Deserialize, Serialize};
+use serde_hashkey::{from_key, from_key_tagged, from_key_with_config, to_key, to_key_tagged, Key};
+
+/// Serialize a byte slice as bytes rather than as a sequence of integers.
+fn as_bytes<S>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_bytes(bytes)
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Record<'a> {
+    name: &'a str,
+    data: &'a [u8],
+    #[serde(serialize_with = "as_bytes")]
+    raw: &'a [u8],
+    #[serde(borrow)]
+    title: Cow<'a, str>,
+}
+
+fn is_borrowed_from(value: &str, key: &Key) -> bool {
+    fn contains(key: &Key, ptr: *const u8) -> bool {
+        match key {
+            Key::String(string) => string.as_ptr() == ptr,
+            Key::Map(entries) => entries
+                .iter()
+                .any(|(k, v)| contains(k, ptr) || contains(v, ptr)),
+            Key::Seq(seq) => seq.iter().any(|key| contains(key, ptr)),
+            _ => false,
+        }
+    }
+
+    contains(key, value.as_ptr())
+}
+
+#[test]
+fn test_borrowed_struct() {
+    let record = Record {
+        name: "Noah",
+        data: &[1, 2, 3],
+        raw: b"raw",
+        title: Cow::Borrowed("Birds of a feather"),
+    };
+
+    let key = to_key(&record).unwrap();
+    let output: Record<'_> = from_key(&key).unwrap();
+
+    assert_eq!(output, record);
+    assert!(is_borrowed_from(output.name, &key));
+    assert!(matches!(output.title, Cow::Borrowed(..)));
+    assert!(is_borrowed_from(&output.title, &key));
+
+    // Empty byte slices are stored as empty sequences.
+    let record = Record {
+        name: "",
+        data: &[],
+        raw: &[],
+        title: Cow::Borrowed(""),
+    };
+
+    let key = to_key(&record).unwrap();
+    assert_eq!(from_key::<Record<'_>, _>(&key).unwrap(), record);
+}
+
+#[test]
+fn test_borrowed_collections() {
+    let key = to_key(&vec!["a", "b"]).unwrap();
+    let output: Vec<&str> = from_key(&key).unwrap();
+    assert_eq!(output, ["a", "b"]);
+
+    let mut map = BTreeMap::new();
+    map.insert("a", "x");
+    map.insert("b", "y");
+
+    let key = to_key(&map).unwrap();
+    let output: BTreeMap<&str, &str> = from_key(&key).unwrap();
+    assert_eq!(output, map);
+
+    for (k, v) in &output {
+        assert!(is_borrowed_from(k, &key));
+        assert!(is_borrowed_from(v, &key));
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Name<'a> {
+        Short(&'a str),
+        Full { first: &'a str, last: &'a str },
+    }
+
+    let name = Name::Full {
+        first: "Jane",
+        last: "Doe",
+    };
+
+    let key = to_key(&name).unwrap();
+    assert_eq!(from_key::<Name<'_>, _>(&key).unwrap(), name);
+
+    let key = to_key(&Name::Short("J")).unwrap();
+    assert_eq!(from_key::<Name<'_>, _>(&key).unwrap(), Name::Short("J"));
+}
+
+#[test]
+fn test_borrowed_with_config() {
+    let record = Record {
+        name: "Noah",
+        data: &[1, 2, 3],
+        raw: b"raw",
+        title: Cow::Borrowed("Birds of a feather"),
+    };
+
+    let key = to_key_tagged(&record).unwrap();
+    let output: Record<'_> = from_key_tagged(&key).unwrap();
+    assert_eq!(output, record);
+
+    let key = to_key(&record).unwrap();
+    let output: Record<'_> = from_key_with_config(&key, &serde_hashkey::KeyConfig::new()).unwrap();
+    assert_eq!(output, record);
+}
+
+#[test]
+fn test_bytes_from_sequence() {
+    /// Only accepts bytes, which is what a borrowed byte slice requires.
+    struct Bytes<'a>(&'a [u8]);
+
+    impl<'de> serde::Deserialize<'de> for Bytes<'de> {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            <&[u8]>::deserialize(deserializer).map(Bytes)
+        }
+    }
+
+    let key = to_key(&vec![1u8, 2, 3]).unwrap();
+    assert_eq!(from_key::<Bytes<'_>, _>(&key).unwrap().0, [1, 2, 3]);
+
+    // Sequences of other integers are not bytes.
+    let key = to_key(&vec![1u16, 2, 3]).unwrap();
+    assert!(from_key::<Bytes<'_>, _>(&key).is_err());
+
+    // Deserializing owned bytes still works.
+    let key = to_key(&vec![1u8, 2, 3]).unwrap();
+    assert_eq!(from_key::<Vec<u8>, _>(&key).unwrap(), [1, 2, 3]);
+}
@@ -0,0 +1,75 @@
+#![cfg(feature = "bytes")]
+
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use serde_hashkey::{Error, Key};
+
+fn shared(bytes: &[u8]) -> Key {
+    Key::from(bytes1::Bytes::from(bytes.to_vec()))
+}
+
+fn boxed(bytes: &[u8]) -> Key {
+    Key::from(bytes.to_vec())
+}
+
+fn hash(key: &Key) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn test_no_copy() {
+    let payload = bytes1::Bytes::from(vec![1u8, 2, 3, 4]);
+    assert!(payload.is_unique());
+
+    let key: Key = Key::from(payload.clone());
+    assert!(!payload.is_unique());
+
+    let shared = key.as_shared_bytes().unwrap();
+    assert_eq!(shared.as_ptr(), payload.as_ptr());
+
+    let cloned = key.clone();
+    assert_eq!(cloned.as_shared_bytes().unwrap().as_ptr(), payload.as_ptr());
+
+    drop((key, cloned, shared));
+    assert!(payload.is_unique());
+}
+
+#[test]
+fn test_not_shared() {
+    assert_eq!(boxed(b"abc").as_shared_bytes(), None);
+    assert_eq!(
+        Key::<serde_hashkey::RejectFloatPolicy>::Unit.as_shared_bytes(),
+        None
+    );
+}
+
+#[test]
+fn test_parity() -> Result<(), Error> {
+    for bytes in [&b""[..], b"a", b"hello world", &[0xff; 300]] {
+        let a = shared(bytes);
+        let b = boxed(bytes);
+
+        assert_eq!(a, b);
+        assert_eq!(a.cmp(&b), Ordering::Equal);
+        assert_eq!(hash(&a), hash(&b));
+        assert_eq!(format!("{:?}", a), format!("{:?}", b));
+        assert_eq!(a.to_bytes(), b.to_bytes());
+        assert_eq!(Key::from_bytes(&a.to_bytes())?, b);
+        assert_eq!(
+            serde_json::to_string(&a).unwrap(),
+            serde_json::to_string(&b).unwrap()
+        );
+    }
+
+    let mut keys = vec![shared(b"b"), boxed(b"c"), shared(b"a"), boxed(b"ab")];
+    keys.sort();
+    assert_eq!(
+        keys,
+        vec![boxed(b"a"), shared(b"ab"), boxed(b"b"), shared(b"c")]
+    );
+    Ok(())
+}
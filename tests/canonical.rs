@@ -0,0 +1,280 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use serde_derive::{Deserialize, Serialize};
+use serde_hashkey::{
+    from_key, key, to_key_with_canonical_integers, to_key_with_compact_integers, Error, Integer,
+    Key,
+};
+
+fn hash(key: &Key) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn test_equal_across_widths() -> Result<(), Error> {
+    let keys = vec![
+        to_key_with_canonical_integers(&1u8)?,
+        to_key_with_canonical_integers(&1u16)?,
+        to_key_with_canonical_integers(&1u32)?,
+        to_key_with_canonical_integers(&1u64)?,
+        to_key_with_canonical_integers(&1u128)?,
+        to_key_with_canonical_integers(&1i8)?,
+        to_key_with_canonical_integers(&1i64)?,
+        to_key_with_canonical_integers(&1i128)?,
+    ];
+
+    for key in &keys {
+        assert_eq!(*key, keys[0]);
+        assert_eq!(hash(key), hash(&keys[0]));
+    }
+
+    assert_eq!(
+        to_key_with_canonical_integers(&-1i8)?,
+        to_key_with_canonical_integers(&-1i64)?
+    );
+    assert_ne!(
+        to_key_with_canonical_integers(&-1i8)?,
+        to_key_with_canonical_integers(&255u8)?
+    );
+    Ok(())
+}
+
+#[test]
+fn test_representation() {
+    let cases = [
+        (Integer::I64(0), Integer::U8(0)),
+        (Integer::U128(255), Integer::U8(255)),
+        (Integer::I32(256), Integer::U16(256)),
+        (Integer::I64(65_536), Integer::U32(65_536)),
+        (
+            Integer::U128(u128::from(u32::MAX) + 1),
+            Integer::U64(u64::from(u32::MAX) + 1),
+        ),
+        (Integer::U128(u128::MAX), Integer::U128(u128::MAX)),
+        (Integer::I8(-1), Integer::I128(-1)),
+        (Integer::I128(i128::MIN), Integer::I128(i128::MIN)),
+    ];
+
+    for (integer, expected) in cases {
+        assert_eq!(integer.canonical(), expected);
+        assert_eq!(expected.canonical(), expected);
+    }
+}
+
+#[test]
+fn test_ordering() -> Result<(), Error> {
+    let values: Vec<i128> = vec![
+        i128::MIN,
+        i128::from(i64::MIN) - 1,
+        -129,
+        -1,
+        0,
+        1,
+        255,
+        256,
+        65_535,
+        65_536,
+        i128::from(u64::MAX),
+        i128::from(u64::MAX) + 1,
+        i128::MAX,
+    ];
+
+    let mut keys = values
+        .iter()
+        .rev()
+        .map(to_key_with_canonical_integers)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    keys.sort();
+
+    let sorted = keys
+        .iter()
+        .map(|key| key.as_integer().and_then(Integer::to_i128).unwrap())
+        .collect::<Vec<_>>();
+
+    assert_eq!(sorted, values);
+    Ok(())
+}
+
+#[test]
+fn test_roundtrip() -> Result<(), Error> {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Narrow {
+        id: u8,
+        offset: i8,
+        ids: Vec<u16>,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Wide {
+        id: u64,
+        offset: i128,
+        ids: Vec<i64>,
+    }
+
+    let narrow = Narrow {
+        id: 42,
+        offset: -5,
+        ids: vec![1, 2, 3],
+    };
+
+    let wide = Wide {
+        id: 42,
+        offset: -5,
+        ids: vec![1, 2, 3],
+    };
+
+    let key = to_key_with_canonical_integers(&narrow)?;
+    assert_eq!(key, to_key_with_canonical_integers(&wide)?);
+    assert!(matches!(key["ids"], Key::IntegerSeq(..)));
+
+    assert_eq!(from_key::<Narrow, _>(&key)?, narrow);
+    assert_eq!(from_key::<Wide, _>(&key)?, wide);
+
+    assert_eq!(
+        from_key::<u128, _>(&to_key_with_canonical_integers(&u128::MAX)?)?,
+        u128::MAX
+    );
+    assert_eq!(
+        from_key::<i128, _>(&to_key_with_canonical_integers(&i128::MIN)?)?,
+        i128::MIN
+    );
+    assert!(from_key::<u8, _>(&to_key_with_canonical_integers(&256u32)?).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_mixed_sequences() -> Result<(), Error> {
+    // Sequences use the narrowest width which holds every element.
+    let key = to_key_with_canonical_integers(&vec![1u32, 300])?;
+    assert_eq!(key, key!([1u16, 300u16]));
+    assert!(matches!(key, Key::IntegerSeq(..)));
+    assert_eq!(key, to_key_with_canonical_integers(&(1u8, 300u64))?);
+
+    let key = to_key_with_canonical_integers(&vec![1u32, 2])?;
+    assert_eq!(key, key!([1u8, 2u8]));
+
+    // Negative values are stored as `i128`, so every element is.
+    let key = to_key_with_canonical_integers(&vec![-1i8, 1])?;
+    assert_eq!(key, key!([(-1i128), 1i128]));
+    assert_eq!(key, to_key_with_canonical_integers(&(-1i64, 1u8))?);
+
+    // Unless some element doesn't fit in an `i128`.
+    let key = to_key_with_canonical_integers(&(-1i8, u128::MAX))?;
+    assert_eq!(key, Key::Seq(vec![key!(-1i128), key!(u128::MAX)].into()));
+    Ok(())
+}
+
+#[test]
+fn test_compact_representation() {
+    let cases = [
+        (Integer::U64(7), Integer::U8(7)),
+        (Integer::I64(7), Integer::U8(7)),
+        (Integer::I64(-3), Integer::I8(-3)),
+        (Integer::I32(-128), Integer::I8(-128)),
+        (Integer::I32(-129), Integer::I16(-129)),
+        (Integer::I64(-32_769), Integer::I32(-32_769)),
+        (
+            Integer::I128(i128::from(i32::MIN) - 1),
+            Integer::I64(i64::from(i32::MIN) - 1),
+        ),
+        (Integer::I128(i128::MIN), Integer::I128(i128::MIN)),
+        (Integer::U128(u128::MAX), Integer::U128(u128::MAX)),
+    ];
+
+    for (integer, expected) in cases {
+        assert_eq!(integer.compact(), expected);
+        assert_eq!(expected.compact(), expected);
+        assert_eq!(integer.to_string().parse(), Ok(expected));
+    }
+}
+
+#[test]
+fn test_compact_structs() -> Result<(), Error> {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Narrow {
+        id: u8,
+        parent: u8,
+        offset: i8,
+        children: Vec<u8>,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Wide {
+        id: u64,
+        parent: u64,
+        offset: i64,
+        children: Vec<u64>,
+    }
+
+    let narrow = Narrow {
+        id: 7,
+        parent: 1,
+        offset: -3,
+        children: vec![8, 9],
+    };
+
+    let wide = Wide {
+        id: 7,
+        parent: 1,
+        offset: -3,
+        children: vec![8, 9],
+    };
+
+    let key = to_key_with_compact_integers(&wide)?;
+    assert_eq!(key, to_key_with_compact_integers(&narrow)?);
+    assert_eq!(hash(&key), hash(&to_key_with_compact_integers(&narrow)?));
+
+    assert_eq!(key["id"], Key::Integer(Integer::U8(7)));
+    assert_eq!(key["offset"], Key::Integer(Integer::I8(-3)));
+    assert_eq!(key["children"], key!([8u8, 9u8]));
+
+    assert_eq!(from_key::<Wide, _>(&key)?, wide);
+    assert_eq!(from_key::<Narrow, _>(&key)?, narrow);
+    Ok(())
+}
+
+#[test]
+fn test_compact_coercion() -> Result<(), Error> {
+    let key = to_key_with_compact_integers(&u64::MAX)?;
+    assert_eq!(key, Key::Integer(Integer::U64(u64::MAX)));
+    assert_eq!(from_key::<u128, _>(&key)?, u128::from(u64::MAX));
+    assert!(from_key::<u32, _>(&key).is_err());
+
+    let key = to_key_with_compact_integers(&-300i128)?;
+    assert_eq!(key, Key::Integer(Integer::I16(-300)));
+    assert_eq!(from_key::<i128, _>(&key)?, -300);
+    assert_eq!(from_key::<i32, _>(&key)?, -300);
+    assert!(from_key::<i8, _>(&key).is_err());
+    assert!(from_key::<u64, _>(&key).is_err());
+
+    // Sequences use the narrowest width which holds every element.
+    let key = to_key_with_compact_integers(&vec![-1i64, 1, 1000])?;
+    assert_eq!(key, key!([(-1i16), 1i16, 1000i16]));
+    assert_eq!(from_key::<Vec<i64>, _>(&key)?, vec![-1, 1, 1000]);
+    Ok(())
+}
+
+#[test]
+fn test_compact_sequences() -> Result<(), Error> {
+    let key = to_key_with_compact_integers(&vec![1u64, 300])?;
+    assert_eq!(key, key!([1u16, 300u16]));
+    assert_eq!(key, to_key_with_compact_integers(&(1u8, 300u32))?);
+
+    // Signed sequences are as wide as the widest of their values.
+    let key = to_key_with_compact_integers(&vec![-3i64, 200])?;
+    assert_eq!(key, key!([(-3i16), 200i16]));
+    assert_eq!(from_key::<Vec<i64>, _>(&key)?, vec![-3, 200]);
+
+    // Values which don't fit in any signed width are left as they are.
+    let key = to_key_with_compact_integers(&(-1i64, u128::MAX))?;
+    assert_eq!(key, Key::Seq(vec![key!(-1i8), key!(u128::MAX)].into()));
+
+    // Nested sequences are compacted independently.
+    let key = to_key_with_compact_integers(&vec![vec![1u64], vec![1000]])?;
+    assert_eq!(key, key!([[1u8], [1000u16]]));
+    Ok(())
+}
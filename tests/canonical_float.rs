@@ -0,0 +1,127 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use serde_derive::{Deserialize, Serialize};
+use serde_hashkey::{
+    from_key, to_key_with_canonical_float, CanonicalFloat, CanonicalFloatPolicy, Error, Float, Key,
+};
+
+fn hash(key: &Key<CanonicalFloatPolicy>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn test_nan_folding() -> Result<(), Error> {
+    let nans = [
+        f64::NAN,
+        -f64::NAN,
+        f64::from_bits(0x7ff0_0000_0000_0001),
+        f64::from_bits(0xfff8_0000_0000_dead),
+    ];
+
+    let expected = to_key_with_canonical_float(&f64::NAN)?;
+
+    for nan in nans {
+        let key = to_key_with_canonical_float(&nan)?;
+        assert_eq!(key, expected);
+        assert_eq!(hash(&key), hash(&expected));
+        assert_eq!(key.to_bytes(), b"\x0d\x7f\xf8\0\0\0\0\0\0");
+    }
+
+    assert_eq!(
+        to_key_with_canonical_float(&f64::NAN)?,
+        to_key_with_canonical_float(&-f64::NAN)?
+    );
+    assert_eq!(
+        to_key_with_canonical_float(&f32::from_bits(0xffc0_0001))?.to_bytes(),
+        b"\x0c\x7f\xc0\0\0"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_zero_folding() -> Result<(), Error> {
+    let zero = to_key_with_canonical_float(&0.0f64)?;
+    let negative = to_key_with_canonical_float(&-0.0f64)?;
+    assert_eq!(zero, negative);
+    assert_eq!(hash(&zero), hash(&negative));
+    assert_eq!(negative.to_bytes(), zero.to_bytes());
+    assert_eq!(from_key::<f64, _>(&negative)?.to_bits(), 0);
+
+    assert_eq!(
+        to_key_with_canonical_float(&-0.0f32)?,
+        to_key_with_canonical_float(&0.0f32)?
+    );
+
+    // Different widths are still different keys.
+    assert_ne!(
+        to_key_with_canonical_float(&0.0f32)?,
+        to_key_with_canonical_float(&0.0f64)?
+    );
+    Ok(())
+}
+
+#[test]
+fn test_ordering() {
+    let sorted = [
+        f64::NEG_INFINITY,
+        -1.5,
+        -f64::MIN_POSITIVE,
+        0.0,
+        f64::MIN_POSITIVE,
+        1.5,
+        f64::INFINITY,
+        f64::NAN,
+    ];
+
+    for (i, a) in sorted.iter().enumerate() {
+        for (j, b) in sorted.iter().enumerate() {
+            let (a, b) = (CanonicalFloat::new(*a), CanonicalFloat::new(*b));
+            assert_eq!(a.cmp(&b), i.cmp(&j), "{:?} cmp {:?}", a, b);
+        }
+    }
+
+    assert_eq!(
+        CanonicalFloat::new(-0.0f64).cmp(&CanonicalFloat::new(0.0)),
+        std::cmp::Ordering::Equal
+    );
+}
+
+#[test]
+fn test_roundtrip() -> Result<(), Error> {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Reading {
+        value: f64,
+        history: Vec<f32>,
+    }
+
+    let reading = Reading {
+        value: 21.5,
+        history: vec![20.0, -0.0, 21.0],
+    };
+
+    let key = to_key_with_canonical_float(&reading)?;
+    assert!(matches!(key["value"], Key::Float(Float::F64(..))));
+
+    let decoded = from_key::<Reading, _>(&key)?;
+    assert_eq!(decoded, reading);
+    assert_eq!(decoded.history[1].to_bits(), 0);
+
+    assert_eq!(
+        Key::<CanonicalFloatPolicy>::from_bytes(&key.to_bytes())?,
+        key.normalize()
+    );
+    Ok(())
+}
+
+#[test]
+fn test_decoding_canonicalizes() -> Result<(), Error> {
+    let key = Key::<CanonicalFloatPolicy>::from_bytes(b"\x0d\xff\xf8\0\0\0\0\0\x01")?;
+    assert_eq!(key, to_key_with_canonical_float(&f64::NAN)?);
+
+    let key = Key::<CanonicalFloatPolicy>::from_bytes(b"\x0d\x80\0\0\0\0\0\0\0")?;
+    assert_eq!(key, Key::from(0.0f64));
+    Ok(())
+}
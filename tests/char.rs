@@ -0,0 +1,64 @@
+use serde_derive::{Deserialize, Serialize};
+use serde_hashkey::{from_key, key, to_key, Error, Key, RejectFloatPolicy};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Glyph {
+    value: char,
+    fallback: Option<char>,
+    name: String,
+}
+
+#[test]
+fn test_char() -> Result<(), Error> {
+    let key = to_key(&'a')?;
+    assert_eq!(key, Key::Char('a'));
+    assert_eq!(key, key!('a'));
+    assert_ne!(key, to_key("a")?);
+    assert_eq!(from_key::<char, _>(&key)?, 'a');
+    assert_eq!(from_key::<String, _>(&key)?, "a");
+    assert_eq!(from_key::<Key, _>(&key)?, key);
+
+    // Single character strings can still be deserialized as a char.
+    assert_eq!(from_key::<char, _>(&to_key("b")?)?, 'b');
+    Ok(())
+}
+
+#[test]
+fn test_option() -> Result<(), Error> {
+    assert_eq!(to_key(&Some('🦀'))?, Key::Char('🦀'));
+    assert_eq!(
+        from_key::<Option<char>, _>(&to_key(&Some('🦀'))?)?,
+        Some('🦀')
+    );
+    assert_eq!(from_key::<Option<char>, _>(&to_key(&None::<char>)?)?, None);
+    Ok(())
+}
+
+#[test]
+fn test_struct() -> Result<(), Error> {
+    let glyph = Glyph {
+        value: 'x',
+        fallback: Some('?'),
+        name: String::from("x"),
+    };
+
+    let key = to_key(&glyph)?;
+    assert_eq!(key, key!({ "value": 'x', "fallback": '?', "name": "x" }));
+    assert_eq!(from_key::<Glyph, _>(&key)?, glyph);
+    assert_eq!(Key::from_bytes(&key.to_bytes())?, key.normalize());
+    Ok(())
+}
+
+#[test]
+fn test_order() {
+    let mut keys = vec![key!("a"), key!('b'), key!(b"c"), key!('a')];
+    keys.sort();
+    assert_eq!(keys, vec![key!(b"c"), key!('a'), key!('b'), key!("a")]);
+}
+
+#[test]
+fn test_binary() -> Result<(), Error> {
+    assert_eq!(key!('a').to_bytes(), b"\x13\x00\x00\x00\x61");
+    assert!(Key::<RejectFloatPolicy>::from_bytes(b"\x13\x00\x00\xd8\x00").is_err());
+    Ok(())
+}
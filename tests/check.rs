@@ -0,0 +1,211 @@
+use std::collections::BTreeMap;
+
+use serde::ser::{Error as _, Serialize, Serializer};
+use serde_derive::Serialize;
+use serde_hashkey::{check_key, Error, FloatWidth};
+
+fn check<T>(value: &T) -> Vec<(String, Error)>
+where
+    T: ?Sized + Serialize,
+{
+    match check_key(value) {
+        Ok(()) => Vec::new(),
+        Err(errors) => errors
+            .into_iter()
+            .map(|(path, error)| (path.to_string(), error))
+            .collect(),
+    }
+}
+
+fn f32(value: f64) -> Error {
+    Error::UnsupportedFloat {
+        width: FloatWidth::F32,
+        value,
+    }
+}
+
+fn f64(value: f64) -> Error {
+    Error::UnsupportedFloat {
+        width: FloatWidth::F64,
+        value,
+    }
+}
+
+#[derive(Serialize)]
+struct Sample {
+    name: &'static str,
+    min: f64,
+    max: f64,
+}
+
+#[derive(Serialize)]
+enum Shape {
+    Circle(f32),
+    Rect(u32, f32),
+    Point { x: f64, y: u32 },
+}
+
+#[derive(Serialize)]
+struct Measurement {
+    id: u32,
+    weight: f32,
+    samples: Vec<Sample>,
+    shapes: Vec<Shape>,
+    scale: Option<f64>,
+}
+
+#[test]
+fn test_all_reported() {
+    let value = Measurement {
+        id: 1,
+        weight: 1.0,
+        samples: vec![
+            Sample {
+                name: "a",
+                min: 0.0,
+                max: 1.0,
+            },
+            Sample {
+                name: "b",
+                min: 2.0,
+                max: 3.0,
+            },
+        ],
+        shapes: vec![
+            Shape::Circle(1.0),
+            Shape::Rect(1, 2.0),
+            Shape::Point { x: 1.0, y: 2 },
+        ],
+        scale: Some(0.5),
+    };
+
+    assert_eq!(
+        check(&value),
+        vec![
+            (String::from("weight"), f32(1.0)),
+            (String::from("samples[0].min"), f64(0.0)),
+            (String::from("samples[0].max"), f64(1.0)),
+            (String::from("samples[1].min"), f64(2.0)),
+            (String::from("samples[1].max"), f64(3.0)),
+            (String::from("shapes[0].Circle"), f32(1.0)),
+            (String::from("shapes[1].Rect[1]"), f32(2.0)),
+            (String::from("shapes[2].Point.x"), f64(1.0)),
+            (String::from("scale"), f64(0.5)),
+        ]
+    );
+}
+
+#[test]
+fn test_valid() {
+    assert_eq!(check_key(&("a", 1u32, vec![Some(true)])), Ok(()));
+    assert!(check_key("unsized").is_ok());
+}
+
+#[test]
+fn test_map_keys() {
+    let mut map = BTreeMap::new();
+    map.insert("a", 1.0f32);
+    map.insert("b", 2.0f32);
+
+    assert_eq!(
+        check(&map),
+        vec![(String::from("a"), f32(1.0)), (String::from("b"), f32(2.0)),]
+    );
+
+    let mut map = BTreeMap::new();
+    map.insert(1u32, 1.0f64);
+    map.insert(2u32, 2.0f64);
+
+    assert_eq!(
+        check(&map),
+        vec![
+            (String::from("{0}"), f64(1.0)),
+            (String::from("{1}"), f64(2.0)),
+        ]
+    );
+
+    struct FloatKey(Vec<((u32, f32), &'static str)>);
+
+    impl Serialize for FloatKey {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.collect_map(self.0.iter().map(|(k, v)| (k, v)))
+        }
+    }
+
+    assert_eq!(
+        check(&FloatKey(vec![((1, 1.0), "value")])),
+        vec![(String::from("{0}[1]"), f32(1.0))]
+    );
+}
+
+#[test]
+fn test_custom_error() {
+    struct Failing;
+
+    impl Serialize for Failing {
+        fn serialize<S>(&self, _: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            Err(S::Error::custom("failing"))
+        }
+    }
+
+    #[derive(Serialize)]
+    struct Outer {
+        first: Failing,
+        second: f32,
+    }
+
+    assert_eq!(
+        check(&Outer {
+            first: Failing,
+            second: 1.0,
+        }),
+        vec![
+            (
+                String::from("first"),
+                Error::Custom(String::from("failing"))
+            ),
+            (String::from("second"), f32(1.0)),
+        ]
+    );
+}
+
+#[test]
+fn test_float_message() {
+    #[derive(Serialize)]
+    struct Book {
+        rating: f32,
+    }
+
+    #[derive(Serialize)]
+    struct Library {
+        books: Vec<Book>,
+    }
+
+    let books = [1.0, 2.0, 3.0, 42.5]
+        .iter()
+        .map(|&rating| Book { rating })
+        .collect();
+
+    let errors = check_key(&Library { books }).unwrap_err();
+
+    let messages = errors
+        .iter()
+        .map(|(path, error)| format!("{} at {}", error, path))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        messages[3],
+        "unsupported float f32 = 42.5 at books[3].rating"
+    );
+    assert_eq!(errors[3].1, f32(42.5));
+
+    let errors = check_key(&(0.1f32, 0.1f64)).unwrap_err();
+    assert_eq!(errors[0].1.to_string(), "unsupported float f32 = 0.1");
+    assert_eq!(errors[1].1.to_string(), "unsupported float f64 = 0.1");
+}
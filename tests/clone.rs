@@ -0,0 +1,49 @@
+use std::mem;
+
+use serde_hashkey::{key, to_key, Error, Key};
+
+/// Drop a deeply nested chain of sequences without recursing.
+fn unwind(mut key: Key) {
+    while let Key::Seq(seq) = &mut key {
+        match mem::take(seq).into_vec().pop() {
+            Some(inner) => key = inner,
+            None => break,
+        }
+    }
+}
+
+#[test]
+fn test_clone() -> Result<(), Error> {
+    let key = key!({
+        "a": [1u32, 2u32],
+        "b": [[], {}, ()],
+        (key!({ "c": b"bytes" })): [true, "d"],
+    });
+
+    assert_eq!(key.clone(), key);
+
+    // Sequences are cloned as they are, without being compacted.
+    let seq: Key = Key::Seq(vec![Key::from(1u32), Key::from(2u32)].into());
+    assert!(matches!(seq.clone(), Key::Seq(..)));
+    assert_eq!(seq.clone(), seq);
+
+    let scalar = to_key(&"scalar")?;
+    assert_eq!(scalar.clone(), scalar);
+    Ok(())
+}
+
+#[test]
+fn test_deep() {
+    let mut key: Key = Key::Seq(Box::default());
+
+    for _ in 0..100_000 {
+        key = Key::Seq(vec![Key::from(1u32), key].into());
+    }
+
+    let clone = key.clone();
+    assert_eq!(clone.iter().count(), key.iter().count());
+    assert_eq!(clone.iter().count(), 200_001);
+
+    unwind(clone);
+    unwind(key);
+}
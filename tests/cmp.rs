@@ -0,0 +1,147 @@
+use serde_derive::Serialize;
+use serde_hashkey::{key, to_key, Error, Integer, Key};
+
+#[derive(Serialize)]
+struct Book {
+    title: &'static str,
+    pages: u32,
+    published: bool,
+}
+
+#[test]
+fn test_lookup() -> Result<(), Error> {
+    let key = to_key(&Book {
+        title: "Birds of a feather",
+        pages: 320,
+        published: true,
+    })?;
+
+    assert!(key["title"] == "Birds of a feather");
+    assert!(key["pages"] == 320u64);
+    assert!(key["pages"] == 320i64);
+    assert!(key["published"] == true);
+
+    assert!("Birds of a feather" == key["title"]);
+    assert!(320u64 == key["pages"]);
+    assert!(true == key["published"]);
+    Ok(())
+}
+
+#[test]
+fn test_bytes() {
+    let key = key!(b"png");
+    assert_eq!(key, b"png"[..]);
+    assert_eq!(b"png"[..], key);
+    assert_ne!(key, b"jpg"[..]);
+}
+
+#[test]
+fn test_str() {
+    let key = key!("foo");
+    assert_eq!(key, "foo");
+    assert_eq!(key, *"foo");
+    assert_eq!("foo", key);
+    assert_ne!(key, "bar");
+    assert_ne!(key!('f'), "f");
+    assert_ne!(key!(b"foo"), "foo");
+}
+
+#[test]
+fn test_integers() {
+    let key: Key = Key::Integer(Integer::U8(42));
+    assert_eq!(key, 42u64);
+    assert_eq!(key, 42i64);
+    assert_eq!(42u64, key);
+    assert_eq!(42i64, key);
+    assert_ne!(key, 43u64);
+
+    // Values which don't fit are never equal.
+    assert_ne!(key!(-1i32), u64::MAX);
+    assert_eq!(key!(-1i8), -1i64);
+    assert_ne!(key!(u128::MAX), u64::MAX);
+    assert_eq!(key!(u64::MAX), u64::MAX);
+    assert_eq!(key!(i128::from(i64::MIN)), i64::MIN);
+}
+
+#[test]
+fn test_mismatched() {
+    // Mismatched kinds of keys compare unequal.
+    let keys: Vec<Key> = vec![
+        key!(null),
+        key!(()),
+        key!("1"),
+        key!('1'),
+        key!(b"1"),
+        key!([1u64]),
+        key!({ "1": 1u64 }),
+    ];
+
+    for key in &keys {
+        assert_ne!(*key, 1u64);
+        assert_ne!(*key, 1i64);
+        assert_ne!(*key, true);
+    }
+
+    assert_ne!(key!(1u64), true);
+    assert_ne!(key!(true), 1u64);
+    assert_ne!(key!("true"), true);
+    assert_ne!(key!([1u8, 2u8]), b"\x01\x02"[..]);
+    assert_ne!(key!("ab"), b"ab"[..]);
+}
+
+#[test]
+fn test_integer_ordering() {
+    // Every width, with values interleaved across them.
+    let sorted = [
+        Integer::I128(i128::MIN),
+        Integer::I64(i64::MIN),
+        Integer::I32(-129),
+        Integer::I8(-128),
+        Integer::I16(-1),
+        Integer::I64(-1),
+        Integer::I8(0),
+        Integer::U8(0),
+        Integer::U128(1),
+        Integer::I32(255),
+        Integer::U8(255),
+        Integer::U16(256),
+        Integer::U32(u32::MAX),
+        Integer::I128(i128::from(u64::MAX)),
+        Integer::U64(u64::MAX),
+        Integer::I128(i128::MAX),
+        Integer::U128(i128::MAX as u128),
+        Integer::U128(i128::MAX as u128 + 1),
+        Integer::U128(u128::MAX),
+    ];
+
+    for (i, a) in sorted.iter().enumerate() {
+        for (j, b) in sorted.iter().enumerate() {
+            assert_eq!(a.cmp(b), i.cmp(&j), "{:?} cmp {:?}", a, b);
+            assert_eq!(a.partial_cmp(b), Some(i.cmp(&j)));
+            assert_eq!(a == b, i == j);
+        }
+    }
+
+    let mut shuffled = sorted.to_vec();
+    shuffled.reverse();
+    shuffled.sort();
+    assert_eq!(shuffled, sorted);
+
+    assert!(Integer::I64(-1) < Integer::U8(0));
+    assert!(Integer::U8(0) < Integer::U128(u128::MAX));
+    assert!(Integer::I8(5) > Integer::U8(1));
+}
+
+#[test]
+fn test_integer_ordering_keys() {
+    use std::collections::BTreeMap;
+
+    let mut map = BTreeMap::new();
+    map.insert(key!(300u16), "c");
+    map.insert(key!(-5i64), "a");
+    map.insert(key!(1u8), "b");
+    map.insert(key!(1u64), "b'");
+
+    let values = map.values().copied().collect::<Vec<_>>();
+    assert_eq!(values, ["a", "b", "b'", "c"]);
+}
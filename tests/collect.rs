@@ -0,0 +1,88 @@
+use std::collections::BTreeMap;
+
+use serde_derive::Serialize;
+use serde_hashkey::{key, to_key, Error, Key};
+
+#[derive(Serialize)]
+struct Author {
+    name: &'static str,
+    age: u32,
+}
+
+#[test]
+fn test_collect_seq() -> Result<(), Error> {
+    let key: Key = vec![key!("a"), Key::from(1u32)].into_iter().collect();
+    assert_eq!(key, to_key(&("a", 1u32))?);
+
+    let key: Key = (1u8..=3).map(Key::from).collect();
+    assert_eq!(key, to_key(&vec![1u8, 2, 3])?);
+    assert!(matches!(key, Key::IntegerSeq(..)));
+
+    let key: Key = Vec::<Key>::new().into_iter().collect();
+    assert_eq!(key, to_key(&Vec::<u32>::new())?);
+    Ok(())
+}
+
+#[test]
+fn test_collect_map() -> Result<(), Error> {
+    let entries = vec![("a", 1u32), ("b", 2u32)];
+
+    let key: Key = entries
+        .iter()
+        .map(|(k, v)| Ok((to_key(k)?, to_key(v)?)))
+        .collect::<Result<_, Error>>()?;
+
+    assert_eq!(
+        key,
+        to_key(&entries.into_iter().collect::<BTreeMap<_, _>>())?
+    );
+
+    let key: Key = Vec::<(Key, Key)>::new().into_iter().collect();
+    assert_eq!(key, to_key(&BTreeMap::<u32, u32>::new())?);
+    Ok(())
+}
+
+#[test]
+fn test_extend() -> Result<(), Error> {
+    let mut key = to_key(&Author {
+        name: "Noah",
+        age: 42,
+    })?;
+
+    key.extend(vec![(key!("pages"), Key::from(320u32))]);
+    key.extend(Vec::<(Key, Key)>::new());
+
+    assert_eq!(key.get(&key!("pages")), Some(&Key::from(320u32)));
+    assert_eq!(key.iter_map().map(|e| e.len()), Some(3));
+
+    let mut key = Key::Unit;
+    key.extend(vec![(key!("a"), Key::Unit)]);
+    assert_eq!(key, key!({ "a": () }));
+
+    let mut key = to_key(&vec![1u32])?;
+    key.extend(vec![key!("a")]);
+    assert_eq!(key, to_key(&(1u32, "a"))?);
+
+    let mut key = Key::Unit;
+    key.extend(Vec::<Key>::new());
+    assert_eq!(key, to_key(&Vec::<u32>::new())?);
+    Ok(())
+}
+
+#[test]
+#[should_panic(expected = "can only extend a map with entries")]
+fn test_extend_scalar_map() {
+    let mut key = key!("a");
+    key.extend(vec![(key!("a"), Key::Unit)]);
+}
+
+#[test]
+#[should_panic(expected = "can only extend a sequence with elements")]
+fn test_extend_map_with_elements() {
+    let mut key = to_key(&Author {
+        name: "Noah",
+        age: 42,
+    })
+    .unwrap();
+    key.extend(vec![key!("a")]);
+}
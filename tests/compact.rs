@@ -0,0 +1,131 @@
+use std::mem;
+
+use serde_derive::{Deserialize, Serialize};
+use serde_hashkey::{from_key, to_key, Integer, IntegerSeq, Key};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Features {
+    ids: Vec<u32>,
+    deltas: Vec<i64>,
+    mixed: (u32, u64),
+    empty: Vec<u32>,
+    pair: Option<(u8, u8)>,
+}
+
+#[test]
+fn test_serializer_picks_compact() {
+    let value = Features {
+        ids: vec![1, 2, 3],
+        deltas: vec![-1],
+        mixed: (1, 2),
+        empty: vec![],
+        pair: Some((4, 5)),
+    };
+
+    let key = to_key(&value).unwrap();
+
+    let field = |name: &str| {
+        let path = vec![serde_hashkey::PathSegment::Field(name.into())]
+            .into_iter()
+            .collect();
+        key.get_path(&path).cloned()
+    };
+
+    assert_eq!(
+        field("ids"),
+        Some(Key::IntegerSeq(IntegerSeq::U32(vec![1, 2, 3].into())))
+    );
+    assert_eq!(
+        field("deltas"),
+        Some(Key::IntegerSeq(IntegerSeq::I64(vec![-1].into())))
+    );
+    assert_eq!(
+        field("mixed"),
+        Some(Key::Seq(vec![Key::from(1u32), Key::from(2u64)].into()))
+    );
+    assert_eq!(field("empty"), Some(Key::Seq(vec![].into())));
+    assert_eq!(
+        field("pair"),
+        Some(Key::IntegerSeq(IntegerSeq::U8(vec![4, 5].into())))
+    );
+
+    assert_eq!(from_key::<Features, _>(&key).unwrap(), value);
+}
+
+#[test]
+fn test_normalize() {
+    let seq: Key = Key::Seq(vec![Key::from(1u16), Key::from(2u16)].into());
+    let compact: Key = Key::IntegerSeq(IntegerSeq::U16(vec![1, 2].into()));
+
+    assert_ne!(seq, compact);
+    assert_eq!(seq.clone().normalize(), compact);
+    assert_eq!(seq.to_bytes(), compact.to_bytes());
+    assert_eq!(Key::from_bytes(&seq.to_bytes()), Ok(compact));
+}
+
+#[test]
+fn test_accessors() {
+    let seq = IntegerSeq::I8(vec![-1, 2].into());
+    assert_eq!(seq.len(), 2);
+    assert!(!seq.is_empty());
+    assert_eq!(seq.get(1), Some(Integer::I8(2)));
+    assert_eq!(seq.get(2), None);
+    assert_eq!(
+        seq.iter().collect::<Vec<_>>(),
+        vec![Integer::I8(-1), Integer::I8(2)]
+    );
+}
+
+#[test]
+fn test_tuple_variant() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Shape {
+        Rect(u32, u32),
+    }
+
+    let key = to_key(&Shape::Rect(1, 2)).unwrap();
+    assert_eq!(from_key::<Shape, _>(&key).unwrap(), Shape::Rect(1, 2));
+}
+
+#[test]
+fn test_json() {
+    let key: Key = serde_json::from_str("[1, 2, 3]").unwrap();
+    assert_eq!(key, Key::IntegerSeq(IntegerSeq::U64(vec![1, 2, 3].into())));
+    assert_eq!(serde_json::to_string(&key).unwrap(), "[1,2,3]");
+}
+
+#[test]
+fn test_memory() {
+    let values = (0..10_000u32).collect::<Vec<_>>();
+    let key = to_key(&values).unwrap();
+
+    let payload = match &key {
+        Key::IntegerSeq(IntegerSeq::U32(seq)) => mem::size_of_val(&**seq),
+        other => panic!("expected compact sequence, got {:?}", other),
+    };
+
+    let generic = values.len() * mem::size_of::<Key>();
+
+    assert_eq!(payload, 40_000);
+    assert!(generic >= 8 * payload, "{} vs {}", generic, payload);
+}
+
+#[cfg(feature = "ordered-float")]
+#[test]
+fn test_floats() {
+    use serde_hashkey::{to_key_with_ordered_float, FloatSeq, OrderedFloat, OrderedFloatPolicy};
+
+    let key = to_key_with_ordered_float(&vec![1.5f64, -2.0]).unwrap();
+
+    assert_eq!(
+        key,
+        Key::<OrderedFloatPolicy>::FloatSeq(FloatSeq::F64(
+            vec![OrderedFloat(1.5), OrderedFloat(-2.0)].into()
+        ))
+    );
+    assert_eq!(from_key::<Vec<f64>, _>(&key).unwrap(), vec![1.5, -2.0]);
+    assert_eq!(
+        Key::<OrderedFloatPolicy>::from_bytes(&key.to_bytes()),
+        Ok(key)
+    );
+}
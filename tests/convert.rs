@@ -0,0 +1,109 @@
+use std::convert::TryFrom;
+
+use serde_hashkey::{key, to_key, Error, Integer, Key};
+
+#[test]
+fn test_success() -> Result<(), Error> {
+    assert_eq!(String::try_from(key!("hello"))?, "hello");
+    assert_eq!(String::try_from(&key!("hello"))?, "hello");
+    assert_eq!(Vec::<u8>::try_from(key!(b"abc"))?, b"abc");
+    assert_eq!(Vec::<u8>::try_from(&key!(b"abc"))?, b"abc");
+    assert!(bool::try_from(key!(true))?);
+    assert!(!bool::try_from(&key!(false))?);
+
+    let seq = key!(["a", "b"]);
+    assert_eq!(Vec::<Key>::try_from(&seq)?, vec![key!("a"), key!("b")]);
+    assert_eq!(Vec::<Key>::try_from(seq)?, vec![key!("a"), key!("b")]);
+
+    // Compact sequences are expanded.
+    let seq = to_key(&vec![1u32, 2])?;
+    assert!(matches!(seq, Key::IntegerSeq(..)));
+    assert_eq!(Vec::<Key>::try_from(&seq)?, vec![key!(1u32), key!(2u32)]);
+    assert_eq!(Vec::<Key>::try_from(seq)?, vec![key!(1u32), key!(2u32)]);
+
+    let map = key!({ "a": 1u32 });
+    assert_eq!(
+        Vec::<(Key, Key)>::try_from(&map)?,
+        vec![(key!("a"), key!(1u32))]
+    );
+    assert_eq!(
+        Vec::<(Key, Key)>::try_from(map)?,
+        vec![(key!("a"), key!(1u32))]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_integers() -> Result<(), Error> {
+    let key: Key = Key::Integer(Integer::U64(200));
+    assert_eq!(u8::try_from(&key)?, 200);
+    assert_eq!(i16::try_from(&key)?, 200);
+    assert_eq!(u128::try_from(&key)?, 200);
+    assert_eq!(usize::try_from(key)?, 200);
+
+    assert_eq!(i8::try_from(key!(-128i64))?, -128);
+    assert_eq!(i64::try_from(key!(i128::from(i64::MIN)))?, i64::MIN);
+    assert_eq!(u128::try_from(key!(u128::MAX))?, u128::MAX);
+    assert_eq!(isize::try_from(key!(-1i8))?, -1);
+    Ok(())
+}
+
+#[test]
+fn test_overflow() {
+    assert_eq!(i8::try_from(key!(200u8)), Err(Error::IntegerOverflow("i8")));
+    assert_eq!(
+        u32::try_from(key!(-1i32)),
+        Err(Error::IntegerOverflow("u32"))
+    );
+    assert_eq!(
+        i128::try_from(key!(u128::MAX)),
+        Err(Error::IntegerOverflow("i128"))
+    );
+    assert_eq!(
+        u64::try_from(&key!(u128::from(u64::MAX) + 1)),
+        Err(Error::IntegerOverflow("u64"))
+    );
+    assert_eq!(
+        Error::IntegerOverflow("u8").to_string(),
+        "integer out of range for u8"
+    );
+}
+
+#[test]
+fn test_mismatch() {
+    assert_eq!(
+        String::try_from(key!('a')),
+        Err(Error::Unexpected("string"))
+    );
+    assert_eq!(
+        String::try_from(&key!(b"a")),
+        Err(Error::Unexpected("string"))
+    );
+    assert_eq!(
+        Vec::<u8>::try_from(key!([1u8, 2u8])),
+        Err(Error::Unexpected("bytes"))
+    );
+    assert_eq!(
+        Vec::<u8>::try_from(&key!("a")),
+        Err(Error::Unexpected("bytes"))
+    );
+    assert_eq!(bool::try_from(key!(1u8)), Err(Error::Unexpected("bool")));
+    assert_eq!(u8::try_from(key!(true)), Err(Error::Unexpected("integer")));
+    assert_eq!(i64::try_from(&key!("1")), Err(Error::Unexpected("integer")));
+    assert_eq!(
+        Vec::<Key>::try_from(key!({})),
+        Err(Error::Unexpected("sequence"))
+    );
+    assert_eq!(
+        Vec::<Key>::try_from(&key!(())),
+        Err(Error::Unexpected("sequence"))
+    );
+    assert_eq!(
+        Vec::<(Key, Key)>::try_from(key!([])),
+        Err(Error::Unexpected("map"))
+    );
+    assert_eq!(
+        Vec::<(Key, Key)>::try_from(&key!(null)),
+        Err(Error::Unexpected("map"))
+    );
+}
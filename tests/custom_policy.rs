@@ -0,0 +1,111 @@
+use std::collections::HashSet;
+
+use serde::{de, ser};
+use serde_derive::{Deserialize, Serialize};
+use serde_hashkey::{from_key, to_key_with, Error, Float, FloatPolicy, FloatRepr, FloatWidth, Key};
+
+/// A float rounded to three decimal places, stored as thousandths.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct Rounded(i64);
+
+impl Rounded {
+    fn new(value: f64, width: FloatWidth) -> Result<Self, Error> {
+        let rounded = (value * 1000.0).round();
+
+        if !rounded.is_finite() || rounded.abs() >= (1u64 << 53) as f64 {
+            return Err(Error::UnsupportedFloat { width, value });
+        }
+
+        Ok(Rounded(rounded as i64))
+    }
+
+    fn value(self) -> f64 {
+        self.0 as f64 / 1000.0
+    }
+}
+
+impl FloatRepr<f32> for Rounded {
+    fn serialize(value: f32) -> Result<Self, Error> {
+        Rounded::new(f64::from(value), FloatWidth::F32)
+    }
+
+    fn visit<'de, V>(&self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_f64(self.value())
+    }
+}
+
+impl FloatRepr<f64> for Rounded {
+    fn serialize(value: f64) -> Result<Self, Error> {
+        Rounded::new(value, FloatWidth::F64)
+    }
+
+    fn visit<'de, V>(&self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_f64(self.value())
+    }
+}
+
+impl ser::Serialize for Rounded {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        serializer.serialize_f64(self.value())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct RoundedPolicy;
+
+impl FloatPolicy for RoundedPolicy {
+    type F32 = Rounded;
+    type F64 = Rounded;
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Point {
+    x: f64,
+    y: f32,
+}
+
+fn key<T>(value: T) -> Result<Key<RoundedPolicy>, Error>
+where
+    T: ser::Serialize,
+{
+    to_key_with(&value)
+}
+
+#[test]
+fn test_custom_policy() -> Result<(), Error> {
+    let a = key(Point { x: 1.0001, y: 2.0 })?;
+    let b = key(Point { x: 1.0004, y: 2.0 })?;
+    let c = key(Point { x: 1.0006, y: 2.0 })?;
+
+    assert_eq!(a, b);
+    assert_ne!(b, c);
+    assert!(a < c);
+    assert!(matches!(a["x"], Key::Float(Float::F64(Rounded(1000)))));
+    assert!(matches!(a["y"], Key::Float(Float::F32(Rounded(2000)))));
+
+    let set = vec![a.clone(), b, c].into_iter().collect::<HashSet<_>>();
+    assert_eq!(set.len(), 2);
+
+    assert_eq!(from_key::<Point, _>(&a)?, Point { x: 1.0, y: 2.0 });
+    assert_eq!(Key::<RoundedPolicy>::from_bytes(&a.to_bytes())?, a);
+    Ok(())
+}
+
+#[test]
+fn test_custom_policy_rejects() {
+    assert_eq!(
+        key(f64::NAN).unwrap_err().to_string(),
+        "unsupported float f64 = NaN"
+    );
+    assert!(key(f32::INFINITY).is_err());
+    assert!(key(1e300f64).is_err());
+}
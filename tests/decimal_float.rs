@@ -0,0 +1,123 @@
+#![cfg(feature = "decimal")]
+
+use std::str::FromStr;
+
+use rust_decimal1::Decimal;
+use serde_derive::{Deserialize, Serialize};
+use serde_hashkey::{
+    from_key, to_key_with_decimal_float, DecimalFloat, DecimalFloatPolicy, Error, Float,
+    FloatWidth, Key,
+};
+
+fn decimal(s: &str) -> Key<DecimalFloatPolicy> {
+    Key::from(Decimal::from_str(s).unwrap())
+}
+
+#[test]
+fn test_decimal_conversion() {
+    // The shortest decimal is used, not the binary approximation.
+    assert_eq!(to_key_with_decimal_float(&0.1f64).unwrap(), decimal("0.1"));
+    assert_eq!(to_key_with_decimal_float(&0.1f32).unwrap(), decimal("0.1"));
+    assert_eq!(
+        to_key_with_decimal_float(&19.99f64).unwrap(),
+        decimal("19.99")
+    );
+    assert_eq!(
+        to_key_with_decimal_float(&-1234.5f64).unwrap(),
+        decimal("-1234.5")
+    );
+
+    // Decimals which only differ in scale are equal.
+    assert_eq!(decimal("0.10"), decimal("0.1"));
+
+    // Both widths produce the same key.
+    assert_eq!(
+        to_key_with_decimal_float(&2.5f32).unwrap(),
+        to_key_with_decimal_float(&2.5f64).unwrap()
+    );
+
+    assert_eq!(
+        to_key_with_decimal_float(&-0.0f64).unwrap(),
+        to_key_with_decimal_float(&0.0f64).unwrap()
+    );
+
+    // Floats which are too small to be represented are rounded to zero.
+    assert_eq!(to_key_with_decimal_float(&1e-30f64).unwrap(), decimal("0"));
+}
+
+#[test]
+fn test_decimal_ordering() {
+    let a = to_key_with_decimal_float(&-1.5f64).unwrap();
+    let b = to_key_with_decimal_float(&0.1f32).unwrap();
+    let c = to_key_with_decimal_float(&0.25f64).unwrap();
+    assert!(a < b);
+    assert!(b < c);
+}
+
+#[test]
+fn test_decimal_rejected() {
+    assert!(matches!(
+        to_key_with_decimal_float(&f64::NAN),
+        Err(Error::NonFiniteFloat {
+            width: FloatWidth::F64,
+            ..
+        })
+    ));
+
+    assert!(matches!(
+        to_key_with_decimal_float(&f32::INFINITY),
+        Err(Error::NonFiniteFloat {
+            width: FloatWidth::F32,
+            ..
+        })
+    ));
+
+    assert_eq!(
+        to_key_with_decimal_float(&1e30f64),
+        Err(Error::UnsupportedFloat {
+            width: FloatWidth::F64,
+            value: 1e30,
+        })
+    );
+
+    assert_eq!(
+        to_key_with_decimal_float(&1e30f32),
+        Err(Error::UnsupportedFloat {
+            width: FloatWidth::F32,
+            value: f64::from(1e30f32),
+        })
+    );
+}
+
+#[test]
+fn test_decimal_roundtrip() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Invoice {
+        total: f64,
+        tax: f32,
+        lines: Vec<f64>,
+    }
+
+    let invoice = Invoice {
+        total: 107.1,
+        tax: 7.1,
+        lines: vec![0.1, 0.2, 99.99, 6.81],
+    };
+
+    let key = to_key_with_decimal_float(&invoice).unwrap();
+    assert_eq!(key["total"], decimal("107.1"));
+    assert_eq!(key["tax"], decimal("7.1"));
+    assert_eq!(from_key::<Invoice, _>(&key).unwrap(), invoice);
+
+    let bytes = key.to_bytes();
+    assert_eq!(
+        Key::<DecimalFloatPolicy>::from_bytes(&bytes).unwrap(),
+        key.normalize()
+    );
+
+    let value = Key::<DecimalFloatPolicy>::Float(Float::F64(DecimalFloat(
+        Decimal::from_str("0.3").unwrap(),
+    )));
+    assert_eq!(from_key::<f64, _>(&value).unwrap(), 0.3);
+    assert_eq!(from_key::<f32, _>(&value).unwrap(), 0.3);
+}
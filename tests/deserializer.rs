@@ -0,0 +1,116 @@
+use std::collections::BTreeMap;
+
+use serde::de::{DeserializeSeed, IntoDeserializer as _};
+use serde_derive::{Deserialize, Serialize};
+use serde_hashkey::{to_key, Error, Key, KeyDeserializer};
+
+#[derive(Serialize)]
+struct Book {
+    title: &'static str,
+    ratings: Vec<&'static str>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct BorrowedBook<'a> {
+    title: &'a str,
+    #[serde(borrow)]
+    ratings: Vec<&'a str>,
+}
+
+#[test]
+fn test_borrowed() -> Result<(), Error> {
+    let key = to_key(&Book {
+        title: "Birds of a feather",
+        ratings: vec!["good", "great"],
+    })?;
+
+    let book = serde::Deserialize::deserialize(KeyDeserializer::new(&key))?;
+
+    assert_eq!(
+        BorrowedBook {
+            title: "Birds of a feather",
+            ratings: vec!["good", "great"],
+        },
+        book
+    );
+
+    let bytes: Key = Key::Bytes(vec![1, 2, 3].into());
+    let value: &[u8] = serde::Deserialize::deserialize(bytes.deserializer())?;
+    assert_eq!(value, [1, 2, 3]);
+    Ok(())
+}
+
+#[test]
+fn test_into_deserializer() -> Result<(), Error> {
+    let key = to_key(&("a", 1u32))?;
+    let deserializer: KeyDeserializer<'_, _> = (&key).into_deserializer();
+    let value: (String, u32) = serde::Deserialize::deserialize(deserializer)?;
+    assert_eq!(value, (String::from("a"), 1));
+    Ok(())
+}
+
+#[test]
+fn test_seed() -> Result<(), Error> {
+    /// Counts the entries of a map without deserializing them.
+    struct CountEntries;
+
+    impl<'de> DeserializeSeed<'de> for CountEntries {
+        type Value = usize;
+
+        fn deserialize<D>(self, deserializer: D) -> Result<usize, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            let map = BTreeMap::<String, serde::de::IgnoredAny>::deserialize(deserializer)?;
+            Ok(map.len())
+        }
+    }
+
+    use serde::Deserialize as _;
+
+    let mut map = BTreeMap::new();
+    map.insert("a", 1u32);
+    map.insert("b", 2u32);
+    let key = to_key(&map)?;
+
+    assert_eq!(CountEntries.deserialize(key.deserializer())?, 2);
+    Ok(())
+}
+
+#[test]
+fn test_path_to_error() -> Result<(), Error> {
+    #[derive(Serialize)]
+    struct Library {
+        books: Vec<Book>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Strict {
+        books: Vec<StrictBook>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct StrictBook {
+        title: String,
+        ratings: Vec<u32>,
+    }
+
+    let key = to_key(&Library {
+        books: vec![
+            Book {
+                title: "a",
+                ratings: vec![],
+            },
+            Book {
+                title: "b",
+                ratings: vec!["good"],
+            },
+        ],
+    })?;
+
+    let error = serde_path_to_error::deserialize::<_, Strict>(key.deserializer()).unwrap_err();
+    assert_eq!(error.path().to_string(), "books[1].ratings[0]");
+    Ok(())
+}
@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use serde::ser::{SerializeMap, SerializeStruct, SerializeStructVariant};
+use serde::{Serialize, Serializer};
+use serde_hashkey::{to_key, to_key_with_config, Error, Key, KeyConfig, RejectFloatPolicy};
+
+fn strict() -> KeyConfig {
+    KeyConfig::new().reject_duplicate_keys(true)
+}
+
+/// A map which deliberately serializes the same key twice.
+struct Duplicated<A, B>(A, B);
+
+impl<A, B> Serialize for Duplicated<A, B>
+where
+    A: Serialize,
+    B: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(3))?;
+        map.serialize_entry(&self.0, &1u32)?;
+        map.serialize_entry("unique", &2u32)?;
+        map.serialize_key(&self.1)?;
+        map.serialize_value(&3u32)?;
+        map.end()
+    }
+}
+
+#[test]
+fn test_default_keeps_duplicates() {
+    let key = to_key(&Duplicated("a", "a")).unwrap();
+
+    match key {
+        Key::Map(entries) => assert_eq!(entries.len(), 3),
+        key => panic!("expected a map, got {:?}", key),
+    }
+}
+
+#[test]
+fn test_strict() {
+    let error = to_key_with_config::<RejectFloatPolicy, _>(&Duplicated("a", "a"), &strict());
+    assert_eq!(error, Err(Error::DuplicateKey(String::from("a"))));
+
+    let key = to_key_with_config::<RejectFloatPolicy, _>(&Duplicated("a", "b"), &strict());
+    assert_eq!(key, to_key(&Duplicated("a", "b")));
+}
+
+#[test]
+fn test_rendering() {
+    let error = to_key_with_config::<RejectFloatPolicy, _>(&Duplicated(42u32, 42u32), &strict());
+    assert_eq!(error, Err(Error::DuplicateKey(String::from("42"))));
+
+    // Integers of different widths are different keys.
+    let key = to_key_with_config::<RejectFloatPolicy, _>(&Duplicated(42u32, 42u64), &strict());
+    assert!(key.is_ok());
+
+    let error = to_key_with_config::<RejectFloatPolicy, _>(
+        &Duplicated((1u32, "x"), (1u32, "x")),
+        &strict(),
+    );
+    assert_eq!(error, Err(Error::DuplicateKey(String::from("[1, x]"))));
+
+    let error = to_key_with_config::<RejectFloatPolicy, _>(
+        &Duplicated(Some(vec![1u8, 2]), Some(vec![1u8, 2])),
+        &strict(),
+    );
+    assert_eq!(error, Err(Error::DuplicateKey(String::from("[1, 2]"))));
+
+    let error = to_key_with_config::<RejectFloatPolicy, _>(&Duplicated((), ()), &strict());
+    assert_eq!(error, Err(Error::DuplicateKey(String::from("()"))));
+
+    let error = to_key_with_config::<RejectFloatPolicy, _>(
+        &Duplicated(None::<u32>, None::<u32>),
+        &strict(),
+    );
+    assert_eq!(error, Err(Error::DuplicateKey(String::from("null"))));
+}
+
+#[test]
+fn test_broken_eq() {
+    /// A key whose `Eq` implementation never considers two keys equal, so a
+    /// `HashMap` happily stores it twice.
+    #[derive(Serialize)]
+    struct Broken(&'static str);
+
+    impl PartialEq for Broken {
+        fn eq(&self, _: &Self) -> bool {
+            false
+        }
+    }
+
+    impl Eq for Broken {}
+
+    impl Hash for Broken {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            self.0.hash(state);
+        }
+    }
+
+    let mut map = HashMap::new();
+    map.insert(Broken("a"), 1u32);
+    map.insert(Broken("a"), 2u32);
+    assert_eq!(map.len(), 2);
+
+    assert!(to_key(&map).is_ok());
+
+    let error = to_key_with_config::<RejectFloatPolicy, _>(&map, &strict());
+    assert_eq!(error, Err(Error::DuplicateKey(String::from("a"))));
+}
+
+#[test]
+fn test_nested() {
+    #[derive(Serialize)]
+    struct Outer {
+        inner: Vec<Duplicated<&'static str, &'static str>>,
+    }
+
+    let outer = Outer {
+        inner: vec![Duplicated("a", "b"), Duplicated("c", "c")],
+    };
+
+    let error = to_key_with_config::<RejectFloatPolicy, _>(&outer, &strict());
+    assert_eq!(error, Err(Error::DuplicateKey(String::from("c"))));
+}
+
+#[test]
+fn test_structs() {
+    struct Struct;
+
+    impl Serialize for Struct {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            let mut st = serializer.serialize_struct("Struct", 2)?;
+            st.serialize_field("x", &1u32)?;
+            st.serialize_field("x", &2u32)?;
+            st.end()
+        }
+    }
+
+    struct Variant;
+
+    impl Serialize for Variant {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            let mut variant = serializer.serialize_struct_variant("Enum", 0, "Variant", 2)?;
+            variant.serialize_field("y", &1u32)?;
+            variant.serialize_field("y", &2u32)?;
+            variant.end()
+        }
+    }
+
+    assert!(to_key(&Struct).is_ok());
+    assert!(to_key(&Variant).is_ok());
+
+    let error = to_key_with_config::<RejectFloatPolicy, _>(&Struct, &strict());
+    assert_eq!(error, Err(Error::DuplicateKey(String::from("x"))));
+
+    let error = to_key_with_config::<RejectFloatPolicy, _>(&Variant, &strict());
+    assert_eq!(error, Err(Error::DuplicateKey(String::from("y"))));
+
+    // Tagging with type names doesn't hide duplicates.
+    let config = strict().type_names(true);
+    let error = to_key_with_config::<RejectFloatPolicy, _>(&Struct, &config);
+    assert_eq!(error, Err(Error::DuplicateKey(String::from("x"))));
+}
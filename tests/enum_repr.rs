@@ -0,0 +1,178 @@
+use std::fmt;
+
+use serde::de::DeserializeOwned;
+use serde_derive::{Deserialize, Serialize};
+use serde_hashkey::{
+    from_key, from_key_with_config, key, to_key, to_key_with_config, EnumRepr, Error, Key,
+    KeyConfig, KeyPath, PathSegment, RejectFloatPolicy,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum Operation {
+    Stop,
+    Wait(u32),
+    Move(u32, u32),
+    Jump { height: u32 },
+}
+
+/// The same enum as [Operation], but using serde's own adjacently tagged
+/// representation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "t", content = "c")]
+enum Adjacent {
+    Stop,
+    Wait(u32),
+    Move(u32, u32),
+    Jump { height: u32 },
+}
+
+fn adjacent() -> KeyConfig {
+    KeyConfig::new().enum_repr(EnumRepr::adjacent())
+}
+
+fn roundtrip<T>(value: T, config: &KeyConfig) -> Key
+where
+    T: fmt::Debug + PartialEq + serde::Serialize + DeserializeOwned,
+{
+    let key = to_key_with_config::<RejectFloatPolicy, _>(&value, config).unwrap();
+    let value2 = from_key_with_config::<T, _>(&key, config).unwrap();
+    assert_eq!(value, value2);
+    key
+}
+
+#[test]
+fn test_default_config() {
+    for operation in [
+        Operation::Stop,
+        Operation::Wait(1),
+        Operation::Move(1, 2),
+        Operation::Jump { height: 3 },
+    ] {
+        let key = roundtrip(operation.clone(), &KeyConfig::new());
+        assert_eq!(key, to_key(&operation).unwrap());
+    }
+}
+
+#[test]
+fn test_adjacent_shapes() {
+    let config = adjacent();
+
+    assert_eq!(roundtrip(Operation::Stop, &config), key!({ "t": "Stop" }));
+
+    assert_eq!(
+        roundtrip(Operation::Wait(1), &config),
+        key!({ "t": "Wait", "c": 1u32 })
+    );
+
+    assert_eq!(
+        roundtrip(Operation::Move(1, 2), &config),
+        key!({ "t": "Move", "c": [1u32, 2u32] })
+    );
+
+    assert_eq!(
+        roundtrip(Operation::Jump { height: 3 }, &config),
+        key!({ "t": "Jump", "c": { "height": 3u32 } })
+    );
+}
+
+#[test]
+fn test_matches_serde_adjacent() {
+    let config = adjacent();
+
+    let pairs = [
+        (Operation::Stop, Adjacent::Stop),
+        (Operation::Wait(1), Adjacent::Wait(1)),
+        (Operation::Move(1, 2), Adjacent::Move(1, 2)),
+        (Operation::Jump { height: 3 }, Adjacent::Jump { height: 3 }),
+    ];
+
+    for (operation, expected) in pairs {
+        let key = roundtrip(operation, &config);
+        assert_eq!(key, to_key(&expected).unwrap());
+        assert_eq!(from_key::<Adjacent, _>(&key).unwrap(), expected);
+    }
+}
+
+#[test]
+fn test_distinct_from_external() {
+    let config = adjacent();
+
+    for operation in [
+        Operation::Stop,
+        Operation::Wait(1),
+        Operation::Move(1, 2),
+        Operation::Jump { height: 3 },
+    ] {
+        let external = to_key(&operation).unwrap();
+        let adjacent = to_key_with_config::<RejectFloatPolicy, _>(&operation, &config).unwrap();
+        assert_ne!(external, adjacent);
+
+        assert!(from_key::<Operation, _>(&adjacent).is_err());
+        assert!(from_key_with_config::<Operation, _>(&external, &config).is_err());
+    }
+
+    // An ordinary map with a single entry can't be confused with an enum.
+    let single = key!({ "Wait": 1u32 });
+    assert_eq!(single, to_key(&Operation::Wait(1)).unwrap());
+    assert!(from_key_with_config::<Operation, _>(&single, &config).is_err());
+}
+
+#[test]
+fn test_custom_names() {
+    let config = KeyConfig::new().enum_repr(EnumRepr::Adjacent {
+        tag: "type",
+        content: "value",
+    });
+
+    assert_eq!(
+        roundtrip(Operation::Wait(1), &config),
+        key!({ "type": "Wait", "value": 1u32 })
+    );
+
+    let key = to_key_with_config::<RejectFloatPolicy, _>(&Operation::Wait(1), &adjacent()).unwrap();
+    assert!(from_key_with_config::<Operation, _>(&key, &config).is_err());
+}
+
+#[test]
+fn test_nested() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Plan {
+        steps: Vec<Operation>,
+        last: Option<Operation>,
+    }
+
+    let plan = Plan {
+        steps: vec![Operation::Stop, Operation::Move(1, 2)],
+        last: Some(Operation::Jump { height: 3 }),
+    };
+
+    let key = roundtrip(plan, &adjacent());
+
+    let steps = key.get(&key!("steps")).unwrap();
+    assert_eq!(steps.get_index(0), Some(&key!({ "t": "Stop" })));
+}
+
+#[test]
+fn test_float_path() {
+    #[derive(Serialize)]
+    enum Shape {
+        Circle { radius: f32 },
+    }
+
+    let error =
+        to_key_with_config::<RejectFloatPolicy, _>(&Shape::Circle { radius: 1.5 }, &adjacent())
+            .unwrap_err();
+
+    match error {
+        Error::FloatRejected { path, .. } => {
+            assert_eq!(
+                path,
+                KeyPath::from(vec![
+                    PathSegment::Field("c".into()),
+                    PathSegment::Field("radius".into()),
+                ])
+            );
+        }
+        error => panic!("expected a rejected float, got {:?}", error),
+    }
+}
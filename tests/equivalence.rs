@@ -0,0 +1,172 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use serde::ser::{Serialize, Serializer};
+use serde_derive::Serialize;
+use serde_hashkey::{
+    to_key_filtered, to_key_with, to_key_with_partial, Error, FieldFilter, FloatPolicy,
+    RejectFloatPolicy, TotalOrderFloatPolicy,
+};
+
+struct Bytes(&'static [u8]);
+
+impl Serialize for Bytes {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+#[derive(Serialize)]
+struct Unit;
+
+#[derive(Serialize)]
+struct Newtype(u32);
+
+#[derive(Serialize)]
+struct Tuple(u32, &'static str);
+
+#[derive(Serialize)]
+struct Struct {
+    a: u32,
+    b: Option<Option<u32>>,
+    c: Vec<Newtype>,
+}
+
+#[derive(Serialize)]
+enum Enum {
+    Unit,
+    Newtype(u32),
+    Tuple(u32, Vec<u8>),
+    Struct { a: u32, b: Tuple },
+}
+
+#[derive(Serialize, PartialEq, Eq, PartialOrd, Ord)]
+struct Point {
+    x: u32,
+    y: u32,
+}
+
+/// Assert that every way of building a key which doesn't change it agrees
+/// with [to_key_with], for the given float policy.
+fn check_with<F, T>(value: &T)
+where
+    F: FloatPolicy + fmt::Debug + PartialEq,
+    T: ?Sized + Serialize,
+{
+    let expected = to_key_with::<F, _>(value);
+    let filtered = to_key_filtered::<_, F>(value, &FieldFilter::new());
+    let partial = to_key_with_partial::<_, F>(value).map_err(Error::from);
+
+    assert_eq!(filtered, expected);
+
+    match (partial, expected) {
+        (Ok(partial), Ok(expected)) => assert_eq!(partial, expected),
+        (Err(partial), Err(Error::FloatRejected { error, .. })) => assert_eq!(partial, *error),
+        (partial, expected) => assert_eq!(partial, expected),
+    }
+}
+
+fn check<T>(value: &T)
+where
+    T: ?Sized + Serialize,
+{
+    check_with::<RejectFloatPolicy, T>(value);
+    check_with::<TotalOrderFloatPolicy, T>(value);
+}
+
+#[test]
+fn test_scalars() {
+    check(&());
+    check(&true);
+    check(&42u8);
+    check(&-42i64);
+    check(&u128::MAX);
+    check(&i128::MIN);
+    check(&1.5f32);
+    check(&-2.5f64);
+    check(&'a');
+    check("hello");
+    check(&Bytes(b"bytes"));
+    check(&Unit);
+    check(&Newtype(1));
+}
+
+#[test]
+fn test_options() {
+    check(&None::<u32>);
+    check(&Some(1u32));
+    check(&Some(None::<u32>));
+    check(&Some(Some(None::<u32>)));
+    check(&vec![Some(1u32), None]);
+}
+
+#[test]
+fn test_sequences() {
+    check(&Vec::<u32>::new());
+    check(&vec![1u32, 2, 3]);
+    check(&vec![1.0f32, 2.0]);
+    check(&vec![vec!["a"], vec![]]);
+    check(&(1u32, "a", ()));
+    check(&Tuple(1, "a"));
+    check(&vec![(1u32, 2.5f64)]);
+}
+
+#[test]
+fn test_maps() {
+    let mut strings = BTreeMap::new();
+    strings.insert("a", 1u32);
+    strings.insert("b", 2u32);
+    check(&strings);
+
+    let mut points = BTreeMap::new();
+    points.insert(Point { x: 1, y: 2 }, vec![1u32]);
+    points.insert(Point { x: 3, y: 4 }, vec![]);
+    check(&points);
+
+    let mut floats = BTreeMap::new();
+    floats.insert(1u32, 1.5f32);
+    check(&floats);
+
+    check(&vec![1u32, 2].into_iter().collect::<BTreeSet<_>>());
+}
+
+#[test]
+fn test_structs() {
+    check(&Struct {
+        a: 1,
+        b: Some(None),
+        c: vec![Newtype(2), Newtype(3)],
+    });
+
+    check(&Point { x: 1, y: 2 });
+}
+
+#[test]
+fn test_enums() {
+    check(&Enum::Unit);
+    check(&Enum::Newtype(1));
+    check(&Enum::Tuple(1, vec![2, 3]));
+    check(&Enum::Struct {
+        a: 1,
+        b: Tuple(2, "b"),
+    });
+    check(&vec![Enum::Unit, Enum::Newtype(1)]);
+}
+
+#[test]
+fn test_floats() {
+    #[derive(Serialize)]
+    enum Shape {
+        Circle(f32),
+        Rect(u32, f64),
+        Point { x: u32, y: f32 },
+    }
+
+    check(&Shape::Circle(1.0));
+    check(&Shape::Rect(1, 2.0));
+    check(&Shape::Point { x: 1, y: 2.0 });
+    check(&vec![Some(vec![1.0f32])]);
+}
@@ -0,0 +1,281 @@
+use std::time::Instant;
+
+use serde::de;
+use serde_derive::{Deserialize, Serialize};
+use serde_hashkey::{from_key, from_key_owned, key, to_key, Error, Key};
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Point {
+    x: u32,
+    y: u32,
+    z: Option<u32>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Strict {
+    a: u32,
+    b: u32,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+enum Shape {
+    Point { x: u32, y: u32 },
+}
+
+#[test]
+fn test_field_order() -> Result<(), Error> {
+    let expected = Point {
+        x: 1,
+        y: 2,
+        z: Some(3),
+    };
+
+    assert_eq!(
+        from_key::<Point, _>(&key!({ "x": 1u32, "y": 2u32, "z": 3u32 }))?,
+        expected
+    );
+    assert_eq!(
+        from_key::<Point, _>(&key!({ "z": 3u32, "y": 2u32, "x": 1u32 }))?,
+        expected
+    );
+    assert_eq!(
+        from_key::<Point, _>(&key!({ "w": 0u32, "y": 2u32, "x": 1u32, "z": 3u32 }))?,
+        expected
+    );
+
+    let key = key!({ "y": 2u32, "x": 1u32 });
+    assert_eq!(
+        from_key::<Point, _>(&key)?,
+        Point {
+            x: 1,
+            y: 2,
+            z: None
+        }
+    );
+
+    let key = key!({ "Point": { "y": 2u32, "x": 1u32 } });
+    assert_eq!(from_key::<Shape, _>(&key)?, Shape::Point { x: 1, y: 2 });
+    Ok(())
+}
+
+#[test]
+fn test_non_string_keys() -> Result<(), Error> {
+    let key = key!({ 7u32: 0u32, "y": 2u32, "x": 1u32 });
+
+    assert_eq!(
+        from_key::<Point, _>(&key)?,
+        Point {
+            x: 1,
+            y: 2,
+            z: None
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn test_field_errors() {
+    let error = from_key::<Strict, _>(&key!({ "b": 2u32, "c": 3u32, "a": 1u32 })).unwrap_err();
+    assert_eq!(error.to_string(), "unknown field `c`, expected `a` or `b`");
+
+    let error = from_key::<Point, _>(&key!({ "y": 2u32, "x": 1u32, "y": 3u32 })).unwrap_err();
+    assert_eq!(error.to_string(), "duplicate field `y`");
+
+    let error = from_key::<Point, _>(&key!({ "z": 3u32, "y": 2u32 })).unwrap_err();
+    assert_eq!(error.to_string(), "missing field `x`");
+
+    let error = from_key_owned::<Strict, _>(key!({ "b": 2u32, "c": 3u32, "a": 1u32 })).unwrap_err();
+    assert_eq!(error.to_string(), "unknown field `c`, expected `a` or `b`");
+
+    let error = from_key_owned::<Point, _>(key!({ "z": 3u32, "y": 2u32 })).unwrap_err();
+    assert_eq!(error.to_string(), "missing field `x`");
+}
+
+#[test]
+fn test_normalized() -> Result<(), Error> {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct Book {
+        title: String,
+        author: String,
+        year: u32,
+        chapters: Vec<Chapter>,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Chapter {
+        pages: u32,
+        name: String,
+    }
+
+    let book = Book {
+        title: String::from("Birds of a feather"),
+        author: String::from("Noah"),
+        year: 1999,
+        chapters: vec![Chapter {
+            pages: 10,
+            name: String::from("Beginnings"),
+        }],
+    };
+
+    let key = to_key(&book)?.normalize();
+    assert!(matches!(&key, Key::Map(entries) if entries[0].0 == key!("author")));
+    assert_eq!(from_key::<Book, _>(&key)?, book);
+    assert_eq!(from_key_owned::<Book, _>(key)?, book);
+    Ok(())
+}
+
+#[test]
+fn test_ordered_visitor() -> Result<(), Error> {
+    /// A struct which can only be deserialized from fields in declared order.
+    #[derive(Debug, PartialEq)]
+    struct Ordered {
+        b: u32,
+        a: u32,
+    }
+
+    impl<'de> de::Deserialize<'de> for Ordered {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: de::Deserializer<'de>,
+        {
+            struct Visitor;
+
+            impl<'de> de::Visitor<'de> for Visitor {
+                type Value = Ordered;
+
+                fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    f.write_str("struct Ordered")
+                }
+
+                fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+                where
+                    A: de::MapAccess<'de>,
+                {
+                    let mut field = |name: &str| match map.next_entry::<String, u32>()? {
+                        Some((key, value)) if key == name => Ok(value),
+                        _ => Err(de::Error::missing_field("field in order")),
+                    };
+
+                    let b = field("b")?;
+                    let a = field("a")?;
+                    Ok(Ordered { b, a })
+                }
+            }
+
+            deserializer.deserialize_struct("Ordered", &["b", "a"], Visitor)
+        }
+    }
+
+    let expected = Ordered { b: 2, a: 1 };
+    assert_eq!(
+        from_key::<Ordered, _>(&key!({ "b": 2u32, "a": 1u32 }))?,
+        expected
+    );
+    assert_eq!(
+        from_key::<Ordered, _>(&key!({ "a": 1u32, "b": 2u32 }))?,
+        expected
+    );
+    // Entries which aren't fields are served last.
+    let key = key!({ "c": 3u32, "a": 1u32, "b": 2u32 });
+    assert_eq!(from_key::<Ordered, _>(&key)?, expected);
+    assert_eq!(from_key_owned::<Ordered, _>(key)?, expected);
+    Ok(())
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Wide {
+    p: u32,
+    o: u32,
+    n: u32,
+    m: u32,
+    l: u32,
+    k: u32,
+    j: u32,
+    i: u32,
+    h: u32,
+    g: u32,
+    f: u32,
+    e: u32,
+}
+
+fn wide() -> Wide {
+    Wide {
+        p: 1,
+        o: 2,
+        n: 3,
+        m: 4,
+        l: 5,
+        k: 6,
+        j: 7,
+        i: 8,
+        h: 9,
+        g: 10,
+        f: 11,
+        e: 12,
+    }
+}
+
+#[test]
+fn test_wide() -> Result<(), Error> {
+    let key = to_key(&wide())?;
+    let normalized = key.clone().normalize();
+    assert_ne!(key, normalized);
+
+    assert_eq!(from_key::<Wide, _>(&key)?, wide());
+    assert_eq!(from_key::<Wide, _>(&normalized)?, wide());
+    assert_eq!(from_key_owned::<Wide, _>(normalized)?, wide());
+    Ok(())
+}
+
+/// Compares deserializing a wide struct from a key with its fields in the order
+/// in which they are declared, and from a normalized key which has them in
+/// reverse order and has to be put in field order first. Run with `cargo test
+/// --release --test fields -- --ignored --nocapture` to see the timings.
+#[test]
+#[ignore]
+fn bench_field_order() -> Result<(), Error> {
+    let key = to_key(&wide())?;
+    let normalized = key.clone().normalize();
+
+    for (name, key) in [("declared", &key), ("normalized", &normalized)] {
+        let start = Instant::now();
+
+        for _ in 0..100_000 {
+            from_key::<Wide, _>(key)?;
+        }
+
+        println!("{}: {:?}", name, start.elapsed());
+    }
+
+    Ok(())
+}
+
+/// Compares deserializing a struct with and without a large unknown field,
+/// which is skipped without being visited. Run with `cargo test --release
+/// --test fields -- --ignored --nocapture` to see the timings.
+#[test]
+#[ignore]
+fn bench_unknown_field() -> Result<(), Error> {
+    let small = key!({ "x": 1u32, "y": 2u32 });
+
+    let large = key!({
+        "x": 1u32,
+        "unknown": Key::Seq((0..1000u32).map(|n| key!({ "n": n })).collect()),
+        "y": 2u32,
+    });
+
+    for (name, key) in [("small", &small), ("large", &large)] {
+        let start = Instant::now();
+
+        for _ in 0..100_000 {
+            from_key::<Point, _>(key)?;
+        }
+
+        println!("{}: {:?}", name, start.elapsed());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,292 @@
+use std::collections::BTreeMap;
+
+use serde::ser;
+use serde_derive::Serialize;
+use serde_hashkey::{key, to_key, to_key_filtered, Error, FieldFilter, Key, RejectFloatPolicy};
+
+#[derive(Clone, Serialize)]
+struct Request {
+    path: &'static str,
+    trace_id: &'static str,
+    author: Author,
+    books: Vec<Book>,
+    headers: BTreeMap<&'static str, &'static str>,
+}
+
+#[derive(Clone, Serialize)]
+struct Author {
+    name: &'static str,
+    updated_at: u64,
+}
+
+#[derive(Clone, Serialize)]
+enum Book {
+    Paper { name: &'static str, created_at: u64 },
+    Digital(&'static str, u64),
+    Audio(Author),
+}
+
+fn request(trace_id: &'static str, at: u64) -> Request {
+    Request {
+        path: "/books",
+        trace_id,
+        author: Author {
+            name: "Noah",
+            updated_at: at,
+        },
+        books: vec![
+            Book::Paper {
+                name: "Birds of a feather",
+                created_at: at,
+            },
+            Book::Digital("Fish of a fin", 7),
+            Book::Audio(Author {
+                name: "Jane",
+                updated_at: at,
+            }),
+        ],
+        headers: vec![("x-trace-id", trace_id), ("accept", "text/plain")]
+            .into_iter()
+            .collect(),
+    }
+}
+
+/// A value which errors if it is ever serialized.
+struct Poison;
+
+impl ser::Serialize for Poison {
+    fn serialize<S>(&self, _: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        Err(ser::Error::custom("poison was serialized"))
+    }
+}
+
+/// Assert that filtering while serializing produces the same key as stripping
+/// afterwards.
+fn check(value: &Request, filter: &FieldFilter) -> Result<Key, Error> {
+    let filtered: Key = to_key_filtered(value, filter)?;
+
+    let mut stripped = to_key(value)?;
+    stripped.strip(filter);
+
+    assert_eq!(filtered, stripped);
+    Ok(filtered)
+}
+
+#[test]
+fn test_exclude_everywhere() -> Result<(), Error> {
+    let filter = FieldFilter::new()
+        .exclude("trace_id")
+        .exclude("*_at")
+        .exclude("x-trace-id");
+
+    let a = check(&request("a", 1), &filter)?;
+    let b = check(&request("b", 2), &filter)?;
+    assert_eq!(a, b);
+
+    assert_ne!(to_key(&request("a", 1))?, to_key(&request("b", 2))?);
+    Ok(())
+}
+
+#[test]
+fn test_exclude_qualified() -> Result<(), Error> {
+    let filter = FieldFilter::new()
+        .exclude("author.updated_at")
+        .exclude("books.Paper.created_at");
+
+    let a = check(&request("a", 1), &filter)?;
+
+    let mut other = request("a", 1);
+    other.author.updated_at = 2;
+
+    if let Book::Paper { created_at, .. } = &mut other.books[0] {
+        *created_at = 2;
+    }
+
+    assert_eq!(a, check(&other, &filter)?);
+
+    // The nested author of the audio book is not affected.
+    if let Book::Audio(author) = &mut other.books[2] {
+        author.updated_at = 2;
+    }
+
+    assert_ne!(a, check(&other, &filter)?);
+    Ok(())
+}
+
+#[test]
+fn test_exclude_variants() -> Result<(), Error> {
+    let filter = FieldFilter::new().exclude("books.Digital").exclude("Audio");
+    let key = check(&request("a", 1), &filter)?;
+
+    let expected: Key = to_key(&vec![
+        to_key(&Book::Paper {
+            name: "Birds of a feather",
+            created_at: 1,
+        })?,
+        key!({}),
+        key!({}),
+    ])?;
+
+    assert_eq!(
+        key.get_path(&vec![serde_hashkey::PathSegment::Field("books".into())].into()),
+        Some(&expected)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_globs() {
+    let filter = FieldFilter::new()
+        .exclude("a?c")
+        .exclude("root.**.leaf")
+        .exclude("x*y*z");
+
+    assert!(filter.is_excluded(&["abc"]));
+    assert!(filter.is_excluded(&["nested", "axc"]));
+    assert!(!filter.is_excluded(&["abbc"]));
+
+    assert!(filter.is_excluded(&["root", "leaf"]));
+    assert!(filter.is_excluded(&["root", "a", "b", "leaf"]));
+    assert!(!filter.is_excluded(&["other", "leaf"]));
+
+    assert!(filter.is_excluded(&["xyz"]));
+    assert!(filter.is_excluded(&["x_y_y_z"]));
+    assert!(!filter.is_excluded(&["x_y_y_"]));
+
+    assert!(!FieldFilter::new().exclude("").is_excluded(&[""]));
+    assert!(!filter.is_excluded::<&str>(&[]));
+}
+
+#[test]
+fn test_excluded_values_are_not_serialized() -> Result<(), Error> {
+    #[derive(Serialize)]
+    struct Value {
+        id: u32,
+        poison: Poison,
+        timestamp: f64,
+        nested: BTreeMap<&'static str, Poison>,
+    }
+
+    let value = Value {
+        id: 1,
+        poison: Poison,
+        timestamp: 1.5,
+        nested: vec![("poison", Poison)].into_iter().collect(),
+    };
+
+    assert!(to_key(&value).is_err());
+
+    let filter = FieldFilter::new().exclude("poison").exclude("timestamp");
+    let key: Key = to_key_filtered(&value, &filter)?;
+
+    #[derive(Serialize)]
+    struct Expected {
+        id: u32,
+        nested: BTreeMap<&'static str, ()>,
+    }
+
+    assert_eq!(
+        key,
+        to_key(&Expected {
+            id: 1,
+            nested: BTreeMap::new(),
+        })?
+    );
+    Ok(())
+}
+
+#[test]
+fn test_map_keys_are_not_filtered() -> Result<(), Error> {
+    #[derive(PartialEq, Eq, PartialOrd, Ord, Serialize)]
+    struct Id {
+        trace_id: u32,
+        name: &'static str,
+    }
+
+    let mut value = BTreeMap::new();
+    value.insert(
+        Id {
+            trace_id: 1,
+            name: "a",
+        },
+        "trace_id",
+    );
+
+    let filter = FieldFilter::new().exclude("trace_id").exclude("name");
+    let key: Key = to_key_filtered(&value, &filter)?;
+
+    let mut stripped = to_key(&value)?;
+    stripped.strip(&filter);
+
+    assert_eq!(key, stripped);
+    assert_eq!(key, to_key(&value)?);
+    Ok(())
+}
+
+#[test]
+fn test_float_paths() {
+    #[derive(Serialize)]
+    enum Shape {
+        Tuple(u32, f32),
+        Struct { radius: f32 },
+        Newtype(Vec<f32>),
+    }
+
+    #[derive(Serialize)]
+    struct Value {
+        x: Vec<f32>,
+        shapes: Vec<Shape>,
+        scores: BTreeMap<u32, f32>,
+    }
+
+    fn check(value: &Value, expected: &str) {
+        let error = to_key(value).unwrap_err();
+        assert!(matches!(error, Error::FloatRejected { .. }));
+        assert_eq!(error.to_string(), expected);
+
+        let filtered = to_key_filtered::<_, RejectFloatPolicy>(value, &FieldFilter::new());
+        assert_eq!(filtered, Err(error));
+    }
+
+    let value = Value {
+        x: vec![1.5],
+        shapes: Vec::new(),
+        scores: BTreeMap::new(),
+    };
+    check(&value, "unsupported float f32 = 1.5 at x[0]");
+
+    let value = Value {
+        x: Vec::new(),
+        shapes: vec![Shape::Tuple(1, 2.5)],
+        ..value
+    };
+    check(&value, "unsupported float f32 = 2.5 at shapes[0].Tuple[1]");
+
+    let value = Value {
+        shapes: vec![Shape::Newtype(Vec::new()), Shape::Struct { radius: 0.5 }],
+        ..value
+    };
+    check(
+        &value,
+        "unsupported float f32 = 0.5 at shapes[1].Struct.radius",
+    );
+
+    let value = Value {
+        shapes: vec![Shape::Newtype(vec![0.25])],
+        ..value
+    };
+    check(
+        &value,
+        "unsupported float f32 = 0.25 at shapes[0].Newtype[0]",
+    );
+
+    let value = Value {
+        shapes: Vec::new(),
+        scores: vec![(7, 0.5)].into_iter().collect(),
+        ..value
+    };
+    check(&value, "unsupported float f32 = 0.5 at scores{0}");
+}
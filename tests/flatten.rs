@@ -0,0 +1,114 @@
+use serde_derive::Serialize;
+use serde_hashkey::{key, to_key, Error};
+
+#[derive(Serialize)]
+struct Author {
+    name: &'static str,
+    age: u32,
+    tags: Vec<&'static str>,
+}
+
+#[derive(Serialize)]
+struct Meta {
+    empty: Empty,
+    nested: Nested,
+}
+
+#[derive(Serialize)]
+struct Empty {}
+
+#[derive(Serialize)]
+struct Nested {
+    deeper: Author,
+}
+
+#[derive(Serialize)]
+struct Book {
+    title: &'static str,
+    author: Author,
+    meta: Meta,
+}
+
+fn author(name: &'static str) -> Author {
+    Author {
+        name,
+        age: 42,
+        tags: vec!["a", "b"],
+    }
+}
+
+#[test]
+fn test_roundtrip() -> Result<(), Error> {
+    let key = to_key(&Book {
+        title: "Birds of a feather",
+        author: author("Noah"),
+        meta: Meta {
+            empty: Empty {},
+            nested: Nested {
+                deeper: author("Jane"),
+            },
+        },
+    })?;
+
+    let flat = key.clone().flatten(".")?;
+
+    let expected = key!({
+        "title": "Birds of a feather",
+        "author.name": "Noah",
+        "author.age": 42u32,
+        "author.tags": ["a", "b"],
+        "meta.empty": {},
+        "meta.nested.deeper.name": "Jane",
+        "meta.nested.deeper.age": 42u32,
+        "meta.nested.deeper.tags": ["a", "b"],
+    });
+
+    assert_eq!(flat, expected);
+    assert_eq!(flat.clone().unflatten(".")?, key);
+    assert_eq!(key.clone().flatten("::")?.unflatten("::")?, key);
+    Ok(())
+}
+
+#[test]
+fn test_non_maps() -> Result<(), Error> {
+    assert_eq!(key!("a.b").flatten(".")?, key!("a.b"));
+    assert_eq!(key!("a.b").unflatten(".")?, key!("a.b"));
+    Ok(())
+}
+
+#[test]
+fn test_errors() {
+    let key = key!({ "a": { 1u32: "b" } });
+    assert_eq!(key.flatten("."), Err(Error::Unexpected("string map key")));
+
+    let key = key!({ "a": { "b.c": "d" } });
+    assert_eq!(
+        key.flatten("."),
+        Err(Error::SeparatorInKey(String::from("b.c")))
+    );
+
+    let key = key!({ "a": "b" });
+    assert_eq!(key.clone().flatten(""), Err(Error::EmptySeparator));
+    assert_eq!(key.unflatten(""), Err(Error::EmptySeparator));
+
+    let key = key!({ "a": "b", "a.b": "c" });
+    assert_eq!(
+        key.unflatten("."),
+        Err(Error::ConflictingPath(String::from("a.b")))
+    );
+
+    let key = key!({ "a.b": "c", "a": "b" });
+    assert_eq!(
+        key.unflatten("."),
+        Err(Error::ConflictingPath(String::from("a")))
+    );
+
+    let key = key!({ "a": "b", "a": "c" });
+    assert_eq!(
+        key.unflatten("."),
+        Err(Error::ConflictingPath(String::from("a")))
+    );
+
+    let key = key!({ 1u32: "b" });
+    assert_eq!(key.unflatten("."), Err(Error::Unexpected("string map key")));
+}
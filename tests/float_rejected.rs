@@ -0,0 +1,145 @@
+use std::collections::BTreeMap;
+
+use serde::ser::SerializeMap as _;
+use serde_derive::Serialize;
+use serde_hashkey::{
+    to_key, to_key_rejecting_non_finite, Error, FloatWidth, KeyPath, PathSegment,
+    RejectFloatPolicy, SerializeMap,
+};
+
+fn rejected(error: Error) -> (KeyPath, Error) {
+    match error {
+        Error::FloatRejected { path, error } => (path, *error),
+        error => panic!("expected a rejected float, got {:?}", error),
+    }
+}
+
+#[test]
+fn test_nested_path() {
+    #[derive(Serialize)]
+    struct Author {
+        name: &'static str,
+        scores: Vec<f32>,
+    }
+
+    #[derive(Serialize)]
+    struct Book {
+        title: &'static str,
+        author: Author,
+    }
+
+    let book = Book {
+        title: "Birds of a feather",
+        author: Author {
+            name: "Noah",
+            scores: vec![1.0, 2.0, 3.0, 4.5],
+        },
+    };
+
+    let error = to_key(&book).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "unsupported float f32 = 1 at author.scores[0]"
+    );
+
+    let (path, error) = rejected(error);
+
+    assert_eq!(
+        path.segments(),
+        &[
+            PathSegment::Field("author".into()),
+            PathSegment::Field("scores".into()),
+            PathSegment::Index(0),
+        ]
+    );
+
+    assert_eq!(
+        error,
+        Error::UnsupportedFloat {
+            width: FloatWidth::F32,
+            value: 1.0
+        }
+    );
+}
+
+#[test]
+fn test_first_rejected_float() {
+    #[derive(Serialize)]
+    struct Author {
+        scores: Vec<f64>,
+    }
+
+    #[derive(Serialize)]
+    struct Book {
+        author: Author,
+    }
+
+    let book = Book {
+        author: Author {
+            scores: vec![1.0, 2.0, 3.0, f64::NAN],
+        },
+    };
+
+    let (path, error) = rejected(to_key_rejecting_non_finite(&book).unwrap_err());
+    assert_eq!(path.to_string(), "author.scores[3]");
+    assert!(matches!(
+        error,
+        Error::NonFiniteFloat {
+            width: FloatWidth::F64,
+            value,
+        } if value.is_nan()
+    ));
+}
+
+#[test]
+fn test_root_float() {
+    assert_eq!(
+        to_key(&1.5f64),
+        Err(Error::UnsupportedFloat {
+            width: FloatWidth::F64,
+            value: 1.5
+        })
+    );
+}
+
+#[test]
+fn test_maps() {
+    let mut map = BTreeMap::new();
+    map.insert("rating", 4.5f32);
+    let (path, _) = rejected(to_key(&map).unwrap_err());
+    assert_eq!(path.to_string(), "rating");
+
+    // Entries without a string key are referred to by their position.
+    let mut map = BTreeMap::new();
+    map.insert(1u32, 1u32);
+    map.insert(2u32, 2u32);
+    let mut outer = BTreeMap::new();
+    outer.insert(7u32, vec![(map, 0.5f64)]);
+    let (path, _) = rejected(to_key(&outer).unwrap_err());
+    assert_eq!(path.to_string(), "{0}[0][1]");
+
+    // Floats in map keys are reported at the entry of the key.
+    let mut map = SerializeMap::<RejectFloatPolicy>::new();
+    map.serialize_entry("a", &1u32).unwrap();
+    let (path, _) = rejected(map.serialize_key(&(1u32, 2.5f32)).unwrap_err());
+    assert_eq!(path.to_string(), "{1}[1]");
+}
+
+#[test]
+fn test_variants() {
+    #[derive(Serialize)]
+    enum Shape {
+        Circle(f32),
+        Rectangle(u32, f32),
+        Square { side: f64 },
+    }
+
+    let (path, _) = rejected(to_key(&Shape::Circle(1.5)).unwrap_err());
+    assert_eq!(path.to_string(), "Circle");
+
+    let (path, _) = rejected(to_key(&Shape::Rectangle(1, 1.5)).unwrap_err());
+    assert_eq!(path.to_string(), "Rectangle[1]");
+
+    let (path, _) = rejected(to_key(&vec![Shape::Square { side: 1.5 }]).unwrap_err());
+    assert_eq!(path.to_string(), "[0].Square.side");
+}
@@ -0,0 +1,112 @@
+use std::borrow::Cow;
+
+use serde_hashkey::{key, to_key, Error, Key, RejectFloatPolicy};
+
+#[test]
+fn test_agrees_with_serialized() -> Result<(), Error> {
+    assert_eq!(Key::from("x"), to_key(&"x")?);
+    assert_eq!(Key::from(String::from("x")), to_key(&String::from("x"))?);
+    assert_eq!(Key::from(Cow::Borrowed("x")), to_key(&"x")?);
+    assert_eq!(
+        Key::from(Cow::<str>::Owned(String::from("x"))),
+        to_key(&"x")?
+    );
+    assert_eq!(Key::from('x'), to_key(&'x')?);
+    assert_eq!(Key::from(true), to_key(&true)?);
+    assert_eq!(Key::from(42u16), to_key(&42u16)?);
+    assert_eq!(Key::from(Some("x")), to_key(&Some("x"))?);
+    assert_eq!(Key::from(None::<&str>), to_key(&None::<&str>)?);
+    assert_eq!(Key::from(Some(Some(1u8))), to_key(&Some(Some(1u8)))?);
+    assert_eq!(Key::from(Some(None::<u8>)), to_key(&Some(None::<u8>))?);
+    assert_eq!(
+        Key::from(Some(Some(None::<u8>))),
+        to_key(&Some(Some(None::<u8>)))?
+    );
+    Ok(())
+}
+
+#[test]
+fn test_seq() -> Result<(), Error> {
+    let ints = Key::from(vec![Key::from(1u32), Key::from(2u32)]);
+    assert!(matches!(ints, Key::IntegerSeq(..)));
+    assert_eq!(ints, to_key(&vec![1u32, 2])?);
+
+    let mixed = Key::from(vec![Key::from(1u32), Key::from(2u8)]);
+    assert!(matches!(mixed, Key::Seq(..)));
+    assert_eq!(mixed, to_key(&(1u32, 2u8))?);
+
+    assert_eq!(Key::from(Vec::<Key>::new()), to_key(&Vec::<u32>::new())?);
+    assert_eq!(Key::from(vec![key!("a")]), to_key(&vec!["a"])?);
+    Ok(())
+}
+
+#[test]
+fn test_bytes() {
+    let expected: Key = Key::from(b"abc".to_vec());
+    let bytes: &[u8] = b"abc";
+    assert_eq!(Key::from(bytes), expected);
+    assert_eq!(Key::from(*b"abc"), expected);
+    assert_eq!(Key::from(b"abc"), expected);
+    assert!(Key::<RejectFloatPolicy>::from([1u8, 2]).is_bytes());
+}
+
+#[test]
+fn test_option() {
+    assert_eq!(Key::from(None::<u32>), key!(null));
+    assert_eq!(Key::from(Some(42u32)), key!(42u32));
+    assert_eq!(Key::from(Some(key!([1u32]))), key!([1u32]));
+    assert_eq!(Key::from(Some(None::<u32>)), key!([null]));
+    assert_eq!(Key::from(Some(Some(None::<u32>))), key!([[null]]));
+    assert_eq!(Key::from(Some(key!(null))), key!([null]));
+    assert_eq!(key!({ "a": (Some(None::<u32>)) }), key!({ "a": [null] }));
+}
+
+#[test]
+fn test_float_constructors() -> Result<(), Error> {
+    use serde_hashkey::{
+        to_key_with_canonical_float, to_key_with_widened_float, CanonicalFloatPolicy, Float,
+        FloatWidth, WidenF32Policy,
+    };
+
+    let a = Key::<CanonicalFloatPolicy>::from_f32(-0.0)?;
+    assert_eq!(a, to_key_with_canonical_float(&-0.0f32)?);
+    assert_eq!(a, Key::Float(Float::f32(0.0)?));
+
+    let b = Key::<CanonicalFloatPolicy>::from_f64(f64::NAN)?;
+    assert_eq!(b, to_key_with_canonical_float(&-f64::NAN)?);
+    assert_eq!(b, Key::Float(Float::f64(f64::NAN)?));
+
+    // Policies which widen floats do so when constructing them too.
+    let c = Key::<WidenF32Policy>::from_f32(2.5)?;
+    assert_eq!(c, to_key_with_widened_float(&2.5f32)?);
+    assert_eq!(c, Key::<WidenF32Policy>::from_f64(2.5)?);
+
+    // Rejected floats produce the same errors as serializing them.
+    assert_eq!(
+        Key::<RejectFloatPolicy>::from_f32(1.0),
+        Err(Error::UnsupportedFloat {
+            width: FloatWidth::F32,
+            value: 1.0
+        })
+    );
+    assert_eq!(Key::<RejectFloatPolicy>::from_f32(1.0), to_key(&1.0f32));
+    assert_eq!(Key::<RejectFloatPolicy>::from_f64(1.0), to_key(&1.0f64));
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "ordered-float")]
+fn test_ordered_float() -> Result<(), Error> {
+    use serde_hashkey::{to_key_with_ordered_float, Float, OrderedFloat, OrderedFloatPolicy};
+
+    let a: Key<OrderedFloatPolicy> = Key::from(1.25f32);
+    assert_eq!(a, to_key_with_ordered_float(&1.25f32)?);
+    assert_eq!(a, Key::from_f32(1.25)?);
+    assert_eq!(a, Key::Float(Float::F32(OrderedFloat(1.25))));
+
+    let b: Key<OrderedFloatPolicy> = Key::from(f64::NAN);
+    assert_eq!(b, to_key_with_ordered_float(&f64::NAN)?);
+    assert_eq!(b, Key::from_f64(f64::NAN)?);
+    assert_ne!(a, b);
+    Ok(())
+}
@@ -0,0 +1,228 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use serde::de::{self, DeserializeOwned};
+use serde::{Deserializer, Serializer};
+use serde_derive::{Deserialize, Serialize};
+use serde_hashkey::{
+    from_key, from_key_owned, to_key, to_key_with, IntegerSeq, Key, TotalOrderFloatPolicy,
+};
+
+/// Bytes which are serialized as bytes and deserialized from an owned buffer.
+#[derive(Debug, PartialEq)]
+struct ByteBuf(Vec<u8>);
+
+impl serde::Serialize for ByteBuf {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bytes(&self.0)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for ByteBuf {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct Visitor;
+
+        impl<'de> de::Visitor<'de> for Visitor {
+            type Value = ByteBuf;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("bytes")
+            }
+
+            fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E> {
+                Ok(ByteBuf(v.to_vec()))
+            }
+
+            fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+                Ok(ByteBuf(v))
+            }
+        }
+
+        deserializer.deserialize_byte_buf(Visitor)
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Author {
+    name: String,
+    age: u32,
+    books: Vec<String>,
+    nick: Option<String>,
+    ratings: BTreeMap<String, u8>,
+    avatar: ByteBuf,
+    raw: Vec<u8>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Shape {
+    Empty,
+    Square(u32),
+    Rect(u32, u32),
+    Circle { radius: u32 },
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+enum Internal {
+    A { a: u32 },
+    B { b: String },
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "t", content = "c")]
+enum Adjacent {
+    A(u32),
+    B(String),
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+enum Untagged {
+    Integer(u32),
+    String(String),
+    Seq(Vec<u32>),
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Newtype(String);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Unit;
+
+fn noah() -> Author {
+    let mut ratings = BTreeMap::new();
+    ratings.insert(String::from("a"), 1);
+    ratings.insert(String::from("b"), 5);
+
+    Author {
+        name: String::from("Noah"),
+        age: 42,
+        books: vec![String::from("Birds of a feather"), String::from("Fish")],
+        nick: None,
+        ratings,
+        avatar: ByteBuf(vec![1, 2, 3]),
+        raw: vec![4, 5, 6],
+    }
+}
+
+/// Deserialize the given key as `T` both by reference and by value, and
+/// check that the outcomes are the same.
+fn same<T, F>(key: &Key<F>) -> Result<T, serde_hashkey::Error>
+where
+    T: fmt::Debug + PartialEq + DeserializeOwned,
+    F: serde_hashkey::FloatPolicy + Clone,
+{
+    let borrowed = from_key::<T, F>(key);
+    let owned = from_key_owned::<T, F>(key.clone());
+    assert_eq!(borrowed, owned);
+    owned
+}
+
+/// Round trip the given value by value.
+fn roundtrip<T>(value: &T)
+where
+    T: fmt::Debug + PartialEq + serde::Serialize + DeserializeOwned,
+{
+    let key = to_key(value).unwrap();
+    assert_eq!(same::<T, _>(&key).unwrap(), *value);
+}
+
+#[test]
+fn test_roundtrip() {
+    roundtrip(&noah());
+    roundtrip(&Author {
+        nick: Some(String::from("N")),
+        avatar: ByteBuf(Vec::new()),
+        raw: Vec::new(),
+        ..noah()
+    });
+    roundtrip(&Shape::Empty);
+    roundtrip(&Shape::Square(1));
+    roundtrip(&Shape::Rect(1, 2));
+    roundtrip(&Shape::Circle { radius: 3 });
+    roundtrip(&Internal::A { a: 1 });
+    roundtrip(&Internal::B {
+        b: String::from("b"),
+    });
+    roundtrip(&Adjacent::A(1));
+    roundtrip(&Adjacent::B(String::from("b")));
+    roundtrip(&Untagged::Integer(1));
+    roundtrip(&Untagged::String(String::from("a")));
+    roundtrip(&Untagged::Seq(vec![1, 2]));
+    roundtrip(&Newtype(String::from("a")));
+    roundtrip(&Unit);
+    roundtrip(&());
+    roundtrip(&(1u32, String::from("a"), 'c', true));
+    roundtrip(&Some(Some(1u32)));
+    roundtrip(&None::<u32>);
+    roundtrip(&u128::MAX);
+    roundtrip(&i128::MIN);
+    roundtrip(&vec![Shape::Empty, Shape::Square(1)]);
+
+    let mut map = HashMap::new();
+    map.insert(vec![1u32, 2], Shape::Empty);
+    map.insert(vec![], Shape::Circle { radius: 1 });
+    roundtrip(&map);
+}
+
+#[test]
+fn test_mismatches() {
+    // Errors are the same as when deserializing by reference.
+    let key = to_key(&noah()).unwrap();
+    assert!(same::<Shape, _>(&key).is_err());
+    assert!(same::<Vec<u32>, _>(&key).is_err());
+    assert!(same::<(u32, u32), _>(&to_key(&(1u32, 2u32, 3u32)).unwrap()).is_err());
+    assert!(same::<Shape, _>(&to_key(&"Unknown").unwrap()).is_err());
+    assert!(same::<Shape, _>(&to_key(&(1u32, 2u32)).unwrap()).is_err());
+
+    // Integers are accepted as unit variants.
+    assert_eq!(
+        same::<Shape, _>(&to_key(&0u32).unwrap()).unwrap(),
+        Shape::Empty
+    );
+}
+
+#[test]
+fn test_keys() {
+    let key = to_key(&noah()).unwrap();
+    assert_eq!(same::<Key, _>(&key).unwrap(), key);
+
+    let key = to_key_with::<TotalOrderFloatPolicy, _>(&(1.5f32, vec![2.5f64, 3.5])).unwrap();
+    assert_eq!(same::<Key<TotalOrderFloatPolicy>, _>(&key).unwrap(), key);
+    assert_eq!(
+        same::<(f32, Vec<f64>), _>(&key).unwrap(),
+        (1.5f32, vec![2.5f64, 3.5])
+    );
+}
+
+#[test]
+fn test_bytes_are_moved() {
+    let key = to_key(&ByteBuf(vec![1, 2, 3])).unwrap();
+
+    let ptr = match &key {
+        Key::Bytes(bytes) => bytes.as_ptr(),
+        key => panic!("expected bytes, got {:?}", key),
+    };
+
+    let bytes: ByteBuf = from_key_owned(key).unwrap();
+    assert_eq!(bytes, ByteBuf(vec![1, 2, 3]));
+    assert_eq!(bytes.0.as_ptr(), ptr);
+
+    // Byte slices are stored as compact sequences, which are moved as well.
+    let key = to_key(&vec![1u8, 2, 3]).unwrap();
+
+    let ptr = match &key {
+        Key::IntegerSeq(IntegerSeq::U8(seq)) => seq.as_ptr(),
+        key => panic!("expected a sequence, got {:?}", key),
+    };
+
+    let bytes: ByteBuf = from_key_owned(key).unwrap();
+    assert_eq!(bytes, ByteBuf(vec![1, 2, 3]));
+    assert_eq!(bytes.0.as_ptr(), ptr);
+}
@@ -0,0 +1,73 @@
+//! Regression tests for issues found through fuzzing.
+
+use serde::de::value::{Error, MapAccessDeserializer, SeqAccessDeserializer};
+use serde::de::{self, Deserialize};
+use serde_hashkey::Key;
+
+/// Access which claims to contain far more elements than it does.
+struct Lying(u32);
+
+impl<'de> de::SeqAccess<'de> for Lying {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Error>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        if self.0 == 0 {
+            return Ok(None);
+        }
+
+        self.0 -= 1;
+        seed.deserialize(de::IntoDeserializer::<Error>::into_deserializer(self.0))
+            .map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(usize::MAX)
+    }
+}
+
+impl<'de> de::MapAccess<'de> for Lying {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Error>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        de::SeqAccess::next_element_seed(self, seed)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Error>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        seed.deserialize(de::IntoDeserializer::<Error>::into_deserializer(self.0))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(usize::MAX)
+    }
+}
+
+#[test]
+fn untrusted_seq_size_hint() -> Result<(), Error> {
+    let key =
+        Key::<serde_hashkey::RejectFloatPolicy>::deserialize(SeqAccessDeserializer::new(Lying(2)))?;
+    assert_eq!(
+        key,
+        Key::Seq(vec![Key::from(1u32), Key::from(0u32)].into()).normalize()
+    );
+    Ok(())
+}
+
+#[test]
+fn untrusted_map_size_hint() -> Result<(), Error> {
+    let key =
+        Key::<serde_hashkey::RejectFloatPolicy>::deserialize(MapAccessDeserializer::new(Lying(1)))?;
+    assert_eq!(
+        key,
+        Key::Map(vec![(Key::from(0u32), Key::from(0u32))].into())
+    );
+    Ok(())
+}
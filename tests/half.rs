@@ -0,0 +1,155 @@
+#![cfg(feature = "half")]
+
+use half2::f16;
+use serde_derive::{Deserialize, Serialize};
+use serde_hashkey::{
+    check_key, from_key, to_key, to_key_filtered, to_key_with_canonical_float, to_key_with_partial,
+    to_key_with_widened_float, CanonicalFloat, CanonicalFloatPolicy, Error, FieldFilter, Float,
+    FloatWidth, Key,
+};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Input {
+    weight: f16,
+    bias: f16,
+    scale: f32,
+    history: Vec<f16>,
+}
+
+fn input() -> Input {
+    Input {
+        weight: f16::from_f32(0.1),
+        bias: f16::from_f32(-2.5),
+        scale: 0.1,
+        history: vec![f16::ONE, f16::MAX, f16::MIN_POSITIVE_SUBNORMAL],
+    }
+}
+
+#[test]
+fn test_f16_roundtrip() {
+    let input = input();
+    let key = to_key_with_canonical_float(&input).unwrap();
+
+    assert_eq!(
+        key["weight"],
+        Key::Float(Float::F16(CanonicalFloat::new(f16::from_f32(0.1).to_f32())))
+    );
+    assert_eq!(
+        key["bias"],
+        Key::Float(Float::F16(CanonicalFloat::new(-2.5)))
+    );
+    assert_eq!(from_key::<Input, _>(&key).unwrap(), input);
+
+    let bytes = key.to_bytes();
+    let decoded = Key::<CanonicalFloatPolicy>::from_bytes(&bytes).unwrap();
+    assert_eq!(decoded.to_bytes(), bytes);
+    assert_eq!(from_key::<Input, _>(&decoded).unwrap(), input);
+}
+
+#[test]
+fn test_f16_sequences_are_not_compact() {
+    let input = input();
+    let key = to_key_with_canonical_float(&input).unwrap();
+
+    let expected = Key::Seq(
+        input
+            .history
+            .iter()
+            .map(|v| Key::Float(Float::F16(CanonicalFloat::new(v.to_f32()))))
+            .collect(),
+    );
+
+    assert_eq!(key["history"], expected);
+    assert_eq!(expected.clone().normalize(), expected);
+    assert_eq!(
+        Key::<CanonicalFloatPolicy>::from_bytes(&expected.to_bytes()),
+        Ok(expected)
+    );
+
+    // Sequences of f32 are compact.
+    let key = to_key_with_canonical_float(&vec![1.5f32, 2.5]).unwrap();
+    assert!(matches!(key, Key::FloatSeq(..)));
+}
+
+#[test]
+fn test_f16_entry_points() {
+    let input = input();
+    let key = to_key_with_canonical_float(&input).unwrap();
+
+    assert_eq!(
+        to_key_filtered::<_, CanonicalFloatPolicy>(&input, &FieldFilter::new()).unwrap(),
+        key
+    );
+    assert_eq!(
+        to_key_with_partial::<_, CanonicalFloatPolicy>(&input).unwrap(),
+        key
+    );
+
+    // Serializing a key which holds an f16 produces the same key.
+    assert_eq!(to_key_with_canonical_float(&key).unwrap(), key);
+    assert_eq!(
+        to_key_with_canonical_float(&key["weight"]).unwrap(),
+        key["weight"]
+    );
+}
+
+#[test]
+fn test_f16_distinct_from_f32() {
+    let half = to_key_with_canonical_float(&f16::from_f32(1.5)).unwrap();
+    let single = to_key_with_canonical_float(&1.5f32).unwrap();
+    assert_ne!(half, single);
+    assert_ne!(half.to_bytes(), single.to_bytes());
+    assert_ne!(half.to_id(), single.to_id());
+
+    // But both hold the same value.
+    assert_eq!(from_key::<f32, _>(&half).unwrap(), 1.5);
+    assert_eq!(from_key::<f16, _>(&single).unwrap(), f16::from_f32(1.5));
+
+    // Policies which ignore the width treat them the same.
+    assert_eq!(
+        to_key_with_widened_float(&f16::from_f32(1.5)).unwrap(),
+        to_key_with_widened_float(&1.5f64).unwrap()
+    );
+}
+
+#[test]
+fn test_f16_rejected() {
+    let expected = Error::UnsupportedFloat {
+        width: FloatWidth::F16,
+        value: 0.5,
+    };
+
+    assert_eq!(to_key(&f16::from_f32(0.5)), Err(expected));
+    assert_eq!(
+        Error::UnsupportedFloat {
+            width: FloatWidth::F16,
+            value: 0.5,
+        }
+        .to_string(),
+        "unsupported float f16 = 0.5"
+    );
+
+    let errors = check_key(&input()).unwrap_err();
+    let paths = errors
+        .iter()
+        .map(|(path, _)| path.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        paths,
+        [
+            "weight",
+            "bias",
+            "scale",
+            "history[0]",
+            "history[1]",
+            "history[2]"
+        ]
+    );
+    assert_eq!(
+        errors[1].1,
+        Error::UnsupportedFloat {
+            width: FloatWidth::F16,
+            value: -2.5,
+        }
+    );
+}
@@ -0,0 +1,525 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
+use std::hash::{Hash, Hasher};
+
+use serde::ser::{
+    Serialize, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple,
+    SerializeTupleVariant, Serializer,
+};
+use serde_derive::Serialize;
+use serde_hashkey::{
+    hash_value, hash_value_with, to_key, to_key_with, Error, FloatPolicy, KeyPath, PathSegment,
+    RejectFloatPolicy, TotalOrderFloatPolicy,
+};
+
+/// Hasher which records every write, so that the framing of the data being
+/// hashed is compared and not only the resulting hash.
+#[derive(Debug, Default, PartialEq)]
+struct Recorder(Vec<Vec<u8>>);
+
+impl Hasher for Recorder {
+    fn finish(&self) -> u64 {
+        0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.push(bytes.to_vec());
+    }
+}
+
+#[track_caller]
+fn assert_same<F, T>(value: &T)
+where
+    F: FloatPolicy + Hash,
+    T: ?Sized + Serialize,
+{
+    let key = to_key_with::<F, _>(value).unwrap();
+
+    let mut expected = Recorder::default();
+    key.hash(&mut expected);
+    let mut actual = Recorder::default();
+    hash_value_with::<F, _, _>(value, &mut actual).unwrap();
+    assert_eq!(actual, expected);
+
+    let mut expected = DefaultHasher::new();
+    key.hash(&mut expected);
+    let mut actual = DefaultHasher::new();
+    hash_value_with::<F, _, _>(value, &mut actual).unwrap();
+    assert_eq!(actual.finish(), expected.finish());
+}
+
+#[derive(Serialize)]
+struct Author {
+    name: &'static str,
+    age: u32,
+    books: Vec<u32>,
+    tags: Vec<&'static str>,
+    nick: Option<&'static str>,
+}
+
+#[derive(Serialize)]
+enum Shape {
+    Empty,
+    Square(u32),
+    Rect(u32, u32),
+    Circle { radius: u32 },
+}
+
+#[test]
+fn test_values() {
+    assert_same::<RejectFloatPolicy, _>(&());
+    assert_same::<RejectFloatPolicy, _>(&None::<u32>);
+    assert_same::<RejectFloatPolicy, _>(&Some(1u8));
+    assert_same::<RejectFloatPolicy, _>(&true);
+    assert_same::<RejectFloatPolicy, _>(&'a');
+    assert_same::<RejectFloatPolicy, _>("hello");
+    assert_same::<RejectFloatPolicy, _>(&serde_bytes_like(b"hello"));
+    assert_same::<RejectFloatPolicy, _>(&u128::MAX);
+    assert_same::<RejectFloatPolicy, _>(&i8::MIN);
+
+    assert_same::<RejectFloatPolicy, _>(&Vec::<u32>::new());
+    assert_same::<RejectFloatPolicy, _>(&vec![1u32, 2, 3]);
+    assert_same::<RejectFloatPolicy, _>(&(1u32, 2u64));
+    assert_same::<RejectFloatPolicy, _>(&(1u32, "a", 2u32));
+    assert_same::<RejectFloatPolicy, _>(&[vec![1u8], vec![]]);
+
+    let author = Author {
+        name: "Noah",
+        age: 42,
+        books: vec![1, 2, 3],
+        tags: vec!["a", "b"],
+        nick: None,
+    };
+
+    assert_same::<RejectFloatPolicy, _>(&author);
+
+    for shape in [
+        Shape::Empty,
+        Shape::Square(1),
+        Shape::Rect(1, 2),
+        Shape::Circle { radius: 3 },
+    ] {
+        assert_same::<RejectFloatPolicy, _>(&shape);
+    }
+
+    let mut map = BTreeMap::new();
+    map.insert("b", vec![2u32]);
+    map.insert("a", vec![1u32]);
+    assert_same::<RejectFloatPolicy, _>(&map);
+}
+
+#[test]
+fn test_floats() {
+    assert_same::<TotalOrderFloatPolicy, _>(&1.5f32);
+    assert_same::<TotalOrderFloatPolicy, _>(&[1.5f32, 2.5]);
+    assert_same::<TotalOrderFloatPolicy, _>(&[1.5f64, 2.5]);
+    assert_same::<TotalOrderFloatPolicy, _>(&(1.5f32, 2.5f64));
+    assert_same::<TotalOrderFloatPolicy, _>(&(1u32, 2.5f64));
+}
+
+#[test]
+fn test_distinct() {
+    fn hash<T>(value: &T) -> u64
+    where
+        T: ?Sized + Serialize,
+    {
+        let mut hasher = DefaultHasher::new();
+        hash_value(value, &mut hasher).unwrap();
+        hasher.finish()
+    }
+
+    assert_ne!(hash(&()), hash(&None::<()>));
+    assert_ne!(hash(&vec![1u32]), hash(&(1u32, "")));
+    assert_ne!(hash(&("ab", "c")), hash(&("a", "bc")));
+    assert_ne!(
+        hash(&vec![vec![1u32], vec![]]),
+        hash(&vec![vec![], vec![1u32]])
+    );
+}
+
+#[test]
+fn test_errors() {
+    #[derive(Serialize)]
+    struct Circle {
+        radius: f32,
+    }
+
+    #[derive(Serialize)]
+    enum Shape {
+        Circle { radius: f32 },
+        Points(u32, f32),
+    }
+
+    let mut map = BTreeMap::new();
+    map.insert("radius", 1.5f32);
+
+    let values: Vec<Box<dyn erased::Serialize>> = vec![
+        Box::new(Circle { radius: 1.5 }),
+        Box::new(Shape::Circle { radius: 1.5 }),
+        Box::new(Shape::Points(1, 1.5)),
+        Box::new(vec![1.5f32]),
+        Box::new((1u32, 1.5f32)),
+        Box::new(vec![Some(1u32), None, Some(2)]),
+        Box::new(map),
+    ];
+
+    for value in values {
+        let expected = value.to_key();
+        let actual = value.hash_value(&mut DefaultHasher::new());
+        assert_eq!(actual, expected.map(|_| ()));
+    }
+
+    let error = hash_value(&Circle { radius: 1.5 }, &mut DefaultHasher::new()).unwrap_err();
+
+    match error {
+        Error::FloatRejected { path, .. } => {
+            assert_eq!(
+                path,
+                KeyPath::from(vec![PathSegment::Field("radius".into())])
+            );
+        }
+        error => panic!("expected a rejected float, got {:?}", error),
+    }
+}
+
+#[test]
+fn test_invalid_length() {
+    /// Sequence which reports the wrong length.
+    struct Lying;
+
+    impl Serialize for Lying {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            let mut seq = serializer.serialize_seq(Some(3))?;
+            seq.serialize_element("a")?;
+            seq.end()
+        }
+    }
+
+    assert_eq!(
+        hash_value(&Lying, &mut DefaultHasher::new()),
+        Err(Error::InvalidLength)
+    );
+}
+
+/// Bytes which serialize as bytes rather than as a sequence.
+fn serde_bytes_like(bytes: &'static [u8]) -> impl Serialize {
+    struct Bytes(&'static [u8]);
+
+    impl Serialize for Bytes {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.serialize_bytes(self.0)
+        }
+    }
+
+    Bytes(bytes)
+}
+
+mod erased {
+    use std::collections::hash_map::DefaultHasher;
+
+    use serde_hashkey::{Error, Key};
+
+    pub(super) trait Serialize {
+        fn to_key(&self) -> Result<Key, Error>;
+
+        fn hash_value(&self, hasher: &mut DefaultHasher) -> Result<(), Error>;
+    }
+
+    impl<T> Serialize for T
+    where
+        T: serde::Serialize,
+    {
+        fn to_key(&self) -> Result<Key, Error> {
+            serde_hashkey::to_key(self)
+        }
+
+        fn hash_value(&self, hasher: &mut DefaultHasher) -> Result<(), Error> {
+            serde_hashkey::hash_value(self, hasher)
+        }
+    }
+}
+
+/// Deterministic xorshift generator, used to build random values.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+}
+
+const NAMES: [&str; 4] = ["a", "b", "c", "d"];
+
+/// A value which exercises every method of a serializer.
+#[derive(Debug)]
+enum Value {
+    Bool(bool),
+    I8(i8),
+    I32(i32),
+    U64(u64),
+    I128(i128),
+    F32(f32),
+    F64(f64),
+    Char(char),
+    Str(String),
+    Bytes(Vec<u8>),
+    Unit,
+    None,
+    Some(Box<Value>),
+    UnitStruct,
+    Newtype(Box<Value>),
+    Seq(Vec<Value>),
+    UnsizedSeq(Vec<Value>),
+    Tuple(Vec<Value>),
+    Map(Vec<(Value, Value)>),
+    UnsizedMap(Vec<(Value, Value)>),
+    Struct(Vec<Value>),
+    UnitVariant(usize),
+    NewtypeVariant(usize, Box<Value>),
+    TupleVariant(usize, Vec<Value>),
+    StructVariant(usize, Vec<Value>),
+}
+
+impl Value {
+    fn generate(rng: &mut Rng, depth: usize, floats: bool) -> Self {
+        let kinds = if depth == 0 { 12 } else { 25 };
+
+        match rng.below(kinds) {
+            0 => Value::Bool(rng.below(2) == 0),
+            1 => Value::I8(rng.next() as i8),
+            2 => Value::I32(rng.below(4) as i32),
+            3 => Value::U64(rng.next()),
+            4 => Value::I128(rng.next() as i128 * -3),
+            5 if floats => Value::F32(rng.below(8) as f32 / 2.0),
+            6 if floats => Value::F64(rng.below(8) as f64 / 4.0),
+            5 | 6 => Value::I32(rng.next() as i32),
+            7 => Value::Char(char::from(b'a' + rng.below(4) as u8)),
+            8 => Value::Str(NAMES[rng.below(4) as usize].repeat(rng.below(3) as usize)),
+            9 => Value::Bytes(vec![rng.next() as u8; rng.below(3) as usize]),
+            10 => Value::Unit,
+            11 => Value::None,
+            12 => Value::Some(Box::new(Value::generate(rng, depth - 1, floats))),
+            13 => Value::UnitStruct,
+            14 => Value::Newtype(Box::new(Value::generate(rng, depth - 1, floats))),
+            15 => Value::Seq(Value::many(rng, depth, floats)),
+            16 => Value::UnsizedSeq(Value::many(rng, depth, floats)),
+            17 => Value::Tuple(Value::many(rng, depth, floats)),
+            18 => Value::Map(Value::pairs(rng, depth, floats)),
+            19 => Value::UnsizedMap(Value::pairs(rng, depth, floats)),
+            20 => Value::Struct(Value::fields(rng, depth, floats)),
+            21 => Value::UnitVariant(rng.below(4) as usize),
+            22 => Value::NewtypeVariant(
+                rng.below(4) as usize,
+                Box::new(Value::generate(rng, depth - 1, floats)),
+            ),
+            23 => Value::TupleVariant(rng.below(4) as usize, Value::many(rng, depth, floats)),
+            _ => Value::StructVariant(rng.below(4) as usize, Value::fields(rng, depth, floats)),
+        }
+    }
+
+    fn many(rng: &mut Rng, depth: usize, floats: bool) -> Vec<Value> {
+        // NB: Sequences of scalars of the same kind are common, since they're
+        // stored compactly.
+        if rng.below(2) == 0 {
+            let kind = rng.below(3);
+
+            return (0..rng.below(5))
+                .map(|_| match kind {
+                    0 => Value::I32(rng.below(4) as i32),
+                    1 if floats => Value::F64(rng.below(4) as f64),
+                    _ => Value::U64(rng.below(4)),
+                })
+                .collect();
+        }
+
+        (0..rng.below(4))
+            .map(|_| Value::generate(rng, depth - 1, floats))
+            .collect()
+    }
+
+    fn pairs(rng: &mut Rng, depth: usize, floats: bool) -> Vec<(Value, Value)> {
+        (0..rng.below(4))
+            .map(|_| {
+                (
+                    Value::generate(rng, depth - 1, floats),
+                    Value::generate(rng, depth - 1, floats),
+                )
+            })
+            .collect()
+    }
+
+    fn fields(rng: &mut Rng, depth: usize, floats: bool) -> Vec<Value> {
+        (0..rng.below(NAMES.len() as u64 + 1))
+            .map(|_| Value::generate(rng, depth - 1, floats))
+            .collect()
+    }
+}
+
+impl Serialize for Value {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Value::Bool(v) => serializer.serialize_bool(*v),
+            Value::I8(v) => serializer.serialize_i8(*v),
+            Value::I32(v) => serializer.serialize_i32(*v),
+            Value::U64(v) => serializer.serialize_u64(*v),
+            Value::I128(v) => serializer.serialize_i128(*v),
+            Value::F32(v) => serializer.serialize_f32(*v),
+            Value::F64(v) => serializer.serialize_f64(*v),
+            Value::Char(v) => serializer.serialize_char(*v),
+            Value::Str(v) => serializer.serialize_str(v),
+            Value::Bytes(v) => serializer.serialize_bytes(v),
+            Value::Unit => serializer.serialize_unit(),
+            Value::None => serializer.serialize_none(),
+            Value::Some(v) => serializer.serialize_some(v),
+            Value::UnitStruct => serializer.serialize_unit_struct("Unit"),
+            Value::Newtype(v) => serializer.serialize_newtype_struct("Newtype", v),
+            Value::Seq(values) | Value::UnsizedSeq(values) => {
+                let len = matches!(self, Value::Seq(..)).then_some(values.len());
+                let mut seq = serializer.serialize_seq(len)?;
+
+                for value in values {
+                    seq.serialize_element(value)?;
+                }
+
+                seq.end()
+            }
+            Value::Tuple(values) => {
+                let mut tuple = serializer.serialize_tuple(values.len())?;
+
+                for value in values {
+                    tuple.serialize_element(value)?;
+                }
+
+                tuple.end()
+            }
+            Value::Map(entries) | Value::UnsizedMap(entries) => {
+                let len = matches!(self, Value::Map(..)).then_some(entries.len());
+                let mut map = serializer.serialize_map(len)?;
+
+                for (index, (key, value)) in entries.iter().enumerate() {
+                    // NB: Alternate between the two ways of serializing
+                    // entries.
+                    if index % 2 == 0 {
+                        map.serialize_entry(key, value)?;
+                    } else {
+                        map.serialize_key(key)?;
+                        map.serialize_value(value)?;
+                    }
+                }
+
+                map.end()
+            }
+            Value::Struct(fields) => {
+                let mut st = serializer.serialize_struct("Struct", fields.len())?;
+
+                for (name, value) in NAMES.iter().zip(fields) {
+                    st.serialize_field(name, value)?;
+                }
+
+                st.end()
+            }
+            Value::UnitVariant(index) => {
+                serializer.serialize_unit_variant("Enum", *index as u32, NAMES[*index])
+            }
+            Value::NewtypeVariant(index, value) => {
+                serializer.serialize_newtype_variant("Enum", *index as u32, NAMES[*index], value)
+            }
+            Value::TupleVariant(index, values) => {
+                let mut variant = serializer.serialize_tuple_variant(
+                    "Enum",
+                    *index as u32,
+                    NAMES[*index],
+                    values.len(),
+                )?;
+
+                for value in values {
+                    variant.serialize_field(value)?;
+                }
+
+                variant.end()
+            }
+            Value::StructVariant(index, fields) => {
+                let mut variant = serializer.serialize_struct_variant(
+                    "Enum",
+                    *index as u32,
+                    NAMES[*index],
+                    fields.len(),
+                )?;
+
+                for (name, value) in NAMES.iter().zip(fields) {
+                    variant.serialize_field(name, value)?;
+                }
+
+                variant.end()
+            }
+        }
+    }
+}
+
+#[test]
+fn test_generated() {
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+
+    for _ in 0..2000 {
+        let value = Value::generate(&mut rng, 4, true);
+        assert_same::<TotalOrderFloatPolicy, _>(&value);
+
+        // Floats are rejected by the default policy. Paths are not compared,
+        // since they differ for map values serialized without their keys.
+        let expected = to_key(&value).is_ok();
+        assert_eq!(
+            hash_value(&value, &mut DefaultHasher::new()).is_ok(),
+            expected
+        );
+
+        let value = Value::generate(&mut rng, 4, false);
+        assert_same::<RejectFloatPolicy, _>(&value);
+    }
+}
+
+#[test]
+#[cfg(feature = "ordered-float")]
+fn test_ordered_float() {
+    use serde_hashkey::{hash_value_with_ordered_float, to_key_with_ordered_float};
+
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+
+    for _ in 0..500 {
+        let value = Value::generate(&mut rng, 4, true);
+
+        let mut expected = Recorder::default();
+        to_key_with_ordered_float(&value)
+            .unwrap()
+            .hash(&mut expected);
+        let mut actual = Recorder::default();
+        hash_value_with_ordered_float(&value, &mut actual).unwrap();
+        assert_eq!(actual, expected);
+    }
+}
+
+#[test]
+#[cfg(feature = "half")]
+fn test_half() {
+    use half2::f16;
+
+    assert_same::<TotalOrderFloatPolicy, _>(&f16::from_f32(1.5));
+    assert_same::<TotalOrderFloatPolicy, _>(&vec![f16::from_f32(1.5), f16::from_f32(2.0)]);
+    assert_same::<TotalOrderFloatPolicy, _>(&(1.5f32, f16::from_f32(2.0)));
+    assert_same::<TotalOrderFloatPolicy, _>(&(f16::from_f32(2.0), 1.5f32));
+}
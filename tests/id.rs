@@ -0,0 +1,105 @@
+use serde_derive::Serialize;
+use serde_hashkey::{key, to_key, IdAlphabet, IdConfig, Key};
+
+#[derive(Serialize)]
+struct Build {
+    name: &'static str,
+    rev: u32,
+}
+
+#[test]
+fn test_golden() {
+    let key: Key = Key::Unit;
+    assert_eq!(key.to_id(), "ny2axhh7wn5jrhffittlw6akfr");
+
+    let key = to_key(&("hello", 42u8)).unwrap();
+    assert_eq!(key.to_id(), "fkvjprcmd2brrfhzjezymourxs");
+
+    let key = to_key(&Build {
+        name: "Hello World!",
+        rev: 7,
+    })
+    .unwrap();
+    assert_eq!(key.to_id(), "od3xd6os7igjh3obxlsd2ll3dl");
+}
+
+#[test]
+fn test_alphabets() {
+    let key = to_key(&("hello", 42u8)).unwrap();
+
+    let hex = IdConfig::new().alphabet(IdAlphabet::Hex).len(usize::MAX);
+    assert_eq!(
+        key.to_id_with(&hex),
+        "2aaa97c44c1e831894f94933863a91bcbe23a1a5409f7415008cd0de662a3938"
+    );
+
+    let base64 = IdConfig::new()
+        .alphabet(IdAlphabet::Base64Url)
+        .len(usize::MAX);
+    assert_eq!(
+        key.to_id_with(&base64),
+        "KqqXxEwegxiU-UkzhjqRvL4joaVAn3QVAIzQ3mYqOTg"
+    );
+
+    let base32 = IdConfig::new().len(usize::MAX);
+    assert_eq!(
+        key.to_id_with(&base32),
+        "fkvjprcmd2brrfhzjezymourxs7chinficpxifiartin4zrkhe4a"
+    );
+
+    assert_eq!(key.to_id_with(&IdConfig::new().len(0)), "f");
+}
+
+#[test]
+fn test_prefix() {
+    let key = to_key(&Build {
+        name: "Hello World!",
+        rev: 7,
+    })
+    .unwrap();
+
+    let config = IdConfig::new().len(8).prefix_field("name");
+    assert_eq!(key.to_id_with(&config), "hello_world_-od3xd6os");
+
+    let config = IdConfig::new().len(8).prefix_field("rev");
+    assert_eq!(key.to_id_with(&config), "7-od3xd6os");
+
+    let config = IdConfig::new().len(8).prefix_field("missing");
+    assert_eq!(key.to_id_with(&config), "od3xd6os");
+
+    let long = to_key(&Build {
+        name: "a-very-long-name-which-is-longer-than-the-limit",
+        rev: 7,
+    })
+    .unwrap();
+    let id = long.to_id_with(&IdConfig::new().prefix_field("name"));
+    assert!(id.starts_with("a-very-long-name-which-is-longer-"));
+    assert_eq!(id.len(), 32 + 1 + 26);
+}
+
+#[test]
+fn test_order_independent() {
+    let a = key!({ "rev": 7u32, "name": "Hello World!" });
+
+    assert_eq!(a.to_id(), "od3xd6os7igjh3obxlsd2ll3dl");
+    assert_eq!(a.to_id(), a.clone().normalize().to_id());
+}
+
+#[test]
+fn test_nested_order_independent() {
+    let a: Key = Key::Map(
+        vec![
+            (
+                Key::Seq(vec![key!(0u8)].into()),
+                key!({ "b": 1u32, "a": [{ "d": (), "c": () }] }),
+            ),
+            (Key::Seq(vec![key!(1u8), key!("x")].into()), key!(())),
+        ]
+        .into(),
+    );
+
+    let b = a.clone().normalize();
+    assert_ne!(a, b);
+    assert_eq!(a.to_id(), "lzbofp5kull73kllbytodx46ku");
+    assert_eq!(b.to_id(), "lzbofp5kull73kllbytodx46ku");
+}
@@ -0,0 +1,86 @@
+use serde_derive::Serialize;
+use serde_hashkey::{key, to_key, Error, Key};
+
+#[derive(Serialize)]
+struct Author {
+    name: &'static str,
+    age: Option<u32>,
+}
+
+#[derive(Serialize)]
+struct Chapter {
+    title: &'static str,
+    pages: Vec<u32>,
+}
+
+#[derive(Serialize)]
+struct Book {
+    title: &'static str,
+    authors: Vec<Author>,
+    chapters: Vec<Chapter>,
+}
+
+fn book() -> Book {
+    Book {
+        title: "Birds of a feather",
+        authors: vec![
+            Author {
+                name: "Noah",
+                age: Some(42),
+            },
+            Author {
+                name: "Jane",
+                age: None,
+            },
+        ],
+        chapters: vec![Chapter {
+            title: "Flocking",
+            pages: vec![1, 2],
+        }],
+    }
+}
+
+#[test]
+fn test_index_chain() -> Result<(), Error> {
+    let key = to_key(&book())?;
+
+    assert_eq!(key["title"], key!("Birds of a feather"));
+    assert_eq!(key["authors"][0]["name"], key!("Noah"));
+    assert_eq!(key["authors"][0]["age"], Key::from(42u32));
+    assert_eq!(key["authors"][1]["name"], key!("Jane"));
+    assert_eq!(key["chapters"][0]["title"], key!("Flocking"));
+    assert_eq!(key["chapters"][0]["pages"], to_key(&vec![1u32, 2])?);
+    Ok(())
+}
+
+#[test]
+fn test_index_missing() -> Result<(), Error> {
+    let key = to_key(&book())?;
+
+    assert_eq!(key["missing"], Key::Unit);
+    assert_eq!(key["missing"]["deeper"][3], Key::Unit);
+    assert_eq!(key["authors"][2]["name"], Key::Unit);
+    assert_eq!(key["title"]["name"], Key::Unit);
+    assert_eq!(key["title"][0], Key::Unit);
+    assert_eq!(key[0], Key::Unit);
+    assert_eq!(key["authors"]["name"], Key::Unit);
+
+    // A present null value can be told apart from a missing value.
+    assert_eq!(key["authors"][1]["age"], Key::Null);
+    assert!(key["authors"][1].get(&key!("age")).is_some());
+
+    // Compact sequences can't be indexed into.
+    assert_eq!(key["chapters"][0]["pages"][0], Key::Unit);
+    Ok(())
+}
+
+#[cfg(feature = "ordered-float")]
+#[test]
+fn test_index_ordered_float() -> Result<(), Error> {
+    use serde_hashkey::to_key_with_ordered_float;
+
+    let key = to_key_with_ordered_float(&vec![("a", 1.5f64)])?;
+    assert_eq!(key[0][1], to_key_with_ordered_float(&1.5f64)?);
+    assert_eq!(key[1], Key::Unit);
+    Ok(())
+}
@@ -0,0 +1,163 @@
+use std::collections::BTreeMap;
+
+use serde_derive::Serialize;
+use serde_hashkey::{
+    from_deserializer, from_deserializer_with, to_key, Error, Key, RejectFloatPolicy,
+    RejectNonFinitePolicy,
+};
+
+#[derive(Serialize)]
+struct Book {
+    title: &'static str,
+    pages: u64,
+    tags: Vec<&'static str>,
+}
+
+fn book() -> Book {
+    Book {
+        title: "Birds of a feather",
+        pages: 42,
+        tags: vec!["fiction", "birds"],
+    }
+}
+
+fn cbor<T>(value: &T) -> Vec<u8>
+where
+    T: serde::Serialize,
+{
+    let mut out = Vec::new();
+    ciborium::ser::into_writer(value, &mut out).unwrap();
+    out
+}
+
+/// The deserializer of ciborium isn't public, so it's reached through a type
+/// which deserializes using it.
+struct Ingested(Key<RejectFloatPolicy>);
+
+impl<'de> serde::Deserialize<'de> for Ingested {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        from_deserializer(deserializer).map(Ingested)
+    }
+}
+
+fn from_cbor(bytes: &[u8]) -> Result<Key, ciborium::de::Error<std::io::Error>> {
+    Ok(ciborium::de::from_reader::<Ingested, _>(bytes)?.0)
+}
+
+/// Serializes as bytes instead of as a sequence.
+struct Data(&'static [u8]);
+
+impl serde::Serialize for Data {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+#[test]
+fn test_json() -> Result<(), Box<dyn std::error::Error>> {
+    let json = serde_json::to_string(&book())?;
+    let key = from_deserializer(&mut serde_json::Deserializer::from_str(&json))?;
+    assert_eq!(key, to_key(&book())?);
+    Ok(())
+}
+
+#[test]
+fn test_json_rejected_floats() {
+    let json = r#"{"title": "a", "ratings": [1, 2, 4.5]}"#;
+    let error = from_deserializer(&mut serde_json::Deserializer::from_str(json)).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "unsupported float f64 = 4.5 at ratings[2] at line 1 column 36"
+    );
+
+    let error = from_deserializer(&mut serde_json::Deserializer::from_str("4.5")).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "unsupported float f64 = 4.5 at line 1 column 3"
+    );
+
+    // The policy in use decides which floats are rejected.
+    let json = r#"[{"a": 1.5}, {"b": 1e999}]"#;
+    let error = from_deserializer_with::<RejectNonFinitePolicy, _>(
+        &mut serde_json::Deserializer::from_str(json),
+    );
+    assert!(error.is_err());
+
+    let json = r#"[{"a": 1.5}, {"b": 2.5}]"#;
+    let key = from_deserializer_with::<RejectNonFinitePolicy, _>(
+        &mut serde_json::Deserializer::from_str(json),
+    );
+    assert!(key.is_ok());
+}
+
+#[test]
+fn test_cbor() -> Result<(), Box<dyn std::error::Error>> {
+    let bytes = cbor(&book());
+    let key = from_cbor(&bytes)?;
+    assert_eq!(key, to_key(&book())?);
+    Ok(())
+}
+
+#[test]
+fn test_cbor_bytes_and_wide_integers() -> Result<(), Box<dyn std::error::Error>> {
+    #[derive(Serialize)]
+    struct Wide {
+        data: Data,
+        large: u128,
+        small: i128,
+    }
+
+    let value = Wide {
+        data: Data(&[1, 2, 3]),
+        large: u128::MAX,
+        small: i128::MIN,
+    };
+
+    let bytes = cbor(&value);
+    let key = from_cbor(&bytes)?;
+
+    assert_eq!(key, to_key(&value)?);
+    assert_eq!(
+        key.get(&to_key("data")?),
+        Some(&Key::Bytes(vec![1, 2, 3].into()))
+    );
+    assert_eq!(key.get(&to_key("large")?), Some(&Key::from(u128::MAX)));
+    assert_eq!(key.get(&to_key("small")?), Some(&Key::from(i128::MIN)));
+    Ok(())
+}
+
+#[test]
+fn test_cbor_rejected_floats() {
+    let mut map = BTreeMap::new();
+    map.insert("ratings", vec![1.5f32]);
+
+    // All floats are visited as `f64` by ciborium.
+    let bytes = cbor(&map);
+    let error = from_cbor(&bytes).unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("unsupported float f64 = 1.5 at ratings[0]"),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn test_deserialize_impl() -> Result<(), Error> {
+    let key: Key = serde_json::from_str(r#"{"a": [1, {"b": 2}]}"#).unwrap();
+    let error = serde_json::from_str::<Key>(r#"{"a": [1, {"b": 2.5}]}"#).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "unsupported float f64 = 2.5 at a[1].b at line 1 column 19"
+    );
+    let a = key.get(&to_key("a")?);
+    assert_eq!(a.and_then(|a| a.get_index(0)), Some(&Key::from(1u64)));
+    Ok(())
+}
@@ -0,0 +1,270 @@
+use std::convert::TryFrom;
+use std::num::{
+    NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128,
+    NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize, Wrapping,
+};
+
+use serde::Serialize;
+use serde_hashkey::{to_key, Error, Integer, Key};
+
+/// Assert that a key built by hand is the expected integer, and that it's the
+/// same as the key built by serializing the value.
+fn check<T>(value: T, expected: Integer)
+where
+    T: Copy + Serialize,
+    Key: From<T>,
+{
+    assert_eq!(Key::from(value), Key::Integer(expected));
+    assert_eq!(to_key(&value).unwrap(), Key::from(value));
+}
+
+#[test]
+fn test_platform_independent() {
+    check(42usize, Integer::U64(42));
+    check(usize::MAX, Integer::U64(usize::MAX as u64));
+    check(-42isize, Integer::I64(-42));
+    check(isize::MIN, Integer::I64(isize::MIN as i64));
+    check(NonZeroUsize::new(1).unwrap(), Integer::U64(1));
+    check(NonZeroIsize::new(-1).unwrap(), Integer::I64(-1));
+}
+
+#[test]
+fn test_non_zero() {
+    check(NonZeroI8::new(-8).unwrap(), Integer::I8(-8));
+    check(NonZeroI16::new(-16).unwrap(), Integer::I16(-16));
+    check(NonZeroI32::new(-32).unwrap(), Integer::I32(-32));
+    check(NonZeroI64::new(-64).unwrap(), Integer::I64(-64));
+    check(NonZeroI128::new(-128).unwrap(), Integer::I128(-128));
+    check(NonZeroU8::new(8).unwrap(), Integer::U8(8));
+    check(NonZeroU16::new(16).unwrap(), Integer::U16(16));
+    check(NonZeroU32::new(32).unwrap(), Integer::U32(32));
+    check(NonZeroU64::new(64).unwrap(), Integer::U64(64));
+    check(NonZeroU128::new(128).unwrap(), Integer::U128(128));
+}
+
+#[test]
+fn test_wrapping() {
+    check(Wrapping(7u16), Integer::U16(7));
+    check(Wrapping(-7i64), Integer::I64(-7));
+    check(Wrapping(7usize), Integer::U64(7));
+}
+
+#[test]
+fn test_extraction_boundaries() {
+    let min = Integer::I64(i64::MIN);
+    assert_eq!(min.to_i64(), Some(i64::MIN));
+    assert_eq!(min.to_i128(), Some(i128::from(i64::MIN)));
+    assert_eq!(min.to_u64(), None);
+    assert_eq!(min.to_u128(), None);
+
+    let below = Integer::I128(i128::from(i64::MIN) - 1);
+    assert_eq!(below.to_i64(), None);
+    assert_eq!(below.to_i128(), Some(i128::from(i64::MIN) - 1));
+
+    let max = Integer::U64(u64::MAX);
+    assert_eq!(max.to_i64(), None);
+    assert_eq!(max.to_u64(), Some(u64::MAX));
+    assert_eq!(max.to_i128(), Some(i128::from(u64::MAX)));
+    assert_eq!(max.to_u128(), Some(u128::from(u64::MAX)));
+
+    let above = Integer::U128(u128::from(u64::MAX) + 1);
+    assert_eq!(above.to_u64(), None);
+    assert_eq!(above.to_i128(), Some(i128::from(u64::MAX) + 1));
+
+    let max = Integer::U128(u128::MAX);
+    assert_eq!(max.to_i64(), None);
+    assert_eq!(max.to_u64(), None);
+    assert_eq!(max.to_i128(), None);
+    assert_eq!(max.to_u128(), Some(u128::MAX));
+
+    assert_eq!(Integer::I128(i128::MAX).to_u128(), Some(i128::MAX as u128));
+    assert_eq!(Integer::U128(i128::MAX as u128).to_i128(), Some(i128::MAX));
+    assert_eq!(Integer::I128(i128::MIN).to_i128(), Some(i128::MIN));
+    assert_eq!(Integer::I8(-1).to_u64(), None);
+}
+
+#[test]
+fn test_sign() {
+    // The helpers can be used in constant contexts.
+    const _: () = assert!(Integer::I16(-1).is_negative());
+    const _: () = assert!(Integer::I16(1).is_signed_variant());
+    const WIDE: Option<i64> = Integer::U32(u32::MAX).to_i64();
+    assert_eq!(WIDE, Some(i64::from(u32::MAX)));
+
+    assert!(Integer::I128(i128::MIN).is_negative());
+    assert!(!Integer::I64(0).is_negative());
+    assert!(!Integer::U128(u128::MAX).is_negative());
+    assert!(Integer::I8(0).is_signed_variant());
+    assert!(!Integer::U8(0).is_signed_variant());
+}
+
+#[test]
+fn test_try_from() {
+    assert_eq!(
+        i64::try_from(Integer::I128(i128::from(i64::MIN))),
+        Ok(i64::MIN)
+    );
+    assert_eq!(
+        u64::try_from(&Integer::U128(u128::from(u64::MAX))),
+        Ok(u64::MAX)
+    );
+    assert_eq!(u128::try_from(Integer::U128(u128::MAX)), Ok(u128::MAX));
+    assert_eq!(u8::try_from(Integer::I64(255)), Ok(255));
+    assert_eq!(i8::try_from(&Integer::U64(127)), Ok(127));
+
+    assert_eq!(
+        i64::try_from(Integer::U64(u64::MAX)),
+        Err(Error::IntegerOverflow("i64"))
+    );
+    assert_eq!(
+        u64::try_from(&Integer::I64(i64::MIN)),
+        Err(Error::IntegerOverflow("u64"))
+    );
+    assert_eq!(
+        i128::try_from(Integer::U128(u128::MAX)),
+        Err(Error::IntegerOverflow("i128"))
+    );
+    assert_eq!(
+        u8::try_from(Integer::U16(256)),
+        Err(Error::IntegerOverflow("u8"))
+    );
+}
+
+#[test]
+fn test_display_parse_roundtrip() {
+    let cases = [
+        (Integer::I8(i8::MIN), "-128"),
+        (Integer::I16(i16::MIN), "-32768"),
+        (Integer::I32(i32::MIN), "-2147483648"),
+        (Integer::I64(i64::MIN), "-9223372036854775808"),
+        (
+            Integer::I128(i128::MIN),
+            "-170141183460469231731687303715884105728",
+        ),
+        (Integer::U8(u8::MAX), "255"),
+        (Integer::U16(u16::MAX), "65535"),
+        (Integer::U32(u32::MAX), "4294967295"),
+        (Integer::U64(u64::MAX), "18446744073709551615"),
+        (
+            Integer::U128(u128::MAX),
+            "340282366920938463463374607431768211455",
+        ),
+        (Integer::U8(0), "0"),
+    ];
+
+    for (integer, string) in cases {
+        assert_eq!(integer.to_string(), string);
+        assert_eq!(string.parse::<Integer>(), Ok(integer));
+    }
+}
+
+#[test]
+fn test_parse_narrowest() {
+    assert_eq!("127".parse(), Ok(Integer::U8(127)));
+    assert_eq!("256".parse(), Ok(Integer::U16(256)));
+    assert_eq!("+7".parse(), Ok(Integer::U8(7)));
+    assert_eq!("-0".parse(), Ok(Integer::U8(0)));
+    assert_eq!("-1".parse(), Ok(Integer::I8(-1)));
+    assert_eq!("-129".parse(), Ok(Integer::I16(-129)));
+    assert_eq!("-32769".parse(), Ok(Integer::I32(-32769)));
+    assert_eq!("-2147483649".parse(), Ok(Integer::I64(-2147483649)));
+    assert_eq!(
+        (i128::from(i64::MIN) - 1).to_string().parse(),
+        Ok(Integer::I128(i128::from(i64::MIN) - 1))
+    );
+    assert_eq!(
+        (u128::from(u64::MAX) + 1).to_string().parse(),
+        Ok(Integer::U128(u128::from(u64::MAX) + 1))
+    );
+
+    // Widths are not preserved, since the narrowest variant is used.
+    assert_eq!(Integer::U64(42).to_string().parse(), Ok(Integer::U8(42)));
+    assert_eq!(Integer::I64(42).to_string().parse(), Ok(Integer::U8(42)));
+}
+
+#[test]
+fn test_parse_errors() {
+    assert_eq!(
+        "340282366920938463463374607431768211456".parse::<Integer>(),
+        Err(Error::IntegerOverflow("u128"))
+    );
+    assert_eq!(
+        "-170141183460469231731687303715884105729".parse::<Integer>(),
+        Err(Error::IntegerOverflow("i128"))
+    );
+
+    for garbage in ["", "-", "+", "abc", "1.5", "1e3", " 1", "1 ", "--1", "0x10"] {
+        assert_eq!(
+            garbage.parse::<Integer>(),
+            Err(Error::InvalidInteger(garbage.into())),
+            "{:?}",
+            garbage
+        );
+    }
+
+    assert_eq!(
+        Error::InvalidInteger("abc".into()).to_string(),
+        "invalid integer `abc`"
+    );
+}
+
+#[test]
+fn test_display_formatting() {
+    assert_eq!(format!("{:>5}", Integer::U8(42)), "   42");
+    assert_eq!(format!("{:+}", Integer::I64(42)), "+42");
+}
+
+/// Keys built from pointer-sized integers holding values which are
+/// representable on both 32-bit and 64-bit targets must be identical, since
+/// they would otherwise differ between platforms.
+#[test]
+fn test_pointer_width_matrix() {
+    #[derive(serde_derive::Serialize)]
+    struct Record<T> {
+        len: T,
+        items: Vec<T>,
+    }
+
+    // Values a 32-bit `usize` can hold, next to their 64-bit counterparts.
+    let unsigned = [
+        (0u32 as usize, 0u64),
+        (1u32 as usize, 1u64),
+        (u32::MAX as usize, u64::from(u32::MAX)),
+    ];
+
+    for (native, wide) in unsigned {
+        let key = to_key(&native).unwrap();
+        assert_eq!(key, Key::Integer(Integer::U64(wide)));
+        assert_eq!(key, Key::from(native));
+
+        let native = to_key(&Record {
+            len: native,
+            items: vec![native; 2],
+        })
+        .unwrap();
+
+        let wide = to_key(&Record {
+            len: wide,
+            items: vec![wide; 2],
+        })
+        .unwrap();
+
+        assert_eq!(native, wide);
+        assert_eq!(native.to_bytes(), wide.to_bytes());
+        assert_eq!(native.to_id(), wide.to_id());
+    }
+
+    let signed = [
+        (i32::MIN as isize, i64::from(i32::MIN)),
+        (-1i32 as isize, -1i64),
+        (i32::MAX as isize, i64::from(i32::MAX)),
+    ];
+
+    for (native, wide) in signed {
+        let key = to_key(&native).unwrap();
+        assert_eq!(key, Key::Integer(Integer::I64(wide)));
+        assert_eq!(key.to_bytes(), to_key(&wide).unwrap().to_bytes());
+        assert_eq!(key, Key::from(native));
+        assert_eq!(key.to_id(), to_key(&wide).unwrap().to_id());
+    }
+}
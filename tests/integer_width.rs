@@ -0,0 +1,166 @@
+use std::convert::TryFrom;
+use std::fmt;
+
+use serde::de;
+use serde_derive::Deserialize;
+use serde_hashkey::{from_key, from_key_owned, key, to_key, Error, Integer, Key};
+
+/// An integer which only accepts being visited as exactly its own type.
+#[derive(Debug, PartialEq)]
+struct Strict<T>(T);
+
+macro_rules! strict {
+    ($($ty:ty => $method:ident, $visit:ident;)*) => {
+        $(
+            impl<'de> de::Deserialize<'de> for Strict<$ty> {
+                fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+                where
+                    D: de::Deserializer<'de>,
+                {
+                    struct Visitor;
+
+                    impl de::Visitor<'_> for Visitor {
+                        type Value = Strict<$ty>;
+
+                        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                            f.write_str(stringify!($ty))
+                        }
+
+                        fn $visit<E>(self, v: $ty) -> Result<Self::Value, E> {
+                            Ok(Strict(v))
+                        }
+                    }
+
+                    deserializer.$method(Visitor)
+                }
+            }
+        )*
+    };
+}
+
+strict! {
+    i8 => deserialize_i8, visit_i8;
+    i16 => deserialize_i16, visit_i16;
+    i32 => deserialize_i32, visit_i32;
+    i64 => deserialize_i64, visit_i64;
+    i128 => deserialize_i128, visit_i128;
+    u8 => deserialize_u8, visit_u8;
+    u16 => deserialize_u16, visit_u16;
+    u32 => deserialize_u32, visit_u32;
+    u64 => deserialize_u64, visit_u64;
+    u128 => deserialize_u128, visit_u128;
+}
+
+/// Deserialize the given key both by reference and by value.
+fn strict<T>(key: &Key) -> Result<T, Error>
+where
+    T: fmt::Debug + PartialEq + de::DeserializeOwned,
+{
+    let value = from_key::<T, _>(key);
+    assert_eq!(value, from_key_owned::<T, _>(key.clone()));
+    value
+}
+
+#[test]
+fn test_widening() -> Result<(), Error> {
+    assert_eq!(strict::<Strict<u64>>(&key!(5u8))?, Strict(5));
+    assert_eq!(
+        strict::<Strict<u128>>(&key!(u64::MAX))?,
+        Strict(u128::from(u64::MAX))
+    );
+    assert_eq!(strict::<Strict<i64>>(&key!(-5i8))?, Strict(-5));
+    assert_eq!(
+        strict::<Strict<i128>>(&key!(i64::MIN))?,
+        Strict(i128::from(i64::MIN))
+    );
+    assert_eq!(
+        strict::<Strict<i32>>(&key!(u16::MAX))?,
+        Strict(i32::from(u16::MAX))
+    );
+    Ok(())
+}
+
+#[test]
+fn test_narrowing() -> Result<(), Error> {
+    assert_eq!(strict::<Strict<u8>>(&key!(5u64))?, Strict(5));
+    assert_eq!(
+        strict::<Strict<u32>>(&key!(u128::from(u32::MAX)))?,
+        Strict(u32::MAX)
+    );
+    assert_eq!(strict::<Strict<i8>>(&key!(-1i64))?, Strict(-1));
+    assert_eq!(
+        strict::<Strict<i8>>(&key!(i128::from(i8::MIN)))?,
+        Strict(i8::MIN)
+    );
+    assert_eq!(strict::<Strict<u8>>(&key!(255i32))?, Strict(255));
+
+    // Plain integer types are converted the same way.
+    assert_eq!(strict::<u8>(&key!(5u64))?, 5);
+    assert_eq!(strict::<i16>(&key!(-300i128))?, -300);
+    Ok(())
+}
+
+#[test]
+fn test_out_of_range() {
+    let error = strict::<Strict<u8>>(&key!(300u64)).unwrap_err();
+    assert_eq!(error, Error::IntegerOverflow("u8"));
+    assert_eq!(error.to_string(), "integer out of range for u8");
+
+    let error = strict::<i8>(&key!(-129i64)).unwrap_err();
+    assert_eq!(error, Error::IntegerOverflow("i8"));
+
+    let error = strict::<u64>(&key!(u128::MAX)).unwrap_err();
+    assert_eq!(error, Error::IntegerOverflow("u64"));
+
+    // Deserializing reports the same error as converting.
+    assert_eq!(Err(error), u64::try_from(Integer::U128(u128::MAX)));
+}
+
+#[test]
+fn test_sign_mismatch() -> Result<(), Error> {
+    let error = strict::<Strict<u32>>(&key!(-1i64)).unwrap_err();
+    assert_eq!(error, Error::IntegerOverflow("u32"));
+
+    let error = strict::<i8>(&key!(128u8)).unwrap_err();
+    assert_eq!(error, Error::IntegerOverflow("i8"));
+
+    assert_eq!(strict::<Strict<i8>>(&key!(127u8))?, Strict(127));
+    assert_eq!(strict::<Strict<u16>>(&key!(7i128))?, Strict(7));
+    Ok(())
+}
+
+#[test]
+fn test_containers() -> Result<(), Error> {
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Point {
+        x: Strict<u8>,
+        y: Strict<i16>,
+    }
+
+    let key = to_key(&serde_json::json!({ "x": 1, "y": -2 }))?;
+    assert_eq!(
+        strict::<Point>(&key)?,
+        Point {
+            x: Strict(1),
+            y: Strict(-2)
+        }
+    );
+
+    // Elements of compact sequences are converted as well.
+    let key = to_key(&vec![1u64, 2, 3])?;
+    assert!(matches!(key, Key::IntegerSeq(..)));
+    assert_eq!(
+        strict::<Vec<Strict<u8>>>(&key)?,
+        vec![Strict(1), Strict(2), Strict(3)]
+    );
+
+    let key = to_key(&vec![1u64, 300])?;
+    assert_eq!(
+        strict::<Vec<u8>>(&key).unwrap_err(),
+        Error::IntegerOverflow("u8")
+    );
+
+    // Values which aren't integers are left for the visitor to reject.
+    assert!(strict::<Strict<u8>>(&to_key(&"1")?).is_err());
+    Ok(())
+}
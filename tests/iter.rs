@@ -0,0 +1,97 @@
+use std::collections::BTreeMap;
+
+use serde_derive::Serialize;
+use serde_hashkey::{
+    to_key, to_key_from_iter, to_key_from_pairs, Error, FloatWidth, Key, KeyPath, PathSegment,
+    RejectFloatPolicy,
+};
+
+#[derive(Debug, Clone, Serialize)]
+struct Row {
+    id: u32,
+    name: &'static str,
+}
+
+fn rows() -> Vec<Row> {
+    vec![
+        Row { id: 1, name: "a" },
+        Row { id: 2, name: "b" },
+        Row { id: 3, name: "c" },
+    ]
+}
+
+fn f32(value: f64) -> Error {
+    Error::UnsupportedFloat {
+        width: FloatWidth::F32,
+        value,
+    }
+}
+
+fn f64(value: f64) -> Error {
+    Error::UnsupportedFloat {
+        width: FloatWidth::F64,
+        value,
+    }
+}
+
+fn rejected(path: Vec<PathSegment>, error: Error) -> Error {
+    Error::FloatRejected {
+        path: KeyPath::from(path),
+        error: Box::new(error),
+    }
+}
+
+#[test]
+fn test_iter_equivalence() -> Result<(), Error> {
+    let key: Key = to_key_from_iter(rows())?;
+    assert_eq!(key, to_key(&rows())?);
+
+    let key: Key = to_key_from_iter(rows().into_iter().map(|row| row.id))?;
+    assert_eq!(key, to_key(&vec![1u32, 2, 3])?);
+
+    let key: Key = to_key_from_iter(Vec::<u32>::new())?;
+    assert_eq!(key, to_key(&Vec::<u32>::new())?);
+    Ok(())
+}
+
+#[test]
+fn test_pairs_equivalence() -> Result<(), Error> {
+    let map = rows()
+        .into_iter()
+        .map(|row| (row.name, row.id))
+        .collect::<BTreeMap<_, _>>();
+
+    let key: Key = to_key_from_pairs(map.iter())?;
+    assert_eq!(key, to_key(&map)?);
+    Ok(())
+}
+
+#[test]
+fn test_error_index() {
+    let result: Result<Key, _> = to_key_from_iter(vec![Some(1.0f64), None, Some(2.0)]);
+    assert_eq!(result, Err(rejected(vec![PathSegment::Index(0)], f64(1.0))));
+
+    let items = vec![(1u32, 0.0f32), (2u32, 1.0f32)];
+    let result: Result<Key, _> = to_key_from_pairs(items.into_iter().skip(1));
+    assert_eq!(result, Err(rejected(vec![PathSegment::Entry(0)], f32(1.0))));
+
+    let items: Vec<(u32, Option<f32>)> = vec![(1, None), (2, Some(1.0))];
+    let result: Result<Key, _> = to_key_from_pairs(items);
+    let error = result.unwrap_err();
+    assert_eq!(error, rejected(vec![PathSegment::Entry(1)], f32(1.0)));
+    assert_eq!(error.to_string(), "unsupported float f32 = 1 at {1}");
+
+    // Paths are the same as when serializing the collected items.
+    let items = vec![("a", vec![0.5f32])];
+    let result: Result<Key, _> = to_key_from_pairs(items.clone());
+    assert_eq!(
+        result,
+        Err(to_key(&items.into_iter().collect::<BTreeMap<_, _>>()).unwrap_err())
+    );
+    assert_eq!(
+        to_key_from_iter::<_, _, RejectFloatPolicy>(vec![vec![0.5f32]])
+            .unwrap_err()
+            .to_string(),
+        "unsupported float f32 = 0.5 at [0][0]"
+    );
+}
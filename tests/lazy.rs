@@ -0,0 +1,73 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use serde_derive::Serialize;
+use serde_hashkey::{static_key, to_key, Error, Key, LazyKey};
+
+#[derive(Serialize)]
+struct Version {
+    name: &'static str,
+    version: u32,
+}
+
+static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+static COUNTED: LazyKey = LazyKey::new(|| {
+    CALLS.fetch_add(1, Ordering::SeqCst);
+    to_key(&Version {
+        name: "schema",
+        version: 2,
+    })
+});
+
+fn schema_version() -> &'static Key {
+    static_key!("schema_version").unwrap()
+}
+
+#[test]
+fn test_static_key() -> Result<(), Error> {
+    assert_eq!(*schema_version(), to_key("schema_version")?);
+    assert!(std::ptr::eq(schema_version(), schema_version()));
+
+    let key = static_key!(Version {
+        name: "schema",
+        version: 2,
+    })
+    .unwrap();
+
+    assert_eq!(
+        *key,
+        to_key(&Version {
+            name: "schema",
+            version: 2,
+        })?
+    );
+    Ok(())
+}
+
+#[test]
+fn test_threads() -> Result<(), Error> {
+    let handles = (0..8)
+        .map(|_| thread::spawn(|| COUNTED.get().unwrap() as *const Key as usize))
+        .collect::<Vec<_>>();
+
+    let expected = COUNTED.get().unwrap() as *const Key as usize;
+
+    for handle in handles {
+        assert_eq!(handle.join().unwrap(), expected);
+    }
+
+    assert_eq!(CALLS.load(Ordering::SeqCst), 1);
+    Ok(())
+}
+
+#[test]
+fn test_error() {
+    fn float() -> Result<&'static Key, &'static Error> {
+        static_key!(1.5f64)
+    }
+
+    let error = float().unwrap_err();
+    assert!(std::ptr::eq(error, float().unwrap_err()));
+    assert_eq!(error.to_string(), "unsupported float f64 = 1.5");
+}
@@ -0,0 +1,123 @@
+use std::collections::BTreeMap;
+
+use serde_derive::Serialize;
+use serde_hashkey::{key, to_key, Error, Key};
+
+#[derive(Serialize)]
+struct Author {
+    name: &'static str,
+    age: u32,
+}
+
+#[derive(Serialize)]
+struct Book {
+    title: &'static str,
+    authors: Vec<Author>,
+    tags: Vec<&'static str>,
+    pages: Vec<u32>,
+    subtitle: Option<&'static str>,
+    sequel: bool,
+}
+
+#[test]
+fn test_scalars() {
+    assert_eq!(key!(null), Key::Null);
+    assert_eq!(key!(()), Key::Unit);
+    assert_eq!(key!(true), Key::Bool(true));
+    assert_eq!(key!("a"), Key::String("a".into()));
+    assert_eq!(key!(42u8), Key::from(42u8));
+    assert_eq!(key!(-1), Key::from(-1i32));
+    assert_eq!(key!(b"ab"), Key::from(b"ab".to_vec()));
+}
+
+#[test]
+fn test_nested() -> Result<(), Error> {
+    let key = key!({
+        "title": "Birds of a feather",
+        "authors": [
+            { "name": "Noah", "age": 42u32 },
+            { "name": "Jane", "age": 41u32, },
+        ],
+        "tags": ["birds"],
+        "pages": [1u32, 2u32, 3u32],
+        "subtitle": null,
+        "sequel": false,
+    });
+
+    let expected = to_key(&Book {
+        title: "Birds of a feather",
+        authors: vec![
+            Author {
+                name: "Noah",
+                age: 42,
+            },
+            Author {
+                name: "Jane",
+                age: 41,
+            },
+        ],
+        tags: vec!["birds"],
+        pages: vec![1, 2, 3],
+        subtitle: None,
+        sequel: false,
+    })?;
+
+    assert_eq!(key, expected);
+    assert!(matches!(key["pages"], Key::IntegerSeq(..)));
+    Ok(())
+}
+
+#[test]
+fn test_empty() -> Result<(), Error> {
+    assert_eq!(key!([]), to_key(&Vec::<u32>::new())?);
+    assert_eq!(key!({}), to_key(&BTreeMap::<u32, u32>::new())?);
+    assert_eq!(key!([[], {}]), Key::Seq(vec![key!([]), key!({})].into()));
+    Ok(())
+}
+
+#[test]
+fn test_interpolation() -> Result<(), Error> {
+    let id = 7u64;
+    let name = String::from("Noah");
+    let inner = key!(["x"]);
+
+    let key = key!({
+        "id": id,
+        "next": id + 1,
+        (name.as_str()): name.clone(),
+        (1u8): inner.clone(),
+        "list": [id, id * 2,],
+    });
+
+    let expected = Key::Map(
+        vec![
+            (key!("id"), Key::from(7u64)),
+            (key!("next"), Key::from(8u64)),
+            (key!("Noah"), key!("Noah")),
+            (Key::from(1u8), to_key(&vec!["x"])?),
+            (key!("list"), to_key(&vec![7u64, 14])?),
+        ]
+        .into(),
+    );
+
+    assert_eq!(key, expected);
+    Ok(())
+}
+
+#[cfg(feature = "ordered-float")]
+#[test]
+fn test_floats() -> Result<(), Error> {
+    use serde_hashkey::{key_float, to_key_with_ordered_float};
+
+    let rating = 4.5f32;
+    let key = key_float!([rating, -1.5f32, f32::INFINITY]);
+    assert_eq!(
+        key,
+        to_key_with_ordered_float(&vec![4.5f32, -1.5, f32::INFINITY])?
+    );
+
+    let key = key_float!({ "a": 1.5, "b": [1u32] });
+    assert_eq!(key["a"], to_key_with_ordered_float(&1.5f64)?);
+    assert_eq!(key["b"], to_key_with_ordered_float(&vec![1u32])?);
+    Ok(())
+}
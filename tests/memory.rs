@@ -0,0 +1,56 @@
+use std::collections::BTreeMap;
+use std::mem;
+
+use serde_hashkey::{key, to_key, Error, Key};
+
+const KEY: usize = mem::size_of::<Key>();
+
+#[test]
+fn test_flat() -> Result<(), Error> {
+    assert_eq!(key!(()).memory_usage(), KEY);
+    assert_eq!(key!(42u64).memory_usage(), KEY);
+    assert_eq!(key!("").memory_usage(), KEY);
+    assert_eq!(to_key(&"a".repeat(1000))?.memory_usage(), KEY + 1000);
+    assert_eq!(key!(b"bytes").memory_usage(), KEY + 5);
+    Ok(())
+}
+
+#[test]
+fn test_map() -> Result<(), Error> {
+    let map = (0..1000u32)
+        .map(|n| (format!("{:04}", n), n))
+        .collect::<BTreeMap<_, _>>();
+
+    let key = to_key(&map)?;
+
+    // The map itself, then for each entry two keys, plus 4 bytes of string
+    // for the key. The integer values are stored inline.
+    assert_eq!(key.memory_usage(), KEY + 1000 * (2 * KEY + 4));
+
+    // A string of the same total length is much smaller.
+    assert!(to_key(&"a".repeat(4000))?.memory_usage() < key.memory_usage() / 10);
+    Ok(())
+}
+
+#[test]
+fn test_nested() -> Result<(), Error> {
+    let key = key!({ "a": ["bc", [1u16, 2u16]] });
+
+    let expected = KEY // root
+        + 2 * KEY // one map entry
+        + 1 // "a"
+        + 2 * KEY // sequence with two elements
+        + 2 // "bc"
+        + 2 * 2; // compact sequence of two u16
+
+    assert_eq!(key.memory_usage(), expected);
+    Ok(())
+}
+
+#[cfg(target_pointer_width = "64")]
+#[test]
+fn test_size() {
+    // Keys stay the same size regardless of which features are enabled.
+    assert_eq!(KEY, 32);
+    assert_eq!(mem::size_of::<Option<Key>>(), 32);
+}
@@ -0,0 +1,50 @@
+#![cfg(feature = "miette")]
+
+use miette7::Diagnostic;
+use serde_derive::Serialize;
+use serde_hashkey::{to_key, Error, Key};
+
+fn code(error: &Error) -> String {
+    error.code().unwrap().to_string()
+}
+
+#[test]
+fn test_float_rejected() {
+    #[derive(Serialize)]
+    struct Point {
+        x: f32,
+    }
+
+    let error = to_key(&Point { x: 1.0 }).unwrap_err();
+    assert_eq!(code(&error), "serde_hashkey::float_rejected");
+
+    let help = error.help().unwrap().to_string();
+    assert!(help.contains("to_key_with_ordered_float"), "{}", help);
+}
+
+#[test]
+fn test_codes() {
+    let key: Key = Key::Unit;
+
+    let error = key.clone().flatten("").unwrap_err();
+    assert_eq!(code(&error), "serde_hashkey::empty_separator");
+    assert!(error.help().is_some());
+
+    let error = Key::<serde_hashkey::RejectFloatPolicy>::from_bytes(&[0xff]).unwrap_err();
+    assert_eq!(code(&error), "serde_hashkey::invalid_bytes");
+    assert!(error.help().is_some());
+
+    let error = key.to_query_string().unwrap_err();
+    assert_eq!(code(&error), "serde_hashkey::unexpected");
+    assert!(error.help().is_none());
+
+    let error = Error::UnsupportedType("bytes");
+    assert_eq!(code(&error), "serde_hashkey::unsupported_type");
+    assert!(error.help().is_none());
+
+    let error =
+        serde_hashkey::from_key::<u8, _>(&Key::<serde_hashkey::RejectFloatPolicy>::from(300u32))
+            .unwrap_err();
+    assert_eq!(code(&error), "serde_hashkey::integer_overflow");
+    assert!(error.help().is_none());
+}
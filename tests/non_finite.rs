@@ -0,0 +1,170 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use serde_derive::{Deserialize, Serialize};
+use serde_hashkey::{
+    from_key, to_key, to_key_rejecting_non_finite, Error, FiniteFloat, FloatWidth, Key,
+    RejectNonFinitePolicy,
+};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Sensor {
+    name: String,
+    readings: Vec<Reading>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Reading {
+    celsius: f64,
+    humidity: f32,
+}
+
+fn hash(key: &Key<RejectNonFinitePolicy>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn test_finite_roundtrip() -> Result<(), Error> {
+    let sensor = Sensor {
+        name: String::from("attic"),
+        readings: vec![
+            Reading {
+                celsius: 21.5,
+                humidity: 0.4,
+            },
+            Reading {
+                celsius: f64::MAX,
+                humidity: f32::MIN_POSITIVE,
+            },
+        ],
+    };
+
+    let key = to_key_rejecting_non_finite(&sensor)?;
+    assert_eq!(from_key::<Sensor, _>(&key)?, sensor);
+    assert_eq!(
+        Key::<RejectNonFinitePolicy>::from_bytes(&key.to_bytes())?,
+        key.normalize()
+    );
+    Ok(())
+}
+
+#[test]
+fn test_nan_rejected_in_nested_struct() {
+    let sensor = Sensor {
+        name: String::from("attic"),
+        readings: vec![Reading {
+            celsius: f64::NAN,
+            humidity: 0.4,
+        }],
+    };
+
+    let error = to_key_rejecting_non_finite(&sensor).unwrap_err();
+    assert!(matches!(
+        &error,
+        Error::FloatRejected { error, .. } if matches!(
+            **error,
+            Error::NonFiniteFloat {
+                width: FloatWidth::F64,
+                value,
+            } if value.is_nan()
+        )
+    ));
+    assert_eq!(
+        error.to_string(),
+        "non-finite float f64 = NaN at readings[0].celsius"
+    );
+
+    let sensor = Sensor {
+        name: String::from("attic"),
+        readings: vec![Reading {
+            celsius: 1.0,
+            humidity: f32::NAN,
+        }],
+    };
+
+    assert!(matches!(
+        to_key_rejecting_non_finite(&sensor),
+        Err(Error::FloatRejected { error, .. }) if matches!(
+            *error,
+            Error::NonFiniteFloat {
+                width: FloatWidth::F32,
+                ..
+            }
+        )
+    ));
+}
+
+#[test]
+fn test_infinity_rejected() {
+    assert_eq!(
+        to_key_rejecting_non_finite(&f32::INFINITY),
+        Err(Error::NonFiniteFloat {
+            width: FloatWidth::F32,
+            value: f64::INFINITY,
+        })
+    );
+    assert_eq!(
+        to_key_rejecting_non_finite(&f32::NEG_INFINITY),
+        Err(Error::NonFiniteFloat {
+            width: FloatWidth::F32,
+            value: f64::NEG_INFINITY,
+        })
+    );
+    assert_eq!(
+        to_key_rejecting_non_finite(&f64::INFINITY),
+        Err(Error::NonFiniteFloat {
+            width: FloatWidth::F64,
+            value: f64::INFINITY,
+        })
+    );
+    assert_eq!(
+        to_key_rejecting_non_finite(&vec![1.0f64, f64::NEG_INFINITY])
+            .unwrap_err()
+            .to_string(),
+        "non-finite float f64 = -inf at [1]"
+    );
+}
+
+#[test]
+fn test_distinct_from_unsupported_float() {
+    // The default policy rejects every float with a different error.
+    assert!(matches!(
+        to_key(&1.0f64),
+        Err(Error::UnsupportedFloat { .. })
+    ));
+    assert!(matches!(
+        to_key_rejecting_non_finite(&f64::NAN),
+        Err(Error::NonFiniteFloat { .. })
+    ));
+    assert!(to_key_rejecting_non_finite(&1.0f64).is_ok());
+}
+
+#[test]
+fn test_ordering_and_zero() -> Result<(), Error> {
+    let mut floats = vec![1.5f64, -0.5, f64::MAX, f64::MIN, 0.0];
+    let mut keys = floats
+        .iter()
+        .map(to_key_rejecting_non_finite)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    keys.sort();
+    floats.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    let sorted = keys
+        .iter()
+        .map(from_key::<f64, _>)
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(sorted, floats);
+
+    let zero = to_key_rejecting_non_finite(&0.0f64)?;
+    let negative = to_key_rejecting_non_finite(&-0.0f64)?;
+    assert_eq!(zero, negative);
+    assert_eq!(hash(&zero), hash(&negative));
+    assert!(from_key::<f64, _>(&negative)?.is_sign_negative());
+
+    assert!(FiniteFloat::new(f32::NAN).is_err());
+    assert_eq!(FiniteFloat::new(2.5f32)?.get(), 2.5);
+    Ok(())
+}
@@ -0,0 +1,107 @@
+use std::mem;
+
+use serde_hashkey::{key, Key};
+
+fn fixture() -> Key {
+    // Maps whose keys are themselves unsorted maps, and sequences which can
+    // only be compacted once they have been constructed by hand.
+    Key::Seq(
+        vec![
+            Key::Map(
+                vec![
+                    (key!({ "b": 2u32, "a": 1u32 }), key!("x")),
+                    (key!({ "a": 0u32 }), key!({ "d": [], "c": () })),
+                ]
+                .into(),
+            ),
+            Key::Seq(vec![Key::from(1u32), Key::from(2u32)].into()),
+            key!("tail"),
+        ]
+        .into(),
+    )
+}
+
+/// Drop a deeply nested chain of maps without recursing.
+fn unwind(mut key: Key) {
+    while let Key::Map(map) = &mut key {
+        let entries = mem::take(map).into_vec();
+
+        match entries.into_iter().find(|(_, v)| matches!(v, Key::Map(..))) {
+            Some((_, inner)) => key = inner,
+            None => break,
+        }
+    }
+}
+
+#[test]
+fn test_in_place() {
+    let mut key = fixture();
+    key.normalize_in_place();
+
+    let expected = Key::Seq(
+        vec![
+            Key::Map(
+                vec![
+                    (key!({ "a": 0u32 }), key!({ "c": (), "d": [] })),
+                    (key!({ "a": 1u32, "b": 2u32 }), key!("x")),
+                ]
+                .into(),
+            ),
+            key!([1u32, 2u32]),
+            key!("tail"),
+        ]
+        .into(),
+    );
+
+    assert_eq!(key, expected);
+    assert!(matches!(key[1], Key::IntegerSeq(..)));
+    assert_eq!(fixture().normalize(), expected);
+
+    // Normalizing is idempotent.
+    key.normalize_in_place();
+    assert_eq!(key, expected);
+
+    let mut scalar = key!("a");
+    scalar.normalize_in_place();
+    assert_eq!(scalar, key!("a"));
+}
+
+#[test]
+fn test_large() {
+    let entries = (0..100_000u32)
+        .rev()
+        .map(|n| (Key::from(n), Key::Seq(vec![Key::from(n)].into())))
+        .collect::<Vec<_>>();
+
+    let mut key: Key = Key::Map(entries.into());
+    key.normalize_in_place();
+
+    let map = key.iter_map().unwrap().collect::<Vec<_>>();
+    assert_eq!(map.len(), 100_000);
+    assert!(map.windows(2).all(|w| w[0].0 < w[1].0));
+    assert!(map.iter().all(|(_, v)| matches!(v, Key::IntegerSeq(..))));
+}
+
+#[test]
+fn test_deep() {
+    let mut key: Key = key!([2u32, 1u32]);
+
+    for _ in 0..100_000 {
+        key = Key::Map(vec![(key!("b"), Key::Unit), (key!("a"), key)].into());
+    }
+
+    key.normalize_in_place();
+
+    let mut current = &key;
+    let mut depth = 0;
+
+    while let Key::Map(map) = current {
+        assert_eq!(map[0].0, key!("a"));
+        current = &map[0].1;
+        depth += 1;
+    }
+
+    assert_eq!(depth, 100_000);
+    assert_eq!(*current, key!([2u32, 1u32]));
+    unwind(key);
+}
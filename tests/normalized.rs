@@ -0,0 +1,82 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use serde_derive::Serialize;
+use serde_hashkey::{to_key, to_key_normalized, Error, Key, NormalizedKey};
+
+#[derive(Serialize)]
+struct Request {
+    path: &'static str,
+    query: HashMap<&'static str, u32>,
+}
+
+fn query(entries: &[(&'static str, u32)]) -> HashMap<&'static str, u32> {
+    entries.iter().copied().collect()
+}
+
+#[test]
+fn test_different_order() -> Result<(), Error> {
+    let entries = (0..32)
+        .map(|n| (["a", "b", "c", "d"][n % 4], n as u32))
+        .collect::<BTreeMap<_, _>>();
+
+    let forward = Key::Map(
+        entries
+            .iter()
+            .map(|(k, v)| Ok((to_key(k)?, to_key(v)?)))
+            .collect::<Result<Vec<_>, Error>>()?
+            .into(),
+    );
+
+    let backward = Key::Map(
+        entries
+            .iter()
+            .rev()
+            .map(|(k, v)| Ok((to_key(k)?, to_key(v)?)))
+            .collect::<Result<Vec<_>, Error>>()?
+            .into(),
+    );
+
+    assert_ne!(forward, backward);
+    assert_eq!(forward.into_normalized(), NormalizedKey::from(backward));
+    Ok(())
+}
+
+#[test]
+fn test_nested_cache() -> Result<(), Error> {
+    let mut seen = HashSet::new();
+
+    for _ in 0..8 {
+        // Hash maps iterate in a different order for each instance.
+        let key = to_key_normalized(&Request {
+            path: "/books",
+            query: query(&[("page", 1), ("limit", 10), ("offset", 0), ("sort", 2)]),
+        })?;
+
+        seen.insert(key);
+    }
+
+    assert_eq!(seen.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_conversions() -> Result<(), Error> {
+    let key = to_key(&query(&[("b", 2), ("a", 1)]))?;
+    let normalized = NormalizedKey::new(key.clone());
+
+    assert_eq!(*normalized, key.clone().normalize());
+    assert_eq!(normalized.as_ref(), &key.clone().normalize());
+    assert_eq!(Key::from(normalized.clone()), key.clone().normalize());
+    assert_eq!(normalized.clone().into_inner(), key.normalize());
+
+    // Converting back and forth normalizes again.
+    let mut key = Key::from(normalized.clone());
+    key.extend(vec![(to_key("0")?, Key::Unit)]);
+    let renormalized = NormalizedKey::from(key);
+    assert_eq!(
+        renormalized.iter_map().unwrap().next().unwrap().0,
+        to_key("0")?
+    );
+    assert_ne!(renormalized, normalized);
+    Ok(())
+}
@@ -0,0 +1,92 @@
+#![cfg(feature = "ordered-float")]
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use serde_hashkey::{to_key_with_ordered_float, Error, Key, NumericKey, OrderedFloatPolicy};
+
+fn numeric<T>(value: &T) -> Result<NumericKey<OrderedFloatPolicy>, Error>
+where
+    T: serde::Serialize,
+{
+    Ok(NumericKey(to_key_with_ordered_float(value)?))
+}
+
+fn hash<T>(value: &T) -> u64
+where
+    T: Hash,
+{
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[track_caller]
+fn assert_same(a: NumericKey<OrderedFloatPolicy>, b: NumericKey<OrderedFloatPolicy>) {
+    assert_eq!(a, b);
+    assert_eq!(hash(&a), hash(&b));
+}
+
+#[test]
+fn test_integers_and_floats() -> Result<(), Error> {
+    assert_same(numeric(&1u8)?, numeric(&1u64)?);
+    assert_same(numeric(&1u8)?, numeric(&1.0f32)?);
+    assert_same(numeric(&1i128)?, numeric(&1.0f64)?);
+    assert_same(numeric(&1.5f32)?, numeric(&1.5f64)?);
+    assert_same(numeric(&-7i8)?, numeric(&-7.0f64)?);
+    assert_same(numeric(&i64::MIN)?, numeric(&(i64::MIN as f64))?);
+
+    assert_ne!(numeric(&0u32)?, numeric(&0.5f64)?);
+    assert_ne!(numeric(&1u32)?, numeric(&-1i32)?);
+    assert_ne!(numeric(&1u32)?, numeric(&"1")?);
+    assert_ne!(numeric(&u128::MAX)?, numeric(&(u128::MAX as f64))?);
+    Ok(())
+}
+
+#[test]
+fn test_exactly_representable_boundary() -> Result<(), Error> {
+    const TWO_53: u64 = 1 << 53;
+
+    assert_same(numeric(&TWO_53)?, numeric(&(TWO_53 as f64))?);
+    // 2^53 + 1 is the first integer which can't be represented by an f64, so
+    // the conversion rounds down to 2^53 which must not be equal.
+    assert_ne!(numeric(&(TWO_53 + 1))?, numeric(&((TWO_53 + 1) as f64))?);
+    assert_same(numeric(&(TWO_53 + 2))?, numeric(&((TWO_53 + 2) as f64))?);
+    assert_ne!(
+        numeric(&(-(TWO_53 as i64) - 1))?,
+        numeric(&(-(TWO_53 as f64) - 1.0))?
+    );
+    Ok(())
+}
+
+#[test]
+fn test_zero_and_nan() -> Result<(), Error> {
+    assert_same(numeric(&0.0f64)?, numeric(&-0.0f64)?);
+    assert_same(numeric(&0u8)?, numeric(&-0.0f32)?);
+    assert_same(numeric(&0i64)?, numeric(&0.0f32)?);
+
+    assert_same(numeric(&f64::NAN)?, numeric(&f64::NAN)?);
+    assert_same(numeric(&f32::NAN)?, numeric(&-f64::NAN)?);
+    assert_ne!(numeric(&f64::NAN)?, numeric(&0u32)?);
+    assert_ne!(numeric(&f64::NAN)?, numeric(&f64::INFINITY)?);
+    assert_ne!(numeric(&f64::INFINITY)?, numeric(&f64::NEG_INFINITY)?);
+    Ok(())
+}
+
+#[test]
+fn test_nested() -> Result<(), Error> {
+    let a = numeric(&vec![(String::from("id"), 42u32)])?;
+    let b = NumericKey(Key::Seq(
+        vec![Key::Seq(
+            vec![
+                Key::String("id".into()),
+                to_key_with_ordered_float(&42.0f64)?,
+            ]
+            .into(),
+        )]
+        .into(),
+    ));
+
+    assert_same(a, b);
+    Ok(())
+}
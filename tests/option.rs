@@ -0,0 +1,154 @@
+use std::fmt::Debug;
+
+use serde::de::DeserializeOwned;
+use serde::ser::Serialize;
+use serde_derive::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use serde_hashkey::{from_key, from_key_owned, hash_value, key, to_key, value_eq_key, Error, Key};
+
+fn hash(key: &Key) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn hash_of<T>(value: &T) -> Result<u64, Error>
+where
+    T: ?Sized + Serialize,
+{
+    let mut hasher = DefaultHasher::new();
+    hash_value(value, &mut hasher)?;
+    Ok(hasher.finish())
+}
+
+fn roundtrip<T>(value: T) -> Result<Key, Error>
+where
+    T: Debug + PartialEq + Serialize + DeserializeOwned,
+{
+    let key = to_key(&value)?;
+    assert_eq!(from_key::<T, _>(&key)?, value);
+    assert_eq!(from_key::<Key, _>(&key)?, key);
+    assert_eq!(Key::from_bytes(&key.to_bytes())?, key);
+    Ok(key)
+}
+
+#[test]
+fn test_unit_option() -> Result<(), Error> {
+    let none = roundtrip(None::<()>)?;
+    let some = roundtrip(Some(()))?;
+
+    assert_eq!(none, Key::Null);
+    assert_eq!(some, Key::Unit);
+    assert_ne!(none, some);
+    Ok(())
+}
+
+#[test]
+fn test_nested_option() -> Result<(), Error> {
+    assert_eq!(roundtrip(None::<Option<u32>>)?, Key::Null);
+    assert_eq!(roundtrip(Some(Some(42u32)))?, key!(42u32));
+
+    // An inner `None` is wrapped so that it can be told apart from an outer
+    // one.
+    assert_eq!(roundtrip(Some(None::<u32>))?, key!([null]));
+    assert_eq!(from_key::<Option<Option<u32>>, _>(&key!(null))?, None);
+    assert_eq!(
+        from_key::<Option<Option<u32>>, _>(&key!([null]))?,
+        Some(None)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_nested_option_inhabitants() -> Result<(), Error> {
+    let values: [(Option<Option<Option<u8>>>, Key); 5] = [
+        (None, key!(null)),
+        (Some(None), key!([null])),
+        (Some(Some(None)), key!([[null]])),
+        (Some(Some(Some(0))), key!(0u8)),
+        (Some(Some(Some(42))), key!(42u8)),
+    ];
+
+    for (value, expected) in &values {
+        let key = roundtrip(*value)?;
+        assert_eq!(key, *expected, "{:?}", value);
+        assert_eq!(
+            from_key_owned::<Option<Option<Option<u8>>>, _>(key.clone())?,
+            *value
+        );
+        assert_eq!(hash_of(value)?, hash(&key));
+        assert!(value_eq_key(value, &key)?);
+
+        for (other, _) in &values {
+            assert_eq!(value_eq_key(other, &key)?, other == value);
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_nested_option_containers() -> Result<(), Error> {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Patch {
+        age: Option<Option<u32>>,
+        name: Option<Option<String>>,
+    }
+
+    // `Some(None)` clears a field, while `None` leaves it alone.
+    let patch = Patch {
+        age: None,
+        name: Some(None),
+    };
+    assert_eq!(roundtrip(patch)?, key!({ "age": null, "name": [null] }));
+
+    // Sequences which look like a nested `None` are wrapped as well when
+    // they're inside of a `Some`.
+    assert_eq!(roundtrip(vec![None::<u32>])?, key!([null]));
+    assert_eq!(roundtrip(Some(vec![None::<u32>]))?, key!([[null]]));
+    assert_eq!(roundtrip(Some((None::<u32>,)))?, key!([[null]]));
+    assert_eq!(
+        roundtrip(Some(vec![None::<u32>, None]))?,
+        key!([null, null])
+    );
+
+    let value = vec![None, Some(None), Some(Some(1u32))];
+    let key = roundtrip(value.clone())?;
+    assert_eq!(key, key!([null, [null], 1u32]));
+    assert_eq!(hash_of(&value)?, hash(&key));
+    assert!(value_eq_key(&value, &key)?);
+    Ok(())
+}
+
+#[test]
+fn test_unit_struct() -> Result<(), Error> {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Marker;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Wrapper {
+        marker: Option<Marker>,
+    }
+
+    assert_eq!(roundtrip(Marker)?, Key::Unit);
+    assert_eq!(roundtrip(Some(Marker))?, Key::Unit);
+    assert_eq!(roundtrip(None::<Marker>)?, Key::Null);
+
+    let some = roundtrip(Wrapper {
+        marker: Some(Marker),
+    })?;
+    let none = roundtrip(Wrapper { marker: None })?;
+    assert_eq!(some, key!({ "marker": () }));
+    assert_eq!(none, key!({ "marker": null }));
+    Ok(())
+}
+
+#[test]
+fn test_order() {
+    // Null sorts before every other key, like `None` sorts before `Some`.
+    let mut keys = vec![key!(false), key!(()), key!(null), key!([])];
+    keys.sort();
+    assert_eq!(keys, vec![key!(null), key!(()), key!(false), key!([])]);
+}
@@ -0,0 +1,125 @@
+#![cfg(feature = "ordered-float")]
+
+use serde_derive::{Deserialize, Serialize};
+use serde_hashkey::{
+    from_key, to_key_with_ordered_float, Float, Key, OrderedFloat, OrderedFloatPolicy,
+};
+
+#[test]
+fn test_deserialize() -> Result<(), serde_json::Error> {
+    assert_eq!(
+        serde_json::from_str::<OrderedFloat<f64>>("1.5")?,
+        OrderedFloat(1.5)
+    );
+    assert_eq!(
+        serde_json::from_str::<OrderedFloat<f32>>("-2")?,
+        OrderedFloat(-2.0)
+    );
+    assert!(serde_json::from_str::<OrderedFloat<f64>>("\"x\"").is_err());
+    Ok(())
+}
+
+#[test]
+fn test_default() {
+    assert_eq!(OrderedFloat::<f64>::default(), OrderedFloat(0.0));
+    assert_eq!(OrderedFloat::<f32>::default(), OrderedFloat(0.0));
+}
+
+#[test]
+fn test_struct_roundtrip() -> Result<(), Box<dyn std::error::Error>> {
+    #[derive(Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+    struct Measurement {
+        value: OrderedFloat<f64>,
+        error: OrderedFloat<f32>,
+    }
+
+    let measurement = Measurement {
+        value: OrderedFloat(42.5),
+        error: OrderedFloat(0.25),
+    };
+
+    let json = serde_json::to_string(&measurement)?;
+    assert_eq!(json, r#"{"value":42.5,"error":0.25}"#);
+    assert_eq!(serde_json::from_str::<Measurement>(&json)?, measurement);
+
+    let key = to_key_with_ordered_float(&measurement)?;
+    assert_eq!(key["value"], Key::Float(Float::F64(OrderedFloat(42.5))));
+    assert_eq!(from_key::<Measurement, _>(&key)?, measurement);
+
+    assert_eq!(
+        Measurement::default(),
+        Measurement {
+            value: OrderedFloat(0.0),
+            error: OrderedFloat(0.0),
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn test_embedded_key() -> Result<(), serde_json::Error> {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Entry {
+        key: Key<OrderedFloatPolicy>,
+    }
+
+    let entry: Entry = serde_json::from_str(r#"{"key": [1.5, "x"]}"#)?;
+    assert_eq!(entry.key[0], Key::Float(Float::F64(OrderedFloat(1.5))));
+    assert_eq!(entry.key[1], Key::String("x".into()));
+
+    let json = serde_json::to_string(&entry)?;
+    assert_eq!(serde_json::from_str::<Entry>(&json)?, entry);
+    Ok(())
+}
+
+#[test]
+fn test_conversions() -> Result<(), serde_hashkey::Error> {
+    let key = to_key_with_ordered_float(&42.0f64)?;
+    assert_eq!(key, Key::Float(Float::F64(42.0.into())));
+
+    let float: OrderedFloat<f32> = OrderedFloat::from(1.5f32);
+    assert_eq!(f32::from(float), 1.5);
+    assert_eq!(f64::from(OrderedFloat(2.5f64)), 2.5);
+
+    let converted: f64 = OrderedFloat(0.5f64).into();
+    assert_eq!(converted, 0.5);
+    Ok(())
+}
+
+#[test]
+fn test_deref() -> Result<(), serde_hashkey::Error> {
+    let key = to_key_with_ordered_float(&41.0f64)?;
+
+    let float = match key {
+        Key::Float(Float::F64(float)) => float,
+        _ => panic!("expected a float"),
+    };
+
+    assert_eq!(*float + 1.0, 42.0);
+    assert!(float.is_finite());
+    assert_eq!(float.abs(), 41.0);
+
+    let mut float = float;
+    *float *= 2.0;
+    assert_eq!(float, OrderedFloat(82.0));
+    Ok(())
+}
+
+#[test]
+fn test_primitive_eq() {
+    assert_eq!(OrderedFloat(1.5f32), 1.5f32);
+    assert_eq!(OrderedFloat(1.5f64), 1.5f64);
+    assert_ne!(OrderedFloat(1.5f64), 2.5f64);
+
+    // Same semantics as comparing ordered floats.
+    assert_eq!(OrderedFloat(f64::NAN), f64::NAN);
+    assert_eq!(OrderedFloat(f32::NAN), f32::NAN);
+}
+
+#[test]
+fn test_display() {
+    assert_eq!(OrderedFloat(1.5f64).to_string(), "1.5");
+    assert_eq!(OrderedFloat(-0.25f32).to_string(), "-0.25");
+    assert_eq!(format!("{:.2}", OrderedFloat(1.0f64)), "1.00");
+    assert_eq!(format!("{:?}", OrderedFloat(1.0f64)), "1.0");
+}
@@ -0,0 +1,108 @@
+use serde_derive::{Deserialize, Serialize};
+use serde_hashkey::{from_key, to_key, Error, Integer, Key};
+
+/// The enum as written by a newer producer.
+#[derive(Debug, Serialize)]
+enum NewKind {
+    A,
+    B(u32),
+    C,
+    D(u32),
+    E(u32, String),
+    F { name: String },
+}
+
+/// The enum as read by an older consumer.
+#[derive(Debug, PartialEq, Deserialize)]
+enum OldKind {
+    A,
+    B(u32),
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(field_identifier, rename_all = "lowercase")]
+enum Field {
+    Name,
+    Age,
+    #[serde(other)]
+    Other,
+}
+
+#[test]
+fn test_known_variants() -> Result<(), Error> {
+    assert_eq!(from_key::<OldKind, _>(&to_key(&NewKind::A)?)?, OldKind::A);
+    assert_eq!(
+        from_key::<OldKind, _>(&to_key(&NewKind::B(42))?)?,
+        OldKind::B(42)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_unknown_variants() -> Result<(), Error> {
+    let values = [
+        NewKind::C,
+        NewKind::D(42),
+        NewKind::E(42, String::from("hello")),
+        NewKind::F {
+            name: String::from("hello"),
+        },
+    ];
+
+    for value in values {
+        let key = to_key(&value)?;
+        assert_eq!(
+            from_key::<OldKind, _>(&key)?,
+            OldKind::Unknown,
+            "{:?}",
+            value
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_unknown_variants_in_containers() -> Result<(), Error> {
+    let key = to_key(&vec![NewKind::A, NewKind::D(1), NewKind::B(2), NewKind::C])?;
+    let values: Vec<OldKind> = from_key(&key)?;
+    assert_eq!(
+        values,
+        vec![
+            OldKind::A,
+            OldKind::Unknown,
+            OldKind::B(2),
+            OldKind::Unknown
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_integer_variants() -> Result<(), Error> {
+    let key: Key = Key::Integer(Integer::U32(0));
+    assert_eq!(from_key::<OldKind, _>(&key)?, OldKind::A);
+
+    let key: Key = Key::Integer(Integer::U32(7));
+    assert_eq!(from_key::<OldKind, _>(&key)?, OldKind::Unknown);
+
+    let key: Key = Key::Map(vec![(Key::Integer(Integer::U32(7)), Key::from(42u32))].into());
+    assert_eq!(from_key::<OldKind, _>(&key)?, OldKind::Unknown);
+
+    // Homogeneous sequences of variant indexes are stored compactly.
+    let key: Key = to_key(&vec![0u32, 5, 0])?;
+    assert!(matches!(key, Key::IntegerSeq(..)));
+    let values: Vec<OldKind> = from_key(&key)?;
+    assert_eq!(values, vec![OldKind::A, OldKind::Unknown, OldKind::A]);
+    Ok(())
+}
+
+#[test]
+fn test_field_identifier() -> Result<(), Error> {
+    assert_eq!(from_key::<Field, _>(&to_key(&"name")?)?, Field::Name);
+    assert_eq!(from_key::<Field, _>(&to_key(&"age")?)?, Field::Age);
+    assert_eq!(from_key::<Field, _>(&to_key(&"email")?)?, Field::Other);
+    Ok(())
+}
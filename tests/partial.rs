@@ -0,0 +1,224 @@
+use std::collections::BTreeMap;
+
+use serde::ser::{Error as _, Serialize, Serializer};
+use serde_derive::Serialize;
+use serde_hashkey::{
+    key, to_key, to_key_with_partial, Error, FloatWidth, Key, PartialError, PathSegment,
+};
+
+#[derive(Serialize)]
+struct Author {
+    name: &'static str,
+    age: u32,
+}
+
+#[derive(Serialize)]
+struct Book {
+    title: &'static str,
+    author: Author,
+    rating: f32,
+}
+
+#[derive(Serialize)]
+struct Library {
+    name: &'static str,
+    books: Vec<Book>,
+    open: bool,
+}
+
+fn book(title: &'static str) -> Book {
+    Book {
+        title,
+        author: Author {
+            name: "Noah",
+            age: 42,
+        },
+        rating: 4.5,
+    }
+}
+
+fn partial<T>(value: &T) -> PartialError
+where
+    T: ?Sized + Serialize,
+{
+    to_key_with_partial(value).unwrap_err()
+}
+
+#[test]
+fn test_same_as_to_key() -> Result<(), Error> {
+    #[derive(Serialize)]
+    enum Shape {
+        Circle(u32),
+        Rect(u32, u32),
+        Point { x: u32, y: u32 },
+        Empty,
+    }
+
+    let value = (
+        vec![Shape::Circle(1), Shape::Rect(2, 3)],
+        Shape::Point { x: 1, y: 2 },
+        Shape::Empty,
+        Some("a"),
+        vec![(1u32, "b")].into_iter().collect::<BTreeMap<_, _>>(),
+    );
+
+    let key: Key = to_key_with_partial(&value)?;
+    assert_eq!(key, to_key(&value)?);
+    Ok(())
+}
+
+#[test]
+fn test_nested() -> Result<(), Error> {
+    #[derive(Serialize)]
+    struct PartialBook {
+        title: &'static str,
+        author: Author,
+    }
+
+    #[derive(Serialize)]
+    struct PartialLibrary {
+        name: &'static str,
+        books: Vec<PartialBook>,
+    }
+
+    let error = partial(&Library {
+        name: "Central",
+        books: vec![book("a")],
+        open: true,
+    });
+
+    assert_eq!(
+        error.error(),
+        &Error::UnsupportedFloat {
+            width: FloatWidth::F32,
+            value: 4.5
+        }
+    );
+    assert_eq!(
+        error.path().segments(),
+        &[
+            PathSegment::Field("books".into()),
+            PathSegment::Index(0),
+            PathSegment::Field("rating".into()),
+        ]
+    );
+    assert_eq!(
+        error.to_string(),
+        "unsupported float f32 = 4.5 at books[0].rating"
+    );
+
+    let expected = to_key(&PartialLibrary {
+        name: "Central",
+        books: vec![PartialBook {
+            title: "a",
+            author: Author {
+                name: "Noah",
+                age: 42,
+            },
+        }],
+    })?;
+
+    assert_eq!(error.partial(), Some(&expected));
+    Ok(())
+}
+
+#[test]
+fn test_empty_prefix() -> Result<(), Error> {
+    let error = partial(&1.5f32);
+    assert_eq!(error.partial(), None);
+    assert!(error.path().is_empty());
+    assert_eq!(error.to_string(), "unsupported float f32 = 1.5");
+
+    let error = partial(&vec![1.5f64]);
+    assert_eq!(error.partial(), Some(&to_key(&Vec::<u32>::new())?));
+    assert_eq!(error.path().to_string(), "[0]");
+    Ok(())
+}
+
+#[test]
+fn test_variants() -> Result<(), Error> {
+    #[derive(Serialize)]
+    enum Shape {
+        Circle(f32),
+        Rect(u32, f32),
+        Point { x: u32, y: f64 },
+    }
+
+    #[derive(Serialize)]
+    enum Point {
+        Point { x: u32 },
+    }
+
+    let error = partial(&Shape::Circle(1.0));
+    assert_eq!(error.path().to_string(), "Circle");
+    assert_eq!(error.partial(), Some(&key!({})));
+
+    let error = partial(&Shape::Rect(1, 2.0));
+    assert_eq!(error.path().to_string(), "Rect[1]");
+    let expected = key!({ "Rect": [1u32] });
+    assert_eq!(error.partial(), Some(&expected));
+
+    let error = partial(&Shape::Point { x: 1, y: 2.0 });
+    assert_eq!(error.path().to_string(), "Point.y");
+    assert_eq!(error.partial(), Some(&to_key(&Point::Point { x: 1 })?));
+    Ok(())
+}
+
+#[test]
+fn test_maps() -> Result<(), Error> {
+    let mut map = BTreeMap::new();
+    map.insert("a", vec![1.0f32]);
+    map.insert("b", vec![2.0f32]);
+
+    let error = partial(&map);
+    assert_eq!(error.path().to_string(), "a[0]");
+
+    let mut expected = BTreeMap::new();
+    expected.insert("a", Vec::<u32>::new());
+    assert_eq!(error.partial(), Some(&to_key(&expected)?));
+
+    struct FloatKey(Vec<((u32, f32), &'static str)>);
+
+    impl Serialize for FloatKey {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.collect_map(self.0.iter().map(|(k, v)| (k, v)))
+        }
+    }
+
+    let error = partial(&FloatKey(vec![((1, 1.0), "a")]));
+    assert_eq!(error.path().to_string(), "{0}[1]");
+    assert_eq!(error.partial(), Some(&key!({})));
+    Ok(())
+}
+
+#[test]
+fn test_custom_error() -> Result<(), Error> {
+    struct Failing;
+
+    impl Serialize for Failing {
+        fn serialize<S>(&self, _: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            Err(S::Error::custom("failing"))
+        }
+    }
+
+    #[derive(Serialize)]
+    struct Outer {
+        first: u32,
+        second: Vec<Failing>,
+    }
+
+    let error = partial(&Outer {
+        first: 1,
+        second: vec![Failing],
+    });
+
+    assert_eq!(error.to_string(), "failing at second[0]");
+    assert_eq!(Error::from(error), Error::Custom(String::from("failing")));
+    Ok(())
+}
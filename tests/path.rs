@@ -0,0 +1,119 @@
+use serde_derive::Serialize;
+use serde_hashkey::{key, to_key, Error, Key, KeyPath, PathSegment};
+
+#[derive(Serialize)]
+struct Author {
+    name: &'static str,
+    age: u32,
+}
+
+#[derive(Serialize)]
+struct Book {
+    title: &'static str,
+    authors: Vec<Author>,
+    empty: Vec<u32>,
+    ratings: Vec<(u32, Option<u32>)>,
+}
+
+#[test]
+fn test_leaves() -> Result<(), Error> {
+    let key = to_key(&Book {
+        title: "Birds of a feather",
+        authors: vec![
+            Author {
+                name: "Noah",
+                age: 42,
+            },
+            Author {
+                name: "Jane",
+                age: 43,
+            },
+        ],
+        empty: vec![],
+        ratings: vec![(5, None)],
+    })?;
+
+    let leaves = key
+        .leaves()
+        .map(|(path, value)| (path.to_string(), value.clone()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        leaves,
+        vec![
+            (String::from("title"), key!("Birds of a feather")),
+            (String::from("authors[0].name"), key!("Noah")),
+            (String::from("authors[0].age"), Key::from(42u32)),
+            (String::from("authors[1].name"), key!("Jane")),
+            (String::from("authors[1].age"), Key::from(43u32)),
+            (String::from("ratings[0][0]"), Key::from(5u32)),
+            (String::from("ratings[0][1]"), Key::Null),
+        ]
+    );
+
+    for (path, value) in key.leaves() {
+        assert!(std::ptr::eq(key.get_path(&path).unwrap(), value));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_non_string_map_keys() {
+    let key = key!({
+        "a": 1u32,
+        [1u32]: { 2u32: "b" },
+    });
+
+    let leaves = key.leaves().collect::<Vec<_>>();
+
+    let expected = vec![PathSegment::Entry(1), PathSegment::Entry(0)]
+        .into_iter()
+        .collect::<KeyPath>();
+
+    assert_eq!(leaves.len(), 2);
+    assert_eq!(leaves[1], (expected.clone(), &key!("b")));
+    assert_eq!(expected.to_string(), "{1}{0}");
+
+    for (path, value) in leaves {
+        assert!(std::ptr::eq(key.get_path(&path).unwrap(), value));
+    }
+}
+
+#[test]
+fn test_scalar_root() {
+    let key: Key = key!("a");
+    assert_eq!(
+        key.leaves().collect::<Vec<_>>(),
+        vec![(KeyPath::new(), &key)]
+    );
+    assert_eq!(key.get_path(&KeyPath::new()), Some(&key));
+}
+
+#[test]
+fn test_missing_paths() {
+    let key = key!(["a"]);
+
+    let path = vec![PathSegment::Index(1)].into_iter().collect::<KeyPath>();
+    assert_eq!(key.get_path(&path), None);
+
+    let path = vec![PathSegment::Field("a".into())]
+        .into_iter()
+        .collect::<KeyPath>();
+    assert_eq!(key.get_path(&path), None);
+}
+
+#[test]
+fn test_deep_leaves() {
+    let mut key: Key = key!("leaf");
+
+    for _ in 0..10_000 {
+        key = Key::Seq(vec![key].into());
+    }
+
+    let mut leaves = key.leaves();
+    let (path, value) = leaves.next().unwrap();
+    assert_eq!(path.len(), 10_000);
+    assert_eq!(value, &key!("leaf"));
+    assert!(leaves.next().is_none());
+}
@@ -0,0 +1,98 @@
+use std::mem;
+
+use serde_derive::Serialize;
+use serde_hashkey::{
+    to_key, to_key_with_canonical_float, to_key_with_widened_float, CanonicalFloatPolicy, Error,
+    FloatPolicy, FloatWidth, Key, RejectFloatPolicy, WidenF32Policy,
+};
+
+/// Drop a deeply nested chain of sequences without recursing.
+fn unwind<F>(mut key: Key<F>)
+where
+    F: FloatPolicy,
+{
+    while let Key::Seq(seq) = &mut key {
+        match mem::take(seq).into_vec().pop() {
+            Some(inner) => key = inner,
+            None => break,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Record<T> {
+    id: u64,
+    name: &'static str,
+    tags: Vec<&'static str>,
+    samples: Vec<T>,
+    nested: Vec<(Option<T>, Vec<u8>)>,
+}
+
+fn record<T>(sample: T) -> Record<T>
+where
+    T: Copy,
+{
+    Record {
+        id: 7,
+        name: "sensor",
+        tags: vec!["a", "b"],
+        samples: vec![sample, sample],
+        nested: vec![(Some(sample), vec![1, 2, 3]), (None, vec![])],
+    }
+}
+
+#[test]
+fn test_float_free_roundtrip() -> Result<(), Error> {
+    let key = to_key_with_canonical_float(&record(42u32))?;
+
+    let reject: Key<RejectFloatPolicy> = key.clone().try_into_policy()?;
+    assert_eq!(reject, to_key(&record(42u32))?);
+
+    let back: Key<CanonicalFloatPolicy> = reject.try_into_policy()?;
+    assert_eq!(back, key);
+    Ok(())
+}
+
+#[test]
+fn test_rejected_float() {
+    let key = to_key_with_canonical_float(&record(1.5f64)).unwrap();
+
+    assert_eq!(
+        key.try_into_policy::<RejectFloatPolicy>(),
+        Err(Error::UnsupportedFloat {
+            width: FloatWidth::F64,
+            value: 1.5
+        })
+    );
+}
+
+#[test]
+fn test_float_policies() -> Result<(), Error> {
+    let key = to_key_with_canonical_float(&record(0.5f32))?;
+
+    let widened: Key<WidenF32Policy> = key.clone().try_into_policy()?;
+    assert_eq!(widened, to_key_with_widened_float(&record(0.5f32))?);
+    assert_eq!(widened, to_key_with_widened_float(&record(0.5f64))?);
+
+    let back: Key<CanonicalFloatPolicy> = widened.try_into_policy()?;
+    assert_eq!(back, to_key_with_canonical_float(&record(0.5f64))?);
+    Ok(())
+}
+
+#[test]
+fn test_deeply_nested() -> Result<(), Error> {
+    let mut key = Key::<CanonicalFloatPolicy>::Seq(Box::default());
+
+    for _ in 0..100_000 {
+        key = Key::Seq(vec![Key::from_f32(0.5)?, key].into());
+    }
+
+    let count = key.iter().count();
+    let converted: Key<WidenF32Policy> = key.try_into_policy()?;
+    assert_eq!(converted.iter().count(), count);
+    assert_eq!(converted[0], Key::from(0.5f64));
+    assert_eq!(converted[1][0], Key::from(0.5f64));
+
+    unwind(converted);
+    Ok(())
+}
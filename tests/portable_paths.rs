@@ -0,0 +1,85 @@
+use std::convert::TryFrom;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use serde_derive::{Deserialize, Serialize};
+use serde_hashkey::{from_key, key, to_key, Error, Key};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Strict {
+    #[serde(with = "serde_hashkey::portable_paths")]
+    path: PathBuf,
+    #[serde(with = "serde_hashkey::portable_paths")]
+    name: OsString,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Lossy {
+    #[serde(with = "serde_hashkey::portable_paths::lossy")]
+    path: PathBuf,
+}
+
+fn strict(path: &str) -> Strict {
+    Strict {
+        path: PathBuf::from(path),
+        name: OsString::from(path),
+    }
+}
+
+#[test]
+fn test_separators() -> Result<(), Error> {
+    let key = to_key(&strict(r"assets\images\logo.png"))?;
+    assert_eq!(key, to_key(&strict("assets/images/logo.png"))?);
+    assert_eq!(key, to_key(&strict(r"assets/images\logo.png"))?);
+
+    let key: Key = Key::from(Path::new(r"C:\Users"));
+    assert_eq!(key, key!("C:/Users"));
+    Ok(())
+}
+
+#[test]
+fn test_round_trip() -> Result<(), Error> {
+    let value = strict("assets/images/logo.png");
+    let key = to_key(&value)?;
+    assert_eq!(from_key::<Strict, _>(&key)?, value);
+
+    let value = Lossy {
+        path: PathBuf::from("a/b"),
+    };
+    assert_eq!(from_key::<Lossy, _>(&to_key(&value)?)?, value);
+
+    let key: Key = Key::from(Path::new("a/b/c.txt"));
+    assert_eq!(PathBuf::try_from(&key)?, PathBuf::from("a/b/c.txt"));
+
+    let key: Key = Key::from(1u32);
+    assert_eq!(PathBuf::try_from(&key), Err(Error::Unexpected("string")));
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_non_utf8() -> Result<(), Error> {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = Path::new(OsStr::from_bytes(b"a/\xffb"));
+
+    let error = to_key(&Strict {
+        path: path.to_owned(),
+        name: OsString::new(),
+    })
+    .unwrap_err();
+    assert_eq!(
+        error,
+        Error::Custom(String::from("path `a/\u{fffd}b` is not valid UTF-8"))
+    );
+
+    let key = to_key(&Lossy {
+        path: path.to_owned(),
+    })?;
+    assert_eq!(key, key!({ "path": "a/\u{fffd}b" }));
+
+    let key: Key = Key::from(path);
+    assert_eq!(key, key!("a/\u{fffd}b"));
+    Ok(())
+}
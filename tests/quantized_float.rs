@@ -0,0 +1,125 @@
+use serde_derive::{Deserialize, Serialize};
+use serde_hashkey::{
+    from_key, to_key_with_quantized_float, Error, FloatWidth, Key, QuantizedFloat,
+    QuantizedFloatPolicy,
+};
+
+#[test]
+fn test_nearby_floats() -> Result<(), Error> {
+    let a = to_key_with_quantized_float::<3, _>(&21.4999999f64)?;
+    let b = to_key_with_quantized_float::<3, _>(&21.5f64)?;
+    let c = to_key_with_quantized_float::<3, _>(&21.50002f32)?;
+    assert_eq!(a, b);
+    assert_eq!(b, c);
+
+    let d = to_key_with_quantized_float::<3, _>(&21.501f64)?;
+    assert_ne!(b, d);
+    assert!(b < d);
+
+    // Without decimals, floats are rounded to integers.
+    assert_eq!(
+        to_key_with_quantized_float::<0, _>(&41.6f64)?,
+        to_key_with_quantized_float::<0, _>(&42.4f64)?
+    );
+    Ok(())
+}
+
+#[test]
+fn test_ties() -> Result<(), Error> {
+    // These are exactly halfway between two grid points, and are rounded away
+    // from zero.
+    let cases = [
+        (0.25, 0.3),
+        (-0.25, -0.3),
+        (0.75, 0.8),
+        (-0.75, -0.8),
+        (2.5, 2.5),
+    ];
+
+    for (value, expected) in cases {
+        let float = QuantizedFloat::<1>::new(value)?;
+        assert_eq!(float.get(), expected, "{}", value);
+        assert_eq!(float, QuantizedFloat::<1>::new(expected)?);
+        assert_eq!(
+            to_key_with_quantized_float::<1, _>(&(value as f32))?,
+            to_key_with_quantized_float::<1, _>(&value)?
+        );
+    }
+
+    assert_eq!(QuantizedFloat::<0>::new(0.5)?.get(), 1.0);
+    assert_eq!(QuantizedFloat::<0>::new(-0.5)?.get(), -1.0);
+    Ok(())
+}
+
+#[test]
+fn test_zero() -> Result<(), Error> {
+    let zero = to_key_with_quantized_float::<1, _>(&0.0f64)?;
+    assert_eq!(to_key_with_quantized_float::<1, _>(&-0.0f64)?, zero);
+    assert_eq!(to_key_with_quantized_float::<1, _>(&-0.01f64)?, zero);
+    assert_eq!(to_key_with_quantized_float::<1, _>(&0.01f64)?, zero);
+    assert!(from_key::<f64, _>(&zero)?.is_sign_positive());
+    Ok(())
+}
+
+#[test]
+fn test_rejected() {
+    assert_eq!(
+        to_key_with_quantized_float::<2, _>(&f32::INFINITY),
+        Err(Error::NonFiniteFloat {
+            width: FloatWidth::F32,
+            value: f64::INFINITY,
+        })
+    );
+
+    assert!(matches!(
+        to_key_with_quantized_float::<2, _>(&f64::NAN),
+        Err(Error::NonFiniteFloat {
+            width: FloatWidth::F64,
+            ..
+        })
+    ));
+
+    assert_eq!(
+        to_key_with_quantized_float::<3, _>(&f64::MAX),
+        Err(Error::UnsupportedFloat {
+            width: FloatWidth::F64,
+            value: f64::MAX,
+        })
+    );
+}
+
+#[test]
+fn test_roundtrip() -> Result<(), Error> {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Reading {
+        sensor: u32,
+        celsius: f64,
+        humidity: f32,
+    }
+
+    let key = to_key_with_quantized_float::<1, _>(&Reading {
+        sensor: 7,
+        celsius: 21.4999999,
+        humidity: 40.04,
+    })?;
+
+    assert_eq!(
+        key["celsius"],
+        Key::<QuantizedFloatPolicy<1>>::from_f64(21.5)?
+    );
+    assert_eq!(
+        from_key::<Reading, _>(&key)?,
+        Reading {
+            sensor: 7,
+            celsius: 21.5,
+            humidity: 40.0,
+        }
+    );
+
+    let bytes = key.to_bytes();
+    assert_eq!(
+        Key::<QuantizedFloatPolicy<1>>::from_bytes(&bytes)?,
+        key.normalize()
+    );
+    Ok(())
+}
@@ -0,0 +1,151 @@
+use serde_derive::Serialize;
+use serde_hashkey::{key, to_key, Error, Key, QueryConfig, QueryNesting};
+
+#[test]
+fn test_round_trip_strings() {
+    let key = key!({
+        "flag": (),
+        "q": "birds & bees",
+        "tag": ["a", "b/c"],
+        "ünïcode": "välue=1",
+    });
+
+    let query = key.to_query_string().unwrap();
+    assert_eq!(
+        query,
+        "flag&q=birds%20%26%20bees&tag=a&tag=b%2Fc&%C3%BCn%C3%AFcode=v%C3%A4lue%3D1"
+    );
+    assert_eq!(Key::from_query_string(&query), Ok(key));
+}
+
+#[test]
+fn test_sorted() {
+    #[derive(Serialize)]
+    struct Params {
+        z: u32,
+        a: u32,
+        m: bool,
+    }
+
+    let key = to_key(&Params {
+        z: 1,
+        a: 2,
+        m: false,
+    })
+    .unwrap();
+    assert_eq!(key.to_query_string().unwrap(), "a=2&m=false&z=1");
+}
+
+#[test]
+fn test_nesting() {
+    let key = key!({
+        "author": { "age": "42", "name": "Noah" },
+        "title": "Birds",
+    });
+
+    assert_eq!(
+        key.to_query_string(),
+        Err(Error::UnsupportedType("nested map"))
+    );
+
+    let dot = QueryConfig::new().nesting(QueryNesting::Dot);
+    let query = key.to_query_string_with(&dot).unwrap();
+    assert_eq!(query, "author.age=42&author.name=Noah&title=Birds");
+    assert_eq!(Key::from_query_string_with(&query, &dot), Ok(key.clone()));
+
+    let bracket = QueryConfig::new().nesting(QueryNesting::Bracket);
+    let query = key.to_query_string_with(&bracket).unwrap();
+    assert_eq!(query, "author[age]=42&author[name]=Noah&title=Birds");
+    assert_eq!(Key::from_query_string_with(&query, &bracket), Ok(key));
+}
+
+#[test]
+fn test_empty_nested_map() {
+    let dot = QueryConfig::new().nesting(QueryNesting::Dot);
+    let bracket = QueryConfig::new().nesting(QueryNesting::Bracket);
+
+    let empty = key!({});
+    assert_eq!(empty.to_query_string_with(&dot), Ok(String::new()));
+
+    let nested = key!({ "a": {} });
+
+    for config in [dot, bracket] {
+        assert_eq!(
+            nested.to_query_string_with(&config),
+            Err(Error::UnsupportedType("empty map"))
+        );
+    }
+
+    let deep = key!({ "a": { "b": {} } });
+    assert_eq!(
+        deep.to_query_string_with(&dot),
+        Err(Error::UnsupportedType("empty map"))
+    );
+}
+
+#[test]
+fn test_parse_numbers() {
+    let config = QueryConfig::new().parse_numbers(true);
+
+    let key: Key = Key::from_query_string_with("a=1&b=-2&c=1.5&d=inf&e=x", &config).unwrap();
+    assert_eq!(
+        key,
+        key!({
+            "a": 1u64,
+            "b": -2i64,
+            // Floats are rejected by the default policy, so they stay strings.
+            "c": "1.5",
+            "d": "inf",
+            "e": "x",
+        })
+    );
+
+    let key: Key = Key::from_query_string("a=1").unwrap();
+    assert_eq!(key, key!({ "a": "1" }));
+}
+
+#[test]
+fn test_errors() {
+    let bytes = key!({ "a": Key::Bytes(vec![1, 2].into()) });
+    assert_eq!(
+        bytes.to_query_string(),
+        Err(Error::UnsupportedType("bytes"))
+    );
+
+    let non_string = key!({ 1u32: "a" });
+    assert_eq!(
+        non_string.to_query_string(),
+        Err(Error::Unexpected("string map key"))
+    );
+
+    let nested_seq = key!({ "a": [[]] });
+    assert_eq!(
+        nested_seq.to_query_string(),
+        Err(Error::UnsupportedType("nested sequence"))
+    );
+
+    let empty_seq = key!({ "a": [] });
+    assert_eq!(
+        empty_seq.to_query_string(),
+        Err(Error::UnsupportedType("empty sequence"))
+    );
+
+    let not_map = key!("a");
+    assert_eq!(not_map.to_query_string(), Err(Error::Unexpected("map")));
+
+    let dot = QueryConfig::new().nesting(QueryNesting::Dot);
+    let dotted = key!({ "a.b": "c" });
+    assert_eq!(
+        dotted.to_query_string_with(&dot),
+        Err(Error::SeparatorInKey("a.b".into()))
+    );
+
+    assert_eq!(
+        Key::<serde_hashkey::RejectFloatPolicy>::from_query_string("a=%zz"),
+        Err(Error::InvalidQuery("%zz".into()))
+    );
+    assert_eq!(
+        Key::<serde_hashkey::RejectFloatPolicy>::from_query_string_with("a=1&a.b=2", &dot),
+        Err(Error::ConflictingPath("a.b".into()))
+    );
+}
@@ -0,0 +1,54 @@
+#![cfg(feature = "redis")]
+
+use redis1::{FromRedisValue, ToRedisArgs, Value};
+use serde_derive::Serialize;
+use serde_hashkey::{key, to_key, Key};
+
+#[derive(Serialize)]
+struct CacheKey {
+    service: &'static str,
+    user: u32,
+}
+
+#[test]
+fn test_golden_args() {
+    let key = to_key(&CacheKey {
+        service: "s",
+        user: 1,
+    })
+    .unwrap();
+
+    let expected = vec![vec![
+        0x11, 0x02, 0x0f, 0x07, b's', b'e', b'r', b'v', b'i', b'c', b'e', 0x0f, 0x01, b's', 0x0f,
+        0x04, b'u', b's', b'e', b'r', 0x09, 0, 0, 0, 1,
+    ]];
+
+    assert_eq!(key.to_redis_args(), expected);
+    assert_eq!(key.num_of_args(), 1);
+}
+
+#[test]
+fn test_equal_keys_equal_args() {
+    let a = key!({ "user": 1u32, "service": "s" });
+
+    let b = a.clone().normalize();
+    assert_eq!(a.to_redis_args(), b.to_redis_args());
+}
+
+#[test]
+fn test_from_redis_value() {
+    let key = to_key(&CacheKey {
+        service: "s",
+        user: 1,
+    })
+    .unwrap();
+
+    let value = Value::BulkString(key.to_bytes());
+    assert_eq!(Key::from_redis_value(value).unwrap(), key.normalize());
+
+    assert!(Key::<serde_hashkey::RejectFloatPolicy>::from_redis_value(Value::Int(1)).is_err());
+    assert!(
+        Key::<serde_hashkey::RejectFloatPolicy>::from_redis_value(Value::BulkString(vec![0xff]))
+            .is_err()
+    );
+}
@@ -0,0 +1,194 @@
+use serde_derive::Serialize;
+use serde_hashkey::{to_key, Error};
+
+mod old {
+    use serde_derive::Serialize;
+
+    #[derive(Serialize)]
+    pub struct Library {
+        pub name: &'static str,
+        pub books: Vec<Book>,
+    }
+
+    #[derive(Serialize)]
+    pub struct Book {
+        pub name: &'static str,
+        pub author: Author,
+    }
+
+    #[derive(Serialize)]
+    pub struct Author {
+        pub name: &'static str,
+        pub age: u32,
+    }
+}
+
+fn library() -> old::Library {
+    old::Library {
+        name: "Central",
+        books: vec![
+            old::Book {
+                name: "Birds of a feather",
+                author: old::Author {
+                    name: "Noah",
+                    age: 42,
+                },
+            },
+            old::Book {
+                name: "Fish of a fin",
+                author: old::Author {
+                    name: "Jane",
+                    age: 24,
+                },
+            },
+        ],
+    }
+}
+
+#[test]
+fn test_rename_everywhere() -> Result<(), Error> {
+    #[derive(Serialize)]
+    struct Library {
+        title: &'static str,
+        books: Vec<Book>,
+    }
+
+    #[derive(Serialize)]
+    struct Book {
+        title: &'static str,
+        author: Author,
+    }
+
+    #[derive(Serialize)]
+    struct Author {
+        title: &'static str,
+        age: u32,
+    }
+
+    let mut key = to_key(&library())?;
+    key.rename_fields(&[("name", "title")]);
+
+    let expected = to_key(&Library {
+        title: "Central",
+        books: vec![
+            Book {
+                title: "Birds of a feather",
+                author: Author {
+                    title: "Noah",
+                    age: 42,
+                },
+            },
+            Book {
+                title: "Fish of a fin",
+                author: Author {
+                    title: "Jane",
+                    age: 24,
+                },
+            },
+        ],
+    })?;
+
+    assert_eq!(key, expected);
+    Ok(())
+}
+
+#[test]
+fn test_rename_qualified() -> Result<(), Error> {
+    #[derive(Serialize)]
+    struct Library {
+        name: &'static str,
+        items: Vec<Book>,
+    }
+
+    #[derive(Serialize)]
+    struct Book {
+        name: &'static str,
+        writer: Author,
+    }
+
+    #[derive(Serialize)]
+    struct Author {
+        full_name: &'static str,
+        age: u32,
+    }
+
+    let mut key = to_key(&library())?;
+
+    // Paths refer to fields by their names before renaming.
+    key.rename_fields(&[
+        ("books.author.name", "full_name"),
+        ("books.author", "writer"),
+        ("books", "items"),
+    ]);
+
+    let expected = to_key(&Library {
+        name: "Central",
+        items: vec![
+            Book {
+                name: "Birds of a feather",
+                writer: Author {
+                    full_name: "Noah",
+                    age: 42,
+                },
+            },
+            Book {
+                name: "Fish of a fin",
+                writer: Author {
+                    full_name: "Jane",
+                    age: 24,
+                },
+            },
+        ],
+    })?;
+
+    assert_eq!(key, expected);
+    Ok(())
+}
+
+#[test]
+fn test_rename_normalized() -> Result<(), Error> {
+    #[derive(Serialize)]
+    struct Author {
+        years: u32,
+        name: &'static str,
+    }
+
+    let mut key = to_key(&old::Author {
+        name: "Noah",
+        age: 42,
+    })?
+    .normalize();
+    key.rename_fields(&[("age", "years")]);
+
+    assert_eq!(
+        key,
+        to_key(&Author {
+            years: 42,
+            name: "Noah"
+        })?
+        .normalize()
+    );
+
+    // Unsorted maps keep their order.
+    let mut key = to_key(&old::Author {
+        name: "Noah",
+        age: 42,
+    })?;
+    key.rename_fields(&[("name", "z"), ("age", "a")]);
+
+    let fields = match &key {
+        serde_hashkey::Key::Map(map) => map.iter().map(|(k, _)| k.clone()).collect::<Vec<_>>(),
+        _ => panic!("expected map"),
+    };
+
+    assert_eq!(fields, vec![to_key(&"z")?, to_key(&"a")?]);
+    Ok(())
+}
+
+#[test]
+fn test_rename_no_match() -> Result<(), Error> {
+    let mut key = to_key(&library())?;
+    key.rename_fields(&[("missing", "other"), ("author.name", "full_name")]);
+    assert_eq!(key, to_key(&library())?);
+    Ok(())
+}
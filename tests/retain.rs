@@ -0,0 +1,132 @@
+use std::mem;
+
+use serde_derive::Serialize;
+use serde_hashkey::{key, to_key, Error, Key};
+
+#[derive(Serialize)]
+struct Request {
+    request_id: &'static str,
+    path: &'static str,
+    params: Params,
+}
+
+#[derive(Serialize)]
+struct Params {
+    request_id: &'static str,
+    page: u32,
+}
+
+/// Drop a deeply nested chain of sequences without recursing.
+fn unwind(mut key: Key) {
+    while let Key::Seq(seq) = &mut key {
+        match mem::take(seq).into_vec().pop() {
+            Some(inner) => key = inner,
+            None => break,
+        }
+    }
+}
+
+fn request() -> Request {
+    Request {
+        request_id: "f00",
+        path: "/books",
+        params: Params {
+            request_id: "ba5",
+            page: 2,
+        },
+    }
+}
+
+#[test]
+fn test_retain() -> Result<(), Error> {
+    let mut key = to_key(&request())?;
+    let mut visited = Vec::new();
+
+    key.retain(|key, value| {
+        visited.push((key.clone(), value.is_some()));
+        key != "request_id"
+    });
+
+    assert_eq!(
+        visited,
+        vec![
+            (key!("request_id"), true),
+            (key!("path"), true),
+            (key!("params"), true),
+        ]
+    );
+
+    // Only the top level is filtered.
+    assert_eq!(
+        key,
+        key!({
+            "path": "/books",
+            "params": { "request_id": "ba5", "page": 2u32 },
+        })
+    );
+    Ok(())
+}
+
+#[test]
+fn test_retain_seq() -> Result<(), Error> {
+    let mut key = key!(["a", 1u32, "b"]);
+    key.retain(|element, value| {
+        assert!(value.is_none());
+        element.is_string()
+    });
+    assert_eq!(key, key!(["a", "b"]));
+
+    // Compact sequences are compacted again.
+    let mut key = to_key(&vec![1u32, 2, 3])?;
+    key.retain(|element, _| *element != 2u64);
+    assert_eq!(key, to_key(&vec![1u32, 3])?);
+    assert!(matches!(key, Key::IntegerSeq(..)));
+
+    let mut key = to_key(&vec![1u32, 2, 3])?;
+    key.retain(|_, _| false);
+    assert_eq!(key, to_key(&Vec::<u32>::new())?);
+    Ok(())
+}
+
+#[test]
+fn test_retain_other() {
+    for mut key in [key!(null), key!(()), key!("a"), key!(1u32)] {
+        let expected = key.clone();
+        key.retain(|_, _| panic!("nothing to visit"));
+        assert_eq!(key, expected);
+    }
+}
+
+#[test]
+fn test_retain_recursive() -> Result<(), Error> {
+    let mut key = to_key(&vec![request(), request()])?;
+    key.retain_recursive(|key, value| value.is_none() || key != "request_id");
+
+    let expected = key!({ "path": "/books", "params": { "page": 2u32 } });
+    assert_eq!(key, key!([(expected.clone()), (expected)]));
+    Ok(())
+}
+
+#[test]
+fn test_retain_recursive_deep() {
+    let mut key: Key = key!("leaf");
+
+    for n in 0..100_000u32 {
+        key = Key::Seq(vec![Key::from(n), key].into());
+    }
+
+    key.retain_recursive(|element, _| !matches!(element, Key::Integer(..)));
+
+    let mut depth = 0;
+    let mut current = &key;
+
+    while let Key::Seq(seq) = current {
+        assert_eq!(seq.len(), 1);
+        depth += 1;
+        current = &seq[0];
+    }
+
+    assert_eq!(depth, 100_000);
+    assert_eq!(*current, "leaf");
+    unwind(key);
+}
@@ -0,0 +1,233 @@
+use std::collections::BTreeMap;
+
+use serde_derive::Serialize;
+use serde_hashkey::{
+    from_key, to_key, Error, FieldSchema, KeySchema, PathSegment, SchemaViolation,
+};
+
+#[derive(Serialize)]
+struct Author {
+    name: &'static str,
+    age: Option<u32>,
+}
+
+#[derive(Serialize)]
+struct Book {
+    title: &'static str,
+    author: Author,
+    tags: Vec<&'static str>,
+    ratings: BTreeMap<u32, u32>,
+}
+
+fn book() -> Book {
+    Book {
+        title: "Birds of a feather",
+        author: Author {
+            name: "Noah",
+            age: Some(42),
+        },
+        tags: vec!["fiction"],
+        ratings: vec![(1, 5)].into_iter().collect(),
+    }
+}
+
+fn field(name: &str, schema: KeySchema) -> FieldSchema {
+    FieldSchema {
+        name: name.into(),
+        schema,
+        required: true,
+    }
+}
+
+#[test]
+fn test_infer() -> Result<(), Error> {
+    let schema = KeySchema::infer(&to_key(&book())?);
+
+    assert_eq!(
+        schema,
+        KeySchema::Map(vec![
+            field("title", KeySchema::String),
+            field(
+                "author",
+                KeySchema::Map(vec![
+                    field("name", KeySchema::String),
+                    field("age", KeySchema::Integer),
+                ])
+            ),
+            field("tags", KeySchema::Seq(Box::new(KeySchema::String))),
+            field(
+                "ratings",
+                KeySchema::Entries(Box::new(KeySchema::Integer), Box::new(KeySchema::Integer))
+            ),
+        ])
+    );
+
+    assert_eq!(
+        KeySchema::infer(&to_key(&Vec::<u32>::new())?),
+        KeySchema::Seq(Box::new(KeySchema::Any))
+    );
+    Ok(())
+}
+
+#[test]
+fn test_merge() -> Result<(), Error> {
+    #[derive(Serialize)]
+    struct Extended {
+        title: &'static str,
+        pages: u32,
+    }
+
+    #[derive(Serialize)]
+    struct Short {
+        title: &'static str,
+    }
+
+    let a = KeySchema::infer(&to_key(&Extended {
+        title: "a",
+        pages: 1,
+    })?);
+    let b = KeySchema::infer(&to_key(&Short { title: "b" })?);
+
+    assert_eq!(
+        a.merge(b),
+        KeySchema::Map(vec![
+            field("title", KeySchema::String),
+            FieldSchema {
+                name: "pages".into(),
+                schema: KeySchema::Integer,
+                required: false,
+            },
+        ])
+    );
+
+    let a = KeySchema::infer(&to_key(&1u32)?);
+    let b = KeySchema::infer(&to_key(&"one")?);
+    assert_eq!(a.merge(b), KeySchema::Any);
+
+    let a = KeySchema::infer(&to_key(&None::<u32>)?);
+    let b = KeySchema::infer(&to_key(&Some(1u32))?);
+    assert_eq!(
+        a.merge(b),
+        KeySchema::Optional(Box::new(KeySchema::Integer))
+    );
+    Ok(())
+}
+
+#[test]
+fn test_validate() -> Result<(), Error> {
+    let schema = KeySchema::infer(&to_key(&book())?);
+
+    let mut other = book();
+    other.title = "Another title";
+    other.tags = vec!["a", "b", "c"];
+    other.ratings.insert(2, 3);
+    assert_eq!(schema.validate(&to_key(&other)?), Ok(()));
+
+    #[derive(Serialize)]
+    struct Wrong {
+        title: u32,
+        author: (),
+        tags: Vec<u32>,
+        extra: bool,
+    }
+
+    let errors = schema
+        .validate(&to_key(&Wrong {
+            title: 1,
+            author: (),
+            tags: vec![1, 2],
+            extra: true,
+        })?)
+        .unwrap_err()
+        .into_iter()
+        .map(|(path, violation)| (path.to_string(), violation))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        errors,
+        vec![
+            (String::from("ratings"), SchemaViolation::MissingField),
+            (
+                String::from("title"),
+                SchemaViolation::UnexpectedKind {
+                    expected: "string",
+                    found: "integer"
+                }
+            ),
+            (
+                String::from("author"),
+                SchemaViolation::UnexpectedKind {
+                    expected: "map",
+                    found: "unit"
+                }
+            ),
+            (
+                String::from("tags[0]"),
+                SchemaViolation::UnexpectedKind {
+                    expected: "string",
+                    found: "integer"
+                }
+            ),
+            (
+                String::from("tags[1]"),
+                SchemaViolation::UnexpectedKind {
+                    expected: "string",
+                    found: "integer"
+                }
+            ),
+            (String::from("extra"), SchemaViolation::UnknownField),
+        ]
+    );
+
+    let errors = schema.validate(&to_key(&"book")?).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].0.is_empty());
+    assert_eq!(errors[0].1.to_string(), "expected map, found string");
+    Ok(())
+}
+
+#[test]
+fn test_entry_paths() -> Result<(), Error> {
+    let schema = KeySchema::infer(&to_key(
+        &vec![(1u32, "one")].into_iter().collect::<BTreeMap<_, _>>(),
+    )?);
+
+    let key = to_key(
+        &vec![(1u32, "one"), (2u32, "two")]
+            .into_iter()
+            .collect::<BTreeMap<_, _>>(),
+    )?;
+    assert_eq!(schema.validate(&key), Ok(()));
+
+    let key = to_key(&vec![(1u32, 1u32)].into_iter().collect::<BTreeMap<_, _>>())?;
+    let errors = schema.validate(&key).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].0.segments(), &[PathSegment::Entry(0)]);
+    Ok(())
+}
+
+#[test]
+fn test_serde() -> Result<(), Box<dyn std::error::Error>> {
+    let schema = KeySchema::infer(&to_key(&book())?).merge(KeySchema::infer(&to_key(&Book {
+        ratings: BTreeMap::new(),
+        author: Author {
+            name: "Jane",
+            age: None,
+        },
+        ..book()
+    })?));
+
+    let json = serde_json::to_string(&schema)?;
+    assert_eq!(
+        json,
+        r#"{"map":[["title","string",true],["author",{"map":[["name","string",true],["age",{"optional":"integer"},true]]},true],["tags",{"seq":"string"},true],["ratings",{"entries":["integer","integer"]},true]]}"#
+    );
+    assert_eq!(serde_json::from_str::<KeySchema>(&json)?, schema);
+
+    // Schemas can also be stored as keys.
+    assert_eq!(from_key::<KeySchema, _>(&to_key(&schema)?)?, schema);
+
+    assert!(serde_json::from_str::<KeySchema>(r#""number""#).is_err());
+    assert!(serde_json::from_str::<KeySchema>(r#"{"seq":"string","map":[]}"#).is_err());
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use serde_derive::Serialize;
+use serde_hashkey::{key, to_key, Error, Key};
+
+#[derive(Serialize)]
+struct Author {
+    name: &'static str,
+    age: u32,
+}
+
+#[derive(Serialize)]
+struct Book {
+    title: &'static str,
+    author: Author,
+    edition: u32,
+}
+
+fn book(edition: u32, age: u32) -> Key {
+    to_key(&Book {
+        title: "Dune",
+        author: Author {
+            name: "Frank Herbert",
+            age,
+        },
+        edition,
+    })
+    .unwrap()
+}
+
+#[test]
+fn test_select_nested() {
+    let key = book(1, 60);
+
+    assert_eq!(
+        key.select(&["title", "author.name"]),
+        key!({
+            "author": { "name": "Frank Herbert" },
+            "title": "Dune",
+        })
+    );
+}
+
+#[test]
+fn test_select_overlapping() {
+    let key = book(1, 60);
+    let expected = key!({ "author": { "age": 60u32, "name": "Frank Herbert" } });
+
+    assert_eq!(key.select(&["author.name", "author"]), expected);
+    assert_eq!(key.select(&["author", "author.name"]), expected);
+}
+
+#[test]
+fn test_select_ignores_unselected() {
+    let a = book(1, 60);
+    let b = book(2, 65);
+
+    assert_ne!(a, b);
+    assert_eq!(
+        a.select(&["title", "author.name"]),
+        b.select(&["title", "author.name"])
+    );
+}
+
+#[test]
+fn test_select_missing() {
+    let key = book(1, 60);
+
+    assert_eq!(
+        key.select(&["title", "missing", "author.name.inner"]),
+        key!({ "title": "Dune" })
+    );
+
+    assert_eq!(
+        key.try_select(&["title", "author.missing"]),
+        Err(Error::MissingPath("author.missing".into()))
+    );
+}
@@ -0,0 +1,96 @@
+use serde_derive::Serialize;
+use serde_hashkey::{key, to_key, Error, Key};
+
+#[derive(Serialize)]
+struct Author {
+    name: &'static str,
+}
+
+#[test]
+fn test_build() -> Result<(), Error> {
+    let mut key = Key::Unit;
+
+    for n in 0..4u32 {
+        key.push(Key::from(n))?;
+    }
+
+    assert_eq!(key, to_key(&vec![0u32, 1, 2, 3])?);
+
+    let mut key = Key::Unit;
+    key.push(key!("a"))?;
+    key.push(to_key(&Author { name: "Noah" })?)?;
+    key.insert(1, Key::from(1u32))?;
+
+    assert_eq!(
+        key,
+        to_key(&(String::from("a"), 1u32, Author { name: "Noah" }))?
+    );
+    Ok(())
+}
+
+#[test]
+fn test_trim() -> Result<(), Error> {
+    let mut key = to_key(&vec![1u32, 2, 3, 4])?;
+
+    assert_eq!(key.pop()?, Some(Key::from(4u32)));
+    assert_eq!(key.remove(0)?, Key::from(1u32));
+    assert_eq!(key, to_key(&vec![2u32, 3])?);
+
+    key.truncate(1)?;
+    assert_eq!(key, to_key(&vec![2u32])?);
+
+    assert_eq!(key.pop()?, Some(Key::from(2u32)));
+    assert_eq!(key.pop()?, None);
+    assert_eq!(key, to_key(&Vec::<u32>::new())?);
+    Ok(())
+}
+
+#[test]
+fn test_compaction() -> Result<(), Error> {
+    let mut key = to_key(&vec![1u32, 2])?;
+    key.push(key!("a"))?;
+    assert_eq!(key, to_key(&(1u32, 2u32, "a"))?);
+
+    key.pop()?;
+    assert_eq!(key, to_key(&vec![1u32, 2])?);
+    assert!(matches!(key, Key::IntegerSeq(..)));
+    Ok(())
+}
+
+#[test]
+fn test_unit() -> Result<(), Error> {
+    let mut key: Key = Key::Unit;
+    assert_eq!(key.pop()?, None);
+    key.truncate(0)?;
+    assert_eq!(key, Key::Unit);
+
+    key.insert(0, key!("a"))?;
+    assert_eq!(key, to_key(&vec!["a"])?);
+    Ok(())
+}
+
+#[test]
+fn test_not_sequence() -> Result<(), Error> {
+    let mut key = to_key(&Author { name: "Noah" })?;
+    let original = key.clone();
+
+    assert_eq!(key.push(Key::Unit), Err(Error::Unexpected("sequence")));
+    assert_eq!(key.pop(), Err(Error::Unexpected("sequence")));
+    assert_eq!(key.truncate(0), Err(Error::Unexpected("sequence")));
+    assert_eq!(key, original);
+    Ok(())
+}
+
+#[test]
+#[should_panic(expected = "insertion index (is 3) should be <= len (is 2)")]
+fn test_insert_out_of_bounds() {
+    let mut key = to_key(&vec![1u32, 2]).unwrap();
+    let _ = key.insert(3, Key::Unit);
+}
+
+#[test]
+#[should_panic(expected = "removal index (is 0) should be < len (is 0)")]
+fn test_remove_unit() {
+    let mut key: Key = Key::Unit;
+    let _ = key.remove(0);
+}
@@ -0,0 +1,168 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use serde::de::DeserializeOwned;
+use serde_derive::{Deserialize, Serialize};
+use serde_hashkey::{from_key, from_key_owned, key, to_key, Error, Key};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Page {
+    page: u32,
+    size: Option<u32>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Filter {
+    ids: Vec<u64>,
+    #[serde(flatten)]
+    page: Page,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Request {
+    path: String,
+    #[serde(flatten)]
+    filter: Filter,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Query {
+    path: String,
+    #[serde(flatten)]
+    params: HashMap<String, String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Method {
+    Get,
+    Post { body: Vec<u8> },
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Call {
+    #[serde(flatten)]
+    method: Method,
+    #[serde(flatten)]
+    page: Page,
+    #[serde(flatten)]
+    rest: BTreeMap<String, Key>,
+}
+
+/// Deserialize the given key both by reference and by value, and check that
+/// it produces the expected value.
+fn check<T>(value: &T, key: &Key) -> Result<(), Error>
+where
+    T: fmt::Debug + PartialEq + serde::Serialize + DeserializeOwned,
+{
+    let output = from_key::<T, _>(key)?;
+    assert_eq!(output, *value);
+    assert_eq!(to_key(&output)?.normalize(), to_key(value)?.normalize());
+    assert_eq!(from_key_owned::<T, _>(key.clone())?, *value);
+    Ok(())
+}
+
+/// Round trip the given value both by reference and by value, and through
+/// its normalized key.
+fn roundtrip<T>(value: &T) -> Result<Key, Error>
+where
+    T: fmt::Debug + PartialEq + serde::Serialize + DeserializeOwned,
+{
+    let key = to_key(value)?;
+    check(value, &key)?;
+    check(value, &key.clone().normalize())?;
+
+    Ok(key)
+}
+
+#[test]
+fn test_flatten() -> Result<(), Error> {
+    let key = roundtrip(&Filter {
+        ids: vec![1, 2],
+        page: Page {
+            page: 1,
+            size: None,
+        },
+    })?;
+
+    assert_eq!(
+        key,
+        key!({ "ids": [1u64, 2u64], "page": 1u32, "size": null })
+    );
+
+    roundtrip(&Filter {
+        ids: vec![],
+        page: Page {
+            page: 2,
+            size: Some(10),
+        },
+    })?;
+    Ok(())
+}
+
+#[test]
+fn test_nested_flatten() -> Result<(), Error> {
+    let key = roundtrip(&Request {
+        path: String::from("/books"),
+        filter: Filter {
+            ids: vec![3],
+            page: Page {
+                page: 1,
+                size: Some(20),
+            },
+        },
+    })?;
+
+    assert_eq!(key.get(&key!("path")), Some(&key!("/books")));
+    assert_eq!(key.get(&key!("size")), Some(&key!(20u32)));
+    Ok(())
+}
+
+#[test]
+fn test_catch_all() -> Result<(), Error> {
+    let mut params = HashMap::new();
+    params.insert(String::from("sort"), String::from("asc"));
+    params.insert(String::from("q"), String::from("birds"));
+
+    roundtrip(&Query {
+        path: String::from("/search"),
+        params,
+    })?;
+
+    roundtrip(&Query {
+        path: String::from("/search"),
+        params: HashMap::new(),
+    })?;
+
+    // Entries which aren't strings are rejected by the catch-all.
+    let key = key!({ "path": "/", "page": 1u32 });
+    assert!(from_key::<Query, _>(&key).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_flatten_enum_and_keys() -> Result<(), Error> {
+    let mut rest = BTreeMap::new();
+    rest.insert(String::from("ids"), key!([1u32, 2u32]));
+    rest.insert(String::from("tag"), key!("a"));
+
+    roundtrip(&Call {
+        method: Method::Post {
+            body: vec![1, 2, 3],
+        },
+        page: Page {
+            page: 1,
+            size: None,
+        },
+        rest,
+    })?;
+
+    roundtrip(&Call {
+        method: Method::Get,
+        page: Page {
+            page: 1,
+            size: Some(1),
+        },
+        rest: BTreeMap::new(),
+    })?;
+    Ok(())
+}
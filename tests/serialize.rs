@@ -0,0 +1,176 @@
+use std::sync::Arc;
+
+use serde::ser::{
+    Serialize as _, SerializeSeq as _, SerializeStructVariant as _, SerializeTupleVariant as _,
+};
+use serde_derive::Serialize;
+use serde_hashkey::{
+    to_key, Error, Key, KeySerializer, RejectFloatPolicy, SerializeMap, SerializeStructVariant,
+    SerializeTupleVariant, SerializeVec,
+};
+
+#[derive(Serialize)]
+struct Author {
+    name: &'static str,
+    age: u32,
+}
+
+#[derive(Serialize)]
+enum Shape {
+    Rectangle(u32, u32),
+    Circle { radius: u32 },
+}
+
+#[test]
+fn test_serialize_vec() -> Result<(), Error> {
+    let mut seq = SerializeVec::new();
+    seq.serialize_element(&1u32)?;
+    seq.serialize_element(&2u32)?;
+    let key: Key = seq.end()?;
+    assert_eq!(key, to_key(&vec![1u32, 2])?);
+    assert!(matches!(key, Key::IntegerSeq(..)));
+
+    let mut tuple = SerializeVec::with_capacity(2);
+    tuple.serialize_element("hello")?;
+    tuple.serialize_element(&true)?;
+    let key: Key = tuple.end()?;
+    assert_eq!(key, to_key(&("hello", true))?);
+
+    let key: Key = SerializeVec::default().end()?;
+    assert_eq!(key, to_key(&Vec::<u32>::new())?);
+    Ok(())
+}
+
+#[test]
+fn test_serialize_map() -> Result<(), Error> {
+    use serde::ser::SerializeMap as _;
+
+    let mut map = SerializeMap::new();
+    map.serialize_entry("name", "Noah")?;
+    map.serialize_entry("age", &42u32)?;
+    let key: Key = map.end()?;
+    assert_eq!(
+        key,
+        to_key(&Author {
+            name: "Noah",
+            age: 42
+        })?
+    );
+
+    let mut map = SerializeMap::default();
+    serde::ser::SerializeStruct::serialize_field(&mut map, "name", "Noah")?;
+    serde::ser::SerializeStruct::serialize_field(&mut map, "age", &42u32)?;
+    let key: Key = serde::ser::SerializeStruct::end(map)?;
+    assert_eq!(
+        key,
+        to_key(&Author {
+            name: "Noah",
+            age: 42
+        })?
+    );
+
+    let mut map = SerializeMap::<serde_hashkey::RejectFloatPolicy>::new();
+    assert!(matches!(
+        map.serialize_value(&42u32),
+        Err(Error::MissingValue)
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_serialize_variants() -> Result<(), Error> {
+    let mut variant = SerializeTupleVariant::new("Rectangle");
+    variant.serialize_field(&3u32)?;
+    variant.serialize_field(&4u32)?;
+    let key: Key = variant.end()?;
+    assert_eq!(key, to_key(&Shape::Rectangle(3, 4))?);
+
+    let mut variant = SerializeStructVariant::new("Circle");
+    variant.serialize_field("radius", &7u32)?;
+    let key: Key = variant.end()?;
+    assert_eq!(key, to_key(&Shape::Circle { radius: 7 })?);
+    Ok(())
+}
+
+#[test]
+fn test_serialize_rejects_floats() {
+    let mut seq = SerializeVec::<serde_hashkey::RejectFloatPolicy>::new();
+    assert!(matches!(
+        seq.serialize_element(&1.0f32),
+        Err(Error::FloatRejected { path, error })
+            if path.to_string() == "[0]"
+                && matches!(*error, Error::UnsupportedFloat { value, .. } if value == 1.0)
+    ));
+}
+
+#[test]
+fn test_key_serializer() -> Result<(), Error> {
+    let author = Author {
+        name: "Noah",
+        age: 42,
+    };
+
+    let key: Key = author.serialize(KeySerializer::new())?;
+    assert_eq!(key, to_key(&author)?);
+
+    let shape = Shape::Circle { radius: 7 };
+    let key = shape.serialize(KeySerializer::<RejectFloatPolicy>::default())?;
+    assert_eq!(key, to_key(&shape)?);
+    Ok(())
+}
+
+#[test]
+fn test_transcode() -> Result<(), Box<dyn std::error::Error>> {
+    // JSON integers are transcoded as `u64`.
+    #[derive(Serialize)]
+    struct Document {
+        name: &'static str,
+        age: u64,
+    }
+
+    let mut json = serde_json::Deserializer::from_str(r#"{"name": "Noah", "age": 42}"#);
+    let key: Key = serde_transcode::transcode(&mut json, KeySerializer::new())?;
+
+    let document = Document {
+        name: "Noah",
+        age: 42,
+    };
+
+    assert_eq!(key, to_key(&document)?);
+
+    let mut json = serde_json::Deserializer::from_str(r#"{"rating": 4.5}"#);
+    let result: Result<Key, _> = serde_transcode::transcode(&mut json, KeySerializer::new());
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_interned_names() -> Result<(), Error> {
+    #[derive(Serialize)]
+    enum Shape {
+        Circle { radius: u32 },
+    }
+
+    fn names(key: &Key) -> (&Key, &Key) {
+        match key {
+            Key::Map(variant) => match &variant[0] {
+                (name, Key::Map(fields)) => (name, &fields[0].0),
+                _ => panic!("expected struct variant"),
+            },
+            _ => panic!("expected struct variant"),
+        }
+    }
+
+    let a = to_key(&Shape::Circle { radius: 1 })?;
+    let b = to_key(&Shape::Circle { radius: 2 })?;
+
+    match (names(&a), names(&b)) {
+        ((Key::String(a1), Key::String(a2)), (Key::String(b1), Key::String(b2))) => {
+            assert!(Arc::ptr_eq(a1, b1));
+            assert!(Arc::ptr_eq(a2, b2));
+        }
+        _ => panic!("expected string names"),
+    }
+
+    Ok(())
+}
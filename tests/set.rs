@@ -0,0 +1,230 @@
+use serde_derive::Serialize;
+use serde_hashkey::{key, to_key, Error, Key};
+
+#[derive(Serialize)]
+struct Author {
+    name: &'static str,
+    age: u32,
+}
+
+#[derive(Serialize)]
+struct Book {
+    title: &'static str,
+    author: Author,
+}
+
+#[test]
+fn test_nested() -> Result<(), Error> {
+    let a = to_key(&Book {
+        title: "Birds of a feather",
+        author: Author {
+            name: "Noah",
+            age: 42,
+        },
+    })?;
+
+    let b = to_key(&Book {
+        title: "Birds of a feather",
+        author: Author {
+            name: "Noah",
+            age: 43,
+        },
+    })?;
+
+    assert_eq!(
+        a.intersection(&b),
+        key!({
+            "author": { "name": "Noah" },
+            "title": "Birds of a feather",
+        })
+    );
+
+    assert_eq!(a.difference(&b), key!({ "author": { "age": 42u32 } }));
+    assert_eq!(b.difference(&a), key!({ "author": { "age": 43u32 } }));
+
+    Ok(())
+}
+
+#[test]
+fn test_type_mismatch() {
+    let a = key!({ "author": { "name": "Noah" }, "id": 1u32 });
+    let b = key!({ "author": "Noah", "id": 1u32 });
+
+    assert_eq!(a.intersection(&b), key!({ "id": 1u32 }));
+    assert_eq!(a.difference(&b), key!({ "author": { "name": "Noah" } }));
+    assert_eq!(b.difference(&a), key!({ "author": "Noah" }));
+}
+
+#[test]
+fn test_empty_results() {
+    let a = key!({ "a": 1u32, "b": { "c": "d" } });
+    let b = key!({ "a": 2u32, "b": { "c": "e" } });
+
+    assert_eq!(a.intersection(&b), key!({}));
+    assert_eq!(a.difference(&a), key!({}));
+    assert_eq!(a.intersection(&key!({})), key!({}));
+    assert_eq!(a.difference(&key!({})), a);
+}
+
+#[test]
+fn test_non_maps() {
+    assert_eq!(key!("a").intersection(&key!("a")), key!("a"));
+    assert_eq!(key!("a").intersection(&key!("b")), key!({}));
+    assert_eq!(key!("a").difference(&key!("a")), key!({}));
+    assert_eq!(key!("a").difference(&key!("b")), key!("a"));
+    assert_eq!(key!("a").intersection(&key!({})), key!({}));
+    assert_eq!(key!({}).intersection(&key!("a")), key!({}));
+}
+
+#[test]
+fn test_duplicate_entries() {
+    // Later entries replace earlier ones with the same key, like when merging.
+    let b = key!({ "a": 1u32, "a": 2u32 });
+
+    let a = key!({ "a": 2u32 });
+    assert_eq!(a.intersection(&b), a);
+    assert_eq!(a.difference(&b), key!({}));
+
+    let a = key!({ "a": 1u32 });
+    assert_eq!(a.intersection(&b), key!({}));
+    assert_eq!(a.difference(&b), a);
+}
+
+#[test]
+fn test_results_are_normalized() {
+    let a = key!({ "b": 2u32, "a": 1u32, "c": 3u32 });
+    let b = key!({ "c": 4u32 });
+
+    assert_eq!(a.difference(&b), key!({ "a": 1u32, "b": 2u32, "c": 3u32 }));
+}
+
+#[test]
+fn test_is_subset_of() {
+    let full = key!({
+        "author": { "age": 42u32, "name": "Noah" },
+        "tags": ["new", { "a": "b", "c": "d" }],
+        "topic": "books",
+    });
+
+    let partial = key!({ "author": { "name": "Noah" } });
+    assert!(partial.is_subset_of(&full));
+    assert!(!full.is_subset_of(&partial));
+
+    let wrong = key!({ "author": { "name": "Jane" } });
+    assert!(!wrong.is_subset_of(&full));
+
+    let mismatch = key!({ "author": "Noah" });
+    assert!(!mismatch.is_subset_of(&full));
+
+    assert!(key!({}).is_subset_of(&full));
+    assert!(!key!("books").is_subset_of(&full));
+}
+
+#[test]
+fn test_is_subset_of_sequences() {
+    let full = key!({ "tags": ["new", { "a": "b", "c": "d" }] });
+
+    let prefix = key!({ "tags": ["new"] });
+    assert!(prefix.is_subset_of(&full));
+
+    let nested = key!({ "tags": ["new", { "c": "d" }] });
+    assert!(nested.is_subset_of(&full));
+
+    let not_prefix = key!({ "tags": [{}] });
+    assert!(!not_prefix.is_subset_of(&full));
+
+    let longer = key!({ "tags": ["new", {}, "extra"] });
+    assert!(!longer.is_subset_of(&full));
+}
+
+#[test]
+fn test_is_subset_of_sanity() {
+    let keys = vec![
+        key!(()),
+        key!("a"),
+        key!(["a", "b"]),
+        key!(["a"]),
+        key!({ "a": "b" }),
+        key!({ "a": "b", "c": { "d": "e" } }),
+        key!({ "a": "b", "c": {} }),
+    ];
+
+    for a in &keys {
+        assert!(a.is_subset_of(a), "{:?} should be a subset of itself", a);
+
+        for b in &keys {
+            if a.is_subset_of(b) && b.is_subset_of(a) {
+                assert_eq!(a, b);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_merge() -> Result<(), Error> {
+    let a = to_key(&Book {
+        title: "Birds of a feather",
+        author: Author {
+            name: "Noah",
+            age: 42,
+        },
+    })?;
+
+    let b = key!({ "author": { "age": 43u32 }, "isbn": "123" });
+
+    // Nested maps are replaced, not merged.
+    let expected = key!({
+        "author": { "age": 43u32 },
+        "isbn": "123",
+        "title": "Birds of a feather",
+    });
+
+    assert_eq!(a.clone().merge(b.clone()), expected);
+
+    let expected = key!({
+        "author": { "age": 43u32, "name": "Noah" },
+        "isbn": "123",
+        "title": "Birds of a feather",
+    });
+
+    assert_eq!(a.merge_deep(b), expected);
+    Ok(())
+}
+
+#[test]
+fn test_merge_conflicts() {
+    let a = key!({ "a": "x", "b": { "c": "y" } });
+
+    // The value from `other` wins, even if only one of the values is a map.
+    let b = key!({ "a": { "c": "z" }, "b": "w" });
+    assert_eq!(a.clone().merge(b.clone()), b);
+    assert_eq!(a.clone().merge_deep(b.clone()), b);
+
+    // Merging with an empty map changes nothing.
+    assert_eq!(a.clone().merge(key!({})), a);
+    assert_eq!(key!({}).merge_deep(a.clone()), a);
+}
+
+#[test]
+fn test_merge_non_maps() {
+    let a = key!({ "a": "x" });
+
+    // Non-maps replace the key wholesale.
+    assert_eq!(a.clone().merge(key!("b")), key!("b"));
+    assert_eq!(key!("b").merge(a.clone()), a);
+    assert_eq!(a.clone().merge_deep(key!(())), key!(()));
+    assert_eq!(key!("a").merge(key!("b")), key!("b"));
+}
+
+#[test]
+fn test_merge_is_normalized() {
+    let a = key!({ "b": "1", "a": "2" });
+    let b = key!({ "c": Key::Seq(vec![Key::from(1u32), Key::from(2u32)].into()) });
+
+    let merged = a.merge(b);
+    assert_eq!(merged, merged.clone().normalize());
+    assert!(matches!(
+        merged.iter_map().unwrap().nth(2),
+        Some((_, Key::IntegerSeq(..)))
+    ));
+}
@@ -0,0 +1,101 @@
+use serde_derive::{Deserialize, Serialize};
+use serde_hashkey::{
+    from_key, to_key_with_string_float, Error, Float, FloatRepr, Key, StringFloat,
+    StringFloatPolicy,
+};
+
+#[test]
+fn test_equal_strings() -> Result<(), Error> {
+    let a = to_key_with_string_float(&1.00f64)?;
+    let b = to_key_with_string_float(&1.0f64)?;
+    assert_eq!(a, b);
+
+    assert_ne!(
+        to_key_with_string_float(&(0.1f64 + 0.2f64))?,
+        to_key_with_string_float(&0.3f64)?
+    );
+    Ok(())
+}
+
+#[test]
+fn test_as_str() -> Result<(), Error> {
+    let cases: [(f64, &str); 8] = [
+        (1.0, "1.0"),
+        (-0.0, "-0.0"),
+        (0.1 + 0.2, "0.30000000000000004"),
+        (1e300, "1e300"),
+        (1e-5, "1e-5"),
+        (f64::NAN, "NaN"),
+        (f64::NEG_INFINITY, "-inf"),
+        (-2.2250738585072014e-308, "-2.2250738585072014e-308"),
+    ];
+
+    for (value, expected) in cases {
+        let float = <StringFloat<f64> as FloatRepr<f64>>::serialize(value)?;
+        assert_eq!(float.as_str(), expected);
+    }
+
+    let float = <StringFloat<f32> as FloatRepr<f32>>::serialize(0.1f32)?;
+    assert_eq!(float.as_str(), "0.1");
+    assert_eq!(float.get(), 0.1f32);
+    Ok(())
+}
+
+#[test]
+fn test_special_values() -> Result<(), Error> {
+    let a = to_key_with_string_float(&f64::NAN)?;
+    let b = to_key_with_string_float(&-f64::NAN)?;
+    assert_eq!(a, b);
+
+    assert_ne!(
+        to_key_with_string_float(&-0.0f64)?,
+        to_key_with_string_float(&0.0f64)?
+    );
+    Ok(())
+}
+
+#[test]
+fn test_ordered_by_string() -> Result<(), Error> {
+    let a = to_key_with_string_float(&10.0f64)?;
+    let b = to_key_with_string_float(&9.0f64)?;
+    assert!(a < b);
+    Ok(())
+}
+
+#[test]
+fn test_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Sample {
+        small: f32,
+        large: f64,
+        tiny: f64,
+    }
+
+    let sample = Sample {
+        small: 0.1,
+        large: 1e300,
+        tiny: 5e-324,
+    };
+
+    let key = to_key_with_string_float(&sample)?;
+    assert_eq!(from_key::<Sample, _>(&key)?, sample);
+
+    let json = serde_json::to_string(&key)?;
+    assert_eq!(json, r#"{"small":0.1,"large":1e+300,"tiny":5e-324}"#);
+
+    let bytes = key.to_bytes();
+    let decoded = Key::<StringFloatPolicy>::from_bytes(&bytes)?;
+    assert_eq!(decoded, key.normalize());
+    assert_eq!(from_key::<Sample, _>(&decoded)?, sample);
+    Ok(())
+}
+
+#[test]
+fn test_from_float() {
+    let key: Key<StringFloatPolicy> = Key::from(1e300f64);
+
+    match key {
+        Key::Float(Float::F64(float)) => assert_eq!(float.as_str(), "1e300"),
+        _ => panic!("expected float"),
+    }
+}
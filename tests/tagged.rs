@@ -0,0 +1,193 @@
+use std::collections::BTreeMap;
+
+use serde_derive::{Deserialize, Serialize};
+use serde_hashkey::{from_key, from_key_owned, key, to_key, Error, Key};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Author {
+    name: String,
+    age: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+enum Event {
+    Created {
+        id: u64,
+        author: Author,
+        tags: Vec<String>,
+        scores: Vec<u32>,
+        ranks: BTreeMap<u32, i64>,
+        parent: Option<u64>,
+    },
+    Deleted {
+        id: u64,
+        reason: Option<String>,
+    },
+    Ping,
+    Wrapped(Author),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "data")]
+enum Adjacent {
+    Count(i32),
+    Author(Author),
+    Empty,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data")]
+enum Message {
+    Post { id: u64, author: Author },
+    Reply(Author),
+    Batch(Vec<u32>),
+    Pair(u32, String),
+    Empty,
+}
+
+/// Reverse the entries of a map, so that the tag entry of an enum which was
+/// serialized into it comes last.
+fn reversed(key: Key) -> Key {
+    match key {
+        Key::Map(entries) => {
+            let mut entries = entries.into_vec();
+            entries.reverse();
+            Key::Map(entries.into())
+        }
+        key => key,
+    }
+}
+
+fn round_trip<T>(value: T) -> Result<Key, Error>
+where
+    T: std::fmt::Debug + PartialEq + serde::Serialize + serde::de::DeserializeOwned,
+{
+    let key = to_key(&value)?;
+    assert_eq!(from_key::<T, _>(&key)?, value);
+    assert_eq!(from_key::<T, _>(&key.clone().normalize())?, value);
+    assert_eq!(from_key::<T, _>(&reversed(key.clone()))?, value);
+    assert_eq!(from_key_owned::<T, _>(key.clone())?, value);
+    assert_eq!(from_key_owned::<T, _>(reversed(key.clone()))?, value);
+    Ok(key)
+}
+
+#[test]
+fn test_internally_tagged() -> Result<(), Error> {
+    let key = round_trip(Event::Created {
+        id: 1,
+        author: Author {
+            name: String::from("Noah"),
+            age: Some(42),
+        },
+        tags: vec![String::from("fiction"), String::from("new")],
+        scores: vec![1, 2, 3],
+        ranks: vec![(1, -1), (2, -2)].into_iter().collect(),
+        parent: None,
+    })?;
+
+    assert_eq!(
+        key.get_path(&vec![serde_hashkey::PathSegment::Field("type".into())].into()),
+        Some(&key!("Created"))
+    );
+
+    round_trip(Event::Deleted {
+        id: u64::MAX,
+        reason: Some(String::from("spam")),
+    })?;
+    round_trip(Event::Deleted {
+        id: 0,
+        reason: None,
+    })?;
+    round_trip(Event::Ping)?;
+    round_trip(Event::Wrapped(Author {
+        name: String::from("Noah"),
+        age: None,
+    }))?;
+    round_trip(vec![
+        Event::Ping,
+        Event::Deleted {
+            id: 2,
+            reason: None,
+        },
+    ])?;
+    Ok(())
+}
+
+#[test]
+fn test_adjacently_tagged() -> Result<(), Error> {
+    round_trip(Adjacent::Count(-42))?;
+    round_trip(Adjacent::Author(Author {
+        name: String::from("Noah"),
+        age: Some(42),
+    }))?;
+    round_trip(Adjacent::Empty)?;
+    Ok(())
+}
+
+#[test]
+fn test_tag_and_content() -> Result<(), Error> {
+    let key = round_trip(Message::Post {
+        id: 1,
+        author: Author {
+            name: String::from("Noah"),
+            age: Some(42),
+        },
+    })?;
+
+    // The content entry sorts before the tag entry once normalized.
+    let key = key.normalize();
+    let Key::Map(entries) = &key else {
+        panic!("expected a map, got {:?}", key);
+    };
+    assert_eq!(entries[0].0, key!("data"));
+    assert_eq!(entries[1].0, key!("type"));
+
+    round_trip(Message::Reply(Author {
+        name: String::from("Noah"),
+        age: None,
+    }))?;
+    round_trip(Message::Batch(vec![]))?;
+    round_trip(Message::Batch(vec![1, 2, 3]))?;
+    round_trip(Message::Pair(1, String::from("a")))?;
+    round_trip(Message::Empty)?;
+    round_trip(vec![Message::Empty, Message::Batch(vec![1])])?;
+    Ok(())
+}
+
+#[test]
+fn test_internally_tagged_normalized() -> Result<(), Error> {
+    let key = to_key(&Event::Deleted {
+        id: 1,
+        reason: Some(String::from("spam")),
+    })?;
+
+    // The tag entry sorts after the fields once normalized.
+    let key = key.normalize();
+    let Key::Map(entries) = &key else {
+        panic!("expected a map, got {:?}", key);
+    };
+    assert_eq!(entries.last().unwrap().0, key!("type"));
+
+    assert_eq!(
+        from_key::<Event, _>(&key)?,
+        Event::Deleted {
+            id: 1,
+            reason: Some(String::from("spam")),
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn test_unknown_tag() -> Result<(), Error> {
+    #[derive(Serialize)]
+    #[serde(tag = "type")]
+    enum Other {
+        Unknown { id: u64 },
+    }
+
+    let key = to_key(&Other::Unknown { id: 1 })?;
+    assert!(from_key::<Event, _>(&key).is_err());
+    Ok(())
+}
@@ -0,0 +1,107 @@
+#![cfg(feature = "testing")]
+
+use std::collections::BTreeMap;
+
+use serde_derive::{Deserialize, Serialize};
+use serde_hashkey::key;
+use serde_hashkey::testing::{assert_key_stable, assert_roundtrip};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Author {
+    name: String,
+    age: u32,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Shape {
+    Circle(u32),
+    Rect(u32, u32),
+    Point { x: i64, y: i64 },
+    Empty,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+enum Event {
+    Created { id: u64 },
+    Deleted,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Skipped {
+    name: String,
+    #[serde(skip_serializing)]
+    hidden: Option<u32>,
+}
+
+#[test]
+fn test_roundtrip() {
+    assert_roundtrip(&Author {
+        name: String::from("Noah"),
+        age: 42,
+    });
+    assert_roundtrip(&vec![
+        Shape::Circle(1),
+        Shape::Rect(2, 3),
+        Shape::Point { x: -1, y: 1 },
+        Shape::Empty,
+    ]);
+    assert_roundtrip(&vec![Event::Created { id: 7 }, Event::Deleted]);
+    assert_roundtrip(&(1u8, -2i128, Some(String::from("a")), ()));
+    assert_roundtrip(&vec![(1u32, 2u64)].into_iter().collect::<BTreeMap<_, _>>());
+    assert_roundtrip(&key!("key"));
+}
+
+#[cfg(feature = "ordered-float")]
+#[test]
+fn test_roundtrip_ordered_float() {
+    use serde_hashkey::testing::assert_roundtrip_ordered_float;
+
+    assert_roundtrip_ordered_float(&vec![1.5f32, -0.0, f32::INFINITY]);
+    assert_roundtrip_ordered_float(&(1.5f64, 2u32));
+}
+
+#[test]
+#[should_panic(expected = "changed when round tripped through a key")]
+fn test_roundtrip_mismatch() {
+    assert_roundtrip(&Skipped {
+        name: String::from("a"),
+        hidden: Some(1),
+    });
+}
+
+#[test]
+#[should_panic(expected = "failed to serialize")]
+fn test_roundtrip_unsupported() {
+    assert_roundtrip(&1.5f32);
+}
+
+#[test]
+fn test_key_stable() {
+    let expected = key!({ "name": "Noah", "age": 42u32 });
+
+    assert_key_stable(
+        &Author {
+            name: String::from("Noah"),
+            age: 42,
+        },
+        &expected,
+    );
+
+    let expected = key!({ "Circle": 1u32 });
+    assert_key_stable(&Shape::Circle(1), &expected);
+}
+
+#[test]
+#[should_panic(expected = "- age: Integer(U32(42))\n  + age: Integer(U32(43))")]
+fn test_key_changed() {
+    let expected = key!({ "name": "Noah", "age": 42u32 });
+
+    assert_key_stable(
+        &Author {
+            name: String::from("Noah"),
+            age: 43,
+        },
+        &expected,
+    );
+}
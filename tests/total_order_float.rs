@@ -0,0 +1,185 @@
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use serde_hashkey::{from_key, to_key_with_total_order_float, Error, TotalOrderFloat};
+
+/// A small xorshift generator, so that the property tests are deterministic.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+fn hash<T>(value: &T) -> u64
+where
+    T: Hash,
+{
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// The reference implementation of `totalOrder`, which flips the bits of
+/// negative floats and the sign bit of positive ones so that the bits can be
+/// compared as unsigned integers.
+fn total_order_key_f64(bits: u64) -> u64 {
+    if bits >> 63 == 1 {
+        !bits
+    } else {
+        bits | 1 << 63
+    }
+}
+
+fn total_order_key_f32(bits: u32) -> u32 {
+    if bits >> 31 == 1 {
+        !bits
+    } else {
+        bits | 1 << 31
+    }
+}
+
+/// Random bit patterns, mixed with the special values which are most likely to
+/// be ordered incorrectly.
+fn samples_f64() -> Vec<u64> {
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+
+    let mut bits = vec![
+        0.0f64.to_bits(),
+        (-0.0f64).to_bits(),
+        f64::INFINITY.to_bits(),
+        f64::NEG_INFINITY.to_bits(),
+        f64::NAN.to_bits(),
+        (-f64::NAN).to_bits(),
+        0x7ff0_0000_0000_0001,
+        0xfff0_0000_0000_0001,
+        0x7fff_ffff_ffff_ffff,
+        0xffff_ffff_ffff_ffff,
+        f64::MIN_POSITIVE.to_bits(),
+        1,
+        1 << 63 | 1,
+    ];
+
+    bits.extend((0..500).map(|_| rng.next()));
+    // Include duplicates, so that equality is exercised.
+    bits.extend_from_within(..50);
+    bits
+}
+
+#[test]
+fn test_total_order_f64() {
+    let bits = samples_f64();
+
+    for &a in &bits {
+        let fa = TotalOrderFloat(f64::from_bits(a));
+
+        for &b in &bits {
+            let fb = TotalOrderFloat(f64::from_bits(b));
+            let expected = total_order_key_f64(a).cmp(&total_order_key_f64(b));
+
+            assert_eq!(fa.cmp(&fb), expected, "{:#x} cmp {:#x}", a, b);
+            assert_eq!(fa.partial_cmp(&fb), Some(expected));
+            assert_eq!(fb.cmp(&fa), expected.reverse());
+            assert_eq!(fa == fb, a == b);
+            assert_eq!(fa == fb, expected == Ordering::Equal);
+
+            if fa == fb {
+                assert_eq!(hash(&fa), hash(&fb));
+            }
+        }
+    }
+
+    let mut sorted = bits
+        .iter()
+        .map(|&b| TotalOrderFloat(f64::from_bits(b)))
+        .collect::<Vec<_>>();
+    sorted.sort();
+
+    let mut expected = bits;
+    expected.sort_by_key(|&b| total_order_key_f64(b));
+
+    let sorted = sorted.iter().map(|f| f.0.to_bits()).collect::<Vec<_>>();
+    assert_eq!(sorted, expected);
+}
+
+#[test]
+fn test_total_order_f32() {
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+
+    let mut bits = vec![
+        0.0f32.to_bits(),
+        (-0.0f32).to_bits(),
+        f32::INFINITY.to_bits(),
+        f32::NEG_INFINITY.to_bits(),
+        f32::NAN.to_bits(),
+        (-f32::NAN).to_bits(),
+        0x7f80_0001,
+        0xff80_0001,
+    ];
+
+    bits.extend((0..500).map(|_| rng.next() as u32));
+
+    let mut sorted = bits
+        .iter()
+        .map(|&b| TotalOrderFloat(f32::from_bits(b)))
+        .collect::<Vec<_>>();
+    sorted.sort();
+
+    for pair in sorted.windows(2) {
+        assert!(pair[0] <= pair[1]);
+        assert_eq!(
+            pair[0] == pair[1],
+            pair[0].0.to_bits() == pair[1].0.to_bits()
+        );
+    }
+
+    let mut expected = bits;
+    expected.sort_by_key(|&b| total_order_key_f32(b));
+
+    let sorted = sorted.iter().map(|f| f.0.to_bits()).collect::<Vec<_>>();
+    assert_eq!(sorted, expected);
+}
+
+#[test]
+fn test_keys() -> Result<(), Error> {
+    let order = [
+        -f64::NAN,
+        f64::NEG_INFINITY,
+        -1.0,
+        -0.0,
+        0.0,
+        1.0,
+        f64::INFINITY,
+        f64::NAN,
+    ];
+
+    let keys = order
+        .iter()
+        .map(to_key_with_total_order_float)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for pair in keys.windows(2) {
+        assert!(pair[0] < pair[1], "{:?} < {:?}", pair[0], pair[1]);
+    }
+
+    // Every float is preserved exactly.
+    for (key, float) in keys.iter().zip(order) {
+        assert_eq!(from_key::<f64, _>(key)?.to_bits(), float.to_bits());
+    }
+
+    let payload = f64::from_bits(0x7ff8_0000_0000_0001);
+    assert_ne!(
+        to_key_with_total_order_float(&payload)?,
+        to_key_with_total_order_float(&f64::NAN)?
+    );
+    assert_eq!(
+        to_key_with_total_order_float(&payload)?,
+        to_key_with_total_order_float(&payload)?
+    );
+    Ok(())
+}
@@ -0,0 +1,120 @@
+use std::mem;
+
+use serde_derive::Serialize;
+use serde_hashkey::{key, to_key, Error, Key};
+
+#[derive(Serialize)]
+struct Request {
+    method: &'static str,
+    headers: Vec<(&'static str, &'static str)>,
+    sizes: Vec<u32>,
+}
+
+fn lowercase(key: Key) -> Key {
+    match key {
+        Key::String(string) => Key::String(string.to_lowercase().into()),
+        key => key,
+    }
+}
+
+/// Drop a deeply nested chain of maps without recursing.
+fn unwind(mut key: Key) {
+    while let Key::Map(map) = &mut key {
+        match mem::take(map).into_vec().pop() {
+            Some((_, inner)) => key = inner,
+            None => break,
+        }
+    }
+}
+
+#[test]
+fn test_map_values() -> Result<(), Error> {
+    let key = to_key(&Request {
+        method: "GET",
+        headers: vec![("Accept", "TEXT/HTML")],
+        sizes: vec![1, 2],
+    })?;
+
+    let mut leaves = Vec::new();
+
+    let key = key.map_values(|value| {
+        leaves.push(value.clone());
+        lowercase(value)
+    });
+
+    // Map keys are not leaves, but tuples are sequences so their elements
+    // are.
+    assert_eq!(
+        leaves,
+        vec![
+            key!("GET"),
+            key!("Accept"),
+            key!("TEXT/HTML"),
+            key!(1u32),
+            key!(2u32),
+        ]
+    );
+
+    let expected = to_key(&Request {
+        method: "get",
+        headers: vec![("accept", "text/html")],
+        sizes: vec![1, 2],
+    })?;
+
+    assert_eq!(key, expected);
+    assert!(matches!(key["sizes"], Key::IntegerSeq(..)));
+
+    assert_eq!(key!("A").map_values(lowercase), key!("a"));
+    assert_eq!(key!({}).map_values(lowercase), key!({}));
+    Ok(())
+}
+
+#[test]
+fn test_map_keys() {
+    let key = key!({
+        "Content-Type": "TEXT/HTML",
+        "Nested": [{ "B": 1u32, "A": 2u32 }],
+        { "Key": "Map" }: "VALUE",
+    })
+    .normalize();
+
+    let key = key.map_keys(lowercase);
+
+    let expected = key!({
+        "content-type": "TEXT/HTML",
+        "nested": [{ "a": 2u32, "b": 1u32 }],
+        { "Key": "Map" }: "VALUE",
+    })
+    .normalize();
+
+    assert_eq!(key, expected);
+
+    // Maps whose keys are left unchanged keep their order.
+    let key = key!({ "b": 1u32, "a": 2u32 }).map_keys(lowercase);
+    assert_eq!(key, key!({ "b": 1u32, "a": 2u32 }));
+    assert_eq!(key!("A").map_keys(lowercase), key!("A"));
+}
+
+#[test]
+fn test_deep() {
+    let mut key: Key = key!("LEAF");
+
+    for _ in 0..100_000 {
+        key = Key::Map(vec![(key!("Level"), key)].into());
+    }
+
+    let key = key.map_values(lowercase).map_keys(lowercase);
+
+    let mut depth = 0;
+    let mut current = &key;
+
+    while let Key::Map(map) = current {
+        assert_eq!(map[0].0, "level");
+        depth += 1;
+        current = &map[0].1;
+    }
+
+    assert_eq!(depth, 100_000);
+    assert_eq!(*current, "leaf");
+    unwind(key);
+}
@@ -0,0 +1,105 @@
+use serde_derive::{Deserialize, Serialize};
+use serde_hashkey::{
+    from_key, from_key_with_config, to_key, to_key_transparent, to_key_with_config, EnumRepr,
+    Error, Key, KeyConfig, RejectFloatPolicy,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum Id {
+    Uuid(String),
+    Serial(u64),
+    Pair(u32, u32),
+    Named { name: String },
+    Anonymous,
+}
+
+#[test]
+fn test_newtype_variant() {
+    let s = String::from("67e55044-10b1-426f-9247-bb680e5fe0c8");
+
+    assert_eq!(
+        to_key_transparent(&Id::Uuid(s.clone())).unwrap(),
+        to_key(&s).unwrap()
+    );
+    assert_eq!(
+        to_key_transparent(&Id::Serial(42)).unwrap(),
+        to_key(&42u64).unwrap()
+    );
+    assert_ne!(to_key(&Id::Uuid(s.clone())).unwrap(), to_key(&s).unwrap());
+}
+
+#[test]
+fn test_other_variants_unchanged() {
+    for id in [
+        Id::Pair(1, 2),
+        Id::Named {
+            name: String::from("Noah"),
+        },
+        Id::Anonymous,
+    ] {
+        assert_eq!(to_key_transparent(&id).unwrap(), to_key(&id).unwrap());
+    }
+}
+
+#[test]
+fn test_nested() {
+    #[derive(Serialize)]
+    struct Record {
+        id: Id,
+        parent: Option<Id>,
+    }
+
+    #[derive(Serialize)]
+    struct Raw {
+        id: u64,
+        parent: Option<&'static str>,
+    }
+
+    let record = Record {
+        id: Id::Serial(1),
+        parent: Some(Id::Uuid(String::from("root"))),
+    };
+
+    let raw = Raw {
+        id: 1,
+        parent: Some("root"),
+    };
+
+    assert_eq!(to_key_transparent(&record).unwrap(), to_key(&raw).unwrap());
+}
+
+#[test]
+fn test_with_adjacent() {
+    let config = KeyConfig::new()
+        .enum_repr(EnumRepr::adjacent())
+        .transparent_newtype_variants(true);
+
+    let key = to_key_with_config::<RejectFloatPolicy, _>(&Id::Serial(42), &config).unwrap();
+    assert_eq!(key, to_key(&42u64).unwrap());
+
+    let key = to_key_with_config::<RejectFloatPolicy, _>(&Id::Pair(1, 2), &config).unwrap();
+    let expected = Key::Map(
+        vec![
+            (Key::String("t".into()), Key::String("Pair".into())),
+            (Key::String("c".into()), to_key(&(1u32, 2u32)).unwrap()),
+        ]
+        .into(),
+    );
+    assert_eq!(key, expected);
+}
+
+#[test]
+fn test_deserialize_is_one_way() {
+    let config = KeyConfig::new().transparent_newtype_variants(true);
+    let key = to_key_with_config::<RejectFloatPolicy, _>(&Id::Serial(42), &config).unwrap();
+
+    assert_eq!(
+        from_key_with_config::<Id, _>(&key, &config),
+        Err(Error::TransparentEnum("Id"))
+    );
+    assert_eq!(from_key_with_config::<u64, _>(&key, &config).unwrap(), 42);
+
+    // Without the option the key is just an integer, which is read as the
+    // index of a unit variant.
+    assert!(from_key::<Id, _>(&key).is_err());
+}
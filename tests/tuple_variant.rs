@@ -0,0 +1,58 @@
+use std::fmt;
+
+use serde::de::DeserializeOwned;
+use serde_derive::{Deserialize, Serialize};
+use serde_hashkey::{from_key, from_key_owned, to_key, Key};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Enum {
+    Operation1(Vec<u32>),
+    Operation2(Vec<String>, Vec<u32>),
+    Empty(),
+    Units((), ()),
+    Struct {},
+    Fields { a: Vec<u32>, b: () },
+}
+
+/// Round trip the given value both by reference and by value.
+fn roundtrip<T>(value: &T) -> Key
+where
+    T: fmt::Debug + PartialEq + serde::Serialize + DeserializeOwned,
+{
+    let key = to_key(value).unwrap();
+    assert_eq!(from_key::<T, _>(&key).unwrap(), *value);
+    assert_eq!(from_key_owned::<T, _>(key.clone()).unwrap(), *value);
+    key
+}
+
+#[test]
+fn test_empty_sequences() {
+    roundtrip(&Enum::Operation1(vec![]));
+    roundtrip(&Enum::Operation2(vec![], vec![]));
+    roundtrip(&Enum::Operation2(vec![String::from("a")], vec![]));
+    roundtrip(&Enum::Fields { a: vec![], b: () });
+    roundtrip(&vec![Enum::Operation1(vec![]), Enum::Operation1(vec![1])]);
+}
+
+#[test]
+fn test_empty_variants() {
+    let key = roundtrip(&Enum::Empty());
+    assert_eq!(
+        key,
+        Key::Map(Box::new([(
+            Key::String("Empty".into()),
+            Key::Seq(Box::new([]))
+        )]))
+    );
+
+    roundtrip(&Enum::Struct {});
+    roundtrip(&vec![Enum::Empty(), Enum::Struct {}]);
+}
+
+#[test]
+fn test_units() {
+    roundtrip(&Enum::Units((), ()));
+    roundtrip(&vec![((), ()), ((), ())]);
+    roundtrip(&vec![Vec::<()>::new(), vec![()], vec![(), ()]]);
+    roundtrip(&((), Vec::<()>::new()));
+}
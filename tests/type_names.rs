@@ -0,0 +1,198 @@
+use serde_derive::{Deserialize, Serialize};
+use serde_hashkey::{
+    from_key, from_key_tagged, key, to_key, to_key_tagged, to_key_with_config, EnumRepr, Error,
+    KeyConfig, KeyPath, PathSegment, RejectFloatPolicy,
+};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct A {
+    x: u32,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct B {
+    x: u32,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Unit;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Meters(u32);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Point(u32, u32);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Shape {
+    Empty,
+    Square(u32),
+    Rectangle(u32, u32),
+    Circle { radius: u32 },
+}
+
+#[test]
+fn test_distinct_types() {
+    assert_eq!(to_key(&A { x: 1 }).unwrap(), to_key(&B { x: 1 }).unwrap());
+    assert_ne!(
+        to_key_tagged(&A { x: 1 }).unwrap(),
+        to_key_tagged(&B { x: 1 }).unwrap()
+    );
+}
+
+#[test]
+fn test_plain_unchanged() {
+    assert_eq!(to_key(&A { x: 1 }).unwrap(), key!({ "x": 1u32 }));
+    assert_eq!(to_key(&Unit).unwrap(), key!(()));
+    assert_eq!(to_key(&Meters(42)).unwrap(), key!(42u32));
+    assert_eq!(to_key(&Shape::Empty).unwrap(), key!("Empty"));
+}
+
+#[test]
+fn test_shapes() {
+    assert_eq!(
+        to_key_tagged(&A { x: 1 }).unwrap(),
+        key!(["A", { "x": 1u32 }])
+    );
+    assert_eq!(to_key_tagged(&Unit).unwrap(), key!(["Unit", ()]));
+    assert_eq!(to_key_tagged(&Meters(42)).unwrap(), key!(["Meters", 42u32]));
+    assert_eq!(
+        to_key_tagged(&Point(1, 2)).unwrap(),
+        key!(["Point", [1u32, 2u32]])
+    );
+
+    for shape in [
+        Shape::Empty,
+        Shape::Square(1),
+        Shape::Rectangle(1, 2),
+        Shape::Circle { radius: 3 },
+    ] {
+        assert_eq!(
+            to_key_tagged(&shape).unwrap(),
+            key!(["Shape", (to_key(&shape).unwrap())])
+        );
+    }
+}
+
+#[test]
+fn test_nested() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Outer {
+        inner: A,
+        shapes: Vec<Shape>,
+        unit: Option<Unit>,
+    }
+
+    let outer = Outer {
+        inner: A { x: 1 },
+        shapes: vec![Shape::Square(2), Shape::Empty],
+        unit: Some(Unit),
+    };
+
+    let key = to_key_tagged(&outer).unwrap();
+
+    let expected = key!([
+        "Outer",
+        {
+            "inner": ["A", { "x": 1u32 }],
+            "shapes": [["Shape", { "Square": 2u32 }], ["Shape", "Empty"]],
+            "unit": ["Unit", ()],
+        }
+    ]);
+
+    assert_eq!(key, expected);
+    assert_eq!(from_key_tagged::<Outer, _>(&key).unwrap(), outer);
+}
+
+#[test]
+fn test_roundtrip() {
+    let key = to_key_tagged(&A { x: 1 }).unwrap();
+    assert_eq!(from_key_tagged::<A, _>(&key).unwrap(), A { x: 1 });
+    assert!(from_key_tagged::<B, _>(&key).is_err());
+    assert!(from_key::<A, _>(&key).is_err());
+
+    let key = to_key_tagged(&Unit).unwrap();
+    assert_eq!(from_key_tagged::<Unit, _>(&key).unwrap(), Unit);
+
+    let key = to_key_tagged(&Meters(42)).unwrap();
+    assert_eq!(from_key_tagged::<Meters, _>(&key).unwrap(), Meters(42));
+
+    let key = to_key_tagged(&Point(1, 2)).unwrap();
+    assert_eq!(from_key_tagged::<Point, _>(&key).unwrap(), Point(1, 2));
+
+    for shape in [
+        Shape::Empty,
+        Shape::Square(1),
+        Shape::Rectangle(1, 2),
+        Shape::Circle { radius: 3 },
+    ] {
+        let key = to_key_tagged(&shape).unwrap();
+        assert_eq!(from_key_tagged::<Shape, _>(&key).unwrap(), shape);
+    }
+
+    // Untagged keys are rejected when expecting tags.
+    let key = to_key(&A { x: 1 }).unwrap();
+    assert_eq!(
+        from_key_tagged::<A, _>(&key),
+        Err(Error::Unexpected(
+            "sequence tagged with the name of the type"
+        ))
+    );
+}
+
+#[test]
+fn test_with_adjacent() {
+    let config = KeyConfig::new()
+        .enum_repr(EnumRepr::adjacent())
+        .type_names(true);
+
+    let key = to_key_with_config::<RejectFloatPolicy, _>(&Shape::Square(1), &config).unwrap();
+
+    let expected = key!(["Shape", { "t": "Square", "c": 1u32 }]);
+
+    assert_eq!(key, expected);
+}
+
+#[test]
+fn test_float_path() {
+    #[derive(Serialize)]
+    struct Circle {
+        radius: f32,
+    }
+
+    #[derive(Serialize)]
+    enum Shape {
+        Circle { radius: f32 },
+    }
+
+    let error = to_key_tagged(&Circle { radius: 1.5 }).unwrap_err();
+
+    match error {
+        Error::FloatRejected { path, .. } => {
+            assert_eq!(
+                path,
+                KeyPath::from(vec![
+                    PathSegment::Index(1),
+                    PathSegment::Field("radius".into()),
+                ])
+            );
+        }
+        error => panic!("expected a rejected float, got {:?}", error),
+    }
+
+    let error = to_key_tagged(&Shape::Circle { radius: 1.5 }).unwrap_err();
+
+    match error {
+        Error::FloatRejected { path, .. } => {
+            assert_eq!(
+                path,
+                KeyPath::from(vec![
+                    PathSegment::Index(1),
+                    PathSegment::Field("Circle".into()),
+                    PathSegment::Field("radius".into()),
+                ])
+            );
+        }
+        error => panic!("expected a rejected float, got {:?}", error),
+    }
+}
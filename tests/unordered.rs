@@ -0,0 +1,59 @@
+use std::collections::HashMap;
+
+use serde_hashkey::{key, Key, UnorderedKey};
+
+#[test]
+fn test_reordered_maps_collide() {
+    let a = key!({
+        "name": "Noah",
+        "age": 42u32,
+        "tags": { "x": 1u32, "y": 2u32 },
+    });
+
+    let b = key!({
+        "tags": { "y": 2u32, "x": 1u32 },
+        "age": 42u32,
+        "name": "Noah",
+    });
+
+    assert_ne!(a, b);
+    assert_eq!(UnorderedKey(a.clone()), UnorderedKey(b.clone()));
+
+    let mut cache = HashMap::new();
+    cache.insert(UnorderedKey(a), 1);
+    assert_eq!(cache.insert(UnorderedKey(b), 2), Some(1));
+    assert_eq!(cache.len(), 1);
+}
+
+#[test]
+fn test_different_maps_are_distinct() {
+    let a = key!({ "a": 1u32, "b": 2u32 });
+    let b = key!({ "a": 2u32, "b": 1u32 });
+    let c = key!({ "a": 1u32 });
+
+    assert_ne!(UnorderedKey(a.clone()), UnorderedKey(b.clone()));
+    assert_ne!(UnorderedKey(a.clone()), UnorderedKey(c.clone()));
+
+    let mut cache = HashMap::new();
+    cache.insert(UnorderedKey(a), 1);
+    cache.insert(UnorderedKey(b), 2);
+    cache.insert(UnorderedKey(c), 3);
+    assert_eq!(cache.len(), 3);
+}
+
+#[test]
+fn test_maps_are_multisets() {
+    let a = key!({ "a": 1u32, "a": 1u32, "b": 2u32 });
+    let b = key!({ "a": 1u32, "b": 2u32, "b": 2u32 });
+    let c = key!({ "b": 2u32, "a": 1u32, "a": 1u32 });
+
+    assert_ne!(UnorderedKey(a.clone()), UnorderedKey(b));
+    assert_eq!(UnorderedKey(a), UnorderedKey(c));
+}
+
+#[test]
+fn test_sequences_are_ordered() {
+    let a: Key = Key::Seq(vec![Key::from(1u32), Key::from(2u32)].into());
+    let b: Key = Key::Seq(vec![Key::from(2u32), Key::from(1u32)].into());
+    assert_ne!(UnorderedKey(a), UnorderedKey(b));
+}
@@ -0,0 +1,50 @@
+use std::path::Path;
+
+use serde::ser::Serialize;
+use serde_hashkey::{key, to_key, Error, Key};
+
+fn key_of<T>(value: &T) -> Result<Key, Error>
+where
+    T: ?Sized + Serialize,
+{
+    to_key(value)
+}
+
+#[test]
+fn test_str() -> Result<(), Error> {
+    let value = String::from("hello");
+    assert_eq!(to_key(&value[..])?, key!("hello"));
+    assert_eq!(to_key("hello")?, to_key(&value)?);
+    assert_eq!(key_of("hello")?, key!("hello"));
+    Ok(())
+}
+
+#[test]
+fn test_slices() -> Result<(), Error> {
+    let bytes = b"hello".to_vec();
+    assert_eq!(to_key(&bytes[..])?, to_key(&bytes)?);
+
+    let numbers: Box<[u32]> = vec![1, 2, 3].into();
+    assert_eq!(to_key(&*numbers)?, to_key(&vec![1u32, 2, 3])?);
+    assert_eq!(key_of(&numbers[1..])?, to_key(&vec![2u32, 3])?);
+    Ok(())
+}
+
+#[test]
+fn test_path() -> Result<(), Error> {
+    assert_eq!(to_key(Path::new("a/b"))?, key!("a/b"));
+    Ok(())
+}
+
+#[cfg(feature = "ordered-float")]
+#[test]
+fn test_ordered_float() -> Result<(), Error> {
+    use serde_hashkey::to_key_with_ordered_float;
+
+    let floats = [1.5f32, 2.5];
+    assert_eq!(
+        to_key_with_ordered_float(&floats[..])?,
+        to_key_with_ordered_float(&floats.to_vec())?
+    );
+    Ok(())
+}
@@ -0,0 +1,224 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::de;
+use serde_derive::{Deserialize, Serialize};
+use serde_hashkey::{from_key, to_key, Error, Integer, Key};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+enum Value {
+    Unsigned(u64),
+    Signed(i64),
+    Bytes(Bytes),
+    Map(BTreeMap<String, Value>),
+}
+
+/// The data as written by a producer using 128-bit integers.
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Wide {
+    Unsigned(u128),
+    Signed(i128),
+    Map(BTreeMap<String, Wide>),
+}
+
+#[derive(Debug, PartialEq)]
+struct Bytes(Vec<u8>);
+
+impl serde::Serialize for Bytes {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_bytes(&self.0)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Bytes {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct Visitor;
+
+        impl<'de> de::Visitor<'de> for Visitor {
+            type Value = Bytes;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("bytes")
+            }
+
+            fn visit_bytes<E>(self, v: &[u8]) -> Result<Bytes, E> {
+                Ok(Bytes(v.to_vec()))
+            }
+        }
+
+        deserializer.deserialize_bytes(Visitor)
+    }
+}
+
+fn round_trip(value: Value) -> Result<(), Error> {
+    let key = to_key(&value)?;
+    assert_eq!(from_key::<Value, _>(&key)?, value);
+    Ok(())
+}
+
+#[test]
+fn test_untagged_integers() -> Result<(), Error> {
+    round_trip(Value::Unsigned(0))?;
+    round_trip(Value::Unsigned(u64::MAX))?;
+    round_trip(Value::Signed(-1))?;
+    round_trip(Value::Signed(i64::MIN))?;
+
+    let key = to_key(&Wide::Unsigned(u64::MAX as u128))?;
+    assert_eq!(key, Key::Integer(Integer::U128(u64::MAX as u128)));
+    assert_eq!(from_key::<Value, _>(&key)?, Value::Unsigned(u64::MAX));
+
+    let key = to_key(&Wide::Signed(i64::MIN as i128))?;
+    assert_eq!(key, Key::Integer(Integer::I128(i64::MIN as i128)));
+    assert_eq!(from_key::<Value, _>(&key)?, Value::Signed(i64::MIN));
+    Ok(())
+}
+
+#[test]
+fn test_untagged_bytes_and_maps() -> Result<(), Error> {
+    round_trip(Value::Bytes(Bytes(vec![1, 2, 3])))?;
+
+    let mut inner = BTreeMap::new();
+    inner.insert(String::from("big"), Value::Unsigned(42));
+    inner.insert(String::from("bytes"), Value::Bytes(Bytes(vec![4, 5])));
+
+    let mut map = BTreeMap::new();
+    map.insert(String::from("inner"), Value::Map(inner));
+    map.insert(String::from("small"), Value::Signed(-42));
+    round_trip(Value::Map(map))?;
+
+    let mut inner = BTreeMap::new();
+    inner.insert(String::from("big"), Wide::Unsigned(42));
+    inner.insert(String::from("small"), Wide::Signed(-42));
+
+    let mut map = BTreeMap::new();
+    map.insert(String::from("inner"), Wide::Map(inner));
+
+    let mut expected_inner = BTreeMap::new();
+    expected_inner.insert(String::from("big"), Value::Unsigned(42));
+    expected_inner.insert(String::from("small"), Value::Signed(-42));
+
+    let mut expected = BTreeMap::new();
+    expected.insert(String::from("inner"), Value::Map(expected_inner));
+
+    let key = to_key(&Wide::Map(map))?;
+    assert_eq!(from_key::<Value, _>(&key)?, Value::Map(expected));
+    Ok(())
+}
+
+#[test]
+fn test_untagged_out_of_range() -> Result<(), Error> {
+    let key = to_key(&Wide::Unsigned(u128::MAX))?;
+    assert!(from_key::<Value, _>(&key).is_err());
+
+    let key = to_key(&Wide::Signed(i128::MIN))?;
+    assert!(from_key::<Value, _>(&key).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_flatten_wide() -> Result<(), Error> {
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Range {
+        start: u64,
+        end: i64,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Outer {
+        name: String,
+        #[serde(flatten)]
+        range: Range,
+    }
+
+    /// The data as written by a producer using 128-bit integers.
+    #[derive(Debug, Serialize, Deserialize)]
+    struct WideRange {
+        start: u128,
+        end: i128,
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct WideOuter<'a> {
+        name: &'a str,
+        #[serde(flatten)]
+        range: WideRange,
+    }
+
+    let key = to_key(&WideOuter {
+        name: "range",
+        range: WideRange {
+            start: u64::MAX as u128,
+            end: i64::MIN as i128,
+        },
+    })?;
+
+    assert_eq!(
+        from_key::<Outer, _>(&key)?,
+        Outer {
+            name: String::from("range"),
+            range: Range {
+                start: u64::MAX,
+                end: i64::MIN,
+            },
+        }
+    );
+
+    let key = to_key(&WideOuter {
+        name: "range",
+        range: WideRange {
+            start: u128::MAX,
+            end: 0,
+        },
+    })?;
+
+    assert_eq!(
+        from_key::<Outer, _>(&key),
+        Err(Error::Custom(String::from(
+            "invalid type: integer `340282366920938463463374607431768211455` as u128, expected any value"
+        )))
+    );
+
+    let key = to_key(&WideOuter {
+        name: "range",
+        range: WideRange {
+            start: 0,
+            end: i128::MIN,
+        },
+    })?;
+
+    assert!(from_key::<Outer, _>(&key).is_err());
+
+    // serde can't deserialize 128-bit integer fields out of buffered content
+    // at all, even if they fit.
+    let key = to_key(&WideOuter {
+        name: "range",
+        range: WideRange { start: 1, end: -1 },
+    })?;
+
+    assert_eq!(
+        from_key::<WideOuter, _>(&key).unwrap_err(),
+        Error::Custom(String::from("u128 is not supported"))
+    );
+    Ok(())
+}
+
+#[test]
+fn test_key_preserves_width() -> Result<(), Error> {
+    let key: Key = Key::Integer(Integer::U128(42));
+    assert_eq!(from_key::<Key, _>(&key)?, key);
+
+    let key: Key = Key::Integer(Integer::I128(-42));
+    assert_eq!(from_key::<Key, _>(&key)?, key);
+
+    let key: Key = to_key(&vec![1u128, 2, 3])?;
+    assert_eq!(from_key::<Key, _>(&key)?, key);
+    Ok(())
+}
@@ -0,0 +1,117 @@
+#![cfg(feature = "valuable")]
+
+use serde_derive::Serialize;
+use serde_hashkey::{to_key, Integer, Key};
+use valuable01::{Valuable, Value, Visit};
+
+/// A simplified tree of visited values.
+#[derive(Debug, PartialEq)]
+enum Tree {
+    Unit,
+    Bool(bool),
+    U8(u8),
+    U32(u32),
+    I64(i64),
+    String(String),
+    List(Vec<Tree>),
+    Map(Vec<(Tree, Tree)>),
+    Other,
+}
+
+fn tree(value: Value<'_>) -> Tree {
+    struct Collect(Vec<Tree>, Vec<(Tree, Tree)>);
+
+    impl Visit for Collect {
+        fn visit_value(&mut self, value: Value<'_>) {
+            self.0.push(tree(value));
+        }
+
+        fn visit_entry(&mut self, key: Value<'_>, value: Value<'_>) {
+            self.1.push((tree(key), tree(value)));
+        }
+    }
+
+    match value {
+        Value::Unit => Tree::Unit,
+        Value::Bool(b) => Tree::Bool(b),
+        Value::U8(v) => Tree::U8(v),
+        Value::U32(v) => Tree::U32(v),
+        Value::I64(v) => Tree::I64(v),
+        Value::String(s) => Tree::String(s.to_owned()),
+        Value::Listable(list) => {
+            let mut collect = Collect(Vec::new(), Vec::new());
+            list.visit(&mut collect);
+            Tree::List(collect.0)
+        }
+        Value::Mappable(map) => {
+            let mut collect = Collect(Vec::new(), Vec::new());
+            map.visit(&mut collect);
+            Tree::Map(collect.1)
+        }
+        _ => Tree::Other,
+    }
+}
+
+fn s(value: &str) -> Tree {
+    Tree::String(value.to_owned())
+}
+
+#[test]
+fn test_nested() {
+    #[derive(Serialize)]
+    struct Inner {
+        flag: bool,
+        values: Vec<i64>,
+    }
+
+    #[derive(Serialize)]
+    struct Outer {
+        id: u32,
+        inner: Inner,
+        nothing: (),
+    }
+
+    let key: Key = to_key(&Outer {
+        id: 42,
+        inner: Inner {
+            flag: true,
+            values: vec![-1, 2],
+        },
+        nothing: (),
+    })
+    .unwrap();
+
+    assert_eq!(
+        tree(key.as_value()),
+        Tree::Map(vec![
+            (s("id"), Tree::U32(42)),
+            (
+                s("inner"),
+                Tree::Map(vec![
+                    (s("flag"), Tree::Bool(true)),
+                    (s("values"), Tree::List(vec![Tree::I64(-1), Tree::I64(2)])),
+                ])
+            ),
+            (s("nothing"), Tree::Unit),
+        ])
+    );
+}
+
+#[test]
+fn test_bytes_and_non_string_keys() {
+    let key: Key = Key::Map(
+        vec![(
+            Key::Integer(Integer::U32(1)),
+            Key::Bytes(vec![1u8, 2].into()),
+        )]
+        .into(),
+    );
+
+    assert_eq!(
+        tree(key.as_value()),
+        Tree::Map(vec![(
+            Tree::U32(1),
+            Tree::List(vec![Tree::U8(1), Tree::U8(2)])
+        )])
+    );
+}
@@ -2,7 +2,8 @@
 
 use serde_derive::{Deserialize, Serialize};
 use serde_hashkey::{
-    from_key, to_key, to_key_with_ordered_float, Error, Float, Key, OrderedFloat, RejectFloatPolicy,
+    from_key, to_key, to_key_with_ordered_float, Error, Float, FloatWidth, Key, OrderedFloat,
+    RejectFloatPolicy,
 };
 use std::collections::BTreeMap;
 
@@ -43,7 +44,7 @@ fn test_enum() -> Result<(), Error> {
         other => panic!("unexpected: {:?}", other),
     }
 
-    assert_eq!(value, from_key(&value)?);
+    assert_eq!(value, from_key::<Key, _>(&value)?);
     assert_eq!(Enum::Operation3, from_key(&to_key(&Enum::Operation3)?)?);
     return Ok(());
 
@@ -80,8 +81,20 @@ fn test_normalize() {
 
 #[test]
 fn deny_floats_by_default() {
-    assert_eq!(to_key(&0f32), Err(Error::UnsupportedType("f32")));
-    assert_eq!(to_key(&0f64), Err(Error::UnsupportedType("f64")));
+    assert_eq!(
+        to_key(&0f32),
+        Err(Error::UnsupportedFloat {
+            width: FloatWidth::F32,
+            value: 0.0
+        })
+    );
+    assert_eq!(
+        to_key(&0f64),
+        Err(Error::UnsupportedFloat {
+            width: FloatWidth::F64,
+            value: 0.0
+        })
+    );
     assert_eq!(
         to_key_with_ordered_float(&0f32),
         Ok(Key::Float(Float::F32(OrderedFloat(0f32))))
@@ -91,3 +104,43 @@ fn deny_floats_by_default() {
         Ok(Key::Float(Float::F64(OrderedFloat(0f64))))
     );
 }
+
+#[test]
+fn test_normalize_ordered_float() -> Result<(), Error> {
+    let mut a = BTreeMap::new();
+    a.insert("b", 2.5f64);
+    a.insert("a", f64::NAN);
+
+    let nested = |first: (f64, f64), second: (f64, f64)| {
+        let entry = |(k, v): (f64, f64)| {
+            (
+                to_key_with_ordered_float(&k).unwrap(),
+                to_key_with_ordered_float(&v).unwrap(),
+            )
+        };
+
+        Key::Map(
+            vec![(
+                Key::String("inner".into()),
+                Key::Map(vec![entry(first), entry(second)].into()),
+            )]
+            .into(),
+        )
+    };
+
+    let x = nested((f64::NAN, 1.0), (-0.5, f64::INFINITY));
+    let y = nested((-0.5, f64::INFINITY), (f64::NAN, 1.0));
+
+    assert_ne!(x, y);
+    assert_eq!(x.clone().normalize(), y.clone().normalize());
+
+    // NaN sorts after every other float, so the entry with a NaN key is last.
+    match &x.normalize()["inner"] {
+        Key::Map(map) => assert_eq!(map[1].0, to_key_with_ordered_float(&f64::NAN)?),
+        other => panic!("unexpected: {:?}", other),
+    }
+
+    let key = to_key_with_ordered_float(&a)?;
+    assert_eq!(key.clone().normalize(), key);
+    Ok(())
+}
@@ -0,0 +1,335 @@
+use std::cell::Cell;
+use std::collections::BTreeMap;
+
+use serde::Serializer;
+use serde_derive::Serialize;
+use serde_hashkey::{
+    to_key, to_key_with, value_eq_key, Error, Key, KeyPath, PathSegment, TotalOrderFloatPolicy,
+};
+
+#[derive(Serialize)]
+struct Author {
+    name: &'static str,
+    age: u32,
+    books: Vec<u32>,
+    nick: Option<&'static str>,
+}
+
+#[derive(Serialize)]
+enum Shape {
+    Empty,
+    Square(u32),
+    Rect(u32, u32),
+    Circle { radius: u32 },
+}
+
+fn noah() -> Author {
+    Author {
+        name: "Noah",
+        age: 42,
+        books: vec![1, 2, 3],
+        nick: None,
+    }
+}
+
+#[test]
+fn test_equal() {
+    let key = to_key(&noah()).unwrap();
+    assert!(value_eq_key(&noah(), &key).unwrap());
+
+    let mut author = noah();
+    author.books.push(4);
+    assert!(!value_eq_key(&author, &key).unwrap());
+
+    let mut author = noah();
+    author.books.pop();
+    assert!(!value_eq_key(&author, &key).unwrap());
+
+    let mut author = noah();
+    author.nick = Some("N");
+    assert!(!value_eq_key(&author, &key).unwrap());
+
+    // Integers of different widths are different keys.
+    assert!(!value_eq_key(&42u64, &to_key(&42u32).unwrap()).unwrap());
+    assert!(value_eq_key(&42u32, &to_key(&42u32).unwrap()).unwrap());
+}
+
+#[test]
+fn test_nested() {
+    #[derive(Serialize)]
+    struct Library {
+        authors: Vec<Author>,
+        shapes: Vec<Shape>,
+        index: BTreeMap<&'static str, (u32, Shape)>,
+    }
+
+    fn library(radius: u32) -> Library {
+        let mut index = BTreeMap::new();
+        index.insert("a", (1, Shape::Rect(1, 2)));
+        index.insert("b", (2, Shape::Circle { radius }));
+
+        Library {
+            authors: vec![noah(), noah()],
+            shapes: vec![
+                Shape::Empty,
+                Shape::Square(1),
+                Shape::Rect(1, 2),
+                Shape::Circle { radius },
+            ],
+            index,
+        }
+    }
+
+    let key = to_key(&library(3)).unwrap();
+    assert!(value_eq_key(&library(3), &key).unwrap());
+    assert!(!value_eq_key(&library(4), &key).unwrap());
+}
+
+#[test]
+fn test_map_order() {
+    let a = to_key(&[("a", 1u32), ("b", 2u32)]).unwrap();
+
+    let mut map = BTreeMap::new();
+    map.insert("b", 2u32);
+    map.insert("a", 1u32);
+
+    assert!(value_eq_key(&map, &to_key(&map).unwrap()).unwrap());
+
+    // Entries are compared in order, like keys are.
+    let key: Key = Key::Map(
+        vec![
+            (Key::String("b".into()), Key::from(2u32)),
+            (Key::String("a".into()), Key::from(1u32)),
+        ]
+        .into(),
+    );
+    assert!(!value_eq_key(&map, &key).unwrap());
+    assert!(!value_eq_key(&map, &a).unwrap());
+}
+
+#[test]
+fn test_compact_seq() {
+    let compact = to_key(&vec![1u32, 2, 3]).unwrap();
+    assert!(matches!(compact, Key::IntegerSeq(..)));
+    assert!(value_eq_key(&vec![1u32, 2, 3], &compact).unwrap());
+    assert!(!value_eq_key(&vec![1u64, 2, 3], &compact).unwrap());
+    assert!(!value_eq_key(&vec![1u32, 2], &compact).unwrap());
+
+    // Sequences which are stored compactly are not equal to the expanded
+    // sequence, since the keys aren't either.
+    let expanded = Key::Seq(vec![Key::from(1u32), Key::from(2u32), Key::from(3u32)].into());
+    assert_ne!(to_key(&vec![1u32, 2, 3]).unwrap(), expanded);
+    assert!(!value_eq_key(&vec![1u32, 2, 3], &expanded).unwrap());
+    assert!(value_eq_key(&vec![1u32, 2, 3], &expanded.normalize()).unwrap());
+
+    let mixed = to_key(&(1u32, 2u64)).unwrap();
+    assert!(matches!(mixed, Key::Seq(..)));
+    assert!(value_eq_key(&(1u32, 2u64), &mixed).unwrap());
+    assert!(!value_eq_key(&(1u32, 2u32), &mixed).unwrap());
+
+    let empty = to_key(&Vec::<u32>::new()).unwrap();
+    assert!(value_eq_key(&Vec::<u32>::new(), &empty).unwrap());
+    assert!(!value_eq_key(&vec![1u32], &empty).unwrap());
+}
+
+#[test]
+fn test_early_exit() {
+    /// Counts how many times it's serialized.
+    struct Counted<'a>(&'a Cell<usize>);
+
+    impl serde::Serialize for Counted<'_> {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            self.0.set(self.0.get() + 1);
+            serializer.serialize_u32(0)
+        }
+    }
+
+    #[derive(Serialize)]
+    struct Record<'a> {
+        id: u32,
+        counted: Counted<'a>,
+    }
+
+    let count = Cell::new(0);
+
+    let key = to_key(&Record {
+        id: 1,
+        counted: Counted(&count),
+    })
+    .unwrap();
+    assert_eq!(count.get(), 1);
+
+    let value = Record {
+        id: 2,
+        counted: Counted(&count),
+    };
+    assert!(!value_eq_key(&value, &key).unwrap());
+    assert_eq!(count.get(), 1);
+
+    let value = (2u32, Counted(&count));
+    assert!(!value_eq_key(&value, &key).unwrap());
+    assert_eq!(count.get(), 1);
+
+    let value = vec![Counted(&count), Counted(&count)];
+    assert!(!value_eq_key(&value, &key).unwrap());
+    assert_eq!(count.get(), 1);
+
+    let value = Record {
+        id: 1,
+        counted: Counted(&count),
+    };
+    assert!(value_eq_key(&value, &key).unwrap());
+    assert_eq!(count.get(), 2);
+
+    // Errors which follow a difference are not reported.
+    assert!(!value_eq_key(&(2u32, 1.5f32), &to_key(&(1u32, 2u32)).unwrap()).unwrap());
+}
+
+#[test]
+fn test_floats() {
+    let key = to_key_with::<TotalOrderFloatPolicy, _>(&(1.5f32, 2.5f64)).unwrap();
+    assert!(value_eq_key(&(1.5f32, 2.5f64), &key).unwrap());
+    assert!(!value_eq_key(&(1.5f32, 3.5f64), &key).unwrap());
+    assert!(!value_eq_key(&(1.5f64, 2.5f64), &key).unwrap());
+
+    let key = to_key_with::<TotalOrderFloatPolicy, _>(&[1.5f32, 2.5]).unwrap();
+    assert!(matches!(key, Key::FloatSeq(..)));
+    assert!(value_eq_key(&[1.5f32, 2.5], &key).unwrap());
+    assert!(!value_eq_key(&[1.5f32, 3.5], &key).unwrap());
+
+    // Floats are rejected by the default policy, with the same path.
+    let key = to_key(&Shape::Circle { radius: 1 }).unwrap();
+
+    #[derive(Serialize)]
+    enum Shape2 {
+        Circle { radius: f32 },
+    }
+
+    let error = value_eq_key(&Shape2::Circle { radius: 1.5 }, &key).unwrap_err();
+    assert_eq!(error, to_key(&Shape2::Circle { radius: 1.5 }).unwrap_err());
+
+    match error {
+        Error::FloatRejected { path, .. } => {
+            assert_eq!(
+                path,
+                KeyPath::from(vec![
+                    PathSegment::Field("Circle".into()),
+                    PathSegment::Field("radius".into()),
+                ])
+            );
+        }
+        error => panic!("expected a rejected float, got {:?}", error),
+    }
+}
+
+#[test]
+#[cfg(feature = "ordered-float")]
+fn test_ordered_float() {
+    use serde_hashkey::to_key_with_ordered_float;
+
+    let key = to_key_with_ordered_float(&vec![(1.5f32, "a"), (f32::NAN, "b")]).unwrap();
+    assert!(value_eq_key(&vec![(1.5f32, "a"), (f32::NAN, "b")], &key).unwrap());
+    assert!(!value_eq_key(&vec![(1.5f32, "a"), (2.5f32, "b")], &key).unwrap());
+}
+
+#[test]
+#[cfg(feature = "half")]
+fn test_half() {
+    use half2::f16;
+
+    let value = vec![f16::from_f32(1.5), f16::from_f32(2.0)];
+    let key = to_key_with::<TotalOrderFloatPolicy, _>(&value).unwrap();
+    assert!(value_eq_key(&value, &key).unwrap());
+    assert!(!value_eq_key(&vec![1.5f32, 2.0], &key).unwrap());
+}
+
+#[test]
+fn test_corpus() {
+    let corpus: Vec<Box<dyn erased::Serialize>> = vec![
+        Box::new(()),
+        Box::new(None::<u32>),
+        Box::new(Some(())),
+        Box::new(Some(1u32)),
+        Box::new(1u32),
+        Box::new(1u64),
+        Box::new(-1i8),
+        Box::new(true),
+        Box::new('a'),
+        Box::new("a"),
+        Box::new(String::from("Noah")),
+        Box::new(Vec::<u32>::new()),
+        Box::new(vec![1u32]),
+        Box::new(vec![1u32, 2]),
+        Box::new(vec![1u64, 2]),
+        Box::new((1u32, 2u64)),
+        Box::new((1u32, "a")),
+        Box::new(vec![vec![1u32], vec![]]),
+        Box::new(vec![vec![], vec![1u32]]),
+        Box::new(noah()),
+        Box::new(Shape::Empty),
+        Box::new(Shape::Square(1)),
+        Box::new(Shape::Square(2)),
+        Box::new(Shape::Rect(1, 2)),
+        Box::new(Shape::Circle { radius: 1 }),
+        Box::new(serde_json::json!({"Square": 1})),
+        Box::new(serde_json::json!({"Circle": {"radius": 1}})),
+        Box::new(serde_json::json!({"name": "Noah", "age": 42})),
+        Box::new(serde_json::json!([1, "a", null, {"x": []}])),
+        Box::new(serde_json::json!([1.5, 2.5])),
+        Box::new(serde_json::json!([1.5, 2])),
+        Box::new(1.5f32),
+        Box::new(1.5f64),
+        Box::new(vec![1.5f32, 2.5]),
+    ];
+
+    // Every pair of values must compare the same way as their keys.
+    for a in &corpus {
+        for b in &corpus {
+            let key = b.to_key_with_total_order().unwrap();
+            let expected = a.to_key_with_total_order().unwrap() == key;
+            assert_eq!(a.eq_total_order(&key).unwrap(), expected);
+
+            if let (Ok(a_key), Ok(key)) = (a.to_key(), b.to_key()) {
+                assert_eq!(a.eq(&key).unwrap(), a_key == key);
+            }
+        }
+    }
+}
+
+mod erased {
+    use serde_hashkey::{Error, Key, TotalOrderFloatPolicy};
+
+    pub(super) trait Serialize {
+        fn to_key(&self) -> Result<Key, Error>;
+
+        fn to_key_with_total_order(&self) -> Result<Key<TotalOrderFloatPolicy>, Error>;
+
+        fn eq(&self, key: &Key) -> Result<bool, Error>;
+
+        fn eq_total_order(&self, key: &Key<TotalOrderFloatPolicy>) -> Result<bool, Error>;
+    }
+
+    impl<T> Serialize for T
+    where
+        T: serde::Serialize,
+    {
+        fn to_key(&self) -> Result<Key, Error> {
+            serde_hashkey::to_key(self)
+        }
+
+        fn to_key_with_total_order(&self) -> Result<Key<TotalOrderFloatPolicy>, Error> {
+            serde_hashkey::to_key_with(self)
+        }
+
+        fn eq(&self, key: &Key) -> Result<bool, Error> {
+            serde_hashkey::value_eq_key(self, key)
+        }
+
+        fn eq_total_order(&self, key: &Key<TotalOrderFloatPolicy>) -> Result<bool, Error> {
+            serde_hashkey::value_eq_key(self, key)
+        }
+    }
+}
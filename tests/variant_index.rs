@@ -0,0 +1,148 @@
+use std::fmt;
+
+use serde::de;
+use serde_derive::Deserialize;
+use serde_hashkey::{from_key, from_key_owned, key, to_key, Error, Key};
+
+#[derive(Debug, PartialEq, Deserialize)]
+enum Shape {
+    Empty,
+    Square(u32),
+    Circle { radius: u32 },
+}
+
+/// An enum whose variant identifier can only be deserialized from a name.
+#[derive(Debug, PartialEq)]
+enum Named {
+    A,
+    B { value: u32 },
+}
+
+impl<'de> de::Deserialize<'de> for Named {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        struct Variant(String);
+
+        impl<'de> de::Deserialize<'de> for Variant {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: de::Deserializer<'de>,
+            {
+                struct Visitor;
+
+                impl de::Visitor<'_> for Visitor {
+                    type Value = Variant;
+
+                    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                        f.write_str("variant name")
+                    }
+
+                    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> {
+                        Ok(Variant(v.to_owned()))
+                    }
+                }
+
+                deserializer.deserialize_identifier(Visitor)
+            }
+        }
+
+        #[derive(Deserialize)]
+        struct Fields {
+            value: u32,
+        }
+
+        struct Visitor;
+
+        impl<'de> de::Visitor<'de> for Visitor {
+            type Value = Named;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("enum Named")
+            }
+
+            fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+            where
+                A: de::EnumAccess<'de>,
+            {
+                use serde::de::VariantAccess;
+
+                let (Variant(name), variant) = data.variant::<Variant>()?;
+
+                match name.as_str() {
+                    "A" => {
+                        variant.unit_variant()?;
+                        Ok(Named::A)
+                    }
+                    "B" => {
+                        let fields = variant.newtype_variant::<Fields>()?;
+                        Ok(Named::B {
+                            value: fields.value,
+                        })
+                    }
+                    _ => Err(de::Error::unknown_variant(&name, &["A", "B"])),
+                }
+            }
+        }
+
+        deserializer.deserialize_enum("Named", &["A", "B"], Visitor)
+    }
+}
+
+#[test]
+fn test_unit_variants() -> Result<(), Error> {
+    assert_eq!(from_key::<Shape, _>(&key!(0u32))?, Shape::Empty);
+    assert_eq!(from_key::<Named, _>(&key!(0u32))?, Named::A);
+    assert_eq!(from_key::<Named, _>(&key!(0u8))?, Named::A);
+    assert_eq!(from_key_owned::<Named, _>(key!(0u32))?, Named::A);
+
+    // Compact sequences of variant indexes.
+    let key = to_key(&vec![0u32, 0])?;
+    assert!(matches!(key, Key::IntegerSeq(..)));
+    assert_eq!(from_key::<Vec<Named>, _>(&key)?, vec![Named::A, Named::A]);
+    Ok(())
+}
+
+#[test]
+fn test_data_variants() -> Result<(), Error> {
+    let key = key!({ 1u32: 7u32 });
+    assert_eq!(from_key::<Shape, _>(&key)?, Shape::Square(7));
+
+    let key = key!({ 2u32: { "radius": 3u32 } });
+    assert_eq!(from_key::<Shape, _>(&key)?, Shape::Circle { radius: 3 });
+    assert_eq!(
+        from_key_owned::<Shape, _>(key)?,
+        Shape::Circle { radius: 3 }
+    );
+
+    let key = key!({ 1u32: { "value": 3u32 } });
+    assert_eq!(from_key::<Named, _>(&key)?, Named::B { value: 3 });
+    assert_eq!(from_key_owned::<Named, _>(key)?, Named::B { value: 3 });
+    Ok(())
+}
+
+#[test]
+fn test_out_of_range() {
+    let error = from_key::<Shape, _>(&key!(3u32)).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "invalid value: integer `3`, expected variant index 0 <= i < 3"
+    );
+
+    let key = key!({ 7u32: 1u32 });
+    assert_eq!(
+        from_key::<Shape, _>(&key).unwrap_err(),
+        from_key_owned::<Shape, _>(key).unwrap_err()
+    );
+
+    let key = key!(-1i8);
+    assert!(from_key::<Shape, _>(&key).is_err());
+
+    // Without names, the index is handed over as-is.
+    let error = from_key::<Named, _>(&key!(2u32)).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "invalid type: integer `2`, expected variant name"
+    );
+}
@@ -0,0 +1,301 @@
+use std::mem;
+
+use serde_derive::Serialize;
+use serde_hashkey::{key, to_key, Error, Key, Walk};
+
+#[derive(Serialize)]
+struct Author {
+    name: &'static str,
+    tags: Vec<&'static str>,
+    ids: Vec<u32>,
+}
+
+/// Drop a deeply nested chain of sequences without recursing.
+fn unwind(mut key: Key) {
+    while let Key::Seq(seq) = &mut key {
+        match mem::take(seq).into_vec().pop() {
+            Some(inner) => key = inner,
+            None => break,
+        }
+    }
+}
+
+#[test]
+fn test_iter_order() -> Result<(), Error> {
+    let key = to_key(&Author {
+        name: "Noah",
+        tags: vec!["a", "b"],
+        ids: vec![1, 2],
+    })?;
+
+    let values = key.iter().cloned().collect::<Vec<_>>();
+
+    assert_eq!(
+        values,
+        vec![
+            key.clone(),
+            key!("name"),
+            key!("Noah"),
+            key!("tags"),
+            to_key(&vec!["a", "b"])?,
+            key!("a"),
+            key!("b"),
+            key!("ids"),
+            to_key(&vec![1u32, 2])?,
+        ]
+    );
+
+    // Map keys are visited as well.
+    let key = to_key(
+        &vec![(vec!["k"], 1u32)]
+            .into_iter()
+            .collect::<std::collections::BTreeMap<_, _>>(),
+    )?;
+    let values = key.iter().skip(1).cloned().collect::<Vec<_>>();
+    assert_eq!(
+        values,
+        vec![to_key(&vec!["k"])?, key!("k"), Key::from(1u32)]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_iter_fused_clone() -> Result<(), Error> {
+    let key = to_key(&("a", ("b", true)))?;
+
+    let mut iter = key.iter();
+    iter.next();
+    let copy = iter.clone();
+    assert_eq!(iter.by_ref().count(), 4);
+    assert_eq!(copy.count(), 4);
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next(), None);
+
+    let key: Key = Key::Unit;
+    assert_eq!(key.iter().collect::<Vec<_>>(), vec![&Key::Unit]);
+    Ok(())
+}
+
+#[test]
+fn test_iter_deep() {
+    let mut key: Key = key!("leaf");
+
+    for _ in 0..100_000 {
+        key = Key::Seq(vec![key].into());
+    }
+
+    assert_eq!(key.iter().count(), 100_001);
+    assert_eq!(key.iter().last(), Some(&key!("leaf")));
+    unwind(key);
+}
+
+#[derive(Serialize)]
+struct Event {
+    name: &'static str,
+    timestamp: u64,
+    children: Vec<Event>,
+}
+
+#[derive(Serialize)]
+struct Stripped {
+    name: &'static str,
+    children: Vec<Stripped>,
+}
+
+fn strip(key: &mut Key, field: &str) {
+    let field = key!(field);
+
+    key.walk_mut(|key| {
+        if let Key::Map(map) = key {
+            let mut entries = mem::take(map).into_vec();
+            entries.retain(|(key, _)| *key != field);
+            *map = entries.into();
+        }
+
+        Walk::Continue
+    });
+}
+
+#[test]
+fn test_walk_strip_timestamps() -> Result<(), Error> {
+    let mut key = to_key(&Event {
+        name: "a",
+        timestamp: 1,
+        children: vec![
+            Event {
+                name: "b",
+                timestamp: 2,
+                children: vec![],
+            },
+            Event {
+                name: "c",
+                timestamp: 3,
+                children: vec![Event {
+                    name: "d",
+                    timestamp: 4,
+                    children: vec![],
+                }],
+            },
+        ],
+    })?;
+
+    strip(&mut key, "timestamp");
+
+    let expected = to_key(&Stripped {
+        name: "a",
+        children: vec![
+            Stripped {
+                name: "b",
+                children: vec![],
+            },
+            Stripped {
+                name: "c",
+                children: vec![Stripped {
+                    name: "d",
+                    children: vec![],
+                }],
+            },
+        ],
+    })?;
+
+    assert_eq!(key, expected);
+    Ok(())
+}
+
+#[test]
+fn test_walk_remove() -> Result<(), Error> {
+    // Removing map values and keys drops the whole entry.
+    let mut key = to_key(&Author {
+        name: "Noah",
+        tags: vec!["a", "b"],
+        ids: vec![1, 2],
+    })?;
+
+    key.walk_mut(|key| {
+        if *key == key!("Noah") || *key == key!("ids") {
+            Walk::Remove
+        } else {
+            Walk::Continue
+        }
+    });
+
+    assert_eq!(key, key!({ "tags": ["a", "b"] }));
+
+    // Removing sequence elements compacts what is left.
+    let mut key = to_key(&("a", 1u32, 2u32))?;
+    key.walk_mut(|key| match key {
+        Key::String(..) => Walk::Remove,
+        _ => Walk::Continue,
+    });
+    assert_eq!(key, to_key(&vec![1u32, 2])?);
+    assert!(matches!(key, Key::IntegerSeq(..)));
+
+    // Removing the root.
+    let mut key = to_key(&vec!["a"])?;
+    key.walk_mut(|_| Walk::Remove);
+    assert_eq!(key, Key::Unit);
+    Ok(())
+}
+
+#[test]
+fn test_walk_order_and_skip() -> Result<(), Error> {
+    let mut key = to_key(&Author {
+        name: "Noah",
+        tags: vec!["a", "b"],
+        ids: vec![1, 2],
+    })?;
+
+    let mut visited = Vec::new();
+
+    key.walk_mut(|key| {
+        visited.push(key.clone());
+        Walk::Continue
+    });
+
+    assert_eq!(visited, key.iter().cloned().collect::<Vec<_>>());
+
+    let mut visited = Vec::new();
+
+    key.walk_mut(|key| {
+        visited.push(key.clone());
+
+        match key {
+            Key::Seq(..) => {
+                *key = key!("tags were here");
+                Walk::SkipChildren
+            }
+            _ => Walk::Continue,
+        }
+    });
+
+    assert!(!visited.contains(&key!("a")));
+    assert_eq!(key.get(&key!("tags")), Some(&key!("tags were here")));
+    Ok(())
+}
+
+#[test]
+fn test_walk_deep() {
+    let mut key: Key = key!("leaf");
+
+    for _ in 0..100_000 {
+        key = Key::Seq(vec![key].into());
+    }
+
+    let mut count = 0;
+
+    key.walk_mut(|key| {
+        count += 1;
+
+        if let Key::String(..) = key {
+            *key = key!("replaced");
+        }
+
+        Walk::Continue
+    });
+
+    assert_eq!(count, 100_001);
+    assert_eq!(key.iter().last(), Some(&key!("replaced")));
+    unwind(key);
+}
+
+#[test]
+fn test_container_iters() -> Result<(), Error> {
+    let author = Author {
+        name: "Noah",
+        tags: vec!["a", "b"],
+        ids: vec![1, 2],
+    };
+
+    let key = to_key(&author)?;
+    let mut entries = key.iter_map().unwrap();
+    assert_eq!(entries.len(), 3);
+    assert_eq!(entries.next_back().map(|e| &e.0), Some(&key!("ids")));
+    assert!(key.iter_seq().is_none());
+
+    let entries = key.clone().into_map_iter().unwrap().collect::<Vec<_>>();
+    assert_eq!(Key::Map(entries.into()), key);
+    assert!(key.into_seq_iter().is_none());
+
+    let key = to_key(&vec!["a", "b"])?;
+    assert_eq!(
+        key.iter_seq().unwrap().collect::<Vec<_>>(),
+        vec![&key!("a"), &key!("b")]
+    );
+    assert!(key.iter_map().is_none());
+    assert!(key.into_map_iter().is_none());
+
+    // Empty containers are distinguishable from other keys.
+    assert_eq!(
+        to_key(&Vec::<()>::new())?.iter_seq().map(|i| i.len()),
+        Some(0)
+    );
+    assert!(to_key(&())?.into_seq_iter().is_none());
+
+    // Compact sequences are only expanded when consumed.
+    let key = to_key(&vec![1u32, 2])?;
+    assert!(key.iter_seq().is_none());
+    let mut elements = key.into_seq_iter().unwrap();
+    assert_eq!(elements.len(), 2);
+    assert_eq!(elements.next_back(), Some(Key::from(2u32)));
+    Ok(())
+}
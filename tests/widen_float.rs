@@ -0,0 +1,84 @@
+use serde_derive::{Deserialize, Serialize};
+use serde_hashkey::{
+    from_key, to_key_with_canonical_float, to_key_with_widened_float, CanonicalFloat,
+    CanonicalFloatPolicy, Float, Key, WidenF32Policy,
+};
+
+#[test]
+fn test_widened_equal() {
+    let a = to_key_with_widened_float(&1.5f32).unwrap();
+    let b = to_key_with_widened_float(&1.5f64).unwrap();
+    assert_eq!(a, b);
+    assert_eq!(a, Key::Float(Float::F64(CanonicalFloat::new(1.5))));
+    assert_eq!(a, Key::<WidenF32Policy>::from(1.5f32));
+    assert_eq!(a, Key::<WidenF32Policy>::from(1.5f64));
+
+    // Values which can't be represented exactly are rounded differently.
+    let a = to_key_with_widened_float(&0.1f32).unwrap();
+    let b = to_key_with_widened_float(&0.1f64).unwrap();
+    assert_ne!(a, b);
+    assert_eq!(a, to_key_with_widened_float(&f64::from(0.1f32)).unwrap());
+
+    // Like the canonical policy, NaNs and negative zero are folded.
+    assert_eq!(
+        to_key_with_widened_float(&f32::NAN).unwrap(),
+        to_key_with_widened_float(&-f64::NAN).unwrap()
+    );
+    assert_eq!(
+        to_key_with_widened_float(&-0.0f32).unwrap(),
+        to_key_with_widened_float(&0.0f64).unwrap()
+    );
+}
+
+#[test]
+fn test_mixed_width_roundtrip() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Reading {
+        narrow: f32,
+        wide: f64,
+    }
+
+    let reading = Reading {
+        narrow: 0.1,
+        wide: 0.1,
+    };
+
+    let key = to_key_with_widened_float(&reading).unwrap();
+    assert_eq!(
+        key["narrow"],
+        Key::Float(Float::F64(CanonicalFloat::new(f64::from(0.1f32))))
+    );
+    assert_eq!(
+        key["wide"],
+        Key::Float(Float::F64(CanonicalFloat::new(0.1)))
+    );
+    assert_eq!(from_key::<Reading, _>(&key).unwrap(), reading);
+
+    // Every `f32` survives being widened and narrowed again.
+    for value in [f32::MIN_POSITIVE, f32::MAX, f32::MIN, 1e-45, 3.4e38] {
+        let key = to_key_with_widened_float(&value).unwrap();
+        assert_eq!(from_key::<f32, _>(&key).unwrap(), value);
+    }
+}
+
+#[test]
+fn test_seq() {
+    let a = to_key_with_widened_float(&vec![1.0f32, 2.5, -4.0]).unwrap();
+    let b = to_key_with_widened_float(&vec![1.0f64, 2.5, -4.0]).unwrap();
+    assert_eq!(a, b);
+    assert!(matches!(a, Key::FloatSeq(..)));
+    assert_eq!(from_key::<Vec<f32>, _>(&a).unwrap(), vec![1.0, 2.5, -4.0]);
+}
+
+#[test]
+fn test_binary() {
+    let key = to_key_with_widened_float(&(1.5f32, 2.5f64)).unwrap();
+    let bytes = key.to_bytes();
+    assert_eq!(Key::<WidenF32Policy>::from_bytes(&bytes).unwrap(), key);
+
+    // Encoded 32-bit floats are widened when decoded.
+    let narrow = to_key_with_canonical_float(&1.5f32).unwrap();
+    assert_eq!(narrow, Key::<CanonicalFloatPolicy>::from(1.5f32));
+    let decoded = Key::<WidenF32Policy>::from_bytes(&narrow.to_bytes()).unwrap();
+    assert_eq!(decoded, Key::Float(Float::F64(CanonicalFloat::new(1.5))));
+}